@@ -7,6 +7,9 @@ const APP_NAME: &str = "cook";
 pub(crate) const LOCAL_CONFIG_DIR: &str = "config";
 const AUTO_AISLE: &str = "aisle.conf";
 pub(crate) const AUTO_PANTRY: &str = "pantry.conf";
+const AUTO_DOCTOR: &str = "doctor.toml";
+const AUTO_SUBSTITUTES: &str = "substitutes.conf";
+const AUTO_NOTIFY: &str = "notify.toml";
 
 /// The configuration bundle every command operates against.
 ///
@@ -19,19 +22,32 @@ pub struct Context {
     base_path: Utf8PathBuf,
     aisle: ConfigSource,
     pantry: ConfigSource,
+    doctor: ConfigSource,
+    substitutes: ConfigSource,
+    notify: ConfigSource,
+    store: ConfigSource,
+    origin: Option<String>,
 }
 
 impl Context {
-    /// A context with no aisle or pantry configuration. Touches nothing.
+    /// A context with no aisle, pantry, doctor, substitutes or notify
+    /// configuration.
+    /// Touches nothing.
     pub fn new(base_path: Utf8PathBuf) -> Self {
         Self {
             base_path,
             aisle: ConfigSource::None,
             pantry: ConfigSource::None,
+            doctor: ConfigSource::None,
+            substitutes: ConfigSource::None,
+            notify: ConfigSource::None,
+            store: ConfigSource::None,
+            origin: None,
         }
     }
 
-    /// A context with aisle and pantry resolved using CookCLI's search order:
+    /// A context with aisle, pantry, doctor, substitutes and notify
+    /// configuration resolved using CookCLI's search order:
     /// `<base>/config/<name>` first, then the platform configuration directory
     /// ([`global_config_path`] — `~/.config/cook/<name>` on Linux, the platform
     /// equivalent elsewhere).
@@ -56,10 +72,18 @@ impl Context {
     pub fn discover(base_path: Utf8PathBuf) -> Self {
         let aisle = Self::discover_one(&base_path, AUTO_AISLE);
         let pantry = Self::discover_one(&base_path, AUTO_PANTRY);
+        let doctor = Self::discover_one(&base_path, AUTO_DOCTOR);
+        let substitutes = Self::discover_one(&base_path, AUTO_SUBSTITUTES);
+        let notify = Self::discover_one(&base_path, AUTO_NOTIFY);
         Self {
             base_path,
             aisle,
             pantry,
+            doctor,
+            substitutes,
+            notify,
+            store: ConfigSource::None,
+            origin: None,
         }
     }
 
@@ -110,6 +134,41 @@ impl Context {
         self
     }
 
+    /// Replace the doctor configuration, whatever discovery found.
+    pub fn with_doctor(mut self, source: ConfigSource) -> Self {
+        self.doctor = source;
+        self
+    }
+
+    /// Replace the substitutes configuration, whatever discovery found.
+    pub fn with_substitutes(mut self, source: ConfigSource) -> Self {
+        self.substitutes = source;
+        self
+    }
+
+    /// Replace the notify configuration, whatever discovery found.
+    pub fn with_notify(mut self, source: ConfigSource) -> Self {
+        self.notify = source;
+        self
+    }
+
+    /// Lay shopping lists out with a store profile. Discovery never sets one:
+    /// which shop you are going to is not something a file can know. See
+    /// [`store_profile`](crate::shopping_list::store_profile) to find one by
+    /// name.
+    pub fn with_store(mut self, source: ConfigSource) -> Self {
+        self.store = source;
+        self
+    }
+
+    /// Name where this context's changes come from, for the pantry's
+    /// [journal](crate::pantry::history). The CLI passes `cli` and the web
+    /// server `web`; nothing is recorded if it is never set.
+    pub fn with_origin(mut self, origin: impl Into<String>) -> Self {
+        self.origin = Some(origin.into());
+        self
+    }
+
     /// The directory recipe paths and searches are resolved against.
     ///
     /// Returned exactly as it was supplied. Unlike the CLI, which canonicalises
@@ -131,6 +190,39 @@ impl Context {
     pub fn pantry(&self) -> &ConfigSource {
         &self.pantry
     }
+
+    /// The [`StoreProfile`](crate::shopping_list::StoreProfile) shopping
+    /// lists are laid out with. Unset means `aisle.conf` order, unchanged.
+    pub fn store(&self) -> &ConfigSource {
+        &self.store
+    }
+
+    /// Where changes made through this context come from, as
+    /// [`with_origin`](Self::with_origin) named it.
+    pub fn origin(&self) -> Option<&str> {
+        self.origin.as_deref()
+    }
+
+    /// The `doctor.toml` whose lint rules
+    /// [`doctor::validate`](crate::doctor::validate) applies on top of the
+    /// parser's own checks. Unset means no lint rules run at all.
+    pub fn doctor(&self) -> &ConfigSource {
+        &self.doctor
+    }
+
+    /// The [`Substitutes`](crate::substitutes::Substitutes) consulted for an
+    /// ingredient the pantry does not have. Unset means nothing stands in for
+    /// anything.
+    pub fn substitutes(&self) -> &ConfigSource {
+        &self.substitutes
+    }
+
+    /// The [`NotifyConfig`](crate::pantry::NotifyConfig) saying which pantry
+    /// events to report and where to send them. Unset means the defaults,
+    /// with nowhere to send anything.
+    pub fn notify(&self) -> &ConfigSource {
+        &self.notify
+    }
 }
 
 /// Resolve `name` inside the platform configuration directory for `cook`, e.g.
@@ -177,12 +269,34 @@ mod tests {
             "[freezer]\npeas = \"1kg\"",
         );
 
+        write(
+            &base.join("config").join("doctor.toml"),
+            "[rules.max-steps]",
+        );
+        write(
+            &base.join("config").join("substitutes.conf"),
+            "shallot = \"onion\"",
+        );
+        write(&base.join("config").join("notify.toml"), "days = 3");
+
         let ctx = Context::new(base.clone());
         assert!(ctx.aisle().is_unset(), "new must not discover local config");
+        assert!(
+            ctx.doctor().is_unset(),
+            "new must not discover local config"
+        );
         assert!(
             ctx.pantry().is_unset(),
             "new must not discover local config"
         );
+        assert!(
+            ctx.substitutes().is_unset(),
+            "new must not discover local config"
+        );
+        assert!(
+            ctx.notify().is_unset(),
+            "new must not discover local config"
+        );
         assert_eq!(ctx.base_path(), base);
     }
 
@@ -212,6 +326,17 @@ mod tests {
         assert!(ctx.aisle().is_unset(), "with_pantry must not set the aisle");
     }
 
+    #[test]
+    fn with_doctor_overrides() {
+        let ctx = Context::new(Utf8PathBuf::from("/tmp"))
+            .with_doctor(ConfigSource::Inline("[rules.max-steps]".to_string()));
+        assert_eq!(
+            ctx.doctor().read().unwrap().as_deref(),
+            Some("[rules.max-steps]")
+        );
+        assert!(ctx.aisle().is_unset() && ctx.pantry().is_unset());
+    }
+
     #[test]
     fn discover_finds_local_config() {
         let dir = tempfile::TempDir::new().unwrap();
@@ -222,8 +347,22 @@ mod tests {
             "[freezer]\npeas = \"1kg\"",
         );
 
+        write(
+            &base.join("config").join("doctor.toml"),
+            "[rules.max-steps]",
+        );
+        write(
+            &base.join("config").join("substitutes.conf"),
+            "shallot = \"onion\"",
+        );
+        write(&base.join("config").join("notify.toml"), "days = 3");
+
         let ctx = Context::discover(base.clone());
 
+        assert_eq!(
+            ctx.doctor().path(),
+            Some(base.join("config").join("doctor.toml").as_path())
+        );
         assert_eq!(
             ctx.aisle().path(),
             Some(base.join("config").join("aisle.conf").as_path())
@@ -232,6 +371,14 @@ mod tests {
             ctx.pantry().path(),
             Some(base.join("config").join("pantry.conf").as_path())
         );
+        assert_eq!(
+            ctx.substitutes().path(),
+            Some(base.join("config").join("substitutes.conf").as_path())
+        );
+        assert_eq!(
+            ctx.notify().path(),
+            Some(base.join("config").join("notify.toml").as_path())
+        );
     }
 
     #[test]
@@ -28,6 +28,27 @@ pub struct Span {
     pub end: usize,
 }
 
+impl Span {
+    /// Resolve this span to lines and columns within `source`, the text it was
+    /// taken from.
+    ///
+    /// Byte offsets are what the parser hands out, but nothing that shows a
+    /// problem to a person — an editor gutter, a CI annotation, a SARIF viewer
+    /// — can use them without the file in hand. Resolving them once, where the
+    /// source is already loaded, spares every such consumer re-reading it.
+    ///
+    /// An offset past the end of `source`, or inside a multi-byte character,
+    /// is pulled back to the nearest character boundary before it rather than
+    /// panicking: a span taken from different text than the one given is a
+    /// caller's mistake, but not one worth crashing a NAPI addon over.
+    pub fn to_range(self, source: &str) -> TextRange {
+        TextRange {
+            start: Position::at(source, self.start),
+            end: Position::at(source, self.end),
+        }
+    }
+}
+
 impl From<std::ops::Range<usize>> for Span {
     fn from(range: std::ops::Range<usize>) -> Self {
         Self {
@@ -37,6 +58,48 @@ impl From<std::ops::Range<usize>> for Span {
     }
 }
 
+/// A point in a source file, as a person would count it.
+///
+/// Both numbers are **1-based**, and `column` counts characters (Unicode
+/// scalar values), not bytes — so a recipe with `crème fraîche` on a line does
+/// not push every later column on that line off by the accents. That is the
+/// convention GitHub annotations and SARIF's `unicodeCodePoints` column kind
+/// both read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Position {
+    /// Line number, starting at 1.
+    pub line: usize,
+    /// Column number within the line, starting at 1.
+    pub column: usize,
+}
+
+impl Position {
+    /// The position of byte `offset` in `source`, clamped as
+    /// [`Span::to_range`] describes.
+    fn at(source: &str, offset: usize) -> Self {
+        let mut offset = offset.min(source.len());
+        while !source.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let before = &source[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        Self {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+}
+
+/// A [`Span`] resolved to lines and columns. `end` is exclusive, as the span's
+/// is: it names the position just after the last character in the range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextRange {
+    /// Where the range starts.
+    pub start: Position,
+    /// Just past where the range ends.
+    pub end: Position,
+}
+
 /// Where in a source file a diagnostic applies.
 ///
 /// Both fields are optional: a diagnostic about a configuration file as a whole
@@ -54,6 +117,14 @@ pub struct Location {
     /// The range within that file the diagnostic refers to.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub span: Option<Span>,
+    /// [`span`](Location::span) resolved against the source it came from.
+    ///
+    /// Set alongside the span whenever the text was at hand when the
+    /// diagnostic was raised, which for every parser diagnostic this crate
+    /// produces it is. `None` when there is no span, and on a diagnostic
+    /// deserialized from JSON written before this field existed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub range: Option<TextRange>,
 }
 
 /// A single problem found while running a command.
@@ -80,6 +151,15 @@ pub struct Diagnostic {
     /// without `default` that omission fails to deserialize.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub hints: Vec<String>,
+    /// The rule that raised this diagnostic, for diagnostics that come from a
+    /// named check — `required-metadata`, say, from
+    /// [`doctor::lint`](crate::doctor::lint). `None` for the parser's own,
+    /// which it does not classify.
+    ///
+    /// Stable across releases, so a consumer can filter or suppress by it; the
+    /// message is for people and may be reworded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
 }
 
 impl Diagnostic {
@@ -90,6 +170,7 @@ impl Diagnostic {
             message: message.into(),
             location: None,
             hints: Vec::new(),
+            code: None,
         }
     }
 
@@ -100,6 +181,7 @@ impl Diagnostic {
             message: message.into(),
             location: None,
             hints: Vec::new(),
+            code: None,
         }
     }
 
@@ -113,14 +195,28 @@ impl Diagnostic {
             message: message.into(),
             location: None,
             hints: Vec::new(),
+            code: None,
         }
     }
 
+    /// Name the rule that raised this diagnostic. See [`code`](Diagnostic::code).
+    pub fn with_code(mut self, code: impl Into<String>) -> Self {
+        self.code = Some(code.into());
+        self
+    }
+
+    /// Add a suggested fix, after any already attached.
+    pub fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        self.hints.push(hint.into());
+        self
+    }
+
     /// Attach a source file to this diagnostic, keeping any span already set.
     pub fn at_file(mut self, file: impl Into<Utf8PathBuf>) -> Self {
         let location = self.location.get_or_insert(Location {
             file: None,
             span: None,
+            range: None,
         });
         location.file = Some(file.into());
         self
@@ -236,8 +332,10 @@ mod tests {
             location: Some(Location {
                 file: None,
                 span: Some(Span { start: 12, end: 20 }),
+                range: None,
             }),
             hints: Vec::new(),
+            code: None,
         }
         .at_file("soup.cook");
 
@@ -249,11 +347,54 @@ mod tests {
         assert_eq!(location.span, Some(Span { start: 12, end: 20 }));
     }
 
+    #[test]
+    fn code_and_hints_are_attached_and_serialized() {
+        let d = Diagnostic::warning("missing servings")
+            .with_code("required-metadata")
+            .with_hint("add `servings: 4` to the front matter");
+        let json = serde_json::to_string(&d).unwrap();
+        assert_eq!(
+            json,
+            r#"{"severity":"warning","message":"missing servings","hints":["add `servings: 4` to the front matter"],"code":"required-metadata"}"#
+        );
+        let back: Diagnostic = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, d);
+    }
+
     #[test]
     fn span_converts_from_a_range() {
         assert_eq!(Span::from(4..9), Span { start: 4, end: 9 });
     }
 
+    #[test]
+    fn span_resolves_to_one_based_lines_and_character_columns() {
+        let source = "first line\ncrème @fraîche{}\n";
+        // `@` is byte 18 but the seventh character of line two: `è` is two
+        // bytes and must count as one column.
+        let at = source.find('@').unwrap();
+        let range = Span::from(at..at + 1).to_range(source);
+        assert_eq!(range.start, Position { line: 2, column: 7 });
+        assert_eq!(range.end, Position { line: 2, column: 8 });
+
+        assert_eq!(
+            Span::from(0..0).to_range(source).start,
+            Position { line: 1, column: 1 }
+        );
+    }
+
+    #[test]
+    fn span_past_the_source_or_mid_character_is_clamped() {
+        let source = "é";
+        // Byte 1 is inside `é`; it is pulled back to the start of it.
+        assert_eq!(
+            Span::from(1..99).to_range(source),
+            TextRange {
+                start: Position { line: 1, column: 1 },
+                end: Position { line: 1, column: 2 },
+            }
+        );
+    }
+
     #[test]
     fn serializes_without_null_location() {
         let d = Diagnostic::warning("no location");
@@ -269,8 +410,10 @@ mod tests {
             location: Some(Location {
                 file: Some(Utf8PathBuf::from("soup.cook")),
                 span: Some(Span { start: 12, end: 20 }),
+                range: None,
             }),
             hints: Vec::new(),
+            code: None,
         };
         let json = serde_json::to_string(&d).unwrap();
         assert_eq!(
@@ -329,7 +472,8 @@ mod tests {
             d.location,
             Some(Location {
                 file: None,
-                span: None
+                span: None,
+                range: None
             })
         );
     }
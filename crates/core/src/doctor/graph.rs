@@ -0,0 +1,549 @@
+//! Problems in how a collection's files fit together, rather than in any one
+//! of them.
+//!
+//! [`validate`](super::validate) reads each recipe on its own. [`check`] reads
+//! the [`ReferenceGraph`] between them, and the images beside them, and reports
+//! four things no single recipe can show:
+//!
+//! - **Reference cycles** ([`REFERENCE_CYCLE`], an error). A recipe that
+//!   references itself, directly or through others, cannot be expanded into a
+//!   shopping list: the expansion would never end. The shopping list stops at
+//!   the repeat with a warning rather than looping, so the list it produces is
+//!   not the one the recipes describe.
+//! - **Menus pointing at missing recipes** ([`MISSING_MENU_RECIPE`], an
+//!   error). A menu is nothing but its references, so one leading nowhere is a
+//!   dish nobody will shop for.
+//! - **Orphaned images** ([`ORPHANED_IMAGE`], a warning). The web UI finds a
+//!   recipe's images by name — `Pancakes.jpg` for the title,
+//!   `Pancakes.3.jpg` for the third step, `Pancakes.2.1.jpg` for the first
+//!   step of the second section — so an image whose name matches no recipe,
+//!   or no step of it, is never shown. An image the front matter names is in
+//!   use whether the path is relative to the recipe or to the collection root.
+//! - **Unreachable recipes** ([`UNREACHABLE_RECIPE`], a hint). A recipe no
+//!   other recipe references and no menu lists. Only reported in a collection
+//!   that has menus: in one without, every recipe stands on its own, and
+//!   reporting all of them would be noise.
+//!
+//! Every finding is a [`Diagnostic`] with its [`code`](Diagnostic::code) set
+//! and the file it concerns as its location — the reference itself, with its
+//! line, where there is one.
+
+use super::lint::IMAGE_EXTENSIONS;
+use crate::{
+    graph::{NodeKind, ReferenceGraph},
+    Context, CoreError, Diagnostic, Outcome,
+};
+use camino::{Utf8Path, Utf8PathBuf};
+use cooklang::Recipe;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The [`code`](Diagnostic::code) of a reference cycle.
+pub const REFERENCE_CYCLE: &str = "reference-cycle";
+/// The [`code`](Diagnostic::code) of a menu reference that leads nowhere.
+pub const MISSING_MENU_RECIPE: &str = "missing-menu-recipe";
+/// The [`code`](Diagnostic::code) of an image nothing shows.
+pub const ORPHANED_IMAGE: &str = "orphaned-image";
+/// The [`code`](Diagnostic::code) of a recipe nothing references.
+pub const UNREACHABLE_RECIPE: &str = "unreachable-recipe";
+
+/// Every check this module makes, by code, with a one-line description, in
+/// the order findings are reported.
+pub const RULES: &[(&str, &str)] = &[
+    (
+        REFERENCE_CYCLE,
+        "Recipe references must not lead back to the recipe making them",
+    ),
+    (
+        MISSING_MENU_RECIPE,
+        "Every recipe a menu references must exist",
+    ),
+    (
+        ORPHANED_IMAGE,
+        "Every image must belong to a recipe, or a step of one",
+    ),
+    (
+        UNREACHABLE_RECIPE,
+        "Where a collection has menus, every recipe should be in one or referenced by another recipe",
+    ),
+];
+
+/// A collection-graph check.
+///
+/// Not `#[non_exhaustive]`: consumers construct this. `..Default::default()`
+/// keeps a literal working if it grows a field.
+#[derive(Debug, Clone, Default)]
+pub struct GraphRequest {
+    /// Directory whose collection to check. Defaults to the context base path.
+    pub base_dir: Option<Utf8PathBuf>,
+}
+
+/// What [`check`] found.
+///
+/// `#[non_exhaustive]` because this is an output type consumers read rather
+/// than construct.
+#[non_exhaustive]
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GraphCheck {
+    /// The root that was checked. Every location in
+    /// [`findings`](GraphCheck::findings) is relative to it.
+    pub base_dir: Utf8PathBuf,
+    /// How many recipes were checked, menus not included.
+    pub total_recipes: usize,
+    /// How many menus were checked.
+    pub total_menus: usize,
+    /// How many image files the collection holds.
+    pub total_images: usize,
+    /// Every problem, cycles first, then menus, images and unreachable
+    /// recipes, each kind in path order.
+    pub findings: Vec<Diagnostic>,
+}
+
+impl GraphCheck {
+    /// The findings of `severity`.
+    pub fn count(&self, severity: crate::Severity) -> usize {
+        self.findings
+            .iter()
+            .filter(|finding| finding.severity == severity)
+            .count()
+    }
+}
+
+/// Check how the collection's recipes, menus and images fit together — the
+/// question `cook doctor graph` asks.
+///
+/// See [the module documentation](self) for what is checked. A recipe that
+/// cannot be read or parsed is left out of the checks that need its content,
+/// with a warning in [`Outcome::diagnostics`]; it is still a recipe that
+/// others may reference.
+///
+/// # Errors
+///
+/// [`CoreError::Search`] if the collection cannot be walked, and
+/// [`CoreError::Io`] if a file in it cannot be listed — as
+/// [`validate`](super::validate). A directory whose images cannot be listed is
+/// a warning in [`Outcome::diagnostics`] instead: the other checks still have
+/// an answer.
+pub fn check(ctx: &Context, req: GraphRequest) -> Result<Outcome<GraphCheck>, CoreError> {
+    let base_dir = req
+        .base_dir
+        .unwrap_or_else(|| ctx.base_path().to_path_buf());
+
+    let mut facts = BTreeMap::new();
+    let Outcome {
+        value: graph,
+        mut diagnostics,
+    } = ReferenceGraph::build_visiting(&base_dir, |path, recipe| {
+        facts.insert(path.to_owned(), recipe_facts(path, recipe));
+    })?;
+
+    let mut findings = cycles(&graph);
+    findings.extend(missing_menu_recipes(&graph));
+
+    let images = list_images(&base_dir, &mut diagnostics);
+    let total_images = images.len();
+    findings.extend(
+        images
+            .iter()
+            .filter_map(|image| orphaned(image, &graph, &facts)),
+    );
+
+    findings.extend(unreachable_recipes(&graph));
+
+    let total_menus = graph
+        .nodes
+        .iter()
+        .filter(|node| node.kind == NodeKind::Menu)
+        .count();
+    Ok(Outcome::with_diagnostics(
+        GraphCheck {
+            total_recipes: graph.nodes.len() - total_menus,
+            total_menus,
+            total_images,
+            findings,
+            base_dir,
+        },
+        diagnostics,
+    ))
+}
+
+fn cycles(graph: &ReferenceGraph) -> Vec<Diagnostic> {
+    graph
+        .cycles()
+        .into_iter()
+        .map(|cycle| {
+            let chain = cycle
+                .iter()
+                .chain(cycle.first())
+                .map(|path| path.as_str())
+                .collect::<Vec<_>>()
+                .join(" → ");
+            let mut finding = Diagnostic::error(format!("recipe references form a cycle: {chain}"))
+                .with_code(REFERENCE_CYCLE)
+                .with_hint(
+                    "a shopping list cannot expand a cycle; remove one of the references in it",
+                )
+                .at_file(cycle[0].clone());
+            // On the reference that starts the cycle, so an annotation lands
+            // on a line that is part of the problem.
+            let next = cycle.get(1).unwrap_or(&cycle[0]);
+            if let Some(edge) = graph
+                .references_from(&cycle[0])
+                .find(|edge| edge.to.as_ref() == Some(next))
+            {
+                finding.location = Some(edge.location.clone());
+            }
+            finding
+        })
+        .collect()
+}
+
+fn missing_menu_recipes(graph: &ReferenceGraph) -> Vec<Diagnostic> {
+    let menus: BTreeSet<&Utf8Path> = graph
+        .nodes
+        .iter()
+        .filter(|node| node.kind == NodeKind::Menu)
+        .map(|node| node.path.as_path())
+        .collect();
+    graph
+        .edges
+        .iter()
+        .filter(|edge| edge.to.is_none() && menus.contains(edge.from.as_path()))
+        .map(|edge| {
+            let mut finding = Diagnostic::error(format!(
+                "menu references recipe '{}', which does not exist",
+                edge.reference
+            ))
+            .with_code(MISSING_MENU_RECIPE)
+            .with_hint("correct the reference, or add the recipe to the collection");
+            finding.location = Some(edge.location.clone());
+            finding
+        })
+        .collect()
+}
+
+fn unreachable_recipes(graph: &ReferenceGraph) -> Vec<Diagnostic> {
+    if !graph.nodes.iter().any(|node| node.kind == NodeKind::Menu) {
+        return Vec::new();
+    }
+    graph
+        .nodes
+        .iter()
+        .filter(|node| node.kind == NodeKind::Recipe)
+        .filter(|node| {
+            !graph
+                .references_to(&node.path)
+                .any(|edge| edge.from != node.path)
+        })
+        .map(|node| {
+            Diagnostic::hint("recipe is not in any menu, and no other recipe references it")
+                .with_code(UNREACHABLE_RECIPE)
+                .with_hint("add it to a menu, or leave it be if it is meant to stand alone")
+                .at_file(node.path.clone())
+        })
+        .collect()
+}
+
+/// What the image check needs to know about one recipe.
+struct Facts {
+    /// How many steps each section has, in order. A recipe without sections
+    /// has one.
+    steps: Vec<usize>,
+    /// The local images its front matter names, as candidate paths relative to
+    /// the collection root: each both as relative to the recipe and as
+    /// relative to the root.
+    named: Vec<Utf8PathBuf>,
+}
+
+/// Facts for one recipe or menu, at `path` relative to the collection root.
+fn recipe_facts(path: &Utf8Path, recipe: &Recipe) -> Facts {
+    let dir = path.parent().unwrap_or(Utf8Path::new(""));
+
+    let steps = recipe
+        .sections
+        .iter()
+        .map(|section| section.content.iter().filter(|c| c.is_step()).count())
+        .collect();
+
+    let mut named = Vec::new();
+    for key in ["image", "images", "picture", "pictures"] {
+        let images: Vec<&str> = match recipe.metadata.map.get(key) {
+            Some(serde_yaml::Value::String(image)) => vec![image.as_str()],
+            Some(serde_yaml::Value::Sequence(images)) => {
+                images.iter().filter_map(|v| v.as_str()).collect()
+            }
+            _ => continue,
+        };
+        for image in images {
+            let image = image.strip_prefix("./").unwrap_or(image);
+            named.push(dir.join(image));
+            named.push(Utf8PathBuf::from(image));
+        }
+    }
+
+    Facts { steps, named }
+}
+
+/// Every image file under `base_dir`, relative to it, in path order. Hidden
+/// files and directories — `.git` — are skipped.
+fn list_images(base_dir: &Utf8Path, diagnostics: &mut Vec<Diagnostic>) -> Vec<Utf8PathBuf> {
+    let mut images = Vec::new();
+    let mut pending = vec![base_dir.to_owned()];
+    while let Some(dir) = pending.pop() {
+        let entries = match dir.read_dir_utf8() {
+            Ok(entries) => entries,
+            Err(_) => {
+                diagnostics.push(
+                    Diagnostic::warning(format!(
+                        "could not list {dir}, so its images were not checked"
+                    ))
+                    .at_file(dir.clone()),
+                );
+                continue;
+            }
+        };
+        for entry in entries.flatten() {
+            if entry.file_name().starts_with('.') {
+                continue;
+            }
+            let path = entry.path();
+            if path.is_dir() {
+                pending.push(path.to_owned());
+            } else if path
+                .extension()
+                .is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext))
+            {
+                images.push(super::relative_to(base_dir, path));
+            }
+        }
+    }
+    images.sort();
+    images
+}
+
+/// The finding for `image`, if nothing shows it.
+fn orphaned(
+    image: &Utf8Path,
+    graph: &ReferenceGraph,
+    facts: &BTreeMap<Utf8PathBuf, Facts>,
+) -> Option<Diagnostic> {
+    if facts.values().any(|f| f.named.iter().any(|n| n == image)) {
+        return None;
+    }
+
+    let dir = image.parent().unwrap_or(Utf8Path::new(""));
+    let stem = image.file_stem()?;
+    let recipe_named = |stem: &str| {
+        ["cook", "menu"]
+            .iter()
+            .map(|ext| dir.join(format!("{stem}.{ext}")))
+            .find(|path| graph.node(path).is_some())
+    };
+
+    // A title image: the whole stem is a recipe's.
+    if recipe_named(stem).is_some() {
+        return None;
+    }
+
+    // A step image: the stem is a recipe's, then one or two step numbers.
+    let parts: Vec<&str> = stem.split('.').collect();
+    for numbers in [1, 2] {
+        if parts.len() <= numbers {
+            continue;
+        }
+        let (name, tail) = parts.split_at(parts.len() - numbers);
+        let Ok(tail) = tail
+            .iter()
+            .map(|n| n.parse::<usize>())
+            .collect::<Result<Vec<_>, _>>()
+        else {
+            continue;
+        };
+        if tail.contains(&0) {
+            continue;
+        }
+        let Some(recipe) = recipe_named(&name.join(".")) else {
+            continue;
+        };
+        // A recipe that did not parse has no steps to judge against.
+        let steps = &facts.get(&recipe)?.steps;
+        let (exists, described) = match (tail[0], tail.get(1).copied()) {
+            (step, None) => {
+                let total: usize = steps.iter().sum();
+                (
+                    step <= total,
+                    format!("step {step}, but {recipe} has {total} step(s)"),
+                )
+            }
+            (section, Some(step)) => {
+                let has = steps.get(section - 1).copied().unwrap_or(0);
+                (
+                    step <= has,
+                    if section > steps.len() {
+                        format!(
+                            "section {section}, but {recipe} has {} section(s)",
+                            steps.len()
+                        )
+                    } else {
+                        format!("step {step} of section {section}, but it has {has} step(s)")
+                    },
+                )
+            }
+        };
+        if exists {
+            return None;
+        }
+        return Some(
+            Diagnostic::warning(format!("image is for {described}"))
+                .with_code(ORPHANED_IMAGE)
+                .with_hint("renumber the image to match its step, or remove it")
+                .at_file(image.to_owned()),
+        );
+    }
+
+    Some(
+        Diagnostic::warning("image belongs to no recipe, so it is never shown")
+            .with_code(ORPHANED_IMAGE)
+            .with_hint(format!(
+                "rename it after the recipe it shows — <recipe>.{ext} for the title, \
+                 <recipe>.<step>.{ext} for a step — or name it in the recipe's `image:`",
+                ext = image.extension().unwrap_or("jpg")
+            ))
+            .at_file(image.to_owned()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{graph::tests::collection, Severity};
+
+    fn run(dir: &tempfile::TempDir) -> GraphCheck {
+        let base = Utf8Path::from_path(dir.path()).unwrap().to_owned();
+        check(&Context::new(base), GraphRequest::default())
+            .expect("check succeeds")
+            .into_value()
+    }
+
+    fn codes(report: &GraphCheck) -> Vec<(&str, &str)> {
+        report
+            .findings
+            .iter()
+            .map(|f| {
+                let file = f.location.as_ref().and_then(|l| l.file.as_ref()).unwrap();
+                (f.code.as_deref().unwrap(), file.as_str())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn a_healthy_collection_has_no_findings() {
+        let dir = collection(&[
+            ("Pancakes.cook", "Mix @flour{} and @./Syrup{}.\n\nServe.\n"),
+            ("Syrup.cook", "Boil @sugar{}.\n"),
+            ("Pancakes.jpg", ""),
+            ("Pancakes.2.jpg", ""),
+            ("Syrup.1.1.png", ""),
+        ]);
+        let report = run(&dir);
+
+        assert_eq!(report.total_recipes, 2);
+        assert_eq!(report.total_menus, 0);
+        assert_eq!(report.total_images, 3);
+        assert!(report.findings.is_empty(), "{:?}", report.findings);
+    }
+
+    #[test]
+    fn a_cycle_is_an_error_on_the_reference_that_starts_it() {
+        let dir = collection(&[
+            ("a.cook", "Make @./b{}.\n"),
+            ("b.cook", "Make @./c{}.\n"),
+            ("c.cook", "Make\n@./a{}.\n"),
+            ("self.cook", "Make @./self{}.\n"),
+        ]);
+        let report = run(&dir);
+
+        assert_eq!(
+            codes(&report),
+            [(REFERENCE_CYCLE, "a.cook"), (REFERENCE_CYCLE, "self.cook")]
+        );
+        let first = &report.findings[0];
+        assert_eq!(first.severity, Severity::Error);
+        assert_eq!(
+            first.message,
+            "recipe references form a cycle: a.cook → b.cook → c.cook → a.cook"
+        );
+        let range = first.location.as_ref().unwrap().range.unwrap();
+        assert_eq!((range.start.line, range.start.column), (1, 7));
+        assert_eq!(
+            report.findings[1].message,
+            "recipe references form a cycle: self.cook → self.cook"
+        );
+    }
+
+    #[test]
+    fn menus_are_checked_for_missing_and_unreachable_recipes() {
+        let dir = collection(&[
+            ("Week.menu", "Monday:\n- @./Soup{}\n- @./Stew{}\n"),
+            ("Soup.cook", "Boil @./Stock{}.\n"),
+            ("Stock.cook", "Boil @bones{}.\n"),
+            ("Salad.cook", "Toss @leaves{}.\n"),
+        ]);
+        let report = run(&dir);
+
+        assert_eq!(
+            codes(&report),
+            [
+                (MISSING_MENU_RECIPE, "Week.menu"),
+                (UNREACHABLE_RECIPE, "Salad.cook"),
+            ]
+        );
+        assert_eq!(
+            report.findings[0].message,
+            "menu references recipe './Stew', which does not exist"
+        );
+        assert_eq!(report.count(Severity::Hint), 1);
+    }
+
+    #[test]
+    fn without_menus_no_recipe_is_unreachable() {
+        let dir = collection(&[("Salad.cook", "Toss @leaves{}.\n")]);
+        assert!(run(&dir).findings.is_empty());
+    }
+
+    #[test]
+    fn images_nothing_shows_are_orphaned() {
+        let dir = collection(&[
+            (
+                "Bread.cook",
+                "---\nimage: photos/loaf.webp\n---\n\nKnead @flour{}.\n\nBake.\n",
+            ),
+            ("photos/loaf.webp", ""),
+            ("Bread.3.jpg", ""),
+            ("Bread.2.1.jpg", ""),
+            ("Cake.jpg", ""),
+            (".git/logo.png", ""),
+            ("notes.txt", ""),
+        ]);
+        let report = run(&dir);
+
+        assert_eq!(report.total_images, 4);
+        assert_eq!(
+            codes(&report),
+            [
+                (ORPHANED_IMAGE, "Bread.2.1.jpg"),
+                (ORPHANED_IMAGE, "Bread.3.jpg"),
+                (ORPHANED_IMAGE, "Cake.jpg"),
+            ]
+        );
+        let messages: Vec<&str> = report.findings.iter().map(|f| f.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            [
+                "image is for section 2, but Bread.cook has 1 section(s)",
+                "image is for step 3, but Bread.cook has 2 step(s)",
+                "image belongs to no recipe, so it is never shown",
+            ]
+        );
+    }
+}
@@ -0,0 +1,809 @@
+//! House-style rules for recipes, configured by `doctor.toml`.
+//!
+//! The parser decides whether a recipe is *valid* Cooklang. These rules decide
+//! whether it is the recipe a particular collection wants: one that states its
+//! servings, whose title agrees with its file name, whose images are where it
+//! says they are. None of that is wrong in general, so no rule runs unless the
+//! collection asks for it.
+//!
+//! # Configuration
+//!
+//! `doctor.toml` is found the way `aisle.conf` and `pantry.conf` are — see
+//! [`Context::discover`](crate::Context::discover) — and holds one table per
+//! enabled rule, under `rules`:
+//!
+//! ```toml
+//! [rules.required-metadata]
+//! severity = "error"
+//! keys = ["servings", "tags", "time"]
+//!
+//! [rules.images]
+//! required = true
+//!
+//! [rules.unitless-quantity]
+//! allow = ["egg", "eggs"]
+//!
+//! [rules.max-steps]
+//! max = 12
+//!
+//! [rules.title-matches-filename]
+//! severity = "hint"
+//! ```
+//!
+//! Every rule takes a `severity` of `error`, `warning`, `hint` or `off`,
+//! defaulting to `warning`. The other keys are each rule's own; see [`Rule`].
+//! A table this module does not recognise, or a key inside one, is reported as
+//! a warning against `doctor.toml` rather than failing the run — so a
+//! collection shared between two versions of `cook` is not unusable on the
+//! older one.
+//!
+//! Every finding is an ordinary [`Diagnostic`] whose
+//! [`code`](Diagnostic::code) is the rule's id, with a hint saying how to fix
+//! it, so it reaches every output `cook doctor validate` has without any of
+//! them knowing rules exist.
+
+use crate::{
+    diagnostic::{parse_failure, Severity},
+    ConfigSource, CoreError, Diagnostic, Location, Outcome, Span,
+};
+use camino::Utf8Path;
+use cooklang::{metadata::StdKey, quantity::Value, Recipe};
+use std::str::FromStr;
+use toml_edit::DocumentMut;
+
+/// Every rule this module knows, by id, with a one-line description.
+///
+/// In the order they run, which is the order their findings are reported in
+/// for any one recipe. The descriptions are what a SARIF viewer shows beside
+/// a finding, so they describe the rule rather than any one violation of it.
+pub const RULES: &[(&str, &str)] = &[
+    (
+        REQUIRED_METADATA,
+        "Recipe front matter must set the configured keys",
+    ),
+    (
+        IMAGES,
+        "Images a recipe names must exist, and a title image may be required",
+    ),
+    (
+        UNITLESS_QUANTITY,
+        "Ingredient quantities must carry a unit unless the ingredient is allowed to be counted",
+    ),
+    (
+        MAX_STEPS,
+        "Recipes must not have more steps than configured",
+    ),
+    (
+        TITLE_MATCHES_FILENAME,
+        "A recipe's title must match its file name",
+    ),
+];
+
+const REQUIRED_METADATA: &str = "required-metadata";
+const IMAGES: &str = "images";
+const UNITLESS_QUANTITY: &str = "unitless-quantity";
+const MAX_STEPS: &str = "max-steps";
+const TITLE_MATCHES_FILENAME: &str = "title-matches-filename";
+
+/// The keys `required-metadata` asks for when `keys` is not given.
+const DEFAULT_REQUIRED_KEYS: &[&str] = &["servings", "tags", "time"];
+
+/// The extensions `cooklang-find` recognises as a recipe's title image, in the
+/// order it tries them.
+pub(crate) const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "webp"];
+
+/// One house-style rule and its settings.
+///
+/// `#[non_exhaustive]` because rules will be added, and a consumer matching
+/// on this must not break when one is.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rule {
+    /// Each of `keys` must be set, to something other than null, in the
+    /// recipe's front matter.
+    ///
+    /// A key Cooklang gives aliases to is satisfied by any of them — `serves`
+    /// for `servings`, `duration` for `time` — and `time` is also satisfied by
+    /// `prep time` or `cook time`, the way the recipe page adds them up.
+    /// Defaults to `servings`, `tags` and `time`.
+    RequiredMetadata {
+        /// The keys every recipe must set.
+        keys: Vec<String>,
+    },
+    /// Every image the front matter names by a local path must exist, relative
+    /// to the recipe's directory. URLs are not fetched.
+    ///
+    /// With `required`, a recipe must also *have* a title image: one named in
+    /// the front matter, or a file beside it with the same stem, as the web
+    /// UI finds them.
+    Images {
+        /// Whether a recipe with no title image at all is a finding.
+        required: bool,
+    },
+    /// An ingredient quantity that is a number with no unit is a finding,
+    /// unless the ingredient is in `allow` — compared lowercased, so that
+    /// `@eggs{3}` can be allowed while `@flour{200}` is not.
+    UnitlessQuantity {
+        /// Ingredients that are counted rather than measured.
+        allow: Vec<String>,
+    },
+    /// A recipe with more than `max` steps, across all its sections, is a
+    /// finding. Defaults to 15.
+    MaxSteps {
+        /// The most steps a recipe may have.
+        max: usize,
+    },
+    /// The recipe's `title`, when it has one, must equal its file stem,
+    /// ignoring case. A recipe with no title takes its name from the file, so
+    /// it trivially agrees.
+    TitleMatchesFilename,
+}
+
+impl Rule {
+    /// The id the rule is configured by and its findings carry as their
+    /// [`code`](Diagnostic::code).
+    pub fn id(&self) -> &'static str {
+        match self {
+            Rule::RequiredMetadata { .. } => REQUIRED_METADATA,
+            Rule::Images { .. } => IMAGES,
+            Rule::UnitlessQuantity { .. } => UNITLESS_QUANTITY,
+            Rule::MaxSteps { .. } => MAX_STEPS,
+            Rule::TitleMatchesFilename => TITLE_MATCHES_FILENAME,
+        }
+    }
+}
+
+/// An enabled rule and the severity its findings are raised at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfiguredRule {
+    /// What to check.
+    pub rule: Rule,
+    /// How much a finding matters.
+    pub severity: Severity,
+}
+
+/// A parsed `doctor.toml`: the rules to run, in [`RULES`] order.
+///
+/// Empty by default, and when there is no `doctor.toml` at all: no rule runs
+/// unless a collection asks for it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LintConfig {
+    /// The enabled rules. A rule configured with `severity = "off"` is not
+    /// here.
+    pub rules: Vec<ConfiguredRule>,
+}
+
+impl LintConfig {
+    /// Read and parse the configuration `source` names.
+    ///
+    /// An unset source is an empty configuration, not an error. Anything in
+    /// the file this module does not understand — an unknown rule, an unknown
+    /// key, a value of the wrong type — comes back as a warning in
+    /// [`Outcome::diagnostics`], located at the file, and is otherwise
+    /// ignored.
+    ///
+    /// # Errors
+    ///
+    /// - [`CoreError::Io`] if the file cannot be read.
+    /// - [`CoreError::Config`] if it is not TOML at all.
+    pub fn load(source: &ConfigSource) -> Result<Outcome<Self>, CoreError> {
+        match source.read()? {
+            Some(text) => Self::parse(&text, source.path()),
+            None => Ok(Outcome::new(Self::default())),
+        }
+    }
+
+    /// Parse `doctor.toml` text. `path` is where it came from, if anywhere,
+    /// and is what the warnings and the error are attributed to.
+    ///
+    /// # Errors
+    ///
+    /// [`CoreError::Config`] if `text` is not TOML.
+    pub fn parse(text: &str, path: Option<&Utf8Path>) -> Result<Outcome<Self>, CoreError> {
+        let doc = text.parse::<DocumentMut>().map_err(|e| CoreError::Config {
+            path: path.map(ToOwned::to_owned),
+            message: parse_failure(&[Diagnostic::error(e.to_string())], "doctor"),
+        })?;
+
+        let mut warnings = Vec::new();
+        let mut warn = |message: String| {
+            let diagnostic = Diagnostic::warning(message);
+            warnings.push(match path {
+                Some(path) => diagnostic.at_file(path),
+                None => diagnostic,
+            });
+        };
+
+        for (key, _) in doc.iter() {
+            if key != "rules" {
+                warn(format!("unknown doctor.toml table '{key}'"));
+            }
+        }
+
+        let Some(rules) = doc.get("rules") else {
+            return Ok(Outcome::with_diagnostics(Self::default(), warnings));
+        };
+        let Some(rules) = rules.as_table_like() else {
+            warn("'rules' in doctor.toml must be a table".to_string());
+            return Ok(Outcome::with_diagnostics(Self::default(), warnings));
+        };
+
+        for (id, _) in rules.iter() {
+            if !RULES.iter().any(|(known, _)| *known == id) {
+                warn(format!("unknown doctor rule '{id}'"));
+            }
+        }
+
+        let mut config = Self::default();
+        for (id, _) in RULES {
+            let Some(table) = rules.get(id) else {
+                continue;
+            };
+            let Some(table) = table.as_table_like() else {
+                warn(format!("rule '{id}' in doctor.toml must be a table"));
+                continue;
+            };
+
+            let mut settings = Settings {
+                id,
+                table,
+                warn: &mut warn,
+            };
+            let severity = settings.severity();
+            let rule = match *id {
+                REQUIRED_METADATA => Rule::RequiredMetadata {
+                    keys: settings.strings("keys").unwrap_or_else(|| {
+                        DEFAULT_REQUIRED_KEYS
+                            .iter()
+                            .map(|k| k.to_string())
+                            .collect()
+                    }),
+                },
+                IMAGES => Rule::Images {
+                    required: settings.boolean("required").unwrap_or(false),
+                },
+                UNITLESS_QUANTITY => Rule::UnitlessQuantity {
+                    allow: settings
+                        .strings("allow")
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|name| name.to_lowercase())
+                        .collect(),
+                },
+                MAX_STEPS => Rule::MaxSteps {
+                    max: settings.count("max").unwrap_or(15),
+                },
+                _ => Rule::TitleMatchesFilename,
+            };
+            settings.unknown_keys();
+
+            if let Some(severity) = severity {
+                config.rules.push(ConfiguredRule { rule, severity });
+            }
+        }
+
+        Ok(Outcome::with_diagnostics(config, warnings))
+    }
+
+    /// Whether any rule is enabled. Skipping the lint pass entirely when none
+    /// is saves re-walking the metadata of every recipe for nothing.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Run every enabled rule against one parsed recipe.
+    ///
+    /// `path` is the recipe's path as it is reported, and what each finding is
+    /// attributed to; `full_path` is where it is on disk, for the rules that
+    /// look beside it. `source` is the text it was parsed from, which findings
+    /// that can point at a particular line are located in.
+    pub fn check(
+        &self,
+        recipe: &Recipe,
+        source: &str,
+        path: &Utf8Path,
+        full_path: &Utf8Path,
+    ) -> Vec<Diagnostic> {
+        let target = Target {
+            recipe,
+            source,
+            path,
+            full_path,
+        };
+        self.rules
+            .iter()
+            .flat_map(|configured| {
+                target
+                    .check(&configured.rule)
+                    .into_iter()
+                    .map(|mut diagnostic| {
+                        diagnostic.severity = configured.severity;
+                        diagnostic.with_code(configured.rule.id())
+                    })
+            })
+            .collect()
+    }
+}
+
+/// One rule's table, read key by key, with everything wrong in it reported
+/// through `warn`.
+struct Settings<'a, F: FnMut(String)> {
+    id: &'a str,
+    table: &'a dyn toml_edit::TableLike,
+    warn: &'a mut F,
+}
+
+impl<F: FnMut(String)> Settings<'_, F> {
+    /// The configured severity, `None` for `off`. An unreadable value keeps
+    /// the rule on at the default rather than silently disabling it.
+    fn severity(&mut self) -> Option<Severity> {
+        let Some(item) = self.table.get("severity") else {
+            return Some(Severity::Warning);
+        };
+        match item.as_str() {
+            Some("error") => Some(Severity::Error),
+            Some("warning") => Some(Severity::Warning),
+            Some("hint") => Some(Severity::Hint),
+            Some("off") => None,
+            _ => {
+                (self.warn)(format!(
+                    "rule '{}': severity must be one of \"error\", \"warning\", \"hint\" or \"off\"",
+                    self.id
+                ));
+                Some(Severity::Warning)
+            }
+        }
+    }
+
+    fn strings(&mut self, key: &str) -> Option<Vec<String>> {
+        let item = self.table.get(key)?;
+        let strings = item.as_array().and_then(|array| {
+            array
+                .iter()
+                .map(|v| v.as_str().map(str::to_string))
+                .collect::<Option<Vec<_>>>()
+        });
+        if strings.is_none() {
+            (self.warn)(format!(
+                "rule '{}': '{key}' must be an array of strings",
+                self.id
+            ));
+        }
+        strings
+    }
+
+    fn boolean(&mut self, key: &str) -> Option<bool> {
+        let item = self.table.get(key)?;
+        let value = item.as_bool();
+        if value.is_none() {
+            (self.warn)(format!("rule '{}': '{key}' must be true or false", self.id));
+        }
+        value
+    }
+
+    fn count(&mut self, key: &str) -> Option<usize> {
+        let item = self.table.get(key)?;
+        let value = item.as_integer().and_then(|n| usize::try_from(n).ok());
+        if value.is_none() {
+            (self.warn)(format!(
+                "rule '{}': '{key}' must be a non-negative whole number",
+                self.id
+            ));
+        }
+        value
+    }
+
+    /// Warn about every key that is not a setting of this rule — including
+    /// one that is a setting of another.
+    fn unknown_keys(&mut self) {
+        let own: &[&str] = match self.id {
+            REQUIRED_METADATA => &["keys"],
+            IMAGES => &["required"],
+            UNITLESS_QUANTITY => &["allow"],
+            MAX_STEPS => &["max"],
+            _ => &[],
+        };
+        let unknown: Vec<String> = self
+            .table
+            .iter()
+            .map(|(key, _)| key.to_string())
+            .filter(|key| key != "severity" && !own.contains(&key.as_str()))
+            .collect();
+        for key in unknown {
+            (self.warn)(format!("rule '{}': unknown setting '{key}'", self.id));
+        }
+    }
+}
+
+/// The recipe a rule is checking, with everything a rule might need of it.
+struct Target<'a> {
+    recipe: &'a Recipe,
+    source: &'a str,
+    path: &'a Utf8Path,
+    full_path: &'a Utf8Path,
+}
+
+impl Target<'_> {
+    fn check(&self, rule: &Rule) -> Vec<Diagnostic> {
+        match rule {
+            Rule::RequiredMetadata { keys } => self.required_metadata(keys),
+            Rule::Images { required } => self.images(*required),
+            Rule::UnitlessQuantity { allow } => self.unitless_quantities(allow),
+            Rule::MaxSteps { max } => self.max_steps(*max),
+            Rule::TitleMatchesFilename => self.title_matches_filename(),
+        }
+    }
+
+    /// A finding about the recipe as a whole.
+    fn finding(&self, message: String, hint: String) -> Diagnostic {
+        Diagnostic::warning(message)
+            .with_hint(hint)
+            .at_file(self.path)
+    }
+
+    /// A finding about the text at `span`, or about the whole recipe when the
+    /// text could not be found.
+    fn finding_at(&self, message: String, hint: String, span: Option<Span>) -> Diagnostic {
+        let mut diagnostic = self.finding(message, hint);
+        if let Some(span) = span {
+            diagnostic.location = Some(Location {
+                file: Some(self.path.to_owned()),
+                span: Some(span),
+                range: Some(span.to_range(self.source)),
+            });
+        }
+        diagnostic
+    }
+
+    /// Whether the front matter sets `key`, or an alias of it, to anything
+    /// but null.
+    fn has_metadata(&self, key: &str) -> bool {
+        let wanted = StdKey::from_str(key).ok();
+        self.recipe.metadata.map.iter().any(|(k, v)| {
+            let Some(k) = k.as_str() else {
+                return false;
+            };
+            if v.is_null() {
+                return false;
+            }
+            if k == key {
+                return true;
+            }
+            let Some(wanted) = wanted else {
+                return false;
+            };
+            match StdKey::from_str(k) {
+                Ok(found) if found == wanted => true,
+                Ok(StdKey::PrepTime | StdKey::CookTime) => wanted == StdKey::Time,
+                _ => false,
+            }
+        })
+    }
+
+    fn required_metadata(&self, keys: &[String]) -> Vec<Diagnostic> {
+        keys.iter()
+            .filter(|key| !self.has_metadata(key))
+            .map(|key| {
+                self.finding(
+                    format!("missing required metadata '{key}'"),
+                    format!("add `{key}:` to the recipe's front matter"),
+                )
+            })
+            .collect()
+    }
+
+    fn images(&self, required: bool) -> Vec<Diagnostic> {
+        let dir = self.full_path.parent().unwrap_or(Utf8Path::new(""));
+        let mut named = Vec::new();
+        for key in ["image", "images", "picture", "pictures"] {
+            match self.recipe.metadata.map.get(key) {
+                Some(serde_yaml::Value::String(image)) => named.push(image.as_str()),
+                Some(serde_yaml::Value::Sequence(images)) => {
+                    named.extend(images.iter().filter_map(|v| v.as_str()))
+                }
+                _ => {}
+            }
+        }
+
+        let mut findings: Vec<Diagnostic> = named
+            .iter()
+            .filter(|image| !image.starts_with("http://") && !image.starts_with("https://"))
+            .filter(|image| !dir.join(image).is_file())
+            .map(|image| {
+                self.finding_at(
+                    format!("image '{image}' does not exist"),
+                    format!("add {image} next to the recipe, or correct the path"),
+                    find(self.source, image, 0),
+                )
+            })
+            .collect();
+
+        let beside = IMAGE_EXTENSIONS
+            .iter()
+            .any(|ext| self.full_path.with_extension(ext).is_file());
+        if required && named.is_empty() && !beside {
+            let stem = self.full_path.file_stem().unwrap_or("recipe");
+            findings.push(self.finding(
+                "recipe has no image".to_string(),
+                format!("add {stem}.jpg next to the recipe, or an `image:` to its front matter"),
+            ));
+        }
+        findings
+    }
+
+    fn unitless_quantities(&self, allow: &[String]) -> Vec<Diagnostic> {
+        // Ingredients come back in the order they are defined, so searching
+        // on from the last one found keeps two uses of one name apart.
+        let mut cursor = 0;
+        let mut findings = Vec::new();
+        for ingredient in &self.recipe.ingredients {
+            if ingredient.reference.is_some() || !ingredient.relation.is_definition() {
+                continue;
+            }
+            let Some(quantity) = &ingredient.quantity else {
+                continue;
+            };
+            if quantity.unit().is_some() || matches!(quantity.value(), Value::Text(_)) {
+                continue;
+            }
+            let span = find(self.source, &format!("{}{{", ingredient.name), cursor);
+            if let Some(span) = span {
+                cursor = span.end;
+            }
+            if allow.contains(&ingredient.name.to_lowercase()) {
+                continue;
+            }
+            findings.push(self.finding_at(
+                format!(
+                    "quantity {} of '{}' has no unit",
+                    quantity.value(),
+                    ingredient.name
+                ),
+                format!(
+                    "write it with a unit, `@{}{{{}%g}}`, or add '{}' to this rule's `allow` list",
+                    ingredient.name,
+                    quantity.value(),
+                    ingredient.name.to_lowercase()
+                ),
+                span,
+            ));
+        }
+        findings
+    }
+
+    fn max_steps(&self, max: usize) -> Vec<Diagnostic> {
+        let steps = self
+            .recipe
+            .sections
+            .iter()
+            .flat_map(|section| &section.content)
+            .filter(|content| content.is_step())
+            .count();
+        if steps <= max {
+            return Vec::new();
+        }
+        vec![self.finding(
+            format!("recipe has {steps} steps, more than the {max} allowed"),
+            "split it into sections or sub-recipes, or combine short steps".to_string(),
+        )]
+    }
+
+    fn title_matches_filename(&self) -> Vec<Diagnostic> {
+        let (Some(title), Some(stem)) = (self.recipe.metadata.title(), self.path.file_stem())
+        else {
+            return Vec::new();
+        };
+        if title.trim().to_lowercase() == stem.to_lowercase() {
+            return Vec::new();
+        }
+        vec![self.finding_at(
+            format!("title '{title}' does not match the file name '{stem}'"),
+            format!("rename the file to '{title}.cook', or the title to '{stem}'"),
+            find(self.source, title, 0),
+        )]
+    }
+}
+
+/// The span of the first `needle` in `source` at or after byte `from`.
+fn find(source: &str, needle: &str, from: usize) -> Option<Span> {
+    let start = from + source.get(from..)?.find(needle)?;
+    Some(Span::from(start..start + needle.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::PARSER;
+
+    fn config(text: &str) -> LintConfig {
+        let outcome = LintConfig::parse(text, None).expect("parses");
+        assert!(
+            outcome.diagnostics.is_empty(),
+            "unexpected warnings: {:?}",
+            outcome.diagnostics
+        );
+        outcome.value
+    }
+
+    /// Lint `recipe` as `dir/name`, where `dir` is a real directory so the
+    /// image rule has somewhere to look.
+    fn lint(config: &LintConfig, dir: &Utf8Path, name: &str, recipe: &str) -> Vec<Diagnostic> {
+        let parsed = PARSER.parse(recipe).into_output().expect("recipe parses");
+        config.check(&parsed, recipe, Utf8Path::new(name), &dir.join(name))
+    }
+
+    fn tempdir() -> (tempfile::TempDir, camino::Utf8PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = Utf8Path::from_path(dir.path()).unwrap().to_owned();
+        (dir, path)
+    }
+
+    fn codes(diagnostics: &[Diagnostic]) -> Vec<&str> {
+        diagnostics
+            .iter()
+            .map(|d| d.code.as_deref().unwrap_or_default())
+            .collect()
+    }
+
+    #[test]
+    fn nothing_runs_without_configuration() {
+        let config = config("");
+        assert!(config.is_empty());
+        let (_dir, path) = tempdir();
+        assert!(lint(&config, &path, "soup.cook", "Add @flour{200}.\n").is_empty());
+    }
+
+    #[test]
+    fn severities_default_to_warning_and_off_disables() {
+        let config = config(
+            "[rules.max-steps]\n\
+             [rules.title-matches-filename]\nseverity = \"error\"\n\
+             [rules.images]\nseverity = \"off\"\n",
+        );
+        assert_eq!(
+            config.rules,
+            vec![
+                ConfiguredRule {
+                    rule: Rule::MaxSteps { max: 15 },
+                    severity: Severity::Warning,
+                },
+                ConfiguredRule {
+                    rule: Rule::TitleMatchesFilename,
+                    severity: Severity::Error,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_rules_and_settings_are_warnings_not_failures() {
+        let outcome = LintConfig::parse(
+            "[rules.no-such-rule]\n\
+             [rules.max-steps]\nmax = \"lots\"\ncolour = 1\n\
+             [rules.images]\nseverity = \"loud\"\n",
+            Some(Utf8Path::new("config/doctor.toml")),
+        )
+        .unwrap();
+        let messages: Vec<&str> = outcome
+            .diagnostics
+            .iter()
+            .map(|d| d.message.as_str())
+            .collect();
+        assert!(messages.contains(&"unknown doctor rule 'no-such-rule'"));
+        assert!(messages.contains(&"rule 'max-steps': 'max' must be a non-negative whole number"));
+        assert!(messages.contains(&"rule 'max-steps': unknown setting 'colour'"));
+        assert!(messages
+            .iter()
+            .any(|m| m.starts_with("rule 'images': severity must be")));
+        assert!(outcome
+            .diagnostics
+            .iter()
+            .all(|d| d.severity == Severity::Warning
+                && d.location.as_ref().and_then(|l| l.file.as_deref())
+                    == Some(Utf8Path::new("config/doctor.toml"))));
+        // A bad value falls back to the default rather than dropping the rule.
+        assert_eq!(outcome.value.rules.len(), 2);
+    }
+
+    #[test]
+    fn a_file_that_is_not_toml_is_a_config_error() {
+        let err = LintConfig::parse("[rules", Some(Utf8Path::new("doctor.toml"))).unwrap_err();
+        assert!(matches!(err, CoreError::Config { .. }));
+        assert!(!err.to_string().contains('\n'));
+    }
+
+    #[test]
+    fn required_metadata_accepts_aliases_and_reports_each_missing_key() {
+        let config = config("[rules.required-metadata]\nseverity = \"error\"\n");
+        let (_dir, path) = tempdir();
+
+        let found = lint(
+            &config,
+            &path,
+            "soup.cook",
+            "---\nserves: 4\nprep time: 10 min\n---\nBoil @water{1%l}.\n",
+        );
+        assert_eq!(found.len(), 1, "{found:?}");
+        assert_eq!(found[0].message, "missing required metadata 'tags'");
+        assert_eq!(found[0].severity, Severity::Error);
+        assert_eq!(found[0].code.as_deref(), Some("required-metadata"));
+        assert!(!found[0].hints.is_empty());
+    }
+
+    #[test]
+    fn images_must_exist_and_can_be_required() {
+        let config = config("[rules.images]\nrequired = true\n");
+        let (_dir, path) = tempdir();
+
+        let found = lint(
+            &config,
+            &path,
+            "soup.cook",
+            "---\nimage: soup.png\n---\nBoil @water{1%l}.\n",
+        );
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].message, "image 'soup.png' does not exist");
+        let range = found[0].location.as_ref().unwrap().range.unwrap();
+        assert_eq!(range.start.line, 2);
+
+        let found = lint(&config, &path, "tea.cook", "Boil @water{1%l}.\n");
+        assert_eq!(found[0].message, "recipe has no image");
+
+        std::fs::write(path.join("tea.jpg"), "").unwrap();
+        assert!(lint(&config, &path, "tea.cook", "Boil @water{1%l}.\n").is_empty());
+    }
+
+    #[test]
+    fn unitless_quantities_are_located_and_can_be_allowed() {
+        let config = config("[rules.unitless-quantity]\nallow = [\"Eggs\"]\n");
+        let (_dir, path) = tempdir();
+        let recipe = "Beat @eggs{3}.\nAdd @flour{200} and @salt{a pinch} and @milk{1%cup}.\n";
+
+        let found = lint(&config, &path, "cake.cook", recipe);
+        assert_eq!(codes(&found), ["unitless-quantity"]);
+        assert_eq!(found[0].message, "quantity 200 of 'flour' has no unit");
+        let location = found[0].location.as_ref().unwrap();
+        let span = location.span.unwrap();
+        assert_eq!(&recipe[span.start..span.end], "flour{");
+        assert_eq!(location.range.unwrap().start.line, 2);
+    }
+
+    #[test]
+    fn max_steps_counts_every_section() {
+        let config = config("[rules.max-steps]\nmax = 2\n");
+        let (_dir, path) = tempdir();
+        let recipe = "= One\nStep one.\n\nStep two.\n\n= Two\nStep three.\n";
+
+        let found = lint(&config, &path, "long.cook", recipe);
+        assert_eq!(found.len(), 1);
+        assert_eq!(
+            found[0].message,
+            "recipe has 3 steps, more than the 2 allowed"
+        );
+    }
+
+    #[test]
+    fn title_must_match_the_file_stem_ignoring_case() {
+        let config = config("[rules.title-matches-filename]\n");
+        let (_dir, path) = tempdir();
+
+        assert!(lint(
+            &config,
+            &path,
+            "Pea Soup.cook",
+            "---\ntitle: pea soup\n---\nBoil @water{1%l}.\n"
+        )
+        .is_empty());
+
+        let found = lint(
+            &config,
+            &path,
+            "soup.cook",
+            "---\ntitle: Leek Soup\n---\nBoil @water{1%l}.\n",
+        );
+        assert_eq!(
+            found[0].message,
+            "title 'Leek Soup' does not match the file name 'soup'"
+        );
+    }
+}
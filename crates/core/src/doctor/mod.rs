@@ -9,15 +9,31 @@
 //! [`aisle_coverage`] and [`pantry_coverage`] answer the other two questions
 //! `cook doctor` asks: which of a collection's ingredients are categorised in
 //! `aisle.conf`, and which of them are already in the pantry.
+//!
+//! [`graph`] checks how the collection's files fit together: reference
+//! cycles, menus pointing nowhere, images nothing shows.
+//!
+//! [`names`] asks a question of the collection alone: which of its
+//! ingredients are spelled more than one way.
+//!
+//! [`lint`] holds the house-style rules a collection can switch on in
+//! `doctor.toml`, which [`validate`] runs on every recipe that parses.
+
+pub mod graph;
+pub mod lint;
+pub mod names;
 
 use crate::{
     diagnostic::{parse_failure, Severity},
+    doctor::lint::LintConfig,
     find::{build_tree, listed_ingredients, parse_or_skip, walk},
     parser::{collect_diagnostics, render_report, PARSER},
+    substitutes::{Substitutes, Substitution},
     ConfigSource, Context, CoreError, Diagnostic, Outcome, Style,
 };
 use camino::{Utf8Path, Utf8PathBuf};
 use cooklang_find::RecipeEntry;
+use serde::Serialize;
 use std::collections::{BTreeMap, BTreeSet};
 
 /// A validation run.
@@ -45,7 +61,7 @@ pub struct ValidateRequest {
 /// `#[non_exhaustive]` because this is an output type consumers read rather
 /// than construct.
 #[non_exhaustive]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct RecipeValidation {
     /// Where the recipe sits under the validation root.
     ///
@@ -58,22 +74,33 @@ pub struct RecipeValidation {
     /// [`rendered`]: RecipeValidation::rendered
     pub path: Utf8PathBuf,
     /// Every problem the parser raised, errors and warnings alike, in the order
-    /// the parser produced them. Empty for a recipe with nothing wrong with it.
+    /// the parser produced them, followed by what each enabled
+    /// [`lint`](crate::doctor::lint) rule found, in rule order. Empty for a
+    /// recipe with nothing wrong with it.
+    ///
+    /// The lint findings are the ones with a [`code`](Diagnostic::code); the
+    /// parser's have none.
     ///
     /// A recipe that could not be read at all carries a single error
     /// diagnostic saying so, rather than dropping out of the report.
     pub diagnostics: Vec<Diagnostic>,
     /// The parser's own multi-line report, with the offending source lines
-    /// quoted, ready to print verbatim. Empty exactly when [`diagnostics`] is —
-    /// except for a recipe that could not be read, which has a diagnostic but
-    /// no source to quote, so nothing to render.
+    /// quoted, ready to print verbatim. Empty exactly when the parser raised
+    /// nothing — so a recipe that could not be read, which has a diagnostic but
+    /// no source to quote, has nothing rendered, and neither do lint findings,
+    /// which are not the parser's to render. A caller printing this prints the
+    /// diagnostics with a [`code`](Diagnostic::code) after it.
     ///
     /// Carries ANSI escape codes when [`ValidateRequest::style`] is
     /// [`Style::Ansi`], and none when it is [`Style::Plain`]. This is the one
     /// difference from [`CoreError::Parse::rendered`], which is always plain.
     ///
+    /// Left out of the serialized form when empty, which for a clean recipe
+    /// it always is.
+    ///
     /// [`diagnostics`]: RecipeValidation::diagnostics
     /// [`CoreError::Parse::rendered`]: crate::CoreError::Parse
+    #[serde(skip_serializing_if = "String::is_empty")]
     pub rendered: String,
     /// The recipes this one references, spelled as they are written in it —
     /// `./sauce`, say. In source order, with a recipe referenced twice listed
@@ -103,10 +130,14 @@ impl RecipeValidation {
 /// them on demand is what makes it impossible for a total to disagree with the
 /// recipes it counts.
 ///
+/// Serializes as its fields; the totals, being methods, do not appear.
+/// [`format::doctor`](crate::format::doctor) renders the report together with
+/// its totals and broken references for the machine-readable outputs.
+///
 /// `#[non_exhaustive]` because this is an output type consumers read rather
 /// than construct.
 #[non_exhaustive]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ValidationReport {
     /// The root that was walked: [`ValidateRequest::base_dir`], or the
     /// context's base path when that was unset.
@@ -123,6 +154,12 @@ pub struct ValidationReport {
     /// order that changes between runs. Sorting makes a printed report
     /// diffable.
     pub recipes: Vec<RecipeValidation>,
+    /// What [`Context::doctor`] said that could not be understood — an
+    /// unknown rule, a setting of the wrong type — each a warning located at
+    /// `doctor.toml`. Empty without one, and belonging to no recipe, so in no
+    /// total either.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub config_diagnostics: Vec<Diagnostic>,
 }
 
 impl ValidationReport {
@@ -185,7 +222,11 @@ impl ValidationReport {
 /// Validate every recipe under `req`'s root.
 ///
 /// The root is [`ValidateRequest::base_dir`], or [`Context::base_path`] when
-/// that is unset. Nothing else on the context is consulted.
+/// that is unset. The only configuration consulted is [`Context::doctor`]:
+/// every rule it enables runs on every recipe that parses, and its findings
+/// join that recipe's diagnostics. Menus are not linted — the rules describe
+/// recipes. Anything in `doctor.toml` that could not be understood comes back
+/// as a warning in [`Outcome::diagnostics`], belonging to no recipe.
 ///
 /// # Errors are data
 ///
@@ -225,6 +266,8 @@ impl ValidationReport {
 ///
 /// - [`CoreError::Search`] if the root does not exist, is not a directory, or
 ///   cannot be turned into a search pattern.
+/// - [`CoreError::Config`] if `doctor.toml` is not TOML, and [`CoreError::Io`]
+///   if it cannot be read. Both before any recipe is looked at.
 /// - [`CoreError::Io`] if a file under the root turned up in the walk and could
 ///   not be listed. A file that is listed and then cannot be *read* is not an
 ///   error: it is one recipe in the report carrying one error diagnostic.
@@ -238,11 +281,16 @@ pub fn validate(
 
     tracing::trace!("validating recipes under {base_dir}");
 
+    let Outcome {
+        value: lints,
+        diagnostics: config_diagnostics,
+    } = LintConfig::load(ctx.doctor())?;
+
     let tree = build_tree(&base_dir)?;
 
     let mut recipes: Vec<RecipeValidation> = walk(&tree)
         .into_iter()
-        .map(|entry| validate_entry(entry, &base_dir, req.style))
+        .map(|entry| validate_entry(entry, &base_dir, req.style, &lints))
         .collect();
     // `walk` already orders the entries by their full path, which under one
     // root is the same order; sorted again on the path as reported, because
@@ -250,20 +298,30 @@ pub fn validate(
     // decides to do.
     recipes.sort_by(|a, b| a.path.cmp(&b.path));
 
-    let diagnostics = recipes
+    let diagnostics = config_diagnostics
         .iter()
-        .flat_map(|r| r.diagnostics.iter().cloned())
+        .chain(recipes.iter().flat_map(|r| &r.diagnostics))
+        .cloned()
         .collect();
 
     Ok(Outcome::with_diagnostics(
-        ValidationReport { base_dir, recipes },
+        ValidationReport {
+            base_dir,
+            recipes,
+            config_diagnostics,
+        },
         diagnostics,
     ))
 }
 
 /// Read, parse and describe one recipe. Never fails: a recipe that cannot be
 /// read is described as such, so that one bad file does not end the walk.
-fn validate_entry(entry: &RecipeEntry, base_dir: &Utf8Path, style: Style) -> RecipeValidation {
+fn validate_entry(
+    entry: &RecipeEntry,
+    base_dir: &Utf8Path,
+    style: Style,
+    lints: &LintConfig,
+) -> RecipeValidation {
     // `build_tree` only ever produces named, file-backed entries, so neither
     // fallback is reachable through it. They are kept because skipping an entry
     // instead would make `total_recipes` disagree with the tree that was
@@ -293,7 +351,7 @@ fn validate_entry(entry: &RecipeEntry, base_dir: &Utf8Path, style: Style) -> Rec
     };
 
     let parsed = PARSER.parse(&content);
-    let diagnostics = collect_diagnostics(parsed.report(), Some(&path));
+    let mut diagnostics = collect_diagnostics(parsed.report(), &content, Some(&path));
 
     // `write` on an empty report produces an empty string anyway; the guard is
     // to skip indexing the source lines of every healthy recipe in a
@@ -324,6 +382,12 @@ fn validate_entry(entry: &RecipeEntry, base_dir: &Utf8Path, style: Style) -> Rec
         })
         .unwrap_or_default();
 
+    if !lints.is_empty() && !entry.is_menu() {
+        if let Some(recipe) = parsed.output() {
+            diagnostics.extend(lints.check(recipe, &content, &path, &full_path));
+        }
+    }
+
     RecipeValidation {
         path,
         diagnostics,
@@ -343,7 +407,7 @@ fn validate_entry(entry: &RecipeEntry, base_dir: &Utf8Path, style: Style) -> Rec
 /// back as [`CoreError::Search`] from [`validate`], never as a path here.
 /// Returning the path whole still beats unwrapping, in a crate a NAPI addon
 /// calls.
-fn relative_to(base_dir: &Utf8Path, path: &Utf8Path) -> Utf8PathBuf {
+pub(crate) fn relative_to(base_dir: &Utf8Path, path: &Utf8Path) -> Utf8PathBuf {
     path.strip_prefix(base_dir).unwrap_or(path).to_owned()
 }
 
@@ -415,6 +479,10 @@ pub struct CheckedIngredient {
     pub name: String,
     /// Whether the configuration names this ingredient.
     pub known: bool,
+    /// What made it known when the configuration does not name it but does
+    /// name a substitute for it. Only [`pantry_coverage`] consults
+    /// substitutes, so this is always `None` from [`aisle_coverage`].
+    pub substituted: Option<Substitution>,
 }
 
 /// How much of a collection's ingredients a configuration accounts for.
@@ -515,7 +583,7 @@ pub fn aisle_coverage(
 
     if let Some(text) = source.read()? {
         let parsed = cooklang::aisle::parse_lenient(&text);
-        diagnostics.extend(collect_diagnostics(parsed.report(), source.path()));
+        diagnostics.extend(collect_diagnostics(parsed.report(), &text, source.path()));
         let conf = parsed
             .output()
             .ok_or_else(|| config_error(source, "aisle", &diagnostics))?;
@@ -523,7 +591,7 @@ pub fn aisle_coverage(
         known.extend(conf.ingredients_info().into_keys());
     }
 
-    coverage(ctx, req, &known, diagnostics)
+    coverage(ctx, req, &known, &Substitutes::default(), diagnostics)
 }
 
 /// Check the collection's ingredients against the pantry configuration
@@ -535,6 +603,11 @@ pub fn aisle_coverage(
 /// pantry configuration nothing is known; see
 /// [`unknown`](IngredientCoverage::unknown).
 ///
+/// An ingredient the pantry does not stock is known too when the
+/// [`Context::substitutes`] configuration names a substitute for it that the
+/// pantry does stock, all of it; [`CheckedIngredient::substituted`] says
+/// which.
+///
 /// Note the direction: this reports on the *collection's* ingredients, so a
 /// pantry item no recipe uses is not mentioned at all.
 ///
@@ -543,7 +616,8 @@ pub fn aisle_coverage(
 /// Exactly as [`aisle_coverage`], except that [`CoreError::Config`] is
 /// genuinely reachable here — a `pantry.conf` that is not TOML — and comes back
 /// worded identically to [`pantry::load`](crate::pantry::load)'s, so that two
-/// commands reading one broken file say the same thing about it.
+/// commands reading one broken file say the same thing about it. A
+/// `substitutes.conf` that is not TOML is a [`CoreError::Config`] as well.
 pub fn pantry_coverage(
     ctx: &Context,
     req: CoverageRequest,
@@ -554,14 +628,20 @@ pub fn pantry_coverage(
 
     if let Some(text) = source.read()? {
         let parsed = cooklang::pantry::parse_lenient(&text);
-        diagnostics.extend(collect_diagnostics(parsed.report(), source.path()));
+        diagnostics.extend(crate::pantry::pantry_diagnostics(
+            parsed.report(),
+            &text,
+            source.path(),
+        ));
         let conf = parsed
             .output()
             .ok_or_else(|| config_error(source, "pantry", &diagnostics))?;
         known.extend(conf.all_items().map(|item| item.name().to_lowercase()));
     }
+    let substitutes = Substitutes::load(ctx.substitutes())?;
+    diagnostics.extend(substitutes.diagnostics);
 
-    coverage(ctx, req, &known, diagnostics)
+    coverage(ctx, req, &known, &substitutes.value, diagnostics)
 }
 
 /// The failure that leaves a lenient parse with no configuration at all.
@@ -577,11 +657,13 @@ fn config_error(source: &ConfigSource, kind: &str, diagnostics: &[Diagnostic]) -
 }
 
 /// Scan the collection and mark each ingredient against `known`, which holds
-/// the configuration's names already lowercased.
+/// the configuration's names already lowercased, and failing that against the
+/// `substitutes` for it.
 fn coverage(
     ctx: &Context,
     req: CoverageRequest,
     known: &BTreeSet<String>,
+    substitutes: &Substitutes,
     mut diagnostics: Vec<Diagnostic>,
 ) -> Result<Outcome<IngredientCoverage>, CoreError> {
     let base_dir = req
@@ -605,9 +687,20 @@ fn coverage(
 
     let ingredients = names
         .into_iter()
-        .map(|name| CheckedIngredient {
-            known: known.contains(&name.to_lowercase()),
-            name,
+        .map(|name| {
+            if known.contains(&name.to_lowercase()) {
+                return CheckedIngredient {
+                    name,
+                    known: true,
+                    substituted: None,
+                };
+            }
+            let substituted = substitutes.find(&name, |part| known.contains(&part.to_lowercase()));
+            CheckedIngredient {
+                name,
+                known: substituted.is_some(),
+                substituted,
+            }
         })
         .collect();
 
@@ -1140,6 +1233,85 @@ mod tests {
         );
     }
 
+    // -----------------------------------------------------------------------
+    // Lint rules
+    // -----------------------------------------------------------------------
+
+    /// Validate `base_dir` with `doctor` as its `doctor.toml`.
+    fn run_linted(base_dir: &Utf8Path, doctor: &str) -> Outcome<ValidationReport> {
+        validate(
+            &Context::new(base_dir.to_owned())
+                .with_doctor(ConfigSource::Inline(doctor.to_string())),
+            ValidateRequest::default(),
+        )
+        .expect("validation succeeds")
+    }
+
+    /// Lint findings join the recipe's diagnostics after the parser's, and
+    /// count in its totals like any other — which is what lets `--strict` and
+    /// every output format treat them without knowing they are lints.
+    #[test]
+    fn enabled_rules_add_coded_diagnostics_to_each_recipe() {
+        let dir = fixture();
+        let outcome = run_linted(
+            &base(&dir),
+            "[rules.required-metadata]\nseverity = \"error\"\nkeys = [\"servings\"]\n",
+        );
+        let report = &outcome.value;
+
+        let pancakes = recipe(report, "Breakfast/pancakes.cook");
+        assert_eq!(pancakes.diagnostics.len(), 1);
+        assert_eq!(
+            pancakes.diagnostics[0].code.as_deref(),
+            Some("required-metadata")
+        );
+        assert!(
+            pancakes.rendered.is_empty(),
+            "a lint finding is not the parser's to render"
+        );
+
+        // The broken recipe produced no recipe to lint, so it carries only
+        // what the parser said.
+        assert!(recipe(report, "broken.cook")
+            .diagnostics
+            .iter()
+            .all(|d| d.code.is_none()));
+        assert!(outcome.has_errors());
+        assert!(report.config_diagnostics.is_empty());
+    }
+
+    #[test]
+    fn menus_are_not_linted() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let base = base(&dir);
+        write(&base.join("week.menu"), "Monday: @./soup{}\n");
+        let outcome = run_linted(&base, "[rules.required-metadata]\n");
+        assert!(outcome.value.recipes[0].diagnostics.is_empty());
+    }
+
+    /// A `doctor.toml` problem belongs to no recipe, so it is kept apart from
+    /// them — but it is still in the flat list a caller gates on.
+    #[test]
+    fn doctor_toml_problems_are_reported_apart_from_recipes() {
+        let dir = fixture();
+        let outcome = run_linted(&base(&dir), "[rules.no-such-rule]\n");
+        assert_eq!(outcome.value.config_diagnostics.len(), 1);
+        assert!(outcome
+            .diagnostics
+            .contains(&outcome.value.config_diagnostics[0]));
+    }
+
+    #[test]
+    fn a_doctor_toml_that_is_not_toml_fails_the_run() {
+        let dir = fixture();
+        let err = validate(
+            &Context::new(base(&dir)).with_doctor(ConfigSource::Inline("[rules".to_string())),
+            ValidateRequest::default(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, CoreError::Config { .. }));
+    }
+
     // -----------------------------------------------------------------------
     // Recipe references
     // -----------------------------------------------------------------------
@@ -1347,6 +1519,35 @@ mod tests {
         );
     }
 
+    /// An ingredient is covered when the pantry has a substitute for it, and
+    /// says which; the aisle check never looks at substitutes.
+    #[test]
+    fn a_pantry_substitute_counts_as_knowing_the_ingredient() {
+        let dir = one_recipe("Add @buttermilk{100%ml} and a @shallot{1}.\n");
+        let ctx = pantry_ctx(&dir, "[fridge]\nmilk = \"1%l\"\nlemon = \"2\"\n").with_substitutes(
+            ConfigSource::Inline("buttermilk = \"milk + lemon\"\nshallot = \"onion\"\n".into()),
+        );
+        let coverage = checked(&ctx, false).value;
+
+        assert_eq!(known(&coverage), ["buttermilk"]);
+        assert_eq!(unknown(&coverage), ["shallot"]);
+        assert_eq!(
+            coverage.ingredients[0]
+                .substituted
+                .as_ref()
+                .map(Substitution::with_joined),
+            Some("milk + lemon".to_string())
+        );
+
+        let aisle = checked(
+            &aisle_ctx(&dir, "[dairy]\nmilk\nlemon\n")
+                .with_substitutes(ConfigSource::Inline("buttermilk = \"milk\"\n".into())),
+            true,
+        )
+        .value;
+        assert!(known(&aisle).is_empty());
+    }
+
     /// Both checks compare names ignoring case, and both report the
     /// ingredient as the *recipe* spells it.
     #[test]
@@ -0,0 +1,687 @@
+//! Near-duplicate ingredient names across a collection.
+//!
+//! A collection written over years, by more than one hand, ends up spelling
+//! one ingredient several ways — `egg` and `eggs`, `Parmesan` and `parmesan
+//! cheese`. Each spelling is a separate line on a shopping list and a separate
+//! item to the pantry, so the pantry's `eggs` does nothing for a recipe asking
+//! for `egg`. [`ingredient_names`] finds those spellings and groups them, so
+//! that someone can decide what each ingredient should be called.
+//!
+//! Two names are grouped when any of these holds, and a group is everything
+//! connected to it by them:
+//!
+//! - They differ only in case: `Salt` and `salt`.
+//! - They are the same once each word is made singular, by English's regular
+//!   rules: `egg` and `eggs`, `tomato` and `tomatoes`, `cherry` and
+//!   `cherries`.
+//! - They are a small number of edits apart: `courgette` and `corgette`. How
+//!   many is allowed grows with the length of the name, so that `rice` and
+//!   `ice` stay apart — see [`NamesRequest::max_distance`].
+//! - One is the other with words added, and it is the *only* name the shorter
+//!   one appears in: `parmesan` and `parmesan cheese`, `olive oil` and `extra
+//!   virgin olive oil`. The second condition is what keeps `oil` from joining
+//!   `olive oil` and `sesame oil` into one group — a word found inside several
+//!   names is a category, not a spelling.
+//! - `aisle.conf` already says they are the same ingredient, listing one as a
+//!   synonym of the other.
+//!
+//! None of these is a proof, and the report says which of them grouped each
+//! cluster so that the reader can judge. Nothing is changed: renaming an
+//! ingredient in a recipe is a decision about the recipe.
+
+use super::relative_to;
+use crate::{
+    find::{build_tree, listed_ingredients, parse_or_skip, walk},
+    parser::collect_diagnostics,
+    Context, CoreError, Location, Outcome, Span,
+};
+use camino::Utf8PathBuf;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// An ingredient-name analysis.
+///
+/// Not `#[non_exhaustive]`: consumers construct this. `..Default::default()`
+/// keeps a literal working if it grows a field.
+#[derive(Debug, Clone, Default)]
+pub struct NamesRequest {
+    /// Directory whose recipes to scan. Defaults to the context base path.
+    pub base_dir: Option<Utf8PathBuf>,
+    /// The most single-character edits two names may be apart and still be
+    /// grouped as misspellings of each other. Defaults to 2.
+    ///
+    /// Whatever this says, a name is allowed one edit per four characters and
+    /// no more — `rice` and `ice` are one edit apart and nothing alike — so the
+    /// default allows one edit for names of four to seven characters, two from
+    /// eight, and none below four. `Some(0)` turns the comparison off, leaving
+    /// case, plurals, added words and `aisle.conf` to group names.
+    pub max_distance: Option<usize>,
+}
+
+/// Why two names in a cluster were grouped.
+///
+/// `#[non_exhaustive]` because a new heuristic is a new reason.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Reason {
+    /// The names differ only in case.
+    Case,
+    /// The names are the same once made singular.
+    Plural,
+    /// The names are a few edits apart.
+    Spelling,
+    /// One name is the other with words added.
+    AddedWords,
+    /// `aisle.conf` lists them as the same ingredient.
+    Synonym,
+}
+
+impl Reason {
+    /// How the reason reads in a report: "plural", "added words".
+    pub fn describe(self) -> &'static str {
+        match self {
+            Reason::Case => "case",
+            Reason::Plural => "plural",
+            Reason::Spelling => "spelling",
+            Reason::AddedWords => "added words",
+            Reason::Synonym => "aisle.conf synonym",
+        }
+    }
+}
+
+/// One spelling of an ingredient, and where the collection uses it.
+///
+/// `#[non_exhaustive]` because this is an output type consumers read rather
+/// than construct.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NameUse {
+    /// The name, exactly as the recipes write it.
+    pub name: String,
+    /// Where it is used: one location per recipe, relative to the scanned
+    /// directory, in path order. The span is that of the first `@name` in the
+    /// recipe, and is missing where the name was written some other way — as
+    /// the alias of an `@name|alias{}`, say — and could not be found.
+    pub locations: Vec<Location>,
+}
+
+impl NameUse {
+    /// How many recipes use this spelling.
+    pub fn count(&self) -> usize {
+        self.locations.len()
+    }
+}
+
+/// A group of names that look like one ingredient.
+///
+/// `#[non_exhaustive]` because this is an output type consumers read rather
+/// than construct.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NameCluster {
+    /// Every spelling in the group, most used first, then alphabetically.
+    pub names: Vec<NameUse>,
+    /// Why they were grouped, each reason once.
+    pub reasons: Vec<Reason>,
+    /// The name to settle on.
+    ///
+    /// The common name `aisle.conf` gives the group when it names any of its
+    /// members — that is the name shopping lists already print — and
+    /// otherwise the spelling most recipes use, the shorter winning a tie.
+    pub canonical: String,
+    /// Spellings `aisle.conf` does not know under any name, to add as
+    /// synonyms on the canonical name's line. Lowercased and de-duplicated,
+    /// since `aisle.conf` is matched ignoring case. Empty when there is no
+    /// aisle configuration: there is no line to add them to, and renaming in
+    /// the recipes is the fix.
+    pub missing_synonyms: Vec<String>,
+}
+
+impl NameCluster {
+    /// How many recipe uses the group covers, across all its spellings.
+    pub fn total_uses(&self) -> usize {
+        self.names.iter().map(NameUse::count).sum()
+    }
+}
+
+/// What [`ingredient_names`] found.
+///
+/// `#[non_exhaustive]` because this is an output type consumers read rather
+/// than construct.
+#[non_exhaustive]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct NamesReport {
+    /// How many recipes were scanned, including any that could not be read or
+    /// parsed — those contribute no names, and say so in
+    /// [`Outcome::diagnostics`].
+    pub total_recipes: usize,
+    /// How many distinct spellings the collection uses, case counted.
+    pub total_names: usize,
+    /// Every group of two or more spellings, the most used first.
+    pub clusters: Vec<NameCluster>,
+}
+
+/// Group the collection's ingredient names that look like one ingredient —
+/// the question `cook doctor ingredients` asks.
+///
+/// See [the module documentation](self) for what groups two names. The aisle
+/// configuration [`Context::aisle`] names is read for its synonyms when there
+/// is one; without it the other heuristics still apply.
+///
+/// # Errors
+///
+/// Exactly as [`aisle_coverage`](super::aisle_coverage): the aisle
+/// configuration failing to read is [`CoreError::Io`], and a collection that
+/// cannot be walked is [`CoreError::Search`] or [`CoreError::Io`]. A recipe
+/// that cannot be parsed is left out, with a warning in
+/// [`Outcome::diagnostics`].
+pub fn ingredient_names(
+    ctx: &Context,
+    req: NamesRequest,
+) -> Result<Outcome<NamesReport>, CoreError> {
+    let base_dir = req
+        .base_dir
+        .unwrap_or_else(|| ctx.base_path().to_path_buf());
+    let max_distance = req.max_distance.unwrap_or(2);
+
+    let source = ctx.aisle();
+    let mut diagnostics = Vec::new();
+    // Each name and synonym, lowercased, to the common name of its line.
+    let mut aisle: BTreeMap<String, String> = BTreeMap::new();
+    let configured = !source.is_unset();
+    if let Some(text) = source.read()? {
+        let parsed = cooklang::aisle::parse_lenient(&text);
+        diagnostics.extend(collect_diagnostics(parsed.report(), &text, source.path()));
+        let conf = parsed
+            .output()
+            .ok_or_else(|| super::config_error(source, "aisle", &diagnostics))?;
+        aisle.extend(
+            conf.ingredients_info()
+                .into_iter()
+                .map(|(name, info)| (name, info.common_name.to_string())),
+        );
+    }
+
+    let tree = build_tree(&base_dir)?;
+    let entries = walk(&tree);
+    let total_recipes = entries.len();
+
+    let mut uses: BTreeMap<String, Vec<Location>> = BTreeMap::new();
+    for entry in entries {
+        let Some(recipe) = parse_or_skip(entry, &mut diagnostics) else {
+            continue;
+        };
+        // Read again for the spans: `parse_or_skip` keeps only the recipe.
+        let content = entry.content().ok();
+        let path = entry.path().map(|path| relative_to(&base_dir, path));
+        for name in listed_ingredients(&recipe) {
+            let span = content
+                .as_deref()
+                .and_then(|text| locate(text, &name).map(|span| (span, text)));
+            uses.entry(name).or_default().push(Location {
+                file: path.clone(),
+                span: span.map(|(span, _)| span),
+                range: span.map(|(span, text)| span.to_range(text)),
+            });
+        }
+    }
+
+    let spellings: Vec<String> = uses.keys().cloned().collect();
+    let total_names = spellings.len();
+    let mut groups = Groups::new(spellings.len());
+    let keys: Vec<Key> = spellings.iter().map(|name| Key::new(name)).collect();
+
+    // Case and plurals: the same singular form.
+    let mut by_singular: BTreeMap<&str, usize> = BTreeMap::new();
+    for (i, key) in keys.iter().enumerate() {
+        match by_singular.get(key.singular.as_str()) {
+            Some(&j) => {
+                let reason = if key.lower == keys[j].lower {
+                    Reason::Case
+                } else {
+                    Reason::Plural
+                };
+                groups.join(i, j, reason);
+            }
+            None => {
+                by_singular.insert(&key.singular, i);
+            }
+        }
+    }
+
+    // Synonyms: the same common name in `aisle.conf`.
+    let mut by_common: BTreeMap<&str, usize> = BTreeMap::new();
+    for (i, key) in keys.iter().enumerate() {
+        let Some(common) = aisle.get(&key.lower) else {
+            continue;
+        };
+        let common = common.as_str();
+        match by_common.get(common) {
+            Some(&j) if keys[j].lower != key.lower => groups.join(i, j, Reason::Synonym),
+            Some(_) => {}
+            None => {
+                by_common.insert(common, i);
+            }
+        }
+    }
+
+    for i in 0..keys.len() {
+        for j in i + 1..keys.len() {
+            let (a, b) = (&keys[i], &keys[j]);
+            if a.singular == b.singular {
+                continue;
+            }
+            let allowed =
+                max_distance.min(a.singular.chars().count().min(b.singular.chars().count()) / 4);
+            if allowed > 0 && within(&a.singular, &b.singular, allowed) {
+                groups.join(i, j, Reason::Spelling);
+            }
+        }
+    }
+
+    // Added words: a name whose words appear, in order, inside exactly one
+    // other singular form.
+    let singulars: BTreeSet<&str> = keys.iter().map(|key| key.singular.as_str()).collect();
+    for (i, key) in keys.iter().enumerate() {
+        let mut containing = singulars
+            .iter()
+            .filter(|other| **other != key.singular && contains_words(other, &key.singular));
+        if let (Some(other), None) = (containing.next(), containing.next()) {
+            let j = by_singular[other];
+            groups.join(i, j, Reason::AddedWords);
+        }
+    }
+
+    let mut clusters: Vec<NameCluster> = groups
+        .into_clusters()
+        .into_iter()
+        .map(|(members, reasons)| {
+            let mut names: Vec<NameUse> = members
+                .into_iter()
+                .map(|i| NameUse {
+                    locations: uses.remove(&spellings[i]).unwrap_or_default(),
+                    name: spellings[i].clone(),
+                })
+                .collect();
+            names.sort_by(|a, b| b.count().cmp(&a.count()).then_with(|| a.name.cmp(&b.name)));
+            cluster(names, reasons, &aisle, configured)
+        })
+        .collect();
+    clusters.sort_by(|a, b| {
+        b.total_uses()
+            .cmp(&a.total_uses())
+            .then_with(|| a.canonical.cmp(&b.canonical))
+    });
+
+    Ok(Outcome::with_diagnostics(
+        NamesReport {
+            total_recipes,
+            total_names,
+            clusters,
+        },
+        diagnostics,
+    ))
+}
+
+/// Settle a group's canonical name and the synonyms `aisle.conf` lacks.
+fn cluster(
+    names: Vec<NameUse>,
+    reasons: BTreeSet<Reason>,
+    aisle: &BTreeMap<String, String>,
+    configured: bool,
+) -> NameCluster {
+    let known = names
+        .iter()
+        .find_map(|name| aisle.get(&name.name.to_lowercase()));
+    let canonical = match known {
+        Some(common) => common.clone(),
+        None => names
+            .iter()
+            .max_by(|a, b| {
+                a.count()
+                    .cmp(&b.count())
+                    .then_with(|| b.name.len().cmp(&a.name.len()))
+                    .then_with(|| b.name.cmp(&a.name))
+            })
+            .map(|name| name.name.clone())
+            .unwrap_or_default(),
+    };
+
+    let missing_synonyms = if configured {
+        let canonical = canonical.to_lowercase();
+        names
+            .iter()
+            .map(|name| name.name.to_lowercase())
+            .filter(|name| *name != canonical && !aisle.contains_key(name))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    NameCluster {
+        names,
+        reasons: reasons.into_iter().collect(),
+        canonical,
+        missing_synonyms,
+    }
+}
+
+/// A name, in the forms it is compared in.
+struct Key {
+    /// Lowercased, with runs of whitespace made one space.
+    lower: String,
+    /// [`lower`](Key::lower) with each word made singular.
+    singular: String,
+}
+
+impl Key {
+    fn new(name: &str) -> Self {
+        let lower = name
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .collect::<Vec<_>>()
+            .join(" ");
+        let singular = lower.split(' ').map(singular).collect::<Vec<_>>().join(" ");
+        Key { lower, singular }
+    }
+}
+
+/// One lowercase word made singular by English's regular rules.
+///
+/// Deliberately naive: `cherries` becomes `cherry` and `tomatoes` `tomato`,
+/// but `leaves` becomes `leave`. That is fine for comparing — `leaves` and
+/// `leaf` stay apart, which is the same answer as not trying — and nothing
+/// here is ever shown to the user.
+fn singular(word: &str) -> String {
+    let strip = |suffix: &str, with: &str| {
+        word.strip_suffix(suffix)
+            .filter(|stem| stem.chars().count() >= 2)
+            .map(|stem| format!("{stem}{with}"))
+    };
+    strip("ies", "y")
+        .or_else(|| strip("oes", "o"))
+        .or_else(|| {
+            ["ches", "shes", "sses", "xes", "zes"]
+                .iter()
+                .find_map(|s| strip(s, &s[..s.len() - 2]))
+        })
+        .or_else(|| {
+            if word.ends_with("ss") || word.ends_with("us") || word.ends_with("is") {
+                None
+            } else {
+                strip("s", "")
+            }
+        })
+        .unwrap_or_else(|| word.to_string())
+}
+
+/// Whether `inner`'s words appear, consecutively, among `outer`'s.
+fn contains_words(outer: &str, inner: &str) -> bool {
+    let outer: Vec<&str> = outer.split(' ').collect();
+    let inner: Vec<&str> = inner.split(' ').collect();
+    inner.len() < outer.len() && outer.windows(inner.len()).any(|window| window == inner)
+}
+
+/// Whether `a` and `b` are at most `limit` single-character edits apart —
+/// insertions, deletions and substitutions, counted in characters.
+fn within(a: &str, b: &str, limit: usize) -> bool {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.len().abs_diff(b.len()) > limit {
+        return false;
+    }
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        if current.iter().min().is_some_and(|&best| best > limit) {
+            return false;
+        }
+        previous = current;
+    }
+    previous[b.len()] <= limit
+}
+
+/// The first `@name` in a recipe's source, spanning the name.
+fn locate(source: &str, name: &str) -> Option<Span> {
+    let needle = format!("@{name}");
+    let mut from = 0;
+    while let Some(found) = source.get(from..)?.find(&needle) {
+        let start = from + found + 1;
+        let end = start + name.len();
+        // `@egg` is not a use of `eg`: the name must end where a name can.
+        let next = source[end..].chars().next();
+        if !next.is_some_and(|c| c.is_alphanumeric() || c == '_') {
+            return Some(Span::from(start..end));
+        }
+        from = end;
+    }
+    None
+}
+
+/// A union-find over name indices, remembering why each union was made.
+struct Groups {
+    parent: Vec<usize>,
+    reasons: BTreeMap<usize, BTreeSet<Reason>>,
+}
+
+impl Groups {
+    fn new(len: usize) -> Self {
+        Groups {
+            parent: (0..len).collect(),
+            reasons: BTreeMap::new(),
+        }
+    }
+
+    fn root(&mut self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        i
+    }
+
+    fn join(&mut self, a: usize, b: usize, reason: Reason) {
+        let (a, b) = (self.root(a), self.root(b));
+        let mut reasons = self.reasons.remove(&a).unwrap_or_default();
+        if a != b {
+            reasons.extend(self.reasons.remove(&b).unwrap_or_default());
+        }
+        reasons.insert(reason);
+        let root = a.min(b);
+        self.parent[a.max(b)] = root;
+        self.reasons.insert(root, reasons);
+    }
+
+    /// Every group of two or more, as its members in index order and the
+    /// reasons it was formed.
+    fn into_clusters(mut self) -> Vec<(Vec<usize>, BTreeSet<Reason>)> {
+        let mut members: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        for i in 0..self.parent.len() {
+            let root = self.root(i);
+            members.entry(root).or_default().push(i);
+        }
+        members
+            .into_iter()
+            .filter(|(_, members)| members.len() > 1)
+            .map(|(root, members)| (members, self.reasons.remove(&root).unwrap_or_default()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConfigSource;
+    use camino::Utf8Path;
+
+    fn collection(recipes: &[(&str, &str)]) -> tempfile::TempDir {
+        let dir = tempfile::TempDir::new().unwrap();
+        for (name, text) in recipes {
+            std::fs::write(dir.path().join(name), text).unwrap();
+        }
+        dir
+    }
+
+    fn run(dir: &tempfile::TempDir, aisle: Option<&str>) -> NamesReport {
+        let base = Utf8Path::from_path(dir.path()).unwrap().to_owned();
+        let mut ctx = Context::new(base);
+        if let Some(aisle) = aisle {
+            ctx = ctx.with_aisle(ConfigSource::Inline(aisle.to_string()));
+        }
+        ingredient_names(&ctx, NamesRequest::default())
+            .expect("analysis succeeds")
+            .into_value()
+    }
+
+    fn spellings(cluster: &NameCluster) -> Vec<&str> {
+        cluster.names.iter().map(|n| n.name.as_str()).collect()
+    }
+
+    #[test]
+    fn plurals_and_case_are_grouped_under_the_most_used_spelling() {
+        let dir = collection(&[
+            ("a.cook", "Beat @eggs{2}.\n"),
+            ("b.cook", "Beat @eggs{3} with @Salt{}.\n"),
+            ("c.cook", "Fry an @egg{1} with @salt{}.\n"),
+        ]);
+        let report = run(&dir, None);
+
+        assert_eq!(report.total_recipes, 3);
+        assert_eq!(report.total_names, 4);
+        assert_eq!(report.clusters.len(), 2);
+
+        let eggs = &report.clusters[0];
+        assert_eq!(spellings(eggs), ["eggs", "egg"]);
+        assert_eq!(eggs.canonical, "eggs");
+        assert_eq!(eggs.reasons, [Reason::Plural]);
+        assert_eq!(eggs.total_uses(), 3);
+        // Nothing to add synonyms to without an aisle configuration.
+        assert!(eggs.missing_synonyms.is_empty());
+
+        let salt = &report.clusters[1];
+        assert_eq!(spellings(salt), ["Salt", "salt"]);
+        assert_eq!(salt.reasons, [Reason::Case]);
+        // A tie goes to the shorter, then the alphabetically earlier.
+        assert_eq!(salt.canonical, "Salt");
+    }
+
+    #[test]
+    fn each_use_is_located_at_the_first_mention_in_its_recipe() {
+        let dir = collection(&[
+            ("a.cook", "Beat @eggs{2}.\n"),
+            ("b.cook", "---\ntitle: B\n---\n\nCrack an @egg{1}.\n"),
+        ]);
+        let report = run(&dir, None);
+
+        let egg = report.clusters[0]
+            .names
+            .iter()
+            .find(|n| n.name == "egg")
+            .unwrap();
+        assert_eq!(egg.count(), 1);
+        let location = &egg.locations[0];
+        assert_eq!(location.file.as_deref(), Some(Utf8Path::new("b.cook")));
+        let range = location.range.unwrap();
+        assert_eq!((range.start.line, range.start.column), (5, 11));
+    }
+
+    #[test]
+    fn added_words_group_only_when_the_shorter_name_is_in_one_other() {
+        let dir = collection(&[
+            ("a.cook", "Grate @Parmesan{} into @olive oil{}.\n"),
+            ("b.cook", "Grate @parmesan cheese{} into @sesame oil{}.\n"),
+            (
+                "c.cook",
+                "Dress with @extra virgin olive oil{} and @oil{}.\n",
+            ),
+        ]);
+        let report = run(&dir, None);
+
+        let mut groups: Vec<Vec<&str>> = report.clusters.iter().map(spellings).collect();
+        groups.sort();
+        assert_eq!(
+            groups,
+            [
+                vec!["Parmesan", "parmesan cheese"],
+                vec!["extra virgin olive oil", "olive oil"],
+            ]
+        );
+        assert!(report
+            .clusters
+            .iter()
+            .all(|c| c.reasons == [Reason::AddedWords]));
+    }
+
+    #[test]
+    fn misspellings_are_grouped_but_short_names_are_left_alone() {
+        let dir = collection(&[
+            ("a.cook", "Slice @courgette{} and @rice{}.\n"),
+            ("b.cook", "Slice @corgette{} over @ice{}.\n"),
+        ]);
+        let report = run(&dir, None);
+
+        assert_eq!(report.clusters.len(), 1);
+        assert_eq!(spellings(&report.clusters[0]), ["corgette", "courgette"]);
+        assert_eq!(report.clusters[0].reasons, [Reason::Spelling]);
+
+        let off = ingredient_names(
+            &Context::new(Utf8Path::from_path(dir.path()).unwrap().to_owned()),
+            NamesRequest {
+                max_distance: Some(0),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .into_value();
+        assert!(off.clusters.is_empty());
+    }
+
+    #[test]
+    fn aisle_synonyms_group_names_and_settle_the_canonical_one() {
+        let dir = collection(&[
+            ("a.cook", "Grate @parmigiano{}.\n"),
+            ("b.cook", "Grate @parmesan{}.\n"),
+            ("c.cook", "Grate @parmesans{} and @parmesan{}.\n"),
+        ]);
+        let report = run(&dir, Some("[dairy]\nparmesan|parmigiano\n"));
+
+        assert_eq!(report.clusters.len(), 1);
+        let cluster = &report.clusters[0];
+        assert_eq!(cluster.canonical, "parmesan");
+        assert_eq!(cluster.reasons, [Reason::Plural, Reason::Synonym]);
+        // `parmigiano` is already a synonym; only the plural is new.
+        assert_eq!(cluster.missing_synonyms, ["parmesans"]);
+    }
+
+    #[test]
+    fn singular_follows_the_regular_rules_and_leaves_the_rest() {
+        for (word, expected) in [
+            ("eggs", "egg"),
+            ("cherries", "cherry"),
+            ("tomatoes", "tomato"),
+            ("peaches", "peach"),
+            ("radishes", "radish"),
+            ("couscous", "couscous"),
+            ("glass", "glass"),
+            ("hummus", "hummus"),
+            ("s", "s"),
+        ] {
+            assert_eq!(singular(word), expected, "{word}");
+        }
+    }
+
+    #[test]
+    fn within_counts_edits_in_characters() {
+        assert!(within("courgette", "corgette", 1));
+        assert!(within("öl", "ol", 1));
+        assert!(!within("kitten", "sitting", 2));
+        assert!(within("kitten", "sitting", 3));
+    }
+}
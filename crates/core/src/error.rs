@@ -188,6 +188,72 @@ pub enum CoreError {
         message: String,
     },
 
+    /// A shopping list was asked for by a name it cannot have.
+    ///
+    /// Names become part of file names, so they are held to lowercase ASCII
+    /// letters, digits, `-` and `_`; see
+    /// [`ShoppingListStore::named`](crate::shopping_list::ShoppingListStore::named).
+    #[error("invalid shopping list name '{name}': use lowercase letters, digits, '-' and '_'")]
+    ShoppingListName {
+        /// The name as it was given.
+        name: String,
+    },
+
+    /// An item could not be added to a shopping list by hand.
+    ///
+    /// The `.shopping-list` format stores it as one line, so a name or
+    /// quantity that would not read back as the same line is refused; see
+    /// [`ShoppingListStore::add_item`](crate::shopping_list::ShoppingListStore::add_item).
+    #[error("cannot add '{name}' to the shopping list: {message}")]
+    ShoppingListItem {
+        /// The item's name as it was given.
+        name: String,
+        /// What is wrong with it.
+        message: String,
+    },
+
+    /// A change to the pantry could not be undone.
+    ///
+    /// See [`pantry::undo`](crate::pantry::undo).
+    #[error("cannot undo the pantry change: {message}")]
+    PantryHistory {
+        /// Why not.
+        message: String,
+    },
+
+    /// A spreadsheet could not be imported into the pantry at all.
+    ///
+    /// About the file as a whole — JSON that is not a list of items, a CSV
+    /// with no `name` column — rather than about one row in it, which
+    /// [`pantry::import`](crate::pantry::import) rejects and carries on past.
+    /// Nothing has been written when this is returned.
+    #[error("cannot import into the pantry{}: {message}", .path.as_ref().map(|p| format!(" from {p}")).unwrap_or_default())]
+    PantryImport {
+        /// The file being imported, when it came from one.
+        path: Option<Utf8PathBuf>,
+        /// What is wrong with it.
+        message: String,
+    },
+
+    /// A shopping list could not be put back as it was.
+    ///
+    /// See [`ShoppingListStore::restore`](crate::shopping_list::ShoppingListStore::restore).
+    #[error("cannot restore the shopping list: {message}")]
+    ShoppingListHistory {
+        /// Why not.
+        message: String,
+    },
+
+    /// A store profile was asked for that there is no file for.
+    ///
+    /// See [`store_profile`](crate::shopping_list::store_profile) for where
+    /// profiles are looked for.
+    #[error("no store profile named '{name}'")]
+    UnknownStore {
+        /// The name as it was given.
+        name: String,
+    },
+
     /// A file could not be read or written.
     ///
     /// There is deliberately no `From<std::io::Error>`: every call site must
@@ -227,10 +293,16 @@ mod tests {
             | CoreError::ReadOnlyConfig { .. }
             | CoreError::Config { .. }
             | CoreError::PantryEdit { .. }
+            | CoreError::PantryHistory { .. }
+            | CoreError::PantryImport { .. }
             | CoreError::Render { .. }
             | CoreError::Reference { .. }
             | CoreError::Search { .. }
             | CoreError::InvalidShoppingList { .. }
+            | CoreError::ShoppingListName { .. }
+            | CoreError::ShoppingListItem { .. }
+            | CoreError::ShoppingListHistory { .. }
+            | CoreError::UnknownStore { .. }
             | CoreError::Io { .. } => {}
         }
     }
@@ -276,6 +348,26 @@ mod tests {
                 path: Utf8PathBuf::from("/recipes/.shopping-list"),
                 message: "Invalid multiplier: expected a number".to_string(),
             },
+            CoreError::ShoppingListName {
+                name: "Party Time".to_string(),
+            },
+            CoreError::ShoppingListItem {
+                name: "candles{".to_string(),
+                message: "the name must not contain braces".to_string(),
+            },
+            CoreError::PantryHistory {
+                message: "milk in [dairy] has changed since".to_string(),
+            },
+            CoreError::PantryImport {
+                path: Some(Utf8PathBuf::from("pantry.csv")),
+                message: "there is no 'name' column".to_string(),
+            },
+            CoreError::ShoppingListHistory {
+                message: "the history only goes back to 2026-10-01T09:00:00Z".to_string(),
+            },
+            CoreError::UnknownStore {
+                name: "tesco".to_string(),
+            },
             CoreError::Io {
                 path: Utf8PathBuf::from("config/aisle.conf"),
                 source: std::io::Error::new(std::io::ErrorKind::NotFound, "not found"),
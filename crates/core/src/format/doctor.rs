@@ -0,0 +1,535 @@
+//! Validation report formatters.
+//!
+//! Each function renders a [`ValidationReport`] for a machine to read rather
+//! than a person: a CI system failing a build, a code-review tool placing an
+//! annotation on the offending line. They all report the same findings — every
+//! diagnostic the parser raised, plus one error per reference
+//! [`broken_references`](crate::doctor::broken_references) could not resolve —
+//! so a collection passes or fails identically whichever format is asked for.
+//!
+//! # Paths
+//!
+//! Every function takes a `root`, the directory file paths are written
+//! relative to. A recipe's path in the report is relative to the validated
+//! directory, but an annotation is placed by a path relative to the
+//! *repository*, and only the caller knows where one sits in the other. The
+//! CLI passes the validated directory as seen from the working directory; the
+//! server, which has no repository, passes an empty path.
+
+use crate::{
+    diagnostic::{Position, Severity},
+    doctor::{lint, ValidationReport},
+    Diagnostic,
+};
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::Serialize;
+use serde_json::json;
+use std::collections::BTreeMap;
+
+/// The rule id SARIF results carry for a diagnostic raised by the parser.
+///
+/// The parser does not classify its diagnostics, so they share one id; the
+/// message is what tells them apart. A diagnostic that does carry a
+/// [`code`](Diagnostic::code) — a lint finding — is reported under that
+/// instead.
+const PARSE_RULE: &str = "cooklang/parse";
+
+/// The rule id SARIF results carry for a reference that leads nowhere.
+const REFERENCE_RULE: &str = "cooklang/missing-reference";
+
+/// One recipe's findings, as every format here reports them.
+struct Findings<'a> {
+    /// Where the recipe is, relative to the caller's `root`.
+    file: Utf8PathBuf,
+    /// What the parser said about it.
+    diagnostics: &'a [Diagnostic],
+    /// The references in it that could not be resolved.
+    broken: &'a [String],
+}
+
+/// Gather each recipe's findings, clean recipes included, in report order.
+fn findings<'a>(
+    report: &'a ValidationReport,
+    broken: &'a BTreeMap<&Utf8Path, Vec<String>>,
+    root: &Utf8Path,
+) -> Vec<Findings<'a>> {
+    report
+        .recipes
+        .iter()
+        .map(|recipe| Findings {
+            file: root.join(&recipe.path),
+            diagnostics: &recipe.diagnostics,
+            broken: broken
+                .get(recipe.path.as_path())
+                .map_or(&[][..], Vec::as_slice),
+        })
+        .collect()
+}
+
+/// The message a broken reference is reported with, in every format.
+fn missing_reference(reference: &str) -> String {
+    format!("Missing reference: {reference}")
+}
+
+/// The start and end of a diagnostic's range, when it has one.
+fn range_of(diagnostic: &Diagnostic) -> Option<(Position, Position)> {
+    let range = diagnostic.location.as_ref()?.range?;
+    Some((range.start, range.end))
+}
+
+/// Every count `cook doctor validate` prints in its summary, with broken
+/// references folded into the error totals as the summary folds them.
+#[derive(Debug, Serialize)]
+struct Summary {
+    total_recipes: usize,
+    recipes_with_errors: usize,
+    recipes_with_warnings: usize,
+    total_errors: usize,
+    total_warnings: usize,
+}
+
+impl Summary {
+    fn of(report: &ValidationReport, broken: &BTreeMap<&Utf8Path, Vec<String>>) -> Self {
+        let failing = report
+            .recipes
+            .iter()
+            .filter(|r| {
+                broken.contains_key(r.path.as_path())
+                    || r.diagnostics.iter().any(|d| d.severity == Severity::Error)
+            })
+            .count();
+        Self {
+            total_recipes: report.total_recipes(),
+            recipes_with_errors: failing,
+            recipes_with_warnings: report.recipes_with_warnings(),
+            total_errors: report.total_errors() + broken.values().map(Vec::len).sum::<usize>(),
+            total_warnings: report.total_warnings(),
+        }
+    }
+}
+
+/// Render the report as the JSON `cook doctor validate --format json` prints.
+///
+/// The report as it serializes — every recipe, clean ones included, with its
+/// diagnostics and references — alongside the broken references keyed by
+/// recipe path, the summary counts, and — when `doctor.toml` had anything
+/// wrong with it — [`config_diagnostics`](ValidationReport::config_diagnostics).
+/// Recipe paths are joined onto `root`
+/// here, as in every other format, so `base_dir` is left out: it would name a
+/// directory the paths are no longer relative to.
+pub fn build_json_value(
+    report: &ValidationReport,
+    broken: &BTreeMap<&Utf8Path, Vec<String>>,
+    root: &Utf8Path,
+) -> serde_json::Value {
+    let recipes: Vec<serde_json::Value> = report
+        .recipes
+        .iter()
+        .map(|recipe| {
+            let file = root.join(&recipe.path);
+            let mut value = serde_json::to_value(recipe).unwrap_or_default();
+            // The diagnostics name the recipe by the same path, and must keep
+            // agreeing with it.
+            if let Some(diagnostics) = value["diagnostics"].as_array_mut() {
+                for diagnostic in diagnostics {
+                    if let Some(location) = diagnostic["location"].as_object_mut() {
+                        if location.contains_key("file") {
+                            location.insert("file".to_string(), json!(file));
+                        }
+                    }
+                }
+            }
+            value["path"] = json!(file);
+            value
+        })
+        .collect();
+    let broken_by_file: BTreeMap<Utf8PathBuf, &Vec<String>> = broken
+        .iter()
+        .map(|(path, references)| (root.join(path), references))
+        .collect();
+
+    let mut value = json!({
+        "summary": Summary::of(report, broken),
+        "recipes": recipes,
+        "broken_references": broken_by_file,
+    });
+    if !report.config_diagnostics.is_empty() {
+        value["config_diagnostics"] = json!(report.config_diagnostics);
+    }
+    value
+}
+
+/// Render the report as a SARIF 2.1.0 log, the format GitHub code scanning
+/// and most review tools ingest.
+///
+/// One result per finding, with a region wherever the diagnostic has a
+/// resolved [`range`](crate::Location::range). Columns count characters, and
+/// the run says so through `columnKind`, so a viewer reading the default UTF-16
+/// units does not misplace an annotation on a line with an accent on it.
+/// [`Severity::Hint`] becomes SARIF's `note`; hints attached to a diagnostic go
+/// in the result's `properties`, since SARIF's own `fixes` need replacement
+/// text this report does not have.
+pub fn build_sarif_value(
+    report: &ValidationReport,
+    broken: &BTreeMap<&Utf8Path, Vec<String>>,
+    root: &Utf8Path,
+) -> serde_json::Value {
+    let mut results = Vec::new();
+    for recipe in findings(report, broken, root) {
+        for diagnostic in recipe.diagnostics {
+            let level = match diagnostic.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+                _ => "note",
+            };
+            let mut physical = json!({ "artifactLocation": { "uri": recipe.file } });
+            if let Some((start, end)) = range_of(diagnostic) {
+                physical["region"] = json!({
+                    "startLine": start.line,
+                    "startColumn": start.column,
+                    "endLine": end.line,
+                    "endColumn": end.column,
+                });
+            }
+            let mut result = json!({
+                "ruleId": diagnostic.code.as_deref().unwrap_or(PARSE_RULE),
+                "level": level,
+                "message": { "text": diagnostic.message },
+                "locations": [{ "physicalLocation": physical }],
+            });
+            if !diagnostic.hints.is_empty() {
+                result["properties"] = json!({ "hints": diagnostic.hints });
+            }
+            results.push(result);
+        }
+        for reference in recipe.broken {
+            results.push(json!({
+                "ruleId": REFERENCE_RULE,
+                "level": "error",
+                "message": { "text": missing_reference(reference) },
+                "locations": [{
+                    "physicalLocation": { "artifactLocation": { "uri": recipe.file } }
+                }],
+            }));
+        }
+    }
+
+    let mut rules = vec![
+        json!({
+            "id": PARSE_RULE,
+            "shortDescription": { "text": "Cooklang syntax and semantics" },
+        }),
+        json!({
+            "id": REFERENCE_RULE,
+            "shortDescription": { "text": "Recipe reference that resolves to no recipe" },
+        }),
+    ];
+    rules.extend(
+        lint::RULES.iter().map(
+            |(id, description)| json!({ "id": id, "shortDescription": { "text": description } }),
+        ),
+    );
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "cook doctor",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": "https://cooklang.org/cli/",
+                    "rules": rules,
+                }
+            },
+            "columnKind": "unicodeCodePoints",
+            "results": results,
+        }],
+    })
+}
+
+/// Render the report as a JUnit XML document, one test case per recipe.
+///
+/// JUnit has no notion of a warning, so only errors and broken references
+/// fail a case. A recipe's warnings and hints are still written, to the case's
+/// `system-out`, where a CI test view shows them without turning the build
+/// red — the same line `cook doctor validate` draws without `--strict`.
+pub fn build_junit(
+    report: &ValidationReport,
+    broken: &BTreeMap<&Utf8Path, Vec<String>>,
+    root: &Utf8Path,
+) -> String {
+    let recipes = findings(report, broken, root);
+    let failures = recipes
+        .iter()
+        .filter(|r| {
+            !r.broken.is_empty() || r.diagnostics.iter().any(|d| d.severity == Severity::Error)
+        })
+        .count();
+
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str(&format!(
+        "<testsuites name=\"cook doctor validate\" tests=\"{}\" failures=\"{failures}\" errors=\"0\">\n",
+        recipes.len()
+    ));
+    out.push_str(&format!(
+        "  <testsuite name=\"recipes\" tests=\"{}\" failures=\"{failures}\" errors=\"0\" skipped=\"0\">\n",
+        recipes.len()
+    ));
+
+    for recipe in &recipes {
+        let name = xml_escape(recipe.file.as_str());
+        let mut errors = Vec::new();
+        let mut notes = Vec::new();
+        for diagnostic in recipe.diagnostics {
+            let line = describe(&recipe.file, diagnostic);
+            if diagnostic.severity == Severity::Error {
+                errors.push(line);
+            } else {
+                notes.push(line);
+            }
+        }
+        errors.extend(
+            recipe
+                .broken
+                .iter()
+                .map(|reference| format!("{}: {}", recipe.file, missing_reference(reference))),
+        );
+
+        if errors.is_empty() && notes.is_empty() {
+            out.push_str(&format!(
+                "    <testcase name=\"{name}\" classname=\"recipes\"/>\n"
+            ));
+            continue;
+        }
+
+        out.push_str(&format!(
+            "    <testcase name=\"{name}\" classname=\"recipes\">\n"
+        ));
+        if let Some(first) = errors.first() {
+            out.push_str(&format!(
+                "      <failure message=\"{}\" type=\"error\">{}</failure>\n",
+                xml_escape(first),
+                xml_escape(&errors.join("\n"))
+            ));
+        }
+        if !notes.is_empty() {
+            out.push_str(&format!(
+                "      <system-out>{}</system-out>\n",
+                xml_escape(&notes.join("\n"))
+            ));
+        }
+        out.push_str("    </testcase>\n");
+    }
+
+    out.push_str("  </testsuite>\n</testsuites>\n");
+    out
+}
+
+/// One finding on one line, as a compiler would print it:
+/// `soup.cook:3:7: warning: message`.
+fn describe(file: &Utf8Path, diagnostic: &Diagnostic) -> String {
+    let severity = match diagnostic.severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        _ => "hint",
+    };
+    let message = diagnostic.message.lines().next().unwrap_or_default();
+    match range_of(diagnostic) {
+        Some((start, _)) => format!(
+            "{file}:{}:{}: {severity}: {message}",
+            start.line, start.column
+        ),
+        None => format!("{file}: {severity}: {message}"),
+    }
+}
+
+/// Escape the five characters XML reserves, in text and attribute values
+/// alike.
+fn xml_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Render the report as GitHub Actions workflow commands, one per finding.
+///
+/// Printed from a workflow step, each line becomes an annotation on the line
+/// of the file it names, in the pull request's diff. Clean recipes print
+/// nothing, so a clean collection prints nothing at all.
+pub fn build_github(
+    report: &ValidationReport,
+    broken: &BTreeMap<&Utf8Path, Vec<String>>,
+    root: &Utf8Path,
+) -> String {
+    let mut out = String::new();
+    for recipe in findings(report, broken, root) {
+        let file = escape_property(recipe.file.as_str());
+        for diagnostic in recipe.diagnostics {
+            let command = match diagnostic.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+                _ => "notice",
+            };
+            let mut properties = format!("file={file}");
+            if let Some((start, end)) = range_of(diagnostic) {
+                properties.push_str(&format!(",line={},col={}", start.line, start.column));
+                // GitHub only honours an end column on a single-line range.
+                if end.line == start.line {
+                    properties.push_str(&format!(",endColumn={}", end.column));
+                } else {
+                    properties.push_str(&format!(",endLine={}", end.line));
+                }
+            }
+            // A lint finding is titled by its rule, so the annotation says
+            // which house rule it is enforcing.
+            if let Some(code) = &diagnostic.code {
+                properties.push_str(&format!(",title={}", escape_property(code)));
+            }
+            let mut message = diagnostic.message.clone();
+            for hint in &diagnostic.hints {
+                message.push_str(&format!("\nHint: {hint}"));
+            }
+            out.push_str(&format!(
+                "::{command} {properties}::{}\n",
+                escape_data(&message)
+            ));
+        }
+        for reference in recipe.broken {
+            out.push_str(&format!(
+                "::error file={file}::{}\n",
+                escape_data(&missing_reference(reference))
+            ));
+        }
+    }
+    out
+}
+
+/// Escape a workflow command's message, which may span lines.
+fn escape_data(text: &str) -> String {
+    text.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape a workflow command property, where `:` and `,` are separators too.
+fn escape_property(text: &str) -> String {
+    escape_data(text).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        doctor::{broken_references, validate, ValidateRequest},
+        Context,
+    };
+
+    /// A collection with one clean recipe, one with a warning, one with an
+    /// error and one referencing a recipe that does not exist.
+    fn fixture() -> (tempfile::TempDir, ValidationReport) {
+        let dir = tempfile::tempdir().unwrap();
+        let base = Utf8Path::from_path(dir.path()).unwrap().to_owned();
+        std::fs::write(base.join("clean.cook"), "Boil @water{1%l}.\n").unwrap();
+        std::fs::write(
+            base.join("broken.cook"),
+            "Intro.\nAdd @{1%tsp} to the pot.\n",
+        )
+        .unwrap();
+        std::fs::write(base.join("refers.cook"), "Add @./missing{1%cup} & more.\n").unwrap();
+        let report = validate(&Context::new(base), ValidateRequest::default())
+            .unwrap()
+            .into_value();
+        (dir, report)
+    }
+
+    #[test]
+    fn json_reports_every_recipe_with_summary_and_broken_references() {
+        let (_dir, report) = fixture();
+        let broken = broken_references(&report);
+        let value = build_json_value(&report, &broken, Utf8Path::new("recipes"));
+
+        assert_eq!(value["summary"]["total_recipes"], 3);
+        assert_eq!(value["summary"]["recipes_with_errors"], 2);
+        assert_eq!(value["recipes"][0]["path"], "recipes/broken.cook");
+        assert_eq!(
+            value["broken_references"]["recipes/refers.cook"][0],
+            "./missing"
+        );
+        let range = &value["recipes"][0]["diagnostics"][0]["location"]["range"];
+        assert_eq!(range["start"]["line"], 2);
+        assert_eq!(
+            value["recipes"][0]["diagnostics"][0]["location"]["file"],
+            "recipes/broken.cook"
+        );
+    }
+
+    #[test]
+    fn sarif_places_results_on_lines_and_counts_broken_references() {
+        let (_dir, report) = fixture();
+        let broken = broken_references(&report);
+        let value = build_sarif_value(&report, &broken, Utf8Path::new(""));
+
+        assert_eq!(value["version"], "2.1.0");
+        let results = value["runs"][0]["results"].as_array().unwrap();
+        let parse = results
+            .iter()
+            .find(|r| r["ruleId"] == PARSE_RULE)
+            .expect("parse result");
+        let location = &parse["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "broken.cook");
+        assert_eq!(location["region"]["startLine"], 2);
+
+        let reference = results
+            .iter()
+            .find(|r| r["ruleId"] == REFERENCE_RULE)
+            .expect("reference result");
+        assert_eq!(reference["level"], "error");
+        assert_eq!(reference["message"]["text"], "Missing reference: ./missing");
+    }
+
+    #[test]
+    fn junit_fails_only_recipes_with_errors() {
+        let (_dir, report) = fixture();
+        let broken = broken_references(&report);
+        let xml = build_junit(&report, &broken, Utf8Path::new(""));
+
+        assert!(xml.contains("tests=\"3\" failures=\"2\""), "{xml}");
+        assert!(xml.contains("<testcase name=\"clean.cook\" classname=\"recipes\"/>"));
+        assert!(xml.contains("Missing reference: ./missing"));
+        assert!(xml.contains("broken.cook:2:"), "{xml}");
+    }
+
+    #[test]
+    fn github_emits_one_annotation_per_finding() {
+        let (_dir, report) = fixture();
+        let broken = broken_references(&report);
+        let out = build_github(&report, &broken, Utf8Path::new("recipes"));
+
+        let lines: Vec<&str> = out.lines().collect();
+        assert!(
+            lines
+                .iter()
+                .any(|l| l.starts_with("::error file=recipes/broken.cook,line=2,col=")),
+            "{out}"
+        );
+        assert!(lines.contains(&"::error file=recipes/refers.cook::Missing reference: ./missing"));
+        assert!(!out.contains("clean.cook"));
+    }
+
+    #[test]
+    fn escapes_keep_one_command_per_line() {
+        assert_eq!(escape_data("50% off\nnext"), "50%25 off%0Anext");
+        assert_eq!(escape_property("a:b,c"), "a%3Ab%2Cc");
+        assert_eq!(xml_escape("<a & \"b\">"), "&lt;a &amp; &quot;b&quot;&gt;");
+    }
+}
@@ -0,0 +1,270 @@
+//! Reference graph formatters.
+//!
+//! Each function renders a [`ReferenceGraph`] for another tool to draw:
+//! Graphviz reads [`build_dot`], GitHub and most Markdown viewers render
+//! [`build_mermaid`] in a fenced block, and [`build_json_value`] is for
+//! anything that wants to walk the graph itself.
+//!
+//! The drawings name a file by its path without the extension — recipes are
+//! ellipses, menus boxes, so `.cook` and `.menu` would only repeat the shape —
+//! and draw one arrow per pair of files however often one references the
+//! other. A reference that resolves to nothing is drawn to a dashed red node
+//! named as the reference spells it; since references resolve against the
+//! collection root, two files writing `./Gone` point to the same one. The
+//! JSON keeps every reference, with the line it is on.
+
+use crate::graph::{NodeKind, ReferenceGraph};
+use camino::Utf8Path;
+use serde_json::json;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// How a file is labelled in a drawing: its path without the extension.
+fn label(path: &Utf8Path) -> String {
+    path.with_extension("").to_string()
+}
+
+/// Every pair of files the graph draws an arrow between, once each, in edge
+/// order. A `None` target is a reference that leads nowhere, kept by how it is
+/// written.
+fn arrows(graph: &ReferenceGraph) -> Vec<(&Utf8Path, Result<&Utf8Path, &str>)> {
+    let mut seen = BTreeSet::new();
+    graph
+        .edges
+        .iter()
+        .map(|edge| {
+            let to = edge.to.as_deref().ok_or(edge.reference.as_str());
+            (edge.from.as_path(), to)
+        })
+        .filter(|arrow| seen.insert(*arrow))
+        .collect()
+}
+
+/// Every reference that leads nowhere, once each, in edge order.
+fn missing(graph: &ReferenceGraph) -> Vec<&str> {
+    let mut seen = BTreeSet::new();
+    graph
+        .edges
+        .iter()
+        .filter(|edge| edge.to.is_none())
+        .map(|edge| edge.reference.as_str())
+        .filter(|reference| seen.insert(*reference))
+        .collect()
+}
+
+/// Quote a string as a DOT identifier.
+fn dot_quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Render the graph in Graphviz's DOT language, for `dot -Tsvg`.
+///
+/// Nodes are identified by their path, so the output reads on its own and
+/// diffs cleanly as a collection changes. A rooted graph's
+/// [`root`](ReferenceGraph::root) is drawn bold.
+pub fn build_dot(graph: &ReferenceGraph) -> String {
+    let mut out = String::from("digraph recipes {\n    rankdir=LR;\n    node [shape=ellipse];\n");
+    for node in &graph.nodes {
+        let mut attributes = vec![format!("label={}", dot_quote(&label(&node.path)))];
+        if node.kind == NodeKind::Menu {
+            attributes.push("shape=box".to_string());
+        }
+        if graph.root.as_ref() == Some(&node.path) {
+            attributes.push("style=bold".to_string());
+        }
+        out.push_str(&format!(
+            "    {} [{}];\n",
+            dot_quote(node.path.as_str()),
+            attributes.join(", ")
+        ));
+    }
+    for reference in missing(graph) {
+        out.push_str(&format!(
+            "    {} [label={}, style=dashed, color=red, fontcolor=red];\n",
+            dot_quote(&format!("missing:{reference}")),
+            dot_quote(reference)
+        ));
+    }
+    for (from, to) in arrows(graph) {
+        let (to, style) = match to {
+            Ok(to) => (to.to_string(), ""),
+            Err(reference) => (format!("missing:{reference}"), " [style=dashed, color=red]"),
+        };
+        out.push_str(&format!(
+            "    {} -> {}{style};\n",
+            dot_quote(from.as_str()),
+            dot_quote(&to)
+        ));
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// Escape a label for Mermaid, whose quoted strings take entity codes rather
+/// than backslashes.
+fn mermaid_escape(text: &str) -> String {
+    text.replace('"', "#quot;")
+}
+
+/// Render the graph as a Mermaid flowchart, without the surrounding code
+/// fence.
+///
+/// Mermaid ids cannot hold a path, so nodes are numbered `n0`, `n1`, … in
+/// graph order and missing references `m0`, `m1`, …; the label carries the
+/// name. A rooted graph's [`root`](ReferenceGraph::root) gets the `root`
+/// class.
+pub fn build_mermaid(graph: &ReferenceGraph) -> String {
+    let ids: BTreeMap<&Utf8Path, String> = graph
+        .nodes
+        .iter()
+        .enumerate()
+        .map(|(index, node)| (node.path.as_path(), format!("n{index}")))
+        .collect();
+    let missing_ids: BTreeMap<&str, String> = missing(graph)
+        .into_iter()
+        .enumerate()
+        .map(|(index, reference)| (reference, format!("m{index}")))
+        .collect();
+
+    let mut out = String::from("flowchart LR\n");
+    for node in &graph.nodes {
+        let id = &ids[node.path.as_path()];
+        let text = mermaid_escape(&label(&node.path));
+        match node.kind {
+            NodeKind::Menu => out.push_str(&format!("    {id}[[\"{text}\"]]\n")),
+            NodeKind::Recipe => out.push_str(&format!("    {id}([\"{text}\"])\n")),
+        }
+    }
+    for (reference, id) in &missing_ids {
+        out.push_str(&format!(
+            "    {id}[\"{}\"]:::missing\n",
+            mermaid_escape(reference)
+        ));
+    }
+    for (from, to) in arrows(graph) {
+        let to = match to {
+            Ok(to) => &ids[to],
+            Err(reference) => &missing_ids[reference],
+        };
+        out.push_str(&format!("    {} --> {to}\n", ids[from]));
+    }
+    if !missing_ids.is_empty() {
+        out.push_str("    classDef missing stroke:#d00,stroke-dasharray:4 2,color:#d00\n");
+    }
+    if let Some(id) = graph.root.as_deref().and_then(|root| ids.get(root)) {
+        out.push_str(&format!(
+            "    classDef root stroke-width:3px\n    class {id} root\n"
+        ));
+    }
+    out
+}
+
+/// Render the graph as the JSON `cook graph --format json` prints.
+///
+/// The [`root`](ReferenceGraph::root) of a rooted graph, every node with its
+/// kind, and every reference — repeated ones included —
+/// with the file it resolves to (`null` when none) and the line it is written
+/// on. Paths are relative to the collection, and `base_dir` is left out so
+/// the output does not depend on where the collection happens to be checked
+/// out.
+pub fn build_json_value(graph: &ReferenceGraph) -> serde_json::Value {
+    let edges: Vec<serde_json::Value> = graph
+        .edges
+        .iter()
+        .map(|edge| {
+            json!({
+                "from": edge.from,
+                "to": edge.to,
+                "reference": edge.reference,
+                "line": edge.location.range.map(|range| range.start.line),
+            })
+        })
+        .collect();
+    let mut value = json!({
+        "nodes": graph.nodes,
+        "edges": edges,
+    });
+    if let Some(root) = &graph.root {
+        value["root"] = json!(root);
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A menu of two recipes, one of which uses the other, plus a reference
+    /// to a recipe that does not exist from each of them.
+    fn fixture() -> (tempfile::TempDir, ReferenceGraph) {
+        let dir = tempfile::tempdir().unwrap();
+        let base = Utf8Path::from_path(dir.path()).unwrap().to_owned();
+        std::fs::create_dir(base.join("Mains")).unwrap();
+        std::fs::write(
+            base.join("Week.menu"),
+            "- @./Mains/Stew{}\n- @./Mains/Stew{}\n- @./Gone{}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            base.join("Mains/Stew.cook"),
+            "Add @./Stock{} and @./Gone{}.\n",
+        )
+        .unwrap();
+        std::fs::write(base.join("Stock.cook"), "Boil @\"bones\"{}.\n").unwrap();
+        let graph = ReferenceGraph::build(&base).unwrap().into_value();
+        (dir, graph)
+    }
+
+    #[test]
+    fn dot_draws_each_pair_once_and_missing_references_dashed() {
+        let (_dir, graph) = fixture();
+        let graph = graph.rooted("Week.menu", None).unwrap();
+        let dot = build_dot(&graph);
+
+        assert!(dot.starts_with("digraph recipes {\n"));
+        assert!(dot.contains("\"Week.menu\" [label=\"Week\", shape=box, style=bold];"));
+        assert!(dot.contains("\"Mains/Stew.cook\" [label=\"Mains/Stew\"];"));
+        assert_eq!(
+            dot.matches("\"Week.menu\" -> \"Mains/Stew.cook\";").count(),
+            1
+        );
+        assert_eq!(dot.matches("\"missing:./Gone\" [label").count(), 1);
+        assert!(dot.contains("\"Mains/Stew.cook\" -> \"missing:./Gone\" [style=dashed"));
+        assert!(dot.ends_with("}\n"));
+    }
+
+    #[test]
+    fn mermaid_numbers_nodes_and_marks_missing_ones() {
+        let (_dir, graph) = fixture();
+        let mermaid = build_mermaid(&graph);
+
+        assert!(mermaid.starts_with("flowchart LR\n"));
+        // Nodes are in path order: Mains/Stew, Stock, Week.
+        assert!(mermaid.contains("    n0([\"Mains/Stew\"])\n"));
+        assert!(mermaid.contains("    n2[[\"Week\"]]\n"));
+        assert!(mermaid.contains("    m0[\"./Gone\"]:::missing\n"));
+        assert!(mermaid.contains("    n0 --> n1\n"));
+        assert_eq!(mermaid.matches("    n2 --> n0\n").count(), 1);
+        assert!(mermaid.contains("classDef missing"));
+        assert!(!mermaid.contains("class n"));
+    }
+
+    #[test]
+    fn json_keeps_every_reference_with_its_line() {
+        let (_dir, graph) = fixture();
+        let value = build_json_value(&graph);
+
+        assert!(value.get("root").is_none());
+        assert!(value.get("base_dir").is_none());
+        assert_eq!(value["nodes"][2]["path"], "Week.menu");
+        assert_eq!(value["nodes"][2]["kind"], "menu");
+        let from_menu: Vec<_> = value["edges"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|edge| edge["from"] == "Week.menu")
+            .collect();
+        assert_eq!(from_menu.len(), 3);
+        assert_eq!(from_menu[2]["to"], serde_json::Value::Null);
+        assert_eq!(from_menu[2]["line"], 3);
+    }
+}
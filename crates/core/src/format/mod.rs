@@ -7,8 +7,13 @@
 //!
 //! [`shopping_list`] stays here: it renders this crate's
 //! [`AggregatedList`](crate::shopping_list::AggregatedList), so moving it would
-//! point the dependency between the two crates the wrong way.
+//! point the dependency between the two crates the wrong way. [`doctor`] stays
+//! for the same reason, rendering
+//! [`ValidationReport`](crate::doctor::ValidationReport), and so does
+//! [`graph`], rendering [`ReferenceGraph`](crate::graph::ReferenceGraph).
 
+pub mod doctor;
+pub mod graph;
 pub mod shopping_list;
 
 // `cooklang_source as cooklang`: in this crate the formatter can keep the bare
@@ -37,10 +37,12 @@
 
 use crate::{
     format::{quantity::ordered_components, Style},
-    shopping_list::AggregatedList,
+    shopping_list::{packages::RawPackages, sources::RawSource, AggregatedList, IngredientSources},
 };
+use chrono::{DateTime, Utc};
 use cooklang::quantity::{GroupedQuantity, Quantity, Value};
 use serde::Serialize;
+use std::collections::HashMap;
 use yansi::Paint;
 
 /// Render one quantity the way the human and markdown output show it.
@@ -54,16 +56,59 @@ pub(crate) fn quantity_fmt(qty: &Quantity) -> String {
     }
 }
 
-/// Add a quantity cell to a table row, joining the parts that could not be
+/// Render an ingredient's amount, joining the parts that could not be
 /// combined into a single unit with commas, in
 /// [`ordered_components`](crate::format::quantity::ordered_components) order.
-fn total_quantity_fmt(qty: &GroupedQuantity, row: &mut tabular::Row) {
-    let content = ordered_components(qty)
+///
+/// An ingredient counted in packages leads with them, in place of the part
+/// they were counted from. One the pantry covers in part ends by saying how
+/// much of it is already there: `800 g (200 g in the pantry)`.
+fn amount_fmt(
+    qty: &GroupedQuantity,
+    packages: Option<&RawPackages>,
+    in_pantry: Option<&GroupedQuantity>,
+) -> String {
+    let amount = packages
+        .map(RawPackages::describe)
+        .into_iter()
+        .chain(
+            ordered_components(qty)
+                .into_iter()
+                .filter(|q| !packages.is_some_and(|p| p.covers(q)))
+                .map(quantity_fmt),
+        )
+        .collect::<Vec<_>>()
+        .join(", ");
+    match in_pantry {
+        Some(in_pantry) => {
+            let have = ordered_components(in_pantry)
+                .into_iter()
+                .map(quantity_fmt)
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("{amount} ({have} in the pantry)")
+        }
+        None => amount,
+    }
+}
+
+/// Who asked for an ingredient, as the human and Markdown outputs say it:
+/// `Pizza`, or `Pizza › sauces/tomato` for an ingredient of a sauce the pizza
+/// references.
+fn source_label(source: &RawSource) -> String {
+    std::iter::once(source.recipe.as_str())
+        .chain(source.via.iter().map(String::as_str))
+        .collect::<Vec<_>>()
+        .join(" › ")
+}
+
+/// One source's own amount, or nothing for a recipe that gave none.
+fn source_amount(source: &RawSource) -> String {
+    ordered_components(&source.quantity)
         .into_iter()
         .map(quantity_fmt)
-        .reduce(|s, q| format!("{s}, {q}"))
-        .unwrap_or_default();
-    row.add_ansi_cell(content);
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
 /// Render the list as the aligned two-column table `cook shopping-list` prints.
@@ -71,13 +116,41 @@ fn total_quantity_fmt(qty: &GroupedQuantity, row: &mut tabular::Row) {
 /// `plain` drops the aisle category headings and lists every ingredient in the
 /// order the recipes introduced them. `style` decides whether the headings
 /// carry ANSI colour; `Style::Plain` is `Style::Ansi` with the escapes removed.
-pub fn build_human_table(list: AggregatedList, plain: bool, style: Style) -> tabular::Table {
+/// `show_sources` follows each ingredient with a row per recipe that asked
+/// for it, giving that recipe's own amount. Any
+/// [`substitutions`](AggregatedList::substitutions) come last, under a heading
+/// of their own, each with what stood in for it.
+pub fn build_human_table(
+    list: AggregatedList,
+    plain: bool,
+    style: Style,
+    show_sources: bool,
+) -> tabular::Table {
     let mut table = tabular::Table::new("{:<} {:<}");
+    let (packages, in_pantry, sources) =
+        (&list.raw_packages, &list.raw_in_pantry, &list.raw_sources);
+    let rows = |igr: String, q: &GroupedQuantity| {
+        let amount = amount_fmt(q, packages.get(&igr), in_pantry.get(&igr));
+        let sources = sources.raw(&igr).filter(|_| show_sources).map(|source| {
+            let label = format!("  ↳ {}", source_label(source));
+            let label = if style.is_ansi() {
+                label.dim().to_string()
+            } else {
+                label
+            };
+            tabular::Row::new()
+                .with_ansi_cell(label)
+                .with_cell(source_amount(source))
+        });
+        std::iter::once(tabular::Row::new().with_cell(&igr).with_ansi_cell(amount))
+            .chain(sources)
+            .collect::<Vec<_>>()
+    };
     if plain {
         for (igr, q) in list.raw_items {
-            let mut row = tabular::Row::new().with_cell(igr);
-            total_quantity_fmt(&q, &mut row);
-            table.add_row(row);
+            for row in rows(igr, &q) {
+                table.add_row(row);
+            }
         }
     } else {
         for (cat, items) in list.raw_categories {
@@ -88,33 +161,73 @@ pub fn build_human_table(list: AggregatedList, plain: bool, style: Style) -> tab
             };
             table.add_heading(heading);
             for (igr, q) in items {
-                let mut row = tabular::Row::new().with_cell(igr);
-                total_quantity_fmt(&q, &mut row);
-                table.add_row(row);
+                for row in rows(igr, &q) {
+                    table.add_row(row);
+                }
             }
         }
     }
+    if !list.substitutions.is_empty() {
+        let heading = if style.is_ansi() {
+            format!("[{}]", SUBSTITUTED.green())
+        } else {
+            format!("[{SUBSTITUTED}]")
+        };
+        table.add_heading(heading);
+        for substitution in &list.substitutions {
+            table.add_row(
+                tabular::Row::new()
+                    .with_cell(&substitution.ingredient)
+                    .with_cell(format!("→ {}", substitution.with_joined())),
+            );
+        }
+    }
     table
 }
 
+/// The heading the human and Markdown lists put the substitutions under.
+const SUBSTITUTED: &str = "substituted from the pantry";
+
 /// Render the list as Markdown.
 ///
 /// `plain` drops the category headings; `ingredients_only` drops the
-/// quantities, leaving a bare checklist of names.
-pub fn build_md_value(list: AggregatedList, plain: bool, ingredients_only: bool) -> String {
+/// quantities, leaving a bare checklist of names. `show_sources` nests the
+/// recipes that asked for each ingredient under it, each with its own amount
+/// unless `ingredients_only`. Any
+/// [`substitutions`](AggregatedList::substitutions) follow under a heading of
+/// their own.
+pub fn build_md_value(
+    list: AggregatedList,
+    plain: bool,
+    ingredients_only: bool,
+    show_sources: bool,
+) -> String {
     let mut output = String::new();
 
+    let (packages, in_pantry, sources) =
+        (&list.raw_packages, &list.raw_in_pantry, &list.raw_sources);
     let format_ingredient = |ingredient: &str, quantity: &GroupedQuantity| {
-        if ingredients_only {
+        let mut line = if ingredients_only {
             format!("- {ingredient}\n")
         } else {
-            let quantity_string = ordered_components(quantity)
-                .into_iter()
-                .map(quantity_fmt)
-                .collect::<Vec<_>>()
-                .join(", ");
+            let quantity_string = amount_fmt(
+                quantity,
+                packages.get(ingredient),
+                in_pantry.get(ingredient),
+            );
             format!("- *{quantity_string}* {ingredient}\n")
+        };
+        if show_sources {
+            for source in sources.raw(ingredient) {
+                let amount = source_amount(source);
+                if ingredients_only || amount.is_empty() {
+                    line.push_str(&format!("  - {}\n", source_label(source)));
+                } else {
+                    line.push_str(&format!("  - *{amount}* {}\n", source_label(source)));
+                }
+            }
         }
+        line
     };
     if plain {
         // no categories, simple list
@@ -132,6 +245,19 @@ pub fn build_md_value(list: AggregatedList, plain: bool, ingredients_only: bool)
             }
         }
     }
+    if !list.substitutions.is_empty() {
+        if !output.is_empty() {
+            output.push('\n');
+        }
+        output.push_str(&format!("# {SUBSTITUTED}\n"));
+        for substitution in &list.substitutions {
+            output.push_str(&format!(
+                "- {} → {}\n",
+                substitution.ingredient,
+                substitution.with_joined()
+            ));
+        }
+    }
     output
 }
 
@@ -154,24 +280,80 @@ impl From<Quantity> for JsonQuantity {
 struct JsonIngredient {
     name: String,
     quantity: Vec<JsonQuantity>,
+    /// Only present once the list has been counted in packages, so that the
+    /// document is unchanged for everyone who has not asked for them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    packages: Option<JsonPackages>,
+    /// What the pantry already covers of an ingredient it covers in part;
+    /// `quantity` is then what is left to buy. Likewise only present when
+    /// there is something to say.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    in_pantry: Vec<JsonQuantity>,
+    /// Always present: which recipes asked for the ingredient, how much each.
+    sources: Vec<JsonSource>,
+}
+
+#[derive(Serialize)]
+struct JsonSource {
+    recipe: String,
+    via: Vec<String>,
+    quantity: Vec<JsonQuantity>,
+}
+
+#[derive(Serialize)]
+struct JsonPackages {
+    count: u32,
+    size: JsonQuantity,
+    leftover: JsonQuantity,
+}
+
+/// `GroupedQuantity::into_vec` would save the clones, but it yields the
+/// components in the group's own random order; going through
+/// `ordered_components` keeps every output path on one ordering rule.
+fn json_quantities(qty: &GroupedQuantity) -> Vec<JsonQuantity> {
+    ordered_components(qty)
+        .into_iter()
+        .cloned()
+        .map(JsonQuantity::from)
+        .collect()
 }
 
-impl From<(String, GroupedQuantity)> for JsonIngredient {
-    fn from((name, qty): (String, GroupedQuantity)) -> Self {
-        // `GroupedQuantity::into_vec` would save the clones, but it yields the
-        // components in the group's own random order; going through
-        // `ordered_components` keeps every output path on one ordering rule.
+impl JsonIngredient {
+    fn new((name, qty): (String, GroupedQuantity), extras: &JsonExtras) -> Self {
         JsonIngredient {
-            name,
-            quantity: ordered_components(&qty)
-                .into_iter()
-                .cloned()
-                .map(JsonQuantity::from)
+            packages: extras.packages.get(&name).map(|p| JsonPackages {
+                count: p.count,
+                size: p.size.clone().into(),
+                leftover: p.leftover.clone().into(),
+            }),
+            in_pantry: extras
+                .in_pantry
+                .get(&name)
+                .map(json_quantities)
+                .unwrap_or_default(),
+            sources: extras
+                .sources
+                .raw(&name)
+                .map(|source| JsonSource {
+                    recipe: source.recipe.clone(),
+                    via: source.via.clone(),
+                    quantity: json_quantities(&source.quantity),
+                })
                 .collect(),
+            name,
+            quantity: json_quantities(&qty),
         }
     }
 }
 
+/// What [`JsonIngredient::new`] needs from the list beyond the ingredient
+/// itself.
+struct JsonExtras {
+    packages: HashMap<String, RawPackages>,
+    in_pantry: HashMap<String, GroupedQuantity>,
+    sources: IngredientSources,
+}
+
 #[derive(Serialize)]
 struct JsonCategory {
     category: String,
@@ -179,26 +361,40 @@ struct JsonCategory {
 }
 
 fn json_categories(list: AggregatedList) -> Vec<JsonCategory> {
+    let extras = JsonExtras {
+        packages: list.raw_packages,
+        in_pantry: list.raw_in_pantry,
+        sources: list.raw_sources,
+    };
     list.raw_categories
         .into_iter()
         .map(|(category, items)| JsonCategory {
             category,
-            items: items.into_iter().map(JsonIngredient::from).collect(),
+            items: items
+                .into_iter()
+                .map(|item| JsonIngredient::new(item, &extras))
+                .collect(),
         })
         .collect()
 }
 
+fn json_items(list: AggregatedList) -> Vec<JsonIngredient> {
+    let extras = JsonExtras {
+        packages: list.raw_packages,
+        in_pantry: list.raw_in_pantry,
+        sources: list.raw_sources,
+    };
+    list.raw_items
+        .into_iter()
+        .map(|item| JsonIngredient::new(item, &extras))
+        .collect()
+}
+
 /// Render the list as JSON: an array of categories, each with its items, or a
 /// flat array of items when `plain` is set.
 pub fn build_json_value(list: AggregatedList, plain: bool) -> serde_json::Value {
     if plain {
-        serde_json::to_value(
-            list.raw_items
-                .into_iter()
-                .map(JsonIngredient::from)
-                .collect::<Vec<_>>(),
-        )
-        .unwrap()
+        serde_json::to_value(json_items(list)).unwrap()
     } else {
         serde_json::to_value(json_categories(list)).unwrap()
     }
@@ -213,18 +409,249 @@ pub fn build_json_value(list: AggregatedList, plain: bool) -> serde_json::Value
 /// `-f json --plain` for anyone scripting against both (#419).
 pub fn build_yaml_value(list: AggregatedList, plain: bool) -> serde_yaml::Value {
     if plain {
-        serde_yaml::to_value(
-            list.raw_items
-                .into_iter()
-                .map(JsonIngredient::from)
-                .collect::<Vec<_>>(),
-        )
-        .unwrap()
+        serde_yaml::to_value(json_items(list)).unwrap()
     } else {
         serde_yaml::to_value(json_categories(list)).unwrap()
     }
 }
 
+/// Every ingredient with the category it is under, in the order the output
+/// lists them: by category, or in recipe order with no categories when
+/// `plain`.
+fn entries(list: &AggregatedList, plain: bool) -> Vec<(Option<&str>, &str, &GroupedQuantity)> {
+    if plain {
+        list.raw_items
+            .iter()
+            .map(|(name, quantity)| (None, name.as_str(), quantity))
+            .collect()
+    } else {
+        list.raw_categories
+            .iter()
+            .flat_map(|(category, items)| {
+                items
+                    .iter()
+                    .map(move |(name, quantity)| (Some(category.as_str()), name.as_str(), quantity))
+            })
+            .collect()
+    }
+}
+
+/// What to buy of one ingredient, as the text formats below say it: packages
+/// and pantry included, as in the human table.
+fn entry_amount(list: &AggregatedList, name: &str, quantity: &GroupedQuantity) -> String {
+    amount_fmt(
+        quantity,
+        list.raw_packages.get(name),
+        list.raw_in_pantry.get(name),
+    )
+}
+
+/// Render the list as CSV, with a header row: `category`, `item`,
+/// `quantity`, `unit`, `amount` and `recipes`.
+///
+/// One row per quantity, so an ingredient measured in two units that do not
+/// convert is two rows; one with no quantity at all is a single row with
+/// `quantity`, `unit` and `amount` empty. `quantity` and `unit` are the
+/// numbers, for a spreadsheet to add up; `amount` says what to buy as the
+/// human table does, in [packages](AggregatedList::round_to_packages) and
+/// with what the pantry already holds, and is the same on every row of an
+/// ingredient. `recipes` names every recipe that asked for it, separated by
+/// `"; "`. `category` is empty when `plain`.
+///
+/// Fields are quoted as RFC 4180 has it — only when they hold a comma, quote
+/// or line break — and rows end in CRLF, which every spreadsheet reads.
+pub fn build_csv(list: AggregatedList, plain: bool) -> String {
+    let mut output = String::new();
+    push_csv_row(
+        &mut output,
+        &["category", "item", "quantity", "unit", "amount", "recipes"],
+    );
+    for (category, name, quantity) in entries(&list, plain) {
+        let recipes = list.raw_sources.recipes(name).join("; ");
+        let amount = entry_amount(&list, name, quantity);
+        let components = ordered_components(quantity);
+        if components.is_empty() {
+            push_csv_row(
+                &mut output,
+                &[category.unwrap_or_default(), name, "", "", "", &recipes],
+            );
+        }
+        for component in components {
+            push_csv_row(
+                &mut output,
+                &[
+                    category.unwrap_or_default(),
+                    name,
+                    &component.value().to_string(),
+                    component.unit().unwrap_or_default(),
+                    &amount,
+                    &recipes,
+                ],
+            );
+        }
+    }
+    output
+}
+
+/// Append one CSV record, quoting the fields that need it and ending the line
+/// with CRLF as RFC 4180 does. Shared with the pantry's
+/// [`export`](crate::pantry::export).
+pub(crate) fn push_csv_row(output: &mut String, fields: &[&str]) {
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|field| {
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        })
+        .collect();
+    output.push_str(&fields.join(","));
+    output.push_str("\r\n");
+}
+
+/// Render the list as a compact checklist for printing: each category's name
+/// over its ingredients, one `☐ name (amount)` line each, and a blank line
+/// between categories.
+///
+/// `plain` drops the category names; `ingredients_only` drops the amounts.
+pub fn build_checklist(list: AggregatedList, plain: bool, ingredients_only: bool) -> String {
+    let mut output = String::new();
+    let mut current = None;
+    for (category, name, quantity) in entries(&list, plain) {
+        if let Some(category) = category.filter(|c| current != Some(*c)) {
+            if current.is_some() {
+                output.push('\n');
+            }
+            output.push_str(&format!("{category}\n"));
+            current = Some(category);
+        }
+        let amount = entry_amount(&list, name, quantity);
+        if ingredients_only || amount.is_empty() {
+            output.push_str(&format!("☐ {name}\n"));
+        } else {
+            output.push_str(&format!("☐ {name} ({amount})\n"));
+        }
+    }
+    output
+}
+
+/// Render the list in the [todo.txt](https://github.com/todotxt/todo.txt)
+/// format: one task per ingredient, in the `+shopping` project, with its
+/// category as a `@context`.
+///
+/// A context is a single word, so the category's spaces become hyphens:
+/// `@fruit-and-veg`. `plain` leaves the contexts out; `ingredients_only`
+/// leaves the amounts out.
+pub fn build_todo_txt(list: AggregatedList, plain: bool, ingredients_only: bool) -> String {
+    let mut output = String::new();
+    for (category, name, quantity) in entries(&list, plain) {
+        let mut task = name.to_string();
+        let amount = entry_amount(&list, name, quantity);
+        if !ingredients_only && !amount.is_empty() {
+            task.push_str(&format!(" ({amount})"));
+        }
+        task.push_str(" +shopping");
+        if let Some(category) = category {
+            let context: Vec<&str> = category.split_whitespace().collect();
+            task.push_str(&format!(" @{}", context.join("-")));
+        }
+        output.push_str(&task);
+        output.push('\n');
+    }
+    output
+}
+
+/// Render the list as an iCalendar file (RFC 5545) with one `VTODO` per
+/// ingredient, for CalDAV task apps to import.
+///
+/// Each task's `SUMMARY` is the ingredient and its amount, `CATEGORIES` its
+/// aisle category (left out when `plain`) and `DESCRIPTION` the recipes that
+/// asked for it. `ingredients_only` leaves the amounts out of the summaries.
+///
+/// `stamp` is the file's creation time: RFC 5545 requires every task to
+/// carry one as `DTSTAMP`, and it makes each `UID` unique to this export, so
+/// importing the list twice adds it twice rather than overwriting tasks that
+/// may have been ticked off since.
+pub fn build_ics(
+    list: AggregatedList,
+    plain: bool,
+    ingredients_only: bool,
+    stamp: DateTime<Utc>,
+) -> String {
+    let stamp = stamp.format("%Y%m%dT%H%M%SZ").to_string();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Cooklang//CookCLI//EN".to_string(),
+    ];
+    for (i, (category, name, quantity)) in entries(&list, plain).into_iter().enumerate() {
+        let amount = entry_amount(&list, name, quantity);
+        let summary = if ingredients_only || amount.is_empty() {
+            name.to_string()
+        } else {
+            format!("{name} ({amount})")
+        };
+        let slug: String = name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_lowercase()
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        lines.push("BEGIN:VTODO".to_string());
+        lines.push(format!("UID:{stamp}-{i}-{slug}@cookcli"));
+        lines.push(format!("DTSTAMP:{stamp}"));
+        lines.push(format!("SUMMARY:{}", ics_text(&summary)));
+        if let Some(category) = category {
+            lines.push(format!("CATEGORIES:{}", ics_text(category)));
+        }
+        let recipes = list.raw_sources.recipes(name);
+        if !recipes.is_empty() {
+            lines.push(format!("DESCRIPTION:{}", ics_text(&recipes.join(", "))));
+        }
+        lines.push("STATUS:NEEDS-ACTION".to_string());
+        lines.push("END:VTODO".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut output = String::new();
+    for line in lines {
+        output.push_str(&fold_ics_line(&line));
+        output.push_str("\r\n");
+    }
+    output
+}
+
+/// Escape an iCalendar `TEXT` value.
+fn ics_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Fold a content line longer than the 75 octets RFC 5545 allows, breaking
+/// only between characters and starting each continuation with a space.
+fn fold_ics_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            // The leading space counts towards the next line's 75.
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -265,6 +692,7 @@ mod tests {
                     ScaledRecipe::new(RecipeSource::Path("b.cook".into())),
                 ],
                 ignore_references: false,
+                cook_date: None,
             },
         )
         .expect("generates")
@@ -274,7 +702,7 @@ mod tests {
     #[test]
     fn the_human_table_groups_by_category_unless_plain() {
         let dir = fixture();
-        let categorised = build_human_table(list(&dir), false, Style::Plain).to_string();
+        let categorised = build_human_table(list(&dir), false, Style::Plain, false).to_string();
         assert!(
             categorised.contains("[produce]") && categorised.contains("[other]"),
             "expected category headings: {categorised}"
@@ -285,7 +713,7 @@ mod tests {
             "quantities must be merged: {categorised}"
         );
 
-        let plain = build_human_table(list(&dir), true, Style::Plain).to_string();
+        let plain = build_human_table(list(&dir), true, Style::Plain, false).to_string();
         assert!(
             !plain.contains('['),
             "--plain must drop the headings: {plain}"
@@ -297,8 +725,8 @@ mod tests {
     #[test]
     fn plain_style_is_ansi_with_the_escapes_removed() {
         let dir = fixture();
-        let plain = build_human_table(list(&dir), false, Style::Plain).to_string();
-        let coloured = build_human_table(list(&dir), false, Style::Ansi).to_string();
+        let plain = build_human_table(list(&dir), false, Style::Plain, false).to_string();
+        let coloured = build_human_table(list(&dir), false, Style::Ansi, false).to_string();
 
         assert!(
             !plain.contains('\u{1b}'),
@@ -395,14 +823,14 @@ mod tests {
     fn markdown_headings_and_quantities_follow_the_flags() {
         let dir = fixture();
 
-        let full = build_md_value(list(&dir), false, false);
+        let full = build_md_value(list(&dir), false, false, false);
         assert!(full.contains("# produce\n- *5* tomatoes\n"), "{full}");
 
-        let plain = build_md_value(list(&dir), true, false);
+        let plain = build_md_value(list(&dir), true, false, false);
         assert!(!plain.contains('#'), "--plain drops headings: {plain}");
         assert!(plain.starts_with("- *5* tomatoes\n"), "{plain}");
 
-        let names_only = build_md_value(list(&dir), false, true);
+        let names_only = build_md_value(list(&dir), false, true, false);
         assert!(
             names_only.contains("# produce\n- tomatoes\n"),
             "{names_only}"
@@ -413,6 +841,120 @@ mod tests {
         );
     }
 
+    #[test]
+    fn sources_are_shown_on_request_and_always_in_json() {
+        let dir = fixture();
+
+        let table = build_human_table(list(&dir), true, Style::Plain, true).to_string();
+        let rows: Vec<Vec<&str>> = table
+            .lines()
+            .map(|line| line.split_whitespace().collect())
+            .collect();
+        assert_eq!(rows[0], ["tomatoes", "5"]);
+        assert_eq!(rows[1], ["↳", "a", "3"], "{table}");
+        assert_eq!(rows[2], ["↳", "b", "2"], "{table}");
+        let without = build_human_table(list(&dir), true, Style::Plain, false).to_string();
+        assert!(!without.contains('↳'), "{without}");
+
+        let markdown = build_md_value(list(&dir), false, false, true);
+        assert!(
+            markdown.contains("- *5* tomatoes\n  - *3* a\n  - *2* b\n"),
+            "{markdown}"
+        );
+
+        let json = build_json_value(list(&dir), true);
+        assert_eq!(json[0]["sources"][1]["recipe"], "b");
+        assert_eq!(json[0]["sources"][1]["via"], serde_json::json!([]));
+        assert_eq!(
+            json[0]["sources"][1]["quantity"][0]["value"]["value"]["value"],
+            2.0
+        );
+    }
+
+    #[test]
+    fn csv_has_a_row_per_quantity_naming_the_recipes() {
+        let dir = fixture();
+
+        let csv = build_csv(list(&dir), false);
+        let rows: Vec<&str> = csv.split_terminator("\r\n").collect();
+        assert_eq!(rows[0], "category,item,quantity,unit,amount,recipes");
+        assert!(rows.contains(&"produce,tomatoes,5,,5,a; b"), "{csv}");
+        assert!(rows.contains(&"spices,salt,2,tsp,2 tsp,a; b"), "{csv}");
+        assert!(rows.contains(&"other,water,2,l,2 l,a"), "{csv}");
+
+        let plain = build_csv(list(&dir), true);
+        assert!(
+            plain.contains("\r\n,tomatoes,5,,5,a; b\r\n"),
+            "--plain leaves the category empty: {plain}"
+        );
+
+        let mut quoted = String::new();
+        push_csv_row(&mut quoted, &["a, b", "say \"hi\"", "plain"]);
+        assert_eq!(quoted, "\"a, b\",\"say \"\"hi\"\"\",plain\r\n");
+    }
+
+    #[test]
+    fn checklist_and_todo_txt_follow_the_flags() {
+        let dir = fixture();
+
+        let checklist = build_checklist(list(&dir), false, false);
+        assert!(
+            checklist.starts_with("produce\n☐ tomatoes (5)\n\nspices\n☐ salt (2 tsp)\n"),
+            "{checklist}"
+        );
+        let names_only = build_checklist(list(&dir), true, true);
+        assert_eq!(names_only, "☐ tomatoes\n☐ salt\n☐ water\n");
+
+        let todo = build_todo_txt(list(&dir), false, false);
+        assert!(
+            todo.starts_with("tomatoes (5) +shopping @produce\n"),
+            "{todo}"
+        );
+        let plain = build_todo_txt(list(&dir), true, true);
+        assert_eq!(
+            plain,
+            "tomatoes +shopping\nsalt +shopping\nwater +shopping\n"
+        );
+    }
+
+    #[test]
+    fn ics_has_a_vtodo_per_ingredient_in_crlf_lines() {
+        let dir = fixture();
+        let stamp = "2026-10-19T08:30:00Z".parse().unwrap();
+
+        let ics = build_ics(list(&dir), false, false, stamp);
+        assert!(
+            ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"),
+            "{ics}"
+        );
+        assert!(ics.ends_with("END:VCALENDAR\r\n"), "{ics}");
+        assert_eq!(ics.matches("BEGIN:VTODO\r\n").count(), 3, "{ics}");
+        assert!(
+            ics.contains(
+                "BEGIN:VTODO\r\nUID:20261019T083000Z-0-tomatoes@cookcli\r\n\
+                 DTSTAMP:20261019T083000Z\r\nSUMMARY:tomatoes (5)\r\n\
+                 CATEGORIES:produce\r\nDESCRIPTION:a\\, b\r\nSTATUS:NEEDS-ACTION\r\n"
+            ),
+            "{ics}"
+        );
+        assert!(
+            !ics.replace("\r\n", "").contains('\n'),
+            "every line ends in CRLF: {ics:?}"
+        );
+    }
+
+    #[test]
+    fn ics_escapes_text_and_folds_long_lines() {
+        assert_eq!(ics_text("a,b;c\\d\ne"), r"a\,b\;c\\d\ne");
+
+        let long = format!("SUMMARY:{}", "é".repeat(50));
+        let folded = fold_ics_line(&long);
+        for line in folded.split("\r\n") {
+            assert!(line.len() <= 75, "{} octets: {line:?}", line.len());
+        }
+        assert_eq!(folded.replace("\r\n ", ""), long);
+    }
+
     /// Two units that cannot be added stay side by side — and every writer
     /// puts them in the same order.
     ///
@@ -438,19 +980,20 @@ mod tests {
                         ScaledRecipe::new(RecipeSource::Path("b.cook".into())),
                     ],
                     ignore_references: false,
+                    cook_date: None,
                 },
             )
             .expect("generates")
             .value
         };
 
-        let table = build_human_table(aggregated(), true, Style::Plain).to_string();
+        let table = build_human_table(aggregated(), true, Style::Plain, false).to_string();
         assert!(
             table.contains("flour 1 cup, 200 g"),
             "inconvertible units are joined with a comma, cup before g: {table}"
         );
 
-        let markdown = build_md_value(aggregated(), true, false);
+        let markdown = build_md_value(aggregated(), true, false, false);
         assert!(
             markdown.contains("- *1 cup, 200 g* flour\n"),
             "markdown agrees with the table: {markdown}"
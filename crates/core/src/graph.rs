@@ -0,0 +1,615 @@
+//! The reference graph of a collection: which recipes and menus name which
+//! other recipes.
+//!
+//! A recipe that writes `@./sauce{}` makes the sauce part of itself — the
+//! shopping list expands it into its ingredients, the recipe page links to it
+//! — and a menu is a list of such references. [`ReferenceGraph::build`] walks
+//! a collection and records every one of them, resolved the way
+//! [`doctor::broken_references`](crate::doctor::broken_references) resolves
+//! them: against the collection root, by [`find::get_recipe`].
+//!
+//! The graph answers structural questions — what references this, what does
+//! this reference, where do the references go round in a circle — and leaves
+//! judging the answers to its callers. [`reference_graph`] is the command form
+//! `cook graph` and the server's graph page use, growing the graph from one
+//! file when asked to.
+
+use crate::{
+    doctor::relative_to,
+    find::{self, build_tree, parse_or_skip, walk},
+    Context, CoreError, Location, Outcome, Span,
+};
+use camino::{Utf8Path, Utf8PathBuf};
+use cooklang::Recipe;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// What [`reference_graph`] should draw.
+///
+/// Not `#[non_exhaustive]`: consumers construct this. `..Default::default()`
+/// keeps a literal working if it grows a field.
+#[derive(Debug, Clone, Default)]
+pub struct GraphRequest {
+    /// Collection to walk. Defaults to the context's base path.
+    pub base_dir: Option<Utf8PathBuf>,
+    /// Draw only what this recipe or menu reaches, looked up as
+    /// [`ReferenceGraph::rooted`] does. `None` draws the whole collection.
+    pub root: Option<String>,
+    /// How many references deep to follow from `root`. `None` follows them
+    /// all; ignored without a root.
+    pub depth: Option<usize>,
+}
+
+/// Build the reference graph of a collection, or the part of it one file
+/// reaches.
+///
+/// # Errors
+///
+/// As [`ReferenceGraph::build`], and as [`ReferenceGraph::rooted`] when
+/// [`GraphRequest::root`] is given.
+pub fn reference_graph(
+    ctx: &Context,
+    req: GraphRequest,
+) -> Result<Outcome<ReferenceGraph>, CoreError> {
+    let base_dir = req
+        .base_dir
+        .unwrap_or_else(|| ctx.base_path().to_path_buf());
+    let Outcome { value, diagnostics } = ReferenceGraph::build(&base_dir)?;
+    let value = match req.root {
+        Some(root) => value.rooted(&root, req.depth)?,
+        None => value,
+    };
+    Ok(Outcome::with_diagnostics(value, diagnostics))
+}
+
+/// Whether a node is a recipe or a menu.
+///
+/// `#[non_exhaustive]` because this is an output type consumers read rather
+/// than construct.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeKind {
+    /// A `.cook` file.
+    Recipe,
+    /// A `.menu` file.
+    Menu,
+}
+
+/// One recipe or menu in the collection.
+///
+/// `#[non_exhaustive]` because this is an output type consumers read rather
+/// than construct.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Node {
+    /// The file, relative to [`ReferenceGraph::base_dir`].
+    pub path: Utf8PathBuf,
+    /// Whether it is a recipe or a menu.
+    pub kind: NodeKind,
+}
+
+/// One reference, from the file that writes it to the file it resolves to.
+///
+/// `#[non_exhaustive]` because this is an output type consumers read rather
+/// than construct.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Edge {
+    /// The referring file, relative to [`ReferenceGraph::base_dir`].
+    pub from: Utf8PathBuf,
+    /// The file the reference resolves to, relative to
+    /// [`ReferenceGraph::base_dir`], or `None` when it resolves to nothing.
+    pub to: Option<Utf8PathBuf>,
+    /// The reference as the referring file spells it: `./sauce`.
+    pub reference: String,
+    /// Where the reference is written. The span covers the reference after its
+    /// `@`, and is that of the first such reference when a file makes the same
+    /// one twice — each use is still its own edge.
+    pub location: Location,
+}
+
+/// Every recipe and menu in a collection, and the references between them.
+///
+/// `#[non_exhaustive]` because this is an output type consumers read rather
+/// than construct.
+#[non_exhaustive]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ReferenceGraph {
+    /// The root that was walked, which every path in the graph is relative
+    /// to.
+    pub base_dir: Utf8PathBuf,
+    /// The file a [`rooted`](ReferenceGraph::rooted) graph was grown from,
+    /// relative to [`base_dir`](ReferenceGraph::base_dir); `None` for a whole
+    /// collection.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub root: Option<Utf8PathBuf>,
+    /// Every recipe and menu, in path order. A file that could not be read or
+    /// parsed is still a node — it exists, and other files may reference it —
+    /// but has no edges out of it.
+    pub nodes: Vec<Node>,
+    /// Every reference, grouped by referring file in path order and then in
+    /// the order the file writes them.
+    pub edges: Vec<Edge>,
+}
+
+impl ReferenceGraph {
+    /// Walk the collection under `base_dir` and resolve every reference in it.
+    ///
+    /// A recipe that cannot be read or parsed contributes no references, with
+    /// a warning in [`Outcome::diagnostics`] saying so; it does not stop the
+    /// walk.
+    ///
+    /// # Errors
+    ///
+    /// [`CoreError::Search`] if the collection cannot be walked, and
+    /// [`CoreError::Io`] if a file in it cannot be listed — as
+    /// [`doctor::validate`](crate::doctor::validate). A reference that leads
+    /// nowhere is an [`Edge`] whose `to` is `None`, not an error.
+    pub fn build(base_dir: &Utf8Path) -> Result<Outcome<ReferenceGraph>, CoreError> {
+        Self::build_visiting(base_dir, |_, _| {})
+    }
+
+    /// As [`build`](Self::build), also handing each recipe and menu that
+    /// parses to `visit`, with its path relative to `base_dir`. For a caller
+    /// that needs more from each file than its references, so that the
+    /// collection is walked and parsed once rather than twice.
+    pub(crate) fn build_visiting(
+        base_dir: &Utf8Path,
+        mut visit: impl FnMut(&Utf8Path, &Recipe),
+    ) -> Result<Outcome<ReferenceGraph>, CoreError> {
+        let tree = build_tree(base_dir)?;
+        let mut diagnostics = Vec::new();
+        let mut nodes = Vec::new();
+        let mut edges = Vec::new();
+
+        for entry in walk(&tree) {
+            let Some(full_path) = entry.path() else {
+                continue;
+            };
+            let path = relative_to(base_dir, full_path);
+            nodes.push(Node {
+                path: path.clone(),
+                kind: if entry.is_menu() {
+                    NodeKind::Menu
+                } else {
+                    NodeKind::Recipe
+                },
+            });
+
+            let Some(recipe) = parse_or_skip(entry, &mut diagnostics) else {
+                continue;
+            };
+            visit(&path, &recipe);
+            let source = entry.content().unwrap_or_default();
+            for ingredient in &recipe.ingredients {
+                let Some(reference) = &ingredient.reference else {
+                    continue;
+                };
+                // Spelled as `doctor::validate` collects them, so that the two
+                // agree on what a reference is called.
+                let lookup = if reference.components.is_empty() {
+                    reference.name.clone()
+                } else {
+                    reference.path("/")
+                };
+                let to = find::get_recipe(base_dir, &lookup)
+                    .ok()
+                    .and_then(|found| found.path().map(|p| relative_to(base_dir, p)));
+                let span = locate(&source, &lookup);
+                edges.push(Edge {
+                    from: path.clone(),
+                    to,
+                    reference: lookup,
+                    location: Location {
+                        file: Some(path.clone()),
+                        span,
+                        range: span.map(|span| span.to_range(&source)),
+                    },
+                });
+            }
+        }
+
+        Ok(Outcome::with_diagnostics(
+            ReferenceGraph {
+                base_dir: base_dir.to_owned(),
+                root: None,
+                nodes,
+                edges,
+            },
+            diagnostics,
+        ))
+    }
+
+    /// The node at `path`, if the collection has one.
+    pub fn node(&self, path: &Utf8Path) -> Option<&Node> {
+        self.nodes.iter().find(|node| node.path == path)
+    }
+
+    /// The references `path` makes, in the order it writes them.
+    pub fn references_from<'a>(&'a self, path: &'a Utf8Path) -> impl Iterator<Item = &'a Edge> {
+        self.edges.iter().filter(move |edge| edge.from == path)
+    }
+
+    /// The references that resolve to `path`, in the order of
+    /// [`edges`](ReferenceGraph::edges).
+    pub fn references_to<'a>(&'a self, path: &'a Utf8Path) -> impl Iterator<Item = &'a Edge> {
+        self.edges
+            .iter()
+            .filter(move |edge| edge.to.as_deref() == Some(path))
+    }
+
+    /// The part of the graph reachable from `root` by following references,
+    /// at most `depth` of them deep — `Some(0)` is the root alone, `Some(1)`
+    /// adds what it references directly, `None` follows every reference to
+    /// its end.
+    ///
+    /// `root` is looked up the way a reference is, by
+    /// [`find::get_recipe`] against [`base_dir`](ReferenceGraph::base_dir):
+    /// `Breakfast/Pancakes`, `Week.menu` and `./Pancakes` all work.
+    ///
+    /// An edge is kept when both its files are: so a reference from the last
+    /// level back to an earlier one — a cycle — still shows. A reference that
+    /// leads nowhere is kept when the file making it was expanded, that is,
+    /// was less than `depth` deep.
+    ///
+    /// # Errors
+    ///
+    /// [`CoreError::RecipeNotFound`] if `root` names no file in this graph,
+    /// and whatever [`find::get_recipe`] returns if it cannot be looked up at
+    /// all.
+    pub fn rooted(&self, root: &str, depth: Option<usize>) -> Result<ReferenceGraph, CoreError> {
+        let found = find::get_recipe(&self.base_dir, root)?;
+        let root = found
+            .path()
+            .map(|path| relative_to(&self.base_dir, path))
+            .filter(|path| self.node(path).is_some())
+            .ok_or_else(|| CoreError::RecipeNotFound {
+                name: root.to_string(),
+            })?;
+
+        // Breadth first, so each file's distance is its shortest.
+        let mut distance: BTreeMap<&Utf8Path, usize> = BTreeMap::from([(root.as_path(), 0)]);
+        let mut queue = std::collections::VecDeque::from([root.as_path()]);
+        while let Some(current) = queue.pop_front() {
+            let here = distance[current];
+            if depth.is_some_and(|depth| here >= depth) {
+                continue;
+            }
+            for edge in self.references_from(current) {
+                if let Some(to) = edge.to.as_deref() {
+                    if !distance.contains_key(to) {
+                        distance.insert(to, here + 1);
+                        queue.push_back(to);
+                    }
+                }
+            }
+        }
+
+        let expanded = |path: &Utf8Path| {
+            distance
+                .get(path)
+                .is_some_and(|d| depth.is_none_or(|max| *d < max))
+        };
+        Ok(ReferenceGraph {
+            base_dir: self.base_dir.clone(),
+            root: Some(root.clone()),
+            nodes: self
+                .nodes
+                .iter()
+                .filter(|node| distance.contains_key(node.path.as_path()))
+                .cloned()
+                .collect(),
+            edges: self
+                .edges
+                .iter()
+                .filter(|edge| match edge.to.as_deref() {
+                    Some(to) => {
+                        distance.contains_key(edge.from.as_path()) && distance.contains_key(to)
+                    }
+                    None => expanded(&edge.from),
+                })
+                .cloned()
+                .collect(),
+        })
+    }
+
+    /// Every cycle of references, each once.
+    ///
+    /// A cycle is listed as the files it passes through, starting from the
+    /// first in path order and without repeating it at the end: a recipe
+    /// referencing itself is `[a]`, two referencing each other `[a, b]`.
+    /// Where several cycles share files — `a` and `b` referencing each other
+    /// and `b` and `c` too — one of them stands for the lot, since breaking
+    /// any reference in it is the start of the fix and the next run will find
+    /// the rest.
+    pub fn cycles(&self) -> Vec<Vec<Utf8PathBuf>> {
+        let mut successors: BTreeMap<&Utf8Path, BTreeSet<&Utf8Path>> = BTreeMap::new();
+        for edge in &self.edges {
+            if let Some(to) = &edge.to {
+                successors.entry(&edge.from).or_default().insert(to);
+            }
+        }
+
+        let mut cycles = Vec::new();
+        for component in strongly_connected(&successors) {
+            let start = component[0];
+            let looped = successors
+                .get(start)
+                .is_some_and(|next| next.contains(start));
+            if component.len() == 1 && !looped {
+                continue;
+            }
+
+            // Walk from the first file, always to the first successor still
+            // inside the component, until a file comes round again. Every file
+            // in a component has such a successor, so this ends, and what lies
+            // between the two visits is a cycle.
+            let members: BTreeSet<&Utf8Path> = component.iter().copied().collect();
+            let mut trail: Vec<&Utf8Path> = Vec::new();
+            let mut current = start;
+            while !trail.contains(&current) {
+                trail.push(current);
+                current = successors[current]
+                    .iter()
+                    .copied()
+                    .find(|next| members.contains(next))
+                    .expect("every file in a cycle references another in it");
+            }
+            let from = trail.iter().position(|p| *p == current).unwrap_or(0);
+            let mut cycle: Vec<&Utf8Path> = trail.split_off(from);
+            let first = (0..cycle.len()).min_by_key(|&i| cycle[i]).unwrap_or(0);
+            cycle.rotate_left(first);
+            cycles.push(cycle.into_iter().map(ToOwned::to_owned).collect());
+        }
+        cycles.sort();
+        cycles
+    }
+}
+
+/// The strongly connected components of `successors`, each in path order,
+/// by Tarjan's algorithm. Iterative rather than recursive, so that a long
+/// chain of references cannot overflow the stack.
+fn strongly_connected<'a>(
+    successors: &BTreeMap<&'a Utf8Path, BTreeSet<&'a Utf8Path>>,
+) -> Vec<Vec<&'a Utf8Path>> {
+    struct State<'a> {
+        index: BTreeMap<&'a Utf8Path, usize>,
+        low: BTreeMap<&'a Utf8Path, usize>,
+        stack: Vec<&'a Utf8Path>,
+        on_stack: BTreeSet<&'a Utf8Path>,
+        components: Vec<Vec<&'a Utf8Path>>,
+    }
+
+    let mut state = State {
+        index: BTreeMap::new(),
+        low: BTreeMap::new(),
+        stack: Vec::new(),
+        on_stack: BTreeSet::new(),
+        components: Vec::new(),
+    };
+    let empty = BTreeSet::new();
+
+    for &root in successors.keys() {
+        if state.index.contains_key(root) {
+            continue;
+        }
+        // Each frame is a file and the successors still to visit from it.
+        let mut frames: Vec<(&Utf8Path, Vec<&Utf8Path>)> = Vec::new();
+        let visit = |node: &'a Utf8Path, state: &mut State<'a>| {
+            let next = state.index.len();
+            state.index.insert(node, next);
+            state.low.insert(node, next);
+            state.stack.push(node);
+            state.on_stack.insert(node);
+            let pending: Vec<&Utf8Path> = successors
+                .get(node)
+                .unwrap_or(&empty)
+                .iter()
+                .rev()
+                .copied()
+                .collect();
+            (node, pending)
+        };
+        frames.push(visit(root, &mut state));
+
+        while let Some((node, pending)) = frames.last_mut() {
+            let node = *node;
+            if let Some(next) = pending.pop() {
+                if !state.index.contains_key(next) {
+                    frames.push(visit(next, &mut state));
+                } else if state.on_stack.contains(next) {
+                    let low = state.low[node].min(state.index[next]);
+                    state.low.insert(node, low);
+                }
+                continue;
+            }
+
+            frames.pop();
+            if let Some((parent, _)) = frames.last() {
+                let low = state.low[*parent].min(state.low[node]);
+                state.low.insert(*parent, low);
+            }
+            if state.low[node] == state.index[node] {
+                let mut component = Vec::new();
+                while let Some(member) = state.stack.pop() {
+                    state.on_stack.remove(member);
+                    component.push(member);
+                    if member == node {
+                        break;
+                    }
+                }
+                component.sort();
+                state.components.push(component);
+            }
+        }
+    }
+
+    state.components
+}
+
+/// The first `@reference` in a file's source, spanning the reference.
+fn locate(source: &str, written: &str) -> Option<Span> {
+    let start = source.find(&format!("@{written}"))? + 1;
+    Some(Span::from(start..start + written.len()))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// A collection in a temporary directory, with `files` written into it at
+    /// the paths given. Shared with the doctor's graph checks, which walk the
+    /// same kind of collection.
+    pub(crate) fn collection(files: &[(&str, &str)]) -> tempfile::TempDir {
+        let dir = tempfile::TempDir::new().unwrap();
+        for (name, text) in files {
+            let path = dir.path().join(name);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, text).unwrap();
+        }
+        dir
+    }
+
+    fn build(dir: &tempfile::TempDir) -> ReferenceGraph {
+        ReferenceGraph::build(Utf8Path::from_path(dir.path()).unwrap())
+            .expect("graph builds")
+            .into_value()
+    }
+
+    fn paths(cycle: &[Utf8PathBuf]) -> Vec<&str> {
+        cycle.iter().map(|p| p.as_str()).collect()
+    }
+
+    #[test]
+    fn every_reference_is_an_edge_resolved_against_the_root() {
+        let dir = collection(&[
+            ("Plan.menu", "Dinner:\n- @./Mains/Stew{2}\n- @./Gone{}\n"),
+            ("Mains/Stew.cook", "Simmer @./Stock{} with @beef{}.\n"),
+            ("Stock.cook", "Boil @bones{}.\n"),
+        ]);
+        let graph = build(&dir);
+
+        let nodes: Vec<(&str, NodeKind)> = graph
+            .nodes
+            .iter()
+            .map(|n| (n.path.as_str(), n.kind))
+            .collect();
+        assert_eq!(
+            nodes,
+            [
+                ("Mains/Stew.cook", NodeKind::Recipe),
+                ("Plan.menu", NodeKind::Menu),
+                ("Stock.cook", NodeKind::Recipe),
+            ]
+        );
+
+        let edges: Vec<(&str, Option<&str>)> = graph
+            .edges
+            .iter()
+            .map(|e| (e.from.as_str(), e.to.as_ref().map(|t| t.as_str())))
+            .collect();
+        assert_eq!(
+            edges,
+            [
+                ("Mains/Stew.cook", Some("Stock.cook")),
+                ("Plan.menu", Some("Mains/Stew.cook")),
+                ("Plan.menu", None),
+            ]
+        );
+
+        let gone = &graph.edges[2];
+        assert_eq!(gone.reference, "./Gone");
+        let range = gone.location.range.unwrap();
+        assert_eq!((range.start.line, range.start.column), (3, 4));
+
+        let into_stock: Vec<&str> = graph
+            .references_to(Utf8Path::new("Stock.cook"))
+            .map(|e| e.from.as_str())
+            .collect();
+        assert_eq!(into_stock, ["Mains/Stew.cook"]);
+        assert!(graph.cycles().is_empty());
+    }
+
+    #[test]
+    fn a_rooted_graph_follows_references_to_the_depth_asked() {
+        let dir = collection(&[
+            ("Plan.menu", "- @./Stew{}\n- @./Gone{}\n"),
+            ("Stew.cook", "Add @./Stock{}.\n"),
+            ("Stock.cook", "Add @./Bones{} and @./Missing{}.\n"),
+            ("Bones.cook", "Roast @bones{}, then add to @./Stew{}.\n"),
+            ("Salad.cook", "Toss @leaves{}.\n"),
+        ]);
+        let graph = build(&dir);
+
+        let nodes = |g: &ReferenceGraph| -> Vec<String> {
+            g.nodes.iter().map(|n| n.path.to_string()).collect()
+        };
+        let edges = |g: &ReferenceGraph| -> Vec<String> {
+            g.edges
+                .iter()
+                .map(|e| format!("{} -> {}", e.from, e.reference))
+                .collect()
+        };
+
+        let root_only = graph.rooted("Plan.menu", Some(0)).unwrap();
+        assert_eq!(root_only.root.as_deref(), Some(Utf8Path::new("Plan.menu")));
+        assert_eq!(nodes(&root_only), ["Plan.menu"]);
+        assert!(root_only.edges.is_empty());
+
+        let one = graph.rooted("Plan.menu", Some(1)).unwrap();
+        assert_eq!(nodes(&one), ["Plan.menu", "Stew.cook"]);
+        assert_eq!(edges(&one), ["Plan.menu -> ./Stew", "Plan.menu -> ./Gone"]);
+
+        // The reference back from `Bones` to `Stew` shows, though `Bones` is
+        // the last level and was not expanded.
+        let two = graph.rooted("./Stew", Some(2)).unwrap();
+        assert_eq!(nodes(&two), ["Bones.cook", "Stew.cook", "Stock.cook"]);
+        assert_eq!(
+            edges(&two),
+            [
+                "Bones.cook -> ./Stew",
+                "Stew.cook -> ./Stock",
+                "Stock.cook -> ./Bones",
+                "Stock.cook -> ./Missing",
+            ]
+        );
+
+        // At depth 1 `Stock` is the last level, so its missing reference goes.
+        let one = graph.rooted("./Stew", Some(1)).unwrap();
+        assert_eq!(edges(&one), ["Stew.cook -> ./Stock"]);
+
+        let all = graph.rooted("Stew", None).unwrap();
+        assert_eq!(all.edges.len(), 4);
+        assert_eq!(all.nodes.len(), 3);
+
+        assert!(matches!(
+            graph.rooted("Nowhere", None),
+            Err(CoreError::RecipeNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn cycles_are_found_once_each_from_their_first_file() {
+        let dir = collection(&[
+            ("a.cook", "@./b{}\n"),
+            ("b.cook", "@./a{}\n"),
+            ("c.cook", "@./d{}\n"),
+            ("d.cook", "@./e{}\n"),
+            ("e.cook", "@./c{}\n"),
+            ("lead.cook", "@./c{}\n"),
+            ("me.cook", "@./me{}\n"),
+        ]);
+        let cycles = build(&dir).cycles();
+        let cycles: Vec<Vec<&str>> = cycles.iter().map(|c| paths(c)).collect();
+
+        assert_eq!(
+            cycles,
+            [
+                vec!["a.cook", "b.cook"],
+                vec!["c.cook", "d.cook", "e.cook"],
+                vec!["me.cook"],
+            ]
+        );
+    }
+}
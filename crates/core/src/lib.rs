@@ -17,17 +17,21 @@ pub mod error;
 pub mod find;
 pub mod format;
 mod fs_atomic;
+pub mod graph;
 pub mod outcome;
 pub mod pantry;
 pub mod parser;
 pub mod recipe;
 pub mod report;
+mod rfc3339;
 pub mod search;
 pub mod shopping_list;
 pub mod source;
+pub mod substitutes;
+mod units;
 
 pub use context::{global_config_path, Context};
-pub use diagnostic::{Diagnostic, Location, Severity, Span};
+pub use diagnostic::{Diagnostic, Location, Position, Severity, Span, TextRange};
 pub use error::CoreError;
 pub use find::REFERENCE_SEPARATOR;
 pub use format::{PaperSize, Style};
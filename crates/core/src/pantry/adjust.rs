@@ -0,0 +1,241 @@
+//! Adding to and taking from one item's quantity.
+//!
+//! [`update`](super::update) sets a quantity outright, which leaves the
+//! arithmetic to whoever is at the keyboard: used 200 g of a `1%kg` bag of
+//! flour, and it is on them to work out and type `0.8%kg`. [`take`] and
+//! [`top_up`] do the sum instead. The amount may be in any unit that converts
+//! to the item's own (see [`crate::units`]), and the result is written in the
+//! item's unit, so the file keeps saying flour in `kg` however it was used.
+//!
+//! An item is found by name, ignoring case, as [`consume`](super::consume)
+//! finds the items a recipe draws on. A name the pantry has in more than one
+//! section needs [`AdjustRequest::section`] to say which.
+
+use super::{
+    edit, is_depleted, journal, load, normalise_array_section, path_to_edit, read_document,
+    split_quantity, write_quantity, ItemChange, PantryItem,
+};
+use crate::{fs_atomic::write_atomically, units, Context, CoreError, Outcome};
+use serde::Serialize;
+
+/// Amounts closer than this are the same amount, as in
+/// [`consume`](super::consume).
+const EPSILON: f64 = 1e-9;
+
+/// Which item to change, and by how much.
+///
+/// Not `#[non_exhaustive]`: consumers construct this. `..Default::default()`
+/// keeps a literal working if it grows a field.
+#[derive(Debug, Clone, Default)]
+pub struct AdjustRequest {
+    /// The item's name, matched ignoring case.
+    pub name: String,
+    /// The section it is in, matched exactly, or `None` to find it wherever
+    /// it is.
+    pub section: Option<String>,
+    /// How much, as pantry files write it: `"200%g"`, `"2"`.
+    pub amount: String,
+    /// Work out the new quantity without writing anything.
+    pub dry_run: bool,
+}
+
+/// One item's quantity, before and after.
+///
+/// `#[non_exhaustive]` because this is an output type consumers read rather
+/// than construct.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AdjustedItem {
+    /// The item's name, as the pantry writes it.
+    pub name: String,
+    /// The section it is in.
+    pub section: String,
+    /// How much was taken or added, in the item's own unit: `"0.2%kg"`.
+    /// Never more than there was, for [`take`].
+    pub amount: String,
+    /// The quantity before, or `None` for an item [`top_up`] gave its first
+    /// quantity.
+    pub before: Option<String>,
+    /// The quantity now — or, for a dry run, what it would be.
+    pub after: String,
+    /// True when [`take`] was asked for more than there was. What there was
+    /// is used up.
+    pub short: bool,
+    /// True when nothing is left.
+    pub empty: bool,
+    /// True when what is left counts as running out, by the rule
+    /// [`depleted`](super::depleted) uses.
+    pub low: bool,
+}
+
+/// Take `req.amount` off an item's quantity and write it back.
+///
+/// The quantity never goes below zero: taking more than there is empties the
+/// item and says so in [`AdjustedItem::short`].
+///
+/// Only the item's quantity changes; see [what a write
+/// touches](super#what-a-write-touches). The change is recorded in the
+/// [journal](super::history) as `use <name> <amount>`.
+///
+/// # Errors
+///
+/// - [`CoreError::PantryEdit`], writing nothing, if no item has the name, or
+///   more than one does and no section was given; if the amount is not a
+///   number and a unit, or is not in a unit that converts to the item's; or
+///   if the item has no quantity to take from — none at all, `unlim`, or
+///   text that is not a number and a unit.
+/// - As [`load`] and [`update`](super::update) otherwise.
+pub fn take(ctx: &Context, req: AdjustRequest) -> Result<Outcome<AdjustedItem>, CoreError> {
+    adjust(ctx, req, Direction::Take)
+}
+
+/// Add `req.amount` to an item's quantity and write it back.
+///
+/// An item kept with no quantity at all is given the amount as its first
+/// one, in the amount's own unit. Otherwise as [`take`], recorded in the
+/// journal as `top up <name> <amount>`.
+///
+/// # Errors
+///
+/// As [`take`], except that an item with no quantity is not one.
+pub fn top_up(ctx: &Context, req: AdjustRequest) -> Result<Outcome<AdjustedItem>, CoreError> {
+    adjust(ctx, req, Direction::TopUp)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Take,
+    TopUp,
+}
+
+fn adjust(
+    ctx: &Context,
+    req: AdjustRequest,
+    direction: Direction,
+) -> Result<Outcome<AdjustedItem>, CoreError> {
+    let refuse = |message: String| CoreError::PantryEdit { message };
+    let Some((amount, amount_unit)) = split_quantity(&req.amount) else {
+        return Err(refuse(format!(
+            "'{}' is not a number and a unit, such as 200%g",
+            req.amount
+        )));
+    };
+
+    let loaded = load(ctx)?;
+    let mut diagnostics = loaded.diagnostics;
+    let item = find(loaded.value.items(), &req)?;
+
+    let (before, have, unit) = match item.quantity.as_deref() {
+        None if direction == Direction::TopUp => (None, 0.0, amount_unit.clone()),
+        None | Some("unlim" | "unlimited") => {
+            return Err(refuse(format!(
+                "'{}' in section '{}' is not counted, so there is nothing to take from",
+                item.name, item.section
+            )));
+        }
+        Some(written) => match split_quantity(written) {
+            Some((have, unit)) => (Some(written.to_string()), have, unit),
+            None => {
+                return Err(refuse(format!(
+                    "'{}' in section '{}' has the quantity '{written}', which is not a number \
+                     and a unit",
+                    item.name, item.section
+                )))
+            }
+        },
+    };
+    let Some(amount) = units::convert(amount, &amount_unit, &unit) else {
+        return Err(refuse(format!(
+            "'{}' is kept in {}, which {} does not convert to",
+            item.name,
+            if unit.is_empty() { "a count" } else { &unit },
+            req.amount
+        )));
+    };
+
+    let (changed, now, short) = match direction {
+        Direction::TopUp => (amount, have + amount, false),
+        Direction::Take => {
+            let taken = amount.min(have);
+            (taken, have - taken, amount - taken > EPSILON)
+        }
+    };
+    let after = write_quantity(now, &unit);
+    let mut adjusted_item = item.clone();
+    adjusted_item.quantity = Some(after.clone());
+    let adjusted = AdjustedItem {
+        name: item.name.clone(),
+        section: item.section.clone(),
+        amount: write_quantity(changed, &unit),
+        before,
+        empty: now <= EPSILON,
+        low: now <= EPSILON || is_depleted(&adjusted_item, false),
+        after,
+        short,
+    };
+
+    if !req.dry_run {
+        let path = path_to_edit(ctx)?;
+        let (mut doc, _) = read_document(&path)?;
+        diagnostics.extend(normalise_array_section(&mut doc, &item.section, &path));
+        let was = edit::entry_text(&doc, &item.section, &item.name);
+        edit::apply(
+            &mut doc,
+            &item.section,
+            &item.name,
+            &edit::Attributes {
+                quantity: Some(adjusted.after.clone()),
+                ..Default::default()
+            },
+        )?;
+        let change = ItemChange {
+            section: item.section.clone(),
+            name: item.name.clone(),
+            before: was,
+            after: edit::entry_text(&doc, &item.section, &item.name),
+        };
+        // Taking nothing from nothing leaves the entry as it was: there is
+        // no change to write or to record.
+        if change.before != change.after {
+            write_atomically(&path, doc.to_string())?;
+            let operation = match direction {
+                Direction::Take => format!("use {} {}", item.name, req.amount),
+                Direction::TopUp => format!("top up {} {}", item.name, req.amount),
+            };
+            diagnostics.extend(journal::append(ctx, &path, &operation, None, vec![change]));
+        }
+    }
+
+    Ok(Outcome::with_diagnostics(adjusted, diagnostics))
+}
+
+/// The one item `req` names.
+fn find<'a>(
+    items: impl Iterator<Item = &'a PantryItem>,
+    req: &AdjustRequest,
+) -> Result<&'a PantryItem, CoreError> {
+    let matching: Vec<&PantryItem> = items
+        .filter(|item| item.name.eq_ignore_ascii_case(req.name.trim()))
+        .filter(|item| req.section.as_ref().is_none_or(|s| &item.section == s))
+        .collect();
+    match matching.as_slice() {
+        [item] => Ok(item),
+        [] => Err(CoreError::PantryEdit {
+            message: match &req.section {
+                Some(section) => format!("item '{}' not found in section '{section}'", req.name),
+                None => format!("item '{}' not found in the pantry", req.name),
+            },
+        }),
+        several => Err(CoreError::PantryEdit {
+            message: format!(
+                "'{}' is in more than one section ({}); say which",
+                req.name,
+                several
+                    .iter()
+                    .map(|item| item.section.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }),
+    }
+}
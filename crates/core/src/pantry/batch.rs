@@ -0,0 +1,216 @@
+//! Several edits to the pantry, written together.
+//!
+//! A page that lets someone tick ten items and move them to the freezer would
+//! otherwise make ten writes, any of which can fail after the ones before it
+//! have landed — leaving the pantry half moved, and ten entries in the
+//! journal for what was one action. [`batch`] applies every edit to one copy
+//! of the file in memory and writes it once: either all of them happen or
+//! none does, and the journal records them as a single change that one
+//! [`undo`](super::undo) takes back.
+//!
+//! Each edit means what the function of the same name means — an
+//! [`Add`](BatchEdit::Add) is refused where [`add`](super::add) would be, and
+//! so on — and sees the pantry as the edits before it left it, so adding an
+//! item and then updating it in the same batch works. [`Move`](BatchEdit::Move)
+//! has no single-item function: it takes an entry out of one section and puts
+//! it in another exactly as it was written, attributes `cooklang` does not
+//! know included.
+
+use super::{
+    add_attributes, add_to, edit, normalise_array_section, path_to_edit, read_document,
+    remove_from, save, update_attributes, update_in, AddRequest, ItemChange, PantryContents,
+    RemoveRequest, UpdateRequest,
+};
+use crate::{Context, CoreError, Outcome};
+
+/// One edit in a [`BatchRequest`].
+///
+/// Not `#[non_exhaustive]`: consumers construct this, and a new kind of edit
+/// is one they would have to be told about anyway.
+#[derive(Debug, Clone)]
+pub enum BatchEdit {
+    /// As [`add`](super::add).
+    Add(AddRequest),
+    /// As [`update`](super::update).
+    Update(UpdateRequest),
+    /// As [`remove`](super::remove).
+    Remove(RemoveRequest),
+    /// Take an item out of its section and put it in another.
+    Move(MoveRequest),
+}
+
+/// Which item to move, and where.
+///
+/// Not `#[non_exhaustive]`: consumers construct this. `..Default::default()`
+/// keeps a literal working if it grows a field.
+#[derive(Debug, Clone, Default)]
+pub struct MoveRequest {
+    /// The section holding it, matched exactly.
+    pub section: String,
+    /// The item's name, matched exactly.
+    pub name: String,
+    /// The section to move it to, created if the file has no such section.
+    pub to: String,
+}
+
+/// The edits to make, in order.
+///
+/// Not `#[non_exhaustive]`: consumers construct this. `..Default::default()`
+/// keeps a literal working if it grows a field.
+#[derive(Debug, Clone, Default)]
+pub struct BatchRequest {
+    /// Applied first to last, each to the pantry as the ones before it left
+    /// it.
+    pub edits: Vec<BatchEdit>,
+}
+
+/// Apply every edit in `req` and write the pantry back once.
+///
+/// Returns the pantry as it now stands on disk, like [`add`](super::add).
+/// The journal records one change, `batch`, holding every item the edits
+/// left different, with what it was before the first of them and after the
+/// last; an item edited and then edited back is not in it.
+///
+/// # Errors
+///
+/// - [`CoreError::PantryEdit`] if `req` has no edits, or if any edit is
+///   refused for the reason its single-item function would refuse it, or a
+///   move finds the item already in the section it is moving to. The message
+///   says which edit, counting from 1. Nothing is written.
+/// - [`CoreError::MissingConfig`] and [`CoreError::ReadOnlyConfig`] as
+///   [`remove`](super::remove): a batch does not create a pantry, even one
+///   that only adds.
+/// - As [`load`](super::load) otherwise, plus [`CoreError::Io`] if the file
+///   cannot be written.
+pub fn batch(ctx: &Context, req: BatchRequest) -> Result<Outcome<PantryContents>, CoreError> {
+    if req.edits.is_empty() {
+        return Err(CoreError::PantryEdit {
+            message: "a batch needs at least one edit".to_string(),
+        });
+    }
+
+    let path = path_to_edit(ctx)?;
+    let (mut doc, mut diagnostics) = read_document(&path)?;
+    // Sections written as arrays are rewritten before the snapshot the journal
+    // is taken from, as the single-item edits do, so that their items are
+    // recorded as they were rather than as not there at all.
+    for edit in &req.edits {
+        let (section, to) = match edit {
+            BatchEdit::Add(AddRequest { section, .. })
+            | BatchEdit::Update(UpdateRequest { section, .. })
+            | BatchEdit::Remove(RemoveRequest { section, .. }) => (section, None),
+            BatchEdit::Move(req) => (&req.section, Some(&req.to)),
+        };
+        for section in std::iter::once(section).chain(to) {
+            diagnostics.extend(normalise_array_section(&mut doc, section, &path));
+        }
+    }
+    let original = doc.clone();
+
+    // Every entry touched, in the order first touched, so that the journal
+    // lists them as the edits did.
+    let mut touched: Vec<(String, String)> = Vec::new();
+    let mut touch = |section: &str, name: &str| {
+        if !touched.iter().any(|(s, n)| s == section && n == name) {
+            touched.push((section.to_string(), name.to_string()));
+        }
+    };
+
+    for (index, edit) in req.edits.iter().enumerate() {
+        let applied = match edit {
+            BatchEdit::Add(add) => add_attributes(add, &mut diagnostics).and_then(|attributes| {
+                add_to(
+                    &mut doc,
+                    &path,
+                    &add.section,
+                    &add.name,
+                    &attributes,
+                    &mut diagnostics,
+                )
+            }),
+            BatchEdit::Update(update) => update_attributes(update).and_then(|attributes| {
+                update_in(
+                    &mut doc,
+                    &path,
+                    &update.section,
+                    &update.name,
+                    &attributes,
+                    &mut diagnostics,
+                )
+                .map(drop)
+            }),
+            BatchEdit::Remove(remove) => remove_from(
+                &mut doc,
+                &path,
+                &remove.section,
+                &remove.name,
+                &mut diagnostics,
+            )
+            .map(drop),
+            BatchEdit::Move(req) => move_item(&mut doc, &path, req, &mut diagnostics),
+        };
+        applied.map_err(|e| match e {
+            CoreError::PantryEdit { message } => CoreError::PantryEdit {
+                message: format!("edit {}: {message}", index + 1),
+            },
+            e => e,
+        })?;
+
+        match edit {
+            BatchEdit::Add(AddRequest { section, name, .. })
+            | BatchEdit::Update(UpdateRequest { section, name, .. })
+            | BatchEdit::Remove(RemoveRequest { section, name }) => touch(section, name),
+            BatchEdit::Move(req) => {
+                touch(&req.section, &req.name);
+                touch(&req.to, &req.name);
+            }
+        }
+    }
+
+    let changes = touched
+        .into_iter()
+        .filter_map(|(section, name)| {
+            let before = edit::entry_text(&original, &section, &name);
+            let after = edit::entry_text(&doc, &section, &name);
+            (before != after).then_some(ItemChange {
+                section,
+                name,
+                before,
+                after,
+            })
+        })
+        .collect();
+    save(ctx, &path, &doc, diagnostics, "batch", changes)
+}
+
+/// Take the entry at `req.section`/`req.name` out, and put it in `req.to`
+/// exactly as it was written.
+fn move_item(
+    doc: &mut toml_edit::DocumentMut,
+    path: &camino::Utf8Path,
+    req: &MoveRequest,
+    diagnostics: &mut Vec<crate::Diagnostic>,
+) -> Result<(), CoreError> {
+    if edit::item_exists(doc, &req.to, &req.name) {
+        return Err(CoreError::PantryEdit {
+            message: format!("item '{}' already exists in section '{}'", req.name, req.to),
+        });
+    }
+    // Taken out as `remove` would, dropping a section it empties.
+    let Some(text) = remove_from(doc, path, &req.section, &req.name, diagnostics)? else {
+        return Ok(());
+    };
+    // Only a quantity fits at the top of the file; see
+    // `edit::check_general_attributes`.
+    if req.to == edit::GENERAL && text.starts_with('{') {
+        return Err(CoreError::PantryEdit {
+            message: format!(
+                "item '{}' carries more than a quantity, so it cannot be moved above the first \
+                 section header, where only a quantity can be written",
+                req.name
+            ),
+        });
+    }
+    diagnostics.extend(normalise_array_section(doc, &req.to, path));
+    edit::put(doc, &req.to, &req.name, Some(&text))
+}
@@ -0,0 +1,270 @@
+//! Taking what a recipe used out of the pantry.
+//!
+//! [`consume`] is the other end of the shopping list's
+//! [`commit_to_pantry`](crate::shopping_list::commit_to_pantry): once a recipe
+//! has been cooked, each of its ingredients is drawn down from the pantry
+//! items of the same name, converting between units of mass or volume where
+//! the recipe and the pantry disagree (see [`crate::units`]).
+//!
+//! The recipe is expanded as a shopping list expands it, so cooking a pasta
+//! that references a sauce uses up the sauce's ingredients too, at the scale
+//! asked for.
+
+use super::{
+    edit, is_depleted, journal, load, path_to_edit, read_document, split_quantity, write_quantity,
+    ItemChange, PantryItem,
+};
+use crate::{
+    format::{quantity::ordered_components, shopping_list::quantity_fmt},
+    fs_atomic::write_atomically,
+    shopping_list::{extract_ingredients, ExtractOptions, ScaledRecipe},
+    units, Context, CoreError, Diagnostic, Outcome, RecipeSource,
+};
+use cooklang::{ingredient_list::IngredientList, quantity::Value};
+use serde::Serialize;
+
+/// Amounts closer than this are the same amount: conversions leave float
+/// noise, and using `1 kg` of `1000%g` should leave nothing rather than a
+/// millionth of a gram.
+const EPSILON: f64 = 1e-9;
+
+/// Which recipe was cooked.
+///
+/// Not `#[non_exhaustive]`: consumers construct this.
+#[derive(Debug, Clone)]
+pub struct ConsumeRequest {
+    /// The recipe, at the scale it was cooked at.
+    pub recipe: ScaledRecipe,
+    /// Work out what would change without writing anything.
+    pub dry_run: bool,
+}
+
+/// One pantry item a recipe drew on.
+///
+/// `#[non_exhaustive]` because this is an output type consumers read rather
+/// than construct.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConsumedItem {
+    /// The item's name, as the pantry writes it.
+    pub name: String,
+    /// The section it is in.
+    pub section: String,
+    /// How much was taken, in the item's own unit: `"150%g"`.
+    pub used: String,
+    /// The quantity before.
+    pub before: String,
+    /// The quantity now — or, for a dry run, what it would be.
+    pub after: String,
+    /// True when nothing is left.
+    pub empty: bool,
+    /// True when what is left counts as running out, by the rule
+    /// [`depleted`](super::depleted) uses. Also true when `empty` is.
+    pub low: bool,
+}
+
+/// What cooking a recipe took out of the pantry.
+///
+/// `#[non_exhaustive]` because this is an output type consumers read rather
+/// than construct.
+#[non_exhaustive]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Consumption {
+    /// Every pantry item that was drawn on, in the order the recipe uses
+    /// them.
+    pub items: Vec<ConsumedItem>,
+    /// Ingredients the recipe asked for more of than the pantry counted, by
+    /// the recipe's name for them. What there was is used up.
+    pub short: Vec<String>,
+    /// Ingredients the pantry has no entry for at all.
+    pub not_stocked: Vec<String>,
+}
+
+/// Take what `req`'s recipe uses out of the pantry and write it back.
+///
+/// Each ingredient is matched against pantry items by name, ignoring case.
+/// Its amounts are drawn from those items in file order, each converted into
+/// the item's own unit, and an item is never taken below zero; anything still
+/// wanted afterwards puts the ingredient in [`Consumption::short`]. An item
+/// kept without a quantity, or as `unlim`, is never drawn down — it was never
+/// counted — and covers whatever the counted ones could not. Amounts written
+/// as text (`a pinch`) are left alone, and so is an ingredient with no amount
+/// at all.
+///
+/// The pantry is written once, after every ingredient has been drawn, so a
+/// failure leaves it as it was; only the quantities that changed are touched
+/// — see [what a write touches](super#what-a-write-touches). The write is
+/// one change in the [journal](super::history), so a single
+/// [`undo`](super::undo) puts the whole recipe back. Nothing is written for a
+/// [`ConsumeRequest::dry_run`], or when nothing changed.
+///
+/// A pantry quantity that is not a number and a unit, and a recipe amount in
+/// a unit no stocked item converts to, are each reported as a warning in
+/// [`Outcome::diagnostics`], alongside the recipe's own.
+///
+/// # Errors
+///
+/// - As [`load`], for the pantry, and as
+///   [`shopping_list::generate`](crate::shopping_list::generate), for the
+///   recipe.
+/// - [`CoreError::ReadOnlyConfig`] if the pantry is held inline and this is
+///   not a dry run, and [`CoreError::Io`] if it cannot be written.
+pub fn consume(ctx: &Context, req: ConsumeRequest) -> Result<Outcome<Consumption>, CoreError> {
+    let loaded = load(ctx)?;
+    let mut diagnostics = loaded.diagnostics;
+    let original: Vec<PantryItem> = loaded.value.items().cloned().collect();
+    let mut stock = original.clone();
+
+    let mut needed = IngredientList::new();
+    diagnostics.extend(extract_ingredients(
+        ctx,
+        &req.recipe,
+        &ExtractOptions::default(),
+        &mut needed,
+    )?);
+
+    let mut consumption = Consumption::default();
+    let mut changed: Vec<usize> = Vec::new();
+    let mut unreadable: Vec<usize> = Vec::new();
+
+    for (name, need) in needed.iter() {
+        let matching: Vec<usize> = (0..stock.len())
+            .filter(|&i| stock[i].name.eq_ignore_ascii_case(name))
+            .collect();
+        if matching.is_empty() {
+            consumption.not_stocked.push(name.clone());
+            continue;
+        }
+        let uncounted = matching.iter().any(|&i| {
+            matches!(
+                stock[i].quantity.as_deref(),
+                None | Some("unlim" | "unlimited")
+            )
+        });
+
+        let mut short = false;
+        for quantity in ordered_components(need) {
+            let Value::Number(number) = quantity.value() else {
+                continue;
+            };
+            let unit = quantity.unit().unwrap_or_default();
+            let mut left = number.value();
+            let mut readable = false;
+            let mut converts = false;
+
+            for &i in &matching {
+                if left <= EPSILON {
+                    break;
+                }
+                let Some(written) = stock[i].quantity.clone() else {
+                    continue;
+                };
+                let Some((have, have_unit)) = split_quantity(&written) else {
+                    if !uncounted && !unreadable.contains(&i) {
+                        unreadable.push(i);
+                    }
+                    continue;
+                };
+                readable = true;
+                let Some(wanted) = units::convert(left, unit, &have_unit) else {
+                    continue;
+                };
+                converts = true;
+                let taken = wanted.min(have);
+                if taken <= EPSILON {
+                    continue;
+                }
+                left -= units::convert(taken, &have_unit, unit).unwrap_or(left);
+                stock[i].quantity = Some(write_quantity(have - taken, &have_unit));
+                if !changed.contains(&i) {
+                    changed.push(i);
+                }
+            }
+
+            if readable && !converts && !uncounted {
+                diagnostics.push(Diagnostic::warning(format!(
+                    "the pantry has {name}, but not in a unit {} converts to, so none of it \
+                     is used",
+                    quantity_fmt(quantity)
+                )));
+            }
+            if left > EPSILON && !uncounted {
+                short = true;
+            }
+        }
+        if short {
+            consumption.short.push(name.clone());
+        }
+    }
+
+    for i in unreadable {
+        let item = &stock[i];
+        diagnostics.push(Diagnostic::warning(format!(
+            "{} in [{}] has the quantity '{}', which is not a number and a unit, so nothing \
+             is taken from it",
+            item.name,
+            item.section,
+            item.quantity.as_deref().unwrap_or_default()
+        )));
+    }
+
+    for &i in &changed {
+        let (before, after) = (&original[i], &stock[i]);
+        let (was, _) = split_quantity(before.quantity.as_deref().unwrap_or_default())
+            .expect("only quantities that read as amounts are changed");
+        let (now, unit) = split_quantity(after.quantity.as_deref().unwrap_or_default())
+            .expect("written by write_quantity");
+        consumption.items.push(ConsumedItem {
+            name: after.name.clone(),
+            section: after.section.clone(),
+            used: write_quantity(was - now, &unit),
+            before: before.quantity.clone().unwrap_or_default(),
+            after: after.quantity.clone().unwrap_or_default(),
+            empty: now <= EPSILON,
+            low: now <= EPSILON || is_depleted(after, false),
+        });
+    }
+
+    if !req.dry_run && !changed.is_empty() {
+        let path = path_to_edit(ctx)?;
+        let (mut doc, _) = read_document(&path)?;
+        let mut changes = Vec::new();
+        for &i in &changed {
+            let item = &stock[i];
+            let before = edit::entry_text(&doc, &item.section, &item.name);
+            edit::apply(
+                &mut doc,
+                &item.section,
+                &item.name,
+                &edit::Attributes {
+                    quantity: item.quantity.clone(),
+                    ..Default::default()
+                },
+            )?;
+            changes.push(ItemChange {
+                section: item.section.clone(),
+                name: item.name.clone(),
+                before,
+                after: edit::entry_text(&doc, &item.section, &item.name),
+            });
+        }
+        write_atomically(&path, doc.to_string())?;
+        let operation = format!("consume {}", recipe_label(&req.recipe));
+        diagnostics.extend(journal::append(ctx, &path, &operation, None, changes));
+    }
+
+    Ok(Outcome::with_diagnostics(consumption, diagnostics))
+}
+
+/// The recipe as the journal names it: `Easy Pancakes`, `Easy Pancakes:2`.
+fn recipe_label(recipe: &ScaledRecipe) -> String {
+    let name = match &recipe.source {
+        RecipeSource::Path(path) => path.as_str(),
+        RecipeSource::Content { name, .. } => name.as_str(),
+    };
+    if recipe.scale == 1.0 {
+        name.to_string()
+    } else {
+        format!("{name}:{}", recipe.scale)
+    }
+}
@@ -79,6 +79,7 @@ pub(super) fn check_general_attributes(
         ("bought", &attributes.bought),
         ("expire", &attributes.expire),
         ("low", &attributes.low),
+        ("restock", &attributes.restock),
     ]
     .into_iter()
     .filter(|(_, value)| value.is_some())
@@ -179,6 +180,27 @@ pub(super) fn insert(doc: &mut DocumentMut, section: &str, name: &str, attribute
     }
 }
 
+/// Make the item at `section`/`name` carry exactly `attributes`, replacing
+/// whatever it was written as where it stands, or insert it if it is not
+/// there.
+///
+/// Unlike [`apply`], nothing the entry carried before is kept — keys this
+/// crate does not model included. That is the point: it is what an import
+/// that replaces the pantry asks for.
+pub(super) fn replace(doc: &mut DocumentMut, section: &str, name: &str, attributes: &Attributes) {
+    let existing = if section == GENERAL {
+        doc.as_table_mut().get_mut(name)
+    } else {
+        doc.get_mut(section)
+            .and_then(Item::as_table_like_mut)
+            .and_then(|table| table.get_mut(name))
+    };
+    match existing {
+        Some(existing) => *existing = attributes.to_item(section),
+        None => insert(doc, section, name, attributes),
+    }
+}
+
 /// Take `name` out of `section`, and the section too if that empties it.
 ///
 /// Removing an emptied section matches what the file would say after a
@@ -203,6 +225,87 @@ pub(super) fn remove(doc: &mut DocumentMut, section: &str, name: &str) {
     }
 }
 
+/// The value of the item at `section`/`name` as TOML text, without the
+/// whitespace around it: `"1%kg"`, `{ quantity = "1%kg", low = "200%g" }`.
+/// `None` if there is no such item.
+///
+/// This is what the [journal](super::history) records of an item before and
+/// after a change, and what [`put`] writes back. An item written as a
+/// `[section.name]` table comes back as the inline table it is equivalent to.
+pub(super) fn entry_text(doc: &DocumentMut, section: &str, name: &str) -> Option<String> {
+    if !item_exists(doc, section, name) {
+        return None;
+    }
+    let item = section_entries(doc, section)?.get(name)?;
+    let mut value = match item {
+        Item::Value(value) => value.clone(),
+        Item::Table(table) => Value::InlineTable(table.clone().into_inline_table()),
+        _ => return None,
+    };
+    value.decor_mut().clear();
+    Some(value.to_string())
+}
+
+/// The string value of `key` on the item at `section`/`name`, for an
+/// attribute `cooklang` does not model and so does not hand back.
+///
+/// `None` if there is no such item, it is written in the short form, or the
+/// key is missing or not a string.
+pub(super) fn attribute(doc: &DocumentMut, section: &str, name: &str, key: &str) -> Option<String> {
+    if !item_exists(doc, section, name) {
+        return None;
+    }
+    let value = section_entries(doc, section)?
+        .get(name)?
+        .as_table_like()?
+        .get(key)?;
+    value.as_str().map(ToOwned::to_owned)
+}
+
+/// Make the item at `section`/`name` be `text`, as [`entry_text`] wrote it,
+/// or take it out when `text` is `None`.
+///
+/// An item that is there is replaced where it stands; one that is not is
+/// added, creating its section as [`insert`] does.
+///
+/// # Errors
+///
+/// [`CoreError::PantryHistory`] if `text` is not a TOML value, which only a
+/// hand-edited journal can give.
+pub(super) fn put(
+    doc: &mut DocumentMut,
+    section: &str,
+    name: &str,
+    text: Option<&str>,
+) -> Result<(), CoreError> {
+    let Some(text) = text else {
+        remove(doc, section, name);
+        return Ok(());
+    };
+    let value: Value = text.parse().map_err(|_| CoreError::PantryHistory {
+        message: format!("the journal's entry for '{name}' in section '{section}' is not TOML"),
+    })?;
+
+    if section == GENERAL {
+        doc.insert(name, Item::Value(value));
+        return Ok(());
+    }
+    if !doc.get(section).is_some_and(Item::is_table_like) {
+        let mut table = Table::new();
+        table.set_implicit(false);
+        doc.insert(section, Item::Table(table));
+    }
+    if let Some(table) = doc.get_mut(section).and_then(Item::as_table_like_mut) {
+        match table.get_mut(name) {
+            Some(existing) => *existing = Item::Value(value),
+            None => {
+                table.insert(name, Item::Value(value));
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Apply `attributes` to the item already at `section`/`name`.
 ///
 /// Only the attributes that are set are written. Everything else the entry
@@ -230,7 +333,8 @@ pub(super) fn apply(
     let stays_short = existing.as_str().is_some()
         && attributes.bought.is_none()
         && attributes.expire.is_none()
-        && attributes.low.is_none();
+        && attributes.low.is_none()
+        && attributes.restock.is_none();
     if stays_short {
         if let Some(quantity) = &attributes.quantity {
             *existing = toml_edit::value(quantity.as_str());
@@ -295,6 +399,9 @@ pub(super) struct Attributes {
     pub bought: Option<String>,
     pub expire: Option<String>,
     pub low: Option<String>,
+    /// The amount [`restock`](super::restock) tops the item up to. Not one of
+    /// the attributes `cooklang` models, so only an import sets it.
+    pub restock: Option<String>,
 }
 
 impl Attributes {
@@ -304,6 +411,7 @@ impl Attributes {
             && self.bought.is_none()
             && self.expire.is_none()
             && self.low.is_none()
+            && self.restock.is_none()
     }
 
     /// The value a fresh item takes.
@@ -315,7 +423,10 @@ impl Attributes {
     /// an attribute that would need a table.
     fn to_item(&self, section: &str) -> Item {
         let short = section == GENERAL
-            || (self.bought.is_none() && self.expire.is_none() && self.low.is_none());
+            || (self.bought.is_none()
+                && self.expire.is_none()
+                && self.low.is_none()
+                && self.restock.is_none());
         if short {
             return toml_edit::value(self.quantity.clone().unwrap_or_default());
         }
@@ -331,6 +442,7 @@ impl Attributes {
             ("bought", &self.bought),
             ("expire", &self.expire),
             ("low", &self.low),
+            ("restock", &self.restock),
         ] {
             if let Some(value) = value {
                 table.insert(key, value.as_str().into());
@@ -0,0 +1,258 @@
+//! What changed in the pantry, and taking it back.
+//!
+//! Every write [`add`](super::add), [`remove`](super::remove),
+//! [`update`](super::update) and [`consume`](super::consume) make is recorded
+//! in a journal beside the pantry file — `pantry.journal` next to
+//! `pantry.conf` — with when it was made, where from
+//! ([`Context::origin`]), and each item it touched as it was before and
+//! after. [`history`] reads it back and [`undo`] walks it backwards.
+//!
+//! The journal is append-only, like the shopping list's checked log: an undo
+//! is recorded as one more change, naming the change it took back, and
+//! nothing is ever rewritten or trimmed. It is JSON, one change per line; a
+//! line that cannot be read is skipped rather than failing the whole file.
+//!
+//! An item is recorded as the TOML value the file holds for it — `"1%kg"`,
+//! `{ quantity = "1%kg", low = "200%g" }` — so that undoing puts back exactly
+//! what was there, attributes this crate does not model included.
+
+use super::{edit, path_to_create, path_to_edit, read_document};
+use crate::{
+    fs_atomic::write_atomically, rfc3339, ConfigSource, Context, CoreError, Diagnostic, Outcome,
+};
+use camino::{Utf8Path, Utf8PathBuf};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write as _;
+
+/// One change to the pantry, as the journal records it.
+///
+/// `#[non_exhaustive]` because this is an output type that consumers read
+/// rather than construct.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PantryChange {
+    /// The change's number, counting up from 1 through the journal.
+    pub id: u64,
+    /// When it was made.
+    #[serde(with = "crate::rfc3339")]
+    pub at: DateTime<Utc>,
+    /// Where it came from, as [`Context::with_origin`] named it: `cli`,
+    /// `web`. `None` if nobody said.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin: Option<String>,
+    /// What it was: `add`, `remove`, `update`, `consume Easy Pancakes`, or
+    /// `undo` and the operation it took back.
+    pub operation: String,
+    /// The [`id`](Self::id) of the change this one undid, for an undo.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub undoes: Option<u64>,
+    /// Every item it touched, in the order they were written.
+    pub items: Vec<ItemChange>,
+}
+
+/// One item's part in a [`PantryChange`].
+///
+/// `#[non_exhaustive]` because this is an output type that consumers read
+/// rather than construct.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItemChange {
+    /// The section it is in.
+    pub section: String,
+    /// The item's name.
+    pub name: String,
+    /// Its value in the file before, as TOML; `None` if it was not there.
+    pub before: Option<String>,
+    /// Its value after, as TOML; `None` if the change took it out.
+    pub after: Option<String>,
+}
+
+/// How far back to go.
+///
+/// Not `#[non_exhaustive]`: consumers construct this.
+#[derive(Debug, Clone)]
+pub struct UndoRequest {
+    /// How many changes to take back, newest first.
+    pub count: usize,
+}
+
+impl Default for UndoRequest {
+    fn default() -> Self {
+        Self { count: 1 }
+    }
+}
+
+/// Every change recorded for the pantry [`Context::pantry`] names, oldest
+/// first. Empty when nothing has been recorded yet, when the context carries
+/// no pantry file, or when it carries the pantry inline.
+///
+/// # Errors
+///
+/// [`CoreError::Io`] if the journal is there but cannot be read.
+pub fn history(ctx: &Context) -> Result<Outcome<Vec<PantryChange>>, CoreError> {
+    let Some(path) = journal_for(ctx) else {
+        return Ok(Outcome::new(Vec::new()));
+    };
+    Ok(Outcome::new(read_journal(&path)?))
+}
+
+/// Take back the last [`UndoRequest::count`] changes that have not been
+/// undone already, newest first, and answer with them in that order. Fewer
+/// are taken back when the journal has fewer; an empty answer means there
+/// was nothing to undo.
+///
+/// Each item is put back to what it was before its change — added back if
+/// the change removed it, taken out if the change added it — and the file is
+/// written once, so a failure leaves it as it was. Each undo is then recorded
+/// in the journal, so [`history`] shows it and a further undo goes a change
+/// further back rather than redoing this one.
+///
+/// # Errors
+///
+/// - [`CoreError::PantryHistory`] if an item is no longer what the change
+///   left it as — someone has edited it since, by hand or otherwise. Nothing
+///   is written: putting back the old value would lose the newer one.
+/// - [`CoreError::MissingConfig`] and [`CoreError::ReadOnlyConfig`] as for
+///   [`update`](super::update), and as [`load`](super::load) for the file.
+pub fn undo(ctx: &Context, req: UndoRequest) -> Result<Outcome<Vec<PantryChange>>, CoreError> {
+    let path = path_to_edit(ctx)?;
+    let journal = journal_path(&path);
+    let undone: Vec<PantryChange> = undoable(read_journal(&journal)?)
+        .into_iter()
+        .rev()
+        .take(req.count)
+        .collect();
+    if undone.is_empty() {
+        return Ok(Outcome::new(Vec::new()));
+    }
+
+    let (mut doc, diagnostics) = read_document(&path)?;
+    let mut reverted = Vec::new();
+    for change in &undone {
+        let mut items = Vec::new();
+        for item in change.items.iter().rev() {
+            let now = edit::entry_text(&doc, &item.section, &item.name);
+            if now != item.after {
+                return Err(CoreError::PantryHistory {
+                    message: format!(
+                        "'{}' in section '{}' has changed since '{}' at {}, so it cannot be put \
+                         back as it was",
+                        item.name,
+                        item.section,
+                        change.operation,
+                        rfc3339::format(&change.at)
+                    ),
+                });
+            }
+            edit::put(&mut doc, &item.section, &item.name, item.before.as_deref())?;
+            items.push(ItemChange {
+                section: item.section.clone(),
+                name: item.name.clone(),
+                before: item.after.clone(),
+                after: item.before.clone(),
+            });
+        }
+        reverted.push((change, items));
+    }
+    write_atomically(&path, doc.to_string())?;
+
+    let mut outcome = Outcome::with_diagnostics(undone.clone(), diagnostics);
+    for (change, items) in reverted {
+        let operation = format!("undo {}", change.operation);
+        if let Some(warning) = append(ctx, &path, &operation, Some(change.id), items) {
+            outcome.diagnostics.push(warning);
+        }
+    }
+    Ok(outcome)
+}
+
+/// Record a change just written to the pantry at `path`. Nothing is recorded
+/// for a change that touched no item.
+///
+/// Called after the write, so that a write that failed is never recorded. A
+/// journal that cannot be written is answered with a warning rather than an
+/// error: the pantry has already changed, and failing would say it had not.
+pub(super) fn append(
+    ctx: &Context,
+    path: &Utf8Path,
+    operation: &str,
+    undoes: Option<u64>,
+    items: Vec<ItemChange>,
+) -> Option<Diagnostic> {
+    if items.is_empty() {
+        return None;
+    }
+    let journal = journal_path(path);
+    let written = read_journal(&journal).and_then(|changes| {
+        let change = PantryChange {
+            id: changes.last().map_or(1, |last| last.id + 1),
+            at: Utc::now(),
+            origin: ctx.origin().map(str::to_string),
+            operation: operation.to_string(),
+            undoes,
+            items,
+        };
+        let mut line = serde_json::to_string(&change).expect("a change serialises");
+        line.push('\n');
+        fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&journal)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(|source| CoreError::Io {
+                path: journal.clone(),
+                source,
+            })
+    });
+    written.err().map(|error| {
+        Diagnostic::warning(format!(
+            "the pantry was changed, but the change could not be recorded in its journal: \
+             {error}"
+        ))
+        .at_file(journal)
+    })
+}
+
+/// The changes not yet undone, oldest first.
+fn undoable(journal: Vec<PantryChange>) -> Vec<PantryChange> {
+    let mut stack: Vec<PantryChange> = Vec::new();
+    for change in journal {
+        match change.undoes {
+            Some(id) => stack.retain(|done| done.id != id),
+            None => stack.push(change),
+        }
+    }
+    stack
+}
+
+/// The journal beside the pantry file `ctx` would write, if it names one.
+fn journal_for(ctx: &Context) -> Option<Utf8PathBuf> {
+    match ctx.pantry() {
+        ConfigSource::Inline(_) => None,
+        _ => path_to_create(ctx).ok().map(|path| journal_path(&path)),
+    }
+}
+
+/// `pantry.journal` for `pantry.conf`.
+pub(super) fn journal_path(pantry: &Utf8Path) -> Utf8PathBuf {
+    pantry.with_extension("journal")
+}
+
+fn read_journal(path: &Utf8Path) -> Result<Vec<PantryChange>, CoreError> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(source) if source.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(source) => {
+            return Err(CoreError::Io {
+                path: path.to_owned(),
+                source,
+            })
+        }
+    };
+    Ok(text
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
@@ -3,16 +3,31 @@
 //! [`load`] reads the configuration [`Context::pantry`] points at — a file, or
 //! text an editor is holding — and the queries answer questions about it:
 //! everything in it ([`list`]), what is running out ([`depleted`]), what is
-//! about to go off ([`expiring`]), and which recipes it can already cook
-//! ([`recipes`]).
+//! about to go off ([`expiring`]), which recipes it can already cook
+//! ([`recipes`]), and which would use up what is about to go off
+//! ([`use_up`]). [`restock`] puts what is running out on a saved shopping
+//! list.
 //!
 //! [`plan`] is the odd one out: it answers "what should I stock?" by looking at
 //! the recipe collection alone, and never reads the pantry at all.
+//! [`plan_for`] asks the narrower question of what to buy for particular
+//! recipes, within a budget, and does start from what the pantry holds.
 //!
-//! [`add`], [`remove`] and [`update`] change the pantry and write it back.
-//! They are the only functions in this crate that write to a file the user
-//! owns, so read [`write_atomically`] and **[what a write
-//! touches](#what-a-write-touches)** before calling them.
+//! [`add`], [`remove`] and [`update`] change the pantry and write it back,
+//! [`take`] and [`top_up`] do the arithmetic on one item's quantity, and
+//! [`consume`] takes what a cooked recipe used out of it; [`batch`] makes
+//! several edits in one write. They are the
+//! only functions in this crate that write to a file the user owns, apart
+//! from [`shopping_list::commit_to_pantry`](crate::shopping_list::commit_to_pantry)
+//! which goes through them, so read [`write_atomically`] and **[what a write
+//! touches](#what-a-write-touches)** before calling them. Each of them records
+//! what it changed in the pantry's journal, which [`history`] reads and
+//! [`undo`] takes back. [`import`] reads a spreadsheet in through the same
+//! path, and [`export`] writes the pantry out as one.
+//!
+//! [`pending_notifications`] turns what is expiring and running out into
+//! events to push somewhere, once each; [`mark_notified`] records that they
+//! went.
 //!
 //! # What a write touches
 //!
@@ -54,11 +69,29 @@
 //! them as unknown fields, which is about what `cook pantry list` can show
 //! rather than about anything being lost.
 
+pub use adjust::{take, top_up, AdjustRequest, AdjustedItem};
+pub use batch::{batch, BatchEdit, BatchRequest, MoveRequest};
+pub use consume::{consume, ConsumeRequest, ConsumedItem, Consumption};
+pub use journal::{history, undo, ItemChange, PantryChange, UndoRequest};
+pub use notify::{
+    mark_notified, pending_notifications, Notifications, NotifyConfig, NotifyRequest, PantryEvent,
+    PantryEventKind,
+};
+pub use restock::{restock, Restock, RestockItem, RestockRequest};
+pub use target::{
+    plan_for, PlanBudget, PlanTarget, PlannedRecipe, TargetPlan, TargetPlanRequest, UnplannedRecipe,
+};
+pub use transfer::{
+    export, import, ExportRequest, Import, ImportMode, ImportRequest, RejectedRow, TransferFormat,
+};
+pub use use_up::{use_up, RescuedItem, UseUpMatch, UseUpRequest};
+
 use crate::{
     diagnostic::parse_failure,
     find::{build_tree, listed_ingredients, parse_or_skip, walk},
     fs_atomic::write_atomically,
     parser::collect_diagnostics,
+    substitutes::{Substitutes, Substitution},
     ConfigSource, Context, CoreError, Diagnostic, Outcome,
 };
 use camino::{Utf8Path, Utf8PathBuf};
@@ -234,7 +267,7 @@ pub fn load(ctx: &Context) -> Result<Outcome<PantryContents>, CoreError> {
     tracing::trace!("loading pantry from {:?}", path);
 
     let parsed = cooklang::pantry::parse_lenient(&text);
-    let diagnostics = collect_diagnostics(parsed.report(), path);
+    let diagnostics = pantry_diagnostics(parsed.report(), &text, path);
 
     match parsed.output() {
         Some(conf) => Ok(Outcome::with_diagnostics(
@@ -502,12 +535,17 @@ pub struct PartialMatch {
 #[non_exhaustive]
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct RecipeMatches {
-    /// Recipes every one of whose ingredients is in stock, by title, in
-    /// alphabetical order.
+    /// Recipes every one of whose ingredients is in stock, or stood in for,
+    /// by title, in alphabetical order.
     pub full: Vec<String>,
     /// Recipes that are only partly covered, at or above
     /// [`RecipesRequest::threshold`], in alphabetical order.
     pub partial: Vec<PartialMatch>,
+    /// The substitutions each recipe in [`full`](Self::full) or
+    /// [`partial`](Self::partial) was counted with, by title, in alphabetical
+    /// order of the ingredients they stood in for. A recipe that needed none
+    /// is not here.
+    pub substitutions: BTreeMap<String, Vec<Substitution>>,
 }
 
 /// Work out which recipes under [`Context::base_path`] the pantry can cook.
@@ -519,6 +557,11 @@ pub struct RecipeMatches {
 /// no ingredients at all matches nothing. See [`listed_ingredients`] for what
 /// else is left out.
 ///
+/// An ingredient that is not in stock still counts when
+/// [`Context::substitutes`] names a substitute every part of which is, and
+/// the substitution made is recorded in [`RecipeMatches::substitutions`]. See
+/// [`crate::substitutes`].
+///
 /// Recipes are found by walking the collection, `.menu` files included. A
 /// recipe that cannot be read or parsed is left out, with a warning in
 /// [`Outcome::diagnostics`] naming it — it is not counted as a match or a
@@ -528,6 +571,7 @@ pub struct RecipeMatches {
 /// # Errors
 ///
 /// - As [`load`], since this needs the pantry.
+/// - As [`Substitutes::load`], for `substitutes.conf`.
 /// - [`CoreError::Search`] if the collection cannot be walked, and
 ///   [`CoreError::Io`] if a file in it cannot be listed — as
 ///   `doctor::validate`.
@@ -539,6 +583,9 @@ pub fn recipes(ctx: &Context, req: RecipesRequest) -> Result<Outcome<RecipeMatch
         .items()
         .map(|item| item.name.to_lowercase())
         .collect();
+    let substitutes = Substitutes::load(ctx.substitutes())?;
+    diagnostics.extend(substitutes.diagnostics);
+    let substitutes = substitutes.value;
 
     let tree = build_tree(ctx.base_path())?;
     let mut matches = RecipeMatches::default();
@@ -557,23 +604,35 @@ pub fn recipes(ctx: &Context, req: RecipesRequest) -> Result<Outcome<RecipeMatch
             continue;
         }
 
-        let available = wanted.iter().filter(|name| stocked.contains(*name)).count();
+        let mut missing = Vec::new();
+        let mut substituted = Vec::new();
+        // From a `BTreeSet`, so `missing` comes out in order.
+        for ingredient in &wanted {
+            if stocked.contains(ingredient) {
+                continue;
+            }
+            match substitutes.find(ingredient, |part| stocked.contains(&part.to_lowercase())) {
+                Some(substitution) => substituted.push(substitution),
+                None => missing.push(ingredient.clone()),
+            }
+        }
+        let available = wanted.len() - missing.len();
         let percentage = available * 100 / wanted.len();
         let name = recipe_name(entry);
 
-        if available == wanted.len() {
-            matches.full.push(name);
+        if missing.is_empty() {
+            matches.full.push(name.clone());
         } else if percentage >= usize::from(req.threshold) {
             matches.partial.push(PartialMatch {
-                name,
+                name: name.clone(),
                 percentage,
-                // From a `BTreeSet`, so already in order.
-                missing: wanted
-                    .iter()
-                    .filter(|name| !stocked.contains(*name))
-                    .cloned()
-                    .collect(),
+                missing,
             });
+        } else {
+            continue;
+        }
+        if !substituted.is_empty() {
+            matches.substitutions.insert(name, substituted);
         }
     }
 
@@ -748,6 +807,12 @@ fn most_wanted(missing: &[BTreeSet<String>]) -> Option<String> {
 // add, remove, update
 // ---------------------------------------------------------------------------
 
+/// Where, under the base directory, the ingredient datastore conventionally
+/// lives. Nothing here reads it unless told to — [`AddRequest::datastore`] is
+/// a path — but the command line and the server both default to it when it
+/// exists, and should agree on the name.
+pub const DATASTORE_DIR: &str = "db";
+
 /// An item to add to the pantry.
 ///
 /// Not `#[non_exhaustive]`: consumers construct this. `..Default::default()`
@@ -769,6 +834,9 @@ pub struct AddRequest {
     pub expire: Option<String>,
     /// The quantity at or below which it counts as low.
     pub low: Option<String>,
+    /// The ingredient datastore to look up how long the item keeps in, when
+    /// [`expire`](Self::expire) is not given. See [`add`].
+    pub datastore: Option<Utf8PathBuf>,
 }
 
 /// Which item to take out of the pantry.
@@ -816,6 +884,16 @@ pub struct UpdateRequest {
 /// Only the entry asked for is touched; see [what a write
 /// touches](self#what-a-write-touches).
 ///
+/// # Expiry from the datastore
+///
+/// With no [`AddRequest::expire`] but an [`AddRequest::datastore`], the
+/// expiry is worked out from the `shelf life`, `fridge life` or `freezer life`
+/// the item's `meta.yml` gives — which one depending on the section — counted
+/// from the bought date, or from today if there is none or it cannot be read
+/// as a date. Nothing is filled in when the datastore does not say; a
+/// `meta.yml` that cannot be read is a warning, not an error. Items added to
+/// `general` are left alone, since it cannot hold an expiry.
+///
 /// # Errors
 ///
 /// - [`CoreError::ReadOnlyConfig`] if the context carries the pantry inline.
@@ -827,30 +905,78 @@ pub struct UpdateRequest {
 /// - [`CoreError::Config`] if the existing file cannot be parsed at all, and
 ///   [`CoreError::Io`] if it cannot be read or the new one cannot be written.
 pub fn add(ctx: &Context, req: AddRequest) -> Result<Outcome<PantryContents>, CoreError> {
+    let mut diagnostics = Vec::new();
+    let attributes = add_attributes(&req, &mut diagnostics)?;
+
+    let path = path_to_create(ctx)?;
+    let (mut doc, read_diagnostics) = read_document_or_empty(&path)?;
+    diagnostics.extend(read_diagnostics);
+
+    add_to(
+        &mut doc,
+        &path,
+        &req.section,
+        &req.name,
+        &attributes,
+        &mut diagnostics,
+    )?;
+
+    let change = ItemChange {
+        after: edit::entry_text(&doc, &req.section, &req.name),
+        section: req.section,
+        name: req.name,
+        before: None,
+    };
+    save(ctx, &path, &doc, diagnostics, "add", vec![change])
+}
+
+/// What [`add`] writes for `req`, with the expiry filled in from the
+/// datastore, checked against the section it goes in. Needs no pantry, so it
+/// is checked before one is looked for.
+fn add_attributes(
+    req: &AddRequest,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<edit::Attributes, CoreError> {
+    let expire = match (&req.expire, &req.datastore) {
+        (None, Some(datastore)) if req.section != edit::GENERAL => {
+            let from = req
+                .bought
+                .as_deref()
+                .and_then(parse_date)
+                .unwrap_or_else(|| Local::now().date_naive());
+            shelf_life::expiry(datastore, &req.name, &req.section, from, diagnostics)
+        }
+        (expire, _) => expire.clone(),
+    };
     let attributes = edit::Attributes {
-        quantity: req.quantity,
-        bought: req.bought,
-        expire: req.expire,
-        low: req.low,
+        quantity: req.quantity.clone(),
+        bought: req.bought.clone(),
+        expire,
+        low: req.low.clone(),
+        restock: None,
     };
     edit::check_general_attributes(&req.section, &req.name, &attributes)?;
+    Ok(attributes)
+}
 
-    let path = path_to_create(ctx)?;
-    let (mut doc, mut diagnostics) = read_document_or_empty(&path)?;
-
-    if edit::item_exists(&doc, &req.section, &req.name) {
+/// The document half of [`add`]: refuse an item the section already holds,
+/// and insert it otherwise.
+fn add_to(
+    doc: &mut toml_edit::DocumentMut,
+    path: &Utf8Path,
+    section: &str,
+    name: &str,
+    attributes: &edit::Attributes,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<(), CoreError> {
+    if edit::item_exists(doc, section, name) {
         return Err(CoreError::PantryEdit {
-            message: format!(
-                "item '{}' already exists in section '{}'",
-                req.name, req.section
-            ),
+            message: format!("item '{name}' already exists in section '{section}'"),
         });
     }
-
-    diagnostics.extend(normalise_array_section(&mut doc, &req.section, &path));
-    edit::insert(&mut doc, &req.section, &req.name, &attributes);
-
-    save(&path, &doc, diagnostics)
+    diagnostics.extend(normalise_array_section(doc, section, path));
+    edit::insert(doc, section, name, attributes);
+    Ok(())
 }
 
 /// Take an item out of the pantry and write it back.
@@ -878,17 +1004,15 @@ pub fn remove(ctx: &Context, req: RemoveRequest) -> Result<Outcome<PantryContent
     let path = path_to_edit(ctx)?;
     let (mut doc, mut diagnostics) = read_document(&path)?;
 
-    diagnostics.extend(normalise_array_section(&mut doc, &req.section, &path));
-    if !edit::section_exists(&doc, &req.section) {
-        return Err(section_not_found(&req.section));
-    }
-    if !edit::item_exists(&doc, &req.section, &req.name) {
-        return Err(item_not_found(&req.name, &req.section));
-    }
-
-    edit::remove(&mut doc, &req.section, &req.name);
+    let before = remove_from(&mut doc, &path, &req.section, &req.name, &mut diagnostics)?;
 
-    save(&path, &doc, diagnostics)
+    let change = ItemChange {
+        section: req.section,
+        name: req.name,
+        before,
+        after: None,
+    };
+    save(ctx, &path, &doc, diagnostics, "remove", vec![change])
 }
 
 /// Change an item already in the pantry and write it back.
@@ -913,15 +1037,41 @@ pub fn remove(ctx: &Context, req: RemoveRequest) -> Result<Outcome<PantryContent
 /// - As [`load`] otherwise, plus [`CoreError::Io`] if the file cannot be
 ///   written.
 pub fn update(ctx: &Context, req: UpdateRequest) -> Result<Outcome<PantryContents>, CoreError> {
-    let attributes = edit::Attributes {
-        quantity: req.quantity,
-        bought: req.bought,
-        expire: req.expire,
-        low: req.low,
-    };
-
     // Checked before anything is read: an update of nothing is a mistake
     // whether or not there is a pantry to make it in.
+    let attributes = update_attributes(&req)?;
+
+    let path = path_to_edit(ctx)?;
+    let (mut doc, mut diagnostics) = read_document(&path)?;
+
+    let before = update_in(
+        &mut doc,
+        &path,
+        &req.section,
+        &req.name,
+        &attributes,
+        &mut diagnostics,
+    )?;
+
+    let change = ItemChange {
+        after: edit::entry_text(&doc, &req.section, &req.name),
+        section: req.section,
+        name: req.name,
+        before,
+    };
+    save(ctx, &path, &doc, diagnostics, "update", vec![change])
+}
+
+/// What [`update`] writes for `req`, refused when it sets nothing or sets
+/// what its section has no room for.
+fn update_attributes(req: &UpdateRequest) -> Result<edit::Attributes, CoreError> {
+    let attributes = edit::Attributes {
+        quantity: req.quantity.clone(),
+        bought: req.bought.clone(),
+        expire: req.expire.clone(),
+        low: req.low.clone(),
+        restock: None,
+    };
     if attributes.is_empty() {
         return Err(CoreError::PantryEdit {
             message: format!(
@@ -930,23 +1080,56 @@ pub fn update(ctx: &Context, req: UpdateRequest) -> Result<Outcome<PantryContent
             ),
         });
     }
-
     edit::check_general_attributes(&req.section, &req.name, &attributes)?;
+    Ok(attributes)
+}
 
-    let path = path_to_edit(ctx)?;
-    let (mut doc, mut diagnostics) = read_document(&path)?;
+/// The document half of [`update`]. Returns the entry as it was, as
+/// [`edit::entry_text`] writes it.
+fn update_in(
+    doc: &mut toml_edit::DocumentMut,
+    path: &Utf8Path,
+    section: &str,
+    name: &str,
+    attributes: &edit::Attributes,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<Option<String>, CoreError> {
+    let before = check_item(doc, path, section, name, diagnostics)?;
+    edit::apply(doc, section, name, attributes)?;
+    Ok(before)
+}
+
+/// The document half of [`remove`]. Returns the entry as it was.
+fn remove_from(
+    doc: &mut toml_edit::DocumentMut,
+    path: &Utf8Path,
+    section: &str,
+    name: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<Option<String>, CoreError> {
+    let before = check_item(doc, path, section, name, diagnostics)?;
+    edit::remove(doc, section, name);
+    Ok(before)
+}
 
-    diagnostics.extend(normalise_array_section(&mut doc, &req.section, &path));
-    if !edit::section_exists(&doc, &req.section) {
-        return Err(section_not_found(&req.section));
+/// Refuse unless `doc` has `section`, and `name` in it — after turning a
+/// section written as an array into a table, which is the one shape the
+/// edits know how to change. Returns the entry as it stands.
+fn check_item(
+    doc: &mut toml_edit::DocumentMut,
+    path: &Utf8Path,
+    section: &str,
+    name: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<Option<String>, CoreError> {
+    diagnostics.extend(normalise_array_section(doc, section, path));
+    if !edit::section_exists(doc, section) {
+        return Err(section_not_found(section));
     }
-    if !edit::item_exists(&doc, &req.section, &req.name) {
-        return Err(item_not_found(&req.name, &req.section));
+    if !edit::item_exists(doc, section, name) {
+        return Err(item_not_found(name, section));
     }
-
-    edit::apply(&mut doc, &req.section, &req.name, &attributes)?;
-
-    save(&path, &doc, diagnostics)
+    Ok(edit::entry_text(doc, section, name))
 }
 
 fn section_not_found(section: &str) -> CoreError {
@@ -998,7 +1181,7 @@ fn parse_conf(
     text: &str,
 ) -> Result<(cooklang::pantry::PantryConf, Vec<Diagnostic>), CoreError> {
     let parsed = cooklang::pantry::parse_lenient(text);
-    let diagnostics = collect_diagnostics(parsed.report(), Some(path));
+    let diagnostics = pantry_diagnostics(parsed.report(), text, Some(path));
     match parsed.output() {
         Some(conf) => Ok((conf.clone(), diagnostics)),
         None => Err(CoreError::Config {
@@ -1008,6 +1191,37 @@ fn parse_conf(
     }
 }
 
+/// Attributes a pantry item can carry that this crate reads for itself, beyond
+/// the four `cooklang` models: `restock`, the amount [`restock`] tops an item
+/// up to.
+const OWN_ATTRIBUTES: [&str; 1] = ["restock"];
+
+/// Whether `message` is `cooklang` calling one of this crate's own item
+/// attributes unknown.
+///
+/// Its lenient parse warns about every key it does not model, and cannot be
+/// told about more. Left alone, every command that reads the pantry would warn
+/// about an attribute `cook pantry restock` is documented to read, so the
+/// warning is dropped wherever pantry diagnostics are collected. Matched on
+/// the message because that is all the report carries.
+pub fn is_own_attribute_warning(message: &str) -> bool {
+    OWN_ATTRIBUTES
+        .iter()
+        .any(|key| message.contains(&format!("Unknown field '{key}' ")))
+}
+
+/// [`collect_diagnostics`] for a pantry file, without the warnings
+/// [`is_own_attribute_warning`] recognises.
+pub(crate) fn pantry_diagnostics(
+    report: &cooklang::error::SourceReport,
+    text: &str,
+    path: Option<&Utf8Path>,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = collect_diagnostics(report, text, path);
+    diagnostics.retain(|diagnostic| !is_own_attribute_warning(&diagnostic.message));
+    diagnostics
+}
+
 /// Convert a section written as an array of names into the equivalent table,
 /// and say so.
 ///
@@ -1076,18 +1290,23 @@ fn parse_document(
     Ok((edit::parse(text, path)?, diagnostics))
 }
 
-/// Write the edited document over `path` and read back what it now says.
+/// Write the edited document over `path`, record `operation` in the
+/// [journal](history), and read back what the file now says.
 ///
 /// Reading back rather than deriving the result from the edit is what keeps the
 /// returned [`PantryContents`] honest: it is the file as the next command will
 /// see it, normalisation and all.
 fn save(
+    ctx: &Context,
     path: &Utf8Path,
     doc: &toml_edit::DocumentMut,
-    diagnostics: Vec<Diagnostic>,
+    mut diagnostics: Vec<Diagnostic>,
+    operation: &str,
+    items: Vec<ItemChange>,
 ) -> Result<Outcome<PantryContents>, CoreError> {
     let text = doc.to_string();
     write_atomically(path, &text)?;
+    diagnostics.extend(journal::append(ctx, path, operation, None, items));
 
     // The re-read is for the value, not for its diagnostics: those are the same
     // ones already collected from reading the file, and reporting them twice
@@ -1123,6 +1342,29 @@ static QUANTITY: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(r"^(\d+(?:\.\d+)?)\s*%?\s*(.*)$").expect("the quantity pattern is valid")
 });
 
+/// A quantity as a number and its unit as written — `500%g` is `(500.0, "g")`
+/// — or `None` if it is not a quantity at all. A bare count has an empty unit.
+pub(crate) fn split_quantity(quantity: &str) -> Option<(f64, String)> {
+    let captures = QUANTITY.captures(quantity.trim())?;
+    let value = captures[1].parse::<f64>().ok()?;
+    Some((value, captures[2].trim().to_string()))
+}
+
+/// An amount written the way pantry files write it: `1200%g`, or a bare `3`.
+///
+/// Rounded to three decimal places, because a converted amount carries float
+/// noise and `0.30000000000000004%kg` is not something anyone wrote.
+pub(crate) fn write_quantity(value: f64, unit: &str) -> String {
+    let value = (value * 1000.0).round() / 1000.0;
+    // `-0` is what rounding a hair below zero gives.
+    let value = if value == 0.0 { 0.0 } else { value };
+    if unit.is_empty() {
+        value.to_string()
+    } else {
+        format!("{value}%{unit}")
+    }
+}
+
 /// The unit of a quantity, lowercased, or `None` if it is not a quantity at
 /// all. A bare count has an empty unit.
 fn unit_of(quantity: &str) -> Option<String> {
@@ -1164,13 +1406,23 @@ const DATE_FORMATS: [&str; 6] = [
 ];
 
 /// Read a date in any of [`DATE_FORMATS`], or `None`.
-fn parse_date(date: &str) -> Option<NaiveDate> {
+pub(crate) fn parse_date(date: &str) -> Option<NaiveDate> {
     DATE_FORMATS
         .iter()
         .find_map(|format| NaiveDate::parse_from_str(date, format).ok())
 }
 
+mod adjust;
+mod batch;
+mod consume;
 mod edit;
+mod journal;
+mod notify;
+mod restock;
+mod shelf_life;
+mod target;
+mod transfer;
+mod use_up;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,520 @@
+//! Telling something outside when the pantry needs attention.
+//!
+//! [`expiring`](super::expiring) and [`depleted`](super::depleted) answer
+//! when asked; a phone, Home Assistant or a local script would have to keep
+//! asking. [`pending_notifications`] turns the same two questions into events
+//! — an item has come within the expiry window, an item is running out —
+//! and remembers which it has already reported, so that each is reported
+//! once rather than on every run.
+//!
+//! Delivering them is the caller's: `cook pantry notify` runs the command and
+//! posts to the webhook [`NotifyConfig`] names, then calls [`mark_notified`].
+//! Until it does, the same events stay pending, so a webhook that was down
+//! is tried again next time rather than missed.
+//!
+//! # What counts as the same event
+//!
+//! What has been reported is kept beside the pantry file — `pantry.notified`
+//! next to `pantry.conf` — as every event that was standing at the last
+//! [`mark_notified`]. An event is new when it was not standing then. So an
+//! item that is restocked and later runs out again is reported again, and
+//! so is one whose expiry date is changed and comes round again; one that
+//! stays expired is reported when it comes within the window and not every
+//! day after.
+//!
+//! # `notify.toml`
+//!
+//! ```toml
+//! # How many days ahead an item counts as expiring (default 7).
+//! days = 3
+//! # Which events to report (default both).
+//! events = ["expiring", "depleted"]
+//! # Run through the shell, with the events as JSON on its standard input.
+//! command = "notify-send Pantry \"$COOK_NOTIFY_MESSAGE\""
+//! # POST the events as JSON here.
+//! webhook = "https://ntfy.sh/my-pantry"
+//! ```
+//!
+//! Both `command` and `webhook` may be given; each batch of events goes to
+//! both.
+
+use super::{expiring_on, is_depleted, load, ExpiringRequest, PantryContents};
+use crate::{
+    diagnostic::parse_failure, fs_atomic::write_atomically, ConfigSource, Context, CoreError,
+    Diagnostic, Outcome,
+};
+use camino::{Utf8Path, Utf8PathBuf};
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use toml_edit::DocumentMut;
+
+/// The settings in `notify.toml`. See the [module documentation](self).
+///
+/// `#[non_exhaustive]` because it is read from a file rather than built;
+/// [`NotifyConfig::default`] is what an absent file means.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotifyConfig {
+    /// How many days ahead an item counts as expiring.
+    pub days: u32,
+    /// Whether items coming within [`days`](Self::days) of expiring are
+    /// reported.
+    pub expiring: bool,
+    /// Whether items running out are reported, as
+    /// [`depleted`](super::depleted) counts them.
+    pub depleted: bool,
+    /// A shell command to run with each batch of events.
+    pub command: Option<String>,
+    /// A URL to POST each batch of events to.
+    pub webhook: Option<String>,
+}
+
+impl Default for NotifyConfig {
+    /// A week ahead, as [`ExpiringRequest`]'s default, both kinds of event,
+    /// and nowhere to send them.
+    fn default() -> Self {
+        Self {
+            days: 7,
+            expiring: true,
+            depleted: true,
+            command: None,
+            webhook: None,
+        }
+    }
+}
+
+impl NotifyConfig {
+    /// Load `notify.toml` from `source`. An unset source is the default.
+    ///
+    /// # Errors
+    ///
+    /// - [`CoreError::Io`] if the file cannot be read.
+    /// - [`CoreError::Config`] if it is not TOML at all.
+    pub fn load(source: &ConfigSource) -> Result<Outcome<Self>, CoreError> {
+        match source.read()? {
+            Some(text) => Self::parse(&text, source.path()),
+            None => Ok(Outcome::new(Self::default())),
+        }
+    }
+
+    /// Parse `notify.toml` text. `path` is where it came from, if anywhere,
+    /// and is what the warnings and the error are attributed to.
+    ///
+    /// A key this does not know, or a value of the wrong kind, is a warning,
+    /// and the default is kept in its place.
+    ///
+    /// # Errors
+    ///
+    /// [`CoreError::Config`] if `text` is not TOML.
+    pub fn parse(text: &str, path: Option<&Utf8Path>) -> Result<Outcome<Self>, CoreError> {
+        let doc = text.parse::<DocumentMut>().map_err(|e| CoreError::Config {
+            path: path.map(ToOwned::to_owned),
+            message: parse_failure(&[Diagnostic::error(e.to_string())], "notify"),
+        })?;
+
+        let mut warnings = Vec::new();
+        let mut warn = |message: String| {
+            let diagnostic = Diagnostic::warning(message);
+            warnings.push(match path {
+                Some(path) => diagnostic.at_file(path),
+                None => diagnostic,
+            });
+        };
+
+        let mut config = Self::default();
+        for (key, item) in doc.iter() {
+            match key {
+                "days" => match item.as_integer().and_then(|days| u32::try_from(days).ok()) {
+                    Some(days) => config.days = days,
+                    None => {
+                        warn("'days' in notify.toml must be a whole number of days".to_string())
+                    }
+                },
+                "events" => {
+                    let events: Option<Vec<&str>> = item
+                        .as_array()
+                        .and_then(|events| events.iter().map(|event| event.as_str()).collect());
+                    match events {
+                        Some(events) => {
+                            config.expiring = false;
+                            config.depleted = false;
+                            for event in events {
+                                match event {
+                                    "expiring" => config.expiring = true,
+                                    "depleted" => config.depleted = true,
+                                    other => warn(format!(
+                                        "unknown event '{other}' in notify.toml: it can be \
+                                         'expiring' or 'depleted'"
+                                    )),
+                                }
+                            }
+                        }
+                        None => warn(
+                            "'events' in notify.toml must be a list, like \
+                             [\"expiring\", \"depleted\"]"
+                                .to_string(),
+                        ),
+                    }
+                }
+                "command" | "webhook" => match item.as_str().map(str::trim) {
+                    Some(value) if !value.is_empty() => {
+                        let value = Some(value.to_string());
+                        if key == "command" {
+                            config.command = value;
+                        } else {
+                            config.webhook = value;
+                        }
+                    }
+                    _ => warn(format!("'{key}' in notify.toml must be a string")),
+                },
+                other => warn(format!("unknown notify.toml key '{other}'")),
+            }
+        }
+        Ok(Outcome::with_diagnostics(config, warnings))
+    }
+
+    /// The request these settings make.
+    pub fn request(&self) -> NotifyRequest {
+        NotifyRequest {
+            days: self.days,
+            expiring: self.expiring,
+            depleted: self.depleted,
+        }
+    }
+}
+
+/// Which events to look for.
+///
+/// Not `#[non_exhaustive]`: consumers construct this. `..Default::default()`
+/// keeps a literal working if it grows a field.
+#[derive(Debug, Clone)]
+pub struct NotifyRequest {
+    /// As [`NotifyConfig::days`].
+    pub days: u32,
+    /// As [`NotifyConfig::expiring`].
+    pub expiring: bool,
+    /// As [`NotifyConfig::depleted`].
+    pub depleted: bool,
+}
+
+impl Default for NotifyRequest {
+    /// As [`NotifyConfig::default`].
+    fn default() -> Self {
+        NotifyConfig::default().request()
+    }
+}
+
+/// What an event is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PantryEventKind {
+    /// The item has come within the expiry window, or has expired.
+    Expiring,
+    /// The item is running out.
+    Depleted,
+}
+
+/// Something about one item worth telling someone.
+///
+/// `#[non_exhaustive]` because this is an output type consumers read rather
+/// than construct.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PantryEvent {
+    /// What happened.
+    pub event: PantryEventKind,
+    /// The item's section.
+    pub section: String,
+    /// The item's name, as the pantry writes it.
+    pub name: String,
+    /// How much is left, as the pantry writes it.
+    pub quantity: Option<String>,
+    /// For an expiring item, its expiry date in ISO 8601, as
+    /// [`ExpiringItem::expire_date`](super::ExpiringItem::expire_date).
+    pub expire_date: Option<String>,
+    /// For an expiring item, days from today until it expires; negative once
+    /// it has.
+    pub days_until_expiry: Option<i64>,
+}
+
+impl PantryEvent {
+    /// One line about it: `milk (dairy) expires tomorrow`,
+    /// `honey (pantry) is running out`.
+    pub fn describe(&self) -> String {
+        let what = match (self.event, self.days_until_expiry) {
+            (PantryEventKind::Depleted, _) => "is running out".to_string(),
+            (PantryEventKind::Expiring, Some(days)) if days < -1 => {
+                format!("expired {} days ago", -days)
+            }
+            (PantryEventKind::Expiring, Some(-1)) => "expired yesterday".to_string(),
+            (PantryEventKind::Expiring, Some(0)) => "expires today".to_string(),
+            (PantryEventKind::Expiring, Some(1)) => "expires tomorrow".to_string(),
+            (PantryEventKind::Expiring, Some(days)) => format!("expires in {days} days"),
+            (PantryEventKind::Expiring, None) => "is expiring".to_string(),
+        };
+        format!("{} ({}) {what}", self.name, self.section)
+    }
+
+    fn key(&self) -> Sent {
+        Sent {
+            event: self.event,
+            section: self.section.clone(),
+            name: self.name.clone(),
+            expire_date: self.expire_date.clone(),
+        }
+    }
+}
+
+/// An event as `pantry.notified` remembers it: enough to tell it from
+/// another, and no more.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+struct Sent {
+    event: PantryEventKind,
+    section: String,
+    name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    expire_date: Option<String>,
+}
+
+/// The events not yet reported, and what to remember once they have been.
+///
+/// `#[non_exhaustive]` because this is an output type consumers read rather
+/// than construct.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notifications {
+    /// The new events: expiring items soonest first, then items running out
+    /// in file order.
+    pub events: Vec<PantryEvent>,
+    /// Where what has been reported is kept. `None` for a pantry supplied
+    /// inline, which has nowhere to keep it — so every event is new.
+    state: Option<Utf8PathBuf>,
+    /// Every event standing now, new or not.
+    standing: BTreeSet<Sent>,
+    /// What the state file held, to tell whether it needs writing.
+    remembered: BTreeSet<Sent>,
+}
+
+impl Notifications {
+    /// Every event, [described](PantryEvent::describe), one per line.
+    pub fn message(&self) -> String {
+        self.events
+            .iter()
+            .map(PantryEvent::describe)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// The events in the pantry [`Context::pantry`] names that have not been
+/// reported yet. Nothing is written; see [`mark_notified`].
+///
+/// A `pantry.notified` that cannot be read as JSON is a warning, and every
+/// event standing is new again — reporting twice is better than not at all.
+///
+/// # Errors
+///
+/// - As [`load`].
+/// - [`CoreError::Io`] if `pantry.notified` is there but cannot be read.
+pub fn pending_notifications(
+    ctx: &Context,
+    req: NotifyRequest,
+) -> Result<Outcome<Notifications>, CoreError> {
+    let loaded = load(ctx)?;
+    let mut diagnostics = loaded.diagnostics;
+    let state = match ctx.pantry() {
+        ConfigSource::Path(path) => Some(state_path(path)),
+        _ => None,
+    };
+    let remembered = match &state {
+        Some(path) => read_state(path, &mut diagnostics)?,
+        None => BTreeSet::new(),
+    };
+
+    let standing_events = events_on(&loaded.value, &req, Local::now().date_naive());
+    let standing: BTreeSet<Sent> = standing_events.iter().map(PantryEvent::key).collect();
+    let events = standing_events
+        .into_iter()
+        .filter(|event| !remembered.contains(&event.key()))
+        .collect();
+
+    Ok(Outcome::with_diagnostics(
+        Notifications {
+            events,
+            state,
+            standing,
+            remembered,
+        },
+        diagnostics,
+    ))
+}
+
+/// Remember that `notifications` have been reported, so that
+/// [`pending_notifications`] does not return them again.
+///
+/// Call it even when there were no new events: the events that have stopped
+/// standing are forgotten too, which is what lets them be reported again
+/// when they come back. Writes nothing when nothing has changed, or for a
+/// pantry supplied inline.
+///
+/// # Errors
+///
+/// [`CoreError::Io`] if `pantry.notified` cannot be written.
+pub fn mark_notified(notifications: &Notifications) -> Result<(), CoreError> {
+    let Some(path) = &notifications.state else {
+        return Ok(());
+    };
+    if notifications.standing == notifications.remembered {
+        return Ok(());
+    }
+    let json = serde_json::to_string_pretty(&notifications.standing)
+        .expect("events always serialise to JSON");
+    write_atomically(path, format!("{json}\n"))
+}
+
+/// `pantry.notified` for `pantry.conf`.
+fn state_path(pantry: &Utf8Path) -> Utf8PathBuf {
+    pantry.with_extension("notified")
+}
+
+fn read_state(
+    path: &Utf8Path,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<BTreeSet<Sent>, CoreError> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(source) if source.kind() == std::io::ErrorKind::NotFound => return Ok(BTreeSet::new()),
+        Err(source) => {
+            return Err(CoreError::Io {
+                path: path.to_owned(),
+                source,
+            })
+        }
+    };
+    match serde_json::from_str(&text) {
+        Ok(sent) => Ok(sent),
+        Err(e) => {
+            diagnostics.push(
+                Diagnostic::warning(format!(
+                    "could not read what has been notified already ({e}), so everything \
+                     standing is notified again"
+                ))
+                .at_file(path),
+            );
+            Ok(BTreeSet::new())
+        }
+    }
+}
+
+/// Every event standing in `contents` on `today`, new or not.
+fn events_on(contents: &PantryContents, req: &NotifyRequest, today: NaiveDate) -> Vec<PantryEvent> {
+    let mut events = Vec::new();
+    if req.expiring {
+        let expiring = ExpiringRequest {
+            days: req.days,
+            include_unknown: false,
+        };
+        events.extend(
+            expiring_on(contents, &expiring, today)
+                .into_iter()
+                .map(|expiring| PantryEvent {
+                    event: PantryEventKind::Expiring,
+                    section: expiring.item.section,
+                    name: expiring.item.name,
+                    quantity: expiring.item.quantity,
+                    expire_date: expiring.expire_date,
+                    days_until_expiry: expiring.days_until_expiry,
+                }),
+        );
+    }
+    if req.depleted {
+        events.extend(
+            contents
+                .items()
+                .filter(|item| is_depleted(item, false))
+                .map(|item| PantryEvent {
+                    event: PantryEventKind::Depleted,
+                    section: item.section.clone(),
+                    name: item.name.clone(),
+                    quantity: item.quantity.clone(),
+                    expire_date: None,
+                    days_until_expiry: None,
+                }),
+        );
+    }
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_are_worded_by_how_far_off_they_are() {
+        let event = |days| PantryEvent {
+            event: PantryEventKind::Expiring,
+            section: "dairy".to_string(),
+            name: "milk".to_string(),
+            quantity: None,
+            expire_date: None,
+            days_until_expiry: days,
+        };
+        assert_eq!(
+            event(Some(-3)).describe(),
+            "milk (dairy) expired 3 days ago"
+        );
+        assert_eq!(event(Some(-1)).describe(), "milk (dairy) expired yesterday");
+        assert_eq!(event(Some(0)).describe(), "milk (dairy) expires today");
+        assert_eq!(event(Some(1)).describe(), "milk (dairy) expires tomorrow");
+        assert_eq!(event(Some(5)).describe(), "milk (dairy) expires in 5 days");
+        let depleted = PantryEvent {
+            event: PantryEventKind::Depleted,
+            ..event(None)
+        };
+        assert_eq!(depleted.describe(), "milk (dairy) is running out");
+    }
+
+    #[test]
+    fn the_window_and_the_kinds_of_event_are_the_request_s() {
+        let contents = load(
+            &Context::new("/nowhere".into()).with_pantry(ConfigSource::Inline(
+                "[dairy]\nmilk = { quantity = \"1%l\", expire = \"2025-06-05\" }\n\
+             honey = { quantity = \"0\" }\n"
+                    .to_string(),
+            )),
+        )
+        .unwrap()
+        .value;
+        let today = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+        let kinds = |req: NotifyRequest| {
+            events_on(&contents, &req, today)
+                .into_iter()
+                .map(|event| (event.event, event.name))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            kinds(NotifyRequest::default()),
+            [
+                (PantryEventKind::Expiring, "milk".to_string()),
+                (PantryEventKind::Depleted, "honey".to_string()),
+            ]
+        );
+        assert_eq!(
+            kinds(NotifyRequest {
+                days: 3,
+                ..Default::default()
+            }),
+            [(PantryEventKind::Depleted, "honey".to_string())],
+            "the milk is four days off"
+        );
+        assert_eq!(
+            kinds(NotifyRequest {
+                depleted: false,
+                ..Default::default()
+            }),
+            [(PantryEventKind::Expiring, "milk".to_string())]
+        );
+    }
+}
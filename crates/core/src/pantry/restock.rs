@@ -0,0 +1,231 @@
+//! Putting what is running out on the shopping list.
+//!
+//! [`depleted`](super::depleted) already knows what is low. [`restock`] closes
+//! the loop: every item it reports goes on a saved shopping list as an
+//! [item added by hand](crate::shopping_list::ManualItem), so the next trip
+//! buys it without anyone having to remember.
+//!
+//! # How much
+//!
+//! An item can say how much it should be topped up to with a `restock`
+//! attribute beside its `low` threshold:
+//!
+//! ```toml
+//! [dairy]
+//! milk = { quantity = "300%ml", low = "500%ml", restock = "2%l" }
+//! ```
+//!
+//! The list then asks for the difference — `1.7%l` here — converted into the
+//! unit `restock` is written in, as [`crate::units`] converts. Without a
+//! `restock`, or with one that cannot be compared with the stock (a different
+//! kind of unit, or text that is not a quantity), the item goes on the list
+//! with no amount, and the second case is warned about.
+//!
+//! `cooklang` does not know the attribute, and would warn about it on every
+//! read; see [`is_own_attribute_warning`](super::is_own_attribute_warning)
+//! for how that is kept quiet.
+//!
+//! # Already on the list
+//!
+//! An item is left off when the list already has something of the same name,
+//! compared ignoring case: an ingredient of a recipe on it, or an item added by
+//! hand — including one an earlier restock added. Running it twice, or on a
+//! timer, adds nothing the second time.
+//!
+//! Names are compared as the aisle configuration folds them, as the list
+//! itself adds them up: `scallions` in the pantry is already on a list whose
+//! recipe asks for `spring onions` when the aisle file has
+//! `spring onions|scallions`.
+
+use super::{depleted, edit, split_quantity, write_quantity, DepletedRequest, PantryItem};
+use crate::{
+    shopping_list::{saved_ingredient_names, ManualItem, ShoppingListStore},
+    units, Context, CoreError, Diagnostic, Outcome,
+};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// The attribute an item's top-up amount is written in. Also a column of
+/// the pantry's [`export`](super::export).
+pub(super) const RESTOCK: &str = "restock";
+
+/// How to restock.
+///
+/// Not `#[non_exhaustive]`: consumers construct this. `..Default::default()`
+/// keeps a literal working if it grows a field.
+#[derive(Debug, Clone, Default)]
+pub struct RestockRequest {
+    /// Work out what would be added without changing the list.
+    pub dry_run: bool,
+}
+
+/// One item that is running out, and what the list was asked for.
+///
+/// `#[non_exhaustive]` because this is an output type consumers read rather
+/// than construct.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RestockItem {
+    /// The item's name, as the pantry writes it and as it goes on the list.
+    pub name: String,
+    /// The pantry section it is in.
+    pub section: String,
+    /// How much the pantry holds, as the file writes it.
+    pub stock: Option<String>,
+    /// The item's `restock` attribute, if it has one.
+    pub restock: Option<String>,
+    /// How much the list asks for: what it takes to reach
+    /// [`restock`](Self::restock), or `None` for no particular amount.
+    pub quantity: Option<String>,
+}
+
+/// What a restock did, or would do for a dry run.
+///
+/// `#[non_exhaustive]` because this is an output type consumers read rather
+/// than construct.
+#[non_exhaustive]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Restock {
+    /// The items put on the list, in pantry file order.
+    pub added: Vec<RestockItem>,
+    /// The items running out that the list already had, and so were left off.
+    pub already_listed: Vec<RestockItem>,
+}
+
+/// Put every item [`depleted`] reports on the list in `store`, except those
+/// it already has. See the [module documentation](self) for the amounts.
+///
+/// Each item is added through [`ShoppingListStore::add_item`], so each is a
+/// change of its own in the list's history, and none is given a category —
+/// aisle configuration files it like any ingredient.
+///
+/// # Errors
+///
+/// - As [`depleted`], for the pantry.
+/// - As [`saved_ingredient_names`], since the list has to be read to know
+///   what is already on it — a recipe on it that cannot be read fails the
+///   restock rather than risking a duplicate.
+/// - [`CoreError::Io`] if the aisle configuration is named but cannot be
+///   read.
+/// - As [`ShoppingListStore::add_item`]. Items added before the error stay
+///   on the list; running it again adds only the rest.
+pub fn restock(
+    ctx: &Context,
+    store: &ShoppingListStore,
+    req: RestockRequest,
+) -> Result<Outcome<Restock>, CoreError> {
+    let running_out = depleted(ctx, DepletedRequest::default())?;
+    let mut diagnostics = running_out.diagnostics;
+
+    // Both reads succeeded in `depleted`, so neither can fail now short of the
+    // file changing in between, in which case no item has a target.
+    let doc = ctx
+        .pantry()
+        .read()?
+        .and_then(|text| text.parse::<toml_edit::DocumentMut>().ok())
+        .unwrap_or_default();
+
+    let common = common_names(ctx, &mut diagnostics)?;
+    let key = |name: &str| {
+        let name = name.to_lowercase();
+        common.get(&name).cloned().unwrap_or(name)
+    };
+    let listed = saved_ingredient_names(ctx, store)?;
+    diagnostics.extend(listed.diagnostics);
+    let mut listed: HashSet<String> = listed.value.iter().map(|name| key(name)).collect();
+
+    let mut restocked = Restock::default();
+    for item in running_out.value {
+        let target = edit::attribute(&doc, &item.section, &item.name, RESTOCK);
+        let quantity = target
+            .as_deref()
+            .and_then(|target| top_up(&item, target, &mut diagnostics));
+        let entry = RestockItem {
+            name: item.name,
+            section: item.section,
+            stock: item.quantity,
+            restock: target,
+            quantity,
+        };
+        // Inserting marks it listed too, so that the same name in a second
+        // section is not added twice.
+        if !listed.insert(key(&entry.name)) {
+            restocked.already_listed.push(entry);
+            continue;
+        }
+        if !req.dry_run {
+            store.add_item(ManualItem {
+                name: entry.name.clone(),
+                quantity: entry.quantity.clone(),
+                category: None,
+            })?;
+        }
+        restocked.added.push(entry);
+    }
+
+    Ok(Outcome::with_diagnostics(restocked, diagnostics))
+}
+
+/// Every name and synonym in the aisle configuration, lowercased, to the
+/// common name of its line, lowercased. Empty without one; one that cannot be
+/// parsed is warned about and names are then compared as written.
+fn common_names(
+    ctx: &Context,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<HashMap<String, String>, CoreError> {
+    let source = ctx.aisle();
+    let Some(text) = source.read()? else {
+        return Ok(HashMap::new());
+    };
+    let parsed = cooklang::aisle::parse_lenient(&text);
+    let Some(conf) = parsed.output() else {
+        let diagnostic = Diagnostic::warning(
+            "aisle configuration could not be parsed, so synonyms are not matched against the list",
+        );
+        diagnostics.push(match source.path() {
+            Some(path) => diagnostic.at_file(path),
+            None => diagnostic,
+        });
+        return Ok(HashMap::new());
+    };
+    Ok(conf
+        .ingredients_info()
+        .into_iter()
+        .map(|(name, info)| (name.to_lowercase(), info.common_name.to_lowercase()))
+        .collect())
+}
+
+/// How much it takes to bring `item` up to `target`, in `target`'s unit, or
+/// `None` with a warning when the two cannot be compared.
+///
+/// A target at or below the stock asks for nothing in particular rather than
+/// for nothing at all: the item is low by its own threshold, so something is
+/// wanted, and the two attributes disagree about how much.
+fn top_up(item: &PantryItem, target: &str, diagnostics: &mut Vec<Diagnostic>) -> Option<String> {
+    let mut warn = |why: String| {
+        diagnostics.push(Diagnostic::warning(format!(
+            "{} in [{}] has restock = '{target}', but {why}, so no amount is put on the list",
+            item.name, item.section
+        )));
+    };
+    let Some((want, unit)) = split_quantity(target) else {
+        warn("that is not a number and a unit".to_string());
+        return None;
+    };
+    let stock = item.quantity.as_deref().unwrap_or_default();
+    let Some((have, have_unit)) = split_quantity(stock) else {
+        warn(format!("its quantity '{stock}' is not a number and a unit"));
+        return None;
+    };
+    let Some(have) = units::convert(have, &have_unit, &unit) else {
+        warn(format!(
+            "its quantity '{stock}' is not in a unit that converts"
+        ));
+        return None;
+    };
+    if want <= have {
+        warn(format!("it already holds '{stock}'"));
+        return None;
+    }
+    Some(write_quantity(want - have, &unit))
+}
@@ -0,0 +1,111 @@
+//! How long an item keeps, from the ingredient datastore.
+//!
+//! The same per-ingredient datastore `cook report` templates read, and the
+//! shopping list reads package sizes from, can say how many days an
+//! ingredient keeps in its `meta.yml`:
+//!
+//! ```yaml
+//! # db/milk/meta.yml
+//! shelf life: 1
+//! fridge life: 7
+//! freezer life: 90
+//! ```
+//!
+//! [`add`](super::add) uses this to fill in an expiry date the request did not
+//! give: the day it was bought (today, unless it says), plus the days the
+//! section it goes in keeps it. Which of the three a section means is guessed
+//! from its name — one with `freez` in it is a freezer, one with `fridge`,
+//! `refrig` or `chill` is a fridge, and anything else is a shelf. A section
+//! whose kind of life the file does not give gets no date, rather than the
+//! wrong one.
+//!
+//! The directory is looked up by the item's name as given, then lowercased,
+//! as package sizes are.
+
+use super::ISO_DATE;
+use crate::Diagnostic;
+use camino::Utf8Path;
+use chrono::{Days, NaiveDate};
+
+/// The file in each ingredient's datastore directory that holds how long it
+/// keeps.
+const META_FILE: &str = "meta.yml";
+
+/// The key in [`META_FILE`] for the days an item keeps in `section`.
+fn life_key(section: &str) -> &'static str {
+    let section = section.to_lowercase();
+    if section.contains("freez") {
+        "freezer life"
+    } else if ["fridge", "refrig", "chill"]
+        .iter()
+        .any(|word| section.contains(word))
+    {
+        "fridge life"
+    } else {
+        "shelf life"
+    }
+}
+
+/// The expiry date `<datastore>/<name>/meta.yml` gives an item put in
+/// `section` on `from`, written as the pantry writes dates. `None`, with a
+/// warning naming the file where there is something to warn about, when it
+/// gives none.
+pub(super) fn expiry(
+    datastore: &Utf8Path,
+    name: &str,
+    section: &str,
+    from: NaiveDate,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Option<String> {
+    let file = [name.to_string(), name.to_lowercase()]
+        .into_iter()
+        .map(|dir| datastore.join(dir).join(META_FILE))
+        .find(|file| file.is_file())?;
+    let mut warn = |message: String| {
+        diagnostics.push(Diagnostic::warning(message).at_file(&file));
+    };
+
+    let text = match std::fs::read_to_string(&file) {
+        Ok(text) => text,
+        Err(e) => {
+            warn(format!("could not read {META_FILE}: {e}"));
+            return None;
+        }
+    };
+    let yaml: serde_yaml::Value = match serde_yaml::from_str(&text) {
+        Ok(yaml) => yaml,
+        Err(e) => {
+            warn(format!("{META_FILE} is not valid YAML: {e}"));
+            return None;
+        }
+    };
+
+    let key = life_key(section);
+    let days = match yaml.get(key)? {
+        serde_yaml::Value::Number(n) => n.as_u64(),
+        serde_yaml::Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    };
+    let Some(days) = days else {
+        warn(format!("{key} must be a whole number of days"));
+        return None;
+    };
+    let date = from.checked_add_days(Days::new(days))?;
+    Some(date.format(ISO_DATE).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::life_key;
+
+    #[test]
+    fn the_kind_of_life_is_guessed_from_the_section_name() {
+        assert_eq!(life_key("Freezer"), "freezer life");
+        assert_eq!(life_key("deep-freeze"), "freezer life");
+        assert_eq!(life_key("fridge"), "fridge life");
+        assert_eq!(life_key("Refrigerator"), "fridge life");
+        assert_eq!(life_key("chiller"), "fridge life");
+        assert_eq!(life_key("pantry"), "shelf life");
+        assert_eq!(life_key("general"), "shelf life");
+    }
+}
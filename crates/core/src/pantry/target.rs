@@ -0,0 +1,433 @@
+//! What to buy to cook particular recipes.
+//!
+//! [`plan`](super::plan) answers "what should a kitchen keep?" for the whole
+//! collection, starting from nothing. [`plan_for`] answers a narrower
+//! question: "I want to cook the weeknight recipes — what is the least I can
+//! buy to cook as many of them as I can, spending at most twenty
+//! ingredients, or twenty pounds?" It starts from what the pantry already
+//! holds, and only looks at the recipes it is pointed at.
+//!
+//! # How the plan is made
+//!
+//! Each target recipe is worth the same, so the plan makes recipes cookable
+//! one at a time, cheapest to finish first: at each step it takes the recipe
+//! whose missing ingredients cost least to buy, counting what earlier steps
+//! bought as bought, and buys them if the budget still covers them. Shared
+//! ingredients make later recipes cheaper, and a recipe the purchases so far
+//! happen to complete joins the plan at no cost. It stops when the cheapest
+//! recipe left costs more than the budget has left.
+//!
+//! That is greedy, like [`plan`](super::plan), and for the same reason: the
+//! best answer is a set-cover problem, and the cheapest-first order is
+//! deterministic and close enough for a shopping trip. Ties go to the recipe
+//! needing fewer ingredients, then to the alphabetically first.
+
+use super::{load, recipe_name};
+use crate::{
+    find::{build_tree, listed_ingredients, parse_or_skip, walk},
+    shopping_list::packages::package_price,
+    substitutes::Substitutes,
+    Context, CoreError, Diagnostic, Outcome,
+};
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Which recipes a [`TargetPlanRequest`] is for.
+///
+/// Not `#[non_exhaustive]`: consumers construct this, and a new way of
+/// picking recipes is one they would have to be told about anyway.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlanTarget {
+    /// One recipe, by its title or by its path under
+    /// [`Context::base_path`] with or without the `.cook`, compared ignoring
+    /// case.
+    Recipe(String),
+    /// Every recipe in this directory under [`Context::base_path`], and in
+    /// the directories below it.
+    Folder(Utf8PathBuf),
+    /// Every recipe whose front matter gives this tag, compared ignoring
+    /// case.
+    Tag(String),
+}
+
+impl std::fmt::Display for PlanTarget {
+    /// As a warning names it: `recipe 'Pad Thai'`, `folder 'Dinners'`,
+    /// `tag 'weeknight'`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlanTarget::Recipe(name) => write!(f, "recipe '{name}'"),
+            PlanTarget::Folder(folder) => write!(f, "folder '{folder}'"),
+            PlanTarget::Tag(tag) => write!(f, "tag '{tag}'"),
+        }
+    }
+}
+
+/// How much a [`TargetPlanRequest`] may buy.
+///
+/// Not `#[non_exhaustive]`, as [`PlanTarget`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PlanBudget {
+    /// At most this many ingredients.
+    Ingredients(usize),
+    /// At most this much money, each ingredient priced at one package from
+    /// [`TargetPlanRequest::datastore`]. See [`plan_for`].
+    Cost(f64),
+}
+
+/// The recipes to plan for, and how much to spend on them.
+///
+/// Not `#[non_exhaustive]`: consumers construct this. `..Default::default()`
+/// keeps a literal working if it grows a field.
+#[derive(Debug, Clone, Default)]
+pub struct TargetPlanRequest {
+    /// The recipes wanted: every recipe any of these picks. Empty picks every
+    /// recipe in the collection.
+    pub targets: Vec<PlanTarget>,
+    /// The most the plan may buy, or `None` to buy whatever makes every
+    /// target cookable.
+    pub budget: Option<PlanBudget>,
+    /// The ingredient datastore to price ingredients from. Without one
+    /// nothing is priced, and a [`PlanBudget::Cost`] buys only what costs
+    /// nothing — which is everything.
+    pub datastore: Option<Utf8PathBuf>,
+}
+
+/// A target recipe the plan makes cookable, and what to buy for it.
+///
+/// `#[non_exhaustive]` because this is an output type consumers read rather
+/// than construct.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlannedRecipe {
+    /// The recipe's title, or its file stem when it has none.
+    pub name: String,
+    /// Its file, relative to [`Context::base_path`].
+    pub path: Option<Utf8PathBuf>,
+    /// The ingredients to buy for it that no recipe before it in the plan
+    /// needed, lowercased as they were compared, in alphabetical order. Empty
+    /// for a recipe the pantry can already cook, or one that what was bought
+    /// for earlier recipes completes.
+    pub buy: Vec<String>,
+    /// What [`buy`](Self::buy) costs. `None` without a datastore.
+    pub cost: Option<f64>,
+}
+
+/// A target recipe the budget does not stretch to.
+///
+/// `#[non_exhaustive]` because this is an output type consumers read rather
+/// than construct.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UnplannedRecipe {
+    /// The recipe's title, or its file stem when it has none.
+    pub name: String,
+    /// Its file, relative to [`Context::base_path`].
+    pub path: Option<Utf8PathBuf>,
+    /// What it still needs once the whole plan is bought, lowercased, in
+    /// alphabetical order.
+    pub missing: Vec<String>,
+    /// What [`missing`](Self::missing) would cost. `None` without a
+    /// datastore.
+    pub cost: Option<f64>,
+}
+
+/// What to buy to cook the target recipes.
+///
+/// `#[non_exhaustive]` because this is an output type consumers read rather
+/// than construct.
+#[non_exhaustive]
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TargetPlan {
+    /// The recipes the plan makes cookable, in the order it got to them:
+    /// those the pantry can already cook first.
+    pub cookable: Vec<PlannedRecipe>,
+    /// The target recipes left out, cheapest to finish first.
+    pub out_of_budget: Vec<UnplannedRecipe>,
+    /// Everything to buy, in the order the plan bought it.
+    pub buy: Vec<String>,
+    /// What [`buy`](Self::buy) costs in all. `None` without a datastore.
+    pub cost: Option<f64>,
+    /// The ingredients in [`buy`](Self::buy) the datastore has no price for,
+    /// and which [`cost`](Self::cost) counts as free — so with any here, the
+    /// total is the least it will come to.
+    pub unpriced: Vec<String>,
+}
+
+/// Work out the least to buy to cook as many of the target recipes as the
+/// budget allows.
+///
+/// An ingredient is in hand when the pantry lists an item of that name —
+/// compared lowercased, whatever quantity is left, as [`recipes`](super::recipes)
+/// counts them — or when [`Context::substitutes`] names a substitute every
+/// part of which the pantry lists. With no pantry configured, nothing is in
+/// hand. See the [module documentation](self) for how the rest is chosen.
+///
+/// With a datastore, an ingredient costs one package from its
+/// `shopping.yml`: its `price_per_unit` times the number in its
+/// `package_size`, or the `price_per_unit` alone when it gives no size.
+/// An ingredient with no price costs nothing, and is listed in
+/// [`TargetPlan::unpriced`].
+///
+/// `.menu` files are not targets, and recipes that list no ingredients are
+/// left out, as [`plan`](super::plan) leaves them out. A target that picks
+/// no recipe is a warning in [`Outcome::diagnostics`], as is a recipe that
+/// cannot be read or parsed.
+///
+/// # Errors
+///
+/// - As [`load`], except [`CoreError::MissingConfig`].
+/// - As [`Substitutes::load`], for `substitutes.conf`.
+/// - [`CoreError::Search`] if the collection cannot be walked, and
+///   [`CoreError::Io`] if a file in it cannot be listed.
+pub fn plan_for(ctx: &Context, req: TargetPlanRequest) -> Result<Outcome<TargetPlan>, CoreError> {
+    let mut diagnostics = Vec::new();
+    let stocked: BTreeSet<String> = match load(ctx) {
+        Ok(loaded) => {
+            diagnostics.extend(loaded.diagnostics);
+            loaded
+                .value
+                .items()
+                .map(|item| item.name.to_lowercase())
+                .collect()
+        }
+        Err(CoreError::MissingConfig { .. }) => BTreeSet::new(),
+        Err(e) => return Err(e),
+    };
+    let substitutes = Substitutes::load(ctx.substitutes())?;
+    diagnostics.extend(substitutes.diagnostics);
+    let substitutes = substitutes.value;
+
+    let tree = build_tree(ctx.base_path())?;
+    let mut picked = vec![false; req.targets.len()];
+    let mut wanted: Vec<Wanted> = Vec::new();
+    for entry in walk(&tree) {
+        if entry.is_menu() {
+            continue;
+        }
+        let path = entry.path().map(|path| {
+            path.strip_prefix(ctx.base_path())
+                .unwrap_or(path)
+                .to_owned()
+        });
+        let name = recipe_name(entry);
+        let tags = entry.tags();
+        let mut is_target = req.targets.is_empty();
+        for (target, picked) in req.targets.iter().zip(&mut picked) {
+            if picks(target, &name, path.as_deref(), &tags) {
+                *picked = true;
+                is_target = true;
+            }
+        }
+        if !is_target {
+            continue;
+        }
+
+        let Some(recipe) = parse_or_skip(entry, &mut diagnostics) else {
+            continue;
+        };
+        let ingredients: BTreeSet<String> = listed_ingredients(&recipe)
+            .iter()
+            .map(|name| name.to_lowercase())
+            .collect();
+        if ingredients.is_empty() {
+            continue;
+        }
+        let missing = ingredients
+            .into_iter()
+            .filter(|ingredient| {
+                !stocked.contains(ingredient)
+                    && substitutes
+                        .find(ingredient, |part| stocked.contains(&part.to_lowercase()))
+                        .is_none()
+            })
+            .collect();
+        wanted.push(Wanted {
+            name,
+            path,
+            missing,
+        });
+    }
+    for (target, picked) in req.targets.iter().zip(picked) {
+        if !picked {
+            diagnostics.push(Diagnostic::warning(format!(
+                "{target} picks no recipe, so it was not planned for"
+            )));
+        }
+    }
+
+    let prices = Prices::new(&req, &wanted, &mut diagnostics);
+    let mut plan = TargetPlan {
+        cost: prices.priced.then_some(0.0),
+        ..TargetPlan::default()
+    };
+    let mut bought: BTreeSet<String> = BTreeSet::new();
+    let mut spent = 0.0;
+
+    loop {
+        // What each recipe still needs, and what that costs, given what has
+        // been bought so far.
+        let cheapest = wanted
+            .iter()
+            .enumerate()
+            .map(|(index, recipe)| {
+                let needs: Vec<String> = recipe.missing.difference(&bought).cloned().collect();
+                let cost = prices.budget_cost(&needs);
+                (index, needs, cost)
+            })
+            .min_by(|(a, a_needs, a_cost), (b, b_needs, b_cost)| {
+                a_cost
+                    .total_cmp(b_cost)
+                    .then(a_needs.len().cmp(&b_needs.len()))
+                    .then_with(|| wanted[*a].name.cmp(&wanted[*b].name))
+            });
+        let Some((index, needs, cost)) = cheapest else {
+            break;
+        };
+        if req
+            .budget
+            .is_some_and(|budget| spent + cost > budget.limit() + 1e-9)
+        {
+            break;
+        }
+
+        spent += cost;
+        let recipe = wanted.remove(index);
+        let price = prices.price(&needs);
+        if let (Some(total), Some(price)) = (&mut plan.cost, price) {
+            *total += price;
+        }
+        for ingredient in &needs {
+            if !prices.has_price(ingredient) {
+                plan.unpriced.push(ingredient.clone());
+            }
+        }
+        bought.extend(needs.iter().cloned());
+        plan.buy.extend(needs.iter().cloned());
+        plan.cookable.push(PlannedRecipe {
+            name: recipe.name,
+            path: recipe.path,
+            buy: needs,
+            cost: price,
+        });
+    }
+
+    let mut out_of_budget: Vec<UnplannedRecipe> = wanted
+        .into_iter()
+        .map(|recipe| {
+            let missing: Vec<String> = recipe.missing.difference(&bought).cloned().collect();
+            UnplannedRecipe {
+                cost: prices.price(&missing),
+                name: recipe.name,
+                path: recipe.path,
+                missing,
+            }
+        })
+        .collect();
+    out_of_budget.sort_by(|a, b| {
+        prices
+            .budget_cost(&a.missing)
+            .total_cmp(&prices.budget_cost(&b.missing))
+            .then(a.missing.len().cmp(&b.missing.len()))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    plan.out_of_budget = out_of_budget;
+
+    Ok(Outcome::with_diagnostics(plan, diagnostics))
+}
+
+/// A target recipe, and what it needs that is not in hand.
+struct Wanted {
+    name: String,
+    path: Option<Utf8PathBuf>,
+    missing: BTreeSet<String>,
+}
+
+/// Whether `target` picks the recipe called `name` at `path`, tagged `tags`.
+fn picks(target: &PlanTarget, name: &str, path: Option<&Utf8Path>, tags: &[String]) -> bool {
+    match target {
+        PlanTarget::Recipe(wanted) => {
+            let wanted = wanted.trim_start_matches("./");
+            name.eq_ignore_ascii_case(wanted)
+                || path.is_some_and(|path| {
+                    path.as_str().eq_ignore_ascii_case(wanted)
+                        || path
+                            .with_extension("")
+                            .as_str()
+                            .eq_ignore_ascii_case(wanted)
+                })
+        }
+        PlanTarget::Folder(folder) => {
+            path.is_some_and(|path| path.starts_with(folder.as_str().trim_start_matches("./")))
+        }
+        PlanTarget::Tag(wanted) => tags.iter().any(|tag| tag.eq_ignore_ascii_case(wanted)),
+    }
+}
+
+/// What ingredients cost, and what a budget counts them as.
+struct Prices {
+    /// Whether there is a datastore to price anything from.
+    priced: bool,
+    /// By ingredient, for those the datastore prices.
+    by_ingredient: BTreeMap<String, f64>,
+    /// Whether the budget is money rather than a count.
+    by_cost: bool,
+}
+
+impl Prices {
+    /// Price every ingredient any of `wanted` is missing, once.
+    fn new(req: &TargetPlanRequest, wanted: &[Wanted], diagnostics: &mut Vec<Diagnostic>) -> Self {
+        let mut by_ingredient = BTreeMap::new();
+        if let Some(datastore) = &req.datastore {
+            let missing: BTreeSet<&String> = wanted.iter().flat_map(|w| &w.missing).collect();
+            for ingredient in missing {
+                if let Some(price) = package_price(datastore, ingredient, diagnostics) {
+                    by_ingredient.insert(ingredient.clone(), price);
+                }
+            }
+        }
+        Self {
+            priced: req.datastore.is_some(),
+            by_ingredient,
+            by_cost: matches!(req.budget, Some(PlanBudget::Cost(_))),
+        }
+    }
+
+    fn has_price(&self, ingredient: &str) -> bool {
+        self.by_ingredient.contains_key(ingredient)
+    }
+
+    /// What buying `ingredients` costs, unpriced ones free, or `None` with
+    /// no datastore.
+    fn price(&self, ingredients: &[String]) -> Option<f64> {
+        self.priced.then(|| {
+            ingredients
+                .iter()
+                .filter_map(|ingredient| self.by_ingredient.get(ingredient))
+                // Folded from 0.0 rather than summed: an empty `sum` of
+                // floats is -0.0, which prints as "-0.00".
+                .fold(0.0, |total, price| total + price)
+        })
+    }
+
+    /// What buying `ingredients` takes out of the budget: their price for a
+    /// budget in money, and how many they are otherwise — which is also how
+    /// recipes are ranked with no budget at all.
+    fn budget_cost(&self, ingredients: &[String]) -> f64 {
+        if self.by_cost {
+            self.price(ingredients).unwrap_or(0.0)
+        } else {
+            ingredients.len() as f64
+        }
+    }
+}
+
+impl PlanBudget {
+    /// The budget as a number [`Prices::budget_cost`] is measured against.
+    fn limit(self) -> f64 {
+        match self {
+            PlanBudget::Ingredients(count) => count as f64,
+            PlanBudget::Cost(amount) => amount,
+        }
+    }
+}
@@ -732,6 +732,52 @@ fn a_dash_prefixed_ingredient_is_wanted_like_any_other() {
     );
 }
 
+/// A substitute in stock makes up for the ingredient it stands in for, and
+/// the match says which substitution it was counted with.
+#[test]
+fn a_substitute_in_stock_counts_and_is_reported() {
+    let dir = temp();
+    let base = base(&dir);
+    write(
+        &base.join("scones.cook"),
+        "Mix @flour{200%g} with @buttermilk{100%ml}.\n",
+    );
+    write(
+        &base.join("gratin.cook"),
+        "Bake @potatoes{1%kg} with a @shallot{1}.\n",
+    );
+    let ctx = Context::new(base)
+        .with_pantry(ConfigSource::Inline(
+            "[test]\nflour = \"1%kg\"\nmilk = \"1%l\"\nLemon = \"2\"\npotatoes = \"2%kg\"\n"
+                .to_string(),
+        ))
+        .with_substitutes(ConfigSource::Inline(
+            "buttermilk = \"milk + lemon\"\nshallot = \"onion\"\n".to_string(),
+        ));
+
+    let found = recipes(&ctx, RecipesRequest { threshold: 50 })
+        .expect("reports")
+        .into_value();
+
+    assert_eq!(found.full, ["scones"]);
+    let scones = &found.substitutions["scones"];
+    assert_eq!(scones.len(), 1);
+    assert_eq!(
+        (scones[0].ingredient.as_str(), scones[0].with_joined()),
+        ("buttermilk", "milk + lemon".to_string())
+    );
+    assert_eq!(
+        found.partial,
+        [PartialMatch {
+            name: "gratin".to_string(),
+            percentage: 50,
+            missing: vec!["shallot".to_string()],
+        }],
+        "no onion, so the shallot is still missing"
+    );
+    assert!(!found.substitutions.contains_key("gratin"));
+}
+
 /// One broken recipe must not cost the caller the answer for the rest.
 #[test]
 fn an_unparseable_recipe_is_skipped_with_a_warning() {
@@ -1042,6 +1088,322 @@ fn a_collection_that_cannot_be_walked_fails_the_plan() {
     }
 }
 
+// ---------------------------------------------------------------------------
+// plan for
+// ---------------------------------------------------------------------------
+
+/// Three weeknight recipes and a feast. The pantry has the eggs and the
+/// garlic, so the omelette wants one thing, and the pasta and the salad two
+/// each — one of them shared.
+fn weeknight_collection() -> (tempfile::TempDir, Context) {
+    let dir = temp();
+    let base = base(&dir);
+    let weeknight = "---\ntags: [weeknight]\n---\n";
+    write(
+        &base.join("weeknight/omelette.cook"),
+        &format!("{weeknight}Whisk @eggs{{3}} and fry in @butter{{10%g}}.\n"),
+    );
+    write(
+        &base.join("weeknight/pasta.cook"),
+        &format!("{weeknight}Boil @pasta{{200%g}}; toss with @tomatoes{{2}} and @garlic{{1}}.\n"),
+    );
+    write(
+        &base.join("weeknight/salad.cook"),
+        &format!("{weeknight}Chop @tomatoes{{2}} and a @cucumber{{1}}.\n"),
+    );
+    write(
+        &base.join("feast.cook"),
+        "Roast @beef{2%kg} in @wine{1%l}.\n",
+    );
+    let ctx = Context::new(base).with_pantry(ConfigSource::Inline(
+        "[fridge]\neggs = \"6\"\ngarlic = \"1\"\n".to_string(),
+    ));
+    (dir, ctx)
+}
+
+fn cookable(plan: &TargetPlan) -> Vec<(&str, Vec<&str>)> {
+    plan.cookable
+        .iter()
+        .map(|recipe| {
+            (
+                recipe.name.as_str(),
+                recipe.buy.iter().map(String::as_str).collect(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn a_plan_for_a_tag_finishes_the_cheapest_recipes_first_within_the_budget() {
+    let (_dir, ctx) = weeknight_collection();
+    let plan = plan_for(
+        &ctx,
+        TargetPlanRequest {
+            targets: vec![PlanTarget::Tag("Weeknight".to_string())],
+            budget: Some(PlanBudget::Ingredients(3)),
+            ..Default::default()
+        },
+    )
+    .expect("plans")
+    .into_value();
+
+    assert_eq!(
+        cookable(&plan),
+        [
+            ("omelette", vec!["butter"]),
+            ("pasta", vec!["pasta", "tomatoes"]),
+        ],
+        "the omelette is one ingredient short; the pasta and the salad tie on \
+         two, and the pasta is first alphabetically"
+    );
+    assert_eq!(plan.buy, ["butter", "pasta", "tomatoes"]);
+    assert_eq!(plan.out_of_budget.len(), 1);
+    assert_eq!(plan.out_of_budget[0].name, "salad");
+    assert_eq!(
+        plan.out_of_budget[0].missing,
+        ["cucumber"],
+        "the tomatoes bought for the pasta count towards the salad"
+    );
+    assert_eq!(plan.cost, None, "nothing is priced without a datastore");
+}
+
+#[test]
+fn without_a_budget_every_target_is_planned_and_nothing_else() {
+    let (_dir, ctx) = weeknight_collection();
+    for target in [
+        PlanTarget::Folder("weeknight".into()),
+        PlanTarget::Tag("weeknight".to_string()),
+    ] {
+        let plan = plan_for(
+            &ctx,
+            TargetPlanRequest {
+                targets: vec![target.clone()],
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .into_value();
+        assert_eq!(
+            cookable(&plan),
+            [
+                ("omelette", vec!["butter"]),
+                ("pasta", vec!["pasta", "tomatoes"]),
+                ("salad", vec!["cucumber"]),
+            ],
+            "{target} must pick the three weeknight recipes and not the feast"
+        );
+        assert!(plan.out_of_budget.is_empty());
+    }
+}
+
+#[test]
+fn a_recipe_target_matches_its_title_or_its_path() {
+    let (_dir, ctx) = weeknight_collection();
+    for name in ["Salad", "weeknight/salad", "weeknight/salad.cook"] {
+        let plan = plan_for(
+            &ctx,
+            TargetPlanRequest {
+                targets: vec![PlanTarget::Recipe(name.to_string())],
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .into_value();
+        assert_eq!(
+            cookable(&plan),
+            [("salad", vec!["cucumber", "tomatoes"])],
+            "{name} must pick the salad"
+        );
+    }
+}
+
+#[test]
+fn a_cost_budget_prices_one_package_from_the_datastore() {
+    let (dir, ctx) = weeknight_collection();
+    let db = base(&dir).join("db");
+    write(&db.join("butter/shopping.yml"), "price_per_unit: 2.5\n");
+    write(
+        &db.join("pasta/shopping.yml"),
+        "price_per_unit: 0.5\npackage_size: 2\n",
+    );
+    write(
+        &db.join("tomatoes/shopping.yml"),
+        "price_per_unit: 0.25\npackage_size: 8\n",
+    );
+
+    let plan = plan_for(
+        &ctx,
+        TargetPlanRequest {
+            targets: vec![PlanTarget::Tag("weeknight".to_string())],
+            budget: Some(PlanBudget::Cost(3.0)),
+            datastore: Some(db),
+        },
+    )
+    .expect("plans")
+    .into_value();
+
+    assert_eq!(
+        cookable(&plan),
+        [
+            ("salad", vec!["cucumber", "tomatoes"]),
+            ("pasta", vec!["pasta"]),
+        ],
+        "the salad costs 2 with the cucumber unpriced, which makes the pasta \
+         cost 1 — and then the 2.50 omelette no longer fits"
+    );
+    assert_eq!(plan.cost, Some(3.0));
+    assert_eq!(plan.unpriced, ["cucumber"]);
+    assert_eq!(plan.out_of_budget[0].name, "omelette");
+    assert_eq!(plan.out_of_budget[0].cost, Some(2.5));
+}
+
+#[test]
+fn a_plan_for_counts_substitutes_and_needs_no_pantry() {
+    let (dir, ctx) = weeknight_collection();
+    let target = || TargetPlanRequest {
+        targets: vec![PlanTarget::Recipe("omelette".to_string())],
+        ..Default::default()
+    };
+
+    let with_substitute =
+        ctx.with_substitutes(ConfigSource::Inline("butter = \"garlic\"\n".to_string()));
+    assert_eq!(
+        cookable(&plan_for(&with_substitute, target()).unwrap().value),
+        [("omelette", vec![])],
+        "the pantry can already cook what a substitute completes"
+    );
+
+    let without_pantry = Context::new(base(&dir));
+    assert_eq!(
+        cookable(&plan_for(&without_pantry, target()).unwrap().value),
+        [("omelette", vec!["butter", "eggs"])]
+    );
+}
+
+#[test]
+fn a_target_that_picks_nothing_is_a_warning() {
+    let (_dir, ctx) = weeknight_collection();
+    let outcome = plan_for(
+        &ctx,
+        TargetPlanRequest {
+            targets: vec![
+                PlanTarget::Tag("weeknight".to_string()),
+                PlanTarget::Folder("brunch".into()),
+            ],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(outcome.value.cookable.len(), 3);
+    assert_eq!(outcome.diagnostics.len(), 1);
+    assert_eq!(outcome.diagnostics[0].severity, Severity::Warning);
+    assert!(
+        outcome.diagnostics[0]
+            .message
+            .contains("folder 'brunch' picks no recipe"),
+        "{}",
+        outcome.diagnostics[0].message
+    );
+}
+
+// ---------------------------------------------------------------------------
+// use up
+// ---------------------------------------------------------------------------
+
+fn ranked(dir: &tempfile::TempDir, pantry: &str, today: NaiveDate) -> Vec<UseUpMatch> {
+    let ctx = Context::new(base(dir)).with_pantry(ConfigSource::Inline(pantry.to_string()));
+    use_up::use_up_on(&ctx, &UseUpRequest { days: 7 }, today)
+        .expect("ranks")
+        .into_value()
+}
+
+fn ranking(found: &[UseUpMatch]) -> Vec<(&str, f64)> {
+    found
+        .iter()
+        .map(|found| (found.name.as_str(), found.score))
+        .collect()
+}
+
+/// Milk that goes off today outweighs spinach that lasts the week, what a
+/// recipe uses is weighed against what there is, and a recipe that needs
+/// buying for loses out to one that does not.
+#[test]
+fn use_up_ranks_by_urgency_share_and_what_is_in_stock() {
+    let dir = temp();
+    let base = base(&dir);
+    write(
+        &base.join("pancakes.cook"),
+        "Whisk @milk{500%ml} into @flour{200%g}.\n",
+    );
+    write(
+        &base.join("latte.cook"),
+        "Froth @milk{1%l} over @coffee{}.\n",
+    );
+    write(
+        &base.join("spinach pie.cook"),
+        "Wilt @spinach{200%g} and bake in @flour{100%g}.\n",
+    );
+    write(&base.join("cheese toast.cook"), "Melt @cheese{50%g}.\n");
+    write(&base.join("toast.cook"), "Toast @bread{2}.\n");
+    let pantry = r#"
+[fridge]
+milk = { quantity = "1%l", expire = "2025-06-01" }
+spinach = { quantity = "200%g", expire = "2025-06-08" }
+cheese = { quantity = "100%g", expire = "2025-06-30" }
+
+[pantry]
+flour = "1%kg"
+bread = "1"
+"#;
+
+    let found = ranked(&dir, pantry, on(1));
+
+    // Pancakes and latte tie at half, and the tie goes to fewer missing; the
+    // spinach has seven days left of an eight-day window. Cheese is outside
+    // the window and toast uses nothing expiring.
+    assert_eq!(
+        ranking(&found),
+        [("pancakes", 0.5), ("latte", 0.5), ("spinach pie", 0.125)]
+    );
+    assert_eq!(found[1].missing, ["coffee"]);
+    assert_eq!(found[0].uses[0].share, 0.5);
+    assert_eq!(found[0].uses[0].days_until_expiry, 0);
+    assert_eq!(
+        found[0].path.as_deref(),
+        Some(Utf8Path::new("pancakes.cook"))
+    );
+}
+
+/// Food past its date counts as fully urgent rather than dropping out, and an
+/// amount that cannot be compared counts as using all of it.
+#[test]
+fn use_up_counts_expired_items_and_unknown_amounts_in_full() {
+    let dir = temp();
+    let base = base(&dir);
+    write(
+        &base.join("soup.cook"),
+        "Simmer @leeks and @stock{2%cubes}.\n",
+    );
+    let pantry = r#"
+[fridge]
+leeks = { quantity = "3", expire = "2025-06-01" }
+stock = { quantity = "1%l", expire = "2025-06-03" }
+"#;
+
+    let found = ranked(&dir, pantry, on(5));
+
+    assert_eq!(ranking(&found), [("soup", 2.0)]);
+    assert_eq!(
+        found[0]
+            .uses
+            .iter()
+            .map(|used| (used.name.as_str(), used.days_until_expiry, used.share))
+            .collect::<Vec<_>>(),
+        [("leeks", -4, 1.0), ("stock", -2, 1.0)]
+    );
+}
+
 // ---------------------------------------------------------------------------
 // add, remove, update
 // ---------------------------------------------------------------------------
@@ -1137,6 +1499,7 @@ fn add_writes_every_attribute_it_is_given() {
             bought: Some("2025-05-01".to_string()),
             expire: Some("2025-12-01".to_string()),
             low: Some("500%ml".to_string()),
+            datastore: None,
         },
     )
     .expect("adds");
@@ -1531,750 +1894,2031 @@ fn update_changes_the_item_in_the_section_it_names() {
 }
 
 // ---------------------------------------------------------------------------
-// Where a change may and may not be written
+// consume
 // ---------------------------------------------------------------------------
 
-/// Writing an editor's unsaved buffer to a path this crate made up would be
-/// worse than refusing, so all three refuse.
-#[test]
-fn nothing_writes_to_an_inline_pantry() {
-    let dir = temp();
-    let base = base(&dir);
-    let ctx = Context::new(base.clone()).with_pantry(ConfigSource::Inline(SMALL.to_string()));
+const KITCHEN: &str = "\
+# Kitchen
+[pantry]
+flour = { quantity = \"1%kg\", low = \"200%g\" }
+salt = \"unlim\"
+eggs = \"6\"
 
-    let refused = |result: Result<Outcome<PantryContents>, CoreError>, what: &str| match result {
-        Err(CoreError::ReadOnlyConfig { kind }) => assert_eq!(kind, "pantry", "{what}"),
-        other => panic!(
-            "expected ReadOnlyConfig from {what}, got {:?}",
-            other.map(|o| o.value)
-        ),
-    };
+[dairy]
+milk = \"300%ml\"
+";
 
-    refused(
-        add(
-            &ctx,
-            AddRequest {
-                section: "pantry".to_string(),
-                name: "sugar".to_string(),
-                ..Default::default()
-            },
-        ),
-        "add",
+/// A pancake recipe cooked twice over, against [`KITCHEN`].
+fn cooked(dry_run: bool) -> (tempfile::TempDir, Context, Outcome<Consumption>) {
+    let (dir, ctx) = planted(KITCHEN);
+    write(
+        &base(&dir).join("Pancakes.cook"),
+        "Mix @flour{300%g}, @eggs{2}, @milk{500%ml}, @salt{1%tsp} and @sugar{2%tbsp}.\n",
     );
-    refused(
-        remove(
-            &ctx,
-            RemoveRequest {
-                section: "pantry".to_string(),
-                name: "flour".to_string(),
-            },
-        ),
-        "remove",
+    let outcome = consume(
+        &ctx,
+        ConsumeRequest {
+            recipe: crate::shopping_list::ScaledRecipe::scaled(
+                crate::RecipeSource::Path("Pancakes".into()),
+                2.0,
+            ),
+            dry_run,
+        },
+    )
+    .unwrap();
+    (dir, ctx, outcome)
+}
+
+/// Each ingredient comes off in the unit the pantry keeps it in, nothing goes
+/// below zero, and only the changed quantities are rewritten.
+#[test]
+fn consume_draws_each_ingredient_down_in_the_pantrys_own_unit() {
+    let (_dir, ctx, outcome) = cooked(false);
+    let consumption = outcome.value;
+
+    let items: Vec<(&str, &str, &str, bool, bool)> = consumption
+        .items
+        .iter()
+        .map(|i| {
+            (
+                i.name.as_str(),
+                i.used.as_str(),
+                i.after.as_str(),
+                i.empty,
+                i.low,
+            )
+        })
+        .collect();
+    assert_eq!(
+        items,
+        [
+            ("flour", "0.6%kg", "0.4%kg", false, true),
+            ("eggs", "4", "2", false, false),
+            ("milk", "300%ml", "0%ml", true, true),
+        ]
     );
-    refused(
-        update(
-            &ctx,
-            UpdateRequest {
-                section: "pantry".to_string(),
-                name: "flour".to_string(),
-                quantity: Some("2%kg".to_string()),
-                ..Default::default()
-            },
-        ),
-        "update",
+    assert_eq!(consumption.short, ["milk"], "a litre was wanted");
+    assert_eq!(consumption.not_stocked, ["sugar"]);
+    assert_eq!(
+        read_back(&ctx),
+        "\
+# Kitchen
+[pantry]
+flour = { quantity = \"0.4%kg\", low = \"200%g\" }
+salt = \"unlim\"
+eggs = \"2\"
+
+[dairy]
+milk = \"0%ml\"
+"
     );
+}
 
-    assert!(
-        !base.join("config").exists(),
-        "a refused write must not leave a file behind"
+#[test]
+fn a_dry_run_reports_the_same_and_writes_nothing() {
+    let (_dir, ctx, dry) = cooked(true);
+    assert_eq!(read_back(&ctx), KITCHEN);
+    let (_dir, _, real) = cooked(false);
+    assert_eq!(dry.value, real.value);
+}
+
+// ---------------------------------------------------------------------------
+// take, top up
+// ---------------------------------------------------------------------------
+
+fn adjusting(name: &str, amount: &str) -> AdjustRequest {
+    AdjustRequest {
+        name: name.to_string(),
+        amount: amount.to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn take_converts_the_amount_and_writes_the_items_own_unit() {
+    let (_dir, ctx) = planted(KITCHEN);
+
+    let taken = take(&ctx, adjusting("Flour", "200%g")).unwrap().value;
+
+    assert_eq!(taken.name, "flour");
+    assert_eq!(taken.amount, "0.2%kg");
+    assert_eq!(taken.before.as_deref(), Some("1%kg"));
+    assert_eq!(taken.after, "0.8%kg");
+    assert!(!taken.short && !taken.empty && !taken.low);
+    assert_eq!(
+        read_back(&ctx),
+        KITCHEN.replace("quantity = \"1%kg\"", "quantity = \"0.8%kg\"")
     );
 }
 
-/// `add` creates a pantry where there is none; the other two have nothing to
-/// change and say so.
 #[test]
-fn remove_and_update_need_a_pantry_that_exists() {
-    let dir = temp();
-    let ctx = Context::new(base(&dir));
+fn taking_more_than_there_is_empties_the_item_and_says_so() {
+    let (_dir, ctx) = planted(KITCHEN);
 
-    for result in [
-        remove(
-            &ctx,
-            RemoveRequest {
-                section: "pantry".to_string(),
-                name: "flour".to_string(),
-            },
-        ),
-        update(
-            &ctx,
-            UpdateRequest {
-                section: "pantry".to_string(),
-                name: "flour".to_string(),
-                quantity: Some("2%kg".to_string()),
-                ..Default::default()
-            },
-        ),
-    ] {
-        match result {
-            Err(CoreError::MissingConfig { kind }) => assert_eq!(kind, "pantry"),
-            other => panic!("expected MissingConfig, got {:?}", other.map(|o| o.value)),
-        }
-    }
-}
-
-/// A path-backed pantry that is not there is a different thing from no pantry
-/// at all: something named a file and it could not be read.
-#[test]
-fn removing_from_a_pantry_path_that_does_not_exist_is_an_io_error() {
-    let dir = temp();
-    let base = base(&dir);
-    let ctx = ctx_at(&base);
+    let taken = take(&ctx, adjusting("milk", "1%l")).unwrap().value;
 
-    match remove(
-        &ctx,
-        RemoveRequest {
-            section: "pantry".to_string(),
-            name: "flour".to_string(),
-        },
-    ) {
-        Err(CoreError::Io { path, source }) => {
-            assert_eq!(path, pantry_path(&base));
-            assert_eq!(source.kind(), std::io::ErrorKind::NotFound);
-        }
-        other => panic!("expected Io, got {:?}", other.map(|o| o.value)),
-    }
+    assert_eq!(taken.amount, "300%ml");
+    assert_eq!(taken.after, "0%ml");
+    assert!(taken.short && taken.empty && taken.low);
 }
 
 #[test]
-fn a_pantry_that_cannot_be_parsed_stops_a_change() {
-    let (_dir, ctx) = planted("[unclosed\n");
-
-    match add(
-        &ctx,
-        AddRequest {
-            section: "pantry".to_string(),
-            name: "sugar".to_string(),
-            ..Default::default()
-        },
-    ) {
-        Err(CoreError::Config { path, message }) => {
-            assert_eq!(path.as_deref(), ctx.pantry().path());
-            assert!(!message.is_empty());
-            assert!(!message.contains('\n'), "one line: {message:?}");
-        }
-        other => panic!("expected Config, got {:?}", other.map(|o| o.value)),
-    }
-    assert_eq!(read_back(&ctx), "[unclosed\n", "nothing may be written");
-}
-
-// ---------------------------------------------------------------------------
-// What a write keeps, and what it throws away
-// ---------------------------------------------------------------------------
+fn top_up_adds_in_the_items_unit_and_starts_an_uncounted_item_at_the_amount() {
+    let (_dir, ctx) = planted("[dairy]\nmilk = \"300%ml\"\n\n[pantry]\nrice = {}\n");
 
-/// Comments, indentation and the short item form all survive a change, because
-/// the file is edited as a document rather than rebuilt from a parsed model.
-///
-/// The whole original text is asserted with only the new line added, which is
-/// the strongest form this can take: anything reformatted anywhere fails.
-#[test]
-fn a_write_keeps_comments_and_layout() {
-    let original = "# my pantry\n\n[pantry]\n# staple\n  flour   =   \"1%kg\"   # a whole bag\n";
-    let (_dir, ctx) = planted(original);
+    let milk = top_up(&ctx, adjusting("milk", "1%l")).unwrap().value;
+    assert_eq!(
+        (milk.amount.as_str(), milk.after.as_str()),
+        ("1000%ml", "1300%ml")
+    );
 
-    add(
-        &ctx,
-        AddRequest {
-            section: "pantry".to_string(),
-            name: "sugar".to_string(),
-            ..Default::default()
-        },
-    )
-    .expect("adds");
+    let rice = top_up(&ctx, adjusting("rice", "500%g")).unwrap().value;
+    assert_eq!(rice.before, None);
+    assert_eq!(rice.after, "500%g");
 
     assert_eq!(
         read_back(&ctx),
-        format!("{original}sugar = \"\"\n"),
-        "only the new item may be added"
+        "[dairy]\nmilk = \"1300%ml\"\n\n[pantry]\nrice = { quantity = \"500%g\" }\n"
     );
 }
 
-/// An attribute `cooklang` does not model survives an unrelated change. The
-/// parser still warns that it does not understand it — that warning is about
-/// what `cook pantry list` can show, not about anything being lost.
+/// An item in a section written as a list of names has no quantity to add
+/// to. Topping it up has to rewrite the section, as any edit does, rather
+/// than report a change it never wrote.
 #[test]
-fn a_write_keeps_attributes_cooklang_does_not_model() {
-    let (_dir, ctx) = planted("[pantry]\nflour = { quantity = \"1%kg\", shelf = \"top\" }\n");
+fn top_up_gives_an_item_in_an_array_section_its_first_quantity() {
+    let (_dir, ctx) = planted("fridge = [\"milk\", \"eggs\"]\n");
 
-    let outcome = add(
-        &ctx,
-        AddRequest {
-            section: "pantry".to_string(),
-            name: "sugar".to_string(),
-            ..Default::default()
-        },
-    )
-    .expect("adds");
+    let outcome = top_up(&ctx, adjusting("milk", "1%l")).unwrap();
 
     assert!(
         outcome
             .diagnostics
             .iter()
-            .any(|d| d.severity == Severity::Warning && d.message.contains("shelf")),
-        "the unknown attribute must be reported: {:?}",
+            .any(|d| d.message.contains("list of names")),
+        "{:?}",
         outcome.diagnostics
     );
+    assert_eq!(outcome.value.after, "1%l");
     let written = read_back(&ctx);
-    assert!(
-        written.contains("shelf = \"top\""),
-        "the attribute must survive\n{written}"
-    );
-    assert!(written.contains("1%kg"), "{written}");
+    assert!(written.contains("[fridge]"), "{written}");
+    assert!(written.contains("milk = \"1%l\""), "{written}");
+    assert!(written.contains("eggs"), "no name may be lost: {written}");
+    let entries = history(&ctx).unwrap().value;
+    assert_eq!(entries.len(), 1);
+    assert_ne!(entries[0].items[0].before, entries[0].items[0].after);
 }
 
-/// An attribute whose value is not a string survives too. It used to vanish
-/// with no warning at all, which was the quietest of the losses.
 #[test]
-fn a_write_keeps_a_non_string_attribute() {
-    let (_dir, ctx) = planted("[pantry]\nflour = { quantity = 2 }\n");
+fn a_dry_run_works_out_the_quantity_and_writes_nothing() {
+    let (_dir, ctx) = planted(KITCHEN);
 
-    add(
+    let taken = take(
         &ctx,
-        AddRequest {
-            section: "pantry".to_string(),
-            name: "sugar".to_string(),
-            ..Default::default()
+        AdjustRequest {
+            dry_run: true,
+            ..adjusting("eggs", "2")
         },
     )
-    .expect("adds");
+    .unwrap()
+    .value;
 
-    let written = read_back(&ctx);
-    assert!(
-        written.contains("quantity = 2"),
-        "the bare number must survive\n{written}"
-    );
+    assert_eq!(taken.after, "4");
+    assert_eq!(read_back(&ctx), KITCHEN);
+    assert!(history(&ctx).unwrap().value.is_empty());
 }
 
 #[test]
-fn a_write_keeps_section_and_item_order() {
-    let (_dir, ctx) = planted(
-        "[dairy]\nzucchini = \"2\"\napple = \"5\"\nmango = \"3\"\n\n[produce]\nleek = \"1\"\n\n[bakery]\nbread = \"1\"\n",
+fn an_amount_that_does_not_fit_the_item_is_refused_and_nothing_is_written() {
+    let (_dir, ctx) = planted(KITCHEN);
+
+    for (name, amount, expected) in [
+        (
+            "flour",
+            "200%ml",
+            "'flour' is kept in kg, which 200%ml does not convert to",
+        ),
+        ("salt", "1%tsp", "'salt' in section 'pantry' is not counted"),
+        ("flour", "some", "'some' is not a number and a unit"),
+        ("sugar", "1%kg", "item 'sugar' not found in the pantry"),
+    ] {
+        let error = take(&ctx, adjusting(name, amount)).unwrap_err();
+        assert!(
+            matches!(&error, CoreError::PantryEdit { message } if message.contains(expected)),
+            "{name} {amount}: {error}"
+        );
+    }
+    assert_eq!(read_back(&ctx), KITCHEN);
+}
+
+#[test]
+fn a_name_in_more_than_one_section_needs_the_section() {
+    let (_dir, ctx) = planted("[fridge]\nbutter = \"250%g\"\n\n[freezer]\nbutter = \"500%g\"\n");
+
+    let error = take(&ctx, adjusting("butter", "50%g")).unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("'butter' is in more than one section (fridge, freezer); say which"),
+        "{error}"
     );
 
-    add(
+    let taken = take(
         &ctx,
-        AddRequest {
-            section: "dairy".to_string(),
-            name: "banana".to_string(),
-            ..Default::default()
+        AdjustRequest {
+            section: Some("freezer".to_string()),
+            ..adjusting("butter", "50%g")
         },
     )
-    .expect("adds");
+    .unwrap()
+    .value;
+    assert_eq!(taken.after, "450%g");
+}
 
-    let written = read_back(&ctx);
-    let at = |needle: &str| written.find(needle).unwrap_or_else(|| panic!("{needle}"));
-    assert!(
-        at("zucchini") < at("apple") && at("apple") < at("mango") && at("mango") < at("banana"),
-        "items keep file order, with the new one last\n{written}"
-    );
-    assert!(
-        at("[dairy]") < at("[produce]") && at("[produce]") < at("[bakery]"),
-        "sections keep file order\n{written}"
-    );
+#[test]
+fn adjusting_is_recorded_in_the_journal_and_can_be_undone() {
+    let (_dir, ctx) = planted(KITCHEN);
+    take(&ctx, adjusting("flour", "200%g")).unwrap();
+
+    let journal = history(&ctx).unwrap().value;
+    assert_eq!(journal.len(), 1);
+    assert_eq!(journal[0].operation, "use flour 200%g");
+
+    undo(&ctx, UndoRequest::default()).unwrap();
+    assert_eq!(read_back(&ctx), KITCHEN);
+}
+
+// ---------------------------------------------------------------------------
+// batch
+// ---------------------------------------------------------------------------
+
+fn moving(section: &str, name: &str, to: &str) -> BatchEdit {
+    BatchEdit::Move(MoveRequest {
+        section: section.to_string(),
+        name: name.to_string(),
+        to: to.to_string(),
+    })
 }
 
-/// A top-level item can only be written `name = "quantity"`, so giving one an
-/// expiry is refused rather than done wrong.
-///
-/// The two wrong ways are both worse. Dropping the attribute silently is what
-/// this used to do. Writing the inline table it would need is worse still: the
-/// parser reads a top-level inline table as a *section header*, so `salt` would
-/// stop being an item and become a section holding `quantity` and `expire` —
-/// the corruption in <https://github.com/cooklang/cookcli/issues/429>.
 #[test]
-fn a_general_item_cannot_be_given_an_attribute_it_has_no_room_for() {
-    let original =
-        "salt = \"1%kg\"\n\n[dairy]\nmilk = { quantity = \"2%l\", expire = \"2025-12-01\" }\n";
-    let (_dir, ctx) = planted(original);
+fn a_batch_applies_every_edit_in_one_write_and_one_journal_entry() {
+    let (_dir, ctx) = planted(KITCHEN);
 
-    let error = update(
+    batch(
         &ctx,
-        UpdateRequest {
-            section: "general".to_string(),
-            name: "salt".to_string(),
-            expire: Some("2025-11-11".to_string()),
-            ..Default::default()
+        BatchRequest {
+            edits: vec![
+                BatchEdit::Add(AddRequest {
+                    section: "dairy".to_string(),
+                    name: "butter".to_string(),
+                    quantity: Some("250%g".to_string()),
+                    ..Default::default()
+                }),
+                BatchEdit::Update(UpdateRequest {
+                    section: "dairy".to_string(),
+                    name: "butter".to_string(),
+                    low: Some("50%g".to_string()),
+                    ..Default::default()
+                }),
+                BatchEdit::Remove(RemoveRequest {
+                    section: "pantry".to_string(),
+                    name: "salt".to_string(),
+                }),
+                moving("dairy", "milk", "fridge"),
+            ],
         },
     )
-    .expect_err("must refuse");
+    .unwrap();
 
-    assert!(
-        error.to_string().contains("move it into a section"),
-        "the message should say what to do instead: {error}"
+    assert_eq!(
+        read_back(&ctx),
+        "\
+# Kitchen
+[pantry]
+flour = { quantity = \"1%kg\", low = \"200%g\" }
+eggs = \"6\"
+
+[dairy]
+butter = { quantity = \"250%g\", low = \"50%g\" }
+
+[fridge]
+milk = \"300%ml\"
+"
+    );
+    let journal = history(&ctx).unwrap().value;
+    assert_eq!(journal.len(), 1);
+    assert_eq!(journal[0].operation, "batch");
+    let touched: Vec<(&str, &str)> = journal[0]
+        .items
+        .iter()
+        .map(|item| (item.section.as_str(), item.name.as_str()))
+        .collect();
+    assert_eq!(
+        touched,
+        [
+            ("dairy", "butter"),
+            ("pantry", "salt"),
+            ("dairy", "milk"),
+            ("fridge", "milk")
+        ]
+    );
+
+    undo(&ctx, UndoRequest::default()).unwrap();
+    let reread = load(&ctx).unwrap().value;
+    let items: Vec<(&str, &str)> = reread
+        .items()
+        .map(|item| (item.section.as_str(), item.name.as_str()))
+        .collect();
+    assert_eq!(
+        items,
+        [
+            ("pantry", "flour"),
+            ("pantry", "eggs"),
+            ("pantry", "salt"),
+            ("dairy", "milk")
+        ],
+        "one undo takes the whole batch back, putting what it restores last"
     );
-    assert_eq!(read_back(&ctx), original, "nothing may be written");
 }
 
-/// Its quantity can still be changed, in place and in the short form.
 #[test]
-fn a_general_item_quantity_can_be_updated() {
-    let (_dir, ctx) = planted("salt = \"1%kg\"\n\n[dairy]\nmilk = \"2%l\"\n");
+fn a_refused_edit_writes_nothing_and_says_which_it_was() {
+    let (_dir, ctx) = planted(KITCHEN);
 
-    update(
+    let error = batch(
         &ctx,
-        UpdateRequest {
-            section: "general".to_string(),
-            name: "salt".to_string(),
-            quantity: Some("2%kg".to_string()),
-            ..Default::default()
+        BatchRequest {
+            edits: vec![
+                BatchEdit::Remove(RemoveRequest {
+                    section: "pantry".to_string(),
+                    name: "salt".to_string(),
+                }),
+                moving("pantry", "sugar", "fridge"),
+            ],
         },
     )
-    .expect("updates");
+    .unwrap_err();
 
-    let written = read_back(&ctx);
-    assert_eq!(written, "salt = \"2%kg\"\n\n[dairy]\nmilk = \"2%l\"\n");
-    assert!(!written.contains("[general]"), "{written}");
+    assert_eq!(
+        error.to_string(),
+        "cannot change the pantry: edit 2: item 'sugar' not found in section 'pantry'"
+    );
+    assert_eq!(read_back(&ctx), KITCHEN);
+    assert!(history(&ctx).unwrap().value.is_empty());
 }
 
-/// A bare general item gains an empty quantity, because the only shape the
-/// top level can be written in is `name = "quantity"`.
 #[test]
-fn a_bare_general_item_comes_back_with_an_empty_quantity() {
-    let (_dir, ctx) = planted("salt = \"\"\n\n[dairy]\nmilk = \"1%l\"\n");
+fn a_move_keeps_everything_the_entry_carries() {
+    let (_dir, ctx) = planted(
+        "[dairy]\nmilk = { quantity = \"1%l\", restock = \"2%l\" }\n\n[fridge]\ncream = \"1\"\n",
+    );
 
-    let contents = add(
+    batch(
         &ctx,
-        AddRequest {
-            section: "dairy".to_string(),
-            name: "cheese".to_string(),
-            ..Default::default()
+        BatchRequest {
+            edits: vec![moving("dairy", "milk", "fridge")],
         },
     )
-    .expect("adds")
-    .into_value();
+    .unwrap();
 
-    let salt = contents
-        .items()
-        .find(|item| item.name == "salt")
-        .expect("salt survived");
     assert_eq!(
-        salt.quantity.as_deref(),
-        Some(""),
-        "not None, which is what a bare sectioned item gives"
+        read_back(&ctx),
+        "\n[fridge]\ncream = \"1\"\nmilk = { quantity = \"1%l\", restock = \"2%l\" }\n"
     );
 }
 
-/// A non-ASCII item name is not a bare TOML key, so a file that is going to
-/// hold one has to quote it — which `add` does, and a person writing the file
-/// by hand has to.
 #[test]
-fn a_name_that_is_not_a_bare_toml_key_has_to_be_quoted() {
-    let (_dir, ctx) = planted("[mejeri]\nsmörgås = \"1\"\n");
+fn a_move_is_refused_where_the_item_would_not_fit() {
+    let (_dir, ctx) = planted(KITCHEN);
 
-    assert!(matches!(
-        add(
-            &ctx,
-            AddRequest {
-                section: "mejeri".to_string(),
-                name: "äpple".to_string(),
-                ..Default::default()
-            },
+    for (edit, expected) in [
+        (
+            moving("dairy", "milk", "dairy"),
+            "edit 1: item 'milk' already exists in section 'dairy'",
         ),
-        Err(CoreError::Config { .. })
+        (
+            moving("pantry", "flour", "general"),
+            "edit 1: item 'flour' carries more than a quantity",
+        ),
+    ] {
+        let error = batch(&ctx, BatchRequest { edits: vec![edit] }).unwrap_err();
+        assert!(error.to_string().contains(expected), "{error}");
+    }
+    assert_eq!(read_back(&ctx), KITCHEN);
+}
+
+#[test]
+fn an_empty_batch_is_refused() {
+    let (_dir, ctx) = planted(KITCHEN);
+    assert!(matches!(
+        batch(&ctx, BatchRequest::default()),
+        Err(CoreError::PantryEdit { .. })
     ));
 }
 
-/// A section written as an array of item names comes back as a table. The
-/// items survive; the syntax does not.
+// ---------------------------------------------------------------------------
+// journal
+// ---------------------------------------------------------------------------
+
+/// Each write is one change in the journal, saying where it came from and
+/// what the item was before and after; undoing walks back through them and
+/// leaves the file as it was.
+#[test]
+fn every_write_is_journalled_and_undo_walks_back_through_them() {
+    let (_dir, ctx) = planted(SMALL);
+    let ctx = ctx.with_origin("cli");
+    add(
+        &ctx,
+        AddRequest {
+            section: "pantry".to_string(),
+            name: "sugar".to_string(),
+            quantity: Some("1%kg".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let added = read_back(&ctx);
+    update(
+        &ctx,
+        UpdateRequest {
+            section: "pantry".to_string(),
+            name: "flour".to_string(),
+            quantity: Some("500%g".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    remove(
+        &ctx,
+        RemoveRequest {
+            section: "pantry".to_string(),
+            name: "sugar".to_string(),
+        },
+    )
+    .unwrap();
+
+    let journal = history(&ctx).unwrap().value;
+    let summary: Vec<(u64, &str, Option<&str>)> = journal
+        .iter()
+        .map(|change| {
+            (
+                change.id,
+                change.operation.as_str(),
+                change.origin.as_deref(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            (1, "add", Some("cli")),
+            (2, "update", Some("cli")),
+            (3, "remove", Some("cli"))
+        ]
+    );
+    assert_eq!(
+        journal[1].items[0].before.as_deref(),
+        Some(r#"{ quantity = "1%kg", low = "200%g" }"#)
+    );
+    assert_eq!(
+        journal[1].items[0].after.as_deref(),
+        Some(r#"{ quantity = "500%g", low = "200%g" }"#)
+    );
+
+    let undone = undo(&ctx, UndoRequest { count: 2 }).unwrap().value;
+    let operations: Vec<&str> = undone.iter().map(|c| c.operation.as_str()).collect();
+    assert_eq!(operations, ["remove", "update"]);
+    assert_eq!(read_back(&ctx), added);
+
+    // The undos are changes too, and the next undo goes further back rather
+    // than redoing them.
+    let journal = history(&ctx).unwrap().value;
+    let undoes: Vec<Option<u64>> = journal.iter().map(|c| c.undoes).collect();
+    assert_eq!(undoes, [None, None, None, Some(3), Some(2)]);
+    assert_eq!(journal[3].operation, "undo remove");
+
+    let undone = undo(&ctx, UndoRequest::default()).unwrap().value;
+    assert_eq!(undone[0].operation, "add");
+    assert_eq!(read_back(&ctx), SMALL);
+    assert!(undo(&ctx, UndoRequest::default()).unwrap().value.is_empty());
+}
+
+/// Putting back what a change replaced would throw away whatever was written
+/// since, so undo refuses and leaves the file alone.
+#[test]
+fn undo_refuses_an_item_that_has_changed_since() {
+    let (_dir, ctx) = planted(SMALL);
+    update(
+        &ctx,
+        UpdateRequest {
+            section: "pantry".to_string(),
+            name: "flour".to_string(),
+            quantity: Some("500%g".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let edited = "[pantry]\nflour = { quantity = \"2%kg\", low = \"200%g\" }\n";
+    write(ctx.pantry().path().unwrap(), edited);
+
+    match undo(&ctx, UndoRequest::default()) {
+        Err(CoreError::PantryHistory { message }) => {
+            assert!(message.contains("'flour' in section 'pantry'"), "{message}")
+        }
+        other => panic!("expected PantryHistory, got {:?}", other.map(|o| o.value)),
+    }
+    assert_eq!(read_back(&ctx), edited);
+}
+
+/// Cooking a recipe touches several items but is one thing to take back.
+#[test]
+fn a_consumed_recipe_is_undone_in_one_step() {
+    let (_dir, ctx, _) = cooked(false);
+    let journal = history(&ctx).unwrap().value;
+    assert_eq!(journal.len(), 1);
+    assert_eq!(journal[0].operation, "consume Pancakes:2");
+
+    undo(&ctx, UndoRequest::default()).unwrap();
+    assert_eq!(read_back(&ctx), KITCHEN);
+}
+
+// ---------------------------------------------------------------------------
+// Where a change may and may not be written
+// ---------------------------------------------------------------------------
+
+/// Writing an editor's unsaved buffer to a path this crate made up would be
+/// worse than refusing, so all three refuse.
+#[test]
+fn nothing_writes_to_an_inline_pantry() {
+    let dir = temp();
+    let base = base(&dir);
+    let ctx = Context::new(base.clone()).with_pantry(ConfigSource::Inline(SMALL.to_string()));
+
+    let refused = |result: Result<Outcome<PantryContents>, CoreError>, what: &str| match result {
+        Err(CoreError::ReadOnlyConfig { kind }) => assert_eq!(kind, "pantry", "{what}"),
+        other => panic!(
+            "expected ReadOnlyConfig from {what}, got {:?}",
+            other.map(|o| o.value)
+        ),
+    };
+
+    refused(
+        add(
+            &ctx,
+            AddRequest {
+                section: "pantry".to_string(),
+                name: "sugar".to_string(),
+                ..Default::default()
+            },
+        ),
+        "add",
+    );
+    refused(
+        remove(
+            &ctx,
+            RemoveRequest {
+                section: "pantry".to_string(),
+                name: "flour".to_string(),
+            },
+        ),
+        "remove",
+    );
+    refused(
+        update(
+            &ctx,
+            UpdateRequest {
+                section: "pantry".to_string(),
+                name: "flour".to_string(),
+                quantity: Some("2%kg".to_string()),
+                ..Default::default()
+            },
+        ),
+        "update",
+    );
+
+    assert!(
+        !base.join("config").exists(),
+        "a refused write must not leave a file behind"
+    );
+}
+
+/// `add` creates a pantry where there is none; the other two have nothing to
+/// change and say so.
+#[test]
+fn remove_and_update_need_a_pantry_that_exists() {
+    let dir = temp();
+    let ctx = Context::new(base(&dir));
+
+    for result in [
+        remove(
+            &ctx,
+            RemoveRequest {
+                section: "pantry".to_string(),
+                name: "flour".to_string(),
+            },
+        ),
+        update(
+            &ctx,
+            UpdateRequest {
+                section: "pantry".to_string(),
+                name: "flour".to_string(),
+                quantity: Some("2%kg".to_string()),
+                ..Default::default()
+            },
+        ),
+    ] {
+        match result {
+            Err(CoreError::MissingConfig { kind }) => assert_eq!(kind, "pantry"),
+            other => panic!("expected MissingConfig, got {:?}", other.map(|o| o.value)),
+        }
+    }
+}
+
+/// A path-backed pantry that is not there is a different thing from no pantry
+/// at all: something named a file and it could not be read.
+#[test]
+fn removing_from_a_pantry_path_that_does_not_exist_is_an_io_error() {
+    let dir = temp();
+    let base = base(&dir);
+    let ctx = ctx_at(&base);
+
+    match remove(
+        &ctx,
+        RemoveRequest {
+            section: "pantry".to_string(),
+            name: "flour".to_string(),
+        },
+    ) {
+        Err(CoreError::Io { path, source }) => {
+            assert_eq!(path, pantry_path(&base));
+            assert_eq!(source.kind(), std::io::ErrorKind::NotFound);
+        }
+        other => panic!("expected Io, got {:?}", other.map(|o| o.value)),
+    }
+}
+
+#[test]
+fn a_pantry_that_cannot_be_parsed_stops_a_change() {
+    let (_dir, ctx) = planted("[unclosed\n");
+
+    match add(
+        &ctx,
+        AddRequest {
+            section: "pantry".to_string(),
+            name: "sugar".to_string(),
+            ..Default::default()
+        },
+    ) {
+        Err(CoreError::Config { path, message }) => {
+            assert_eq!(path.as_deref(), ctx.pantry().path());
+            assert!(!message.is_empty());
+            assert!(!message.contains('\n'), "one line: {message:?}");
+        }
+        other => panic!("expected Config, got {:?}", other.map(|o| o.value)),
+    }
+    assert_eq!(read_back(&ctx), "[unclosed\n", "nothing may be written");
+}
+
+// ---------------------------------------------------------------------------
+// What a write keeps, and what it throws away
+// ---------------------------------------------------------------------------
+
+/// Comments, indentation and the short item form all survive a change, because
+/// the file is edited as a document rather than rebuilt from a parsed model.
+///
+/// The whole original text is asserted with only the new line added, which is
+/// the strongest form this can take: anything reformatted anywhere fails.
+#[test]
+fn a_write_keeps_comments_and_layout() {
+    let original = "# my pantry\n\n[pantry]\n# staple\n  flour   =   \"1%kg\"   # a whole bag\n";
+    let (_dir, ctx) = planted(original);
+
+    add(
+        &ctx,
+        AddRequest {
+            section: "pantry".to_string(),
+            name: "sugar".to_string(),
+            ..Default::default()
+        },
+    )
+    .expect("adds");
+
+    assert_eq!(
+        read_back(&ctx),
+        format!("{original}sugar = \"\"\n"),
+        "only the new item may be added"
+    );
+}
+
+/// An attribute `cooklang` does not model survives an unrelated change. The
+/// parser still warns that it does not understand it — that warning is about
+/// what `cook pantry list` can show, not about anything being lost.
+#[test]
+fn a_write_keeps_attributes_cooklang_does_not_model() {
+    let (_dir, ctx) = planted("[pantry]\nflour = { quantity = \"1%kg\", shelf = \"top\" }\n");
+
+    let outcome = add(
+        &ctx,
+        AddRequest {
+            section: "pantry".to_string(),
+            name: "sugar".to_string(),
+            ..Default::default()
+        },
+    )
+    .expect("adds");
+
+    assert!(
+        outcome
+            .diagnostics
+            .iter()
+            .any(|d| d.severity == Severity::Warning && d.message.contains("shelf")),
+        "the unknown attribute must be reported: {:?}",
+        outcome.diagnostics
+    );
+    let written = read_back(&ctx);
+    assert!(
+        written.contains("shelf = \"top\""),
+        "the attribute must survive\n{written}"
+    );
+    assert!(written.contains("1%kg"), "{written}");
+}
+
+/// An attribute whose value is not a string survives too. It used to vanish
+/// with no warning at all, which was the quietest of the losses.
+#[test]
+fn a_write_keeps_a_non_string_attribute() {
+    let (_dir, ctx) = planted("[pantry]\nflour = { quantity = 2 }\n");
+
+    add(
+        &ctx,
+        AddRequest {
+            section: "pantry".to_string(),
+            name: "sugar".to_string(),
+            ..Default::default()
+        },
+    )
+    .expect("adds");
+
+    let written = read_back(&ctx);
+    assert!(
+        written.contains("quantity = 2"),
+        "the bare number must survive\n{written}"
+    );
+}
+
+#[test]
+fn a_write_keeps_section_and_item_order() {
+    let (_dir, ctx) = planted(
+        "[dairy]\nzucchini = \"2\"\napple = \"5\"\nmango = \"3\"\n\n[produce]\nleek = \"1\"\n\n[bakery]\nbread = \"1\"\n",
+    );
+
+    add(
+        &ctx,
+        AddRequest {
+            section: "dairy".to_string(),
+            name: "banana".to_string(),
+            ..Default::default()
+        },
+    )
+    .expect("adds");
+
+    let written = read_back(&ctx);
+    let at = |needle: &str| written.find(needle).unwrap_or_else(|| panic!("{needle}"));
+    assert!(
+        at("zucchini") < at("apple") && at("apple") < at("mango") && at("mango") < at("banana"),
+        "items keep file order, with the new one last\n{written}"
+    );
+    assert!(
+        at("[dairy]") < at("[produce]") && at("[produce]") < at("[bakery]"),
+        "sections keep file order\n{written}"
+    );
+}
+
+/// A top-level item can only be written `name = "quantity"`, so giving one an
+/// expiry is refused rather than done wrong.
+///
+/// The two wrong ways are both worse. Dropping the attribute silently is what
+/// this used to do. Writing the inline table it would need is worse still: the
+/// parser reads a top-level inline table as a *section header*, so `salt` would
+/// stop being an item and become a section holding `quantity` and `expire` —
+/// the corruption in <https://github.com/cooklang/cookcli/issues/429>.
+#[test]
+fn a_general_item_cannot_be_given_an_attribute_it_has_no_room_for() {
+    let original =
+        "salt = \"1%kg\"\n\n[dairy]\nmilk = { quantity = \"2%l\", expire = \"2025-12-01\" }\n";
+    let (_dir, ctx) = planted(original);
+
+    let error = update(
+        &ctx,
+        UpdateRequest {
+            section: "general".to_string(),
+            name: "salt".to_string(),
+            expire: Some("2025-11-11".to_string()),
+            ..Default::default()
+        },
+    )
+    .expect_err("must refuse");
+
+    assert!(
+        error.to_string().contains("move it into a section"),
+        "the message should say what to do instead: {error}"
+    );
+    assert_eq!(read_back(&ctx), original, "nothing may be written");
+}
+
+/// Its quantity can still be changed, in place and in the short form.
+#[test]
+fn a_general_item_quantity_can_be_updated() {
+    let (_dir, ctx) = planted("salt = \"1%kg\"\n\n[dairy]\nmilk = \"2%l\"\n");
+
+    update(
+        &ctx,
+        UpdateRequest {
+            section: "general".to_string(),
+            name: "salt".to_string(),
+            quantity: Some("2%kg".to_string()),
+            ..Default::default()
+        },
+    )
+    .expect("updates");
+
+    let written = read_back(&ctx);
+    assert_eq!(written, "salt = \"2%kg\"\n\n[dairy]\nmilk = \"2%l\"\n");
+    assert!(!written.contains("[general]"), "{written}");
+}
+
+/// A bare general item gains an empty quantity, because the only shape the
+/// top level can be written in is `name = "quantity"`.
+#[test]
+fn a_bare_general_item_comes_back_with_an_empty_quantity() {
+    let (_dir, ctx) = planted("salt = \"\"\n\n[dairy]\nmilk = \"1%l\"\n");
+
+    let contents = add(
+        &ctx,
+        AddRequest {
+            section: "dairy".to_string(),
+            name: "cheese".to_string(),
+            ..Default::default()
+        },
+    )
+    .expect("adds")
+    .into_value();
+
+    let salt = contents
+        .items()
+        .find(|item| item.name == "salt")
+        .expect("salt survived");
+    assert_eq!(
+        salt.quantity.as_deref(),
+        Some(""),
+        "not None, which is what a bare sectioned item gives"
+    );
+}
+
+/// A non-ASCII item name is not a bare TOML key, so a file that is going to
+/// hold one has to quote it — which `add` does, and a person writing the file
+/// by hand has to.
+#[test]
+fn a_name_that_is_not_a_bare_toml_key_has_to_be_quoted() {
+    let (_dir, ctx) = planted("[mejeri]\nsmörgås = \"1\"\n");
+
+    assert!(matches!(
+        add(
+            &ctx,
+            AddRequest {
+                section: "mejeri".to_string(),
+                name: "äpple".to_string(),
+                ..Default::default()
+            },
+        ),
+        Err(CoreError::Config { .. })
+    ));
+}
+
+/// A section written as an array of item names comes back as a table. The
+/// items survive; the syntax does not.
+#[test]
+fn a_section_written_as_an_array_comes_back_as_a_table() {
+    let (_dir, ctx) = planted("fridge = [\"milk\", \"eggs\"]\n");
+
+    add(
+        &ctx,
+        AddRequest {
+            section: "fridge".to_string(),
+            name: "butter".to_string(),
+            ..Default::default()
+        },
+    )
+    .expect("adds");
+
+    let written = read_back(&ctx);
+    assert!(
+        written.contains("[fridge]"),
+        "the array became a section table\n{written}"
+    );
+    assert!(
+        !written.contains("[\"milk\""),
+        "and the array syntax is gone\n{written}"
+    );
+    assert_eq!(
+        names(&load(&ctx).expect("reads back").into_value().sections[0].items),
+        ["milk", "eggs", "butter"],
+        "the items themselves survive"
+    );
+}
+
+#[test]
+fn a_write_keeps_non_ascii_names() {
+    let (_dir, ctx) = planted("[mejeri]\n\"smörgås\" = \"1\"\n");
+
+    add(
+        &ctx,
+        AddRequest {
+            section: "mejeri".to_string(),
+            name: "äpple".to_string(),
+            quantity: Some("3".to_string()),
+            ..Default::default()
+        },
+    )
+    .expect("adds");
+
+    let reread = load(&ctx).expect("reads back").into_value();
+    assert_eq!(names(&reread.sections[0].items), ["smörgås", "äpple"]);
+}
+
+/// What the three return is what the file now says, so a caller need not read
+/// it back.
+#[test]
+fn a_change_returns_the_pantry_it_wrote() {
+    let (_dir, ctx) = planted(SMALL);
+
+    let returned = add(
+        &ctx,
+        AddRequest {
+            section: "dairy".to_string(),
+            name: "milk".to_string(),
+            quantity: Some("1%l".to_string()),
+            ..Default::default()
+        },
+    )
+    .expect("adds")
+    .into_value();
+
+    assert_eq!(returned, load(&ctx).expect("reads back").into_value());
+}
+
+// ---------------------------------------------------------------------------
+// Writing the file itself
+// ---------------------------------------------------------------------------
+
+#[test]
+fn a_write_leaves_no_temporary_file_behind() {
+    let (_dir, ctx) = planted(SMALL);
+
+    add(
+        &ctx,
+        AddRequest {
+            section: "pantry".to_string(),
+            name: "sugar".to_string(),
+            ..Default::default()
+        },
+    )
+    .expect("adds");
+
+    let config = ctx.pantry().path().unwrap().parent().unwrap().to_owned();
+    let mut left: Vec<_> = std::fs::read_dir(&config)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    left.sort();
+    assert_eq!(
+        left,
+        ["pantry.conf", "pantry.journal"],
+        "only the pantry and its journal are left"
+    );
+}
+
+/// A pantry symlinked in from a dotfiles repository must be written through,
+/// not replaced by a regular file.
+#[cfg(unix)]
+#[test]
+fn a_write_follows_a_symlinked_pantry() {
+    let dir = temp();
+    let base = base(&dir);
+    let real = base.join("dotfiles").join("pantry.conf");
+    write(&real, SMALL);
+    std::fs::create_dir_all(base.join("config")).unwrap();
+    std::os::unix::fs::symlink(real.as_std_path(), pantry_path(&base).as_std_path()).unwrap();
+
+    let ctx = ctx_at(&base);
+    add(
+        &ctx,
+        AddRequest {
+            section: "pantry".to_string(),
+            name: "sugar".to_string(),
+            ..Default::default()
+        },
+    )
+    .expect("adds");
+
+    assert!(
+        std::fs::symlink_metadata(pantry_path(&base).as_std_path())
+            .unwrap()
+            .file_type()
+            .is_symlink(),
+        "the link itself must survive"
+    );
+    assert!(
+        std::fs::read_to_string(real.as_std_path())
+            .unwrap()
+            .contains("sugar"),
+        "and the change must land on the file it points at"
+    );
+}
+
+/// A temporary file is created from the process umask, which is usually more
+/// permissive than a pantry someone has locked down.
+#[cfg(unix)]
+#[test]
+fn a_write_keeps_the_pantry_file_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let (_dir, ctx) = planted(SMALL);
+    let path = ctx.pantry().path().unwrap().to_owned();
+    std::fs::set_permissions(path.as_std_path(), std::fs::Permissions::from_mode(0o600)).unwrap();
+
+    add(
+        &ctx,
+        AddRequest {
+            section: "pantry".to_string(),
+            name: "sugar".to_string(),
+            ..Default::default()
+        },
+    )
+    .expect("adds");
+
+    let mode = std::fs::metadata(path.as_std_path())
+        .unwrap()
+        .permissions()
+        .mode();
+    assert_eq!(mode & 0o777, 0o600, "the pantry must not become readable");
+}
+
+/// The property the whole write dance is for: whatever goes wrong, the pantry
+/// that was there is still there.
+///
+/// The failure is forced by making the directory unwritable, so the test can
+/// only run as a user the permission applies to — root is not one, and the
+/// probe below says so rather than the test quietly passing.
+#[cfg(unix)]
+#[test]
+fn a_failed_write_leaves_the_pantry_untouched() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let (_dir, ctx) = planted(SMALL);
+    let config = ctx.pantry().path().unwrap().parent().unwrap().to_owned();
+
+    std::fs::set_permissions(config.as_std_path(), std::fs::Permissions::from_mode(0o500)).unwrap();
+    let unwritable = std::fs::File::create(config.join("probe").as_std_path()).is_err();
+
+    let result = add(
+        &ctx,
+        AddRequest {
+            section: "pantry".to_string(),
+            name: "sugar".to_string(),
+            ..Default::default()
+        },
+    );
+
+    // Restore before asserting, so a failure does not also break the cleanup.
+    std::fs::set_permissions(config.as_std_path(), std::fs::Permissions::from_mode(0o700)).unwrap();
+
+    assert!(
+        unwritable,
+        "this test needs a user that a read-only directory applies to; \
+         running as root makes it prove nothing"
+    );
+    match result {
+        Err(CoreError::Io { path, .. }) => assert_eq!(path, *ctx.pantry().path().unwrap()),
+        other => panic!("expected Io, got {:?}", other.map(|o| o.value)),
+    }
+    assert_eq!(read_back(&ctx), SMALL, "the pantry is exactly as it was");
+    let left: Vec<_> = std::fs::read_dir(config.as_std_path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(left, ["pantry.conf"], "and no temporary file is left");
+}
+
+/// A pantry that exists but cannot be read must stop the change. Treating an
+/// unreadable file as an empty one would replace it with a one-item pantry —
+/// the worst thing any of this can do.
+///
+/// Unreadable is arranged with a mode, so the test only means anything as a
+/// user that mode applies to; the probe says so rather than passing quietly
+/// under root.
+#[cfg(unix)]
+#[test]
+fn add_refuses_a_pantry_it_cannot_read_rather_than_replacing_it() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let (_dir, ctx) = planted(SMALL);
+    let path = ctx.pantry().path().unwrap().to_owned();
+    std::fs::set_permissions(path.as_std_path(), std::fs::Permissions::from_mode(0o000)).unwrap();
+    let unreadable = std::fs::read_to_string(path.as_std_path()).is_err();
+
+    let result = add(
+        &ctx,
+        AddRequest {
+            section: "pantry".to_string(),
+            name: "sugar".to_string(),
+            ..Default::default()
+        },
+    );
+
+    std::fs::set_permissions(path.as_std_path(), std::fs::Permissions::from_mode(0o600)).unwrap();
+
+    assert!(
+        unreadable,
+        "this test needs a user that a 000 file applies to; running as root \
+         makes it prove nothing"
+    );
+    match result {
+        Err(CoreError::Io { path: named, .. }) => assert_eq!(named, path),
+        other => panic!("expected Io, got {:?}", other.map(|o| o.value)),
+    }
+    assert_eq!(read_back(&ctx), SMALL, "the pantry is exactly as it was");
+}
+
+/// [`write_atomically`] cleans up after itself when the rename fails, which is
+/// the one failure that happens after the temporary file exists.
+///
+/// Renaming a file onto a directory cannot succeed, which is a portable way to
+/// reach that branch — the pantry commands themselves stop earlier than this,
+/// because a directory cannot be read as a pantry either.
+#[test]
+fn a_write_that_fails_after_writing_its_temporary_file_still_removes_it() {
+    let dir = temp();
+    let base = base(&dir);
+    let target = base.join("a-directory");
+    std::fs::create_dir_all(target.join("child").as_std_path()).unwrap();
+
+    match write_atomically(&target, "contents") {
+        Err(CoreError::Io { path, .. }) => assert_eq!(path, target),
+        other => panic!("expected Io, got {other:?}"),
+    }
+
+    let left: Vec<_> = std::fs::read_dir(base.as_std_path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(left, ["a-directory"], "no temporary file is left behind");
+}
+
+// ---------------------------------------------------------------------------
+// The document survives an edit
+// ---------------------------------------------------------------------------
+
+/// The reproduction from <https://github.com/cooklang/cookcli/issues/429>,
+/// asserted end to end: an unrelated `add` used to destroy the top-level
+/// `salt`, drop the comment and drop `shelf`. The whole file is compared, so
+/// anything reformatted anywhere fails.
+#[test]
+fn an_unrelated_add_changes_only_the_line_it_adds() {
+    let original = "# my pantry notes\n\
+                    salt = { quantity = \"1%kg\", expire = \"2027-01-01\" }\n\
+                    \n\
+                    [fridge]\n\
+                    milk = { quantity = \"1%l\", shelf = \"top\" }\n";
+    let (_dir, ctx) = planted(original);
+
+    add(
+        &ctx,
+        AddRequest {
+            section: "fridge".to_string(),
+            name: "butter".to_string(),
+            quantity: Some("200%g".to_string()),
+            ..Default::default()
+        },
+    )
+    .expect("adds");
+
+    assert_eq!(
+        read_back(&ctx),
+        format!("{original}butter = \"200%g\"\n"),
+        "only the new item may appear"
+    );
+}
+
+/// A section written as a list of names has nowhere to put a quantity, so an
+/// edit has to rewrite it. Every name in it survives that, and the caller is
+/// told the shape changed.
+#[test]
+fn an_array_section_is_rewritten_as_a_table_keeping_its_items() {
+    let (_dir, ctx) = planted("fridge = [\"milk\", \"eggs\"]\n");
+
+    let outcome = add(
+        &ctx,
+        AddRequest {
+            section: "fridge".to_string(),
+            name: "butter".to_string(),
+            quantity: Some("200%g".to_string()),
+            ..Default::default()
+        },
+    )
+    .expect("adds");
+
+    assert!(
+        outcome
+            .diagnostics
+            .iter()
+            .any(|d| d.severity == Severity::Warning && d.message.contains("list of names")),
+        "the rewrite must be reported: {:?}",
+        outcome.diagnostics
+    );
+    assert_eq!(
+        names(&outcome.value.sections[0].items),
+        ["milk", "eggs", "butter"],
+        "no name may be lost to the rewrite"
+    );
+    assert!(read_back(&ctx).contains("[fridge]"));
+}
+
+/// An item whose value is neither a quantity nor a set of attributes is
+/// refused rather than overwritten, and nothing is written at all.
+#[test]
+fn an_update_refuses_an_item_written_as_a_bare_number() {
+    let original = "[pantry]\nflour = 3\n";
+    let (_dir, ctx) = planted(original);
+
+    let error = update(
+        &ctx,
+        UpdateRequest {
+            section: "pantry".to_string(),
+            name: "flour".to_string(),
+            quantity: Some("1%kg".to_string()),
+            ..Default::default()
+        },
+    )
+    .expect_err("must refuse");
+
+    assert!(
+        error.to_string().contains("bare number"),
+        "the message should say what it found: {error}"
+    );
+    assert_eq!(read_back(&ctx), original, "nothing may be written");
+}
+
+/// Adding a `general` item with an attribute it has no room for is refused for
+/// the same reason `update` is: writing the inline table it would need turns
+/// the item into a section.
 #[test]
-fn a_section_written_as_an_array_comes_back_as_a_table() {
-    let (_dir, ctx) = planted("fridge = [\"milk\", \"eggs\"]\n");
+fn a_general_add_with_attributes_is_refused() {
+    let original = "salt = \"1%kg\"\n";
+    let (_dir, ctx) = planted(original);
 
-    add(
+    let error = add(
         &ctx,
         AddRequest {
-            section: "fridge".to_string(),
-            name: "butter".to_string(),
+            section: "general".to_string(),
+            name: "pepper".to_string(),
+            quantity: Some("100%g".to_string()),
+            expire: Some("2027-01-01".to_string()),
             ..Default::default()
         },
     )
-    .expect("adds");
+    .expect_err("must refuse");
 
-    let written = read_back(&ctx);
     assert!(
-        written.contains("[fridge]"),
-        "the array became a section table\n{written}"
+        error.to_string().contains("move it into a section"),
+        "{error}"
     );
-    assert!(
-        !written.contains("[\"milk\""),
-        "and the array syntax is gone\n{written}"
+    assert_eq!(read_back(&ctx), original, "nothing may be written");
+}
+
+// ---------------------------------------------------------------------------
+// import, export
+// ---------------------------------------------------------------------------
+
+fn import_text(ctx: &Context, text: &str, mode: ImportMode) -> Outcome<Import> {
+    import(
+        ctx,
+        ImportRequest {
+            text: text.to_string(),
+            path: Some(Utf8PathBuf::from("stock.csv")),
+            mode,
+            ..Default::default()
+        },
+    )
+    .unwrap()
+}
+
+#[test]
+fn an_export_imports_back_into_the_same_pantry() {
+    let pantry = "\
+salt = \"1%kg\"
+
+[dairy]
+milk = { quantity = \"1%l\", expire = \"2027-01-05\", low = \"500%ml\" }
+
+[\"dry, goods\"]
+rice = { quantity = \"2%kg\", bought = \"2026-09-01\" }
+";
+    let (_dir, ctx) = planted(pantry);
+
+    for format in [TransferFormat::Csv, TransferFormat::Json] {
+        let exported = export(&ctx, ExportRequest { format }).unwrap().value;
+        let (_empty_dir, empty) = planted("");
+        let outcome = import(
+            &empty,
+            ImportRequest {
+                text: exported,
+                format: Some(format),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(outcome.value.added, 3, "{format:?}");
+        assert_eq!(
+            load(&empty).unwrap().value,
+            load(&ctx).unwrap().value,
+            "{format:?}"
+        );
+    }
+}
+
+#[test]
+fn a_csv_export_has_a_header_and_an_empty_cell_for_what_is_not_set() {
+    let (_dir, ctx) = planted("[dairy]\nmilk = { quantity = \"1%l\", low = \"500%ml\" }\n");
+
+    let csv = export(&ctx, ExportRequest::default()).unwrap().value;
+
+    assert_eq!(
+        csv,
+        "name,section,quantity,bought,expire,low,restock\r\nmilk,dairy,1%l,,,500%ml,\r\n"
+    );
+}
+
+/// `restock` is not in `cooklang`'s model, but it is the pantry's own, so a
+/// spreadsheet carries it out and a replace puts it back rather than
+/// stripping it.
+#[test]
+fn restock_survives_an_export_and_a_replace() {
+    let pantry = "[dairy]\nmilk = { quantity = \"300%ml\", low = \"500%ml\", restock = \"2%l\" }\n";
+    let (_dir, ctx) = planted(pantry);
+
+    for format in [TransferFormat::Csv, TransferFormat::Json] {
+        let exported = export(&ctx, ExportRequest { format }).unwrap().value;
+        assert!(exported.contains("2%l"), "{format:?}: {exported}");
+
+        let outcome = import(
+            &ctx,
+            ImportRequest {
+                text: exported,
+                format: Some(format),
+                mode: ImportMode::Replace,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(outcome.value.unchanged, 1, "{format:?}");
+        assert_eq!(read_back(&ctx), pantry, "{format:?}");
+    }
+
+    let outcome = import_text(
+        &ctx,
+        "name,section,restock\nmilk,dairy,a lot\n",
+        ImportMode::Merge,
     );
     assert_eq!(
-        names(&load(&ctx).expect("reads back").into_value().sections[0].items),
-        ["milk", "eggs", "butter"],
-        "the items themselves survive"
+        outcome.value.rejected[0].reason,
+        "its restock 'a lot' is not a number and a unit"
     );
 }
 
+/// A merge is an edit like any other: comments and items the file does not
+/// mention stay where they were, and only the attributes a row gives are set.
 #[test]
-fn a_write_keeps_non_ascii_names() {
-    let (_dir, ctx) = planted("[mejeri]\n\"smörgås\" = \"1\"\n");
+fn a_merge_sets_what_the_rows_give_and_keeps_everything_else() {
+    let (_dir, ctx) = planted(
+        "\
+# Kitchen
+[pantry]
+flour = { quantity = \"1%kg\", low = \"200%g\" } # the big bag
+sugar = \"500%g\"
+",
+    );
 
-    add(
+    let outcome = import_text(
         &ctx,
-        AddRequest {
-            section: "mejeri".to_string(),
-            name: "äpple".to_string(),
-            quantity: Some("3".to_string()),
-            ..Default::default()
-        },
-    )
-    .expect("adds");
+        "Name,Section,Quantity,Notes\nflour,pantry,2%kg,restocked\nsugar,pantry,500%g,\nrice,pantry,1%kg,\n",
+        ImportMode::Merge,
+    );
 
-    let reread = load(&ctx).expect("reads back").into_value();
-    assert_eq!(names(&reread.sections[0].items), ["smörgås", "äpple"]);
+    let summary = &outcome.value;
+    assert_eq!(
+        (
+            summary.added,
+            summary.updated,
+            summary.unchanged,
+            summary.removed
+        ),
+        (1, 1, 1, 0)
+    );
+    assert_eq!(
+        read_back(&ctx),
+        "\
+# Kitchen
+[pantry]
+flour = { quantity = \"2%kg\", low = \"200%g\" } # the big bag
+sugar = \"500%g\"
+rice = \"1%kg\"
+"
+    );
+    assert!(
+        outcome
+            .diagnostics
+            .iter()
+            .any(|d| d.message.contains("'Notes' is not a pantry item field")),
+        "{:?}",
+        outcome.diagnostics
+    );
+    let journal = history(&ctx).unwrap().value;
+    assert_eq!(journal.len(), 1, "the whole import is one change");
+    assert_eq!(journal[0].operation, "import");
 }
 
-/// What the three return is what the file now says, so a caller need not read
-/// it back.
 #[test]
-fn a_change_returns_the_pantry_it_wrote() {
-    let (_dir, ctx) = planted(SMALL);
+fn a_replace_removes_what_the_file_does_not_mention() {
+    let (_dir, ctx) = planted(
+        "\
+[pantry]
+flour = { quantity = \"1%kg\", low = \"200%g\" }
+sugar = \"500%g\"
 
-    let returned = add(
+[dairy]
+milk = \"1%l\"
+",
+    );
+
+    let outcome = import_text(
         &ctx,
-        AddRequest {
-            section: "dairy".to_string(),
-            name: "milk".to_string(),
-            quantity: Some("1%l".to_string()),
-            ..Default::default()
-        },
-    )
-    .expect("adds")
-    .into_value();
+        "name,section,quantity\nflour,pantry,2%kg\nsugar,pantry,500%g\n",
+        ImportMode::Replace,
+    );
 
-    assert_eq!(returned, load(&ctx).expect("reads back").into_value());
+    let summary = &outcome.value;
+    assert_eq!(
+        (
+            summary.added,
+            summary.updated,
+            summary.unchanged,
+            summary.removed
+        ),
+        (0, 1, 1, 1)
+    );
+    assert_eq!(
+        read_back(&ctx),
+        "[pantry]\nflour = \"2%kg\"\nsugar = \"500%g\"\n",
+        "flour loses the low it was not given; the emptied dairy section goes"
+    );
+    assert_eq!(history(&ctx).unwrap().value[0].operation, "import replace");
 }
 
-// ---------------------------------------------------------------------------
-// Writing the file itself
-// ---------------------------------------------------------------------------
+#[test]
+fn rows_that_cannot_be_written_are_reported_and_the_rest_imported() {
+    let original = "[pantry]\nflour = \"1%kg\"\n";
+    let (_dir, ctx) = planted(original);
+
+    let outcome = import_text(
+        &ctx,
+        "\
+name,section,quantity,expire
+rice,pantry,1%kg,
+,pantry,1%kg,
+oats,pantry,a bag,
+beans,pantry,1%kg,soon
+salt,,1%kg,2027-01-01
+rice,pantry,2%kg,
+",
+        ImportMode::Merge,
+    );
+
+    let rejected: Vec<(usize, Option<&str>)> = outcome
+        .value
+        .rejected
+        .iter()
+        .map(|row| (row.row, row.name.as_deref()))
+        .collect();
+    assert_eq!(
+        rejected,
+        [
+            (3, None),
+            (4, Some("oats")),
+            (5, Some("beans")),
+            (6, Some("salt")),
+            (7, Some("rice")),
+        ]
+    );
+    assert!(outcome.value.rejected[4].reason.contains("row 2"));
+    assert_eq!(outcome.value.added, 1);
+    assert_eq!(
+        read_back(&ctx),
+        "[pantry]\nflour = \"1%kg\"\nrice = \"1%kg\"\n"
+    );
+}
 
 #[test]
-fn a_write_leaves_no_temporary_file_behind() {
+fn a_dry_run_counts_without_writing() {
     let (_dir, ctx) = planted(SMALL);
 
-    add(
+    let outcome = import(
         &ctx,
-        AddRequest {
-            section: "pantry".to_string(),
-            name: "sugar".to_string(),
+        ImportRequest {
+            text: "name,section\nrice,pantry\n".to_string(),
+            format: Some(TransferFormat::Csv),
+            dry_run: true,
             ..Default::default()
         },
     )
-    .expect("adds");
+    .unwrap();
 
-    let config = ctx.pantry().path().unwrap().parent().unwrap().to_owned();
-    let left: Vec<_> = std::fs::read_dir(&config)
-        .unwrap()
-        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
-        .collect();
-    assert_eq!(left, ["pantry.conf"], "only the pantry itself is left");
+    assert_eq!(outcome.value.added, 1);
+    assert_eq!(read_back(&ctx), SMALL);
+    assert!(history(&ctx).unwrap().value.is_empty());
 }
 
-/// A pantry symlinked in from a dotfiles repository must be written through,
-/// not replaced by a regular file.
-#[cfg(unix)]
+/// Replacing the pantry with nothing is far likelier to be the wrong file —
+/// or one whose every row was turned away — than what was meant.
 #[test]
-fn a_write_follows_a_symlinked_pantry() {
-    let dir = temp();
-    let base = base(&dir);
-    let real = base.join("dotfiles").join("pantry.conf");
-    write(&real, SMALL);
-    std::fs::create_dir_all(base.join("config")).unwrap();
-    std::os::unix::fs::symlink(real.as_std_path(), pantry_path(&base).as_std_path()).unwrap();
+fn a_replace_with_nothing_to_import_is_refused() {
+    let (_dir, ctx) = planted(SMALL);
 
-    let ctx = ctx_at(&base);
-    add(
+    let error = import(
         &ctx,
-        AddRequest {
-            section: "pantry".to_string(),
-            name: "sugar".to_string(),
+        ImportRequest {
+            text: "name,section\n,pantry\n".to_string(),
+            format: Some(TransferFormat::Csv),
+            mode: ImportMode::Replace,
             ..Default::default()
         },
     )
-    .expect("adds");
+    .expect_err("must refuse");
 
-    assert!(
-        std::fs::symlink_metadata(pantry_path(&base).as_std_path())
-            .unwrap()
-            .file_type()
-            .is_symlink(),
-        "the link itself must survive"
-    );
-    assert!(
-        std::fs::read_to_string(real.as_std_path())
-            .unwrap()
-            .contains("sugar"),
-        "and the change must land on the file it points at"
-    );
+    assert!(matches!(error, CoreError::PantryImport { .. }), "{error}");
+    assert_eq!(read_back(&ctx), SMALL);
 }
 
-/// A temporary file is created from the process umask, which is usually more
-/// permissive than a pantry someone has locked down.
-#[cfg(unix)]
 #[test]
-fn a_write_keeps_the_pantry_file_permissions() {
-    use std::os::unix::fs::PermissionsExt;
-
+fn a_file_without_a_name_column_is_an_error() {
     let (_dir, ctx) = planted(SMALL);
-    let path = ctx.pantry().path().unwrap().to_owned();
-    std::fs::set_permissions(path.as_std_path(), std::fs::Permissions::from_mode(0o600)).unwrap();
 
-    add(
+    let error = import(
         &ctx,
-        AddRequest {
-            section: "pantry".to_string(),
-            name: "sugar".to_string(),
+        ImportRequest {
+            text: "item,quantity\nrice,1%kg\n".to_string(),
+            path: Some(Utf8PathBuf::from("stock.csv")),
             ..Default::default()
         },
     )
-    .expect("adds");
+    .expect_err("must refuse");
 
-    let mode = std::fs::metadata(path.as_std_path())
-        .unwrap()
-        .permissions()
-        .mode();
-    assert_eq!(mode & 0o777, 0o600, "the pantry must not become readable");
+    assert_eq!(
+        error.to_string(),
+        "cannot import into the pantry from stock.csv: there is no 'name' column"
+    );
 }
 
-/// The property the whole write dance is for: whatever goes wrong, the pantry
-/// that was there is still there.
-///
-/// The failure is forced by making the directory unwritable, so the test can
-/// only run as a user the permission applies to — root is not one, and the
-/// probe below says so rather than the test quietly passing.
-#[cfg(unix)]
-#[test]
-fn a_failed_write_leaves_the_pantry_untouched() {
-    use std::os::unix::fs::PermissionsExt;
+// ---------------------------------------------------------------------------
+// restock
+// ---------------------------------------------------------------------------
 
-    let (_dir, ctx) = planted(SMALL);
-    let config = ctx.pantry().path().unwrap().parent().unwrap().to_owned();
+const RUNNING_OUT: &str = "\
+[dairy]
+milk = { quantity = \"300%ml\", low = \"500%ml\", restock = \"2%l\" }
+butter = { quantity = \"250%g\", low = \"50%g\", restock = \"500%g\" }
 
-    std::fs::set_permissions(config.as_std_path(), std::fs::Permissions::from_mode(0o500)).unwrap();
-    let unwritable = std::fs::File::create(config.join("probe").as_std_path()).is_err();
+[pantry]
+eggs = { quantity = \"1\", low = \"2\" }
+";
 
-    let result = add(
-        &ctx,
-        AddRequest {
-            section: "pantry".to_string(),
-            name: "sugar".to_string(),
-            ..Default::default()
-        },
+fn restock_names(items: &[RestockItem]) -> Vec<(&str, Option<&str>)> {
+    items
+        .iter()
+        .map(|item| (item.name.as_str(), item.quantity.as_deref()))
+        .collect()
+}
+
+#[test]
+fn restock_lists_what_is_low_topped_up_to_its_restock_amount() {
+    let (dir, ctx) = planted(RUNNING_OUT);
+    let store = crate::shopping_list::ShoppingListStore::new(&base(&dir));
+
+    let outcome = restock(&ctx, &store, RestockRequest::default()).unwrap();
+
+    assert_eq!(
+        restock_names(&outcome.value.added),
+        [("milk", Some("1.7%l")), ("eggs", None)],
+        "butter is not low; eggs have no restock amount"
+    );
+    let listed: Vec<(String, Option<String>)> = store
+        .items()
+        .unwrap()
+        .into_iter()
+        .map(|item| (item.name, item.quantity))
+        .collect();
+    assert_eq!(
+        listed,
+        [
+            ("milk".to_string(), Some("1.7%l".to_string())),
+            ("eggs".to_string(), None),
+        ]
+    );
+    assert!(
+        outcome.diagnostics.is_empty(),
+        "restock is an attribute cookcli reads, not an unknown one: {:?}",
+        outcome.diagnostics
     );
+}
 
-    // Restore before asserting, so a failure does not also break the cleanup.
-    std::fs::set_permissions(config.as_std_path(), std::fs::Permissions::from_mode(0o700)).unwrap();
+#[test]
+fn restock_leaves_off_what_the_list_already_has() {
+    let (dir, ctx) = planted(RUNNING_OUT);
+    let store = crate::shopping_list::ShoppingListStore::new(&base(&dir));
+    store
+        .add_item(crate::shopping_list::ManualItem {
+            name: "Eggs".to_string(),
+            quantity: Some("12".to_string()),
+            category: None,
+        })
+        .unwrap();
 
-    assert!(
-        unwritable,
-        "this test needs a user that a read-only directory applies to; \
-         running as root makes it prove nothing"
+    let first = restock(&ctx, &store, RestockRequest::default()).unwrap();
+    let second = restock(&ctx, &store, RestockRequest::default()).unwrap();
+
+    assert_eq!(restock_names(&first.value.added), [("milk", Some("1.7%l"))]);
+    assert_eq!(restock_names(&first.value.already_listed), [("eggs", None)]);
+    assert!(second.value.added.is_empty(), "{:?}", second.value);
+    assert_eq!(store.items().unwrap().len(), 2);
+}
+
+/// The list adds `scallions` and `spring onions` up as one ingredient when
+/// the aisle file says they are, so restock must not put a second one on.
+#[test]
+fn restock_knows_an_item_on_the_list_by_its_aisle_synonym() {
+    let (dir, ctx) = planted(
+        "[produce]\nscallions = { quantity = \"1\", low = \"2\" }\nleeks = { quantity = \"0\", low = \"1\" }\n",
     );
-    match result {
-        Err(CoreError::Io { path, .. }) => assert_eq!(path, *ctx.pantry().path().unwrap()),
-        other => panic!("expected Io, got {:?}", other.map(|o| o.value)),
+    let ctx = ctx.with_aisle(ConfigSource::Inline(
+        "[produce]\nspring onions|scallions\nleek|leeks\n".to_string(),
+    ));
+    let store = crate::shopping_list::ShoppingListStore::new(&base(&dir));
+    for name in ["Spring Onions", "leek"] {
+        store
+            .add_item(crate::shopping_list::ManualItem {
+                name: name.to_string(),
+                quantity: None,
+                category: None,
+            })
+            .unwrap();
     }
-    assert_eq!(read_back(&ctx), SMALL, "the pantry is exactly as it was");
-    let left: Vec<_> = std::fs::read_dir(config.as_std_path())
-        .unwrap()
-        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
-        .collect();
-    assert_eq!(left, ["pantry.conf"], "and no temporary file is left");
+
+    let outcome = restock(&ctx, &store, RestockRequest::default()).unwrap();
+
+    assert!(outcome.value.added.is_empty(), "{:?}", outcome.value);
+    assert_eq!(
+        restock_names(&outcome.value.already_listed),
+        [("scallions", None), ("leeks", None)]
+    );
+    assert_eq!(store.items().unwrap().len(), 2);
 }
 
-/// A pantry that exists but cannot be read must stop the change. Treating an
-/// unreadable file as an empty one would replace it with a one-item pantry —
-/// the worst thing any of this can do.
-///
-/// Unreadable is arranged with a mode, so the test only means anything as a
-/// user that mode applies to; the probe says so rather than passing quietly
-/// under root.
-#[cfg(unix)]
 #[test]
-fn add_refuses_a_pantry_it_cannot_read_rather_than_replacing_it() {
-    use std::os::unix::fs::PermissionsExt;
+fn a_restock_dry_run_changes_nothing() {
+    let (dir, ctx) = planted(RUNNING_OUT);
+    let store = crate::shopping_list::ShoppingListStore::new(&base(&dir));
 
-    let (_dir, ctx) = planted(SMALL);
-    let path = ctx.pantry().path().unwrap().to_owned();
-    std::fs::set_permissions(path.as_std_path(), std::fs::Permissions::from_mode(0o000)).unwrap();
-    let unreadable = std::fs::read_to_string(path.as_std_path()).is_err();
+    let outcome = restock(&ctx, &store, RestockRequest { dry_run: true }).unwrap();
 
-    let result = add(
-        &ctx,
-        AddRequest {
-            section: "pantry".to_string(),
-            name: "sugar".to_string(),
-            ..Default::default()
-        },
+    assert_eq!(outcome.value.added.len(), 2);
+    assert!(store.items().unwrap().is_empty());
+}
+
+#[test]
+fn a_restock_amount_in_another_kind_of_unit_is_warned_about_and_left_out() {
+    let (dir, ctx) = planted(
+        "[dairy]\nmilk = { quantity = \"300%ml\", low = \"500%ml\", restock = \"2%kg\" }\n",
     );
+    let store = crate::shopping_list::ShoppingListStore::new(&base(&dir));
 
-    std::fs::set_permissions(path.as_std_path(), std::fs::Permissions::from_mode(0o600)).unwrap();
+    let outcome = restock(&ctx, &store, RestockRequest::default()).unwrap();
 
+    assert_eq!(restock_names(&outcome.value.added), [("milk", None)]);
     assert!(
-        unreadable,
-        "this test needs a user that a 000 file applies to; running as root \
-         makes it prove nothing"
+        outcome.diagnostics[0]
+            .message
+            .contains("restock = '2%kg', but its quantity '300%ml' is not in a unit that converts"),
+        "{:?}",
+        outcome.diagnostics
     );
-    match result {
-        Err(CoreError::Io { path: named, .. }) => assert_eq!(named, path),
-        other => panic!("expected Io, got {:?}", other.map(|o| o.value)),
-    }
-    assert_eq!(read_back(&ctx), SMALL, "the pantry is exactly as it was");
 }
 
-/// [`write_atomically`] cleans up after itself when the rename fails, which is
-/// the one failure that happens after the temporary file exists.
-///
-/// Renaming a file onto a directory cannot succeed, which is a portable way to
-/// reach that branch — the pantry commands themselves stop earlier than this,
-/// because a directory cannot be read as a pantry either.
+/// Every read of the pantry would otherwise warn about `restock`, which
+/// `cooklang` does not know.
 #[test]
-fn a_write_that_fails_after_writing_its_temporary_file_still_removes_it() {
-    let dir = temp();
-    let base = base(&dir);
-    let target = base.join("a-directory");
-    std::fs::create_dir_all(target.join("child").as_std_path()).unwrap();
-
-    match write_atomically(&target, "contents") {
-        Err(CoreError::Io { path, .. }) => assert_eq!(path, target),
-        other => panic!("expected Io, got {other:?}"),
-    }
+fn loading_does_not_warn_about_the_restock_attribute() {
+    let outcome = load(&ctx_with(RUNNING_OUT)).unwrap();
+    assert!(outcome.diagnostics.is_empty(), "{:?}", outcome.diagnostics);
 
-    let left: Vec<_> = std::fs::read_dir(base.as_std_path())
-        .unwrap()
-        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
-        .collect();
-    assert_eq!(left, ["a-directory"], "no temporary file is left behind");
+    let outcome = load(&ctx_with(
+        "[dairy]\nmilk = { quantity = \"1%l\", restok = \"2%l\" }\n",
+    ))
+    .unwrap();
+    assert_eq!(outcome.diagnostics.len(), 1, "a misspelling still warns");
 }
 
 // ---------------------------------------------------------------------------
-// The document survives an edit
+// shelf life
 // ---------------------------------------------------------------------------
 
-/// The reproduction from <https://github.com/cooklang/cookcli/issues/429>,
-/// asserted end to end: an unrelated `add` used to destroy the top-level
-/// `salt`, drop the comment and drop `shelf`. The whole file is compared, so
-/// anything reformatted anywhere fails.
-#[test]
-fn an_unrelated_add_changes_only_the_line_it_adds() {
-    let original = "# my pantry notes\n\
-                    salt = { quantity = \"1%kg\", expire = \"2027-01-01\" }\n\
-                    \n\
-                    [fridge]\n\
-                    milk = { quantity = \"1%l\", shelf = \"top\" }\n";
-    let (_dir, ctx) = planted(original);
+/// A kitchen with a datastore that knows how long milk keeps.
+fn with_datastore(meta: &str) -> (tempfile::TempDir, Context, Utf8PathBuf) {
+    let (dir, ctx) = planted(SMALL);
+    let datastore = base(&dir).join("db");
+    write(&datastore.join("milk").join("meta.yml"), meta);
+    (dir, ctx, datastore)
+}
 
+fn add_milk(
+    ctx: &Context,
+    section: &str,
+    datastore: &Utf8Path,
+    bought: Option<&str>,
+) -> Outcome<PantryContents> {
     add(
-        &ctx,
+        ctx,
         AddRequest {
-            section: "fridge".to_string(),
-            name: "butter".to_string(),
-            quantity: Some("200%g".to_string()),
+            section: section.to_string(),
+            name: "Milk".to_string(),
+            bought: bought.map(str::to_string),
+            datastore: Some(datastore.to_path_buf()),
             ..Default::default()
         },
     )
-    .expect("adds");
+    .unwrap()
+}
+
+fn expire_of<'a>(contents: &'a PantryContents, section: &str) -> Option<&'a str> {
+    contents
+        .items()
+        .find(|item| item.name == "Milk" && item.section == section)
+        .and_then(|item| item.expire.as_deref())
+}
+
+#[test]
+fn add_fills_the_expiry_from_the_life_the_section_gives() {
+    let (_dir, ctx, datastore) =
+        with_datastore("shelf life: 1\nfridge life: 7\nfreezer life: \"90\"\n");
 
+    let added = add_milk(&ctx, "Fridge", &datastore, Some("2026-03-01"));
+    assert_eq!(expire_of(&added.value, "Fridge"), Some("2026-03-08"));
+
+    let added = add_milk(&ctx, "deep freezer", &datastore, Some("2026-03-01"));
+    assert_eq!(expire_of(&added.value, "deep freezer"), Some("2026-05-30"));
+
+    let added = add_milk(&ctx, "cupboard", &datastore, None);
+    let tomorrow = Local::now().date_naive() + chrono::Days::new(1);
     assert_eq!(
-        read_back(&ctx),
-        format!("{original}butter = \"200%g\"\n"),
-        "only the new item may appear"
+        expire_of(&added.value, "cupboard"),
+        Some(tomorrow.format(ISO_DATE).to_string().as_str())
     );
 }
 
-/// A section written as a list of names has nowhere to put a quantity, so an
-/// edit has to rewrite it. Every name in it survives that, and the caller is
-/// told the shape changed.
 #[test]
-fn an_array_section_is_rewritten_as_a_table_keeping_its_items() {
-    let (_dir, ctx) = planted("fridge = [\"milk\", \"eggs\"]\n");
+fn add_leaves_the_expiry_alone_when_it_is_given_or_the_datastore_does_not_say() {
+    let (_dir, ctx, datastore) = with_datastore("fridge life: 7\n");
 
-    let outcome = add(
+    let added = add(
         &ctx,
         AddRequest {
             section: "fridge".to_string(),
-            name: "butter".to_string(),
-            quantity: Some("200%g".to_string()),
+            name: "Milk".to_string(),
+            expire: Some("2030-01-01".to_string()),
+            datastore: Some(datastore.clone()),
             ..Default::default()
         },
     )
-    .expect("adds");
+    .unwrap();
+    assert_eq!(expire_of(&added.value, "fridge"), Some("2030-01-01"));
 
-    assert!(
-        outcome
-            .diagnostics
-            .iter()
-            .any(|d| d.severity == Severity::Warning && d.message.contains("list of names")),
-        "the rewrite must be reported: {:?}",
-        outcome.diagnostics
+    let added = add_milk(&ctx, "cupboard", &datastore, None);
+    assert_eq!(
+        expire_of(&added.value, "cupboard"),
+        None,
+        "no shelf life to go by"
     );
+    assert!(added.diagnostics.is_empty(), "{:?}", added.diagnostics);
+}
+
+#[test]
+fn a_life_that_is_not_a_number_of_days_is_a_warning_naming_the_file() {
+    let (_dir, ctx, datastore) = with_datastore("fridge life: a week\n");
+
+    let added = add_milk(&ctx, "fridge", &datastore, None);
+
+    assert_eq!(expire_of(&added.value, "fridge"), None);
+    assert_eq!(added.diagnostics.len(), 1, "{:?}", added.diagnostics);
     assert_eq!(
-        names(&outcome.value.sections[0].items),
-        ["milk", "eggs", "butter"],
-        "no name may be lost to the rewrite"
+        added.diagnostics[0].message,
+        "fridge life must be a whole number of days"
     );
-    assert!(read_back(&ctx).contains("[fridge]"));
+    assert!(added.diagnostics[0]
+        .location
+        .as_ref()
+        .and_then(|l| l.file.as_ref())
+        .is_some_and(|file| file.ends_with("milk/meta.yml")));
+}
+
+// ---------------------------------------------------------------------------
+// notify
+// ---------------------------------------------------------------------------
+
+/// Expiry dates long past, so the events stand whatever day the tests run.
+const NOTIFY_PANTRY: &str = r#"[dairy]
+milk = { quantity = "1%l", expire = "2001-01-02" }
+yoghurt = { quantity = "500%g", expire = "2001-01-01" }
+
+[cupboard]
+honey = "0"
+rice = "2%kg"
+"#;
+
+fn with_pantry_file(pantry: &str) -> (tempfile::TempDir, Context) {
+    let dir = temp();
+    let path = base(&dir).join("pantry.conf");
+    write(&path, pantry);
+    let ctx = Context::new(base(&dir)).with_pantry(ConfigSource::Path(path));
+    (dir, ctx)
+}
+
+fn pending(ctx: &Context) -> Notifications {
+    pending_notifications(ctx, NotifyRequest::default())
+        .unwrap()
+        .into_value()
+}
+
+fn event_names(notifications: &Notifications) -> Vec<(PantryEventKind, &str)> {
+    notifications
+        .events
+        .iter()
+        .map(|event| (event.event, event.name.as_str()))
+        .collect()
 }
 
-/// An item whose value is neither a quantity nor a set of attributes is
-/// refused rather than overwritten, and nothing is written at all.
 #[test]
-fn an_update_refuses_an_item_written_as_a_bare_number() {
-    let original = "[pantry]\nflour = 3\n";
-    let (_dir, ctx) = planted(original);
+fn notify_reports_expiring_soonest_first_then_running_out() {
+    let (_dir, ctx) = with_pantry_file(NOTIFY_PANTRY);
 
-    let error = update(
-        &ctx,
-        UpdateRequest {
-            section: "pantry".to_string(),
-            name: "flour".to_string(),
-            quantity: Some("1%kg".to_string()),
-            ..Default::default()
-        },
-    )
-    .expect_err("must refuse");
+    let notifications = pending(&ctx);
+
+    assert_eq!(
+        event_names(&notifications),
+        [
+            (PantryEventKind::Expiring, "yoghurt"),
+            (PantryEventKind::Expiring, "milk"),
+            (PantryEventKind::Depleted, "honey"),
+        ]
+    );
+    let milk = &notifications.events[1];
+    assert_eq!(milk.section, "dairy");
+    assert_eq!(milk.quantity.as_deref(), Some("1%l"));
+    assert_eq!(milk.expire_date.as_deref(), Some("2001-01-02"));
+}
+
+#[test]
+fn an_event_is_reported_once_until_it_stops_standing() {
+    let (dir, ctx) = with_pantry_file(NOTIFY_PANTRY);
 
+    let first = pending(&ctx);
     assert!(
-        error.to_string().contains("bare number"),
-        "the message should say what it found: {error}"
+        !base(&dir).join("pantry.notified").exists(),
+        "finding events writes nothing"
+    );
+    mark_notified(&first).unwrap();
+    assert!(pending(&ctx).events.is_empty(), "nothing new since");
+
+    // The honey is restocked, and the forgetting is recorded...
+    write(
+        &base(&dir).join("pantry.conf"),
+        &NOTIFY_PANTRY.replace("honey = \"0\"", "honey = \"500%g\""),
+    );
+    let restocked = pending(&ctx);
+    assert!(restocked.events.is_empty());
+    mark_notified(&restocked).unwrap();
+
+    // ...so running out again is news again.
+    write(&base(&dir).join("pantry.conf"), NOTIFY_PANTRY);
+    assert_eq!(
+        event_names(&pending(&ctx)),
+        [(PantryEventKind::Depleted, "honey")]
     );
-    assert_eq!(read_back(&ctx), original, "nothing may be written");
 }
 
-/// Adding a `general` item with an attribute it has no room for is refused for
-/// the same reason `update` is: writing the inline table it would need turns
-/// the item into a section.
 #[test]
-fn a_general_add_with_attributes_is_refused() {
-    let original = "salt = \"1%kg\"\n";
-    let (_dir, ctx) = planted(original);
+fn a_new_expiry_date_is_a_new_event() {
+    let (dir, ctx) = with_pantry_file(NOTIFY_PANTRY);
+    mark_notified(&pending(&ctx)).unwrap();
 
-    let error = add(
-        &ctx,
-        AddRequest {
-            section: "general".to_string(),
-            name: "pepper".to_string(),
-            quantity: Some("100%g".to_string()),
-            expire: Some("2027-01-01".to_string()),
-            ..Default::default()
-        },
+    write(
+        &base(&dir).join("pantry.conf"),
+        &NOTIFY_PANTRY.replace("2001-01-02", "2001-02-01"),
+    );
+
+    assert_eq!(
+        event_names(&pending(&ctx)),
+        [(PantryEventKind::Expiring, "milk")]
+    );
+}
+
+#[test]
+fn events_not_asked_for_are_neither_reported_nor_remembered() {
+    let (_dir, ctx) = with_pantry_file(NOTIFY_PANTRY);
+    let expiring_only = NotifyRequest {
+        depleted: false,
+        ..Default::default()
+    };
+
+    let notifications = pending_notifications(&ctx, expiring_only)
+        .unwrap()
+        .into_value();
+    assert_eq!(notifications.events.len(), 2);
+    mark_notified(&notifications).unwrap();
+
+    assert_eq!(
+        event_names(&pending(&ctx)),
+        [(PantryEventKind::Depleted, "honey")]
+    );
+}
+
+#[test]
+fn an_inline_pantry_has_nowhere_to_remember_so_every_event_is_new() {
+    let ctx = ctx_with(NOTIFY_PANTRY);
+
+    let first = pending(&ctx);
+    mark_notified(&first).unwrap();
+
+    assert_eq!(pending(&ctx).events.len(), 3);
+}
+
+#[test]
+fn an_unreadable_record_is_a_warning_and_everything_is_reported_again() {
+    let (dir, ctx) = with_pantry_file(NOTIFY_PANTRY);
+    let state = base(&dir).join("pantry.notified");
+    write(&state, "not json");
+
+    let notifications = pending_notifications(&ctx, NotifyRequest::default()).unwrap();
+
+    assert_eq!(notifications.value.events.len(), 3);
+    assert_eq!(notifications.diagnostics.len(), 1);
+    assert_eq!(notifications.diagnostics[0].severity, Severity::Warning);
+    mark_notified(&notifications.value).unwrap();
+    assert!(pending(&ctx).events.is_empty(), "the record is rewritten");
+}
+
+#[test]
+fn notify_config_reads_events_and_hooks_and_warns_about_the_rest() {
+    let parsed = NotifyConfig::parse(
+        "days = 3\nevents = [\"depleted\", \"stale\"]\ncommand = \"notify-send\"\ncolour = \"red\"\n",
+        Some(Utf8Path::new("/config/notify.toml")),
     )
-    .expect_err("must refuse");
+    .unwrap();
+
+    let config = parsed.value;
+    assert_eq!(config.days, 3);
+    assert!(!config.expiring && config.depleted);
+    assert_eq!(config.command.as_deref(), Some("notify-send"));
+    assert_eq!(config.webhook, None);
+    let messages: Vec<_> = parsed
+        .diagnostics
+        .iter()
+        .map(|d| d.message.as_str())
+        .collect();
+    assert_eq!(
+        messages,
+        [
+            "unknown event 'stale' in notify.toml: it can be 'expiring' or 'depleted'",
+            "unknown notify.toml key 'colour'",
+        ]
+    );
 
-    assert!(
-        error.to_string().contains("move it into a section"),
-        "{error}"
+    assert!(matches!(
+        NotifyConfig::parse("days = ", None),
+        Err(CoreError::Config { .. })
+    ));
+    assert_eq!(
+        NotifyConfig::load(&ConfigSource::None).unwrap().value,
+        NotifyConfig::default()
     );
-    assert_eq!(read_back(&ctx), original, "nothing may be written");
 }
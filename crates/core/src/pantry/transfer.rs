@@ -0,0 +1,696 @@
+//! The pantry to and from a spreadsheet.
+//!
+//! Plenty of households already keep their stock in a spreadsheet, and
+//! retyping it item by item with [`add`](super::add) is nobody's idea of
+//! setting up. [`export`] writes the pantry out as CSV or JSON, one row per
+//! item, and [`import`] reads such a file back in — either merged over what
+//! the pantry already holds or replacing it.
+//!
+//! # Columns
+//!
+//! Both formats carry the fields of a [`PantryItem`] — `name`, `section`,
+//! `quantity`, `bought`, `expire` and `low` — and `restock`, the amount
+//! [`restock`](super::restock) tops an item up to, written as the pantry file
+//! writes them (`500%g`, `2026-05-01`). A CSV starts with a header row naming its
+//! columns, in any order and any case; a JSON file is a list of objects with
+//! those keys. Only `name` is needed. An empty cell, a missing key and `null`
+//! all mean the item has no such attribute, and an item with no section goes
+//! in `general`, the items above the first section header.
+//!
+//! A column or key that is none of these is ignored, with a warning, so that
+//! a spreadsheet with a `notes` column can be imported as it stands.
+//!
+//! # Rows that are turned away
+//!
+//! A row that cannot be written into a pantry file is left out and the rest
+//! are imported: no name, a date that cannot be read as one, a quantity that
+//! is not a number and a unit (`quantity`, `low` and `restock`), an attribute
+//! on an item in `general` (see
+//! [`add`](super::add)), or a second row for an item an earlier one already
+//! gave. Each is reported in [`Import::rejected`], saying which row it was
+//! and why.
+//!
+//! # What is written
+//!
+//! An import goes through the same editing path as [`add`](super::add) and
+//! [`update`](super::update), so comments, layout and keys this crate does
+//! not model survive in everything it does not touch — see [what a write
+//! touches](super#what-a-write-touches). The whole import is one change in
+//! the [journal](super::history), so one [`undo`](super::undo) takes it back.
+
+use super::{
+    edit, journal, load, normalise_array_section, parse_conf, parse_date, path_to_create,
+    read_document_or_empty, restock::RESTOCK, split_quantity, ItemChange, PantryItem,
+};
+use crate::{
+    format::shopping_list::push_csv_row, fs_atomic::write_atomically, Context, CoreError,
+    Diagnostic, Outcome,
+};
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The columns of an export, in the order it writes them; the fields an
+/// import reads.
+const COLUMNS: [&str; 7] = [
+    "name", "section", "quantity", "bought", "expire", "low", "restock",
+];
+
+/// The section an item with none is put in, as `cooklang` names the items
+/// above the first header.
+const GENERAL: &str = edit::GENERAL;
+
+/// A spreadsheet format [`export`] writes and [`import`] reads.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TransferFormat {
+    /// Comma-separated values with a header row, as every spreadsheet opens.
+    #[default]
+    Csv,
+    /// A JSON list of objects, one per item.
+    Json,
+}
+
+impl TransferFormat {
+    /// The format a file's extension names — `.csv` or `.json`, in any case —
+    /// or `None`.
+    pub fn from_path(path: &Utf8Path) -> Option<Self> {
+        match path.extension()?.to_ascii_lowercase().as_str() {
+            "csv" => Some(Self::Csv),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+/// How to write the pantry out.
+///
+/// Not `#[non_exhaustive]`: consumers construct this.
+#[derive(Debug, Clone, Default)]
+pub struct ExportRequest {
+    /// The format to write.
+    pub format: TransferFormat,
+}
+
+/// What an import does with what the pantry already holds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ImportMode {
+    /// Keep every item the file does not mention. An item it does mention is
+    /// added if it is new, and otherwise has the attributes the file gives
+    /// set, leaving those it leaves empty as they were.
+    #[default]
+    Merge,
+    /// Make the pantry exactly what the file says: an item it does not
+    /// mention is removed, and one it does is rewritten with only the
+    /// attributes it gives.
+    Replace,
+}
+
+/// A spreadsheet to read into the pantry.
+///
+/// Not `#[non_exhaustive]`: consumers construct this. `..Default::default()`
+/// keeps a literal working if it grows a field.
+#[derive(Debug, Clone, Default)]
+pub struct ImportRequest {
+    /// The file's contents.
+    pub text: String,
+    /// Where they came from, if anywhere. The [`Import::rejected`] rows and
+    /// the warnings are attributed to it, and it is what the format is told
+    /// from when [`format`](Self::format) does not say.
+    pub path: Option<Utf8PathBuf>,
+    /// The format of [`text`](Self::text), or `None` to go by the extension
+    /// of [`path`](Self::path).
+    pub format: Option<TransferFormat>,
+    /// Merge into the pantry or replace it.
+    pub mode: ImportMode,
+    /// Work out what would change without writing anything.
+    pub dry_run: bool,
+}
+
+/// A row [`import`] turned away.
+///
+/// `#[non_exhaustive]` because this is an output type consumers read rather
+/// than construct.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RejectedRow {
+    /// Which row: for a CSV, the line a spreadsheet would show it on, so the
+    /// header is row 1 and the first item row 2; for JSON, the item's place
+    /// in the list, counting from 1.
+    pub row: usize,
+    /// The item's name, if the row gave one.
+    pub name: Option<String>,
+    /// Why it was turned away.
+    pub reason: String,
+}
+
+/// What an import did, or would do for a dry run.
+///
+/// `#[non_exhaustive]` because this is an output type consumers read rather
+/// than construct.
+#[non_exhaustive]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Import {
+    /// How many items were new to the pantry.
+    pub added: usize,
+    /// How many items it already had were changed.
+    pub updated: usize,
+    /// How many items it already had were left as they were, because the file
+    /// said nothing different about them.
+    pub unchanged: usize,
+    /// How many items were taken out because the file did not mention them.
+    /// Only ever non-zero for [`ImportMode::Replace`].
+    pub removed: usize,
+    /// The rows that were left out, in file order.
+    pub rejected: Vec<RejectedRow>,
+}
+
+// ---------------------------------------------------------------------------
+// export
+// ---------------------------------------------------------------------------
+
+/// Write the pantry out as a spreadsheet. See the [module
+/// documentation](self) for the columns.
+///
+/// Every item is written, in file order, with its attributes as the pantry
+/// writes them; an attribute it does not have is an empty cell in a CSV and
+/// is left out of the JSON.
+///
+/// # Errors
+///
+/// As [`load`].
+pub fn export(ctx: &Context, req: ExportRequest) -> Result<Outcome<String>, CoreError> {
+    let Outcome {
+        value: contents,
+        diagnostics,
+    } = load(ctx)?;
+    // `restock` is not in `cooklang`'s model, so it is read from the file
+    // itself, which `load` has just shown to be readable.
+    let doc = ctx
+        .pantry()
+        .read()?
+        .and_then(|text| text.parse::<toml_edit::DocumentMut>().ok())
+        .unwrap_or_default();
+    let items: Vec<Exported> = contents
+        .items()
+        .map(|item| Exported {
+            item,
+            restock: edit::attribute(&doc, &item.section, &item.name, RESTOCK),
+        })
+        .collect();
+    let text = match req.format {
+        TransferFormat::Csv => export_csv(&items),
+        TransferFormat::Json => export_json(&items),
+    };
+    Ok(Outcome::with_diagnostics(text, diagnostics))
+}
+
+/// An item to export, with the one attribute [`PantryItem`] does not carry.
+struct Exported<'a> {
+    item: &'a PantryItem,
+    restock: Option<String>,
+}
+
+fn export_csv(items: &[Exported]) -> String {
+    let mut output = String::new();
+    push_csv_row(&mut output, &COLUMNS);
+    for item in items {
+        let cells = fields(item).map(|field| field.unwrap_or_default());
+        push_csv_row(&mut output, &cells);
+    }
+    output
+}
+
+fn export_json(items: &[Exported]) -> String {
+    let items: Vec<ExportedItem> = items.iter().map(ExportedItem::from).collect();
+    let mut output = serde_json::to_string_pretty(&items).expect("strings serialise");
+    output.push('\n');
+    output
+}
+
+/// An item as the JSON export writes it: the fields in [`COLUMNS`] order, and
+/// the attributes it does not have left out rather than written `null`.
+#[derive(Serialize)]
+struct ExportedItem<'a> {
+    name: &'a str,
+    section: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    quantity: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bought: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expire: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    low: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    restock: Option<&'a str>,
+}
+
+impl<'a> From<&'a Exported<'a>> for ExportedItem<'a> {
+    fn from(exported: &'a Exported<'a>) -> Self {
+        let item = exported.item;
+        Self {
+            name: &item.name,
+            section: &item.section,
+            quantity: item.quantity.as_deref(),
+            bought: item.bought.as_deref(),
+            expire: item.expire.as_deref(),
+            low: item.low.as_deref(),
+            restock: exported.restock.as_deref(),
+        }
+    }
+}
+
+/// An item's fields in [`COLUMNS`] order.
+fn fields<'a>(exported: &'a Exported) -> [Option<&'a str>; 7] {
+    let item = exported.item;
+    [
+        Some(item.name.as_str()),
+        Some(item.section.as_str()),
+        item.quantity.as_deref(),
+        item.bought.as_deref(),
+        item.expire.as_deref(),
+        item.low.as_deref(),
+        exported.restock.as_deref(),
+    ]
+}
+
+// ---------------------------------------------------------------------------
+// import
+// ---------------------------------------------------------------------------
+
+/// Read a spreadsheet into the pantry and write it back. See the [module
+/// documentation](self) for the columns and for which rows are turned away.
+///
+/// Rows are applied in file order, each as [`ImportMode`] says. An item is
+/// matched to the pantry's by section and name exactly, as
+/// [`update`](super::update) matches it, so `Milk` and `milk` are two items.
+/// Nothing is written for a [`ImportRequest::dry_run`], or when nothing
+/// changed; otherwise the pantry file is created if there is none, as
+/// [`add`](super::add) creates it.
+///
+/// Warnings — an ignored column, a section rewritten from a list of names,
+/// anything `cooklang` says about the pantry as it was — come back in
+/// [`Outcome::diagnostics`].
+///
+/// # Errors
+///
+/// - [`CoreError::PantryImport`] if the format cannot be told from the path,
+///   the text is not a CSV with a `name` column or a JSON list of objects, or
+///   a [`ImportMode::Replace`] has no row left to import — which would empty
+///   the pantry, and is far likelier to be the wrong file than what was
+///   meant.
+/// - As [`add`](super::add) otherwise.
+pub fn import(ctx: &Context, req: ImportRequest) -> Result<Outcome<Import>, CoreError> {
+    let path = req.path.as_deref();
+    let format = match (req.format, path) {
+        (Some(format), _) => format,
+        (None, Some(path)) => TransferFormat::from_path(path)
+            .ok_or_else(|| import_error(Some(path), "say whether it is CSV or JSON"))?,
+        (None, None) => return Err(import_error(None, "say whether it is CSV or JSON")),
+    };
+    let rows = match format {
+        TransferFormat::Csv => read_csv(&req.text, path)?,
+        TransferFormat::Json => read_json(&req.text, path)?,
+    };
+
+    let mut diagnostics: Vec<Diagnostic> = rows
+        .ignored
+        .iter()
+        .map(|column| {
+            let diagnostic = Diagnostic::warning(format!(
+                "'{column}' is not a pantry item field, so it is ignored"
+            ));
+            match path {
+                Some(path) => diagnostic.at_file(path),
+                None => diagnostic,
+            }
+        })
+        .collect();
+    let mut summary = Import::default();
+    let accepted = accept(rows.rows, &mut summary.rejected);
+    if req.mode == ImportMode::Replace && accepted.is_empty() {
+        return Err(import_error(
+            path,
+            "no row could be imported, and replacing the pantry with nothing would empty it",
+        ));
+    }
+
+    let pantry = path_to_create(ctx)?;
+    let (mut doc, read) = read_document_or_empty(&pantry)?;
+    diagnostics.extend(read);
+    let before = parse_conf(&pantry, &doc.to_string())?.0;
+
+    let mut changes = Vec::new();
+    let mut imported = BTreeSet::new();
+    for row in accepted {
+        diagnostics.extend(normalise_array_section(&mut doc, &row.section, &pantry));
+        let was = edit::entry_text(&doc, &row.section, &row.name);
+        match (&was, req.mode) {
+            (None, _) => edit::insert(&mut doc, &row.section, &row.name, &row.attributes),
+            (Some(_), ImportMode::Merge) if row.attributes.is_empty() => {}
+            (Some(_), ImportMode::Merge) => {
+                // An entry `apply` cannot read — a hand-written `salt = 3` —
+                // is the one row found to be unwritable only now.
+                if let Err(error) = edit::apply(&mut doc, &row.section, &row.name, &row.attributes)
+                {
+                    summary.rejected.push(RejectedRow {
+                        row: row.row,
+                        name: Some(row.name),
+                        reason: reason(error),
+                    });
+                    continue;
+                }
+            }
+            (Some(was), ImportMode::Replace) => {
+                let mut fresh = toml_edit::DocumentMut::new();
+                edit::insert(&mut fresh, &row.section, &row.name, &row.attributes);
+                if edit::entry_text(&fresh, &row.section, &row.name).as_ref() != Some(was) {
+                    edit::replace(&mut doc, &row.section, &row.name, &row.attributes);
+                }
+            }
+        }
+        let now = edit::entry_text(&doc, &row.section, &row.name);
+        match &was {
+            None => summary.added += 1,
+            Some(_) if was == now => summary.unchanged += 1,
+            Some(_) => summary.updated += 1,
+        }
+        if was != now {
+            changes.push(ItemChange {
+                section: row.section.clone(),
+                name: row.name.clone(),
+                before: was,
+                after: now,
+            });
+        }
+        imported.insert((row.section, row.name));
+    }
+
+    if req.mode == ImportMode::Replace {
+        for (section, items) in &before.sections {
+            for item in items {
+                let key = (section.clone(), item.name().to_string());
+                if imported.contains(&key) || !edit::item_exists(&doc, section, item.name()) {
+                    continue;
+                }
+                let was = edit::entry_text(&doc, section, item.name());
+                edit::remove(&mut doc, section, item.name());
+                summary.removed += 1;
+                changes.push(ItemChange {
+                    section: key.0,
+                    name: key.1,
+                    before: was,
+                    after: None,
+                });
+            }
+        }
+    }
+
+    if !req.dry_run && !changes.is_empty() {
+        write_atomically(&pantry, doc.to_string())?;
+        let operation = match req.mode {
+            ImportMode::Merge => "import",
+            ImportMode::Replace => "import replace",
+        };
+        diagnostics.extend(journal::append(ctx, &pantry, operation, None, changes));
+    }
+
+    Ok(Outcome::with_diagnostics(summary, diagnostics))
+}
+
+fn import_error(path: Option<&Utf8Path>, message: impl Into<String>) -> CoreError {
+    CoreError::PantryImport {
+        path: path.map(ToOwned::to_owned),
+        message: message.into(),
+    }
+}
+
+/// The message of an edit refused for one item, without the "cannot change
+/// the pantry" its `Display` opens with: the row is being turned away, not
+/// the import.
+fn reason(error: CoreError) -> String {
+    match error {
+        CoreError::PantryEdit { message } => message,
+        other => other.to_string(),
+    }
+}
+
+/// One row as the file gave it, before it is checked.
+struct Row {
+    /// As [`RejectedRow::row`].
+    row: usize,
+    /// Each of [`COLUMNS`] the row gave a value for, empty cells left out.
+    fields: BTreeMap<&'static str, String>,
+}
+
+/// A file's rows, and the columns in it that are not pantry fields.
+struct Rows {
+    rows: Vec<Row>,
+    ignored: Vec<String>,
+}
+
+/// A row that can be written.
+struct Accepted {
+    row: usize,
+    section: String,
+    name: String,
+    attributes: edit::Attributes,
+}
+
+/// Check every row, turning away the ones that cannot be written.
+fn accept(rows: Vec<Row>, rejected: &mut Vec<RejectedRow>) -> Vec<Accepted> {
+    let mut accepted: Vec<Accepted> = Vec::new();
+    for Row { row, mut fields } in rows {
+        let name = fields.remove("name");
+        let mut reject = |reason: String| {
+            rejected.push(RejectedRow {
+                row,
+                name: name.clone(),
+                reason,
+            });
+        };
+        let Some(name) = name.clone() else {
+            reject("it has no name".to_string());
+            continue;
+        };
+        let section = fields
+            .remove("section")
+            .unwrap_or_else(|| GENERAL.to_string());
+        let attributes = edit::Attributes {
+            quantity: fields.remove("quantity"),
+            bought: fields.remove("bought"),
+            expire: fields.remove("expire"),
+            low: fields.remove("low"),
+            restock: fields.remove(RESTOCK),
+        };
+
+        let unreadable_amount = [
+            ("quantity", &attributes.quantity),
+            ("low", &attributes.low),
+            ("restock", &attributes.restock),
+        ]
+        .into_iter()
+        .find_map(|(field, value)| {
+            let value = value.as_deref()?;
+            let readable =
+                matches!(value, "unlim" | "unlimited") || split_quantity(value).is_some();
+            (!readable).then(|| format!("its {field} '{value}' is not a number and a unit"))
+        });
+        let unreadable_date = [
+            ("bought", &attributes.bought),
+            ("expire", &attributes.expire),
+        ]
+        .into_iter()
+        .find_map(|(field, value)| {
+            let value = value.as_deref()?;
+            parse_date(value)
+                .is_none()
+                .then(|| format!("its {field} date '{value}' cannot be read as a date"))
+        });
+        if let Some(problem) = unreadable_amount.or(unreadable_date) {
+            reject(problem);
+            continue;
+        }
+        if let Err(error) = edit::check_general_attributes(&section, &name, &attributes) {
+            reject(reason(error));
+            continue;
+        }
+        if let Some(earlier) = accepted
+            .iter()
+            .find(|earlier| earlier.section == section && earlier.name == name)
+        {
+            reject(format!(
+                "row {} already gives '{name}' in section '{section}'",
+                earlier.row
+            ));
+            continue;
+        }
+        accepted.push(Accepted {
+            row,
+            section,
+            name,
+            attributes,
+        });
+    }
+    accepted
+}
+
+/// Which of [`COLUMNS`] `header` names, ignoring case and surrounding space.
+fn column(header: &str) -> Option<&'static str> {
+    let header = header.trim();
+    COLUMNS
+        .into_iter()
+        .find(|column| column.eq_ignore_ascii_case(header))
+}
+
+fn read_csv(text: &str, path: Option<&Utf8Path>) -> Result<Rows, CoreError> {
+    let mut records = csv_records(text).into_iter();
+    let Some(header) = records.next() else {
+        return Err(import_error(path, "the file is empty"));
+    };
+    let columns: Vec<Option<&'static str>> = header.iter().map(|cell| column(cell)).collect();
+    if !columns.contains(&Some("name")) {
+        return Err(import_error(path, "there is no 'name' column"));
+    }
+    let ignored = header
+        .iter()
+        .zip(&columns)
+        .filter(|(cell, column)| column.is_none() && !cell.trim().is_empty())
+        .map(|(cell, _)| cell.trim().to_string())
+        .collect();
+
+    let rows = records
+        .enumerate()
+        // Row 1 is the header.
+        .map(|(i, record)| (i + 2, record))
+        .filter(|(_, record)| record.iter().any(|cell| !cell.trim().is_empty()))
+        .map(|(row, record)| Row {
+            row,
+            fields: columns
+                .iter()
+                .zip(record)
+                .filter_map(|(column, cell)| {
+                    let cell = cell.trim();
+                    Some((column.as_ref().copied()?, cell.to_string())).filter(|_| !cell.is_empty())
+                })
+                .collect(),
+        })
+        .collect();
+    Ok(Rows { rows, ignored })
+}
+
+/// Split CSV text into records of unquoted cells, as RFC 4180 writes them: a
+/// cell in double quotes may hold commas, line breaks and `""` for a quote.
+/// CRLF and LF both end a record. Never fails — a quote left open runs to the
+/// end of the text, which is as much as can be made of it.
+fn csv_records(text: &str) -> Vec<Vec<String>> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut cell = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                cell.push('"');
+            }
+            (true, '"') => quoted = false,
+            (true, c) => cell.push(c),
+            (false, '"') => quoted = true,
+            (false, ',') => record.push(std::mem::take(&mut cell)),
+            (false, '\r') if chars.peek() == Some(&'\n') => {}
+            (false, '\n') => {
+                record.push(std::mem::take(&mut cell));
+                records.push(std::mem::take(&mut record));
+            }
+            (false, c) => cell.push(c),
+        }
+    }
+    if !cell.is_empty() || !record.is_empty() {
+        record.push(cell);
+        records.push(record);
+    }
+    records
+}
+
+fn read_json(text: &str, path: Option<&Utf8Path>) -> Result<Rows, CoreError> {
+    let value: serde_json::Value = serde_json::from_str(text)
+        .map_err(|error| import_error(path, format!("it is not JSON: {error}")))?;
+    let serde_json::Value::Array(items) = value else {
+        return Err(import_error(path, "it is not a JSON list of items"));
+    };
+
+    let mut ignored: Vec<String> = Vec::new();
+    let mut rows = Vec::new();
+    for (i, item) in items.into_iter().enumerate() {
+        let serde_json::Value::Object(object) = item else {
+            return Err(import_error(
+                path,
+                format!("item {} is not an object", i + 1),
+            ));
+        };
+        let mut fields = BTreeMap::new();
+        for (key, value) in object {
+            let Some(column) = column(&key) else {
+                if !ignored.contains(&key) {
+                    ignored.push(key);
+                }
+                continue;
+            };
+            // Numbers are taken as written, so that `"quantity": 2` is the
+            // `2` a spreadsheet export would have meant by it.
+            let value = match value {
+                serde_json::Value::String(value) => value.trim().to_string(),
+                serde_json::Value::Number(number) => number.to_string(),
+                _ => continue,
+            };
+            if !value.is_empty() {
+                fields.insert(column, value);
+            }
+        }
+        rows.push(Row { row: i + 1, fields });
+    }
+    Ok(Rows { rows, ignored })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_records_unquote_commas_quotes_and_line_breaks() {
+        let records = csv_records("name,notes\r\n\"salt, sea\",\"a \"\"big\"\"\nbag\"\nrice,\n");
+        assert_eq!(
+            records,
+            [
+                vec!["name", "notes"],
+                vec!["salt, sea", "a \"big\"\nbag"],
+                vec!["rice", ""],
+            ]
+        );
+    }
+
+    /// A last line with no line break is still a record, and a byte-order
+    /// mark, which spreadsheets like to write, is not part of the first
+    /// column's name.
+    #[test]
+    fn csv_records_take_a_last_unterminated_line_and_skip_a_bom() {
+        let records = csv_records("\u{feff}name\nflour");
+        assert_eq!(records, [vec!["name"], vec!["flour"]]);
+    }
+
+    #[test]
+    fn the_format_is_told_from_the_extension() {
+        assert_eq!(
+            TransferFormat::from_path(Utf8Path::new("stock.CSV")),
+            Some(TransferFormat::Csv)
+        );
+        assert_eq!(
+            TransferFormat::from_path(Utf8Path::new("stock.json")),
+            Some(TransferFormat::Json)
+        );
+        assert_eq!(TransferFormat::from_path(Utf8Path::new("stock.xlsx")), None);
+    }
+}
@@ -0,0 +1,231 @@
+//! Which recipes to cook so that nothing goes off.
+//!
+//! [`expiring`](super::expiring) says what is about to go off and
+//! [`recipes`](super::recipes) says what can be cooked; [`use_up`] answers
+//! both at once, ranking the recipes by how much of what is about to go off
+//! they would use, and how little they still need.
+
+use super::{expiring_on, load, recipe_name, split_quantity, ExpiringRequest};
+use crate::{
+    find::{build_tree, listed_ingredients, parse_or_skip, walk},
+    units, Context, CoreError, Outcome,
+};
+use camino::Utf8PathBuf;
+use chrono::{Local, NaiveDate};
+use cooklang::{quantity::Value, Recipe};
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+/// How far ahead to look.
+///
+/// Not `#[non_exhaustive]`: consumers construct this.
+#[derive(Debug, Clone)]
+pub struct UseUpRequest {
+    /// Count items expiring within this many days of today, as
+    /// [`ExpiringRequest::days`]. Items that have already expired count too.
+    pub days: u32,
+}
+
+impl Default for UseUpRequest {
+    /// A week ahead, as [`ExpiringRequest`]'s default.
+    fn default() -> Self {
+        Self { days: 7 }
+    }
+}
+
+/// An expiring pantry item a recipe would use.
+///
+/// `#[non_exhaustive]` because this is an output type consumers read rather
+/// than construct.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RescuedItem {
+    /// The item's name, as the pantry writes it.
+    pub name: String,
+    /// The section it is in.
+    pub section: String,
+    /// Its expiry date, normalised to ISO 8601 as
+    /// [`ExpiringItem::expire_date`](super::ExpiringItem::expire_date).
+    pub expire_date: String,
+    /// Days from today until it expires; negative once it has expired.
+    pub days_until_expiry: i64,
+    /// How much of what the pantry holds the recipe would use, from just
+    /// above `0.0` to `1.0` for all of it. `1.0` too when it cannot be worked
+    /// out — see [`use_up`].
+    pub share: f64,
+}
+
+/// A recipe worth cooking before its ingredients go off.
+///
+/// `#[non_exhaustive]` because this is an output type consumers read rather
+/// than construct.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UseUpMatch {
+    /// The recipe's title, or its file stem when it has none.
+    pub name: String,
+    /// Its file, relative to [`Context::base_path`].
+    pub path: Option<Utf8PathBuf>,
+    /// What the ranking is by: higher is better. See [`use_up`].
+    pub score: f64,
+    /// The expiring items it would use, soonest to expire first.
+    pub uses: Vec<RescuedItem>,
+    /// The ingredients the pantry does not stock at all, lowercased as they
+    /// were compared, in alphabetical order.
+    pub missing: Vec<String>,
+}
+
+/// The recipes under [`Context::base_path`] that would use up what is about
+/// to go off, best first.
+///
+/// Only a recipe using at least one item expiring within
+/// [`UseUpRequest::days`] is returned. Each such item counts for more the
+/// sooner it expires — one that expires today or already has counts fully,
+/// one expiring on the last day of the window barely — and for more the more
+/// of it the recipe would use, as [`RescuedItem::share`]. The share is worked
+/// out from the recipe's amounts at its own scale, converted into the item's
+/// unit where they measure the same thing; when that cannot be done — the
+/// recipe gives no amount, the item no quantity, or the units do not convert
+/// — the recipe is taken to use all of it.
+///
+/// The total is then multiplied by the fraction of the recipe's ingredients
+/// that are in stock, so that a recipe which uses up the milk but needs five
+/// things bought first ranks below one that can be cooked tonight. That
+/// product is [`UseUpMatch::score`]; ties go to the recipe missing fewer
+/// ingredients, then alphabetically.
+///
+/// Ingredients are matched to pantry items by name, ignoring case, as
+/// [`recipes`](super::recipes) matches them, and recipes that cannot be read
+/// or parsed are left out with a warning, as there.
+///
+/// # Errors
+///
+/// As [`recipes`](super::recipes).
+pub fn use_up(ctx: &Context, req: UseUpRequest) -> Result<Outcome<Vec<UseUpMatch>>, CoreError> {
+    use_up_on(ctx, &req, Local::now().date_naive())
+}
+
+/// [`use_up`] against a given date, as [`expiring_on`] is to
+/// [`expiring`](super::expiring).
+pub(super) fn use_up_on(
+    ctx: &Context,
+    req: &UseUpRequest,
+    today: NaiveDate,
+) -> Result<Outcome<Vec<UseUpMatch>>, CoreError> {
+    let loaded = load(ctx)?;
+    let mut diagnostics = loaded.diagnostics;
+    let stocked: BTreeSet<String> = loaded
+        .value
+        .items()
+        .map(|item| item.name.to_lowercase())
+        .collect();
+    let expiring = expiring_on(
+        &loaded.value,
+        &ExpiringRequest {
+            days: req.days,
+            include_unknown: false,
+        },
+        today,
+    );
+    // The window counted inclusively, so that the last day in it still
+    // counts for something.
+    let window = f64::from(req.days) + 1.0;
+
+    let tree = build_tree(ctx.base_path())?;
+    let mut matches = Vec::new();
+
+    for entry in walk(&tree) {
+        let Some(recipe) = parse_or_skip(entry, &mut diagnostics) else {
+            continue;
+        };
+        let wanted: BTreeSet<String> = listed_ingredients(&recipe)
+            .iter()
+            .map(|name| name.to_lowercase())
+            .collect();
+        if wanted.is_empty() {
+            continue;
+        }
+
+        let mut uses: Vec<RescuedItem> = Vec::new();
+        let mut rescued = 0.0;
+        for expiring in &expiring {
+            let item = &expiring.item;
+            let name = item.name.to_lowercase();
+            if !wanted.contains(&name) {
+                continue;
+            }
+            let days = expiring
+                .days_until_expiry
+                .expect("only items with a date are asked for");
+            let urgency = (window - days.max(0) as f64) / window;
+            let share = share_of(&recipe, &name, item.quantity.as_deref()).unwrap_or(1.0);
+            rescued += urgency * share;
+            uses.push(RescuedItem {
+                name: item.name.clone(),
+                section: item.section.clone(),
+                expire_date: expiring
+                    .expire_date
+                    .clone()
+                    .expect("only items with a date are asked for"),
+                days_until_expiry: days,
+                share,
+            });
+        }
+        if uses.is_empty() {
+            continue;
+        }
+
+        let missing: Vec<String> = wanted
+            .iter()
+            .filter(|name| !stocked.contains(*name))
+            .cloned()
+            .collect();
+        let in_stock = (wanted.len() - missing.len()) as f64 / wanted.len() as f64;
+        matches.push(UseUpMatch {
+            name: recipe_name(entry),
+            path: entry.path().map(|path| {
+                path.strip_prefix(ctx.base_path())
+                    .unwrap_or(path)
+                    .to_owned()
+            }),
+            score: rescued * in_stock,
+            uses,
+            missing,
+        });
+    }
+
+    matches.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then(a.missing.len().cmp(&b.missing.len()))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    Ok(Outcome::with_diagnostics(matches, diagnostics))
+}
+
+/// How much of a pantry item holding `quantity` the recipe's `ingredient`
+/// would use, capped at all of it; `None` if that cannot be worked out.
+fn share_of(recipe: &Recipe, ingredient: &str, quantity: Option<&str>) -> Option<f64> {
+    let (have, unit) = split_quantity(quantity?)?;
+    if have <= 0.0 {
+        return None;
+    }
+    let mut needed = 0.0;
+    let mut any = false;
+    for used in recipe
+        .ingredients
+        .iter()
+        .filter(|used| used.display_name().to_lowercase() == ingredient)
+    {
+        let Some(amount) = &used.quantity else {
+            continue;
+        };
+        let Value::Number(number) = amount.value() else {
+            return None;
+        };
+        needed += units::convert(number.value(), amount.unit().unwrap_or_default(), &unit)?;
+        any = true;
+    }
+    any.then(|| (needed / have).min(1.0))
+}
@@ -75,14 +75,14 @@ pub(crate) fn parse_unscaled(
     let display_path = file.map_or_else(|| name.to_string(), |p| p.to_string());
     let parse_error = |report: &SourceReport| CoreError::Parse {
         name: name.to_string(),
-        diagnostics: collect_diagnostics(report, file),
+        diagnostics: collect_diagnostics(report, text, file),
         rendered: render_report(report, &display_path, text, false),
     };
 
     if parsed.report().has_errors() {
         return Err(parse_error(parsed.report()));
     }
-    let diagnostics = collect_diagnostics(parsed.report(), file);
+    let diagnostics = collect_diagnostics(parsed.report(), text, file);
 
     match parsed.into_result() {
         Ok((recipe, _)) => Ok(Outcome::with_diagnostics(recipe, diagnostics)),
@@ -114,17 +114,22 @@ pub fn render_report(
 }
 
 /// Convert every entry of a `cooklang` report into a [`Diagnostic`].
+///
+/// `source` is the text the report was produced from, so that every span can
+/// be resolved to the lines and columns [`Location::range`] carries while the
+/// text is still in hand.
 pub(crate) fn collect_diagnostics(
     report: &SourceReport,
+    source: &str,
     file: Option<&Utf8Path>,
 ) -> Vec<Diagnostic> {
     report
         .iter()
-        .map(|diag| convert_diagnostic(diag, file))
+        .map(|diag| convert_diagnostic(diag, source, file))
         .collect()
 }
 
-fn convert_diagnostic(diag: &SourceDiag, file: Option<&Utf8Path>) -> Diagnostic {
+fn convert_diagnostic(diag: &SourceDiag, source: &str, file: Option<&Utf8Path>) -> Diagnostic {
     let severity = match diag.severity {
         cooklang::error::Severity::Error => Severity::Error,
         cooklang::error::Severity::Warning => Severity::Warning,
@@ -137,10 +142,11 @@ fn convert_diagnostic(diag: &SourceDiag, file: Option<&Utf8Path>) -> Diagnostic
     Diagnostic {
         severity,
         message: diag.message.to_string(),
-        location: location_for(file, span),
+        location: location_for(file, span, source),
         // Often a ready-to-apply replacement, which is exactly the payload
         // the CLI's `warn!` used to discard.
         hints: diag.hints.iter().map(|h| h.to_string()).collect(),
+        code: None,
     }
 }
 
@@ -149,13 +155,14 @@ fn convert_diagnostic(diag: &SourceDiag, file: Option<&Utf8Path>) -> Diagnostic
 /// A span alone is still worth reporting: an editor parsing an unsaved buffer
 /// has no path but still wants to underline the offending text. Only when
 /// neither is known is there no location at all.
-fn location_for(file: Option<&Utf8Path>, span: Option<Span>) -> Option<Location> {
+fn location_for(file: Option<&Utf8Path>, span: Option<Span>, source: &str) -> Option<Location> {
     if file.is_none() && span.is_none() {
         return None;
     }
     Some(Location {
         file: file.map(ToOwned::to_owned),
         span,
+        range: span.map(|span| span.to_range(source)),
     })
 }
 
@@ -424,37 +431,57 @@ mod tests {
     #[test]
     fn location_is_built_from_whichever_parts_are_known() {
         let file = Utf8Path::new("soup.cook");
-        let span = Span { start: 1, end: 4 };
+        let source = "a\nbcdef";
+        let span = Span { start: 3, end: 5 };
+        let range = Some(span.to_range(source));
 
         // Neither known: no location at all, rather than an empty one.
-        assert_eq!(location_for(None, None), None);
+        assert_eq!(location_for(None, None, source), None);
 
         // A span with no file still locates the problem for an unsaved buffer.
         assert_eq!(
-            location_for(None, Some(span)),
+            location_for(None, Some(span), source),
             Some(Location {
                 file: None,
-                span: Some(span)
+                span: Some(span),
+                range,
             })
         );
 
         assert_eq!(
-            location_for(Some(file), None),
+            location_for(Some(file), None, source),
             Some(Location {
                 file: Some(file.to_owned()),
-                span: None
+                span: None,
+                range: None,
             })
         );
 
         assert_eq!(
-            location_for(Some(file), Some(span)),
+            location_for(Some(file), Some(span), source),
             Some(Location {
                 file: Some(file.to_owned()),
-                span: Some(span)
+                span: Some(span),
+                range,
             })
         );
     }
 
+    /// The range is what a CI annotation is placed by, so it has to point at
+    /// the same text the span does.
+    #[test]
+    fn parser_diagnostics_carry_a_resolved_range() {
+        let text = "Intro line.\nAdd @{1%tsp} to the pot.\n";
+        let Err(CoreError::Parse { diagnostics, .. }) = parse_recipe(text, "broken", 1.0) else {
+            panic!("recipe should fail to parse");
+        };
+        let location = diagnostics[0].location.as_ref().expect("location set");
+        let span = location.span.expect("span set");
+        let range = location.range.expect("range set");
+        assert_eq!(range, span.to_range(text));
+        assert_eq!(range.start.line, 2);
+    }
+
     #[test]
     fn render_report_includes_the_display_path_and_source_context() {
         let text = "Add @{1%tsp} to the pot.\n";
@@ -0,0 +1,23 @@
+//! Times as RFC 3339 to the second, in UTC: `2026-10-19T18:30:00Z`.
+//!
+//! The form the shopping list's history and the pantry's journal both write,
+//! so that the two logs read alike and sort as text. Used as
+//! `#[serde(with = "crate::rfc3339")]` on a `DateTime<Utc>` field.
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Deserializer, Serializer};
+
+pub(crate) fn format(at: &DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+pub(crate) fn serialize<S: Serializer>(at: &DateTime<Utc>, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_str(&format(at))
+}
+
+pub(crate) fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<DateTime<Utc>, D::Error> {
+    let text = String::deserialize(d)?;
+    DateTime::parse_from_rfc3339(&text)
+        .map(|at| at.with_timezone(&Utc))
+        .map_err(serde::de::Error::custom)
+}
@@ -0,0 +1,273 @@
+//! Putting what was bought into the pantry.
+//!
+//! Ticking an item off a saved list says it is in the basket; once the
+//! shopping is home, [`commit_to_pantry`] carries every ticked item over to
+//! the pantry file, so that the next list stops asking for it:
+//!
+//! - An item the pantry already has gets what was bought added to its
+//!   quantity, converted into the unit the pantry writes it in (see
+//!   [`crate::units`]), and a new `bought` date. An item written without a
+//!   quantity, or `unlim`, only gets the date — it was never counted.
+//! - An item the pantry does not have is added, under the aisle category the
+//!   list put it in, with the amount the list asked for.
+//!
+//! The amount bought is the amount the list asked for: what the recipes need,
+//! less what the pantry already covered. Each item is written through
+//! [`pantry::add`] or [`pantry::update`], so only its own entry in the file
+//! changes, and its tick is taken off as soon as it is written — running the
+//! commit again, or after a failure part way through, cannot count anything
+//! twice.
+
+use super::{
+    generate_saved, ordered_components, quantity_fmt, saved_ingredient_names, ShoppingListStore,
+};
+use crate::{
+    pantry::{
+        self, split_quantity, write_quantity, AddRequest, PantryContents, PantryItem, UpdateRequest,
+    },
+    units, ConfigSource, Context, CoreError, Diagnostic, Outcome,
+};
+use chrono::{Local, NaiveDate};
+use cooklang::quantity::{GroupedQuantity, Value};
+use serde::Serialize;
+
+/// The section items written above the first header fall under, which can
+/// carry a quantity but no dates.
+const GENERAL: &str = "general";
+
+/// When the ticked items were bought.
+///
+/// Not `#[non_exhaustive]`: consumers construct this. `..Default::default()`
+/// keeps a literal working if it grows a field.
+#[derive(Debug, Clone, Default)]
+pub struct CommitRequest {
+    /// The date to stamp as `bought`. `None` means today.
+    pub bought: Option<NaiveDate>,
+}
+
+/// One ticked item, as it went into the pantry.
+///
+/// `#[non_exhaustive]` because this is an output type that consumers read
+/// rather than construct.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BoughtItem {
+    /// The item's name in the pantry: the pantry's own spelling when it
+    /// already had it, the list's otherwise.
+    pub name: String,
+    /// The pantry section it is in.
+    pub section: String,
+    /// What the list asked for, rendered as the list shows it.
+    pub bought: Vec<String>,
+    /// How much the pantry holds now, as the file writes it, or `None` for
+    /// an item it does not count.
+    pub stock: Option<String>,
+    /// True when the pantry did not have it before.
+    pub added: bool,
+}
+
+/// Move every ticked item on `store` into the pantry [`Context::pantry`]
+/// names, take their ticks off, and compact the checked log. Answers with
+/// what went in, in list order; an empty answer means nothing was ticked.
+///
+/// The list is the one [`generate_saved`] builds, so items are matched under
+/// their aisle names. A ticked item the list no longer has is left alone.
+///
+/// When the context carries no pantry, one is created where
+/// [`pantry::add`] creates it. Amounts that cannot be added to the pantry's
+/// — a unit that does not convert to the one the pantry uses, a quantity
+/// written as text on either side — are left out of the new quantity with a
+/// warning in [`Outcome::diagnostics`]; the item is still stamped as bought.
+/// A new item the list gave no number for goes in with no quantity, which the
+/// pantry reads as "plenty".
+///
+/// Like [`ShoppingListStore::compact`], this rewrites the checked log, and
+/// keeping concurrent ticks out while it does is the caller's
+/// responsibility.
+///
+/// # Errors
+///
+/// - As [`generate_saved`], since the list has to be built first.
+/// - As [`pantry::load`], [`pantry::add`] and [`pantry::update`], for the
+///   pantry — [`CoreError::ReadOnlyConfig`] for one held inline. Items
+///   written before the error keep their place in the pantry and lose their
+///   ticks; the rest keep theirs.
+pub fn commit_to_pantry(
+    ctx: &Context,
+    store: &ShoppingListStore,
+    req: CommitRequest,
+) -> Result<Outcome<Vec<BoughtItem>>, CoreError> {
+    let checked = store.checked_set()?;
+    if checked.is_empty() {
+        return Ok(Outcome::new(Vec::new()));
+    }
+
+    let generated = generate_saved(ctx, store)?;
+    let mut diagnostics = generated.diagnostics;
+    let list = generated.value;
+    let stocked = match ctx.pantry() {
+        ConfigSource::None => PantryContents {
+            sections: Vec::new(),
+        },
+        _ => {
+            let loaded = pantry::load(ctx)?;
+            diagnostics.extend(loaded.diagnostics);
+            loaded.value
+        }
+    };
+
+    let date = req
+        .bought
+        .unwrap_or_else(|| Local::now().date_naive())
+        .format("%Y-%m-%d")
+        .to_string();
+    let mut committed = Vec::new();
+
+    for (category, items) in &list.raw_categories {
+        for (name, quantity) in items {
+            if !checked.contains(&name.to_lowercase()) {
+                continue;
+            }
+            let existing = stocked
+                .items()
+                .find(|item| item.name.eq_ignore_ascii_case(name));
+            let item = match existing {
+                Some(existing) => restock(ctx, existing, quantity, &date, &mut diagnostics)?,
+                None => stock_new(ctx, category, name, quantity, &date, &mut diagnostics)?,
+            };
+            store.uncheck(name)?;
+            committed.push(BoughtItem {
+                bought: list
+                    .items
+                    .iter()
+                    .find(|listed| &listed.name == name)
+                    .map(|listed| listed.quantities.clone())
+                    .unwrap_or_default(),
+                ..item
+            });
+        }
+    }
+
+    let names = saved_ingredient_names(ctx, store)?;
+    diagnostics.extend(names.diagnostics);
+    store.compact(names.value)?;
+
+    Ok(Outcome::with_diagnostics(committed, diagnostics))
+}
+
+/// Add what was bought to an item the pantry already has.
+fn restock(
+    ctx: &Context,
+    existing: &PantryItem,
+    bought: &GroupedQuantity,
+    date: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<BoughtItem, CoreError> {
+    let written = existing.quantity.as_deref();
+    let counted = match written {
+        None | Some("unlim" | "unlimited") => None,
+        Some(written) => match split_quantity(written) {
+            Some(amount) => Some(amount),
+            None => {
+                diagnostics.push(Diagnostic::warning(format!(
+                    "{} in [{}] has the quantity '{written}', which is not a number and a \
+                     unit, so what was bought is not added to it",
+                    existing.name, existing.section
+                )));
+                None
+            }
+        },
+    };
+    let quantity = counted.map(|(have, unit)| {
+        let total = have + sum_in(bought, &unit, &existing.name, diagnostics);
+        write_quantity(total, &unit)
+    });
+
+    // An item above the first section header cannot carry a date, and an
+    // uncounted one then has nothing to change at all.
+    let bought_date = (existing.section != GENERAL).then(|| date.to_string());
+    if quantity.is_some() || bought_date.is_some() {
+        pantry::update(
+            ctx,
+            UpdateRequest {
+                section: existing.section.clone(),
+                name: existing.name.clone(),
+                quantity: quantity.clone(),
+                bought: bought_date,
+                ..Default::default()
+            },
+        )?;
+    }
+
+    Ok(BoughtItem {
+        name: existing.name.clone(),
+        section: existing.section.clone(),
+        bought: Vec::new(),
+        stock: quantity.or_else(|| existing.quantity.clone()),
+        added: false,
+    })
+}
+
+/// Add an item the pantry does not have, under the list's category for it.
+fn stock_new(
+    ctx: &Context,
+    category: &str,
+    name: &str,
+    bought: &GroupedQuantity,
+    date: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<BoughtItem, CoreError> {
+    let unit = ordered_components(bought)
+        .into_iter()
+        .find(|quantity| matches!(quantity.value(), Value::Number(_)))
+        .map(|quantity| quantity.unit().unwrap_or_default().to_string());
+    let quantity = unit.map(|unit| write_quantity(sum_in(bought, &unit, name, diagnostics), &unit));
+
+    pantry::add(
+        ctx,
+        AddRequest {
+            section: category.to_string(),
+            name: name.to_string(),
+            quantity: quantity.clone(),
+            bought: Some(date.to_string()),
+            ..Default::default()
+        },
+    )?;
+
+    Ok(BoughtItem {
+        name: name.to_string(),
+        section: category.to_string(),
+        bought: Vec::new(),
+        stock: quantity,
+        added: true,
+    })
+}
+
+/// Every amount in `bought` converted into `unit` and added up. Amounts that
+/// are not numbers, or not in a unit that converts, are warned about and left
+/// out.
+fn sum_in(
+    bought: &GroupedQuantity,
+    unit: &str,
+    name: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> f64 {
+    let mut total = 0.0;
+    for quantity in ordered_components(bought) {
+        let converted = match quantity.value() {
+            Value::Number(number) => {
+                units::convert(number.value(), quantity.unit().unwrap_or_default(), unit)
+            }
+            _ => None,
+        };
+        match converted {
+            Some(amount) => total += amount,
+            None => diagnostics.push(Diagnostic::warning(format!(
+                "{} of {name} does not add up with the pantry's {}, so it is not counted",
+                quantity_fmt(quantity),
+                if unit.is_empty() { "count" } else { unit }
+            ))),
+        }
+    }
+    total
+}
@@ -33,33 +33,61 @@
 //! [`generate`] is the whole command: it loads the aisle and pantry
 //! configuration from the [`Context`], expands each recipe's references,
 //! merges duplicate ingredients, subtracts what the pantry already holds, and
-//! returns an [`AggregatedList`].
+//! returns an [`AggregatedList`]. [`subtract_pantry`] is the pantry step on
+//! its own.
 //!
 //! [`extract_ingredients`] is the accumulation step on its own, for callers
-//! that build a list incrementally — the web server adds one recipe at a time
-//! and picks which references to follow per recipe.
+//! that build a list incrementally.
+//! [`extract_ingredients_with_sources`] does the same while recording in
+//! [`IngredientSources`] which recipe asked for how much of what.
 //!
 //! [`ShoppingListStore`] is the other half: the `.shopping-list` and
 //! `.shopping-checked` files that remember which recipes someone put on their
 //! list and what they have already ticked off while shopping.
-
+//! [`generate_saved`] builds the list those files describe, and
+//! [`commit_to_pantry`] moves what has been ticked off it into the pantry.
+//! [`generate_entries`] builds one from entries in the same form that are not
+//! on a store — the web server's list page posts the recipes it shows, each
+//! with the references to follow.
+//!
+//! [`StoreProfile`] lays a finished list out for one particular shop; the
+//! [`Context`] names the profile to use, if any.
+//! [`AggregatedList::round_to_packages`] counts what is on it in the packages
+//! each ingredient is sold in.
+
+mod commit;
+pub(crate) mod packages;
+mod pantry;
+mod profile;
+pub(crate) mod sources;
 mod store;
 
-pub use store::{recipe_display_name, ShoppingListStore, StoredEntry};
+pub use commit::{commit_to_pantry, BoughtItem, CommitRequest};
+pub use packages::Packages;
+pub use pantry::{subtract_pantry, PantrySubtraction};
+pub use profile::{store_profile, store_profile_names, StoreProfile};
+pub use sources::{IngredientSources, ItemSource};
+pub use store::{
+    recipe_display_name, ListChange, ManualItem, ShoppingListStore, StoredEntry, DEFAULT_LIST,
+};
 
 use crate::{
     find,
     format::{quantity::ordered_components, shopping_list::quantity_fmt},
     parser::{parse_recipe_at, parse_unscaled, PARSER},
+    substitutes::{Substitutes, Substitution},
     ConfigSource, Context, CoreError, Diagnostic, Outcome, RecipeSource,
 };
 use camino::{Utf8Path, Utf8PathBuf};
+use chrono::{Local, NaiveDate};
 use cooklang::{
-    aisle::AisleConf, ingredient_list::IngredientList, pantry::PantryConf,
-    quantity::GroupedQuantity, quantity::Value, Recipe,
+    aisle::AisleConf, ingredient_list::IngredientList, model::RecipeReference, pantry::PantryConf,
+    quantity::GroupedQuantity, quantity::Quantity, quantity::Value, Recipe,
 };
 use cooklang_find::RecipeEntry;
+use packages::RawPackages;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Where to find the aisle configuration format, quoted when there is none.
 const AISLE_DOCS: &str = "https://cooklang.org/docs/spec/#shopping-lists";
@@ -117,6 +145,10 @@ pub struct GenerateRequest {
     /// This does not drop the reference: it stays on the list as an item named
     /// after the referenced recipe, with no quantity.
     pub ignore_references: bool,
+    /// The day the food will be cooked. Pantry items that expire before it
+    /// are not subtracted, since they will have gone off by then. `None` is
+    /// today.
+    pub cook_date: Option<NaiveDate>,
 }
 
 /// How [`extract_ingredients`] should treat recipe references.
@@ -153,6 +185,22 @@ pub struct ListItem {
     /// [`ordered_components`](crate::format::quantity::ordered_components)
     /// describes.
     pub quantities: Vec<String>,
+    /// How many packages to buy, once
+    /// [`AggregatedList::round_to_packages`] has been told what the
+    /// ingredient is sold in. Absent otherwise, and from the JSON.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub packages: Option<Packages>,
+    /// How much of what the recipes need the pantry already covers, rendered
+    /// like [`quantities`](Self::quantities), for an ingredient it covers
+    /// only in part. `quantities` is what is left to buy. Empty when the
+    /// pantry has none of it, and absent from the JSON then.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub in_pantry: Vec<String>,
+    /// Which recipes asked for the ingredient and how much each, in the
+    /// order they were added — so that a list can say whose the extra cream
+    /// is. The amounts are before the pantry, as the recipes gave them.
+    #[serde(default)]
+    pub sources: Vec<ItemSource>,
 }
 
 /// A group of ingredients sharing an aisle category.
@@ -184,7 +232,8 @@ pub struct AggregatedList {
     /// Every ingredient, uncategorised, in the order the recipes introduced
     /// them.
     pub items: Vec<ListItem>,
-    /// The same ingredients grouped by aisle category. Empty categories are
+    /// The same ingredients grouped by aisle category, laid out by the
+    /// context's [`StoreProfile`] when it names one. Empty categories are
     /// dropped, so this is empty when the list is.
     pub categories: Vec<ListCategory>,
 
@@ -197,6 +246,30 @@ pub struct AggregatedList {
     /// The same as [`categories`](Self::categories), likewise unrendered.
     #[serde(skip)]
     pub(crate) raw_categories: Vec<(String, Vec<(String, GroupedQuantity)>)>,
+    /// [`ListItem::packages`] by ingredient name, unrendered. Empty until
+    /// [`round_to_packages`](Self::round_to_packages).
+    #[serde(skip)]
+    pub(crate) raw_packages: HashMap<String, RawPackages>,
+    /// [`ListItem::in_pantry`] by ingredient name, unrendered.
+    #[serde(skip)]
+    pub(crate) raw_in_pantry: HashMap<String, GroupedQuantity>,
+    /// [`ListItem::sources`], unrendered.
+    #[serde(skip)]
+    pub(crate) raw_sources: IngredientSources,
+    /// The ingredients left off the list because a substitute for them is in
+    /// the pantry, in the order the recipes introduced them. Only serialised
+    /// when there are some, so that the document is unchanged for everyone
+    /// without a `substitutes.conf`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub substitutions: Vec<Substitution>,
+    /// Every ingredient the pantry covers, wholly or in part, in the order
+    /// the recipes introduced them: the ones with
+    /// [`in_pantry`](ListItem::in_pantry), the ones left off the list because
+    /// the pantry has all that is needed, and the
+    /// [substituted](Self::substitutions) ones. Only serialised when there
+    /// are some.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub from_pantry: Vec<String>,
 }
 
 impl AggregatedList {
@@ -210,23 +283,37 @@ impl AggregatedList {
     /// The uncategorised pairs are taken first because
     /// [`IngredientList::categorize`] consumes the list and reorders what it
     /// keeps, so the insertion order cannot be recovered afterwards.
-    fn build(list: IngredientList, aisle: &AisleConf) -> Self {
+    fn build(
+        subtraction: PantrySubtraction,
+        from_pantry: Vec<String>,
+        sources: IngredientSources,
+        aisle: &AisleConf,
+        profile: &StoreProfile,
+    ) -> Self {
+        let PantrySubtraction {
+            list,
+            covered: raw_in_pantry,
+            substituted: substitutions,
+        } = subtraction;
         let raw_items: Vec<(String, GroupedQuantity)> = list
             .iter()
             .map(|(name, quantity)| (name.clone(), quantity.clone()))
             .collect();
-        let items = raw_items.iter().map(ListItem::render).collect();
+        let render =
+            |item: &(String, GroupedQuantity)| ListItem::render(item, &raw_in_pantry, &sources);
+        let items = raw_items.iter().map(render).collect();
 
-        let raw_categories: Vec<(String, Vec<(String, GroupedQuantity)>)> = list
-            .categorize(aisle)
-            .into_iter()
-            .map(|(category, items)| (category, items.into_iter().collect()))
-            .collect();
+        let raw_categories = profile.arrange(
+            list.categorize(aisle)
+                .into_iter()
+                .map(|(category, items)| (category, items.into_iter().collect()))
+                .collect(),
+        );
         let categories = raw_categories
             .iter()
             .map(|(name, items)| ListCategory {
                 name: name.clone(),
-                items: items.iter().map(ListItem::render).collect(),
+                items: items.iter().map(render).collect(),
             })
             .collect();
 
@@ -235,18 +322,50 @@ impl AggregatedList {
             categories,
             raw_items,
             raw_categories,
+            raw_packages: HashMap::new(),
+            raw_in_pantry,
+            raw_sources: sources,
+            substitutions,
+            from_pantry,
         }
     }
 }
 
 impl ListItem {
-    fn render((name, quantity): &(String, GroupedQuantity)) -> Self {
-        Self {
-            name: name.clone(),
-            quantities: ordered_components(quantity)
+    /// What to buy, as the human table says it: the
+    /// [`packages`](Self::packages) first, in place of the quantity they
+    /// were counted from, then the rest of [`quantities`](Self::quantities).
+    pub fn amount(&self) -> Vec<String> {
+        self.packages
+            .as_ref()
+            .map(ToString::to_string)
+            .into_iter()
+            .chain(
+                self.quantities
+                    .iter()
+                    .filter(|q| !self.packages.as_ref().is_some_and(|p| p.covers(q)))
+                    .cloned(),
+            )
+            .collect()
+    }
+
+    fn render(
+        (name, quantity): &(String, GroupedQuantity),
+        in_pantry: &HashMap<String, GroupedQuantity>,
+        sources: &IngredientSources,
+    ) -> Self {
+        let rendered = |quantity: &GroupedQuantity| {
+            ordered_components(quantity)
                 .into_iter()
                 .map(quantity_fmt)
-                .collect(),
+                .collect()
+        };
+        Self {
+            name: name.clone(),
+            quantities: rendered(quantity),
+            packages: None,
+            in_pantry: in_pantry.get(name).map(rendered).unwrap_or_default(),
+            sources: sources.get(name),
         }
     }
 }
@@ -256,7 +375,8 @@ impl ListItem {
 /// Ingredients with the same name are merged, converting units where they
 /// convert; the aisle configuration from [`Context::aisle`] folds synonyms onto
 /// one spelling and supplies the categories; the pantry configuration from
-/// [`Context::pantry`] is subtracted from what is left. Pass
+/// [`Context::pantry`] is subtracted from what is left, as of
+/// [`GenerateRequest::cook_date`] — see [`subtract_pantry`]. Pass
 /// [`ConfigSource::None`] for either to skip that step — that is how CookCLI's
 /// `--ignore-pantry` works.
 ///
@@ -278,6 +398,188 @@ impl ListItem {
 /// A recipe that reaches itself is *not* an error. See
 /// [`extract_ingredients`].
 pub fn generate(ctx: &Context, req: GenerateRequest) -> Result<Outcome<AggregatedList>, CoreError> {
+    let options = ExtractOptions {
+        ignore_references: req.ignore_references,
+        included_references: None,
+    };
+    let cook_date = req.cook_date.unwrap_or_else(|| Local::now().date_naive());
+    aggregate(ctx, cook_date, |gathered, diagnostics| {
+        for recipe in &req.recipes {
+            diagnostics.extend(extract_ingredients_with_sources(
+                ctx,
+                recipe,
+                &options,
+                &mut gathered.list,
+                &mut gathered.sources,
+            )?);
+        }
+        Ok(())
+    })
+}
+
+/// Build the shopping list someone has saved: everything on `store`,
+/// aggregated and categorised as [`generate`] does it. Recipes are looked up
+/// under [`Context::base_path`], which is normally where `store` lives too.
+///
+/// Each stored recipe is expanded at its stored scale, following only the
+/// references stored under it — the ones ticked when it was added — and a
+/// stored menu expands to the recipes stored under it. Items added by hand are
+/// on it too, in the category they were given, and come from no recipe. Items
+/// already ticked off are still on the list;
+/// [`ShoppingListStore::checked_set`] says which. The pantry is subtracted as
+/// of today.
+///
+/// # Errors
+///
+/// As [`generate`], plus [`CoreError::Io`] if the stored list cannot be read.
+pub fn generate_saved(
+    ctx: &Context,
+    store: &ShoppingListStore,
+) -> Result<Outcome<AggregatedList>, CoreError> {
+    generate_entries(ctx, &store.load()?, &store.items()?)
+}
+
+/// Build the shopping list `entries` and `items` describe, exactly as
+/// [`generate_saved`] builds the one on a store, for a caller that has them
+/// without a store: recipes expanded at their scales, following only the
+/// references each names, and items added by hand in the categories they
+/// were given.
+///
+/// # Errors
+///
+/// As [`generate`].
+pub fn generate_entries(
+    ctx: &Context,
+    entries: &[StoredEntry],
+    items: &[ManualItem],
+) -> Result<Outcome<AggregatedList>, CoreError> {
+    aggregate(ctx, Local::now().date_naive(), |gathered, diagnostics| {
+        diagnostics.extend(extract_saved(ctx, entries, gathered)?);
+        gathered.add_manual(items);
+        Ok(())
+    })
+}
+
+/// The names of every ingredient the list in `store` currently needs, as
+/// [`ShoppingListStore::compact`] wants them.
+///
+/// The names are the recipes' own spellings, before aisle synonyms fold them
+/// together; `compact` compares case-insensitively, and a check made against
+/// either spelling survives. Nothing is subtracted for the pantry either —
+/// an ingredient the pantry covers today is still one someone may tick off.
+///
+/// # Errors
+///
+/// Any recipe that fails to load fails the whole call rather than being
+/// skipped. Callers compact with the result, and compacting against a partial
+/// set of names would throw away checks that are still current.
+pub fn saved_ingredient_names(
+    ctx: &Context,
+    store: &ShoppingListStore,
+) -> Result<Outcome<Vec<String>>, CoreError> {
+    let entries = store.load()?;
+    let mut gathered = Gathered::default();
+    let diagnostics = extract_saved(ctx, &entries, &mut gathered)?;
+    gathered.add_manual(&store.items()?);
+    Ok(Outcome::with_diagnostics(
+        gathered.list.iter().map(|(name, _)| name.clone()).collect(),
+        diagnostics,
+    ))
+}
+
+/// Expand stored shopping list entries into `gathered`.
+///
+/// Each entry is expanded on its own: the same recipe may legitimately be on
+/// the list twice, once per batch, and that is not a reference cycle.
+fn extract_saved(
+    ctx: &Context,
+    entries: &[StoredEntry],
+    gathered: &mut Gathered,
+) -> Result<Vec<Diagnostic>, CoreError> {
+    let mut diagnostics = Vec::new();
+    let recipes = entries.iter().flat_map(|entry| match &entry.recipes {
+        Some(recipes) => recipes.as_slice(),
+        None => std::slice::from_ref(entry),
+    });
+    for recipe in recipes {
+        diagnostics.extend(extract_ingredients_with_sources(
+            ctx,
+            &ScaledRecipe::scaled(
+                RecipeSource::Path(recipe.path.as_str().into()),
+                recipe.scale,
+            ),
+            &ExtractOptions {
+                ignore_references: false,
+                included_references: recipe.included_references.as_deref(),
+            },
+            &mut gathered.list,
+            &mut gathered.sources,
+        )?);
+    }
+    Ok(diagnostics)
+}
+
+/// Every recipe's ingredients merged into one list, and where each came from.
+#[derive(Default)]
+struct Gathered {
+    list: IngredientList,
+    sources: IngredientSources,
+    /// The categories items added by hand were given, by lowercased name.
+    categories: HashMap<String, String>,
+}
+
+impl Gathered {
+    /// Put items added by hand on the list. They come from no recipe, so
+    /// they have no sources.
+    fn add_manual(&mut self, items: &[ManualItem]) {
+        add_manual_items(&mut self.list, items);
+        for item in items {
+            if let Some(category) = &item.category {
+                self.categories
+                    .insert(item.name.to_lowercase(), category.clone());
+            }
+        }
+    }
+}
+
+/// Put items added by hand on `list`, adding up with whatever the recipes
+/// already put there under the same name.
+///
+/// Only the names and quantities: a category given by hand is for
+/// [`StoreProfile::place`] to act on once aisle synonyms are folded.
+pub fn add_manual_items(list: &mut IngredientList, items: &[ManualItem]) {
+    for item in items {
+        let mut quantity = GroupedQuantity::empty();
+        if let Some(written) = &item.quantity {
+            quantity.add(&manual_quantity(written), PARSER.converter());
+        }
+        list.add_ingredient(item.name.clone(), &quantity, PARSER.converter());
+    }
+}
+
+/// A hand-written quantity as a number and a unit where it reads as one
+/// (`4`, `500%g`, `2 packs`), so that it adds up with the recipes' amounts,
+/// and as text otherwise.
+fn manual_quantity(written: &str) -> Quantity {
+    let measured = packages::SIZE.captures(written).and_then(|captures| {
+        let value = captures[1].parse::<f64>().ok()?;
+        let unit = captures[2].trim();
+        Some(Quantity::new(
+            Value::from(value),
+            (!unit.is_empty()).then(|| unit.to_string()),
+        ))
+    });
+    measured.unwrap_or_else(|| Quantity::new(Value::Text(written.to_string()), None))
+}
+
+/// The steps every generated list shares around `fill`, which puts the
+/// recipes' ingredients on it: load the aisle and pantry configuration, then
+/// fold synonyms, subtract the pantry as of `cook_date` and categorise.
+fn aggregate(
+    ctx: &Context,
+    cook_date: NaiveDate,
+    fill: impl FnOnce(&mut Gathered, &mut Vec<Diagnostic>) -> Result<(), CoreError>,
+) -> Result<Outcome<AggregatedList>, CoreError> {
     let mut diagnostics = Vec::new();
 
     // Both configurations are read up front and held for the whole call:
@@ -287,26 +589,61 @@ pub fn generate(ctx: &Context, req: GenerateRequest) -> Result<Outcome<Aggregate
     let aisle = load_aisle(aisle_text.as_deref(), ctx.aisle(), &mut diagnostics);
     let pantry_text = ctx.pantry().read()?;
     let pantry = load_pantry(pantry_text.as_deref(), ctx.pantry(), &mut diagnostics);
-
-    let options = ExtractOptions {
-        ignore_references: req.ignore_references,
-        included_references: None,
-    };
-
-    let mut list = IngredientList::new();
-    for recipe in &req.recipes {
-        diagnostics.extend(extract_ingredients(ctx, recipe, &options, &mut list)?);
-    }
-
-    let mut list = list.use_common_names(&aisle, PARSER.converter());
-    if let Some(pantry) = &pantry {
-        list = list.subtract_pantry(pantry, PARSER.converter());
+    // Unlike the aisle file, a profile is only ever used because someone
+    // asked for it by name, so one that cannot be read fails the list.
+    let profile = StoreProfile::load(ctx.store())?;
+    diagnostics.extend(profile.diagnostics);
+    let substitutes = Substitutes::load(ctx.substitutes())?;
+    diagnostics.extend(substitutes.diagnostics);
+    let substitutes = substitutes.value;
+
+    let mut gathered = Gathered::default();
+    fill(&mut gathered, &mut diagnostics)?;
+
+    let sources = gathered.sources.use_common_names(&aisle);
+    let list = gathered.list.use_common_names(&aisle, PARSER.converter());
+    let mut profile = profile.value;
+    if !gathered.categories.is_empty() {
+        let info = aisle.ingredients_info();
+        for (name, category) in gathered.categories {
+            let name = info
+                .get(&name)
+                .map_or(name, |info| info.common_name.to_string());
+            profile.place(&name, &category);
+        }
     }
+    let (subtraction, from_pantry) = match &pantry {
+        Some(pantry) => {
+            let subtraction = subtract_pantry(&list, pantry, cook_date, &substitutes);
+            diagnostics.extend(
+                subtraction
+                    .diagnostics
+                    .into_iter()
+                    .map(|diagnostic| at_source(diagnostic, ctx.pantry())),
+            );
+            let subtraction = subtraction.value;
+            let from_pantry = list
+                .iter()
+                .map(|(name, _)| name)
+                .filter(|name| {
+                    subtraction.covered.contains_key(*name)
+                        || !subtraction.list.iter().any(|(kept, _)| kept == *name)
+                })
+                .cloned()
+                .collect();
+            (subtraction, from_pantry)
+        }
+        None => (
+            PantrySubtraction {
+                list,
+                ..PantrySubtraction::default()
+            },
+            Vec::new(),
+        ),
+    };
 
-    Ok(Outcome::with_diagnostics(
-        AggregatedList::build(list, &aisle),
-        diagnostics,
-    ))
+    let aggregated = AggregatedList::build(subtraction, from_pantry, sources, &aisle, &profile);
+    Ok(Outcome::with_diagnostics(aggregated, diagnostics))
 }
 
 /// Parse an aisle configuration, degrading to an empty one rather than failing.
@@ -358,6 +695,9 @@ fn load_pantry(
 
     let parsed = cooklang::pantry::parse_lenient(text);
     for warning in parsed.report().warnings() {
+        if crate::pantry::is_own_attribute_warning(&warning.to_string()) {
+            continue;
+        }
         diagnostics.push(at_source(
             Diagnostic::warning(format!("pantry configuration: {warning}")),
             source,
@@ -448,11 +788,65 @@ pub fn extract_ingredients(
     options: &ExtractOptions<'_>,
     list: &mut IngredientList,
 ) -> Result<Vec<Diagnostic>, CoreError> {
+    let (parts, diagnostics) = expand(ctx, recipe, options)?;
+    for (_, part) in parts {
+        sources::merge(list, part);
+    }
+    Ok(diagnostics)
+}
+
+/// [`extract_ingredients`], also recording in `sources` how much of each
+/// ingredient the recipe asked for and through which references.
+///
+/// The recipe is recorded by its display name: [`recipe_display_name`] of a
+/// [`RecipeSource::Path`], and the caller's name for a
+/// [`RecipeSource::Content`].
+///
+/// # Errors
+///
+/// As [`extract_ingredients`].
+pub fn extract_ingredients_with_sources(
+    ctx: &Context,
+    recipe: &ScaledRecipe,
+    options: &ExtractOptions<'_>,
+    list: &mut IngredientList,
+    sources: &mut IngredientSources,
+) -> Result<Vec<Diagnostic>, CoreError> {
+    let label = match &recipe.source {
+        RecipeSource::Path(path) => recipe_display_name(path.as_str()),
+        RecipeSource::Content { name, .. } => name.clone(),
+    };
+    let (parts, diagnostics) = expand(ctx, recipe, options)?;
+    for (via, part) in parts {
+        sources.merge(list, &label, &via, part);
+    }
+    Ok(diagnostics)
+}
+
+/// The ingredients of one recipe reached while expanding, with the chain of
+/// reference display paths that reached it — empty for the starting recipe.
+type Part = (Vec<String>, IngredientList);
+
+/// The work of [`extract_ingredients`]: the recipe's ingredients as one list
+/// per recipe reached, each with the chain of reference display paths that
+/// reached it, in the order they would have been added to a single list.
+fn expand(
+    ctx: &Context,
+    recipe: &ScaledRecipe,
+    options: &ExtractOptions<'_>,
+) -> Result<(Vec<Part>, Vec<Diagnostic>), CoreError> {
     let base_path = ctx.base_path();
     let converter = PARSER.converter();
 
     let (parsed, mut diagnostics) = parse_source(base_path, &recipe.source, recipe.scale)?;
-    let ref_indices = list.add_recipe(&parsed, converter, options.ignore_references);
+    let mut own = IngredientList::new();
+    let ref_indices = own.add_recipe(&parsed, converter, options.ignore_references);
+    let mut parts = vec![(Vec::new(), own)];
+    let mut add_part = |via: Vec<String>, recipe: &Recipe| {
+        let mut part = IngredientList::new();
+        part.add_recipe(recipe, converter, false);
+        parts.push((via, part));
+    };
 
     // The chain of recipes currently being expanded, innermost last, by
     // resolved file path. A reference resolving to something already on it
@@ -482,11 +876,7 @@ pub fn extract_ingredients(
             };
 
             // The display-style path, matching what the web UI shows.
-            let ref_display_path = if reference.components.is_empty() {
-                reference.name.clone()
-            } else {
-                format!("{}/{}", reference.components.join("/"), reference.name)
-            };
+            let ref_display_path = display_path(reference);
 
             // If the caller specified which references to include, skip others.
             // Normalize by stripping "./" prefix so paths stored without one
@@ -607,6 +997,7 @@ pub fn extract_ingredients(
                 let (mut nested_recipe, nested_diagnostics) =
                     parse_entry(&nested_entry, &nested_path, None)?;
                 diagnostics.extend(nested_diagnostics);
+                let nested_via = vec![ref_display_path.clone(), display_path(nested_ref)];
 
                 // Scaling a nested reference depends on the unit it names.
                 match &nested_ingredient.quantity {
@@ -622,7 +1013,7 @@ pub fn extract_ingredients(
                                     message: format!("cannot scale to {target} servings: {e}"),
                                 })?;
                             // References are expanded above, so exclude them here.
-                            list.add_recipe(&nested_recipe, converter, false);
+                            add_part(nested_via, &nested_recipe);
                         }
                     }
                     Some(quantity) => {
@@ -631,22 +1022,33 @@ pub fn extract_ingredients(
                         if let Value::Number(num) = quantity.value() {
                             let scaling = num.to_string().parse().unwrap_or(1.0);
                             nested_recipe.scale(scaling, converter);
-                            list.add_recipe(&nested_recipe, converter, false);
+                            add_part(nested_via, &nested_recipe);
                         }
                     }
-                    None => {
-                        list.add_recipe(&nested_recipe, converter, false);
-                    }
+                    None => add_part(nested_via, &nested_recipe),
                 }
             }
 
             // The referenced recipe's own ingredients go in last, after its
             // nested references, so they are not counted twice.
-            list.add_recipe(&ref_recipe, converter, false);
+            add_part(vec![ref_display_path], &ref_recipe);
         }
     }
 
-    Ok(diagnostics)
+    Ok((parts, diagnostics))
+}
+
+/// How a reference is shown: `sauces/tomato`, without the leading `./`.
+fn display_path(reference: &RecipeReference) -> String {
+    let components = reference
+        .components
+        .iter()
+        .skip_while(|component| *component == ".");
+    components
+        .map(String::as_str)
+        .chain([reference.name.as_str()])
+        .collect::<Vec<_>>()
+        .join("/")
 }
 
 /// The warning to raise if expanding `entry` would lead back up its own chain,
@@ -0,0 +1,331 @@
+//! Rounding a shopping list up to what the shop actually sells.
+//!
+//! A recipe asks for 3 eggs and 250 ml of cream; the shop sells eggs by the
+//! six and cream in 200 ml tubs. Given a package size for an ingredient,
+//! [`AggregatedList::round_to_packages`] works out how many packages cover
+//! what the recipes need and how much of the last one is left over.
+//!
+//! # Where package sizes come from
+//!
+//! The same per-ingredient datastore `cook report` templates read through
+//! `db()`: a directory with one subdirectory per ingredient, each holding a
+//! `shopping.yml`. A `package_size` key there is the size the ingredient is
+//! sold in, as a bare count or a `VALUE%UNIT` quantity:
+//!
+//! ```yaml
+//! # db/eggs/shopping.yml
+//! price_per_unit: 0.25
+//! package_size: 6
+//!
+//! # db/cream/shopping.yml
+//! package_size: 200%ml
+//! ```
+//!
+//! The subdirectory is looked up by the ingredient's name as the list spells
+//! it, then lowercased. An ingredient with no file, or no `package_size` in
+//! it, is left as it was.
+//!
+//! `price_per_unit` is not used here. [`pantry::plan_for`](crate::pantry::plan_for)
+//! reads it to put a price on what a plan buys.
+//!
+//! # Which amount is rounded
+//!
+//! A size with a unit rounds the amount the recipes asked for in that same
+//! unit, compared ignoring case; a bare count rounds the unitless amount.
+//! Units are not converted — CookCLI builds `cooklang` without its unit
+//! database, so `1 l` of milk is never counted against a `500%ml` carton.
+//! Whatever else the recipes asked for stays on the list as it is. A size
+//! that matches nothing the list needs is reported, since it is most likely
+//! a typo or a unit the recipes spell differently.
+
+use super::AggregatedList;
+use crate::{format::shopping_list::quantity_fmt, Diagnostic};
+use camino::{Utf8Path, Utf8PathBuf};
+use cooklang::quantity::{GroupedQuantity, Quantity, Value};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+/// The file in each ingredient's datastore directory that holds its shopping
+/// data.
+const SHOPPING_FILE: &str = "shopping.yml";
+/// The key in [`SHOPPING_FILE`] naming the size the ingredient is sold in.
+const PACKAGE_SIZE: &str = "package_size";
+/// The key in [`SHOPPING_FILE`] naming what one unit of the ingredient
+/// costs, in whatever currency the datastore is kept in.
+const PRICE_PER_UNIT: &str = "price_per_unit";
+
+/// `200%ml`, `200 ml` or `6`, as pantry quantities are written.
+pub(crate) static SIZE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(\d+(?:\.\d+)?)\s*%?\s*(.*)$").expect("the size pattern is valid")
+});
+
+/// How many packages of an ingredient to buy.
+///
+/// `#[non_exhaustive]` because this is an output type that consumers read
+/// rather than construct.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Packages {
+    /// How many packages cover what the recipes need. Never zero.
+    pub count: u32,
+    /// One package, rendered like [`ListItem::quantities`](super::ListItem):
+    /// `"200 ml"`, or `"6"` for a count.
+    pub size: String,
+    /// What is left of the last package once the recipes have had theirs,
+    /// rendered the same way. `"0"` or `"0 ml"` when it comes out exact.
+    pub leftover: String,
+}
+
+impl Packages {
+    /// Whether `quantity`, one of [`ListItem::quantities`](super::ListItem),
+    /// is the amount these packages were counted from and so is said by
+    /// them: a number in the package's unit, or a bare number for a count.
+    pub fn covers(&self, quantity: &str) -> bool {
+        let (value, unit) = split_rendered(quantity);
+        value.parse::<f64>().is_ok() && unit.eq_ignore_ascii_case(split_rendered(&self.size).1)
+    }
+}
+
+/// `"200 ml"` as `("200", "ml")`, and `"6"` as `("6", "")`.
+fn split_rendered(quantity: &str) -> (&str, &str) {
+    quantity.split_once(' ').unwrap_or((quantity, ""))
+}
+
+/// `2 × 200 ml (150 ml left over)`, as the human and Markdown outputs show
+/// it. Nothing is said about an exact fit.
+impl std::fmt::Display for Packages {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} × {}", self.count, self.size)?;
+        let exact = split_rendered(&self.leftover)
+            .0
+            .parse::<f64>()
+            .is_ok_and(|left| left <= f64::EPSILON);
+        if !exact {
+            write!(f, " ({} left over)", self.leftover)?;
+        }
+        Ok(())
+    }
+}
+
+/// [`Packages`] before rendering, for the formatters that need the values.
+#[derive(Debug, Clone)]
+pub(crate) struct RawPackages {
+    pub(crate) count: u32,
+    pub(crate) size: Quantity,
+    pub(crate) leftover: Quantity,
+}
+
+impl RawPackages {
+    pub(crate) fn render(&self) -> Packages {
+        Packages {
+            count: self.count,
+            size: quantity_fmt(&self.size),
+            leftover: quantity_fmt(&self.leftover),
+        }
+    }
+
+    /// The rendered [`Packages`], displayed.
+    pub(crate) fn describe(&self) -> String {
+        self.render().to_string()
+    }
+
+    /// Whether `quantity` is the component these packages were counted
+    /// from, and so is said by them rather than listed again.
+    pub(crate) fn covers(&self, quantity: &Quantity) -> bool {
+        matched_amount(quantity, &self.size).is_some()
+    }
+}
+
+impl AggregatedList {
+    /// Count each ingredient in the packages it is sold in, reading package
+    /// sizes from the datastore at `datastore`. See the
+    /// [module documentation](self) for the file layout.
+    ///
+    /// Ingredients with no package size are left alone, so a datastore that
+    /// does not exist simply changes nothing. A `shopping.yml` that cannot be
+    /// read or does not say a usable size, and a size in a unit nothing on
+    /// the list is measured in, come back as warnings naming the file.
+    pub fn round_to_packages(&mut self, datastore: &Utf8Path) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        for (name, quantity) in &self.raw_items {
+            let Some((file, size)) = package_size(datastore, name, &mut diagnostics) else {
+                continue;
+            };
+            match count_packages(quantity, &size) {
+                Some(packages) => {
+                    self.raw_packages.insert(name.clone(), packages);
+                }
+                None => diagnostics.push(
+                    Diagnostic::warning(format!(
+                        "{name} is sold in packages of {}, which nothing on the list \
+                         is measured in",
+                        quantity_fmt(&size)
+                    ))
+                    .at_file(&file),
+                ),
+            }
+        }
+
+        let rendered = |name: &str| self.raw_packages.get(name).map(RawPackages::render);
+        for item in self
+            .items
+            .iter_mut()
+            .chain(self.categories.iter_mut().flat_map(|c| c.items.iter_mut()))
+        {
+            item.packages = rendered(&item.name);
+        }
+        diagnostics
+    }
+}
+
+/// The package size `<datastore>/<name>/shopping.yml` gives, with the file
+/// it came from. `None`, with a warning where there is something to warn
+/// about, when there is none to use.
+fn package_size(
+    datastore: &Utf8Path,
+    name: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Option<(Utf8PathBuf, Quantity)> {
+    let (file, yaml) = shopping_file(datastore, name, diagnostics)?;
+    let size = size_in(&yaml, &file, diagnostics)?;
+    Some((file, size))
+}
+
+/// What one package of `name` costs, from `<datastore>/<name>/shopping.yml`:
+/// its `price_per_unit` times the number in its `package_size` — the unit
+/// is not looked at, so a `200%ml` tub at `0.01` costs `2` — or the
+/// `price_per_unit` alone where it gives no size.
+///
+/// `None` when the file gives no price, with a warning where there is
+/// something to warn about, as [`package_size`].
+pub(crate) fn package_price(
+    datastore: &Utf8Path,
+    name: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Option<f64> {
+    let (file, yaml) = shopping_file(datastore, name, diagnostics)?;
+    let price = match yaml.get(PRICE_PER_UNIT)? {
+        serde_yaml::Value::Number(n) => n.as_f64(),
+        serde_yaml::Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    };
+    let Some(price) = price.filter(|price: &f64| price.is_finite() && *price >= 0.0) else {
+        diagnostics
+            .push(Diagnostic::warning(format!("{PRICE_PER_UNIT} must be a number")).at_file(&file));
+        return None;
+    };
+    let per_package = match yaml.get(PACKAGE_SIZE) {
+        Some(_) => numeric(size_in(&yaml, &file, diagnostics)?.value())?,
+        None => 1.0,
+    };
+    Some(price * per_package)
+}
+
+/// `<datastore>/<name>/shopping.yml`, looked up by the name as given and
+/// then lowercased, and parsed. `None` when there is no such file, and with
+/// a warning naming it when it cannot be read or parsed.
+fn shopping_file(
+    datastore: &Utf8Path,
+    name: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Option<(Utf8PathBuf, serde_yaml::Value)> {
+    let file = [name.to_string(), name.to_lowercase()]
+        .into_iter()
+        .map(|dir| datastore.join(dir).join(SHOPPING_FILE))
+        .find(|file| file.is_file())?;
+    let mut warn = |message: String| {
+        diagnostics.push(Diagnostic::warning(message).at_file(&file));
+    };
+
+    let text = match std::fs::read_to_string(&file) {
+        Ok(text) => text,
+        Err(e) => {
+            warn(format!("could not read {SHOPPING_FILE}: {e}"));
+            return None;
+        }
+    };
+    match serde_yaml::from_str(&text) {
+        Ok(yaml) => Some((file, yaml)),
+        Err(e) => {
+            warn(format!("{SHOPPING_FILE} is not valid YAML: {e}"));
+            None
+        }
+    }
+}
+
+/// The `package_size` in a parsed [`SHOPPING_FILE`], or `None`, with a
+/// warning naming `file` if it is there but unusable.
+fn size_in(
+    yaml: &serde_yaml::Value,
+    file: &Utf8Path,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Option<Quantity> {
+    let mut warn = |message: String| {
+        diagnostics.push(Diagnostic::warning(message).at_file(file));
+    };
+
+    let written = match yaml.get(PACKAGE_SIZE)? {
+        serde_yaml::Value::Number(n) => n.to_string(),
+        serde_yaml::Value::String(s) => s.trim().to_string(),
+        other => {
+            warn(format!(
+                "{PACKAGE_SIZE} must be a count or a quantity like 200%ml, not {other:?}"
+            ));
+            return None;
+        }
+    };
+    let size = SIZE.captures(&written).and_then(|captures| {
+        let value = captures[1].parse::<f64>().ok().filter(|v| *v > 0.0)?;
+        let unit = captures[2].trim();
+        Some(Quantity::new(
+            Value::from(value),
+            (!unit.is_empty()).then(|| unit.to_string()),
+        ))
+    });
+    if size.is_none() {
+        warn(format!(
+            "{PACKAGE_SIZE} '{written}' is not a count or a quantity like 200%ml"
+        ));
+    }
+    size
+}
+
+/// Round the part of `need` that `size` measures up to whole packages.
+fn count_packages(need: &GroupedQuantity, size: &Quantity) -> Option<RawPackages> {
+    let per_package = numeric(size.value())?;
+    let amount = need
+        .iter()
+        .find_map(|quantity| matched_amount(quantity, size))?;
+    if amount <= 0.0 {
+        return None;
+    }
+    // Shaves off float noise, so that 0.6 l against 200 ml tubs is three
+    // tubs rather than four.
+    let count = ((amount / per_package) - 1e-9).ceil().max(1.0);
+    let leftover = count * per_package - amount;
+    Some(RawPackages {
+        count: count as u32,
+        size: size.clone(),
+        leftover: Quantity::new(Value::from(leftover), size.unit().map(ToOwned::to_owned)),
+    })
+}
+
+/// How much of `size`'s unit `quantity` is, if it is in that unit.
+fn matched_amount(quantity: &Quantity, size: &Quantity) -> Option<f64> {
+    match (quantity.unit(), size.unit()) {
+        (None, None) => numeric(quantity.value()),
+        (Some(have), Some(want)) if have.eq_ignore_ascii_case(want) => numeric(quantity.value()),
+        _ => None,
+    }
+}
+
+/// A value as a number, taking the top of a range: it is the most the
+/// recipes might need.
+fn numeric(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => Some(n.value()),
+        Value::Range { end, .. } => Some(end.value()),
+        Value::Text(_) => None,
+    }
+}
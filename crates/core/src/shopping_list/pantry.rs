@@ -0,0 +1,252 @@
+//! Taking what the pantry already holds off a shopping list.
+//!
+//! `cooklang`'s own `IngredientList::subtract_pantry` only subtracts amounts
+//! written in exactly the same unit, keeps the whole need when they differ,
+//! and reads only the first pantry entry for an ingredient. A pantry with
+//! `flour = "1%kg"` against a recipe's `1200%g` then asked for all 1200 g.
+//! [`subtract_pantry`] is the same idea done with quantities:
+//!
+//! - Every pantry entry for an ingredient counts, across sections, and each
+//!   is converted into the unit the recipes asked for where the two measure
+//!   the same thing — see [`crate::units`].
+//! - Whatever is left to buy stays on the list in the recipes' unit, and what
+//!   the pantry covered is returned beside it, so that a list can say "800 g
+//!   (200 g in the pantry)" rather than silently asking for less.
+//! - An entry whose `expire` date falls before the day the food will be
+//!   cooked is not counted: it will have gone off by then.
+//!
+//! An entry with no quantity, or `"unlim"`, still covers the ingredient
+//! outright, and one with a quantity of zero covers nothing, as before.
+//!
+//! An ingredient the pantry has none of at all may still come off the list
+//! when `substitutes.conf` names something that stands in for it and the
+//! pantry has that — see [`crate::substitutes`]. Which substitutions were
+//! made is returned too, so that the list can say so.
+
+use super::ordered_components;
+use crate::{
+    pantry::parse_date,
+    parser::PARSER,
+    substitutes::{Substitutes, Substitution},
+    units, Diagnostic, Outcome,
+};
+use chrono::NaiveDate;
+use cooklang::{
+    ingredient_list::IngredientList,
+    pantry::PantryConf,
+    quantity::{GroupedQuantity, Quantity, Value},
+};
+use std::collections::HashMap;
+
+/// Amounts closer than this are the same amount: conversions leave float
+/// noise, and `1 kg` less `1000 g` should be nothing left rather than a
+/// millionth of a gram.
+const EPSILON: f64 = 1e-9;
+
+/// A list with the pantry taken off it.
+///
+/// `#[non_exhaustive]` because this is an output type that consumers read
+/// rather than construct.
+#[non_exhaustive]
+#[derive(Debug, Default)]
+pub struct PantrySubtraction {
+    /// What is left to buy.
+    pub list: IngredientList,
+    /// How much of each ingredient still on [`list`](Self::list) the pantry
+    /// covers, in the recipes' units. Only partly covered ingredients are
+    /// here: one the pantry covers entirely is no longer on the list, and one
+    /// it does not cover at all has nothing to say.
+    pub covered: HashMap<String, GroupedQuantity>,
+    /// The ingredients taken off the list because the pantry has none of
+    /// them but does have a substitute, in the order the list had them.
+    pub substituted: Vec<Substitution>,
+}
+
+/// Take what `pantry` holds off `list`, counting only what will still be good
+/// on `cook_date`, and then whatever the pantry has none of but a substitute
+/// for from `substitutes`. See the [module documentation](self).
+///
+/// Never fails: an expired entry, a pantry quantity that cannot be read and
+/// stock in a unit none of the recipes' amounts convert to are each reported
+/// as a warning, and the need is kept.
+pub fn subtract_pantry(
+    list: &IngredientList,
+    pantry: &PantryConf,
+    cook_date: NaiveDate,
+    substitutes: &Substitutes,
+) -> Outcome<PantrySubtraction> {
+    let converter = PARSER.converter();
+    let mut diagnostics = Vec::new();
+    let mut subtraction = PantrySubtraction::default();
+
+    for (name, need) in list.iter() {
+        let stock = match stock(pantry, name, cook_date, &mut diagnostics) {
+            Stock::Nothing => {
+                // A substitute that has gone off is as good as none, but it
+                // was never asked for, so why is not worth a warning.
+                let substitution = substitutes.find(name, |part| {
+                    !matches!(
+                        stock(pantry, part, cook_date, &mut Vec::new()),
+                        Stock::Nothing
+                    )
+                });
+                if let Some(substitution) = substitution {
+                    tracing::debug!(
+                        "'{name}' is not in the pantry, but {} is",
+                        substitution.with_joined()
+                    );
+                    subtraction.substituted.push(substitution);
+                    continue;
+                }
+                subtraction
+                    .list
+                    .add_ingredient(name.clone(), need, converter);
+                continue;
+            }
+            Stock::Plenty => {
+                tracing::debug!("'{name}' is in the pantry with no limit");
+                continue;
+            }
+            Stock::Amounts(amounts) => amounts,
+        };
+
+        let drawn = draw(need, stock);
+        if drawn.unmatched {
+            diagnostics.push(Diagnostic::warning(format!(
+                "the pantry has {name}, but in units the recipes' amounts do not convert \
+                 to, so none of it is subtracted"
+            )));
+        }
+        if drawn.remaining.is_empty() {
+            continue;
+        }
+        subtraction
+            .list
+            .add_ingredient(name.clone(), &drawn.remaining, converter);
+        if !drawn.covered.is_empty() {
+            subtraction.covered.insert(name.clone(), drawn.covered);
+        }
+    }
+
+    Outcome::with_diagnostics(subtraction, diagnostics)
+}
+
+/// What the pantry holds of one ingredient that will still be good to use.
+enum Stock {
+    /// None, or none that counts.
+    Nothing,
+    /// An entry with no quantity, or `unlim`: it covers any amount.
+    Plenty,
+    /// Amounts, each with its lowercased unit, in the order the file lists
+    /// them.
+    Amounts(Vec<(f64, String)>),
+}
+
+fn stock(
+    pantry: &PantryConf,
+    name: &str,
+    cook_date: NaiveDate,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Stock {
+    let mut amounts = Vec::new();
+    for (section, item) in pantry.find_all_ingredients(name) {
+        // An unreadable date is no date, as `cook pantry expiring` reads it.
+        if let Some(expired) = item
+            .expire()
+            .and_then(parse_date)
+            .filter(|date| *date < cook_date)
+        {
+            diagnostics.push(Diagnostic::warning(format!(
+                "{name} in [{section}] expires on {expired}, before it would be cooked on \
+                 {cook_date}, so it is not subtracted"
+            )));
+            continue;
+        }
+        let Some(written) = item.quantity() else {
+            return Stock::Plenty;
+        };
+        if written == "unlim" || written == "unlimited" {
+            return Stock::Plenty;
+        }
+        match item.parsed_quantity() {
+            Some((value, unit)) if value > EPSILON => amounts.push((value, unit)),
+            Some(_) => {}
+            None => diagnostics.push(Diagnostic::warning(format!(
+                "{name} in [{section}] has the quantity '{written}', which is not a number \
+                 and a unit, so it is not subtracted"
+            ))),
+        }
+    }
+    if amounts.is_empty() {
+        Stock::Nothing
+    } else {
+        Stock::Amounts(amounts)
+    }
+}
+
+/// One ingredient's need with the stock drawn from it.
+struct Drawn {
+    /// What is left to buy.
+    remaining: GroupedQuantity,
+    /// What the stock covered.
+    covered: GroupedQuantity,
+    /// The recipes gave amounts and there was stock, but no amount was in a
+    /// unit the stock converts to.
+    unmatched: bool,
+}
+
+/// Draw `stock` down against each amount in `need` in turn, converting the
+/// stock into each amount's unit.
+///
+/// A need with no amounts at all — `@salt` with no quantity — is covered by
+/// any stock, as it always has been. Text amounts (`"a pinch"`) and ranges
+/// say too little to subtract from and are kept as they are.
+fn draw(need: &GroupedQuantity, mut stock: Vec<(f64, String)>) -> Drawn {
+    let converter = PARSER.converter();
+    let mut remaining = GroupedQuantity::empty();
+    let mut covered = GroupedQuantity::empty();
+    let mut measured = false;
+
+    for quantity in ordered_components(need) {
+        let Value::Number(number) = quantity.value() else {
+            remaining.add(quantity, converter);
+            continue;
+        };
+        measured = true;
+        let unit = quantity.unit().unwrap_or_default();
+        let wanted = number.value();
+
+        let mut left = wanted;
+        for (have, have_unit) in stock.iter_mut() {
+            if left <= EPSILON {
+                break;
+            }
+            let Some(available) = units::convert(*have, have_unit, unit) else {
+                continue;
+            };
+            let taken = available.min(left);
+            left -= taken;
+            *have = (*have - units::convert(taken, unit, have_unit).unwrap_or(*have)).max(0.0);
+        }
+
+        let in_unit =
+            |value: f64| Quantity::new(Value::from(value), quantity.unit().map(Into::into));
+        let taken = wanted - left;
+        if taken > EPSILON {
+            covered.add(&in_unit(taken), converter);
+        }
+        if left > EPSILON {
+            if taken > EPSILON {
+                remaining.add(&in_unit(left), converter);
+            } else {
+                remaining.add(quantity, converter);
+            }
+        }
+    }
+
+    Drawn {
+        unmatched: measured && covered.is_empty(),
+        remaining,
+        covered,
+    }
+}
@@ -0,0 +1,450 @@
+//! Store profiles: one shop's layout laid over the aisle configuration.
+//!
+//! `aisle.conf` says which category an ingredient belongs to and lists the
+//! categories in one order. Every shop walks them in its own, calls them by
+//! its own names, and shelves the odd ingredient somewhere else entirely. A
+//! profile records that for one shop, so the same list can be printed for
+//! whichever one you are going to.
+//!
+//! # Configuration
+//!
+//! A profile is a TOML file named after the shop, in a `stores` directory
+//! beside `aisle.conf`: `<base>/config/stores/<name>.conf` first, then the
+//! same under the platform configuration directory. Every key is optional:
+//!
+//! ```toml
+//! # The categories in the order you walk past them. Anything not listed
+//! # follows in aisle.conf order, with "other" last.
+//! order = ["Bakery", "fruit and veg", "milk and dairy"]
+//!
+//! # What this shop calls an aisle.conf category. Two categories renamed to
+//! # the same aisle are shelved together.
+//! [aisles]
+//! "breads and baked goods" = "Bakery"
+//!
+//! # Ingredients this shop keeps in another aisle than aisle.conf says.
+//! [ingredients]
+//! "pine nuts" = "fruit and veg"
+//! ```
+//!
+//! `order` and the targets in `[ingredients]` use the shop's names — the
+//! renamed ones, where `[aisles]` renames. An ingredient moved to an aisle the
+//! list does not otherwise have gets one of its own. Ingredients are matched
+//! without regard to case, after aisle synonyms have been folded. As with
+//! `doctor.toml`, a key this module does not recognise is a warning rather
+//! than an error.
+
+use crate::{context::LOCAL_CONFIG_DIR, diagnostic::parse_failure, global_config_path};
+use crate::{ConfigSource, CoreError, Diagnostic, Outcome};
+use camino::{Utf8Path, Utf8PathBuf};
+use std::collections::HashMap;
+use toml_edit::DocumentMut;
+
+/// The directory, beside `aisle.conf`, that profiles live in.
+const STORES_DIR: &str = "stores";
+const PROFILE_EXTENSION: &str = "conf";
+/// The category `cooklang` puts ingredients no aisle claims in.
+const OTHER: &str = "other";
+
+/// One shop's layout. The default profile changes nothing.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StoreProfile {
+    /// Aisle names in walking order.
+    pub order: Vec<String>,
+    /// `aisle.conf` category to the shop's name for it.
+    pub aisles: HashMap<String, String>,
+    /// Lowercased ingredient name to the aisle the shop keeps it in.
+    pub ingredients: HashMap<String, String>,
+}
+
+impl StoreProfile {
+    /// Read and parse the profile `source` names. An unset source is the
+    /// default profile.
+    ///
+    /// # Errors
+    ///
+    /// - [`CoreError::Io`] if the file cannot be read.
+    /// - [`CoreError::Config`] if it is not TOML at all.
+    pub fn load(source: &ConfigSource) -> Result<Outcome<Self>, CoreError> {
+        match source.read()? {
+            Some(text) => Self::parse(&text, source.path()),
+            None => Ok(Outcome::new(Self::default())),
+        }
+    }
+
+    /// Parse profile text. `path` is where it came from, if anywhere, and is
+    /// what the warnings and the error are attributed to.
+    ///
+    /// # Errors
+    ///
+    /// [`CoreError::Config`] if `text` is not TOML.
+    pub fn parse(text: &str, path: Option<&Utf8Path>) -> Result<Outcome<Self>, CoreError> {
+        let doc = text.parse::<DocumentMut>().map_err(|e| CoreError::Config {
+            path: path.map(ToOwned::to_owned),
+            message: parse_failure(&[Diagnostic::error(e.to_string())], "store profile"),
+        })?;
+
+        let mut warnings = Vec::new();
+        let mut warn = |message: String| {
+            let diagnostic = Diagnostic::warning(message);
+            warnings.push(match path {
+                Some(path) => diagnostic.at_file(path),
+                None => diagnostic,
+            });
+        };
+
+        let mut profile = Self::default();
+        for (key, item) in doc.iter() {
+            match key {
+                "order" => match item.as_array() {
+                    Some(array) => {
+                        for value in array.iter() {
+                            match value.as_str() {
+                                Some(aisle) => profile.order.push(aisle.to_string()),
+                                None => warn(format!("'order' entry {value} is not a string")),
+                            }
+                        }
+                    }
+                    None => warn("'order' must be a list of aisle names".to_string()),
+                },
+                "aisles" | "ingredients" => {
+                    let Some(table) = item.as_table_like() else {
+                        warn(format!("'{key}' must be a table"));
+                        continue;
+                    };
+                    for (name, value) in table.iter() {
+                        let Some(aisle) = value.as_str() else {
+                            warn(format!("'{name}' in [{key}] must name an aisle"));
+                            continue;
+                        };
+                        if key == "aisles" {
+                            profile.aisles.insert(name.to_string(), aisle.to_string());
+                        } else {
+                            profile
+                                .ingredients
+                                .insert(name.to_lowercase(), aisle.to_string());
+                        }
+                    }
+                }
+                _ => warn(format!("unknown store profile key '{key}'")),
+            }
+        }
+
+        Ok(Outcome::with_diagnostics(profile, warnings))
+    }
+
+    /// The shop's name for an `aisle.conf` category.
+    fn aisle_name<'a>(&'a self, category: &'a str) -> &'a str {
+        self.aisles.get(category).map_or(category, String::as_str)
+    }
+
+    /// Keep `ingredient` in this shop's aisle for `category`, as though the
+    /// profile's `[ingredients]` moved it there — unless it already moves
+    /// `ingredient` somewhere itself, which wins. This is how an item added
+    /// to a list by hand with a category of its own is shelved.
+    pub fn place(&mut self, ingredient: &str, category: &str) {
+        let aisle = self.aisle_name(category).to_string();
+        self.ingredients
+            .entry(ingredient.to_lowercase())
+            .or_insert(aisle);
+    }
+
+    /// Lay categorised ingredients out the way this shop does: renamed,
+    /// merged where two categories share an aisle, with the moved ingredients
+    /// in their new aisles and every aisle in walking order.
+    ///
+    /// `categories` is in `aisle.conf` order, as
+    /// [`IngredientList::categorize`](cooklang::ingredient_list::IngredientList::categorize)
+    /// returns it; generic over the quantity so that callers holding rendered
+    /// and unrendered lists can share it. Aisles left empty are dropped.
+    pub fn arrange<T>(
+        &self,
+        categories: Vec<(String, Vec<(String, T)>)>,
+    ) -> Vec<(String, Vec<(String, T)>)> {
+        fn slot<T>(aisles: &mut Vec<(String, Vec<(String, T)>)>, name: &str) -> usize {
+            match aisles.iter().position(|(aisle, _)| aisle == name) {
+                Some(index) => index,
+                None => {
+                    aisles.push((name.to_string(), Vec::new()));
+                    aisles.len() - 1
+                }
+            }
+        }
+
+        let mut aisles: Vec<(String, Vec<(String, T)>)> = Vec::new();
+        let mut moved = Vec::new();
+        for (category, items) in categories {
+            let aisle = self.aisle_name(&category);
+            let index = slot(&mut aisles, aisle);
+            for (name, quantity) in items {
+                match self.ingredients.get(&name.to_lowercase()) {
+                    Some(target) if target != aisle => moved.push((target, (name, quantity))),
+                    _ => aisles[index].1.push((name, quantity)),
+                }
+            }
+        }
+        for (target, item) in moved {
+            let index = slot(&mut aisles, target);
+            aisles[index].1.push(item);
+        }
+        aisles.retain(|(_, items)| !items.is_empty());
+
+        // A stable sort, so the aisles `order` leaves out keep the order
+        // `aisle.conf` gave them.
+        let other = self.aisle_name(OTHER);
+        aisles.sort_by_key(|(aisle, _)| {
+            match self.order.iter().position(|ordered| ordered == aisle) {
+                Some(position) => (0, position),
+                None if aisle == other => (2, 0),
+                None => (1, 0),
+            }
+        });
+        aisles
+    }
+}
+
+/// Where the profile called `name` is: `<base>/config/stores/<name>.conf`,
+/// then the same in the platform configuration directory.
+///
+/// # Errors
+///
+/// [`CoreError::UnknownStore`] if neither exists, or if `name` could not be a
+/// file name.
+pub fn store_profile(base_path: &Utf8Path, name: &str) -> Result<ConfigSource, CoreError> {
+    let unknown = || CoreError::UnknownStore {
+        name: name.to_string(),
+    };
+    if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+        return Err(unknown());
+    }
+    let file = format!("{name}.{PROFILE_EXTENSION}");
+    store_dirs(base_path)
+        .into_iter()
+        .map(|dir| dir.join(&file))
+        .find(|path| path.is_file())
+        .map(ConfigSource::Path)
+        .ok_or_else(unknown)
+}
+
+/// The name of every store profile there is, local and global, sorted and
+/// without duplicates. A missing or unreadable `stores` directory has none.
+pub fn store_profile_names(base_path: &Utf8Path) -> Vec<String> {
+    let mut names: Vec<String> = store_dirs(base_path)
+        .into_iter()
+        .filter_map(|dir| dir.read_dir_utf8().ok())
+        .flatten()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_file())
+        .filter_map(|entry| {
+            let path = entry.path();
+            (path.extension() == Some(PROFILE_EXTENSION))
+                .then(|| path.file_stem().map(ToOwned::to_owned))
+                .flatten()
+        })
+        .filter(|name| !name.starts_with('.'))
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// The `stores` directories, in search order.
+fn store_dirs(base_path: &Utf8Path) -> Vec<Utf8PathBuf> {
+    let mut dirs = vec![base_path.join(LOCAL_CONFIG_DIR).join(STORES_DIR)];
+    if let Ok(global) = global_config_path(STORES_DIR) {
+        dirs.push(global);
+    }
+    dirs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn categories(spec: &[(&str, &[&str])]) -> Vec<(String, Vec<(String, ())>)> {
+        spec.iter()
+            .map(|(category, items)| {
+                (
+                    category.to_string(),
+                    items.iter().map(|item| (item.to_string(), ())).collect(),
+                )
+            })
+            .collect()
+    }
+
+    fn names(arranged: &[(String, Vec<(String, ())>)]) -> Vec<(&str, Vec<&str>)> {
+        arranged
+            .iter()
+            .map(|(aisle, items)| {
+                (
+                    aisle.as_str(),
+                    items.iter().map(|(name, _)| name.as_str()).collect(),
+                )
+            })
+            .collect()
+    }
+
+    fn profile(text: &str) -> StoreProfile {
+        let outcome = StoreProfile::parse(text, None).unwrap();
+        assert!(outcome.diagnostics.is_empty(), "{:?}", outcome.diagnostics);
+        outcome.value
+    }
+
+    #[test]
+    fn the_default_profile_changes_nothing() {
+        let list = categories(&[("produce", &["leek"]), ("other", &["salt"])]);
+        let arranged = StoreProfile::default().arrange(list);
+        assert_eq!(
+            names(&arranged),
+            [("produce", vec!["leek"]), ("other", vec!["salt"])]
+        );
+    }
+
+    #[test]
+    fn order_puts_listed_aisles_first_and_other_last() {
+        let list = categories(&[
+            ("produce", &["leek"]),
+            ("dairy", &["milk"]),
+            ("bakery", &["bread"]),
+            ("other", &["salt"]),
+        ]);
+        let arranged = profile(r#"order = ["bakery", "dairy"]"#).arrange(list);
+        assert_eq!(
+            names(&arranged),
+            [
+                ("bakery", vec!["bread"]),
+                ("dairy", vec!["milk"]),
+                ("produce", vec!["leek"]),
+                ("other", vec!["salt"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn renamed_aisles_merge_and_are_ordered_by_their_new_name() {
+        let list = categories(&[
+            ("produce", &["leek"]),
+            ("dairy", &["milk"]),
+            ("eggs", &["eggs"]),
+        ]);
+        let arranged = profile(
+            r#"
+            order = ["Chilled", "Obst & Gemüse"]
+            [aisles]
+            produce = "Obst & Gemüse"
+            dairy = "Chilled"
+            eggs = "Chilled"
+            "#,
+        )
+        .arrange(list);
+        assert_eq!(
+            names(&arranged),
+            [
+                ("Chilled", vec!["milk", "eggs"]),
+                ("Obst & Gemüse", vec!["leek"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn moved_ingredients_leave_empty_aisles_behind_and_can_open_new_ones() {
+        let list = categories(&[
+            ("produce", &["leek"]),
+            ("baking", &["Pine Nuts"]),
+            ("other", &["salt"]),
+        ]);
+        let arranged = profile(
+            r#"
+            [ingredients]
+            "pine nuts" = "produce"
+            salt = "Spices"
+            "#,
+        )
+        .arrange(list);
+        assert_eq!(
+            names(&arranged),
+            [
+                ("produce", vec!["leek", "Pine Nuts"]),
+                ("Spices", vec!["salt"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn placed_ingredients_go_to_the_renamed_aisle_unless_the_profile_moves_them() {
+        let list = categories(&[("other", &["Candles", "salt"])]);
+        let mut profile = profile(
+            r#"
+            [aisles]
+            household = "Home"
+            [ingredients]
+            salt = "Spices"
+            "#,
+        );
+        profile.place("Candles", "household");
+        profile.place("salt", "household");
+        assert_eq!(
+            names(&profile.arrange(list)),
+            [("Home", vec!["Candles"]), ("Spices", vec!["salt"])]
+        );
+    }
+
+    #[test]
+    fn unknown_keys_and_wrong_types_are_warnings() {
+        let outcome = StoreProfile::parse(
+            "order = \"bakery\"\ncolour = \"blue\"\n[ingredients]\nsalt = 3\n",
+            Some(Utf8Path::new("config/stores/lidl.conf")),
+        )
+        .unwrap();
+        assert_eq!(outcome.value, StoreProfile::default());
+        let messages: Vec<&str> = outcome
+            .diagnostics
+            .iter()
+            .map(|d| d.message.as_str())
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "'order' must be a list of aisle names",
+                "unknown store profile key 'colour'",
+                "'salt' in [ingredients] must name an aisle",
+            ]
+        );
+    }
+
+    #[test]
+    fn a_file_that_is_not_toml_is_a_config_error() {
+        let err = StoreProfile::parse("[aisles", None).unwrap_err();
+        assert!(matches!(err, CoreError::Config { .. }), "{err:?}");
+    }
+
+    #[test]
+    fn profiles_are_found_by_name_in_the_collection() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let base = Utf8Path::from_path(dir.path()).unwrap();
+        let stores = base.join("config").join("stores");
+        std::fs::create_dir_all(&stores).unwrap();
+        std::fs::write(stores.join("lidl.conf"), "").unwrap();
+        std::fs::write(stores.join("aldi.conf"), "").unwrap();
+        std::fs::write(stores.join("notes.txt"), "").unwrap();
+
+        assert_eq!(
+            store_profile(base, "lidl").unwrap().path(),
+            Some(stores.join("lidl.conf").as_path())
+        );
+        for name in ["tesco", "../aisle", ""] {
+            assert!(
+                matches!(
+                    store_profile(base, name),
+                    Err(CoreError::UnknownStore { .. })
+                ),
+                "{name}"
+            );
+        }
+        // The global directory may add to these on a developer's machine.
+        let names = store_profile_names(base);
+        assert!(names.iter().any(|name| name == "aldi"), "{names:?}");
+        assert!(names.iter().any(|name| name == "lidl"), "{names:?}");
+        assert!(!names.iter().any(|name| name == "notes"), "{names:?}");
+    }
+}
@@ -0,0 +1,155 @@
+//! Where each ingredient on a shopping list came from.
+//!
+//! Once [`IngredientList`] has added two recipes' cream together, nothing in
+//! it says whose cream it was. [`IngredientSources`] is kept beside the list
+//! while it is built and remembers every contribution: the recipe on the
+//! list, the chain of referenced recipes it came through (`sauces/bechamel`),
+//! and how much that one contribution was. [`ListItem::sources`] is the
+//! rendered result.
+//!
+//! The amounts are what each recipe asked for, before the pantry is
+//! subtracted, so they add up to what the recipes need rather than to what is
+//! left to buy.
+//!
+//! [`ListItem::sources`]: super::ListItem::sources
+
+use crate::{
+    format::{quantity::ordered_components, shopping_list::quantity_fmt},
+    parser::PARSER,
+};
+use cooklang::{aisle::AisleConf, ingredient_list::IngredientList, quantity::GroupedQuantity};
+use serde::{Deserialize, Serialize};
+
+/// One recipe's part in an ingredient on the list.
+///
+/// `#[non_exhaustive]` because this is an output type that consumers read
+/// rather than construct.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItemSource {
+    /// The recipe that was put on the list, by display name.
+    pub recipe: String,
+    /// The referenced recipes the ingredient came through, outermost first,
+    /// by the display path the reference was written with: `["sauces/tomato"]`
+    /// for an ingredient of a sauce the recipe references. Empty for the
+    /// recipe's own ingredients.
+    #[serde(default)]
+    pub via: Vec<String>,
+    /// How much this recipe asked for, rendered like
+    /// [`ListItem::quantities`](super::ListItem::quantities). Empty when it
+    /// gave no quantity.
+    pub quantities: Vec<String>,
+}
+
+/// [`ItemSource`] before rendering, with the ingredient it is for.
+#[derive(Debug, Clone)]
+pub(crate) struct RawSource {
+    pub(crate) ingredient: String,
+    pub(crate) recipe: String,
+    pub(crate) via: Vec<String>,
+    pub(crate) quantity: GroupedQuantity,
+}
+
+impl RawSource {
+    pub(crate) fn render(&self) -> ItemSource {
+        ItemSource {
+            recipe: self.recipe.clone(),
+            via: self.via.clone(),
+            quantities: ordered_components(&self.quantity)
+                .into_iter()
+                .map(quantity_fmt)
+                .collect(),
+        }
+    }
+}
+
+/// Every contribution to an [`IngredientList`], kept alongside it by
+/// [`extract_ingredients_with_sources`](super::extract_ingredients_with_sources).
+///
+/// Like the list, it is keyed by the recipes' own spellings until
+/// [`use_common_names`](Self::use_common_names) folds aisle synonyms
+/// together; call both with the same aisle configuration.
+#[derive(Debug, Clone, Default)]
+pub struct IngredientSources {
+    /// In the order the contributions were made, which is the order
+    /// [`get`](Self::get) gives them back in.
+    pub(crate) sources: Vec<RawSource>,
+}
+
+impl IngredientSources {
+    /// An empty record.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record that `recipe`, through the references in `via`, contributed
+    /// everything in `part`.
+    pub(crate) fn add(&mut self, recipe: &str, via: &[String], part: &IngredientList) {
+        for (name, quantity) in part.iter() {
+            // One recipe can name the same ingredient through the same chain
+            // more than once only by being on the list twice, and then it is
+            // two batches worth saying separately.
+            self.sources.push(RawSource {
+                ingredient: name.clone(),
+                recipe: recipe.to_string(),
+                via: via.to_vec(),
+                quantity: quantity.clone(),
+            });
+        }
+    }
+
+    /// Rename every ingredient to the name the aisle configuration prefers,
+    /// as [`IngredientList::use_common_names`] does the list's.
+    pub fn use_common_names(mut self, aisle: &AisleConf) -> Self {
+        let info = aisle.ingredients_info();
+        for source in &mut self.sources {
+            if let Some(info) = info.get(&source.ingredient.to_lowercase()) {
+                source.ingredient = info.common_name.to_string();
+            }
+        }
+        self
+    }
+
+    /// Every contribution to `ingredient`, in the order they were made.
+    pub fn get(&self, ingredient: &str) -> Vec<ItemSource> {
+        self.raw(ingredient).map(RawSource::render).collect()
+    }
+
+    pub(crate) fn raw<'a>(&'a self, ingredient: &'a str) -> impl Iterator<Item = &'a RawSource> {
+        self.sources
+            .iter()
+            .filter(move |source| source.ingredient == ingredient)
+    }
+
+    /// The recipes that asked for `ingredient`, each named once, in the
+    /// order they were added.
+    pub(crate) fn recipes(&self, ingredient: &str) -> Vec<String> {
+        let mut recipes: Vec<String> = Vec::new();
+        for source in self.raw(ingredient) {
+            if !recipes.contains(&source.recipe) {
+                recipes.push(source.recipe.clone());
+            }
+        }
+        recipes
+    }
+
+    /// Add `part` to `list` and record it here.
+    pub(crate) fn merge(
+        &mut self,
+        list: &mut IngredientList,
+        recipe: &str,
+        via: &[String],
+        part: IngredientList,
+    ) {
+        self.add(recipe, via, &part);
+        merge(list, part);
+    }
+}
+
+/// Add everything in `part` to `list`, merging quantities as
+/// [`IngredientList::add_recipe`] would have.
+pub(crate) fn merge(list: &mut IngredientList, part: IngredientList) {
+    for (name, quantity) in part {
+        list.add_ingredient(name, &quantity, PARSER.converter());
+    }
+}
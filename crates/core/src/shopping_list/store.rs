@@ -16,15 +16,47 @@
 //!
 //! The two files are the same ones the Cooklang apps read and write, so
 //! anything here has to keep their format exactly.
+//!
+//! # Named lists
+//!
+//! A collection can keep more lists than that one — a list per shop, say.
+//! Each named list is its own pair beside the first, `.shopping-list.<name>`
+//! and `.shopping-checked.<name>`, with its own checked log, and
+//! [`ShoppingListStore::named`] opens one. The unnamed pair is the list called
+//! [`DEFAULT_LIST`]; it is the only one the Cooklang apps know about.
+//!
+//! # Items added by hand
+//!
+//! Not everything bought comes from a recipe. A [`ManualItem`] — toilet
+//! paper, birthday candles — is stored in `.shopping-list` as the format's
+//! free-hand ingredient line, `toilet paper{4}`, which the Cooklang apps read
+//! and show too. The format has nowhere to put a category, so an item's
+//! category, when it is given one, is kept in a third file beside the pair,
+//! `.shopping-categories` (or `.shopping-categories.<name>`): a TOML table of
+//! lowercased item name to category. Without one, aisle configuration files
+//! the item like any ingredient.
+//!
+//! # History
+//!
+//! Every change to a list can be undone: see [`ListChange`] and
+//! [`ShoppingListStore::undo`]. The earlier states and the log of who changed
+//! what are two more files beside the list, `.shopping-history` and
+//! `.shopping-log`.
 
 use crate::fs_atomic::write_atomically;
 use crate::CoreError;
 use camino::{Utf8Path, Utf8PathBuf};
-use cooklang::shopping_list::{self, CheckEntry, RecipeItem, ShoppingList, ShoppingListItem};
+use cooklang::shopping_list::{
+    self, CheckEntry, IngredientItem, RecipeItem, ShoppingList, ShoppingListItem,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::fs;
 
+mod history;
+
+pub use history::ListChange;
+
 /// One entry of a saved shopping list: a recipe, or a menu with recipes
 /// nested inside it.
 ///
@@ -61,8 +93,35 @@ pub struct StoredEntry {
     pub recipes: Option<Vec<StoredEntry>>,
 }
 
+/// Something on a saved shopping list that is not from a recipe. See
+/// [Items added by hand](self#items-added-by-hand).
+///
+/// Not `#[non_exhaustive]`: callers construct one to hand to
+/// [`ShoppingListStore::add_item`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManualItem {
+    /// What to buy, as it should appear on the list.
+    pub name: String,
+    /// How much, as a recipe would write it: `4`, `500%g`, `2 packs`. `None`
+    /// for no particular amount.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<String>,
+    /// The aisle category to list it under, overriding the aisle
+    /// configuration. `None` leaves it to the aisle configuration.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+}
+
+/// The name of the list kept in the unnamed `.shopping-list` /
+/// `.shopping-checked` pair.
+pub const DEFAULT_LIST: &str = "default";
+
+/// Longest name a named list can have, so that its file names stay well inside
+/// every filesystem's limit.
+const MAX_LIST_NAME: usize = 64;
+
 /// Reads and writes the `.shopping-list` / `.shopping-checked` pair beside a
-/// recipe collection.
+/// recipe collection, or the pair of one of its [named lists](self#named-lists).
 ///
 /// Cheap to construct and holds no state of its own — every method reads the
 /// files afresh — so it is fine to build one per request rather than keep one
@@ -74,12 +133,23 @@ pub struct StoredEntry {
 /// [`check`](ShoppingListStore::check) and
 /// [`compact`](ShoppingListStore::compact).
 pub struct ShoppingListStore {
+    /// The list's name; [`DEFAULT_LIST`] for the unnamed pair.
+    name: String,
     /// Path to `.shopping-list`
     list_path: Utf8PathBuf,
     /// Path to `.shopping-checked`
     checked_path: Utf8PathBuf,
-    /// Path to the legacy `.shopping_list.txt` (for migration detection)
-    legacy_path: Utf8PathBuf,
+    /// Path to `.shopping-categories`, the categories of items added by hand.
+    categories_path: Utf8PathBuf,
+    /// Path to `.shopping-history`, the list's earlier states.
+    history_path: Utf8PathBuf,
+    /// Path to `.shopping-log`, who changed the list, when, and how.
+    log_path: Utf8PathBuf,
+    /// Who the changes are recorded as made by.
+    actor: Option<String>,
+    /// Path to the legacy `.shopping_list.txt` (for migration detection).
+    /// Only the default list has one: named lists are newer than the format.
+    legacy_path: Option<Utf8PathBuf>,
 }
 
 /// Convert a caller-supplied scale factor to the format's `multiplier` field.
@@ -100,10 +170,86 @@ impl ShoppingListStore {
     /// Touches nothing: the files are read and created as they are needed.
     pub fn new(base_path: &Utf8Path) -> Self {
         Self {
+            name: DEFAULT_LIST.to_string(),
             list_path: base_path.join(".shopping-list"),
             checked_path: base_path.join(".shopping-checked"),
-            legacy_path: base_path.join(".shopping_list.txt"),
+            categories_path: base_path.join(".shopping-categories"),
+            history_path: base_path.join(".shopping-history"),
+            log_path: base_path.join(".shopping-log"),
+            actor: None,
+            legacy_path: Some(base_path.join(".shopping_list.txt")),
+        }
+    }
+
+    /// Open the list called `name` in the collection rooted at `base_path`.
+    ///
+    /// [`DEFAULT_LIST`] is the same store [`new`](Self::new) opens. Any other
+    /// name is a list of its own, which exists from the first time something
+    /// is added to it until it is [cleared](Self::clear).
+    ///
+    /// # Errors
+    ///
+    /// [`CoreError::ShoppingListName`] unless `name` is 1 to 64 lowercase
+    /// ASCII letters, digits, `-` and `_`. Lowercase only, so that `Party`
+    /// and `party` cannot be two lists on one filesystem and one on another.
+    pub fn named(base_path: &Utf8Path, name: &str) -> Result<Self, CoreError> {
+        if name == DEFAULT_LIST {
+            return Ok(Self::new(base_path));
+        }
+        if !is_list_name(name) {
+            return Err(CoreError::ShoppingListName {
+                name: name.to_string(),
+            });
+        }
+        Ok(Self {
+            name: name.to_string(),
+            list_path: base_path.join(format!(".shopping-list.{name}")),
+            checked_path: base_path.join(format!(".shopping-checked.{name}")),
+            categories_path: base_path.join(format!(".shopping-categories.{name}")),
+            history_path: base_path.join(format!(".shopping-history.{name}")),
+            log_path: base_path.join(format!(".shopping-log.{name}")),
+            actor: None,
+            legacy_path: None,
+        })
+    }
+
+    /// The names of every list in the collection rooted at `base_path`:
+    /// [`DEFAULT_LIST`] first, whether or not anything is on it, then the
+    /// named lists in alphabetical order.
+    ///
+    /// # Errors
+    ///
+    /// [`CoreError::Io`] if the collection directory cannot be read.
+    pub fn list_names(base_path: &Utf8Path) -> Result<Vec<String>, CoreError> {
+        let failed = |source| CoreError::Io {
+            path: base_path.to_path_buf(),
+            source,
+        };
+        let mut names = Vec::new();
+        for entry in fs::read_dir(base_path).map_err(failed)? {
+            let entry = entry.map_err(failed)?;
+            let file_name = entry.file_name();
+            let Some(name) = file_name
+                .to_str()
+                .and_then(|file_name| file_name.strip_prefix(".shopping-list."))
+            else {
+                continue;
+            };
+            // The staging file of an atomic write, `..shopping-list.x.<pid>.tmp`,
+            // does not get this far; one named `.shopping-list.<anything with
+            // a dot>` fails the name check, as does `default`.
+            if is_list_name(name) && name != DEFAULT_LIST {
+                names.push(name.to_string());
+            }
         }
+        names.sort();
+        names.insert(0, DEFAULT_LIST.to_string());
+        Ok(names)
+    }
+
+    /// This list's name; [`DEFAULT_LIST`] for the unnamed pair.
+    pub fn name(&self) -> &str {
+        &self.name
     }
 
     /// Migrate from the old tab-delimited `.shopping_list.txt` if it exists
@@ -114,17 +260,20 @@ impl ShoppingListStore {
     /// empty. The legacy file is renamed to `.shopping_list.txt.bak` once its
     /// contents are safely in the new file.
     fn migrate_if_needed(&self) -> Result<bool, CoreError> {
-        if !self.legacy_path.exists() || self.list_path.exists() {
+        let Some(legacy_path) = &self.legacy_path else {
+            return Ok(false);
+        };
+        if !legacy_path.exists() || self.list_path.exists() {
             return Ok(false);
         }
 
         tracing::info!(
             "Migrating shopping list from legacy format: {}",
-            self.legacy_path
+            legacy_path
         );
 
-        let content = fs::read_to_string(&self.legacy_path).map_err(|source| CoreError::Io {
-            path: self.legacy_path.clone(),
+        let content = fs::read_to_string(legacy_path).map_err(|source| CoreError::Io {
+            path: legacy_path.clone(),
             source,
         })?;
 
@@ -150,12 +299,10 @@ impl ShoppingListStore {
         self.save_list(&list)?;
 
         // Rename the old file so it's not picked up again
-        let backup = self.legacy_path.with_extension("txt.bak");
-        crate::fs_atomic::rename_replace(&self.legacy_path, &backup).map_err(|source| {
-            CoreError::Io {
-                path: self.legacy_path.clone(),
-                source,
-            }
+        let backup = legacy_path.with_extension("txt.bak");
+        crate::fs_atomic::rename_replace(legacy_path, &backup).map_err(|source| CoreError::Io {
+            path: legacy_path.clone(),
+            source,
         })?;
 
         tracing::info!("Migration complete. Legacy file renamed to {}", backup);
@@ -244,6 +391,7 @@ impl ShoppingListStore {
             None => Vec::new(),
         };
 
+        self.record(&format!("add {}", item.path))?;
         list.items.push(ShoppingListItem::Recipe(RecipeItem {
             path: item.path,
             multiplier: to_multiplier(item.scale),
@@ -283,6 +431,7 @@ impl ShoppingListStore {
             })
             .collect();
 
+        self.record(&format!("add {menu_path}"))?;
         list.items.push(ShoppingListItem::Recipe(RecipeItem {
             path: menu_path,
             multiplier: to_multiplier(menu_scale),
@@ -306,23 +455,192 @@ impl ShoppingListStore {
             ShoppingListItem::Recipe(r) => r.path == path,
             _ => false,
         }) {
+            self.record(&format!("remove {path}"))?;
             list.items.remove(pos);
         }
         self.save_list(&list)
     }
 
+    /// Take the first entry whose path is `path` off this list and put it at
+    /// the end of `to`, with everything stored beneath it.
+    ///
+    /// Answers `false`, changing nothing, if there is no such entry. Moving an
+    /// entry to the list it is on leaves it where it is.
+    ///
+    /// `to` is written first, so a failure between the two writes leaves the
+    /// entry on both lists rather than on neither. Ticks stay behind in this
+    /// list's checked log; compacting it afterwards is the caller's
+    /// responsibility, as for [`remove`](Self::remove).
+    pub fn move_entry(&self, path: &str, to: &ShoppingListStore) -> Result<bool, CoreError> {
+        self.migrate_if_needed()?;
+        to.migrate_if_needed()?;
+        let mut list = self.load_list()?;
+        let Some(pos) = list.items.iter().position(|i| match i {
+            ShoppingListItem::Recipe(r) => r.path == path,
+            _ => false,
+        }) else {
+            return Ok(false);
+        };
+        if self.list_path == to.list_path {
+            return Ok(true);
+        }
+
+        let at = chrono::Utc::now();
+        to.record_move(&format!("move {path} from {}", self.name), &self.name, at)?;
+        self.record_move(&format!("move {path} to {}", to.name), &to.name, at)?;
+        let item = list.items.remove(pos);
+        let mut target = to.load_list()?;
+        target.items.push(item);
+        to.save_list(&target)?;
+        self.save_list(&list)?;
+        Ok(true)
+    }
+
     /// Empty the shopping list and forget everything that was ticked off.
+    ///
+    /// The default list is left as an empty `.shopping-list`, which is what
+    /// the Cooklang apps expect to find. A named list is deleted outright, so
+    /// it drops out of [`list_names`](Self::list_names) — though its history
+    /// stays, so that the clear can be [undone](Self::undo).
     pub fn clear(&self) -> Result<(), CoreError> {
-        self.save_list(&ShoppingList::default())?;
-        if self.checked_path.exists() {
-            fs::remove_file(&self.checked_path).map_err(|source| CoreError::Io {
-                path: self.checked_path.clone(),
+        if [&self.list_path, &self.checked_path, &self.categories_path]
+            .iter()
+            .any(|path| path.exists())
+        {
+            self.record("clear")?;
+        }
+        if self.legacy_path.is_some() {
+            self.save_list(&ShoppingList::default())?;
+        } else if self.list_path.exists() {
+            fs::remove_file(&self.list_path).map_err(|source| CoreError::Io {
+                path: self.list_path.clone(),
                 source,
             })?;
         }
+        for path in [&self.checked_path, &self.categories_path] {
+            if path.exists() {
+                fs::remove_file(path).map_err(|source| CoreError::Io {
+                    path: path.clone(),
+                    source,
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    // -- Items added by hand --
+
+    /// Every item added by hand, in the order it was added, each with its
+    /// category if it was given one.
+    pub fn items(&self) -> Result<Vec<ManualItem>, CoreError> {
+        self.migrate_if_needed()?;
+        let list = self.load_list()?;
+        let categories = self.load_categories()?;
+        Ok(list
+            .items
+            .into_iter()
+            .filter_map(|item| match item {
+                ShoppingListItem::Ingredient(item) => Some(ManualItem {
+                    category: table_str(&categories, &item.name.to_lowercase()),
+                    name: item.name,
+                    quantity: item.quantity,
+                }),
+                ShoppingListItem::Recipe(_) => None,
+            })
+            .collect())
+    }
+
+    /// Put an item on the list by hand.
+    ///
+    /// Appends, as [`add`](Self::add) does. A category given here replaces
+    /// any the same name had before; none leaves an earlier one alone.
+    ///
+    /// # Errors
+    ///
+    /// [`CoreError::ShoppingListItem`] for a name or quantity the
+    /// `.shopping-list` format cannot hold: an empty name, one starting `./`
+    /// (which is how recipes are written), or either containing a brace, a
+    /// line break or a `--` or `[-` comment.
+    pub fn add_item(&self, item: ManualItem) -> Result<(), CoreError> {
+        let name = item.name.trim().to_string();
+        check_item_text(&name, &name, "name")?;
+        if name.is_empty() || name.starts_with("./") {
+            return Err(CoreError::ShoppingListItem {
+                name,
+                message: "a name must not be empty or start with './'".to_string(),
+            });
+        }
+        let quantity = item
+            .quantity
+            .map(|quantity| quantity.trim().to_string())
+            .filter(|quantity| !quantity.is_empty());
+        if let Some(quantity) = &quantity {
+            check_item_text(&name, quantity, "quantity")?;
+        }
+
+        self.migrate_if_needed()?;
+        let mut list = self.load_list()?;
+        self.record(&format!("add item {name}"))?;
+        list.items
+            .push(ShoppingListItem::Ingredient(IngredientItem {
+                name: name.clone(),
+                quantity,
+            }));
+        self.save_list(&list)?;
+
+        if let Some(category) = item.category.filter(|c| !c.trim().is_empty()) {
+            let mut categories = self.load_categories()?;
+            categories[name.to_lowercase().as_str()] = toml_edit::value(category.trim());
+            self.save_categories(&categories)?;
+        }
         Ok(())
     }
 
+    /// Take the first item added by hand called `name`, ignoring case, off
+    /// the list. Answers `false`, changing nothing, if there is none.
+    ///
+    /// Its category is forgotten with the last item of that name. As with
+    /// [`remove`](Self::remove), compacting the checked log is the caller's
+    /// responsibility.
+    pub fn remove_item(&self, name: &str) -> Result<bool, CoreError> {
+        self.migrate_if_needed()?;
+        let mut list = self.load_list()?;
+        let is_named = |item: &ShoppingListItem| matches!(item, ShoppingListItem::Ingredient(i) if i.name.eq_ignore_ascii_case(name.trim()));
+        let Some(pos) = list.items.iter().position(is_named) else {
+            return Ok(false);
+        };
+        self.record(&format!("remove item {}", name.trim()))?;
+        list.items.remove(pos);
+        self.save_list(&list)?;
+
+        if !list.items.iter().any(is_named) {
+            let mut categories = self.load_categories()?;
+            if categories.remove(&name.trim().to_lowercase()).is_some() {
+                self.save_categories(&categories)?;
+            }
+        }
+        Ok(true)
+    }
+
+    fn load_categories(&self) -> Result<toml_edit::DocumentMut, CoreError> {
+        if !self.categories_path.exists() {
+            return Ok(toml_edit::DocumentMut::new());
+        }
+        let text = fs::read_to_string(&self.categories_path).map_err(|source| CoreError::Io {
+            path: self.categories_path.clone(),
+            source,
+        })?;
+        text.parse()
+            .map_err(|e: toml_edit::TomlError| CoreError::InvalidShoppingList {
+                path: self.categories_path.clone(),
+                message: e.message().to_string(),
+            })
+    }
+
+    fn save_categories(&self, categories: &toml_edit::DocumentMut) -> Result<(), CoreError> {
+        write_atomically(&self.categories_path, categories.to_string().as_bytes())
+    }
+
     // -- Checked state operations --
 
     /// The ingredients currently ticked off, **lowercased** — the checked log
@@ -337,7 +655,7 @@ impl ShoppingListStore {
     ///
     /// Mutual exclusion against a concurrent [`compact`](Self::compact) is the
     /// caller's responsibility — in CookCLI's web server this is the
-    /// process-wide `AppState::checked_log_lock`. File-level `flock` would not
+    /// process-wide `AppState::shopping_list_lock`. File-level `flock` would not
     /// help: it doesn't serialize callers in the same process (the kernel
     /// treats them as one lock owner), which is the case that actually matters
     /// here.
@@ -410,6 +728,24 @@ impl ShoppingListStore {
 
 // -- Conversion helpers --
 
+/// The string `key` holds in `table`, if it holds one.
+fn table_str(table: &toml_edit::DocumentMut, key: &str) -> Option<String> {
+    table.get(key)?.as_str().map(ToOwned::to_owned)
+}
+
+/// Refuse what would not read back as the same free-hand line: a brace ends
+/// the quantity early, and a line break or comment marker cuts the line.
+fn check_item_text(name: &str, text: &str, what: &str) -> Result<(), CoreError> {
+    let bad = ['{', '}', '\n', '\r'];
+    if text.contains(bad) || text.contains("--") || text.contains("[-") {
+        return Err(CoreError::ShoppingListItem {
+            name: name.to_string(),
+            message: format!("the {what} must not contain braces, line breaks, '--' or '[-'"),
+        });
+    }
+    Ok(())
+}
+
 /// A sub-recipe reference as it is stored under its parent. The `./` a
 /// reference is written with is stripped, because the format writer adds it
 /// back.
@@ -476,6 +812,15 @@ fn entries_from_list(list: &ShoppingList) -> Vec<StoredEntry> {
     items
 }
 
+/// Whether `name` can name a list: see [`ShoppingListStore::named`].
+fn is_list_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= MAX_LIST_NAME
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-' || b == b'_')
+}
+
 /// Derive a human-readable display name from a recipe or menu path.
 ///
 /// E.g. `Breakfast/Easy Pancakes.cook` → `Easy Pancakes`, and
@@ -0,0 +1,405 @@
+//! Undoing changes to a saved shopping list.
+//!
+//! Every change [`ShoppingListStore`] makes to a list — adding, removing,
+//! moving, clearing — first copies the list as it stood into
+//! `.shopping-history` (`.shopping-history.<name>` for a named list), and
+//! says who made it, when, and what it was in `.shopping-log`. The history
+//! keeps the last [`HISTORY_LIMIT`] states, which is what
+//! [`undo`](ShoppingListStore::undo) and
+//! [`restore`](ShoppingListStore::restore) go back to; the log is append-only,
+//! like the checked log, and is never trimmed.
+//!
+//! A state is the whole of all three files the list is kept in — the list,
+//! its ticks and the categories of items added by hand — so that undoing a
+//! clear brings the ticks back too. Ticking and unticking are not changes in
+//! this sense: they are undone by unticking, and recording each one would push
+//! the changes worth undoing out of the history.
+//!
+//! A move changes two lists, and each records it in its own history. The two
+//! records are linked — the same time, and each names the other list — so
+//! that undoing the move on either list undoes it on both, and restoring
+//! either to before it restores the other too. A restore that does that is
+//! linked the same way, across every list it changed.
+//!
+//! Going back puts the ticks back as they were too, so a tick made since the
+//! change is lost; undoing and restoring warn when that happens.
+//!
+//! Both files are JSON, one object per line. A line that cannot be read is
+//! skipped rather than failing the whole file, as for the checked log.
+
+use super::ShoppingListStore;
+use crate::fs_atomic::write_atomically;
+use crate::rfc3339;
+use crate::{CoreError, Diagnostic, Outcome};
+use camino::Utf8Path;
+use chrono::{DateTime, Utc};
+use cooklang::shopping_list;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::Write as _;
+
+/// How many earlier states of a list are kept to go back to.
+pub(crate) const HISTORY_LIMIT: usize = 50;
+
+/// One change to a saved shopping list, as the log records it.
+///
+/// `#[non_exhaustive]` because this is an output type that consumers read
+/// rather than construct.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ListChange {
+    /// When it was made.
+    #[serde(with = "crate::rfc3339")]
+    pub at: DateTime<Utc>,
+    /// Who made it, as the caller named them with
+    /// [`acting_as`](ShoppingListStore::acting_as); `None` if nobody said.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub by: Option<String>,
+    /// What it was, in a few words: `add Breakfast/Easy Pancakes`, `clear`,
+    /// `undo clear`.
+    pub what: String,
+}
+
+/// A list as it stood before a change, and the change. `None` for a file
+/// that did not exist.
+#[derive(Debug, Serialize, Deserialize)]
+struct Snapshot {
+    #[serde(flatten)]
+    change: ListChange,
+    list: Option<String>,
+    checked: Option<String>,
+    categories: Option<String>,
+    /// The other lists the same change changed, each of which recorded it
+    /// at the same time: the other side of a move. Empty for a change to
+    /// this list alone.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    linked: Vec<String>,
+}
+
+impl Snapshot {
+    /// Whether this records a change that the list `name` recorded too, at
+    /// `at`.
+    fn is_linked(&self, name: &str, at: DateTime<Utc>) -> bool {
+        self.change.at == at && self.linked.iter().any(|l| l == name)
+    }
+}
+
+impl ShoppingListStore {
+    /// Record `by` as the one making this store's changes, in the log and
+    /// the history. The CLI passes the user's login name; the web server
+    /// passes `web`.
+    pub fn acting_as(mut self, by: impl Into<String>) -> Self {
+        self.actor = Some(by.into());
+        self
+    }
+
+    /// Every change made to this list, oldest first.
+    pub fn log(&self) -> Result<Vec<ListChange>, CoreError> {
+        read_lines(&self.log_path)
+    }
+
+    /// Put the list back as it was before the last change, and answer with
+    /// that change. `None`, changing nothing, when there is nothing left in
+    /// the history to go back to.
+    ///
+    /// Undoing takes the change out of the history, so undoing again goes a
+    /// further change back rather than redoing it. The log keeps both the
+    /// change and its undoing.
+    ///
+    /// Undoing a move undoes it on the other list too, so the entry ends up
+    /// back where it was and nowhere else. A warning names any tick made
+    /// since the change, which undoing it takes off.
+    ///
+    /// # Errors
+    ///
+    /// [`CoreError::ShoppingListHistory`], changing nothing, if the last
+    /// change was a move and the other list has changed since: that list's
+    /// later changes have to be undone first.
+    pub fn undo(&self) -> Result<Outcome<Option<ListChange>>, CoreError> {
+        let mut history: Vec<Snapshot> = read_lines(&self.history_path)?;
+        let Some(snapshot) = history.pop() else {
+            return Ok(Outcome::new(None));
+        };
+
+        // Every other list the change touched has to be able to go back too
+        // before any of them does.
+        let mut others = Vec::new();
+        for other in self.open_linked(snapshot.linked.iter().map(String::as_str))? {
+            let mut other_history: Vec<Snapshot> = read_lines(&other.history_path)?;
+            let Some(pos) = other_history
+                .iter()
+                .rposition(|s| s.is_linked(&self.name, snapshot.change.at))
+            else {
+                // Dropped from that list's history; there is nothing left
+                // to undo there.
+                continue;
+            };
+            if pos + 1 != other_history.len() {
+                return Err(changed_since(&snapshot.change.what, &other.name));
+            }
+            let other_snapshot = other_history.remove(pos);
+            others.push((other, other_history, other_snapshot));
+        }
+
+        let mut diagnostics = Vec::new();
+        for (other, other_history, other_snapshot) in others {
+            diagnostics.extend(other.put_back(&other_snapshot)?);
+            write_lines(&other.history_path, &other_history)?;
+            other.append_log(&format!("undo {}", other_snapshot.change.what))?;
+        }
+        diagnostics.extend(self.put_back(&snapshot)?);
+        write_lines(&self.history_path, &history)?;
+        self.append_log(&format!("undo {}", snapshot.change.what))?;
+        Ok(Outcome::with_diagnostics(
+            Some(snapshot.change),
+            diagnostics,
+        ))
+    }
+
+    /// Put the list back as it stood at `at`, and answer with the changes
+    /// that were undone to get there, oldest first. Nothing changes, and the
+    /// answer is empty, if the list has not changed since.
+    ///
+    /// Restoring is itself a change, so [`undo`](Self::undo) takes it back.
+    /// Restoring to before a move restores the other list to before it too,
+    /// as undoing the move would; the restore is then a change to both. A
+    /// warning names any tick made since `at`, which restoring takes off.
+    ///
+    /// # Errors
+    ///
+    /// [`CoreError::ShoppingListHistory`], changing nothing, if `at` is
+    /// before the oldest state the history still has, and older ones may
+    /// have been dropped to make room: the list as it stood then is no
+    /// longer known. The same if a move since `at` went to or from a list
+    /// that has changed in other ways since the move.
+    pub fn restore(&self, at: DateTime<Utc>) -> Result<Outcome<Vec<ListChange>>, CoreError> {
+        let history: Vec<Snapshot> = read_lines(&self.history_path)?;
+        let Some(first) = history.iter().position(|s| s.change.at > at) else {
+            return Ok(Outcome::new(Vec::new()));
+        };
+        if first == 0 && history.len() >= HISTORY_LIMIT {
+            return Err(CoreError::ShoppingListHistory {
+                message: format!(
+                    "the history only goes back to {}",
+                    rfc3339::format(&history[0].change.at)
+                ),
+            });
+        }
+
+        let undone = &history[first..];
+
+        // Each other list a move since `at` touched, and the state it goes
+        // back to: as it was before the first of those moves. Every change
+        // it has had since then must be one of them.
+        let linked_to = |s: &Snapshot| undone.iter().any(|u| s.is_linked(&self.name, u.change.at));
+        let names: BTreeSet<&str> = undone
+            .iter()
+            .flat_map(|s| s.linked.iter().map(String::as_str))
+            .collect();
+        let mut others = Vec::new();
+        for other in self.open_linked(names.into_iter())? {
+            let mut other_history: Vec<Snapshot> = read_lines(&other.history_path)?;
+            let Some(from) = other_history.iter().position(linked_to) else {
+                continue;
+            };
+            if let Some(later) = other_history[from..].iter().find(|s| !linked_to(s)) {
+                return Err(changed_since(&later.change.what, &other.name));
+            }
+            others.push((other, other_history.swap_remove(from)));
+        }
+
+        let what = format!("restore to {}", rfc3339::format(&at));
+        let now = Utc::now();
+        let lists: Vec<&str> = std::iter::once(self.name.as_str())
+            .chain(others.iter().map(|(other, _)| other.name.as_str()))
+            .collect();
+        let linked = |name: &str| -> Vec<String> {
+            lists
+                .iter()
+                .filter(|l| **l != name)
+                .map(|l| l.to_string())
+                .collect()
+        };
+        let mut diagnostics = Vec::new();
+        for (other, state) in &others {
+            let change = ListChange {
+                at: now,
+                ..other.change(&format!("{what} on {}", self.name))
+            };
+            other.push_snapshot(change, linked(&other.name))?;
+            diagnostics.extend(other.put_back(state)?);
+        }
+        let change = ListChange {
+            at: now,
+            ..self.change(&what)
+        };
+        self.push_snapshot(change, linked(&self.name))?;
+        diagnostics.extend(self.put_back(&history[first])?);
+        Ok(Outcome::with_diagnostics(
+            history
+                .into_iter()
+                .skip(first)
+                .map(|snapshot| snapshot.change)
+                .collect(),
+            diagnostics,
+        ))
+    }
+
+    /// Copy the list as it stands into the history before `what` changes
+    /// it, and log `what`. Every method that rewrites the list calls this
+    /// first.
+    pub(super) fn record(&self, what: &str) -> Result<(), CoreError> {
+        self.push_snapshot(self.change(what), Vec::new())
+    }
+
+    /// [`record`](Self::record) one side of a move between this list and
+    /// `other`. Both sides are recorded at the same `at`, which is how
+    /// undoing either finds the other.
+    pub(super) fn record_move(
+        &self,
+        what: &str,
+        other: &str,
+        at: DateTime<Utc>,
+    ) -> Result<(), CoreError> {
+        let change = ListChange {
+            at,
+            ..self.change(what)
+        };
+        self.push_snapshot(change, vec![other.to_string()])
+    }
+
+    fn push_snapshot(&self, change: ListChange, linked: Vec<String>) -> Result<(), CoreError> {
+        let what = change.what.clone();
+        let snapshot = Snapshot {
+            change,
+            list: read_if_exists(&self.list_path)?,
+            checked: read_if_exists(&self.checked_path)?,
+            categories: read_if_exists(&self.categories_path)?,
+            linked,
+        };
+        let mut history: Vec<Snapshot> = read_lines(&self.history_path)?;
+        history.push(snapshot);
+        let excess = history.len().saturating_sub(HISTORY_LIMIT);
+        history.drain(..excess);
+        write_lines(&self.history_path, &history)?;
+        self.append_log(&what)
+    }
+
+    /// The lists called `names`, in the same collection, making changes as
+    /// the same one this store is.
+    fn open_linked<'a>(
+        &self,
+        names: impl Iterator<Item = &'a str>,
+    ) -> Result<Vec<ShoppingListStore>, CoreError> {
+        let base = self.list_path.parent().unwrap_or(Utf8Path::new(""));
+        names
+            .map(|name| {
+                Ok(Self {
+                    actor: self.actor.clone(),
+                    ..Self::named(base, name)?
+                })
+            })
+            .collect()
+    }
+
+    fn change(&self, what: &str) -> ListChange {
+        ListChange {
+            at: Utc::now(),
+            by: self.actor.clone(),
+            what: what.to_string(),
+        }
+    }
+
+    /// Write each of a snapshot's files back, or remove it where the
+    /// snapshot had none. Answers with a warning naming what was ticked
+    /// since, if anything was: those ticks are lost.
+    fn put_back(&self, snapshot: &Snapshot) -> Result<Vec<Diagnostic>, CoreError> {
+        let then = shopping_list::checked_set(&shopping_list::parse_checked(
+            snapshot.checked.as_deref().unwrap_or_default(),
+        ));
+        let mut lost: Vec<String> = self.checked_set()?.difference(&then).cloned().collect();
+        lost.sort();
+        let diagnostics = if lost.is_empty() {
+            Vec::new()
+        } else {
+            vec![Diagnostic::warning(format!(
+                "going back to before '{}' on the {} list unticks {}, ticked since",
+                snapshot.change.what,
+                self.name,
+                lost.join(", ")
+            ))
+            .at_file(self.checked_path.clone())]
+        };
+        for (path, contents) in [
+            (&self.list_path, &snapshot.list),
+            (&self.checked_path, &snapshot.checked),
+            (&self.categories_path, &snapshot.categories),
+        ] {
+            match contents {
+                Some(contents) => write_atomically(path, contents.as_bytes())?,
+                None if path.exists() => fs::remove_file(path).map_err(|source| CoreError::Io {
+                    path: path.clone(),
+                    source,
+                })?,
+                None => {}
+            }
+        }
+        Ok(diagnostics)
+    }
+
+    /// Append one line to the log. An append, as for the checked log, so a
+    /// write cut short costs at most the line being written.
+    fn append_log(&self, what: &str) -> Result<(), CoreError> {
+        let failed = |source: std::io::Error| CoreError::Io {
+            path: self.log_path.clone(),
+            source,
+        };
+        let mut line = serde_json::to_string(&self.change(what)).expect("a change serialises");
+        line.push('\n');
+        fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.log_path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(failed)
+    }
+}
+
+fn changed_since(what: &str, list: &str) -> CoreError {
+    CoreError::ShoppingListHistory {
+        message: format!(
+            "'{what}' also changed the {list} list, which has changed since; \
+             undo its later changes first"
+        ),
+    }
+}
+
+fn read_if_exists(path: &Utf8Path) -> Result<Option<String>, CoreError> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    fs::read_to_string(path)
+        .map(Some)
+        .map_err(|source| CoreError::Io {
+            path: path.to_path_buf(),
+            source,
+        })
+}
+
+fn read_lines<T: for<'de> Deserialize<'de>>(path: &Utf8Path) -> Result<Vec<T>, CoreError> {
+    Ok(read_if_exists(path)?
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+fn write_lines<T: Serialize>(path: &Utf8Path, items: &[T]) -> Result<(), CoreError> {
+    let mut buf = String::new();
+    for item in items {
+        buf.push_str(&serde_json::to_string(item).expect("a snapshot serialises"));
+        buf.push('\n');
+    }
+    write_atomically(path, buf.as_bytes())
+}
@@ -464,3 +464,475 @@ fn a_rewrite_never_shortens_the_file_in_place() {
     #[cfg(not(unix))]
     let _ = (before, after);
 }
+
+#[test]
+fn a_named_list_keeps_its_own_pair_of_files() {
+    let dir = temp();
+    let party = ShoppingListStore::named(&base(&dir), "party").unwrap();
+    party.add(entry("Punch", 3.0)).unwrap();
+    party.check("limes").unwrap();
+
+    assert_eq!(party.name(), "party");
+    assert!(store(&dir).load().unwrap().is_empty());
+    assert!(store(&dir).checked_set().unwrap().is_empty());
+    assert_eq!(
+        names_in(&dir),
+        [
+            ".shopping-checked.party",
+            ".shopping-history.party",
+            ".shopping-list.party",
+            ".shopping-log.party"
+        ]
+    );
+    assert_eq!(party.load().unwrap()[0].path, "Punch");
+}
+
+#[test]
+fn the_default_name_opens_the_unnamed_pair() {
+    let dir = temp();
+    ShoppingListStore::named(&base(&dir), DEFAULT_LIST)
+        .unwrap()
+        .add(entry("Soup", 1.0))
+        .unwrap();
+    assert_eq!(list_file(&dir), "./Soup\n");
+}
+
+#[test]
+fn list_names_put_the_default_first_and_skip_what_is_not_a_list() {
+    let dir = temp();
+    for name in ["weekly", "costco"] {
+        ShoppingListStore::named(&base(&dir), name)
+            .unwrap()
+            .add(entry("Soup", 1.0))
+            .unwrap();
+    }
+    write(&base(&dir).join(".shopping-list.bak.1"), "");
+    write(&base(&dir).join("..shopping-list.weekly.42.tmp"), "");
+
+    assert_eq!(
+        ShoppingListStore::list_names(&base(&dir)).unwrap(),
+        ["default", "costco", "weekly"]
+    );
+}
+
+#[test]
+fn names_that_could_not_be_file_names_everywhere_are_refused() {
+    let dir = temp();
+    for name in ["", "Party", "a/b", "..", "x.y", &"a".repeat(65)] {
+        match ShoppingListStore::named(&base(&dir), name) {
+            Err(CoreError::ShoppingListName { name: got }) => assert_eq!(got, name),
+            other => panic!(
+                "expected a name error for {name:?}, got {:?}",
+                other.ok().map(|s| s.name().to_string())
+            ),
+        }
+    }
+}
+
+#[test]
+fn an_entry_moves_with_everything_beneath_it() {
+    let dir = temp();
+    let weekly = store(&dir);
+    let costco = ShoppingListStore::named(&base(&dir), "costco").unwrap();
+    weekly.add(entry("Soup", 1.0)).unwrap();
+    weekly
+        .add(StoredEntry {
+            included_references: Some(vec!["./Stock".to_string()]),
+            ..entry("Stew", 2.0)
+        })
+        .unwrap();
+
+    assert!(weekly.move_entry("Stew", &costco).unwrap());
+
+    assert_eq!(list_file(&dir), "./Soup\n");
+    let moved = costco.load().unwrap();
+    assert_eq!(moved.len(), 1);
+    assert_eq!(moved[0].path, "Stew");
+    assert_eq!(moved[0].scale, 2.0);
+    assert_eq!(
+        moved[0].included_references,
+        Some(vec!["Stock".to_string()])
+    );
+}
+
+#[test]
+fn moving_what_is_not_there_changes_nothing() {
+    let dir = temp();
+    let costco = ShoppingListStore::named(&base(&dir), "costco").unwrap();
+    store(&dir).add(entry("Soup", 1.0)).unwrap();
+
+    assert!(!store(&dir).move_entry("Stew", &costco).unwrap());
+    assert!(store(&dir).move_entry("Soup", &store(&dir)).unwrap());
+
+    assert_eq!(list_file(&dir), "./Soup\n");
+    assert!(costco.load().unwrap().is_empty());
+}
+
+#[test]
+fn clearing_a_named_list_deletes_it() {
+    let dir = temp();
+    let party = ShoppingListStore::named(&base(&dir), "party").unwrap();
+    party.add(entry("Punch", 1.0)).unwrap();
+    party.check("limes").unwrap();
+
+    party.clear().unwrap();
+
+    assert_eq!(
+        names_in(&dir),
+        [".shopping-history.party", ".shopping-log.party"],
+        "only what it takes to undo the clear is left"
+    );
+    assert_eq!(
+        ShoppingListStore::list_names(&base(&dir)).unwrap(),
+        ["default"]
+    );
+}
+
+// -- Items added by hand ----------------------------------------------------
+
+fn manual(name: &str, quantity: Option<&str>, category: Option<&str>) -> ManualItem {
+    ManualItem {
+        name: name.to_string(),
+        quantity: quantity.map(ToOwned::to_owned),
+        category: category.map(ToOwned::to_owned),
+    }
+}
+
+/// The Cooklang apps read the same file, so an item added here is written
+/// the way they write one and sits beside the recipes without disturbing
+/// them.
+#[test]
+fn items_are_written_as_free_hand_lines_beside_the_recipes() {
+    let dir = temp();
+    let store = store(&dir);
+    store.add(entry("Soup", 1.0)).unwrap();
+    store
+        .add_item(manual("  toilet paper ", Some("4"), Some("household")))
+        .unwrap();
+    store
+        .add_item(manual("birthday candles", None, None))
+        .unwrap();
+
+    assert_eq!(
+        list_file(&dir),
+        "./Soup\ntoilet paper{4}\nbirthday candles\n"
+    );
+    assert_eq!(store.load().unwrap().len(), 1, "items are not recipes");
+    assert_eq!(
+        store.items().unwrap(),
+        [
+            manual("toilet paper", Some("4"), Some("household")),
+            manual("birthday candles", None, None),
+        ]
+    );
+}
+
+#[test]
+fn removing_the_last_item_of_a_name_forgets_its_category() {
+    let dir = temp();
+    let store = store(&dir);
+    store.add(entry("Soup", 1.0)).unwrap();
+    store
+        .add_item(manual("Candles", None, Some("party")))
+        .unwrap();
+    store.add_item(manual("candles", Some("2"), None)).unwrap();
+
+    assert!(store.remove_item("CANDLES").unwrap());
+    assert_eq!(
+        store.items().unwrap(),
+        [manual("candles", Some("2"), Some("party"))]
+    );
+
+    assert!(store.remove_item("candles").unwrap());
+    assert!(!store.remove_item("candles").unwrap());
+    assert!(!store.remove_item("Soup").unwrap(), "recipes are not items");
+    assert_eq!(list_file(&dir), "./Soup\n");
+    store.add_item(manual("candles", None, None)).unwrap();
+    assert_eq!(store.items().unwrap(), [manual("candles", None, None)]);
+}
+
+#[test]
+fn items_that_would_not_read_back_are_refused() {
+    let dir = temp();
+    let store = store(&dir);
+    for item in [
+        manual("  ", None, None),
+        manual("./Soup", None, None),
+        manual("candles{2}", None, None),
+        manual("candles -- for the cake", None, None),
+        manual("candles\nmatches", None, None),
+        manual("candles", Some("2}"), None),
+        manual("candles", Some("[- a few -]"), None),
+    ] {
+        match store.add_item(item.clone()) {
+            Err(CoreError::ShoppingListItem { .. }) => {}
+            other => panic!("expected {item:?} to be refused, got {other:?}"),
+        }
+    }
+    assert!(names_in(&dir).is_empty(), "nothing was written");
+}
+
+#[test]
+fn clearing_a_list_deletes_its_categories_too() {
+    let dir = temp();
+    let party = ShoppingListStore::named(&base(&dir), "party").unwrap();
+    party
+        .add_item(manual("balloons", Some("20"), Some("party")))
+        .unwrap();
+    party.clear().unwrap();
+
+    assert_eq!(
+        names_in(&dir),
+        [".shopping-history.party", ".shopping-log.party"]
+    );
+}
+
+// -- History ------------------------------------------------------------------
+
+fn history_file(dir: &tempfile::TempDir) -> String {
+    std::fs::read_to_string(base(dir).join(".shopping-history").as_std_path()).unwrap()
+}
+
+/// The accident this exists for: a clear, undone, brings back the recipes,
+/// the items added by hand and the ticks.
+#[test]
+fn a_clear_is_undone_with_everything_it_took() {
+    let dir = temp();
+    let store = store(&dir).acting_as("alice");
+    store.add(entry("Soup", 2.0)).unwrap();
+    store
+        .add_item(manual("candles", None, Some("party")))
+        .unwrap();
+    store.check("leeks").unwrap();
+
+    store.clear().unwrap();
+    let undone = store
+        .undo()
+        .unwrap()
+        .value
+        .expect("there is a change to undo");
+
+    assert_eq!(undone.what, "clear");
+    assert_eq!(undone.by.as_deref(), Some("alice"));
+    assert_eq!(list_file(&dir), "./Soup{2}\ncandles\n");
+    assert_eq!(checked_file(&dir), "+ leeks\n");
+    assert_eq!(
+        store.items().unwrap(),
+        [manual("candles", None, Some("party"))]
+    );
+}
+
+#[test]
+fn undo_walks_back_one_change_at_a_time_and_the_log_keeps_everything() {
+    let dir = temp();
+    let store = store(&dir);
+    store.add(entry("Soup", 1.0)).unwrap();
+    store.add(entry("Stew", 1.0)).unwrap();
+    store.remove("Nothing").unwrap();
+
+    assert_eq!(store.undo().unwrap().value.unwrap().what, "add Stew");
+    assert_eq!(list_file(&dir), "./Soup\n");
+    assert_eq!(store.undo().unwrap().value.unwrap().what, "add Soup");
+    assert!(!base(&dir).join(".shopping-list").exists());
+    assert!(
+        store.undo().unwrap().value.is_none(),
+        "nothing is left to undo"
+    );
+
+    let log: Vec<String> = store.log().unwrap().into_iter().map(|c| c.what).collect();
+    assert_eq!(
+        log,
+        ["add Soup", "add Stew", "undo add Stew", "undo add Soup"],
+        "removing what is not there is no change"
+    );
+    assert!(store.log().unwrap().iter().all(|c| c.by.is_none()));
+}
+
+/// A move is recorded on both lists; undoing it on either must not leave
+/// the entry on both.
+#[test]
+fn undoing_a_move_on_either_list_undoes_it_on_both() {
+    let dir = temp();
+    let party = ShoppingListStore::named(&base(&dir), "party").unwrap();
+    store(&dir).add(entry("Pancakes", 1.0)).unwrap();
+    store(&dir).move_entry("Pancakes", &party).unwrap();
+
+    assert_eq!(
+        store(&dir).undo().unwrap().value.unwrap().what,
+        "move Pancakes to party"
+    );
+    assert_eq!(list_file(&dir), "./Pancakes\n");
+    assert!(party.load().unwrap().is_empty());
+    assert_eq!(
+        party.undo().unwrap().value,
+        None,
+        "the move is gone from both histories"
+    );
+
+    store(&dir).move_entry("Pancakes", &party).unwrap();
+    assert_eq!(
+        party.undo().unwrap().value.unwrap().what,
+        format!("move Pancakes from {DEFAULT_LIST}")
+    );
+    assert_eq!(list_file(&dir), "./Pancakes\n");
+    assert!(party.load().unwrap().is_empty());
+}
+
+#[test]
+fn a_move_is_not_undone_over_later_changes_to_the_other_list() {
+    let dir = temp();
+    let party = ShoppingListStore::named(&base(&dir), "party").unwrap();
+    store(&dir).add(entry("Pancakes", 1.0)).unwrap();
+    store(&dir).move_entry("Pancakes", &party).unwrap();
+    party.add(entry("Punch", 1.0)).unwrap();
+
+    match store(&dir).undo() {
+        Err(CoreError::ShoppingListHistory { message }) => {
+            assert!(message.contains("party list"), "{message}")
+        }
+        other => panic!("expected a history error, got {other:?}"),
+    }
+    assert!(store(&dir).load().unwrap().is_empty());
+    assert_eq!(party.load().unwrap().len(), 2, "nothing changed");
+
+    party.undo().unwrap();
+    store(&dir).undo().unwrap();
+    assert_eq!(list_file(&dir), "./Pancakes\n");
+    assert!(party.load().unwrap().is_empty());
+}
+
+/// Restoring to before a move is undoing it, on both lists; and the
+/// restore, which then changed both, is undone on both too.
+#[test]
+fn restoring_to_before_a_move_restores_the_other_list_too() {
+    let dir = temp();
+    let party = ShoppingListStore::named(&base(&dir), "party").unwrap();
+    store(&dir).add(entry("Pancakes", 1.0)).unwrap();
+    store(&dir).move_entry("Pancakes", &party).unwrap();
+    let before = store(&dir).log().unwrap()[0].at - chrono::Duration::seconds(1);
+
+    let undone = store(&dir).restore(before).unwrap().value;
+
+    assert_eq!(undone.len(), 2, "the add and the move");
+    assert!(store(&dir).load().unwrap().is_empty());
+    assert!(
+        party.load().unwrap().is_empty(),
+        "the move is undone there too"
+    );
+
+    assert!(party
+        .undo()
+        .unwrap()
+        .value
+        .unwrap()
+        .what
+        .starts_with("restore to "));
+    assert!(store(&dir).load().unwrap().is_empty());
+    assert_eq!(party.load().unwrap()[0].path, "Pancakes");
+}
+
+#[test]
+fn undoing_warns_of_the_ticks_it_takes_off() {
+    let dir = temp();
+    let store = store(&dir);
+    store.add(entry("Soup", 1.0)).unwrap();
+    store.check("leeks").unwrap();
+
+    let outcome = store.undo().unwrap();
+
+    assert_eq!(outcome.diagnostics.len(), 1);
+    assert!(
+        outcome.diagnostics[0].message.contains("leeks"),
+        "{:?}",
+        outcome.diagnostics
+    );
+    assert!(store.checked_set().unwrap().is_empty());
+}
+
+#[test]
+fn the_history_keeps_only_the_latest_states() {
+    let dir = temp();
+    let store = store(&dir);
+    for i in 0..history::HISTORY_LIMIT + 5 {
+        store.add(entry(&format!("Recipe {i}"), 1.0)).unwrap();
+    }
+    assert_eq!(history_file(&dir).lines().count(), history::HISTORY_LIMIT);
+    assert_eq!(store.log().unwrap().len(), history::HISTORY_LIMIT + 5);
+}
+
+/// Restoring is judged by the time each change was made, so these tests
+/// write the history with times of their own rather than sleeping.
+fn dated_history(dir: &tempfile::TempDir, states: &[(&str, &str)]) {
+    let lines: String = states
+        .iter()
+        .map(|(at, list)| {
+            format!(
+                "{}\n",
+                serde_json::json!({ "at": at, "what": "add", "list": list })
+            )
+        })
+        .collect();
+    write(&base(dir).join(".shopping-history"), &lines);
+}
+
+#[test]
+fn restore_goes_back_to_the_list_as_it_stood_then_and_can_be_undone() {
+    let dir = temp();
+    let store = store(&dir);
+    dated_history(
+        &dir,
+        &[
+            ("2026-10-01T09:00:00Z", "./Soup\n"),
+            ("2026-10-02T09:00:00Z", "./Soup\n./Stew\n"),
+        ],
+    );
+    write(
+        &base(&dir).join(".shopping-list"),
+        "./Soup\n./Stew\n./Pie\n",
+    );
+
+    let at = |text: &str| {
+        chrono::DateTime::parse_from_rfc3339(text)
+            .unwrap()
+            .with_timezone(&chrono::Utc)
+    };
+    let undone = store.restore(at("2026-10-01T12:00:00Z")).unwrap().value;
+
+    assert_eq!(undone.len(), 1);
+    assert_eq!(list_file(&dir), "./Soup\n./Stew\n");
+    assert!(store
+        .restore(at("2100-01-01T00:00:00Z"))
+        .unwrap()
+        .value
+        .is_empty());
+
+    assert!(store
+        .undo()
+        .unwrap()
+        .value
+        .unwrap()
+        .what
+        .starts_with("restore to "));
+    assert_eq!(list_file(&dir), "./Soup\n./Stew\n./Pie\n");
+}
+
+#[test]
+fn restoring_to_before_a_full_history_is_refused() {
+    let dir = temp();
+    let store = store(&dir);
+    let states: Vec<(String, &str)> = (0..history::HISTORY_LIMIT)
+        .map(|i| (format!("2026-10-01T09:{:02}:00Z", i), "./Soup\n"))
+        .collect();
+    let states: Vec<(&str, &str)> = states.iter().map(|(at, l)| (at.as_str(), *l)).collect();
+    dated_history(&dir, &states);
+
+    let before = chrono::DateTime::parse_from_rfc3339("2026-09-30T00:00:00Z")
+        .unwrap()
+        .with_timezone(&chrono::Utc);
+    match store.restore(before) {
+        Err(CoreError::ShoppingListHistory { message }) => {
+            assert!(message.contains("2026-10-01T09:00:00Z"), "{message}")
+        }
+        other => panic!("expected a history error, got {other:?}"),
+    }
+}
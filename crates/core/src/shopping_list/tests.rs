@@ -41,6 +41,7 @@ fn request(names: &[&str]) -> GenerateRequest {
     GenerateRequest {
         recipes: names.iter().map(|n| at_path(n)).collect(),
         ignore_references: false,
+        cook_date: None,
     }
 }
 
@@ -151,6 +152,7 @@ fn the_request_scale_is_applied_per_recipe() {
                 ScaledRecipe::scaled(RecipeSource::Path("b.cook".into()), 10.0),
             ],
             ignore_references: false,
+            cook_date: None,
         },
     )
     .expect("generates")
@@ -181,6 +183,32 @@ fn ingredients_are_categorised_by_the_aisle_configuration() {
     assert_eq!(list.categories[2].items[0].name, "sand");
 }
 
+/// A store profile reorders and renames the categories of the finished list,
+/// in both its rendered and unrendered forms.
+#[test]
+fn a_store_profile_lays_the_categories_out_for_its_shop() {
+    let dir = dir_with(&[("a.cook", "Add @tomatoes{3}, @milk{1%l} and @sand{1%kg}.\n")]);
+    let ctx = ctx(&dir)
+        .with_aisle(ConfigSource::Inline(AISLE.to_string()))
+        .with_store(ConfigSource::Inline(
+            "order = [\"Chilled\"]\n[aisles]\ndairy = \"Chilled\"\n[ingredients]\nsand = \"Garden\"\n"
+                .to_string(),
+        ));
+
+    let list = generate(&ctx, request(&["a.cook"]))
+        .expect("generates")
+        .value;
+
+    let names: Vec<&str> = list.categories.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["Chilled", "produce", "Garden"]);
+    let raw: Vec<&str> = list
+        .raw_categories
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect();
+    assert_eq!(raw, names);
+}
+
 /// Without an aisle configuration everything lands in `other`, and the caller
 /// is told why rather than being left to wonder.
 #[test]
@@ -314,6 +342,137 @@ fn pantry_quantities_are_subtracted() {
         Some(vec!["300 g".to_string()]),
         "an ingredient the pantry does not mention is untouched"
     );
+    assert_eq!(list.from_pantry, ["tomatoes", "salt"]);
+}
+
+/// Pantry stock counts in whatever unit it is written in, so long as it
+/// measures the same thing, and what it covers of an ingredient still on the
+/// list is said alongside what is left to buy.
+#[test]
+fn pantry_stock_converts_units_and_marks_what_it_partly_covers() {
+    let dir = dir_with(&[(
+        "a.cook",
+        "Mix @flour{1%kg}, @milk{500%ml}, @sugar{100%g} and @eggs{2}.\n",
+    )]);
+    let ctx = ctx(&dir).with_pantry(ConfigSource::Inline(
+        "[cupboard]\nflour = \"200%g\"\nsugar = \"0.5%kg\"\n\n\
+         [fridge]\nmilk = \"0.2%l\"\neggs = \"1%kg\"\n"
+            .to_string(),
+    ));
+
+    let outcome = generate(&ctx, request(&["a.cook"])).expect("generates");
+    let list = outcome.value;
+    let item = |name: &str| list.items.iter().find(|i| i.name == name);
+
+    let flour = item("flour").expect("flour is only partly stocked");
+    // In the recipe's unit, whatever the pantry's.
+    assert_eq!(flour.quantities, ["0.8 kg"]);
+    assert_eq!(flour.in_pantry, ["0.2 kg"]);
+    let milk = item("milk").expect("milk is only partly stocked");
+    assert_eq!(
+        (milk.quantities.as_slice(), milk.in_pantry.as_slice()),
+        (&["300 ml".to_string()][..], &["200 ml".to_string()][..])
+    );
+    assert!(item("sugar").is_none(), "half a kilo covers 100 g");
+
+    // A kilo of eggs says nothing about two eggs: they stay, and the
+    // mismatch is reported against the pantry.
+    let eggs = item("eggs").expect("eggs are kept");
+    assert_eq!(eggs.quantities, ["2"]);
+    assert!(eggs.in_pantry.is_empty());
+    assert!(
+        outcome
+            .diagnostics
+            .iter()
+            .any(|d| d.message.contains("the pantry has eggs")),
+        "{:?}",
+        outcome.diagnostics
+    );
+}
+
+/// An ingredient kept in two places is drawn from both.
+#[test]
+fn every_pantry_entry_for_an_ingredient_counts() {
+    let dir = dir_with(&[("a.cook", "Add @rice{1%kg}.\n")]);
+    let ctx = ctx(&dir).with_pantry(ConfigSource::Inline(
+        "[cupboard]\nrice = \"300%g\"\n\n[cellar]\nrice = \"0.5%kg\"\n".to_string(),
+    ));
+
+    let list = generate(&ctx, request(&["a.cook"])).unwrap().value;
+
+    assert_eq!(quantities(&list, "rice"), Some(vec!["0.2 kg".to_string()]));
+}
+
+/// Food that will have gone off by the day it is cooked is not counted, and
+/// the list says why the ingredient is still on it.
+#[test]
+fn pantry_items_expiring_before_the_cook_date_are_not_subtracted() {
+    let dir = dir_with(&[("a.cook", "Add @milk{1%l} and @cream{200%ml}.\n")]);
+    let ctx = ctx(&dir).with_pantry(ConfigSource::Inline(
+        "[fridge]\nmilk = { quantity = \"1%l\", expire = \"2025-06-10\" }\n\
+         cream = { quantity = \"200%ml\", expire = \"2025-06-20\" }\n"
+            .to_string(),
+    ));
+    let on = |date: &str| GenerateRequest {
+        cook_date: Some(date.parse().unwrap()),
+        ..request(&["a.cook"])
+    };
+
+    let before = generate(&ctx, on("2025-06-10")).unwrap();
+    assert!(before.value.is_empty(), "{:?}", before.value.items);
+
+    let after = generate(&ctx, on("2025-06-15")).unwrap();
+    assert_eq!(
+        quantities(&after.value, "milk"),
+        Some(vec!["1 l".to_string()])
+    );
+    assert_eq!(quantities(&after.value, "cream"), None);
+    let expired: Vec<_> = after
+        .diagnostics
+        .iter()
+        .filter(|d| d.message.contains("expires on 2025-06-10"))
+        .collect();
+    assert_eq!(expired.len(), 1, "{:?}", after.diagnostics);
+}
+
+/// An ingredient the pantry has none of comes off the list when it has a
+/// substitute, and the list says what stood in for it. One it has some of is
+/// drawn down as usual, substitute or not.
+#[test]
+fn an_ingredient_with_a_substitute_in_the_pantry_comes_off_the_list() {
+    let dir = dir_with(&[(
+        "a.cook",
+        "Mix @buttermilk{200%ml}, @shallots{2} and @cream{300%ml}.\n",
+    )]);
+    let ctx = ctx(&dir)
+        .with_pantry(ConfigSource::Inline(
+            "[fridge]\nmilk = \"1%l\"\nlemon = \"1\"\ncream = \"100%ml\"\n\
+             yogurt = { quantity = \"500%g\", expire = \"2025-06-01\" }\n"
+                .to_string(),
+        ))
+        .with_substitutes(ConfigSource::Inline(
+            "buttermilk = \"milk + lemon\"\nshallots = \"onion\"\ncream = \"yogurt\"\n".to_string(),
+        ));
+
+    let list = generate(
+        &ctx,
+        GenerateRequest {
+            cook_date: Some("2025-06-10".parse().unwrap()),
+            ..request(&["a.cook"])
+        },
+    )
+    .unwrap()
+    .value;
+
+    assert_eq!(quantities(&list, "buttermilk"), None);
+    assert_eq!(quantities(&list, "shallots"), Some(vec!["2".to_string()]));
+    assert_eq!(quantities(&list, "cream"), Some(vec!["200 ml".to_string()]));
+    let substituted: Vec<_> = list
+        .substitutions
+        .iter()
+        .map(|s| (s.ingredient.as_str(), s.with_joined()))
+        .collect();
+    assert_eq!(substituted, [("buttermilk", "milk + lemon".to_string())]);
 }
 
 /// The pantry is only consulted when one is configured — this is how
@@ -378,6 +537,68 @@ fn references_are_expanded_into_their_ingredients() {
     );
 }
 
+/// Each item says which recipe asked for how much, and through which
+/// reference, so the merged total can be traced back.
+#[test]
+fn items_name_the_recipes_and_references_they_came_from() {
+    let dir = referencing_fixture();
+    std::fs::write(dir.path().join("salad.cook"), "Slice @tomatoes{2}.\n").unwrap();
+
+    let list = generate(&ctx(&dir), request(&["main.cook", "salad.cook"]))
+        .expect("generates")
+        .value;
+
+    let tomatoes = list.items.iter().find(|i| i.name == "tomatoes").unwrap();
+    assert_eq!(tomatoes.quantities, vec!["6".to_string()]);
+    assert_eq!(
+        tomatoes.sources,
+        vec![
+            ItemSource {
+                recipe: "main".to_string(),
+                via: vec!["sauce".to_string()],
+                quantities: vec!["4".to_string()],
+            },
+            ItemSource {
+                recipe: "salad".to_string(),
+                via: Vec::new(),
+                quantities: vec!["2".to_string()],
+            },
+        ]
+    );
+    let rice = list.items.iter().find(|i| i.name == "rice").unwrap();
+    assert_eq!(rice.sources.len(), 1);
+    assert!(rice.sources[0].via.is_empty(), "{:?}", rice.sources);
+}
+
+/// Sources follow aisle synonyms onto the name the list uses.
+#[test]
+fn sources_fold_with_aisle_synonyms() {
+    let dir = dir_with(&[
+        ("a.cook", "Add @olive oil{2%tbsp}.\n"),
+        ("b.cook", "Add @olive_oil{1%tbsp}.\n"),
+    ]);
+    let ctx = ctx(&dir).with_aisle(ConfigSource::Inline(
+        "[condiments]\nolive oil | olive_oil\n".to_string(),
+    ));
+
+    let list = generate(&ctx, request(&["a.cook", "b.cook"]))
+        .expect("generates")
+        .value;
+
+    let recipes: Vec<(&str, &[String])> = list.items[0]
+        .sources
+        .iter()
+        .map(|s| (s.recipe.as_str(), s.quantities.as_slice()))
+        .collect();
+    assert_eq!(
+        recipes,
+        vec![
+            ("a", &["2 tbsp".to_string()][..]),
+            ("b", &["1 tbsp".to_string()][..])
+        ]
+    );
+}
+
 /// Records today's surprising behaviour: suppressing expansion does not drop
 /// the reference, it leaves it on the list as a quantity-less item.
 #[test]
@@ -389,6 +610,7 @@ fn ignore_references_leaves_the_reference_as_a_bare_item() {
         GenerateRequest {
             recipes: vec![at_path("main.cook")],
             ignore_references: true,
+            cook_date: None,
         },
     )
     .expect("generates")
@@ -786,6 +1008,7 @@ fn in_memory_recipe_text_reaches_the_list() {
                 name: "unsaved buffer".to_string(),
             })],
             ignore_references: false,
+            cook_date: None,
         },
     )
     .expect("generates")
@@ -815,6 +1038,7 @@ fn two_in_memory_recipes_aggregate_into_one_list() {
                 }),
             ],
             ignore_references: false,
+            cook_date: None,
         },
     )
     .expect("generates")
@@ -846,6 +1070,7 @@ fn a_buffer_and_a_file_aggregate_together() {
                 }),
             ],
             ignore_references: false,
+            cook_date: None,
         },
     )
     .expect("generates")
@@ -870,6 +1095,7 @@ fn an_in_memory_recipe_expands_references_from_disk() {
                 name: "buffer".to_string(),
             })],
             ignore_references: false,
+            cook_date: None,
         },
     )
     .expect("generates")
@@ -899,6 +1125,7 @@ fn a_reference_from_a_buffer_to_an_unsaved_recipe_is_not_found() {
                 name: "buffer".to_string(),
             })],
             ignore_references: false,
+            cook_date: None,
         },
     ) {
         Err(CoreError::RecipeNotFound { name }) => assert!(name.contains("sauce"), "{name}"),
@@ -921,6 +1148,7 @@ fn in_memory_text_is_used_and_the_name_is_never_looked_up() {
                 name: "decoy.cook".to_string(),
             })],
             ignore_references: false,
+            cook_date: None,
         },
     )
     .expect("generates")
@@ -950,6 +1178,7 @@ fn the_request_scale_applies_to_an_in_memory_recipe() {
                 4.0,
             )],
             ignore_references: false,
+            cook_date: None,
         },
     )
     .expect("generates")
@@ -974,6 +1203,7 @@ fn a_broken_buffer_is_a_parse_error_naming_the_supplied_name() {
                 name: "Untitled-1".to_string(),
             })],
             ignore_references: false,
+            cook_date: None,
         },
     ) {
         Err(CoreError::Parse {
@@ -1009,6 +1239,7 @@ fn a_warning_from_a_buffer_carries_no_file() {
                 name: "buffer".to_string(),
             })],
             ignore_references: false,
+            cook_date: None,
         },
     )
     .expect("parses despite warning");
@@ -1022,3 +1253,388 @@ fn a_warning_from_a_buffer_carries_no_file() {
     assert_eq!(location.file, None, "{diagnostic:?}");
     assert!(location.span.is_some(), "{diagnostic:?}");
 }
+
+/// The saved list is what the store says, not every reference: a recipe
+/// expands only the references stored under it, at its stored scale, and a
+/// menu expands to the recipes stored under it.
+#[test]
+fn the_saved_list_follows_the_stored_scales_and_references() {
+    let dir = dir_with(&[
+        (
+            "Pasta.cook",
+            "Boil @pasta{100%g} with @./Sauce{} and @./Pesto{}.\n",
+        ),
+        ("Sauce.cook", "Simmer @tomatoes{2}.\n"),
+        ("Pesto.cook", "Pound @basil{1%bunch}.\n"),
+        ("Salad.cook", "Toss @lettuce{1}.\n"),
+    ]);
+    let store = ShoppingListStore::new(&base(&dir));
+    store
+        .add(StoredEntry {
+            path: "Pasta".to_string(),
+            name: String::new(),
+            scale: 2.0,
+            included_references: Some(vec!["Sauce".to_string()]),
+            recipes: None,
+        })
+        .unwrap();
+    store
+        .add_menu(
+            "Week.menu".to_string(),
+            1.0,
+            vec![StoredEntry {
+                path: "Salad".to_string(),
+                name: String::new(),
+                scale: 3.0,
+                included_references: None,
+                recipes: None,
+            }],
+        )
+        .unwrap();
+
+    let list = generate_saved(&ctx(&dir), &store).unwrap().into_value();
+
+    assert_eq!(quantities(&list, "pasta"), Some(vec!["200 g".to_string()]));
+    assert_eq!(quantities(&list, "tomatoes"), Some(vec!["4".to_string()]));
+    assert_eq!(quantities(&list, "lettuce"), Some(vec!["3".to_string()]));
+    assert_eq!(quantities(&list, "basil"), None);
+
+    let mut names = saved_ingredient_names(&ctx(&dir), &store)
+        .unwrap()
+        .into_value();
+    names.sort();
+    assert_eq!(names, ["lettuce", "pasta", "tomatoes"]);
+}
+
+/// Entries that are not on a store make the list they would make on one:
+/// the web page posts what it shows rather than what is saved.
+#[test]
+fn entries_off_a_store_make_the_same_list_as_on_one() {
+    let dir = dir_with(&[
+        (
+            "Pasta.cook",
+            "Boil @pasta{100%g} with @./Sauce{} and @./Pesto{}.\n",
+        ),
+        ("Sauce.cook", "Simmer @tomatoes{2}.\n"),
+        ("Pesto.cook", "Pound @basil{1%bunch}.\n"),
+    ]);
+    let entry = StoredEntry {
+        path: "Pasta".to_string(),
+        name: String::new(),
+        scale: 2.0,
+        included_references: Some(vec!["Sauce".to_string()]),
+        recipes: None,
+    };
+    let store = ShoppingListStore::new(&base(&dir));
+    store.add(entry.clone()).unwrap();
+    store
+        .add_item(ManualItem {
+            name: "candles".to_string(),
+            quantity: None,
+            category: None,
+        })
+        .unwrap();
+
+    let off = generate_entries(&ctx(&dir), &[entry], &store.items().unwrap())
+        .unwrap()
+        .into_value();
+    let on = generate_saved(&ctx(&dir), &store).unwrap().into_value();
+
+    assert_eq!(off.items, on.items);
+    assert_eq!(quantities(&off, "tomatoes"), Some(vec!["4".to_string()]));
+    assert_eq!(quantities(&off, "basil"), None);
+    assert!(off.items.iter().any(|item| item.name == "candles"));
+}
+
+#[test]
+fn an_empty_saved_list_is_an_empty_list() {
+    let dir = dir_with(&[]);
+    let store = ShoppingListStore::new(&base(&dir));
+    let list = generate_saved(&ctx(&dir), &store).unwrap().into_value();
+    assert!(list.is_empty());
+    assert!(saved_ingredient_names(&ctx(&dir), &store)
+        .unwrap()
+        .into_value()
+        .is_empty());
+}
+
+/// Items added by hand add up with the recipes' amounts, come from no
+/// recipe, and go in the category they were given ahead of aisle.conf's.
+#[test]
+fn items_added_by_hand_join_the_saved_list() {
+    let dir = dir_with(&[("Salad.cook", "Toss @tomatoes{2} and @milk{1%l}.\n")]);
+    let store = ShoppingListStore::new(&base(&dir));
+    store.add(entry_for("Salad")).unwrap();
+    for (name, quantity, category) in [
+        ("tomatoes", Some("3"), None),
+        ("milk", None, Some("fridge")),
+        ("toilet paper", Some("a big pack"), Some("household")),
+        ("candles", None, None),
+    ] {
+        store
+            .add_item(ManualItem {
+                name: name.to_string(),
+                quantity: quantity.map(ToOwned::to_owned),
+                category: category.map(ToOwned::to_owned),
+            })
+            .unwrap();
+    }
+    let ctx = ctx(&dir).with_aisle(ConfigSource::Inline(AISLE.to_string()));
+
+    let list = generate_saved(&ctx, &store).unwrap().into_value();
+
+    assert_eq!(quantities(&list, "tomatoes"), Some(vec!["5".to_string()]));
+    assert_eq!(quantities(&list, "milk"), Some(vec!["1 l".to_string()]));
+    assert_eq!(
+        quantities(&list, "toilet paper"),
+        Some(vec!["a big pack".to_string()])
+    );
+    let toilet_paper = list.items.iter().find(|i| i.name == "toilet paper");
+    assert!(toilet_paper.unwrap().sources.is_empty());
+    let categories: Vec<(&str, Vec<&str>)> = list
+        .categories
+        .iter()
+        .map(|c| {
+            (
+                c.name.as_str(),
+                c.items.iter().map(|i| i.name.as_str()).collect(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        categories,
+        [
+            ("produce", vec!["tomatoes"]),
+            ("fridge", vec!["milk"]),
+            ("household", vec!["toilet paper"]),
+            ("other", vec!["candles"]),
+        ]
+    );
+
+    let mut names = saved_ingredient_names(&ctx, &store).unwrap().into_value();
+    names.sort();
+    assert_eq!(names, ["candles", "milk", "toilet paper", "tomatoes"]);
+}
+
+/// Shopping done: the ticked items go into the pantry — topped up in its own
+/// unit where it had some, added under their aisle otherwise — and lose their
+/// ticks, so a second commit has nothing left to do.
+#[test]
+fn ticked_items_are_moved_into_the_pantry() {
+    let dir = dir_with(&[
+        (
+            "Salad.cook",
+            "Toss @tomatoes{2} and @lettuce{1} with @milk{1%l}.\n",
+        ),
+        (
+            "pantry.conf",
+            "# What we have\n[dairy]\nmilk = { quantity = \"500%ml\", expire = \"2100-01-01\" }\n",
+        ),
+    ]);
+    let store = ShoppingListStore::new(&base(&dir));
+    store.add(entry_for("Salad")).unwrap();
+    store.check("tomatoes").unwrap();
+    store.check("milk").unwrap();
+    let pantry = base(&dir).join("pantry.conf");
+    let ctx = ctx(&dir)
+        .with_aisle(ConfigSource::Inline(AISLE.to_string()))
+        .with_pantry(ConfigSource::Path(pantry.clone()));
+    let bought = NaiveDate::from_ymd_opt(2026, 10, 19);
+
+    let committed = commit_to_pantry(&ctx, &store, CommitRequest { bought })
+        .unwrap()
+        .into_value();
+
+    let summary: Vec<(&str, &str, Option<&str>, bool)> = committed
+        .iter()
+        .map(|i| {
+            (
+                i.name.as_str(),
+                i.section.as_str(),
+                i.stock.as_deref(),
+                i.added,
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("tomatoes", "produce", Some("2"), true),
+            ("milk", "dairy", Some("1000%ml"), false),
+        ]
+    );
+    assert_eq!(committed[1].bought, ["0.5 l"]);
+    let written = std::fs::read_to_string(&pantry).unwrap();
+    assert!(
+        written.starts_with("# What we have\n[dairy]\n"),
+        "{written}"
+    );
+    let stocked = crate::pantry::load(&ctx).unwrap().into_value();
+    let items: Vec<_> = stocked
+        .items()
+        .map(|i| {
+            (
+                i.name.as_str(),
+                i.quantity.as_deref(),
+                i.bought.as_deref(),
+                i.expire.as_deref(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        items,
+        [
+            (
+                "milk",
+                Some("1000%ml"),
+                Some("2026-10-19"),
+                Some("2100-01-01")
+            ),
+            ("tomatoes", Some("2"), Some("2026-10-19"), None),
+        ]
+    );
+    assert!(store.checked_set().unwrap().is_empty());
+
+    let again = commit_to_pantry(&ctx, &store, CommitRequest { bought }).unwrap();
+    assert!(again.value.is_empty());
+}
+
+fn entry_for(path: &str) -> StoredEntry {
+    StoredEntry {
+        path: path.to_string(),
+        name: String::new(),
+        scale: 1.0,
+        included_references: None,
+        recipes: None,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Packages
+// ---------------------------------------------------------------------------
+
+/// A recipe asking for eggs, cream, butter and salt, and a datastore that
+/// knows how three of them are sold.
+fn packaged_fixture() -> tempfile::TempDir {
+    dir_with(&[
+        (
+            "a.cook",
+            "Whisk @eggs{3} with @cream{250%ml}, @butter{130%g} and @salt{1%tsp}.\n",
+        ),
+        (
+            "db/eggs/shopping.yml",
+            "price_per_unit: 0.25\npackage_size: 6\n",
+        ),
+        ("db/cream/shopping.yml", "package_size: 200%ml\n"),
+        ("db/butter/shopping.yml", "package_size: 250 G\n"),
+    ])
+}
+
+fn packages(list: &AggregatedList, name: &str) -> Option<Packages> {
+    list.items
+        .iter()
+        .find(|i| i.name == name)
+        .and_then(|i| i.packages.clone())
+}
+
+/// Needs are rounded up to whole packages, matching units however they are
+/// capitalised, and the rest of the last package is reported.
+#[test]
+fn needs_are_rounded_up_to_whole_packages() {
+    let dir = packaged_fixture();
+    let mut list = generate(&ctx(&dir), request(&["a.cook"]))
+        .unwrap()
+        .into_value();
+
+    let diagnostics = list.round_to_packages(&base(&dir).join("db"));
+
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+    let eggs = packages(&list, "eggs").expect("eggs are packaged");
+    assert_eq!((eggs.count, eggs.size.as_str()), (1, "6"));
+    assert_eq!(eggs.leftover, "3");
+    let cream = packages(&list, "cream").expect("cream is packaged");
+    assert_eq!((cream.count, cream.size.as_str()), (2, "200 ml"));
+    assert_eq!(cream.leftover, "150 ml");
+    let butter = packages(&list, "butter").expect("butter is packaged");
+    assert_eq!((butter.count, butter.size.as_str()), (1, "250 G"));
+    assert_eq!(butter.leftover, "120 G");
+    assert_eq!(packages(&list, "salt"), None);
+
+    assert_eq!(cream.to_string(), "2 × 200 ml (150 ml left over)");
+    assert!(cream.covers("250 ml") && !cream.covers("1 tbsp"));
+
+    // Both views carry them.
+    let categorised = list
+        .categories
+        .iter()
+        .flat_map(|c| &c.items)
+        .find(|i| i.name == "cream")
+        .unwrap();
+    assert_eq!(categorised.packages, Some(cream));
+    assert_eq!(categorised.amount(), ["2 × 200 ml (150 ml left over)"]);
+    let salt = list.items.iter().find(|i| i.name == "salt").unwrap();
+    assert_eq!(salt.amount(), salt.quantities);
+}
+
+/// CSV keeps the raw numbers, for adding up, and says the packages beside
+/// them as the human table does.
+#[test]
+fn csv_says_packages_as_the_human_table_does() {
+    let dir = packaged_fixture();
+    let mut list = generate(&ctx(&dir), request(&["a.cook"]))
+        .unwrap()
+        .into_value();
+    list.round_to_packages(&base(&dir).join("db"));
+
+    let csv = crate::format::shopping_list::build_csv(list, true);
+
+    let rows: Vec<&str> = csv.split_terminator("\r\n").collect();
+    assert!(
+        rows.contains(&",cream,250,ml,2 × 200 ml (150 ml left over),a"),
+        "{csv}"
+    );
+    assert!(rows.contains(&",eggs,3,,1 × 6 (3 left over),a"), "{csv}");
+    assert!(rows.contains(&",salt,1,tsp,1 tsp,a"), "{csv}");
+}
+
+/// A size in a unit nothing on the list is measured in is almost certainly a
+/// mistake, so it is reported against the file rather than silently ignored;
+/// a size that is not a size at all likewise.
+#[test]
+fn a_package_size_that_cannot_apply_is_a_warning_naming_its_file() {
+    let dir = dir_with(&[
+        ("a.cook", "Add @eggs{3} and @milk{1%l}.\n"),
+        ("db/eggs/shopping.yml", "package_size: 500%ml\n"),
+        ("db/milk/shopping.yml", "package_size: a carton\n"),
+    ]);
+    let mut list = generate(&ctx(&dir), request(&["a.cook"]))
+        .unwrap()
+        .into_value();
+
+    let diagnostics = list.round_to_packages(&base(&dir).join("db"));
+
+    assert_eq!(diagnostics.len(), 2, "{diagnostics:?}");
+    assert!(diagnostics[0].message.contains("packages of 500 ml"));
+    assert!(diagnostics[1].message.contains("'a carton'"));
+    let files: Vec<_> = diagnostics
+        .iter()
+        .map(|d| d.location.as_ref().and_then(|l| l.file.clone()).unwrap())
+        .collect();
+    assert!(files[0].ends_with("db/eggs/shopping.yml"));
+    assert!(files[1].ends_with("db/milk/shopping.yml"));
+    assert_eq!(packages(&list, "eggs"), None);
+}
+
+/// No datastore, no change — and no complaint.
+#[test]
+fn a_missing_datastore_changes_nothing() {
+    let dir = packaged_fixture();
+    let mut list = generate(&ctx(&dir), request(&["a.cook"]))
+        .unwrap()
+        .into_value();
+
+    assert!(list
+        .round_to_packages(&base(&dir).join("nowhere"))
+        .is_empty());
+    assert!(list.items.iter().all(|i| i.packages.is_none()));
+}
@@ -0,0 +1,243 @@
+//! What can stand in for an ingredient a recipe asks for.
+//!
+//! A recipe wanting buttermilk can be cooked with milk and a squeeze of lemon;
+//! one wanting a shallot, with an onion. `substitutes.conf` says so, and the
+//! commands that ask "is this in the pantry?" consult it when the answer is
+//! no: [`pantry::recipes`](crate::pantry::recipes) counts a recipe as cookable,
+//! [`shopping_list::generate`](crate::shopping_list::generate) leaves the
+//! ingredient off the list, and
+//! [`doctor::pantry_coverage`](crate::doctor::pantry_coverage) counts it as
+//! covered — each saying which [`Substitution`] it made.
+//!
+//! # Configuration
+//!
+//! `substitutes.conf` is found the way `aisle.conf` and `pantry.conf` are —
+//! see [`Context::discover`](crate::Context::discover). It is TOML, with one
+//! key per ingredient a recipe may ask for:
+//!
+//! ```toml
+//! shallot = "onion"
+//! buttermilk = ["milk + lemon", "yogurt"]
+//! "self-raising flour" = "flour + baking powder"
+//! ```
+//!
+//! A value is one substitute or a list of them, tried in order; a substitute
+//! naming several ingredients joined with `+` needs every one of them. Names
+//! are compared ignoring case, as the pantry compares them.
+//!
+//! A substitute is only ever a yes-or-no: the pantry having it is enough, and
+//! no amount is converted or drawn down, because how much milk makes up for
+//! 200 ml of buttermilk is not something a name can say. Nor is a substitute
+//! substituted in turn.
+
+use crate::{diagnostic::parse_failure, ConfigSource, CoreError, Diagnostic, Outcome};
+use camino::Utf8Path;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use toml_edit::{DocumentMut, Item, Value};
+
+/// A parsed `substitutes.conf`.
+///
+/// Empty by default, and when there is no `substitutes.conf` at all: nothing
+/// stands in for anything.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Substitutes {
+    /// The substitutes for each ingredient, by its lowercased name, in the
+    /// order the file gives them. Each is the ingredients it needs, as
+    /// written.
+    by_ingredient: BTreeMap<String, Vec<Vec<String>>>,
+}
+
+/// An ingredient a recipe asked for, and what stood in for it.
+///
+/// `#[non_exhaustive]` because this is an output type consumers read rather
+/// than construct.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct Substitution {
+    /// The ingredient, as the recipe or list names it.
+    pub ingredient: String,
+    /// What stands in for it, as `substitutes.conf` writes them: every one
+    /// is needed.
+    pub with: Vec<String>,
+}
+
+impl Substitution {
+    /// `milk + lemon`: the substitute as `substitutes.conf` would write it.
+    pub fn with_joined(&self) -> String {
+        self.with.join(" + ")
+    }
+}
+
+impl Substitutes {
+    /// Read and parse the configuration `source` names.
+    ///
+    /// An unset source is an empty configuration, not an error. A value that
+    /// is neither a string nor a list of strings is reported as a warning in
+    /// [`Outcome::diagnostics`], located at the file, and skipped.
+    ///
+    /// # Errors
+    ///
+    /// - [`CoreError::Io`] if the file cannot be read.
+    /// - [`CoreError::Config`] if it is not TOML at all.
+    pub fn load(source: &ConfigSource) -> Result<Outcome<Self>, CoreError> {
+        match source.read()? {
+            Some(text) => Self::parse(&text, source.path()),
+            None => Ok(Outcome::new(Self::default())),
+        }
+    }
+
+    /// Parse `substitutes.conf` text. `path` is where it came from, if
+    /// anywhere, and is what the warnings and the error are attributed to.
+    ///
+    /// # Errors
+    ///
+    /// [`CoreError::Config`] if `text` is not TOML.
+    pub fn parse(text: &str, path: Option<&Utf8Path>) -> Result<Outcome<Self>, CoreError> {
+        let doc = text.parse::<DocumentMut>().map_err(|e| CoreError::Config {
+            path: path.map(ToOwned::to_owned),
+            message: parse_failure(&[Diagnostic::error(e.to_string())], "substitutes"),
+        })?;
+
+        let mut warnings = Vec::new();
+        let mut substitutes = Self::default();
+        for (ingredient, item) in doc.iter() {
+            let written: Option<Vec<&str>> = match item {
+                Item::Value(Value::String(one)) => Some(vec![one.value().as_str()]),
+                Item::Value(Value::Array(many)) => many
+                    .iter()
+                    .map(|value| value.as_str())
+                    .collect::<Option<_>>(),
+                _ => None,
+            };
+            let Some(written) = written else {
+                let diagnostic = Diagnostic::warning(format!(
+                    "the substitutes for '{ingredient}' must be a string or a list of strings, \
+                     so it has none"
+                ));
+                warnings.push(match path {
+                    Some(path) => diagnostic.at_file(path),
+                    None => diagnostic,
+                });
+                continue;
+            };
+            let alternatives: Vec<Vec<String>> = written
+                .into_iter()
+                .map(|substitute| {
+                    substitute
+                        .split('+')
+                        .map(str::trim)
+                        .filter(|part| !part.is_empty())
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                })
+                .filter(|parts| !parts.is_empty())
+                .collect();
+            substitutes
+                .by_ingredient
+                .entry(ingredient.to_lowercase())
+                .or_default()
+                .extend(alternatives);
+        }
+
+        Ok(Outcome::with_diagnostics(substitutes, warnings))
+    }
+
+    /// True when nothing stands in for anything.
+    pub fn is_empty(&self) -> bool {
+        self.by_ingredient.is_empty()
+    }
+
+    /// The first substitute for `ingredient` every part of which `stocked`
+    /// says is there, or `None` if it has none or none of them is.
+    ///
+    /// `stocked` is asked about each part as `substitutes.conf` writes it;
+    /// `ingredient` is looked up ignoring case.
+    pub fn find(
+        &self,
+        ingredient: &str,
+        mut stocked: impl FnMut(&str) -> bool,
+    ) -> Option<Substitution> {
+        self.by_ingredient
+            .get(&ingredient.to_lowercase())?
+            .iter()
+            .find(|parts| parts.iter().all(|part| stocked(part)))
+            .map(|parts| Substitution {
+                ingredient: ingredient.to_string(),
+                with: parts.clone(),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed(text: &str) -> Outcome<Substitutes> {
+        Substitutes::parse(text, Some(Utf8Path::new("substitutes.conf"))).expect("parses")
+    }
+
+    #[test]
+    fn one_substitute_or_a_list_of_them_each_joined_with_plus() {
+        let substitutes =
+            parsed("shallot = \"onion\"\nButtermilk = [\"milk + lemon\", \"yogurt\"]\n").value;
+
+        let stocked = |name: &str| matches!(name, "milk" | "lemon" | "onion");
+        assert_eq!(
+            substitutes.find("buttermilk", stocked),
+            Some(Substitution {
+                ingredient: "buttermilk".to_string(),
+                with: vec!["milk".to_string(), "lemon".to_string()],
+            }),
+            "matched ignoring case, and both parts are needed"
+        );
+        assert_eq!(
+            substitutes
+                .find("Shallot", stocked)
+                .map(|found| found.with_joined()),
+            Some("onion".to_string())
+        );
+    }
+
+    /// Alternatives are tried in the order the file gives them, and one with
+    /// a part missing is passed over.
+    #[test]
+    fn the_first_substitute_wholly_in_stock_is_the_one_made() {
+        let substitutes = parsed("buttermilk = [\"milk + lemon\", \"yogurt\"]\n").value;
+
+        let found = substitutes.find("buttermilk", |name| matches!(name, "milk" | "yogurt"));
+        assert_eq!(
+            found.map(|found| found.with),
+            Some(vec!["yogurt".to_string()])
+        );
+        assert_eq!(substitutes.find("buttermilk", |_| false), None);
+        assert_eq!(substitutes.find("cream", |_| true), None);
+    }
+
+    #[test]
+    fn a_value_that_is_not_a_name_is_skipped_with_a_warning() {
+        let outcome = parsed("shallot = 3\nleek = \"onion\"\n");
+
+        assert!(outcome.value.find("shallot", |_| true).is_none());
+        assert!(outcome.value.find("leek", |_| true).is_some());
+        assert_eq!(outcome.diagnostics.len(), 1);
+        assert!(outcome.diagnostics[0].message.contains("'shallot'"));
+        assert_eq!(
+            outcome.diagnostics[0]
+                .location
+                .as_ref()
+                .and_then(|l| l.file.as_deref()),
+            Some(Utf8Path::new("substitutes.conf"))
+        );
+    }
+
+    #[test]
+    fn a_file_that_is_not_toml_is_a_config_error_naming_it() {
+        let error =
+            Substitutes::parse("shallot = ", Some(Utf8Path::new("substitutes.conf"))).unwrap_err();
+        assert!(
+            matches!(error, CoreError::Config { path: Some(ref path), .. } if path == "substitutes.conf"),
+            "{error:?}"
+        );
+    }
+}
@@ -0,0 +1,129 @@
+//! Converting between the kitchen units people actually write.
+//!
+//! CookCLI builds `cooklang` without its unit database (see the workspace
+//! `Cargo.toml` for why), so `cooklang`'s converter knows no units at all and
+//! quantities stay exactly as authored. That is right for showing a recipe,
+//! but comparing a pantry's `1%kg` of flour with a recipe's `200%g` needs to
+//! know that the two are the same kind of thing.
+//!
+//! This is deliberately small: mass and volume, metric and the common US
+//! kitchen measures, by their usual names and abbreviations. Units are
+//! compared lowercased. Anything else — `cloves`, `pinch`, a bare count —
+//! converts only to itself.
+
+/// What a unit measures, so that grams never turn into millilitres.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dimension {
+    Mass,
+    Volume,
+}
+
+/// Every unit this module knows: its spellings, what it measures, and how many
+/// grams or millilitres one of it is.
+const UNITS: &[(&[&str], Dimension, f64)] = &[
+    (&["mg", "milligram", "milligrams"], Dimension::Mass, 0.001),
+    (&["g", "gram", "grams", "gr"], Dimension::Mass, 1.0),
+    (
+        &["kg", "kilogram", "kilograms", "kilo", "kilos"],
+        Dimension::Mass,
+        1000.0,
+    ),
+    (&["oz", "ounce", "ounces"], Dimension::Mass, 28.349_523_125),
+    (
+        &["lb", "lbs", "pound", "pounds"],
+        Dimension::Mass,
+        453.592_37,
+    ),
+    (
+        &[
+            "ml",
+            "millilitre",
+            "millilitres",
+            "milliliter",
+            "milliliters",
+        ],
+        Dimension::Volume,
+        1.0,
+    ),
+    (
+        &[
+            "cl",
+            "centilitre",
+            "centilitres",
+            "centiliter",
+            "centiliters",
+        ],
+        Dimension::Volume,
+        10.0,
+    ),
+    (
+        &["dl", "decilitre", "decilitres", "deciliter", "deciliters"],
+        Dimension::Volume,
+        100.0,
+    ),
+    (
+        &["l", "litre", "litres", "liter", "liters"],
+        Dimension::Volume,
+        1000.0,
+    ),
+    (
+        &["tsp", "teaspoon", "teaspoons"],
+        Dimension::Volume,
+        4.928_921_593_75,
+    ),
+    (
+        &["tbsp", "tablespoon", "tablespoons"],
+        Dimension::Volume,
+        14.786_764_781_25,
+    ),
+    (
+        &["fl oz", "fluid ounce", "fluid ounces"],
+        Dimension::Volume,
+        29.573_529_562_5,
+    ),
+    (&["cup", "cups"], Dimension::Volume, 236.588_236_5),
+    (&["pint", "pints", "pt"], Dimension::Volume, 473.176_473),
+    (&["quart", "quarts", "qt"], Dimension::Volume, 946.352_946),
+    (
+        &["gallon", "gallons", "gal"],
+        Dimension::Volume,
+        3_785.411_784,
+    ),
+];
+
+fn lookup(unit: &str) -> Option<(Dimension, f64)> {
+    let unit = unit.trim().to_lowercase();
+    UNITS
+        .iter()
+        .find(|(names, _, _)| names.contains(&unit.as_str()))
+        .map(|&(_, dimension, factor)| (dimension, factor))
+}
+
+/// `value` of `from` said in `to`, or `None` if the two do not measure the
+/// same thing. Equal units (ignoring case) always convert, including the
+/// empty unit of a bare count and units this module has never heard of.
+pub(crate) fn convert(value: f64, from: &str, to: &str) -> Option<f64> {
+    if from.trim().eq_ignore_ascii_case(to.trim()) {
+        return Some(value);
+    }
+    let (from_dimension, from_factor) = lookup(from)?;
+    let (to_dimension, to_factor) = lookup(to)?;
+    (from_dimension == to_dimension).then(|| value * from_factor / to_factor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::convert;
+
+    #[test]
+    fn units_of_one_dimension_convert_and_others_do_not() {
+        assert_eq!(convert(1.0, "kg", "g"), Some(1000.0));
+        assert_eq!(convert(250.0, "ML", "l"), Some(0.25));
+        assert!(convert(3.0, "tsp", "tbsp").is_some_and(|v| (v - 1.0).abs() < 1e-9));
+        assert_eq!(convert(1.0, "kg", "ml"), None, "mass is not volume");
+        assert_eq!(convert(2.0, "cloves", "g"), None);
+        assert_eq!(convert(2.0, "Cloves", "cloves"), Some(2.0));
+        assert_eq!(convert(6.0, "", ""), Some(6.0), "a count is a count");
+        assert_eq!(convert(6.0, "", "g"), None);
+    }
+}
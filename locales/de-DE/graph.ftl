@@ -0,0 +1,14 @@
+# Reference graph
+graph-title = Rezeptgraph
+graph-description = Welche Rezepte und Menüs welche verwenden. Klicken Sie auf ein Rezept, um es zu öffnen.
+graph-root = Ausgehend von
+graph-root-placeholder = Gesamte Sammlung
+graph-depth = Tiefe
+graph-depth-placeholder = Unbegrenzt
+graph-show = Anzeigen
+graph-show-all = Alles anzeigen
+graph-download = Herunterladen:
+graph-empty = Keine Rezepte oder Menüs zum Darstellen.
+graph-legend-recipe = Rezept
+graph-legend-menu = Menü
+graph-legend-missing = Fehlendes Rezept
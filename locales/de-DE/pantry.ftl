@@ -6,8 +6,12 @@ pantry-no-config = Keine Vorratskammer-Konfiguration gefunden
 pantry-create-config = Erstellen Sie eine pantry.conf-Datei, um Ihren Bestand zu verfolgen
 pantry-configure = Vorratskammer konfigurieren →
 pantry-no-items-section = Keine Artikel in diesem Bereich
-pantry-show-out-of-stock = Nur nicht vorrätige Artikel anzeigen
-pantry-no-out-of-stock = Keine nicht vorrätigen Artikel in diesem Bereich
+pantry-filter-label = Anzeigen
+pantry-filter-all = Alle
+pantry-filter-expiring = Läuft bald ab
+pantry-filter-low = Knapp
+pantry-filter-out = Nicht vorrätig
+pantry-filter-none = Keine Artikel in diesem Bereich entsprechen dem Filter
 pantry-total-sections = Gesamte Bereiche:
 pantry-edit-config = Konfiguration bearbeiten
 
@@ -44,3 +48,30 @@ pantry-optional = (optional)
 pantry-failed-add = Artikel konnte nicht hinzugefügt werden
 pantry-failed-update = Artikel konnte nicht aktualisiert werden
 pantry-failed-remove = Artikel konnte nicht entfernt werden
+pantry-undo = Letzte Änderung rückgängig machen
+pantry-nothing-to-undo = Nichts rückgängig zu machen
+pantry-failed-undo = Die letzte Änderung konnte nicht rückgängig gemacht werden
+
+# Dashboard
+pantry-view-label = Ansicht
+pantry-view-sections = Bereiche
+pantry-view-calendar = Kalender
+pantry-drag-hint = Ziehen Sie einen Artikel auf einen anderen Bereich, um ihn dorthin zu verschieben; markieren Sie mehrere, um sie gemeinsam zu bearbeiten.
+pantry-calendar-previous = Vorheriger Monat
+pantry-calendar-next = Nächster Monat
+pantry-calendar-undated = Artikel ohne Ablaufdatum: %s
+pantry-bulk-select = Auswählen
+pantry-edit-quantity = Klicken, um die Menge zu ändern
+pantry-bulk-selected = %s ausgewählt
+pantry-bulk-move = Verschieben nach…
+pantry-bulk-set-expiry = Ablaufdatum setzen
+pantry-bulk-remove = Entfernen
+pantry-bulk-clear = Auswahl aufheben
+pantry-bulk-confirm-remove = %s Artikel entfernen?
+pantry-failed-batch = Die Änderungen konnten nicht übernommen werden
+
+# Use It Up
+pantry-use-up = Aufbrauchen
+pantry-use-up-hint = Rezepte, die verbrauchen, was bald abläuft – die besten zuerst
+pantry-use-up-uses = Verwendet:
+pantry-use-up-missing = Fehlt:
@@ -21,6 +21,11 @@ recipe-scale-label = Skalierung
 recipe-servings-label = Portionen
 recipe-print = Rezept drucken
 recipe-added = Hinzugefügt!
+recipe-cooked-it = Gekocht
+recipe-taken-from-pantry = Aus Ihrer Vorratskammer genommen
+recipe-used-up = Aufgebraucht
+recipe-running-low = Wird knapp
+recipe-pantry-failed = Vorratskammer konnte nicht aktualisiert werden
 recipe-main-section = Hauptteil
 recipe-preparation = Vorbereitung
 
@@ -19,6 +19,10 @@ shopping-mark-done = Als erledigt markieren
 shopping-mark-undone = Als unerledigt markieren
 shopping-selected-recipes = Ausgewählte Rezepte
 shopping-clear-all = Alles löschen
+shopping-undo = Rückgängig
+shopping-mark-bought = Als gekauft markieren
+shopping-moved-to-pantry = Abgehakte Artikel in Ihre Vorratskammer übernommen
+shopping-nothing-checked = Noch nichts abgehakt
 shopping-in-pantry = In Ihrer Vorratskammer
 shopping-remove = Entfernen
 shopping-add-recipes-first = Bitte fügen Sie zuerst einige Rezepte hinzu
@@ -32,3 +36,12 @@ shopping-copy-failed = Kopieren fehlgeschlagen
 shopping-copy-options = Kopieroptionen
 shopping-copy-include-aisles = Regalnamen einschließen
 shopping-copy-include-amounts = Mengen einschließen
+shopping-list-label = Liste
+shopping-new-list = Neue Liste
+shopping-new-list-prompt = Name der neuen Liste (Kleinbuchstaben, Ziffern, - und _)
+shopping-invalid-list-name = Ein Listenname darf nur Kleinbuchstaben, Ziffern, '-' und '_' enthalten.
+shopping-move-to = Verschieben nach…
+shopping-store-label = Geschäft
+shopping-store-any = Beliebiges Geschäft
+shopping-partly-in-pantry = bereits in Ihrer Vorratskammer
+shopping-needed-by = Benötigt für
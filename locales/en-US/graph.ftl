@@ -0,0 +1,14 @@
+# Reference graph
+graph-title = Recipe Graph
+graph-description = Which recipes and menus use which. Click a recipe to open it.
+graph-root = Start from
+graph-root-placeholder = Whole collection
+graph-depth = Depth
+graph-depth-placeholder = Unlimited
+graph-show = Show
+graph-show-all = Show everything
+graph-download = Download:
+graph-empty = No recipes or menus to draw.
+graph-legend-recipe = Recipe
+graph-legend-menu = Menu
+graph-legend-missing = Missing recipe
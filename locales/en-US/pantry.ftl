@@ -6,8 +6,12 @@ pantry-no-config = No pantry configuration found
 pantry-create-config = Create a pantry.conf file to track your inventory
 pantry-configure = Configure pantry →
 pantry-no-items-section = No items in this section
-pantry-show-out-of-stock = Show only out of stock
-pantry-no-out-of-stock = No out-of-stock items in this section
+pantry-filter-label = Show
+pantry-filter-all = All
+pantry-filter-expiring = Expiring soon
+pantry-filter-low = Low
+pantry-filter-out = Out of stock
+pantry-filter-none = No items in this section match the filter
 pantry-total-sections = Total sections:
 pantry-edit-config = Edit Configuration
 
@@ -44,3 +48,30 @@ pantry-optional = (optional)
 pantry-failed-add = Failed to add item
 pantry-failed-update = Failed to update item
 pantry-failed-remove = Failed to remove item
+pantry-undo = Undo Last Change
+pantry-nothing-to-undo = Nothing to undo
+pantry-failed-undo = Failed to undo the last change
+
+# Dashboard
+pantry-view-label = View
+pantry-view-sections = Sections
+pantry-view-calendar = Calendar
+pantry-drag-hint = Drag an item onto another section to move it there; tick several to edit them together.
+pantry-calendar-previous = Previous month
+pantry-calendar-next = Next month
+pantry-calendar-undated = Items with no expiry date: %s
+pantry-bulk-select = Select
+pantry-edit-quantity = Click to change the quantity
+pantry-bulk-selected = %s selected
+pantry-bulk-move = Move to…
+pantry-bulk-set-expiry = Set expiry
+pantry-bulk-remove = Remove
+pantry-bulk-clear = Clear selection
+pantry-bulk-confirm-remove = Remove %s items?
+pantry-failed-batch = Failed to apply the changes
+
+# Use It Up
+pantry-use-up = Use It Up
+pantry-use-up-hint = Recipes that use what is about to expire, best first
+pantry-use-up-uses = Uses:
+pantry-use-up-missing = Missing:
@@ -21,6 +21,11 @@ recipe-scale-label = Scale
 recipe-servings-label = servings
 recipe-print = Print Recipe
 recipe-added = Added!
+recipe-cooked-it = Cooked It
+recipe-taken-from-pantry = Taken out of your pantry
+recipe-used-up = Used up
+recipe-running-low = Running low
+recipe-pantry-failed = Failed to update the pantry
 recipe-main-section = Main
 recipe-preparation = preparation
 
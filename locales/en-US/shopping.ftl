@@ -19,6 +19,10 @@ shopping-mark-done = Mark as done
 shopping-mark-undone = Mark as undone
 shopping-selected-recipes = Selected Recipes
 shopping-clear-all = Clear All
+shopping-undo = Undo
+shopping-mark-bought = Mark as bought
+shopping-moved-to-pantry = Checked items moved to your pantry
+shopping-nothing-checked = Nothing is checked off yet
 shopping-in-pantry = In Your Pantry
 shopping-remove = Remove
 shopping-add-recipes-first = Please add some recipes first
@@ -33,3 +37,12 @@ shopping-copy-failed = Copy failed
 shopping-copy-options = Copy options
 shopping-copy-include-aisles = Include aisle names
 shopping-copy-include-amounts = Include amounts
+shopping-list-label = List
+shopping-new-list = New list
+shopping-new-list-prompt = Name of the new list (lowercase letters, digits, - and _)
+shopping-invalid-list-name = A list name may only use lowercase letters, digits, '-' and '_'.
+shopping-move-to = Move to…
+shopping-store-label = Store
+shopping-store-any = Any store
+shopping-partly-in-pantry = already in your pantry
+shopping-needed-by = Needed by
@@ -0,0 +1,14 @@
+# Reference graph
+graph-title = Grafo de Recetas
+graph-description = Qué recetas y menús usan cuáles. Haz clic en una receta para abrirla.
+graph-root = Empezar desde
+graph-root-placeholder = Toda la colección
+graph-depth = Profundidad
+graph-depth-placeholder = Sin límite
+graph-show = Mostrar
+graph-show-all = Mostrar todo
+graph-download = Descargar:
+graph-empty = No hay recetas ni menús que dibujar.
+graph-legend-recipe = Receta
+graph-legend-menu = Menú
+graph-legend-missing = Receta que falta
@@ -6,8 +6,12 @@ pantry-no-config = No se encontró configuración de despensa
 pantry-create-config = Cree un archivo pantry.conf para rastrear su inventario
 pantry-configure = Configurar despensa →
 pantry-no-items-section = No hay artículos en esta sección
-pantry-show-out-of-stock = Mostrar solo artículos agotados
-pantry-no-out-of-stock = No hay artículos agotados en esta sección
+pantry-filter-label = Mostrar
+pantry-filter-all = Todos
+pantry-filter-expiring = Caducan pronto
+pantry-filter-low = Escasos
+pantry-filter-out = Agotados
+pantry-filter-none = Ningún artículo de esta sección coincide con el filtro
 pantry-total-sections = Total de secciones:
 pantry-edit-config = Editar configuración
 
@@ -44,3 +48,30 @@ pantry-optional = (opcional)
 pantry-failed-add = Error al agregar artículo
 pantry-failed-update = Error al actualizar artículo
 pantry-failed-remove = Error al eliminar artículo
+pantry-undo = Deshacer último cambio
+pantry-nothing-to-undo = No hay nada que deshacer
+pantry-failed-undo = No se pudo deshacer el último cambio
+
+# Dashboard
+pantry-view-label = Vista
+pantry-view-sections = Secciones
+pantry-view-calendar = Calendario
+pantry-drag-hint = Arrastre un artículo a otra sección para moverlo allí; marque varios para editarlos juntos.
+pantry-calendar-previous = Mes anterior
+pantry-calendar-next = Mes siguiente
+pantry-calendar-undated = Artículos sin fecha de caducidad: %s
+pantry-bulk-select = Seleccionar
+pantry-edit-quantity = Haga clic para cambiar la cantidad
+pantry-bulk-selected = %s seleccionados
+pantry-bulk-move = Mover a…
+pantry-bulk-set-expiry = Fijar caducidad
+pantry-bulk-remove = Eliminar
+pantry-bulk-clear = Borrar selección
+pantry-bulk-confirm-remove = ¿Eliminar %s artículos?
+pantry-failed-batch = Error al aplicar los cambios
+
+# Use It Up
+pantry-use-up = Aprovéchelo
+pantry-use-up-hint = Recetas que aprovechan lo que está a punto de caducar, las mejores primero
+pantry-use-up-uses = Usa:
+pantry-use-up-missing = Falta:
@@ -21,6 +21,11 @@ recipe-scale-label = Escala
 recipe-servings-label = porciones
 recipe-print = Imprimir receta
 recipe-added = ¡Agregado!
+recipe-cooked-it = Cocinado
+recipe-taken-from-pantry = Retirado de su despensa
+recipe-used-up = Agotado
+recipe-running-low = Queda poco
+recipe-pantry-failed = No se pudo actualizar la despensa
 recipe-main-section = Principal
 recipe-preparation = preparación
 
@@ -19,6 +19,10 @@ shopping-mark-done = Marcar como hecho
 shopping-mark-undone = Marcar como no hecho
 shopping-selected-recipes = Recetas seleccionadas
 shopping-clear-all = Borrar todo
+shopping-undo = Deshacer
+shopping-mark-bought = Marcar como comprado
+shopping-moved-to-pantry = Artículos marcados añadidos a su despensa
+shopping-nothing-checked = Todavía no hay nada marcado
 shopping-in-pantry = En su despensa
 shopping-remove = Eliminar
 shopping-add-recipes-first = Por favor, agregue primero algunas recetas
@@ -32,3 +36,12 @@ shopping-copy-failed = Error al copiar
 shopping-copy-options = Opciones de copia
 shopping-copy-include-aisles = Incluir nombres de secciones
 shopping-copy-include-amounts = Incluir cantidades
+shopping-list-label = Lista
+shopping-new-list = Nueva lista
+shopping-new-list-prompt = Nombre de la nueva lista (minúsculas, dígitos, - y _)
+shopping-invalid-list-name = El nombre de una lista solo puede contener minúsculas, dígitos, '-' y '_'.
+shopping-move-to = Mover a…
+shopping-store-label = Tienda
+shopping-store-any = Cualquier tienda
+shopping-partly-in-pantry = ya en su despensa
+shopping-needed-by = Necesario para
@@ -0,0 +1,14 @@
+# Reference graph
+graph-title = Errezeten Grafoa
+graph-description = Zein errezeta eta menuk erabiltzen dituzten zeintzuk. Egin klik errezeta batean irekitzeko.
+graph-root = Hemendik hasita
+graph-root-placeholder = Bilduma osoa
+graph-depth = Sakonera
+graph-depth-placeholder = Mugarik gabe
+graph-show = Erakutsi
+graph-show-all = Erakutsi dena
+graph-download = Deskargatu:
+graph-empty = Ez dago marrazteko errezetarik edo menurik.
+graph-legend-recipe = Errezeta
+graph-legend-menu = Menua
+graph-legend-missing = Falta den errezeta
@@ -6,8 +6,12 @@ pantry-no-config = Ez da despentsaren konfiguraziorik aurkitu
 pantry-create-config = Sortu pantry.conf artxibo bat inbentarioaren jarraipena egiteko
 pantry-configure = Konfiguratu despentsa →
 pantry-no-items-section = Ez dago elementurik sail honetan
-pantry-show-out-of-stock = Erakutsi bukatutako elementuak bakarrik
-pantry-no-out-of-stock = Ez dago bukatutako elementurik sail honetan
+pantry-filter-label = Erakutsi
+pantry-filter-all = Guztiak
+pantry-filter-expiring = Laster iraungiko dira
+pantry-filter-low = Gutxi
+pantry-filter-out = Bukatuta
+pantry-filter-none = Sail honetako elementurik ez dator bat iragazkiarekin
 pantry-total-sections = Sailak guztira:
 pantry-edit-config = Editatu konfigurazioa
 
@@ -44,3 +48,30 @@ pantry-optional = (aukerakoa)
 pantry-failed-add = Errorea elementua gehitzerakoan
 pantry-failed-update = Errorea elementua eguneratzerakoan
 pantry-failed-remove = Errorea elementua ezabatzerakoan
+pantry-undo = Desegin azken aldaketa
+pantry-nothing-to-undo = Ez dago desegiteko ezer
+pantry-failed-undo = Ezin izan da azken aldaketa desegin
+
+# Dashboard
+pantry-view-label = Ikuspegia
+pantry-view-sections = Sailak
+pantry-view-calendar = Egutegia
+pantry-drag-hint = Arrastatu elementu bat beste sail batera hara eramateko; markatu hainbat batera editatzeko.
+pantry-calendar-previous = Aurreko hilabetea
+pantry-calendar-next = Hurrengo hilabetea
+pantry-calendar-undated = Iraungitze-datarik gabeko elementuak: %s
+pantry-bulk-select = Hautatu
+pantry-edit-quantity = Egin klik kantitatea aldatzeko
+pantry-bulk-selected = %s hautatuta
+pantry-bulk-move = Eraman hona…
+pantry-bulk-set-expiry = Ezarri iraungitzea
+pantry-bulk-remove = Ezabatu
+pantry-bulk-clear = Garbitu hautapena
+pantry-bulk-confirm-remove = %s elementu ezabatu?
+pantry-failed-batch = Errorea aldaketak aplikatzerakoan
+
+# Use It Up
+pantry-use-up = Erabili lehenbailehen
+pantry-use-up-hint = Iraungitzear dagoena erabiltzen duten errezetak, onenak lehenik
+pantry-use-up-uses = Erabiltzen du:
+pantry-use-up-missing = Falta da:
@@ -21,6 +21,11 @@ recipe-scale-label = Eskala
 recipe-servings-label = anoak
 recipe-print = Inprimatu errezeta
 recipe-added = Gehituta!
+recipe-cooked-it = Prestatuta
+recipe-taken-from-pantry = Zure despentsatik kendu da
+recipe-used-up = Agortuta
+recipe-running-low = Gutxi geratzen da
+recipe-pantry-failed = Ezin izan da despentsa eguneratu
 recipe-main-section = Nagusia
 recipe-preparation = prestaketa
 
@@ -19,6 +19,10 @@ shopping-mark-done = Egindako moduan markatu
 shopping-mark-undone = Egiteke moduan markatu
 shopping-selected-recipes = Aukeratutako errezetak
 shopping-clear-all = Ezabatu dena
+shopping-undo = Desegin
+shopping-mark-bought = Erosita bezala markatu
+shopping-moved-to-pantry = Markatutako elementuak zure despentsara eraman dira
+shopping-nothing-checked = Oraindik ez dago ezer markatuta
 shopping-in-pantry = Zure despentsan
 shopping-remove = Ezabatu
 shopping-add-recipes-first = Mesedez, lehenengo gehitu errezeta batzuk
@@ -32,3 +36,12 @@ shopping-copy-failed = Errorea kopiatzerakoan
 shopping-copy-options = Kopiatzeko aukerak
 shopping-copy-include-aisles = Sailen izenak sartu
 shopping-copy-include-amounts = Kopuruak sartu
+shopping-list-label = Zerrenda
+shopping-new-list = Zerrenda berria
+shopping-new-list-prompt = Zerrenda berriaren izena (letra xeheak, digituak, - eta _)
+shopping-invalid-list-name = Zerrenda-izen batek letra xeheak, digituak, '-' eta '_' soilik izan ditzake.
+shopping-move-to = Eraman hona…
+shopping-store-label = Denda
+shopping-store-any = Edozein denda
+shopping-partly-in-pantry = dagoeneko zure despentsan
+shopping-needed-by = Honetarako behar da
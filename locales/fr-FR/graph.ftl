@@ -0,0 +1,14 @@
+# Reference graph
+graph-title = Graphe des Recettes
+graph-description = Quelles recettes et quels menus utilisent lesquels. Cliquez sur une recette pour l'ouvrir.
+graph-root = Partir de
+graph-root-placeholder = Toute la collection
+graph-depth = Profondeur
+graph-depth-placeholder = Illimitée
+graph-show = Afficher
+graph-show-all = Tout afficher
+graph-download = Télécharger :
+graph-empty = Aucune recette ni aucun menu à dessiner.
+graph-legend-recipe = Recette
+graph-legend-menu = Menu
+graph-legend-missing = Recette manquante
@@ -6,8 +6,12 @@ pantry-no-config = Aucune configuration de garde-manger trouvée
 pantry-create-config = Créez un fichier pantry.conf pour suivre votre inventaire
 pantry-configure = Configurer le garde-manger →
 pantry-no-items-section = Aucun article dans cette section
-pantry-show-out-of-stock = Afficher uniquement les articles en rupture de stock
-pantry-no-out-of-stock = Aucun article en rupture de stock dans cette section
+pantry-filter-label = Afficher
+pantry-filter-all = Tous
+pantry-filter-expiring = Expire bientôt
+pantry-filter-low = Stock bas
+pantry-filter-out = En rupture
+pantry-filter-none = Aucun article de cette section ne correspond au filtre
 pantry-total-sections = Total des sections :
 pantry-edit-config = Modifier la configuration
 
@@ -44,3 +48,30 @@ pantry-optional = (optionnel)
 pantry-failed-add = Échec de l'ajout de l'article
 pantry-failed-update = Échec de la mise à jour de l'article
 pantry-failed-remove = Échec de la suppression de l'article
+pantry-undo = Annuler la dernière modification
+pantry-nothing-to-undo = Rien à annuler
+pantry-failed-undo = Impossible d'annuler la dernière modification
+
+# Dashboard
+pantry-view-label = Vue
+pantry-view-sections = Sections
+pantry-view-calendar = Calendrier
+pantry-drag-hint = Faites glisser un article sur une autre section pour l'y déplacer ; cochez-en plusieurs pour les modifier ensemble.
+pantry-calendar-previous = Mois précédent
+pantry-calendar-next = Mois suivant
+pantry-calendar-undated = Articles sans date d'expiration : %s
+pantry-bulk-select = Sélectionner
+pantry-edit-quantity = Cliquez pour modifier la quantité
+pantry-bulk-selected = %s sélectionné(s)
+pantry-bulk-move = Déplacer vers…
+pantry-bulk-set-expiry = Définir l'expiration
+pantry-bulk-remove = Supprimer
+pantry-bulk-clear = Effacer la sélection
+pantry-bulk-confirm-remove = Supprimer %s articles ?
+pantry-failed-batch = Échec de l'application des modifications
+
+# Use It Up
+pantry-use-up = À consommer
+pantry-use-up-hint = Recettes qui utilisent ce qui va bientôt périmer, les meilleures d'abord
+pantry-use-up-uses = Utilise :
+pantry-use-up-missing = Manque :
@@ -21,6 +21,11 @@ recipe-scale-label = Échelle
 recipe-servings-label = portions
 recipe-print = Imprimer la recette
 recipe-added = Ajouté !
+recipe-cooked-it = Cuisiné
+recipe-taken-from-pantry = Retiré de votre garde-manger
+recipe-used-up = Épuisé
+recipe-running-low = Bientôt épuisé
+recipe-pantry-failed = Impossible de mettre à jour le garde-manger
 recipe-main-section = Principal
 recipe-preparation = préparation
 
@@ -19,6 +19,10 @@ shopping-mark-done = Marquer comme fait
 shopping-mark-undone = Marquer comme non fait
 shopping-selected-recipes = Recettes sélectionnées
 shopping-clear-all = Tout effacer
+shopping-undo = Annuler
+shopping-mark-bought = Marquer comme acheté
+shopping-moved-to-pantry = Articles cochés ajoutés à votre garde-manger
+shopping-nothing-checked = Rien n'est encore coché
 shopping-in-pantry = Dans votre garde-manger
 shopping-remove = Retirer
 shopping-add-recipes-first = Veuillez d'abord ajouter quelques recettes
@@ -32,3 +36,12 @@ shopping-copy-failed = Échec de la copie
 shopping-copy-options = Options de copie
 shopping-copy-include-aisles = Inclure les noms de rayons
 shopping-copy-include-amounts = Inclure les quantités
+shopping-list-label = Liste
+shopping-new-list = Nouvelle liste
+shopping-new-list-prompt = Nom de la nouvelle liste (minuscules, chiffres, - et _)
+shopping-invalid-list-name = Un nom de liste ne peut contenir que des minuscules, des chiffres, « - » et « _ ».
+shopping-move-to = Déplacer vers…
+shopping-store-label = Magasin
+shopping-store-any = N’importe quel magasin
+shopping-partly-in-pantry = déjà dans votre garde-manger
+shopping-needed-by = Utilisé dans
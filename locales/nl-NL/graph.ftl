@@ -0,0 +1,14 @@
+# Reference graph
+graph-title = Receptengraaf
+graph-description = Welke recepten en menu's welke gebruiken. Klik op een recept om het te openen.
+graph-root = Beginnen bij
+graph-root-placeholder = Hele collectie
+graph-depth = Diepte
+graph-depth-placeholder = Onbeperkt
+graph-show = Tonen
+graph-show-all = Alles tonen
+graph-download = Downloaden:
+graph-empty = Geen recepten of menu's om te tekenen.
+graph-legend-recipe = Recept
+graph-legend-menu = Menu
+graph-legend-missing = Ontbrekend recept
@@ -6,8 +6,12 @@ pantry-no-config = Geen voorraadkast-configuratie gevonden
 pantry-create-config = Maak een pantry.conf-bestand om uw voorraad bij te houden
 pantry-configure = Voorraadkast configureren →
 pantry-no-items-section = Geen artikelen in deze sectie
-pantry-show-out-of-stock = Alleen niet-voorradige artikelen tonen
-pantry-no-out-of-stock = Geen niet-voorradige artikelen in deze sectie
+pantry-filter-label = Tonen
+pantry-filter-all = Alles
+pantry-filter-expiring = Verloopt binnenkort
+pantry-filter-low = Bijna op
+pantry-filter-out = Niet op voorraad
+pantry-filter-none = Geen artikelen in deze sectie voldoen aan het filter
 pantry-total-sections = Totaal aantal secties:
 pantry-edit-config = Configuratie bewerken
 
@@ -44,3 +48,30 @@ pantry-optional = (optioneel)
 pantry-failed-add = Artikel kon niet worden toegevoegd
 pantry-failed-update = Artikel kon niet worden bijgewerkt
 pantry-failed-remove = Artikel kon niet worden verwijderd
+pantry-undo = Laatste wijziging ongedaan maken
+pantry-nothing-to-undo = Niets om ongedaan te maken
+pantry-failed-undo = Laatste wijziging kon niet ongedaan worden gemaakt
+
+# Dashboard
+pantry-view-label = Weergave
+pantry-view-sections = Secties
+pantry-view-calendar = Kalender
+pantry-drag-hint = Sleep een artikel naar een andere sectie om het daarheen te verplaatsen; vink er meerdere aan om ze samen te bewerken.
+pantry-calendar-previous = Vorige maand
+pantry-calendar-next = Volgende maand
+pantry-calendar-undated = Artikelen zonder houdbaarheidsdatum: %s
+pantry-bulk-select = Selecteren
+pantry-edit-quantity = Klik om de hoeveelheid te wijzigen
+pantry-bulk-selected = %s geselecteerd
+pantry-bulk-move = Verplaatsen naar…
+pantry-bulk-set-expiry = Houdbaarheid instellen
+pantry-bulk-remove = Verwijderen
+pantry-bulk-clear = Selectie wissen
+pantry-bulk-confirm-remove = %s artikelen verwijderen?
+pantry-failed-batch = Wijzigingen konden niet worden toegepast
+
+# Use It Up
+pantry-use-up = Opmaken
+pantry-use-up-hint = Recepten die opmaken wat binnenkort verloopt, de beste eerst
+pantry-use-up-uses = Gebruikt:
+pantry-use-up-missing = Ontbreekt:
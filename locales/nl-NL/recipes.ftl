@@ -21,6 +21,11 @@ recipe-scale-label = Schaal
 recipe-servings-label = porties
 recipe-print = Recept afdrukken
 recipe-added = Toegevoegd!
+recipe-cooked-it = Gekookt
+recipe-taken-from-pantry = Uit uw voorraadkast gehaald
+recipe-used-up = Op
+recipe-running-low = Raakt op
+recipe-pantry-failed = Voorraadkast kon niet worden bijgewerkt
 recipe-main-section = Hoofdgedeelte
 recipe-preparation = voorbereiding
 
@@ -19,6 +19,10 @@ shopping-mark-done = Markeer als gedaan
 shopping-mark-undone = Markeer als niet gedaan
 shopping-selected-recipes = Geselecteerde recepten
 shopping-clear-all = Alles wissen
+shopping-undo = Ongedaan maken
+shopping-mark-bought = Markeren als gekocht
+shopping-moved-to-pantry = Afgevinkte artikelen naar uw voorraadkast verplaatst
+shopping-nothing-checked = Er is nog niets afgevinkt
 shopping-in-pantry = In uw voorraadkast
 shopping-remove = Verwijderen
 shopping-add-recipes-first = Voeg eerst enkele recepten toe
@@ -32,3 +36,12 @@ shopping-copy-failed = Kopiëren mislukt
 shopping-copy-options = Kopieeropties
 shopping-copy-include-aisles = Schapnamen opnemen
 shopping-copy-include-amounts = Hoeveelheden opnemen
+shopping-list-label = Lijst
+shopping-new-list = Nieuwe lijst
+shopping-new-list-prompt = Naam van de nieuwe lijst (kleine letters, cijfers, - en _)
+shopping-invalid-list-name = Een lijstnaam mag alleen kleine letters, cijfers, '-' en '_' bevatten.
+shopping-move-to = Verplaatsen naar…
+shopping-store-label = Winkel
+shopping-store-any = Elke winkel
+shopping-partly-in-pantry = al in uw voorraadkast
+shopping-needed-by = Nodig voor
@@ -0,0 +1,14 @@
+# Reference graph
+graph-title = Receptgraf
+graph-description = Vilka recept och menyer som använder vilka. Klicka på ett recept för att öppna det.
+graph-root = Börja från
+graph-root-placeholder = Hela samlingen
+graph-depth = Djup
+graph-depth-placeholder = Obegränsat
+graph-show = Visa
+graph-show-all = Visa allt
+graph-download = Ladda ner:
+graph-empty = Inga recept eller menyer att rita.
+graph-legend-recipe = Recept
+graph-legend-menu = Meny
+graph-legend-missing = Saknat recept
@@ -6,8 +6,12 @@ pantry-no-config = Ingen skaffer configuration hittades
 pantry-create-config = Skapa en pantry.conf fil för att spåra din Invetering
 pantry-configure = Konfigurera skafferi →
 pantry-no-items-section = Inga produkter i denna sektion
-pantry-show-out-of-stock = Visa bara utgångna
-pantry-no-out-of-stock = Inga utgångna produkter i denna sektion
+pantry-filter-label = Visa
+pantry-filter-all = Alla
+pantry-filter-expiring = Går snart ut
+pantry-filter-low = Lite kvar
+pantry-filter-out = Slut
+pantry-filter-none = Inga produkter i denna sektion matchar filtret
 pantry-total-sections = Totala sektioner:
 pantry-edit-config = Redigera konfiguration
 
@@ -44,3 +48,30 @@ pantry-optional = (valfritt)
 pantry-failed-add = Kunde inte lägga till produkt
 pantry-failed-update = Kunde inte uppdatera produkt
 pantry-failed-remove = Kunde inte radera produkt
+pantry-undo = Ångra senaste ändringen
+pantry-nothing-to-undo = Inget att ångra
+pantry-failed-undo = Kunde inte ångra den senaste ändringen
+
+# Dashboard
+pantry-view-label = Vy
+pantry-view-sections = Sektioner
+pantry-view-calendar = Kalender
+pantry-drag-hint = Dra en produkt till en annan sektion för att flytta den dit; markera flera för att redigera dem tillsammans.
+pantry-calendar-previous = Föregående månad
+pantry-calendar-next = Nästa månad
+pantry-calendar-undated = Produkter utan bäst före-datum: %s
+pantry-bulk-select = Välj
+pantry-edit-quantity = Klicka för att ändra mängden
+pantry-bulk-selected = %s valda
+pantry-bulk-move = Flytta till…
+pantry-bulk-set-expiry = Ange bäst före
+pantry-bulk-remove = Radera
+pantry-bulk-clear = Rensa markering
+pantry-bulk-confirm-remove = Radera %s produkter?
+pantry-failed-batch = Kunde inte tillämpa ändringarna
+
+# Use It Up
+pantry-use-up = Använd upp
+pantry-use-up-hint = Recept som använder det som snart går ut, de bästa först
+pantry-use-up-uses = Använder:
+pantry-use-up-missing = Saknas:
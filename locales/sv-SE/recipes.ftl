@@ -21,6 +21,11 @@ recipe-scale-label = Skala
 recipe-servings-label = portioner
 recipe-print = Skruv ut recept
 recipe-added = Tillagd!
+recipe-cooked-it = Lagat
+recipe-taken-from-pantry = Taget ur ditt skafferi
+recipe-used-up = Slut
+recipe-running-low = Börjar ta slut
+recipe-pantry-failed = Kunde inte uppdatera skafferiet
 recipe-main-section = Huvud
 recipe-preparation = förberedelse
 
@@ -19,6 +19,10 @@ shopping-mark-done = Markera som klar
 shopping-mark-undone = Markera som ej klar
 shopping-selected-recipes = Valda recept
 shopping-clear-all = Rensa alla
+shopping-undo = Ångra
+shopping-mark-bought = Markera som köpt
+shopping-moved-to-pantry = Avbockade varor flyttade till ditt skafferi
+shopping-nothing-checked = Inget är avbockat än
 shopping-in-pantry = I ditt skafferi
 shopping-remove = Radera
 shopping-add-recipes-first = Vänligen lägg till recept först
@@ -32,3 +36,12 @@ shopping-copy-failed = Kopiering misslyckades
 shopping-copy-options = Kopieringsalternativ
 shopping-copy-include-aisles = Inkludera hyllnamn
 shopping-copy-include-amounts = Inkludera mängder
+shopping-list-label = Lista
+shopping-new-list = Ny lista
+shopping-new-list-prompt = Namn på den nya listan (gemener, siffror, - och _)
+shopping-invalid-list-name = Ett listnamn får bara innehålla gemener, siffror, '-' och '_'.
+shopping-move-to = Flytta till…
+shopping-store-label = Butik
+shopping-store-any = Valfri butik
+shopping-partly-in-pantry = redan i ditt skafferi
+shopping-needed-by = Behövs till
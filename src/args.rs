@@ -38,7 +38,7 @@ use crate::lsp;
 use crate::server;
 #[cfg(feature = "self-update")]
 use crate::update;
-use crate::{build, doctor, pantry, recipe, report, search, seed, shopping_list};
+use crate::{build, doctor, graph, pantry, recipe, report, search, seed, shopping_list};
 
 #[derive(Parser, Debug)]
 #[command(
@@ -202,6 +202,22 @@ pub enum Command {
     )]
     Doctor(doctor::DoctorArgs),
 
+    /// Draw which recipes and menus reference which
+    ///
+    /// Writes the reference graph of your collection — recipes, the
+    /// sub-recipes they use with @./other{}, and menus — for Graphviz,
+    /// Mermaid or any tool that reads JSON. References that lead nowhere
+    /// are drawn too, dashed, so a broken one stands out.
+    ///
+    /// Examples:
+    ///   cook graph | dot -Tsvg > graph.svg   # Whole collection as SVG
+    ///   cook graph Week.menu                 # What one menu pulls in
+    ///   cook graph Lasagna -d 1              # Only its direct sub-recipes
+    ///   cook graph -o graph.mmd              # Mermaid, from the extension
+    ///   cook graph -f json                   # Nodes and references as JSON
+    #[command(long_about = "Export the recipe reference graph as Graphviz DOT, Mermaid or JSON")]
+    Graph(graph::GraphArgs),
+
     /// Manage and analyze your pantry inventory
     ///
     /// Add, remove, and update pantry items, check for expiring or depleted
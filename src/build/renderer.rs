@@ -98,6 +98,7 @@ pub fn render_recipe(
         static_mode: true,
         repo_url: repo_url.map(String::from),
         features: FeatureFlags::default(),
+        pantry_configured: false,
     })?;
 
     match kind {
@@ -1,11 +1,17 @@
 use anyhow::Result;
 use camino::{Utf8Path, Utf8PathBuf};
-use clap::{Args, Subcommand};
+use clap::{Args, Subcommand, ValueEnum};
 use cookcli_core::{
-    doctor::{aisle_coverage, broken_references, pantry_coverage, CoverageRequest},
-    Diagnostic, Severity,
+    doctor::{
+        aisle_coverage, broken_references,
+        graph::{check as check_graph, GraphCheck, GraphRequest},
+        names::{ingredient_names, NameCluster, NamesRequest},
+        pantry_coverage, CoverageRequest, ValidationReport,
+    },
+    format::doctor as fmt,
+    Diagnostic, Location, Severity,
 };
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use tracing::warn;
 
 use crate::{util::cli_error, Context};
@@ -57,7 +63,48 @@ enum DoctorCommand {
     ///   cook doctor validate           # Validate current directory
     ///   cook doctor validate -b ~/recipes # Validate specific directory
     ///   cook doctor validate --strict  # Exit with error code if issues found
+    ///   cook doctor validate --format github --strict  # Annotate a pull request
+    ///   cook doctor validate --format sarif > doctor.sarif
     Validate(ValidateArgs),
+
+    /// Find ingredients spelled more than one way across recipes
+    ///
+    /// Groups ingredient names that look like the same ingredient: names
+    /// differing only in case ("Salt", "salt"), plurals ("egg", "eggs"),
+    /// small misspellings ("courgette", "corgette"), extra words
+    /// ("parmesan", "parmesan cheese") and synonyms aisle.conf already
+    /// lists. Each spelling is a separate shopping list line and a separate
+    /// pantry item, so settling on one name fixes both.
+    ///
+    /// For each group it shows how many recipes use each spelling and
+    /// where, and suggests a name to settle on — plus, when there is an
+    /// aisle.conf, the synonyms to add to it.
+    ///
+    /// Example:
+    ///   cook doctor ingredients              # Check current directory
+    ///   cook doctor ingredients -b ~/recipes # Check specific directory
+    ///   cook doctor ingredients --max-distance 1
+    ///   cook doctor ingredients --format json
+    Ingredients(IngredientsArgs),
+
+    /// Check how recipes, menus and images fit together
+    ///
+    /// Looks at the collection as a whole rather than each recipe alone,
+    /// and reports:
+    /// - Recipes whose references lead back to themselves, which a
+    ///   shopping list cannot expand
+    /// - Menus referencing recipes that do not exist
+    /// - Images no recipe or step shows, named as the web UI looks them
+    ///   up: Recipe.jpg, Recipe.3.jpg (step 3), Recipe.2.1.jpg (section 2,
+    ///   step 1)
+    /// - Recipes no menu lists and no other recipe references, when the
+    ///   collection has menus
+    ///
+    /// Example:
+    ///   cook doctor graph              # Check current directory
+    ///   cook doctor graph -b ~/recipes # Check specific directory
+    ///   cook doctor graph --strict     # Exit with error code on errors or warnings
+    Graph(GraphArgs),
 }
 
 #[derive(Debug, Args)]
@@ -98,6 +145,82 @@ struct ValidateArgs {
     /// Use this flag in CI/CD pipelines to fail on validation errors.
     #[arg(long)]
     strict: bool,
+
+    /// Output format
+    ///
+    /// Every format reports the same problems, broken recipe references
+    /// included, and `--strict` fails on them the same way. File paths in
+    /// the machine-readable formats are relative to the current directory,
+    /// so that a CI system running from the repository root can place each
+    /// annotation on the right file and line.
+    #[arg(short, long, value_enum, default_value = "human")]
+    format: ValidateFormat,
+}
+
+#[derive(Debug, Args)]
+struct IngredientsArgs {
+    /// Directory to scan for recipe files
+    ///
+    /// The command will recursively search this directory for .cook files
+    /// and compare the ingredient names they use.
+    /// Defaults to the current directory.
+    #[arg(short, long, value_hint = clap::ValueHint::DirPath)]
+    base_path: Option<Utf8PathBuf>,
+
+    /// Most character edits to treat as a misspelling
+    ///
+    /// Short names are allowed fewer regardless — one edit per four
+    /// characters — so "rice" and "ice" are never grouped. Use 0 to turn
+    /// misspelling detection off. Defaults to 2.
+    #[arg(long)]
+    max_distance: Option<usize>,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value = "human")]
+    format: ReportFormat,
+}
+
+#[derive(Debug, Args)]
+struct GraphArgs {
+    /// Directory to scan for recipe files
+    ///
+    /// The command will recursively search this directory for .cook and
+    /// .menu files and the images beside them.
+    /// Defaults to the current directory.
+    #[arg(short, long, value_hint = clap::ValueHint::DirPath)]
+    base_path: Option<Utf8PathBuf>,
+
+    /// Exit with error code if any errors or warnings are found
+    ///
+    /// Unreachable recipes are hints, and do not fail the check.
+    #[arg(long)]
+    strict: bool,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value = "human")]
+    format: ReportFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ReportFormat {
+    /// Human-readable report (default)
+    Human,
+    /// The full report as JSON
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ValidateFormat {
+    /// Human-readable report with source excerpts (default)
+    Human,
+    /// The full report as JSON, with summary counts
+    Json,
+    /// SARIF 2.1.0, for GitHub code scanning and other review tools
+    Sarif,
+    /// JUnit XML, one test case per recipe
+    Junit,
+    /// GitHub Actions workflow commands, one annotation per problem
+    Github,
 }
 
 pub fn run(ctx: &Context, args: DoctorArgs) -> Result<()> {
@@ -105,6 +228,10 @@ pub fn run(ctx: &Context, args: DoctorArgs) -> Result<()> {
         Some(DoctorCommand::Aisle(aisle_args)) => run_aisle(ctx, aisle_args),
         Some(DoctorCommand::Pantry(pantry_args)) => run_pantry(ctx, pantry_args),
         Some(DoctorCommand::Validate(validate_args)) => run_validate(ctx, validate_args),
+        Some(DoctorCommand::Ingredients(ingredients_args)) => {
+            run_ingredients(ctx, ingredients_args)
+        }
+        Some(DoctorCommand::Graph(graph_args)) => run_graph(ctx, graph_args),
         None => {
             // Run all doctor checks
             println!("Running all doctor checks...\n");
@@ -128,6 +255,17 @@ pub fn run(ctx: &Context, args: DoctorArgs) -> Result<()> {
                 ValidateArgs {
                     base_path: None,
                     strict: false,
+                    format: ValidateFormat::Human,
+                },
+            ));
+
+            println!("\n=== Reference Graph ===");
+            report_check(run_graph(
+                ctx,
+                GraphArgs {
+                    base_path: None,
+                    strict: false,
+                    format: ReportFormat::Human,
                 },
             ));
 
@@ -137,6 +275,16 @@ pub fn run(ctx: &Context, args: DoctorArgs) -> Result<()> {
             println!("\n=== Pantry Check ===");
             report_check(run_pantry(ctx, PantryArgs { base_path: None }));
 
+            println!("\n=== Ingredient Names ===");
+            report_check(run_ingredients(
+                ctx,
+                IngredientsArgs {
+                    base_path: None,
+                    max_distance: None,
+                    format: ReportFormat::Human,
+                },
+            ));
+
             Ok(())
         }
     }
@@ -217,7 +365,11 @@ fn run_pantry(ctx: &Context, args: PantryArgs) -> Result<()> {
         coverage.total_ingredients()
     );
 
-    let in_pantry: Vec<&str> = coverage.known().collect();
+    let in_pantry: Vec<_> = coverage
+        .ingredients
+        .iter()
+        .filter(|ingredient| ingredient.known)
+        .collect();
     if in_pantry.is_empty() {
         println!("\n✓ No recipe ingredients are currently in your pantry");
     } else {
@@ -226,7 +378,14 @@ fn run_pantry(ctx: &Context, args: PantryArgs) -> Result<()> {
             in_pantry.len()
         );
         for ingredient in in_pantry {
-            println!("  ✓ {ingredient}");
+            match &ingredient.substituted {
+                Some(substitution) => println!(
+                    "  ✓ {} (substituted with {})",
+                    ingredient.name,
+                    substitution.with_joined()
+                ),
+                None => println!("  ✓ {}", ingredient.name),
+            }
         }
         println!("\nThese ingredients will be excluded from shopping lists.");
     }
@@ -283,32 +442,236 @@ fn run_aisle(ctx: &Context, args: AisleArgs) -> Result<()> {
     Ok(())
 }
 
+fn run_ingredients(ctx: &Context, args: IngredientsArgs) -> Result<()> {
+    let outcome = ingredient_names(
+        ctx,
+        NamesRequest {
+            base_dir: args.base_path,
+            max_distance: args.max_distance,
+        },
+    )
+    .map_err(cli_error)?;
+    log_diagnostics(&outcome.diagnostics);
+    let report = outcome.value;
+
+    if args.format == ReportFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    println!(
+        "Scanned {} recipes, found {} unique ingredient names",
+        report.total_recipes, report.total_names
+    );
+
+    if report.clusters.is_empty() {
+        println!("✓ No ingredient is spelled more than one way");
+        return Ok(());
+    }
+
+    println!(
+        "\n{} ingredients are spelled more than one way:",
+        report.clusters.len()
+    );
+    for cluster in &report.clusters {
+        print_cluster(cluster);
+    }
+
+    if ctx.aisle().is_unset() {
+        println!("\nRename each group to one name in your recipes, or list the");
+        println!("spellings as synonyms on one line of an aisle.conf file.");
+    }
+
+    Ok(())
+}
+
+fn print_cluster(cluster: &NameCluster) {
+    let reasons: Vec<&str> = cluster.reasons.iter().map(|r| r.describe()).collect();
+    println!("\n  {} ({})", cluster.canonical, reasons.join(", "));
+
+    let width = cluster
+        .names
+        .iter()
+        .map(|name| name.name.chars().count())
+        .max()
+        .unwrap_or(0);
+    for name in &cluster.names {
+        let places: Vec<String> = name.locations.iter().map(place).collect();
+        let uses = if name.count() == 1 {
+            "recipe"
+        } else {
+            "recipes"
+        };
+        println!(
+            "    {:<width$}  {} {uses}: {}",
+            name.name,
+            name.count(),
+            places.join(", ")
+        );
+    }
+
+    if cluster.missing_synonyms.is_empty() {
+        println!("    → use \"{}\" in every recipe", cluster.canonical);
+    } else {
+        println!(
+            "    → add to aisle.conf: {}|{}",
+            cluster.canonical,
+            cluster.missing_synonyms.join("|")
+        );
+    }
+}
+
+fn run_graph(ctx: &Context, args: GraphArgs) -> Result<()> {
+    let outcome = check_graph(
+        ctx,
+        GraphRequest {
+            base_dir: args.base_path,
+        },
+    )
+    .map_err(cli_error)?;
+    log_diagnostics(&outcome.diagnostics);
+    let report = outcome.value;
+
+    let errors = report.count(Severity::Error);
+    let warnings = report.count(Severity::Warning);
+
+    if args.format == ReportFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_graph_report(&report, errors, warnings);
+    }
+
+    if args.strict && (errors > 0 || warnings > 0) {
+        anyhow::bail!(
+            "Reference graph check failed: {errors} error(s), {warnings} warning(s) found"
+        );
+    }
+    Ok(())
+}
+
+fn print_graph_report(report: &GraphCheck, errors: usize, warnings: usize) {
+    println!(
+        "Checked {} recipes, {} menus and {} images",
+        report.total_recipes, report.total_menus, report.total_images
+    );
+
+    for finding in &report.findings {
+        let place = finding.location.as_ref().map(place).unwrap_or_default();
+        println!("\n📄 {place}");
+        print_coded(finding);
+    }
+
+    let hints = report.findings.len() - errors - warnings;
+    println!();
+    if report.findings.is_empty() {
+        println!("✅ Recipes, menus and images all fit together");
+    } else {
+        println!("{errors} error(s), {warnings} warning(s), {hints} hint(s)");
+    }
+}
+
+/// A location as `file:line:column`, or just the file when there is no line.
+fn place(location: &Location) -> String {
+    let file = location
+        .file
+        .as_ref()
+        .map(ToString::to_string)
+        .unwrap_or_default();
+    match location.range {
+        Some(range) => format!("{file}:{}:{}", range.start.line, range.start.column),
+        None => file,
+    }
+}
+
+/// One diagnostic that carries a [`code`](Diagnostic::code), with its hints.
+/// These are core's own findings rather than the parser's, so there is no
+/// rendered source excerpt to print in their place.
+fn print_coded(diagnostic: &Diagnostic) {
+    let icon = match diagnostic.severity {
+        Severity::Error => "❌",
+        Severity::Warning => "⚠️ ",
+        _ => "💡",
+    };
+    match &diagnostic.code {
+        Some(code) => println!("  {icon} {} [{code}]", diagnostic.message),
+        None => println!("  {icon} {}", diagnostic.message),
+    }
+    for hint in &diagnostic.hints {
+        println!("     hint: {hint}");
+    }
+}
+
 fn run_validate(ctx: &Context, args: ValidateArgs) -> Result<()> {
     let report = cookcli_core::doctor::validate(
         ctx,
         cookcli_core::doctor::ValidateRequest {
             base_dir: args.base_path,
-            // This report is going straight to a terminal.
-            style: cookcli_core::Style::Ansi,
+            // The human report is going straight to a terminal; the others are
+            // read by a machine, which has no use for escape codes.
+            style: if args.format == ValidateFormat::Human {
+                cookcli_core::Style::Ansi
+            } else {
+                cookcli_core::Style::Plain
+            },
         },
     )
     .map_err(cli_error)?
     .into_value();
+    // Every recipe's own diagnostics are printed below, in whichever format;
+    // these belong to no recipe, and would otherwise go unseen.
+    log_diagnostics(&report.config_diagnostics);
+
+    let broken = broken_references(&report);
+    let root = display_root(&report.base_dir);
+    match args.format {
+        ValidateFormat::Human => return print_human_report(&report, broken, args.strict),
+        ValidateFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&fmt::build_json_value(&report, &broken, &root))?
+        ),
+        ValidateFormat::Sarif => println!(
+            "{}",
+            serde_json::to_string_pretty(&fmt::build_sarif_value(&report, &broken, &root))?
+        ),
+        ValidateFormat::Junit => print!("{}", fmt::build_junit(&report, &broken, &root)),
+        ValidateFormat::Github => print!("{}", fmt::build_github(&report, &broken, &root)),
+    }
 
+    let total_errors = report.total_errors() + broken.values().map(Vec::len).sum::<usize>();
+    let total_warnings = report.total_warnings();
+    if args.strict && (total_errors > 0 || total_warnings > 0) {
+        anyhow::bail!(
+            "Recipe validation failed with {} errors and {} warnings",
+            total_errors,
+            total_warnings
+        );
+    }
+    Ok(())
+}
+
+fn print_human_report(
+    report: &ValidationReport,
+    broken: BTreeMap<&Utf8Path, Vec<String>>,
+    strict: bool,
+) -> Result<()> {
     for recipe in &report.recipes {
         if recipe.diagnostics.is_empty() {
             continue;
         }
         println!("\n📄 {}", recipe.path);
 
-        if recipe.rendered.is_empty() {
-            // A recipe core could not read has no source to quote, so there is
-            // nothing rendered for it. Print what it did say instead.
-            for diagnostic in &recipe.diagnostics {
+        print!("{}", recipe.rendered);
+        for diagnostic in &recipe.diagnostics {
+            if diagnostic.code.is_some() {
+                // Lint findings are not the parser's, so they are not in what
+                // it rendered.
+                print_coded(diagnostic);
+            } else if recipe.rendered.is_empty() {
+                // A recipe core could not read has no source to quote, so
+                // there is nothing rendered for it. Print what it did say
+                // instead.
                 println!("  ❌ Error: {}", diagnostic.message);
             }
-        } else {
-            print!("{}", recipe.rendered);
         }
     }
 
@@ -331,8 +694,6 @@ fn run_validate(ctx: &Context, args: ValidateArgs) -> Result<()> {
 
     if !report.references().is_empty() {
         println!("\n=== Recipe References ===");
-        let broken = broken_references(&report);
-
         if broken.is_empty() {
             println!("✓ All recipe references are valid");
         } else {
@@ -363,7 +724,7 @@ fn run_validate(ctx: &Context, args: ValidateArgs) -> Result<()> {
             println!("⚠️  {total_warnings} warning(s) found in {recipes_with_warnings} recipe(s)");
         }
 
-        if args.strict {
+        if strict {
             anyhow::bail!(
                 "Recipe validation failed with {} errors and {} warnings",
                 total_errors,
@@ -374,3 +735,23 @@ fn run_validate(ctx: &Context, args: ValidateArgs) -> Result<()> {
 
     Ok(())
 }
+
+/// The validated directory as the machine-readable formats should name it:
+/// relative to the working directory when it is under it, which is where a CI
+/// job runs from and what its annotations are resolved against.
+///
+/// Without `--base-path` the report's root is the canonical, absolute base
+/// path, so the working directory is canonicalised too before comparing them —
+/// otherwise a symlinked checkout would never match and every annotation would
+/// carry an absolute path GitHub cannot place.
+fn display_root(base_dir: &Utf8Path) -> Utf8PathBuf {
+    if base_dir.is_relative() {
+        return base_dir.to_path_buf();
+    }
+    std::env::current_dir()
+        .and_then(|cwd| cwd.canonicalize())
+        .ok()
+        .and_then(|cwd| Utf8PathBuf::from_path_buf(cwd).ok())
+        .and_then(|cwd| base_dir.strip_prefix(cwd).ok().map(Utf8Path::to_path_buf))
+        .unwrap_or_else(|| base_dir.to_path_buf())
+}
@@ -0,0 +1,102 @@
+use anyhow::Result;
+use camino::Utf8PathBuf;
+use clap::{Args, ValueEnum};
+use cookcli_core::{
+    format::graph as fmt,
+    graph::{reference_graph, GraphRequest},
+};
+use tracing::warn;
+
+use crate::{
+    util::{cli_error, write_to_output},
+    Context,
+};
+
+#[derive(Debug, Args)]
+pub struct GraphArgs {
+    /// Recipe or menu to start from
+    ///
+    /// Draws only what this file references, what those reference, and so
+    /// on. Named as a recipe reference is, relative to the base directory:
+    /// "Breakfast/Pancakes", "Week.menu". Draws the whole collection if
+    /// omitted.
+    root: Option<String>,
+
+    /// How many references deep to follow from the root
+    ///
+    /// 0 draws the root alone, 1 adds what it references directly.
+    /// Unlimited if omitted; has no effect without a root.
+    #[arg(short, long, requires = "root")]
+    depth: Option<usize>,
+
+    /// Output format for the graph
+    ///
+    /// Available formats: dot (default), mermaid, json
+    /// If not specified, format is inferred from output file extension
+    /// (.dot or .gv, .mmd or .mermaid, .json).
+    #[arg(short, long, value_enum)]
+    format: Option<GraphFormat>,
+
+    /// Base directory of the recipe collection
+    ///
+    /// Every recipe and menu under it is part of the graph, and references
+    /// are resolved against it. Defaults to the current directory.
+    #[arg(short, long, value_hint = clap::ValueHint::DirPath)]
+    base_path: Option<Utf8PathBuf>,
+
+    /// Output file path (stdout if not specified)
+    #[arg(short, long, value_hint = clap::ValueHint::FilePath)]
+    output: Option<Utf8PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum GraphFormat {
+    /// Graphviz DOT, for `dot -Tsvg`
+    Dot,
+    /// A Mermaid flowchart, for Markdown viewers that render it
+    Mermaid,
+    /// Every node and reference, for other tools to read
+    Json,
+}
+
+pub fn run(ctx: &Context, args: GraphArgs) -> Result<()> {
+    let format =
+        args.format
+            .unwrap_or_else(|| match args.output.as_ref().and_then(|p| p.extension()) {
+                Some("json") => GraphFormat::Json,
+                Some("mmd" | "mermaid") => GraphFormat::Mermaid,
+                _ => GraphFormat::Dot,
+            });
+
+    let outcome = reference_graph(
+        ctx,
+        GraphRequest {
+            base_dir: args.base_path,
+            root: args.root,
+            depth: args.depth,
+        },
+    )
+    .map_err(cli_error)?;
+
+    // Core returns its warnings instead of logging them, so that a library
+    // consumer can show them its own way. Logging them is this boundary's job.
+    for diagnostic in &outcome.diagnostics {
+        match diagnostic.location.as_ref().and_then(|l| l.file.as_ref()) {
+            Some(file) => warn!("{file}: {}", diagnostic.message),
+            None => warn!("{}", diagnostic.message),
+        }
+    }
+    let graph = outcome.value;
+
+    write_to_output(args.output.as_deref(), |w| {
+        match format {
+            GraphFormat::Dot => write!(w, "{}", fmt::build_dot(&graph))?,
+            GraphFormat::Mermaid => write!(w, "{}", fmt::build_mermaid(&graph))?,
+            GraphFormat::Json => {
+                serde_json::to_writer_pretty(&mut *w, &fmt::build_json_value(&graph))?;
+                writeln!(w)?;
+            }
+        }
+        Ok(())
+    })
+}
@@ -3,6 +3,7 @@
 // Commands - make them available as public modules
 pub mod build;
 pub mod doctor;
+pub mod graph;
 #[cfg(feature = "import")]
 pub mod import;
 #[cfg(feature = "sync")]
@@ -42,6 +42,7 @@ pub use cookcli_core::Context;
 // commands
 mod build;
 mod doctor;
+mod graph;
 #[cfg(feature = "import")]
 mod import;
 #[cfg(feature = "sync")]
@@ -86,6 +87,7 @@ pub fn main() -> Result<()> {
         Command::Import(args) => import::run(&ctx, args),
         Command::Report(args) => report::run(&ctx, args),
         Command::Doctor(args) => doctor::run(&ctx, args),
+        Command::Graph(args) => graph::run(&ctx, args),
         Command::Pantry(args) => pantry::run(&ctx, args),
         #[cfg(feature = "lsp")]
         Command::Lsp(args) => lsp::run(&ctx, args),
@@ -123,7 +125,9 @@ fn configure_context() -> Result<Context> {
         bail!("Base path is not a directory: {}", absolute_base_path);
     }
 
-    Ok(Context::discover(absolute_base_path))
+    // Changes made from here are recorded as coming from the command line,
+    // in the pantry's journal.
+    Ok(Context::discover(absolute_base_path).with_origin("cli"))
 }
 
 fn configure_logging(verbosity: u8) {
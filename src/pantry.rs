@@ -1,10 +1,21 @@
-use anyhow::Result;
+use anyhow::{Context as _, Result};
+use camino::Utf8PathBuf;
 use clap::{Args, Subcommand, ValueEnum};
-use cookcli_core::{pantry as core, Diagnostic};
+use cookcli_core::{
+    pantry as core,
+    shopping_list::{ScaledRecipe, DEFAULT_LIST},
+    substitutes::Substitution,
+    CoreError, Diagnostic, Outcome, RecipeSource,
+};
 use serde::Serialize;
+use std::collections::BTreeMap;
 use tracing::warn;
 
-use crate::{util::cli_error, Context as AppContext};
+use crate::{
+    shopping_list::open_saved_list,
+    util::{cli_error, notify, split_recipe_name_and_scaling_factor, write_to_output},
+    Context as AppContext,
+};
 
 /// Core returns its warnings instead of logging them, so that a library
 /// consumer can show them its own way. Logging them is this boundary's job.
@@ -58,6 +69,19 @@ pub enum PantryCommand {
     #[command(alias = "r")]
     Recipes(RecipesArgs),
 
+    /// Suggest recipes that use up what is about to expire
+    ///
+    /// Recipes are ranked by how much of what is expiring they would use,
+    /// counting items that expire sooner for more, and by how few of their
+    /// ingredients are missing from the pantry.
+    ///
+    /// Examples:
+    ///   cook pantry use-up                   # Items expiring within a week
+    ///   cook pantry use-up --days 3          # Only the next three days
+    ///   cook pantry use-up -n 5              # The five best recipes
+    #[command(alias = "u")]
+    UseUp(UseUpArgs),
+
     /// Analyze ingredient usage across recipes to help plan pantry items
     #[command(alias = "pl")]
     Plan(PlanArgs),
@@ -107,6 +131,126 @@ pub enum PantryCommand {
     ///   cook pantry update dairy milk --expire 2025-06-15 --low "500%ml"
     #[command(alias = "up")]
     Update(UpdateArgs),
+
+    /// Take an amount off an item's quantity
+    ///
+    /// The amount may be in any unit that converts to the one the item is
+    /// kept in; the new quantity is written in the item's own unit. Taking
+    /// more than there is empties the item.
+    ///
+    /// Examples:
+    ///   cook pantry use flour 200%g              # 1%kg becomes 0.8%kg
+    ///   cook pantry use eggs 2
+    ///   cook pantry use milk 250%ml --section fridge
+    Use(AdjustArgs),
+
+    /// Add an amount to an item's quantity
+    ///
+    /// As `use`, the other way: the amount may be in any unit that converts
+    /// to the item's. An item kept with no quantity is given the amount.
+    ///
+    /// Examples:
+    ///   cook pantry top-up milk 1%l
+    ///   cook pantry top-up rice 500%g --dry-run
+    #[command(alias = "tu")]
+    TopUp(AdjustArgs),
+
+    /// Take what a cooked recipe used out of the pantry
+    ///
+    /// Each ingredient, scaled as asked, is subtracted from the pantry items
+    /// of the same name, converting between units of mass or volume where
+    /// they differ. Ingredients of referenced recipes are included. Items
+    /// that are now used up or running low are reported.
+    ///
+    /// Examples:
+    ///   cook pantry consume "Easy Pancakes"
+    ///   cook pantry consume "Easy Pancakes:2"     # Cooked a double batch
+    ///   cook pantry consume Pasta --dry-run       # Show what would change
+    #[command(alias = "c")]
+    Consume(ConsumeArgs),
+
+    /// Show what has changed in the pantry, when, and from where
+    ///
+    /// Every change made through cook — from the command line, the web
+    /// interface, or by cooking a recipe — is kept in a journal beside the
+    /// pantry file, with each item as it was before and after.
+    ///
+    /// Examples:
+    ///   cook pantry history                  # Every change, oldest first
+    ///   cook pantry history -n 5             # The last five
+    #[command(alias = "log")]
+    History(HistoryArgs),
+
+    /// Put what is running out on the shopping list
+    ///
+    /// Every item `cook pantry depleted` reports is added to the saved
+    /// shopping list by hand, unless the list already has something of that
+    /// name. An item with a `restock` attribute asks for what it takes to
+    /// reach that amount; one without goes on with no amount.
+    ///
+    /// Examples:
+    ///   cook pantry restock                       # Add to the shopping list
+    ///   cook pantry restock --list market         # Add to a named list
+    ///   cook pantry restock --dry-run             # Show what would be added
+    #[command(alias = "rs")]
+    Restock(RestockArgs),
+
+    /// Tell a command or webhook what is expiring or running out
+    ///
+    /// Runs the command and posts to the webhook that notify.toml names,
+    /// with what has come within the expiry window or is running out since
+    /// the last time, as JSON. Each item is sent once: what was sent is kept
+    /// beside the pantry file, and an item is sent again only once it has
+    /// been dealt with and comes back. Nothing is run when there is nothing
+    /// new.
+    ///
+    /// Run it from cron, or let `cook server --notify-every` run it.
+    ///
+    /// Examples:
+    ///   cook pantry notify                        # Send what is new
+    ///   cook pantry notify --dry-run              # Show what would be sent
+    ///   cook pantry notify --command 'notify-send Pantry "$COOK_NOTIFY_MESSAGE"'
+    Notify(NotifyArgs),
+
+    /// Write the pantry out as a spreadsheet
+    ///
+    /// One row per item, with the columns name, section, quantity, bought,
+    /// expire, low and restock. An attribute an item does not have is left empty.
+    ///
+    /// Examples:
+    ///   cook pantry export                        # CSV to the terminal
+    ///   cook pantry export -o stock.csv           # CSV to a file
+    ///   cook pantry export --format json          # JSON instead
+    Export(ExportArgs),
+
+    /// Read a CSV or JSON file of items into the pantry
+    ///
+    /// The file has the columns `cook pantry export` writes, in any order;
+    /// only name is required, and an item with no section goes in general.
+    /// Other columns are ignored. Rows that cannot be written to the pantry
+    /// are listed and left out, and the rest are imported.
+    ///
+    /// By default the file is merged in: new items are added, and items the
+    /// pantry already has get the attributes the file gives. With --replace
+    /// the pantry becomes exactly what the file says. Either way the import
+    /// is a single change that `cook pantry undo` takes back.
+    ///
+    /// Examples:
+    ///   cook pantry import stock.csv              # Merge into the pantry
+    ///   cook pantry import stock.json --replace   # Replace it
+    ///   cook pantry import stock.csv --dry-run    # Show what would change
+    Import(ImportArgs),
+
+    /// Take back the last changes made to the pantry
+    ///
+    /// Each item is put back as it was before the change. An item that has
+    /// been changed again since, by hand or otherwise, is not overwritten:
+    /// nothing is undone and the item is named instead.
+    ///
+    /// Examples:
+    ///   cook pantry undo                     # Take back the last change
+    ///   cook pantry undo 3                   # Take back the last three
+    Undo(UndoArgs),
 }
 
 #[derive(Debug, Args)]
@@ -138,6 +282,17 @@ pub struct RecipesArgs {
     pub threshold: u8,
 }
 
+#[derive(Debug, Args)]
+pub struct UseUpArgs {
+    /// Count items expiring within this many days (default: 7)
+    #[arg(short = 'd', long, default_value = "7")]
+    pub days: u32,
+
+    /// Show only the N best recipes
+    #[arg(short = 'n', long, value_name = "N")]
+    pub limit: Option<usize>,
+}
+
 #[derive(Debug, Args)]
 pub struct PlanArgs {
     /// Maximum number of ingredients to show (default: show all needed for 100% coverage)
@@ -151,6 +306,51 @@ pub struct PlanArgs {
     /// Allow recipes to be considered cookable even if N ingredients are missing
     #[arg(short = 'm', long, default_value = "0")]
     pub allow_missing: usize,
+
+    /// Plan what to buy for these recipes only, starting from the pantry
+    ///
+    /// A recipe's title or path, `tag:NAME` for every recipe with that tag,
+    /// or `folder:PATH` for every recipe under that directory. Repeat to
+    /// plan for several.
+    #[arg(
+        short = 't',
+        long = "target",
+        value_name = "TARGET",
+        value_parser = parse_target,
+        conflicts_with_all = ["max_ingredients", "skip", "allow_missing"]
+    )]
+    pub targets: Vec<core::PlanTarget>,
+
+    /// With --target, buy at most this many ingredients, or spend at most
+    /// this much with --by-cost
+    #[arg(short = 'b', long, requires = "targets")]
+    pub budget: Option<f64>,
+
+    /// Read --budget as money, pricing one package of each ingredient from
+    /// the datastore's shopping.yml
+    #[arg(long, requires = "budget")]
+    pub by_cost: bool,
+
+    /// Ingredient datastore to price ingredients from. Defaults to `db` in
+    /// the base directory, if there is one
+    #[arg(long, value_name = "DIR", value_hint = clap::ValueHint::DirPath, requires = "targets")]
+    pub datastore: Option<Utf8PathBuf>,
+}
+
+/// `tag:NAME`, `folder:PATH`, or anything else as a recipe's title or path.
+fn parse_target(value: &str) -> Result<core::PlanTarget, String> {
+    let (kind, rest) = match value.split_once(':') {
+        Some((kind @ ("tag" | "folder"), rest)) => (kind, rest.trim()),
+        _ => ("recipe", value.trim()),
+    };
+    if rest.is_empty() {
+        return Err(format!("'{value}' names no {kind}"));
+    }
+    Ok(match kind {
+        "tag" => core::PlanTarget::Tag(rest.to_string()),
+        "folder" => core::PlanTarget::Folder(rest.into()),
+        _ => core::PlanTarget::Recipe(rest.to_string()),
+    })
 }
 
 #[derive(Debug, Args)]
@@ -183,6 +383,33 @@ pub struct AddArgs {
     /// Quantity considered "low" (e.g. "200%g")
     #[arg(long)]
     pub low: Option<String>,
+
+    /// Ingredient datastore to fill in the expiry date from
+    ///
+    /// When --expire is not given, `<DIR>/<name>/meta.yml` is read for how
+    /// many days the item keeps: `freezer life` for a section with "freez"
+    /// in its name, `fridge life` for one with "fridge", "refrig" or "chill",
+    /// and `shelf life` otherwise. The days are counted from --bought, or
+    /// from today. Defaults to `db` in the base directory, if there is one.
+    #[arg(long, value_name = "DIR", value_hint = clap::ValueHint::DirPath)]
+    pub datastore: Option<Utf8PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct AdjustArgs {
+    /// Name of the ingredient, matched ignoring case
+    pub name: String,
+
+    /// How much (e.g. "200%g", "1%l", "2")
+    pub amount: String,
+
+    /// Section containing the item, when the name is in more than one
+    #[arg(long)]
+    pub section: Option<String>,
+
+    /// Show the new quantity without changing the pantry
+    #[arg(long)]
+    pub dry_run: bool,
 }
 
 #[derive(Debug, Args)]
@@ -219,6 +446,115 @@ pub struct UpdateArgs {
     pub low: Option<String>,
 }
 
+#[derive(Debug, Args)]
+pub struct ConsumeArgs {
+    /// The recipe that was cooked, with an optional scaling factor using the
+    /// :N syntax
+    pub recipe: String,
+
+    /// Show what would be taken out without changing the pantry
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct HistoryArgs {
+    /// Show only the last N changes
+    #[arg(short = 'n', long, value_name = "N")]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Args)]
+pub struct RestockArgs {
+    /// The saved shopping list to add to
+    #[arg(short, long, default_value = DEFAULT_LIST, value_name = "NAME")]
+    pub list: String,
+
+    /// Show what would be added without changing the list
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct NotifyArgs {
+    /// Days ahead an item counts as expiring, instead of notify.toml's
+    #[arg(short, long)]
+    pub days: Option<u32>,
+
+    /// Command to run, instead of notify.toml's
+    #[arg(long, value_name = "COMMAND")]
+    pub command: Option<String>,
+
+    /// URL to post to, instead of notify.toml's
+    #[arg(long, value_name = "URL")]
+    pub webhook: Option<String>,
+
+    /// Show what would be sent without sending it or recording it as sent
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum TransferFormat {
+    /// Comma-separated values with a header row
+    Csv,
+    /// A JSON list of items
+    Json,
+}
+
+impl From<TransferFormat> for core::TransferFormat {
+    fn from(format: TransferFormat) -> Self {
+        match format {
+            TransferFormat::Csv => core::TransferFormat::Csv,
+            TransferFormat::Json => core::TransferFormat::Json,
+        }
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct ExportArgs {
+    /// The format to write
+    #[arg(long, value_enum, default_value = "csv")]
+    pub format: TransferFormat,
+
+    /// Write to this file instead of the terminal
+    #[arg(short, long, value_name = "FILE")]
+    pub output: Option<Utf8PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct ImportArgs {
+    /// The CSV or JSON file to read
+    pub file: Utf8PathBuf,
+
+    /// Add and update the items in the file, keeping the rest (default)
+    #[arg(long, conflicts_with = "replace")]
+    pub merge: bool,
+
+    /// Make the pantry exactly what the file says, removing the items it
+    /// does not mention
+    #[arg(long)]
+    pub replace: bool,
+
+    /// The file's format, if its extension does not say
+    #[arg(long, value_enum)]
+    pub format: Option<TransferFormat>,
+
+    /// Show what would change without changing the pantry
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct UndoArgs {
+    /// How many changes to take back
+    #[arg(
+        default_value = "1",
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    pub count: usize,
+}
+
 // Output structures for JSON/YAML formats
 #[derive(Debug, Serialize)]
 struct ListSection {
@@ -272,6 +608,9 @@ struct ExpiringItem {
 struct RecipesOutput {
     full_matches: Vec<String>,
     partial_matches: Vec<PartialMatch>,
+    /// By recipe, for the recipes listed; absent when none needed any.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    substitutions: BTreeMap<String, Vec<Substitution>>,
 }
 
 #[derive(Debug, Serialize)]
@@ -301,7 +640,7 @@ pub fn run(ctx: &AppContext, args: PantryArgs) -> Result<()> {
     let new_ctx;
     let ctx = if let Some(base_path) = args.base_path {
         let absolute_base_path = crate::util::resolve_to_absolute_path(&base_path)?;
-        new_ctx = AppContext::discover(absolute_base_path);
+        new_ctx = AppContext::discover(absolute_base_path).with_origin("cli");
         &new_ctx
     } else {
         ctx
@@ -313,11 +652,21 @@ pub fn run(ctx: &AppContext, args: PantryArgs) -> Result<()> {
         PantryCommand::Depleted(depleted_args) => run_depleted(ctx, depleted_args, format),
         PantryCommand::Expiring(expiring_args) => run_expiring(ctx, expiring_args, format),
         PantryCommand::Recipes(recipes_args) => run_recipes(ctx, recipes_args, format),
+        PantryCommand::UseUp(use_up_args) => run_use_up(ctx, use_up_args, format),
         PantryCommand::Plan(plan_args) => run_plan(ctx, plan_args, format),
         PantryCommand::List(list_args) => run_list(ctx, list_args, format),
         PantryCommand::Add(add_args) => run_add(ctx, add_args),
         PantryCommand::Remove(remove_args) => run_remove(ctx, remove_args),
         PantryCommand::Update(update_args) => run_update(ctx, update_args),
+        PantryCommand::Use(adjust_args) => run_adjust(ctx, adjust_args, core::take, format),
+        PantryCommand::TopUp(adjust_args) => run_adjust(ctx, adjust_args, core::top_up, format),
+        PantryCommand::Consume(consume_args) => run_consume(ctx, consume_args, format),
+        PantryCommand::History(history_args) => run_history(ctx, history_args, format),
+        PantryCommand::Undo(undo_args) => run_undo(ctx, undo_args, format),
+        PantryCommand::Restock(restock_args) => run_restock(ctx, restock_args, format),
+        PantryCommand::Notify(notify_args) => run_notify(ctx, notify_args, format),
+        PantryCommand::Export(export_args) => run_export(ctx, export_args),
+        PantryCommand::Import(import_args) => run_import(ctx, import_args, format),
     }
 }
 
@@ -475,6 +824,23 @@ fn run_recipes(ctx: &AppContext, args: RecipesArgs, format: OutputFormat) -> Res
     } else {
         Vec::new()
     };
+    // Only the substitutions for recipes that are going to be shown.
+    let mut substitutions = matches.substitutions;
+    substitutions.retain(|recipe, _| {
+        full_matches.contains(recipe)
+            || partial_matches_raw
+                .iter()
+                .any(|(partial, _, _)| partial == recipe)
+    });
+    let print_substitutions = |recipe: &str| {
+        for substitution in substitutions.get(recipe).into_iter().flatten() {
+            println!(
+                "    Substituting: {} with {}",
+                substitution.ingredient,
+                substitution.with_joined()
+            );
+        }
+    };
 
     match format {
         OutputFormat::Human => {
@@ -485,6 +851,7 @@ fn run_recipes(ctx: &AppContext, args: RecipesArgs, format: OutputFormat) -> Res
                 println!("\n✓ Complete Matches (all ingredients available):");
                 for recipe in &full_matches {
                     println!("  • {recipe}");
+                    print_substitutions(recipe);
                 }
             }
 
@@ -495,6 +862,7 @@ fn run_recipes(ctx: &AppContext, args: RecipesArgs, format: OutputFormat) -> Res
                 );
                 for (recipe, percentage, missing) in &partial_matches_raw {
                     println!("  • {recipe} ({percentage}% available)");
+                    print_substitutions(recipe);
                     println!("    Missing: {}", missing.join(", "));
                 }
             }
@@ -524,6 +892,7 @@ fn run_recipes(ctx: &AppContext, args: RecipesArgs, format: OutputFormat) -> Res
             let output = RecipesOutput {
                 full_matches,
                 partial_matches,
+                substitutions,
             };
             println!("{}", serde_json::to_string_pretty(&output)?);
         }
@@ -540,6 +909,7 @@ fn run_recipes(ctx: &AppContext, args: RecipesArgs, format: OutputFormat) -> Res
             let output = RecipesOutput {
                 full_matches,
                 partial_matches,
+                substitutions,
             };
             println!("{}", serde_yaml::to_string(&output)?);
         }
@@ -548,7 +918,57 @@ fn run_recipes(ctx: &AppContext, args: RecipesArgs, format: OutputFormat) -> Res
     Ok(())
 }
 
+fn run_use_up(ctx: &AppContext, args: UseUpArgs, format: OutputFormat) -> Result<()> {
+    let outcome = core::use_up(ctx, core::UseUpRequest { days: args.days }).map_err(cli_error)?;
+    log_diagnostics(&outcome.diagnostics);
+    let mut ranked = outcome.value;
+    if let Some(limit) = args.limit {
+        ranked.truncate(limit);
+    }
+
+    match format {
+        OutputFormat::Human => {
+            println!(
+                "Recipes That Use Up Items Expiring Within {} Days:",
+                args.days
+            );
+            println!("==================================================");
+
+            if ranked.is_empty() {
+                println!("\nNo recipe uses anything that is expiring.");
+            }
+            for (i, recipe) in ranked.iter().enumerate() {
+                println!("\n{:2}. {}", i + 1, recipe.name);
+                let uses: Vec<String> = recipe
+                    .uses
+                    .iter()
+                    .map(|item| {
+                        format!(
+                            "{} ({}, {:.0}% of it)",
+                            item.name,
+                            expiry_status(Some(item.days_until_expiry)),
+                            item.share * 100.0
+                        )
+                    })
+                    .collect();
+                println!("    Uses: {}", uses.join(", "));
+                if !recipe.missing.is_empty() {
+                    println!("    Missing: {}", recipe.missing.join(", "));
+                }
+            }
+        }
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&ranked)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&ranked)?),
+    }
+
+    Ok(())
+}
+
 fn run_plan(ctx: &AppContext, args: PlanArgs, format: OutputFormat) -> Result<()> {
+    if !args.targets.is_empty() {
+        return run_plan_for(ctx, args, format);
+    }
+
     let plan = core::plan(
         ctx,
         core::PlanRequest {
@@ -694,6 +1114,99 @@ fn run_plan(ctx: &AppContext, args: PlanArgs, format: OutputFormat) -> Result<()
     Ok(())
 }
 
+fn run_plan_for(ctx: &AppContext, args: PlanArgs, format: OutputFormat) -> Result<()> {
+    let budget = match args.budget {
+        None => None,
+        Some(amount) if !amount.is_finite() || amount < 0.0 => {
+            anyhow::bail!("--budget must be a number no less than 0, not {amount}")
+        }
+        Some(amount) if args.by_cost => Some(core::PlanBudget::Cost(amount)),
+        Some(count) if count.fract() == 0.0 => Some(core::PlanBudget::Ingredients(count as usize)),
+        Some(count) => anyhow::bail!(
+            "--budget counts ingredients, so {count} must be a whole number; add --by-cost to \
+             spend money instead"
+        ),
+    };
+    let datastore = args.datastore.or_else(|| {
+        let db = ctx.base_path().join(core::DATASTORE_DIR);
+        db.is_dir().then_some(db)
+    });
+    let outcome = core::plan_for(
+        ctx,
+        core::TargetPlanRequest {
+            targets: args.targets,
+            budget,
+            datastore,
+        },
+    )
+    .map_err(cli_error)?;
+    log_diagnostics(&outcome.diagnostics);
+    let plan = outcome.value;
+
+    match format {
+        OutputFormat::Human => {
+            let total = plan.cookable.len() + plan.out_of_budget.len();
+            let title = format!("Shopping Plan for {total} Target Recipes:");
+            println!("{title}\n{}", "=".repeat(title.chars().count()));
+            if total == 0 {
+                println!("\nNo recipe matches the targets.");
+                return Ok(());
+            }
+
+            println!(
+                "\nBuying {} ingredient{} makes {} of {} cookable:",
+                plan.buy.len(),
+                if plan.buy.len() == 1 { "" } else { "s" },
+                plan.cookable.len(),
+                total
+            );
+            for (i, recipe) in plan.cookable.iter().enumerate() {
+                let buy = if recipe.buy.is_empty() {
+                    "nothing more to buy".to_string()
+                } else {
+                    format!("buy {}", recipe.buy.join(", "))
+                };
+                match recipe.cost {
+                    Some(cost) if !recipe.buy.is_empty() => {
+                        println!("{:3}. {} — {buy} ({cost:.2})", i + 1, recipe.name)
+                    }
+                    _ => println!("{:3}. {} — {buy}", i + 1, recipe.name),
+                }
+            }
+
+            if let Some(cost) = plan.cost {
+                print!("\nTotal cost: {cost:.2}");
+                if !plan.unpriced.is_empty() {
+                    print!(", plus {} (not priced)", plan.unpriced.join(", "));
+                }
+                println!();
+            }
+
+            if !plan.out_of_budget.is_empty() {
+                println!("\nOut of budget:");
+                for recipe in &plan.out_of_budget {
+                    match recipe.cost {
+                        Some(cost) => println!(
+                            "  - {} — still needs {} ({cost:.2})",
+                            recipe.name,
+                            recipe.missing.join(", ")
+                        ),
+                        None => println!(
+                            "  - {} — still needs {}",
+                            recipe.name,
+                            recipe.missing.join(", ")
+                        ),
+                    }
+                }
+            }
+        }
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&plan)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&plan)?),
+    }
+
+    Ok(())
+}
+
 // ---------------------------------------------------------------------------
 // CRUD run functions
 // ---------------------------------------------------------------------------
@@ -780,6 +1293,11 @@ fn run_list(ctx: &AppContext, args: ListArgs, format: OutputFormat) -> Result<()
 }
 
 fn run_add(ctx: &AppContext, args: AddArgs) -> Result<()> {
+    let expire_given = args.expire.is_some();
+    let datastore = args.datastore.or_else(|| {
+        let db = ctx.base_path().join(core::DATASTORE_DIR);
+        db.is_dir().then_some(db)
+    });
     let outcome = core::add(
         ctx,
         core::AddRequest {
@@ -789,12 +1307,74 @@ fn run_add(ctx: &AppContext, args: AddArgs) -> Result<()> {
             bought: args.bought,
             expire: args.expire,
             low: args.low,
+            datastore,
         },
     )
     .map_err(cli_error)?;
     log_diagnostics(&outcome.diagnostics);
 
     println!("Added '{}' to section '{}'.", args.name, args.section);
+    if !expire_given {
+        let filled = outcome
+            .value
+            .items()
+            .find(|item| item.section == args.section && item.name == args.name)
+            .and_then(|item| item.expire.as_deref());
+        if let Some(expire) = filled {
+            println!("Expires {expire}, from the ingredient datastore.");
+        }
+    }
+    Ok(())
+}
+
+fn run_adjust(
+    ctx: &AppContext,
+    args: AdjustArgs,
+    adjust: fn(&AppContext, core::AdjustRequest) -> Result<Outcome<core::AdjustedItem>, CoreError>,
+    format: OutputFormat,
+) -> Result<()> {
+    let outcome = adjust(
+        ctx,
+        core::AdjustRequest {
+            name: args.name,
+            section: args.section,
+            amount: args.amount.clone(),
+            dry_run: args.dry_run,
+        },
+    )
+    .map_err(cli_error)?;
+    log_diagnostics(&outcome.diagnostics);
+    let item = outcome.value;
+
+    match format {
+        OutputFormat::Human => {
+            let status = if item.empty {
+                " — used up"
+            } else if item.low {
+                " — running low"
+            } else {
+                ""
+            };
+            println!(
+                "{} [{}]: {} → {}{status}",
+                item.name,
+                item.section,
+                item.before.as_deref().unwrap_or("-"),
+                item.after
+            );
+            if item.short {
+                println!(
+                    "\nThe pantry did not have {}; only {} was taken.",
+                    args.amount, item.amount
+                );
+            }
+            if args.dry_run {
+                println!("\nDry run: the pantry was not changed.");
+            }
+        }
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&item)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&item)?),
+    }
     Ok(())
 }
 
@@ -843,6 +1423,299 @@ fn run_update(ctx: &AppContext, args: UpdateArgs) -> Result<()> {
     Ok(())
 }
 
+fn run_consume(ctx: &AppContext, args: ConsumeArgs, format: OutputFormat) -> Result<()> {
+    let recipe = match split_recipe_name_and_scaling_factor(&args.recipe) {
+        Some((name, scale)) => ScaledRecipe::scaled(RecipeSource::Path(name.into()), scale),
+        None => ScaledRecipe::new(RecipeSource::Path(args.recipe.as_str().into())),
+    };
+    let outcome = core::consume(
+        ctx,
+        core::ConsumeRequest {
+            recipe,
+            dry_run: args.dry_run,
+        },
+    )
+    .map_err(cli_error)?;
+    log_diagnostics(&outcome.diagnostics);
+    let consumption = outcome.value;
+
+    match format {
+        OutputFormat::Human => {
+            if consumption.items.is_empty() {
+                println!("Nothing in the pantry was used.");
+            }
+            for item in &consumption.items {
+                let status = if item.empty {
+                    " — used up"
+                } else if item.low {
+                    " — running low"
+                } else {
+                    ""
+                };
+                println!(
+                    "{} [{}]: {} → {}{status}",
+                    item.name, item.section, item.before, item.after
+                );
+            }
+            if !consumption.short.is_empty() {
+                println!(
+                    "\nThe pantry did not have enough: {}",
+                    consumption.short.join(", ")
+                );
+            }
+            if !consumption.not_stocked.is_empty() {
+                println!("Not in the pantry: {}", consumption.not_stocked.join(", "));
+            }
+            if args.dry_run {
+                println!("\nDry run: the pantry was not changed.");
+            }
+        }
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&consumption)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&consumption)?),
+    }
+
+    Ok(())
+}
+
+fn run_history(ctx: &AppContext, args: HistoryArgs, format: OutputFormat) -> Result<()> {
+    let mut journal = core::history(ctx).map_err(cli_error)?.value;
+    if let Some(limit) = args.limit {
+        journal.drain(..journal.len().saturating_sub(limit));
+    }
+
+    match format {
+        OutputFormat::Human => {
+            if journal.is_empty() {
+                println!("No changes have been recorded for the pantry.");
+            }
+            for change in &journal {
+                print_change(change);
+            }
+        }
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&journal)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&journal)?),
+    }
+    Ok(())
+}
+
+fn run_undo(ctx: &AppContext, args: UndoArgs, format: OutputFormat) -> Result<()> {
+    let outcome = core::undo(ctx, core::UndoRequest { count: args.count }).map_err(cli_error)?;
+    log_diagnostics(&outcome.diagnostics);
+    let undone = outcome.value;
+
+    match format {
+        OutputFormat::Human => {
+            if undone.is_empty() {
+                println!("Nothing to undo.");
+            }
+            for change in &undone {
+                println!("Undid #{} {}:", change.id, change.operation);
+                for item in &change.items {
+                    println!("    {}", item_line(item, &item.after, &item.before));
+                }
+            }
+        }
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&undone)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&undone)?),
+    }
+    Ok(())
+}
+
+fn run_restock(ctx: &AppContext, args: RestockArgs, format: OutputFormat) -> Result<()> {
+    let store = open_saved_list(ctx, &args.list)?;
+    let outcome = core::restock(
+        ctx,
+        &store,
+        core::RestockRequest {
+            dry_run: args.dry_run,
+        },
+    )
+    .map_err(cli_error)?;
+    log_diagnostics(&outcome.diagnostics);
+    let restocked = outcome.value;
+
+    match format {
+        OutputFormat::Human => {
+            if restocked.added.is_empty() && restocked.already_listed.is_empty() {
+                println!("Nothing in the pantry is running out.");
+            } else if restocked.added.is_empty() {
+                println!("Everything running out is already on the list.");
+            }
+            for item in &restocked.added {
+                let stock = item.stock.as_deref().unwrap_or("-");
+                match &item.quantity {
+                    Some(quantity) => println!(
+                        "Added {} [{}]: {quantity} (have {stock})",
+                        item.name, item.section
+                    ),
+                    None => println!("Added {} [{}] (have {stock})", item.name, item.section),
+                }
+            }
+            if !restocked.already_listed.is_empty() {
+                let names: Vec<&str> = restocked
+                    .already_listed
+                    .iter()
+                    .map(|item| item.name.as_str())
+                    .collect();
+                println!("Already on the list: {}", names.join(", "));
+            }
+            if args.dry_run {
+                println!("\nDry run: the shopping list was not changed.");
+            }
+        }
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&restocked)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&restocked)?),
+    }
+    Ok(())
+}
+
+fn run_notify(ctx: &AppContext, args: NotifyArgs, format: OutputFormat) -> Result<()> {
+    let config = core::NotifyConfig::load(ctx.notify()).map_err(cli_error)?;
+    log_diagnostics(&config.diagnostics);
+    let config = config.value;
+    let mut req = config.request();
+    if let Some(days) = args.days {
+        req.days = days;
+    }
+    let command = args.command.or(config.command);
+    let webhook = args.webhook.or(config.webhook);
+    if command.is_none() && webhook.is_none() && !args.dry_run {
+        anyhow::bail!(
+            "Nothing to notify with: set command or webhook in notify.toml, \
+             or pass --command or --webhook"
+        );
+    }
+
+    let outcome = core::pending_notifications(ctx, req).map_err(cli_error)?;
+    log_diagnostics(&outcome.diagnostics);
+    let notifications = outcome.value;
+    let payload = notify::Payload::new(&notifications);
+
+    if !args.dry_run {
+        if !notifications.events.is_empty() {
+            tokio::runtime::Runtime::new()?.block_on(notify::deliver(
+                command.as_deref(),
+                webhook.as_deref(),
+                &payload,
+            ))?;
+        }
+        // Even with nothing new, so that what has been dealt with is
+        // forgotten and can be sent again when it comes back.
+        core::mark_notified(&notifications).map_err(cli_error)?;
+    }
+
+    match format {
+        OutputFormat::Human => {
+            if notifications.events.is_empty() {
+                println!("Nothing new to notify about.");
+            } else {
+                if args.dry_run {
+                    println!("Would notify:");
+                } else {
+                    println!("Notified:");
+                }
+                for event in &notifications.events {
+                    println!("  {}", event.describe());
+                }
+            }
+        }
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&payload)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&payload)?),
+    }
+    Ok(())
+}
+
+fn run_export(ctx: &AppContext, args: ExportArgs) -> Result<()> {
+    let outcome = core::export(
+        ctx,
+        core::ExportRequest {
+            format: args.format.into(),
+        },
+    )
+    .map_err(cli_error)?;
+    log_diagnostics(&outcome.diagnostics);
+
+    write_to_output(args.output.as_deref(), |w| {
+        w.write_all(outcome.value.as_bytes())?;
+        Ok(())
+    })
+}
+
+fn run_import(ctx: &AppContext, args: ImportArgs, format: OutputFormat) -> Result<()> {
+    let text = std::fs::read_to_string(&args.file)
+        .with_context(|| format!("Failed to read {}", args.file))?;
+    let outcome = core::import(
+        ctx,
+        core::ImportRequest {
+            text,
+            path: Some(args.file),
+            format: args.format.map(Into::into),
+            mode: if args.replace {
+                core::ImportMode::Replace
+            } else {
+                core::ImportMode::Merge
+            },
+            dry_run: args.dry_run,
+        },
+    )
+    .map_err(cli_error)?;
+    log_diagnostics(&outcome.diagnostics);
+    let import = outcome.value;
+
+    match format {
+        OutputFormat::Human => {
+            println!(
+                "Added {}, updated {}, unchanged {}, removed {}.",
+                import.added, import.updated, import.unchanged, import.removed
+            );
+            if !import.rejected.is_empty() {
+                println!("\nRejected rows:");
+                for row in &import.rejected {
+                    match &row.name {
+                        Some(name) => println!("    row {} ({name}): {}", row.row, row.reason),
+                        None => println!("    row {}: {}", row.row, row.reason),
+                    }
+                }
+            }
+            if args.dry_run {
+                println!("\nDry run: the pantry was not changed.");
+            }
+        }
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&import)?),
+        OutputFormat::Yaml => println!("{}", serde_yaml::to_string(&import)?),
+    }
+    Ok(())
+}
+
+/// One journal entry: a heading, then each item as it went from and to.
+fn print_change(change: &core::PantryChange) {
+    println!(
+        "#{:<4} {}  {:<4} {}",
+        change.id,
+        change
+            .at
+            .with_timezone(&chrono::Local)
+            .format("%Y-%m-%d %H:%M:%S"),
+        change.origin.as_deref().unwrap_or("-"),
+        change.operation
+    );
+    for item in &change.items {
+        println!("      {}", item_line(item, &item.before, &item.after));
+    }
+}
+
+/// `flour [pantry]: "1%kg" → "500%g"`, with `-` for a side the item was not
+/// there on.
+fn item_line(item: &core::ItemChange, from: &Option<String>, to: &Option<String>) -> String {
+    format!(
+        "{} [{}]: {} → {}",
+        item.name,
+        item.section,
+        from.as_deref().unwrap_or("-"),
+        to.as_deref().unwrap_or("-")
+    )
+}
+
 // ---------------------------------------------------------------------------
 // Utility helpers
 // ---------------------------------------------------------------------------
@@ -0,0 +1,82 @@
+use super::common::json_error;
+use crate::server::AppState;
+use axum::{
+    extract::{Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use camino::Utf8Path;
+use cookcli_core::{
+    doctor::{broken_references, validate, ValidateRequest},
+    format::doctor as fmt,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+pub struct ValidateQuery {
+    /// `json` (the default), `sarif`, `junit` or `github`, as
+    /// `cook doctor validate --format` takes them.
+    pub format: Option<String>,
+}
+
+/// The same report `cook doctor validate --format <format>` prints, for the
+/// whole collection.
+///
+/// Paths are relative to the collection root: the server is not running
+/// inside a repository a CI system could resolve anything else against.
+pub async fn validate_recipes(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ValidateQuery>,
+) -> Result<Response, (StatusCode, Json<serde_json::Value>)> {
+    let format = query.format.as_deref().unwrap_or("json");
+    if !matches!(format, "json" | "sarif" | "junit" | "github") {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            json_error(format!(
+                "Unknown format '{format}': expected json, sarif, junit or github"
+            )),
+        ));
+    }
+
+    // Parsing every recipe in the collection is blocking work, and plenty of
+    // it for a large one; keep it off the async workers. Discovered on every
+    // request rather than at startup, so an edited `doctor.toml` takes effect
+    // without a restart, the way an edited recipe does.
+    let base_path = state.base_path.clone();
+    let report = tokio::task::spawn_blocking(move || {
+        validate(
+            &cookcli_core::Context::discover(base_path),
+            ValidateRequest::default(),
+        )
+    })
+    .await
+    .map_err(|e| {
+        tracing::error!("Validation task failed: {e}");
+        (StatusCode::INTERNAL_SERVER_ERROR, json_error(&e))
+    })?
+    .map_err(|e| {
+        tracing::error!("Failed to validate recipes: {:?}", e);
+        (StatusCode::INTERNAL_SERVER_ERROR, json_error(&e))
+    })?
+    .into_value();
+
+    let broken = broken_references(&report);
+    let root = Utf8Path::new("");
+    let response = match format {
+        "sarif" => Json(fmt::build_sarif_value(&report, &broken, root)).into_response(),
+        "junit" => (
+            [(header::CONTENT_TYPE, "application/xml; charset=utf-8")],
+            fmt::build_junit(&report, &broken, root),
+        )
+            .into_response(),
+        "github" => (
+            [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+            fmt::build_github(&report, &broken, root),
+        )
+            .into_response(),
+        _ => Json(fmt::build_json_value(&report, &broken, root)).into_response(),
+    };
+    Ok(response)
+}
@@ -0,0 +1,86 @@
+use super::common::{check_path, json_error};
+use crate::server::AppState;
+use axum::{
+    extract::{Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use cookcli_core::{
+    format::graph as fmt,
+    graph::{reference_graph, GraphRequest},
+    CoreError,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+pub struct GraphQuery {
+    /// Recipe or menu to grow the graph from, relative to the collection
+    /// root: `Breakfast/Pancakes`, `Week.menu`. The whole collection if
+    /// absent.
+    pub root: Option<String>,
+    /// How many references deep to follow from `root`; all of them if absent.
+    pub depth: Option<usize>,
+    /// `json` (the default), `dot` or `mermaid`, as `cook graph --format`
+    /// takes them.
+    pub format: Option<String>,
+}
+
+/// The graph `cook graph` prints: every recipe and menu and the references
+/// between them, or the part of it `root` reaches.
+pub async fn get_graph(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<GraphQuery>,
+) -> Result<Response, (StatusCode, Json<serde_json::Value>)> {
+    let format = query.format.as_deref().unwrap_or("json");
+    if !matches!(format, "json" | "dot" | "mermaid") {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            json_error(format!(
+                "Unknown format '{format}': expected json, dot or mermaid"
+            )),
+        ));
+    }
+    if let Some(root) = &query.root {
+        check_path(root)?;
+    }
+
+    // Every recipe in the collection is parsed to find its references, which
+    // is blocking work; keep it off the async workers.
+    let ctx = cookcli_core::Context::discover(state.base_path.clone());
+    let request = GraphRequest {
+        base_dir: None,
+        root: query.root,
+        depth: query.depth,
+    };
+    let graph = tokio::task::spawn_blocking(move || reference_graph(&ctx, request))
+        .await
+        .map_err(|e| {
+            tracing::error!("Graph task failed: {e}");
+            (StatusCode::INTERNAL_SERVER_ERROR, json_error(&e))
+        })?
+        .map_err(|e| match e {
+            CoreError::RecipeNotFound { .. } => (StatusCode::NOT_FOUND, json_error(&e)),
+            e => {
+                tracing::error!("Failed to build reference graph: {:?}", e);
+                (StatusCode::INTERNAL_SERVER_ERROR, json_error(&e))
+            }
+        })?
+        .into_value();
+
+    let response = match format {
+        "dot" => (
+            [(header::CONTENT_TYPE, "text/vnd.graphviz; charset=utf-8")],
+            fmt::build_dot(&graph),
+        )
+            .into_response(),
+        "mermaid" => (
+            [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+            fmt::build_mermaid(&graph),
+        )
+            .into_response(),
+        _ => Json(fmt::build_json_value(&graph)).into_response(),
+    };
+    Ok(response)
+}
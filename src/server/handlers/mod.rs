@@ -1,4 +1,6 @@
 mod common;
+pub mod doctor;
+pub mod graph;
 pub mod menus;
 pub mod pantry;
 pub mod recipes;
@@ -8,16 +10,21 @@ pub mod stats;
 #[cfg(feature = "sync")]
 pub mod sync;
 
+pub use doctor::validate_recipes;
+pub use graph::get_graph;
 pub use menus::{get_menu, list_menus};
 pub use pantry::{
-    add_item as add_pantry_item, get_depleted, get_expiring, get_pantry,
-    remove_item as remove_pantry_item, update_item as update_pantry_item,
+    add_item as add_pantry_item, batch as batch_pantry, consume_recipe, get_depleted, get_expiring,
+    get_history as get_pantry_history, get_pantry, get_use_up, remove_item as remove_pantry_item,
+    undo as undo_pantry, update_item as update_pantry_item,
 };
 pub use recipes::{all_recipes, recipe, recipe_delete, recipe_raw, recipe_save, reload, search};
 pub use shopping_list::{
-    add_menu_to_shopping_list, add_to_shopping_list, check_shopping_item, clear_shopping_list,
-    compact_checked, get_checked_items, get_shopping_list_items, remove_from_shopping_list,
-    shopping_list, uncheck_shopping_item,
+    add_manual_item, add_menu_to_shopping_list, add_to_shopping_list, check_shopping_item,
+    clear_shopping_list, commit_shopping_list, compact_checked, get_checked_items,
+    get_manual_items, get_shopping_list_history, get_shopping_list_items, list_shopping_lists,
+    list_store_profiles, move_shopping_list_item, remove_from_shopping_list, remove_manual_item,
+    restore_shopping_list, shopping_list, uncheck_shopping_item, undo_shopping_list,
 };
 pub use shopping_list_events::shopping_list_events;
 pub use stats::stats;
@@ -6,6 +6,7 @@ use axum::{
 };
 use camino::Utf8PathBuf;
 use chrono::prelude::*;
+use cookcli_core::{pantry as core_pantry, shopping_list::ScaledRecipe, CoreError};
 use serde::{Deserialize, Serialize};
 use serde_json;
 use std::sync::Arc;
@@ -72,53 +73,83 @@ pub struct ApiResponse {
     pub message: String,
 }
 
+/// A core context for writing the server's pantry, recording its changes as
+/// made from the web, and for reading it with the collection's substitutes.
+fn pantry_context(state: &AppState, pantry_path: &Utf8PathBuf) -> cookcli_core::Context {
+    let ctx = cookcli_core::Context::new(state.base_path.clone())
+        .with_pantry(cookcli_core::ConfigSource::Path(pantry_path.clone()))
+        .with_origin("web");
+    match &state.substitutes_path {
+        Some(path) => ctx.with_substitutes(cookcli_core::ConfigSource::Path(path.clone())),
+        None => ctx,
+    }
+}
+
+/// 404 unless the pantry has `section`, and `name` in it when one is given.
+///
+/// Core refuses a missing section or item too, but as one kind of error it
+/// shares with other refusals; checking first is what lets the API keep
+/// answering 404 for these.
+async fn check_item(
+    state: &AppState,
+    section: &str,
+    name: Option<&str>,
+) -> Result<(), (StatusCode, Json<serde_json::Value>)> {
+    let pantry_conf = load_pantry(state).await?;
+    let Some(items) = pantry_conf.sections.get(section) else {
+        return Err((
+            StatusCode::NOT_FOUND,
+            json_error(format!("Section not found: {section}")),
+        ));
+    };
+    match name {
+        Some(name) if !items.iter().any(|item| item.name() == name) => Err((
+            StatusCode::NOT_FOUND,
+            json_error(format!("Item not found: {name} in {section}")),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// How a refused or failed pantry write is answered.
+fn write_error(e: CoreError) -> (StatusCode, Json<serde_json::Value>) {
+    match e {
+        CoreError::PantryEdit { .. } => (StatusCode::BAD_REQUEST, json_error(&e)),
+        e => {
+            tracing::error!("Failed to write pantry file: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, json_error(&e))
+        }
+    }
+}
+
 pub async fn add_item(
     State(state): State<Arc<AppState>>,
     Json(item): Json<AddPantryItem>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
     let pantry_path = get_pantry_path(&state)?;
-    let mut pantry_conf = load_pantry(&state).await.unwrap_or_default();
-
-    // Create new item
-    let new_item = if item.quantity.is_some()
-        || item.bought.is_some()
-        || item.expire.is_some()
-        || item.low.is_some()
-    {
-        cooklang::pantry::PantryItem::WithAttributes(cooklang::pantry::ItemWithAttributes {
+    let ctx = pantry_context(&state, pantry_path);
+
+    let _guard = state.pantry_lock.lock().await;
+    let outcome = core_pantry::add(
+        &ctx,
+        core_pantry::AddRequest {
+            section: item.section.clone(),
             name: item.name.clone(),
             quantity: item.quantity,
             bought: item.bought,
             expire: item.expire,
             low: item.low,
-        })
-    } else {
-        cooklang::pantry::PantryItem::Simple(item.name.clone())
-    };
-
-    // Add item to the specified section
-    pantry_conf
-        .sections
-        .entry(item.section.clone())
-        .or_insert_with(Vec::new)
-        .push(new_item);
-
-    // Rebuild index
-    pantry_conf.rebuild_index();
-
-    // Serialize back to regular TOML format (not array format)
-    let new_content = serialize_pantry_to_regular_toml(&pantry_conf);
-
-    // Write back to file
-    tokio::fs::write(pantry_path.as_std_path(), new_content)
-        .await
-        .map_err(|e| {
-            tracing::error!("Failed to write pantry file: {e}");
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                json_error(format!("Failed to write pantry file: {e}")),
-            )
-        })?;
+            datastore: Some(state.base_path.join(core_pantry::DATASTORE_DIR))
+                .filter(|db| db.is_dir()),
+        },
+    )
+    .map_err(|e| match e {
+        CoreError::PantryEdit { .. } => (StatusCode::CONFLICT, json_error(&e)),
+        e => write_error(e),
+    })?;
+    for diagnostic in &outcome.diagnostics {
+        tracing::warn!("Adding to the pantry: {}", diagnostic.message);
+    }
 
     Ok(Json(ApiResponse {
         success: true,
@@ -131,40 +162,22 @@ pub async fn remove_item(
     Path((section, name)): Path<(String, String)>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
     let pantry_path = get_pantry_path(&state)?;
-    let mut pantry_conf = load_pantry(&state).await?;
-
-    // Remove item from the specified section
-    if let Some(items) = pantry_conf.sections.get_mut(&section) {
-        items.retain(|item| item.name() != name);
-
-        // Remove section if empty
-        if items.is_empty() {
-            pantry_conf.sections.shift_remove(&section);
-        }
-    } else {
-        return Err((
-            StatusCode::NOT_FOUND,
-            json_error(format!("Section not found: {section}")),
-        ));
+    check_item(&state, &section, Some(&name)).await?;
+    let ctx = pantry_context(&state, pantry_path);
+
+    let _guard = state.pantry_lock.lock().await;
+    let outcome = core_pantry::remove(
+        &ctx,
+        core_pantry::RemoveRequest {
+            section: section.clone(),
+            name: name.clone(),
+        },
+    )
+    .map_err(write_error)?;
+    for diagnostic in &outcome.diagnostics {
+        tracing::warn!("Removing from the pantry: {}", diagnostic.message);
     }
 
-    // Rebuild index
-    pantry_conf.rebuild_index();
-
-    // Serialize back to regular TOML format (not array format)
-    let new_content = serialize_pantry_to_regular_toml(&pantry_conf);
-
-    // Write back to file
-    tokio::fs::write(pantry_path.as_std_path(), new_content)
-        .await
-        .map_err(|e| {
-            tracing::error!("Failed to write pantry file: {e}");
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                json_error(format!("Failed to write pantry file: {e}")),
-            )
-        })?;
-
     Ok(Json(ApiResponse {
         success: true,
         message: format!("Removed {name} from {section}"),
@@ -177,79 +190,178 @@ pub async fn update_item(
     Json(update): Json<UpdatePantryItem>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
     let pantry_path = get_pantry_path(&state)?;
-    let mut pantry_conf = load_pantry(&state).await?;
-
-    // Find and update the item
-    if let Some(items) = pantry_conf.sections.get_mut(&section) {
-        for item in items.iter_mut() {
-            if item.name() == name {
-                // Convert to WithAttributes if needed
-                let updated_item = match item {
-                    cooklang::pantry::PantryItem::Simple(item_name) => {
-                        if update.quantity.is_some()
-                            || update.bought.is_some()
-                            || update.expire.is_some()
-                            || update.low.is_some()
-                        {
-                            cooklang::pantry::PantryItem::WithAttributes(
-                                cooklang::pantry::ItemWithAttributes {
-                                    name: item_name.clone(),
-                                    quantity: update.quantity.clone(),
-                                    bought: update.bought.clone(),
-                                    expire: update.expire.clone(),
-                                    low: update.low,
-                                },
-                            )
-                        } else {
-                            cooklang::pantry::PantryItem::Simple(item_name.clone())
-                        }
-                    }
-                    cooklang::pantry::PantryItem::WithAttributes(attrs) => {
-                        cooklang::pantry::PantryItem::WithAttributes(
-                            cooklang::pantry::ItemWithAttributes {
-                                name: attrs.name.clone(),
-                                quantity: update.quantity.clone().or(attrs.quantity.clone()),
-                                bought: update.bought.clone().or(attrs.bought.clone()),
-                                expire: update.expire.clone().or(attrs.expire.clone()),
-                                low: update.low.clone().or(attrs.low.clone()),
-                            },
-                        )
-                    }
-                };
-                *item = updated_item;
-                break;
-            }
-        }
-    } else {
-        return Err((
-            StatusCode::NOT_FOUND,
-            json_error(format!("Section not found: {section}")),
-        ));
+    check_item(&state, &section, Some(&name)).await?;
+    let ctx = pantry_context(&state, pantry_path);
+
+    let _guard = state.pantry_lock.lock().await;
+    let outcome = core_pantry::update(
+        &ctx,
+        core_pantry::UpdateRequest {
+            section: section.clone(),
+            name: name.clone(),
+            quantity: update.quantity,
+            bought: update.bought,
+            expire: update.expire,
+            low: update.low,
+        },
+    )
+    .map_err(write_error)?;
+    for diagnostic in &outcome.diagnostics {
+        tracing::warn!("Updating the pantry: {}", diagnostic.message);
     }
 
-    // Rebuild index
-    pantry_conf.rebuild_index();
+    Ok(Json(ApiResponse {
+        success: true,
+        message: format!("Updated {name} in {section}"),
+    }))
+}
 
-    // Serialize back to regular TOML format (not array format)
-    let new_content = serialize_pantry_to_regular_toml(&pantry_conf);
+/// One edit in a batch, tagged by what it does:
+/// `{"op": "update", "section": "dairy", "name": "milk", "quantity": "1%l"}`.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum BatchPantryEdit {
+    Add(AddPantryItem),
+    Update {
+        section: String,
+        name: String,
+        #[serde(flatten)]
+        update: UpdatePantryItem,
+    },
+    Remove {
+        section: String,
+        name: String,
+    },
+    Move {
+        section: String,
+        name: String,
+        to: String,
+    },
+}
 
-    // Write back to file
-    tokio::fs::write(pantry_path.as_std_path(), new_content)
-        .await
-        .map_err(|e| {
-            tracing::error!("Failed to write pantry file: {e}");
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                json_error(format!("Failed to write pantry file: {e}")),
-            )
-        })?;
+#[derive(Debug, Deserialize)]
+pub struct BatchPantry {
+    pub edits: Vec<BatchPantryEdit>,
+}
+
+/// Apply several edits in one write: all of them, or — with a 400 naming the
+/// edit that was refused — none. One undo takes the lot back.
+pub async fn batch(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<BatchPantry>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let pantry_path = get_pantry_path(&state)?;
+    let ctx = pantry_context(&state, pantry_path);
+    let count = payload.edits.len();
+    let datastore = Some(state.base_path.join(core_pantry::DATASTORE_DIR)).filter(|db| db.is_dir());
+
+    let edits = payload
+        .edits
+        .into_iter()
+        .map(|edit| match edit {
+            BatchPantryEdit::Add(item) => core_pantry::BatchEdit::Add(core_pantry::AddRequest {
+                section: item.section,
+                name: item.name,
+                quantity: item.quantity,
+                bought: item.bought,
+                expire: item.expire,
+                low: item.low,
+                datastore: datastore.clone(),
+            }),
+            BatchPantryEdit::Update {
+                section,
+                name,
+                update,
+            } => core_pantry::BatchEdit::Update(core_pantry::UpdateRequest {
+                section,
+                name,
+                quantity: update.quantity,
+                bought: update.bought,
+                expire: update.expire,
+                low: update.low,
+            }),
+            BatchPantryEdit::Remove { section, name } => {
+                core_pantry::BatchEdit::Remove(core_pantry::RemoveRequest { section, name })
+            }
+            BatchPantryEdit::Move { section, name, to } => {
+                core_pantry::BatchEdit::Move(core_pantry::MoveRequest { section, name, to })
+            }
+        })
+        .collect();
+
+    let _guard = state.pantry_lock.lock().await;
+    let outcome =
+        core_pantry::batch(&ctx, core_pantry::BatchRequest { edits }).map_err(write_error)?;
+    for diagnostic in &outcome.diagnostics {
+        tracing::warn!("Editing the pantry: {}", diagnostic.message);
+    }
 
     Ok(Json(ApiResponse {
         success: true,
-        message: format!("Updated {name} in {section}"),
+        message: format!(
+            "Applied {count} {}",
+            if count == 1 { "edit" } else { "edits" }
+        ),
     }))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct HistoryQuery {
+    /// Only the last this many changes; all of them if absent.
+    pub limit: Option<usize>,
+}
+
+/// Every change recorded in the pantry's journal, oldest first.
+pub async fn get_history(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<HistoryQuery>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let pantry_path = get_pantry_path(&state)?;
+    let ctx = pantry_context(&state, pantry_path);
+    let _guard = state.pantry_lock.lock().await;
+    let mut journal = core_pantry::history(&ctx)
+        .map_err(|e| {
+            tracing::error!("Failed to read the pantry journal: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, json_error(&e))
+        })?
+        .value;
+    if let Some(limit) = query.limit {
+        journal.drain(..journal.len().saturating_sub(limit));
+    }
+    Ok(Json(journal))
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct UndoPantry {
+    /// How many changes to take back; 1 if absent.
+    pub count: Option<usize>,
+}
+
+/// Take back the last changes to the pantry, answering with what was undone,
+/// newest first. 409 when an item has been changed again since.
+pub async fn undo(
+    State(state): State<Arc<AppState>>,
+    payload: Option<Json<UndoPantry>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let pantry_path = get_pantry_path(&state)?;
+    let ctx = pantry_context(&state, pantry_path);
+    let count = payload.and_then(|Json(p)| p.count).unwrap_or(1);
+
+    let _guard = state.pantry_lock.lock().await;
+    let outcome =
+        core_pantry::undo(&ctx, core_pantry::UndoRequest { count }).map_err(|e| match e {
+            CoreError::PantryHistory { .. } => (StatusCode::CONFLICT, json_error(&e)),
+            e => {
+                tracing::error!("Failed to undo a pantry change: {:?}", e);
+                (StatusCode::INTERNAL_SERVER_ERROR, json_error(&e))
+            }
+        })?;
+    for diagnostic in &outcome.diagnostics {
+        tracing::warn!("Undoing a pantry change: {}", diagnostic.message);
+    }
+    Ok(Json(serde_json::json!({ "undone": outcome.value })))
+}
+
 pub async fn get_pantry(
     State(state): State<Arc<AppState>>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
@@ -325,6 +437,46 @@ pub async fn get_expiring(
     Ok(Json(items))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct UseUpQuery {
+    pub days: Option<u32>,
+    pub limit: Option<usize>,
+}
+
+/// Recipes ranked by how much of what is about to expire they would use,
+/// best first.
+pub async fn get_use_up(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<UseUpQuery>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let pantry_path = get_pantry_path(&state)?;
+    let ctx = pantry_context(&state, pantry_path);
+    let request = core_pantry::UseUpRequest {
+        days: query.days.unwrap_or(7),
+    };
+    // Every recipe in the collection is parsed; keep that off the async
+    // workers.
+    let outcome = tokio::task::spawn_blocking(move || core_pantry::use_up(&ctx, request))
+        .await
+        .map_err(|e| {
+            tracing::error!("Use-up task failed: {e}");
+            (StatusCode::INTERNAL_SERVER_ERROR, json_error(&e))
+        })?
+        .map_err(|e| {
+            tracing::error!("Failed to rank recipes by what they use up: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, json_error(&e))
+        })?;
+    for diagnostic in &outcome.diagnostics {
+        tracing::warn!("Ranking recipes to use up: {}", diagnostic.message);
+    }
+
+    let mut ranked = outcome.value;
+    if let Some(limit) = query.limit {
+        ranked.truncate(limit);
+    }
+    Ok(Json(ranked))
+}
+
 pub async fn get_depleted(
     State(state): State<Arc<AppState>>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
@@ -346,6 +498,59 @@ pub async fn get_depleted(
     Ok(Json(items))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ConsumeRecipe {
+    /// The recipe that was cooked, relative to the collection root.
+    pub recipe: String,
+    /// The scale it was cooked at; 1 if absent.
+    pub scale: Option<f64>,
+    /// Answer with what would change without writing the pantry.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Take what a cooked recipe used out of the pantry, answering with what was
+/// drawn down and what ran short. 404 when the server has no pantry
+/// configuration, or the recipe does not exist.
+pub async fn consume_recipe(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<ConsumeRecipe>,
+) -> Result<impl IntoResponse, (StatusCode, Json<serde_json::Value>)> {
+    let pantry_path = get_pantry_path(&state)?;
+    super::common::check_path(&payload.recipe)?;
+
+    let ctx = pantry_context(&state, pantry_path);
+    let request = core_pantry::ConsumeRequest {
+        recipe: ScaledRecipe {
+            source: cookcli_core::RecipeSource::Path(payload.recipe.as_str().into()),
+            scale: payload.scale.unwrap_or(1.0),
+        },
+        dry_run: payload.dry_run,
+    };
+    // The recipe and everything it references are parsed; keep that off the
+    // async workers.
+    let _guard = state.pantry_lock.lock().await;
+    let outcome = tokio::task::spawn_blocking(move || core_pantry::consume(&ctx, request))
+        .await
+        .map_err(|e| {
+            tracing::error!("Consume task failed: {e}");
+            (StatusCode::INTERNAL_SERVER_ERROR, json_error(&e))
+        })?
+        .map_err(|e| match e {
+            CoreError::RecipeNotFound { .. } => (StatusCode::NOT_FOUND, json_error(&e)),
+            CoreError::Parse { .. } => (StatusCode::BAD_REQUEST, json_error(&e)),
+            e => {
+                tracing::error!("Failed to take a recipe out of the pantry: {:?}", e);
+                (StatusCode::INTERNAL_SERVER_ERROR, json_error(&e))
+            }
+        })?;
+    for diagnostic in &outcome.diagnostics {
+        tracing::warn!("Recipe '{}': {}", payload.recipe, diagnostic.message);
+    }
+
+    Ok(Json(outcome.value))
+}
+
 /// Parse a date string supporting multiple formats
 pub fn parse_date(date_str: &str) -> Option<NaiveDate> {
     let formats = [
@@ -360,7 +565,3 @@ pub fn parse_date(date_str: &str) -> Option<NaiveDate> {
 
     None
 }
-
-fn serialize_pantry_to_regular_toml(pantry_conf: &cooklang::pantry::PantryConf) -> String {
-    cooklang::pantry::to_toml_string(pantry_conf)
-}
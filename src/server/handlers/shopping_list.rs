@@ -1,18 +1,46 @@
+use super::common::json_error;
 use crate::server::AppState;
-use crate::util::menu_scale::{reference_scale_factor, resolve_recipe_info, RecipeInfo};
-use crate::util::PARSER;
+use crate::util::menu_scale::menu_shopping_entries;
 use anyhow::Context as _;
-use axum::{extract::State, http::StatusCode, Json};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
 use camino::Utf8PathBuf;
 use cookcli_core::shopping_list::{
-    extract_ingredients, recipe_display_name, ExtractOptions, ScaledRecipe, ShoppingListStore,
-    StoredEntry,
+    commit_to_pantry, generate_entries, recipe_display_name, saved_ingredient_names, store_profile,
+    store_profile_names, CommitRequest, ListChange, ListItem, ManualItem, ShoppingListStore,
+    StoredEntry, DEFAULT_LIST,
 };
-use cooklang::ingredient_list::IngredientList;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json;
 use std::sync::Arc;
 
+/// Which of the collection's shopping lists a request is about. Every
+/// endpoint that touches the stored list takes it; absent means the default
+/// list, the `.shopping-list` the Cooklang apps share.
+#[derive(Debug, Deserialize)]
+pub struct ListQuery {
+    pub list: Option<String>,
+    /// The store profile to lay the aggregated list out with. Only the
+    /// stateless `POST /api/shopping_list` builds a categorised list, so it is
+    /// the only endpoint that reads this.
+    pub store: Option<String>,
+}
+
+/// The store `query` names, or 400 for a name a list cannot have. Its
+/// changes are logged as made by `web`.
+fn open_store(
+    state: &AppState,
+    query: &ListQuery,
+) -> Result<ShoppingListStore, (StatusCode, Json<serde_json::Value>)> {
+    let name = query.list.as_deref().unwrap_or(DEFAULT_LIST);
+    ShoppingListStore::named(&state.base_path, name)
+        .map(|store| store.acting_as("web"))
+        .map_err(|e| (StatusCode::BAD_REQUEST, json_error(&e)))
+}
+
 #[derive(Debug, Deserialize)]
 pub struct RecipeRequest {
     recipe: String,
@@ -21,174 +49,117 @@ pub struct RecipeRequest {
     included_references: Option<Vec<String>>,
 }
 
+/// One ingredient of `POST /api/shopping_list`: the core item, plus the
+/// amount the page shows — packages first, as the CLI's table has it.
+#[derive(Serialize)]
+struct WebListItem<'a> {
+    #[serde(flatten)]
+    item: &'a ListItem,
+    amount: Vec<String>,
+}
+
 pub async fn shopping_list(
     State(state): State<Arc<AppState>>,
+    Query(query): Query<ListQuery>,
     axum::extract::Json(payload): axum::extract::Json<Vec<RecipeRequest>>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
-    let mut list = IngredientList::new();
-    let core_ctx = cookcli_core::Context::new(state.base_path.clone());
-
-    for entry in payload {
-        let name = entry.recipe;
-        let recipe = ScaledRecipe {
-            source: cookcli_core::RecipeSource::Path(name.as_str().into()),
+    // Items added by hand are not recipes the page can post, so they are read
+    // from the list `?list=` names, as the checked state is below.
+    let store = open_store(&state, &query)?;
+    let manual_items = store.items().map_err(|e| {
+        tracing::error!("Failed to load shopping list items: {:?}", e);
+        (StatusCode::INTERNAL_SERVER_ERROR, json_error(&e))
+    })?;
+    let entries: Vec<StoredEntry> = payload
+        .into_iter()
+        .map(|entry| StoredEntry {
+            name: recipe_display_name(&entry.recipe),
+            path: entry.recipe,
             scale: entry.scale.unwrap_or(1.0),
-        };
-
-        let diagnostics = extract_ingredients(
-            &core_ctx,
-            &recipe,
-            &ExtractOptions {
-                ignore_references: false,
-                included_references: entry.included_references.as_deref(),
-            },
-            &mut list,
-        )
-        .map_err(|e| {
-            tracing::error!("Error processing recipe: {}", e);
-            (
-                StatusCode::BAD_REQUEST,
-                Json(serde_json::json!({ "error": e.to_string() })),
-            )
-        })?;
-
-        for diagnostic in diagnostics {
-            tracing::warn!("Recipe '{}': {}", name, diagnostic.message);
-        }
+            included_references: entry.included_references,
+            recipes: None,
+        })
+        .collect();
+
+    // The list is built as the command line builds it, from the same
+    // configuration: the aisle, the pantry as of today, substitutes and the
+    // store profile `?store=` names.
+    let mut core_ctx = cookcli_core::Context::new(state.base_path.clone());
+    if let Some(path) = &state.aisle_path {
+        core_ctx = core_ctx.with_aisle(cookcli_core::ConfigSource::Path(path.clone()));
     }
-
-    // Load aisle configuration with lenient parsing
-    let aisle_content = if let Some(path) = &state.aisle_path {
-        match std::fs::read_to_string(path) {
-            Ok(content) => {
-                tracing::debug!("Loaded aisle file from: {:?}", path);
-                content
-            }
-            Err(e) => {
-                tracing::warn!("Failed to read aisle file from {:?}: {}", path, e);
-                String::new()
-            }
-        }
-    } else {
-        tracing::debug!("No aisle file configured");
-        String::new()
-    };
-
-    // Parse aisle with lenient parsing
-    let aisle_result = cooklang::aisle::parse_lenient(&aisle_content);
-
-    if aisle_result.report().has_warnings() {
-        for warning in aisle_result.report().warnings() {
-            tracing::warn!("Aisle configuration warning: {}", warning);
-        }
+    if let Some(path) = &state.pantry_path {
+        core_ctx = core_ctx.with_pantry(cookcli_core::ConfigSource::Path(path.clone()));
     }
-
-    let aisle = aisle_result.output().cloned().unwrap_or_default();
-
-    // Load pantry configuration
-    let pantry_conf = if let Some(path) = &state.pantry_path {
-        match std::fs::read_to_string(path) {
-            Ok(content) => {
-                tracing::debug!("Loaded pantry file from: {:?}", path);
-                // Parse pantry file using cooklang pantry parser
-                let result = cooklang::pantry::parse_lenient(&content);
-
-                if result.report().has_warnings() {
-                    for warning in result.report().warnings() {
-                        tracing::warn!("Pantry configuration warning: {}", warning);
-                    }
-                }
-
-                result.output().cloned()
-            }
-            Err(e) => {
-                tracing::warn!("Failed to read pantry file from {:?}: {}", path, e);
-                None
-            }
-        }
-    } else {
-        tracing::debug!("No pantry file configured");
-        None
-    };
-
-    // Use common names from aisle configuration
-    list = list.use_common_names(&aisle, PARSER.converter());
-
-    // Track pantry items that were found and subtracted (excluding zero quantities)
-    let mut pantry_items = Vec::new();
-    if let Some(ref pantry) = pantry_conf {
-        // Check which items from the original list are in the pantry with non-zero quantity
-        for (ingredient_name, _) in list.iter() {
-            if let Some((_, pantry_item)) = pantry.find_ingredient(ingredient_name) {
-                // Check if the pantry item has a non-zero quantity
-                if let Some(qty_str) = pantry_item.quantity() {
-                    // Special case for unlimited
-                    if qty_str == "unlim" || qty_str == "unlimited" {
-                        pantry_items.push(ingredient_name.clone());
-                    } else if let Some((value, _)) = pantry_item.parsed_quantity() {
-                        // Only include if quantity is greater than 0
-                        if value > 0.0 {
-                            pantry_items.push(ingredient_name.clone());
-                        }
-                    }
-                } else {
-                    // No quantity specified means we have it (backward compatibility)
-                    pantry_items.push(ingredient_name.clone());
-                }
-            }
-        }
+    if let Some(path) = &state.substitutes_path {
+        core_ctx = core_ctx.with_substitutes(cookcli_core::ConfigSource::Path(path.clone()));
+    }
+    if let Some(name) = query.store.as_deref() {
+        let profile = store_profile(&state.base_path, name)
+            .map_err(|e| (StatusCode::NOT_FOUND, json_error(&e)))?;
+        core_ctx = core_ctx.with_store(profile);
     }
 
-    // Apply pantry subtraction if pantry is available
-    let final_list = if let Some(ref pantry) = pantry_conf {
-        list.subtract_pantry(pantry, PARSER.converter())
-    } else {
-        list
-    };
-
-    let categories = final_list.categorize(&aisle);
-
-    // Build the response
-    let mut shopping_categories = Vec::new();
-
-    for (category, items) in categories {
-        let mut entries: Vec<(String, _)> = items.into_iter().collect();
-
-        // The "other" bucket holds ingredients with no aisle category. They
-        // arrive in recipe insertion order, which is unhelpful when scanning
-        // a long list — sort alphabetically (case-insensitive) so shoppers
-        // can find items predictably.
-        if category == "other" {
-            entries.sort_by_key(|(a, _)| a.to_lowercase());
+    let outcome = generate_entries(&core_ctx, &entries, &manual_items).map_err(|e| match e {
+        cookcli_core::CoreError::RecipeNotFound { .. }
+        | cookcli_core::CoreError::Parse { .. }
+        | cookcli_core::CoreError::Reference { .. } => {
+            tracing::error!("Error processing recipe: {}", e);
+            (StatusCode::BAD_REQUEST, json_error(&e))
         }
-
-        let mut shopping_items = Vec::new();
-        for (name, qty) in entries {
-            let item_json = serde_json::json!({
-                "name": name,
-                // Not `into_vec()`: that yields the components in the group's
-                // own random order, so an ingredient measured two ways came
-                // back differently on every request.
-                "quantities": crate::util::format::quantity::ordered_components(&qty)
-            });
-            shopping_items.push(item_json);
+        e => {
+            tracing::error!("Failed to build the shopping list: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, json_error(&e))
         }
-
-        if !shopping_items.is_empty() {
-            shopping_categories.push(serde_json::json!({
-                "category": category,
-                "items": shopping_items
-            }));
+    })?;
+    let mut list = outcome.value;
+    let mut diagnostics = outcome.diagnostics;
+    // Packages are counted as `cook shopping-list --datastore` counts them,
+    // from the datastore the server reports on.
+    let datastore = state.base_path.join(cookcli_core::pantry::DATASTORE_DIR);
+    if datastore.is_dir() {
+        diagnostics.extend(list.round_to_packages(&datastore));
+    }
+    for diagnostic in &diagnostics {
+        match diagnostic.location.as_ref().and_then(|l| l.file.as_ref()) {
+            Some(file) => tracing::warn!("{file}: {}", diagnostic.message),
+            None => tracing::warn!("{}", diagnostic.message),
         }
     }
 
+    let categories: Vec<serde_json::Value> = list
+        .categories
+        .into_iter()
+        .map(|category| {
+            let mut items = category.items;
+            // The "other" bucket holds ingredients with no aisle category.
+            // They arrive in recipe insertion order, which is unhelpful when
+            // scanning a long list — sort alphabetically (case-insensitive)
+            // so shoppers can find items predictably.
+            if category.name == "other" {
+                items.sort_by_key(|item| item.name.to_lowercase());
+            }
+            let items: Vec<WebListItem> = items
+                .iter()
+                .map(|item| WebListItem {
+                    amount: item.amount(),
+                    item,
+                })
+                .collect();
+            serde_json::json!({
+                "category": category.name,
+                "items": items,
+            })
+        })
+        .collect();
+
     // Load checked state
-    let store = ShoppingListStore::new(&state.base_path);
     let checked = store.checked_set().unwrap_or_default();
 
     let json_value = serde_json::json!({
-        "categories": shopping_categories,
-        "pantry_items": pantry_items,
+        "categories": categories,
+        "pantry_items": list.from_pantry,
+        "substitutions": list.substitutions,
         "checked": checked.into_iter().collect::<Vec<_>>()
     });
     Ok(Json(json_value))
@@ -196,8 +167,9 @@ pub async fn shopping_list(
 
 pub async fn get_shopping_list_items(
     State(state): State<Arc<AppState>>,
+    Query(query): Query<ListQuery>,
 ) -> Result<Json<Vec<StoredEntry>>, (StatusCode, Json<serde_json::Value>)> {
-    let store = ShoppingListStore::new(&state.base_path);
+    let store = open_store(&state, &query)?;
     let items = store.load().map_err(|e| {
         tracing::error!("Failed to load shopping list: {:?}", e);
         (
@@ -208,6 +180,76 @@ pub async fn get_shopping_list_items(
     Ok(Json(items))
 }
 
+// -- Items added by hand --
+
+pub async fn get_manual_items(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ListQuery>,
+) -> Result<Json<Vec<ManualItem>>, (StatusCode, Json<serde_json::Value>)> {
+    let store = open_store(&state, &query)?;
+    let items = store.items().map_err(|e| {
+        tracing::error!("Failed to load shopping list items: {:?}", e);
+        (StatusCode::INTERNAL_SERVER_ERROR, json_error(&e))
+    })?;
+    Ok(Json(items))
+}
+
+/// 400 for a name or quantity the `.shopping-list` format cannot hold.
+pub async fn add_manual_item(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ListQuery>,
+    Json(payload): Json<ManualItem>,
+) -> Result<StatusCode, (StatusCode, Json<serde_json::Value>)> {
+    let store = open_store(&state, &query)?;
+    let _guard = state.shopping_list_lock.lock().await;
+    store.add_item(payload).map_err(|e| match e {
+        cookcli_core::CoreError::ShoppingListItem { .. } => {
+            (StatusCode::BAD_REQUEST, json_error(&e))
+        }
+        e => {
+            tracing::error!("Failed to add item to shopping list: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, json_error(&e))
+        }
+    })?;
+    Ok(StatusCode::OK)
+}
+
+/// Takes the first item of that name off, then compacts the checked log as
+/// removing a recipe does. 404 if there is no such item.
+pub async fn remove_manual_item(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ListQuery>,
+    Json(payload): Json<CheckItemRequest>,
+) -> Result<StatusCode, (StatusCode, Json<serde_json::Value>)> {
+    let store = open_store(&state, &query)?;
+    let _guard = state.shopping_list_lock.lock().await;
+    let removed = store.remove_item(&payload.name).map_err(|e| {
+        tracing::error!("Failed to remove item from shopping list: {:?}", e);
+        (StatusCode::INTERNAL_SERVER_ERROR, json_error(&e))
+    })?;
+    if !removed {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({
+                "error": format!("'{}' is not on the shopping list", payload.name)
+            })),
+        ));
+    }
+
+    match aggregate_current_ingredient_names(&state, &store) {
+        Ok(names) => {
+            if let Err(e) = store.compact(names) {
+                tracing::warn!("Failed to compact checked log after remove: {:?}", e);
+            }
+        }
+        Err(e) => tracing::warn!(
+            "Skipping compact after remove — aggregation failed: {:?}",
+            e
+        ),
+    }
+    Ok(StatusCode::OK)
+}
+
 #[derive(Debug, Deserialize)]
 pub struct AddItemRequest {
     pub path: String,
@@ -218,9 +260,10 @@ pub struct AddItemRequest {
 
 pub async fn add_to_shopping_list(
     State(state): State<Arc<AppState>>,
+    Query(query): Query<ListQuery>,
     Json(payload): Json<AddItemRequest>,
 ) -> Result<StatusCode, (StatusCode, Json<serde_json::Value>)> {
-    let store = ShoppingListStore::new(&state.base_path);
+    let store = open_store(&state, &query)?;
     // `name` is derived from `path` on load — any client-supplied display
     // name would be silently discarded, so it's not accepted here.
     let item = StoredEntry {
@@ -231,6 +274,7 @@ pub async fn add_to_shopping_list(
         recipes: None,
     };
 
+    let _guard = state.shopping_list_lock.lock().await;
     store.add(item).map_err(|e| {
         tracing::error!("Failed to add to shopping list: {:?}", e);
         (
@@ -249,9 +293,11 @@ pub struct RemoveItemRequest {
 
 pub async fn remove_from_shopping_list(
     State(state): State<Arc<AppState>>,
+    Query(query): Query<ListQuery>,
     Json(payload): Json<RemoveItemRequest>,
 ) -> Result<StatusCode, (StatusCode, Json<serde_json::Value>)> {
-    let store = ShoppingListStore::new(&state.base_path);
+    let store = open_store(&state, &query)?;
+    let _guard = state.shopping_list_lock.lock().await;
     store.remove(&payload.path).map_err(|e| {
         tracing::error!("Failed to remove from shopping list: {:?}", e);
         (
@@ -263,9 +309,7 @@ pub async fn remove_from_shopping_list(
     // Compact the checked log now that one recipe is gone: stale checks
     // (ingredients no longer referenced by any remaining recipe) can drop.
     // Best-effort — a failure here must not break the remove itself.
-    // Serialize against concurrent check/uncheck/compact.
-    let _guard = state.checked_log_lock.lock().await;
-    match aggregate_current_ingredient_names(&state) {
+    match aggregate_current_ingredient_names(&state, &store) {
         Ok(names) => {
             if let Err(e) = store.compact(names) {
                 tracing::warn!("Failed to compact checked log after remove: {:?}", e);
@@ -282,12 +326,13 @@ pub async fn remove_from_shopping_list(
 
 pub async fn clear_shopping_list(
     State(state): State<Arc<AppState>>,
+    Query(query): Query<ListQuery>,
 ) -> Result<StatusCode, (StatusCode, Json<serde_json::Value>)> {
-    // Acquire the checked-log lock so a concurrent check/uncheck can't
+    // Acquire the list lock so a concurrent check/uncheck can't
     // recreate `.shopping-checked` between our remove_file and the caller's
     // view of a cleared list.
-    let _guard = state.checked_log_lock.lock().await;
-    let store = ShoppingListStore::new(&state.base_path);
+    let _guard = state.shopping_list_lock.lock().await;
+    let store = open_store(&state, &query)?;
     store.clear().map_err(|e| {
         tracing::error!("Failed to clear shopping list: {:?}", e);
         (
@@ -308,10 +353,11 @@ pub struct CheckItemRequest {
 
 pub async fn check_shopping_item(
     State(state): State<Arc<AppState>>,
+    Query(query): Query<ListQuery>,
     Json(payload): Json<CheckItemRequest>,
 ) -> Result<StatusCode, (StatusCode, Json<serde_json::Value>)> {
-    let _guard = state.checked_log_lock.lock().await;
-    let store = ShoppingListStore::new(&state.base_path);
+    let _guard = state.shopping_list_lock.lock().await;
+    let store = open_store(&state, &query)?;
     store.check(&payload.name).map_err(|e| {
         tracing::error!("Failed to check item: {:?}", e);
         (
@@ -324,10 +370,11 @@ pub async fn check_shopping_item(
 
 pub async fn uncheck_shopping_item(
     State(state): State<Arc<AppState>>,
+    Query(query): Query<ListQuery>,
     Json(payload): Json<CheckItemRequest>,
 ) -> Result<StatusCode, (StatusCode, Json<serde_json::Value>)> {
-    let _guard = state.checked_log_lock.lock().await;
-    let store = ShoppingListStore::new(&state.base_path);
+    let _guard = state.shopping_list_lock.lock().await;
+    let store = open_store(&state, &query)?;
     store.uncheck(&payload.name).map_err(|e| {
         tracing::error!("Failed to uncheck item: {:?}", e);
         (
@@ -340,8 +387,9 @@ pub async fn uncheck_shopping_item(
 
 pub async fn get_checked_items(
     State(state): State<Arc<AppState>>,
+    Query(query): Query<ListQuery>,
 ) -> Result<Json<Vec<String>>, (StatusCode, Json<serde_json::Value>)> {
-    let store = ShoppingListStore::new(&state.base_path);
+    let store = open_store(&state, &query)?;
     let checked = store.checked_set().map_err(|e| {
         tracing::error!("Failed to get checked items: {:?}", e);
         (
@@ -354,10 +402,11 @@ pub async fn get_checked_items(
 
 pub async fn compact_checked(
     State(state): State<Arc<AppState>>,
+    Query(query): Query<ListQuery>,
 ) -> Result<StatusCode, (StatusCode, Json<serde_json::Value>)> {
-    let _guard = state.checked_log_lock.lock().await;
-    let store = ShoppingListStore::new(&state.base_path);
-    let names = aggregate_current_ingredient_names(&state).map_err(|e| {
+    let _guard = state.shopping_list_lock.lock().await;
+    let store = open_store(&state, &query)?;
+    let names = aggregate_current_ingredient_names(&state, &store).map_err(|e| {
         tracing::error!("Failed to aggregate ingredients for compact: {:?}", e);
         (
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -375,59 +424,225 @@ pub async fn compact_checked(
 }
 
 /// Aggregate the ingredient names a user would see for the currently-stored
-/// shopping list. Walks every recipe reference persisted in `.shopping-list`
-/// and expands it through `extract_ingredients`, honoring any
-/// `included_references` and recipe scale factors.
-///
-/// Returns names in their raw (non-common) form — `compact_checked` does a
-/// case-insensitive comparison so that's fine for the stale-check step.
+/// shopping list; see [`saved_ingredient_names`], which `cook shopping-list
+/// saved compact` shares.
 ///
 /// Returns `Err(..)` if any recipe fails to parse. The caller should refuse
 /// to compact in that case — a partial ingredient set would mark otherwise-
 /// valid checks as stale and wipe them, which is how the original bug this
 /// module was fixing manifested.
-fn aggregate_current_ingredient_names(state: &AppState) -> anyhow::Result<Vec<String>> {
-    let store = ShoppingListStore::new(&state.base_path);
-    let items = store.load()?;
-    let mut list = IngredientList::new();
+fn aggregate_current_ingredient_names(
+    state: &AppState,
+    store: &ShoppingListStore,
+) -> anyhow::Result<Vec<String>> {
     let core_ctx = cookcli_core::Context::new(state.base_path.clone());
+    let names = saved_ingredient_names(&core_ctx, store)
+        .context("aggregating ingredients of the saved shopping list")?;
+    Ok(names.into_value())
+}
 
-    // Each entry is aggregated independently: the shopping list may
-    // legitimately contain the same recipe more than once (e.g. duplicate
-    // entries from the legacy format), and that is not a cycle.
-    let mut add = |path: &str, scale: f64, included: Option<&[String]>| -> anyhow::Result<()> {
-        extract_ingredients(
-            &core_ctx,
-            &ScaledRecipe {
-                source: cookcli_core::RecipeSource::Path(path.into()),
-                scale,
-            },
-            &ExtractOptions {
-                ignore_references: false,
-                included_references: included,
-            },
-            &mut list,
-        )
-        .with_context(|| format!("aggregating ingredients for {path} at scale {scale}"))?;
-        Ok(())
+// -- History --
+
+pub async fn get_shopping_list_history(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ListQuery>,
+) -> Result<Json<Vec<ListChange>>, (StatusCode, Json<serde_json::Value>)> {
+    let store = open_store(&state, &query)?;
+    let log = store.log().map_err(|e| {
+        tracing::error!("Failed to read shopping list history: {:?}", e);
+        (StatusCode::INTERNAL_SERVER_ERROR, json_error(&e))
+    })?;
+    Ok(Json(log))
+}
+
+/// Take back the last change, answering with it, or with `null` when there
+/// is nothing left to undo. 409 when it was a move and the other list has
+/// changed since.
+pub async fn undo_shopping_list(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ListQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    // Undoing rewrites the checked log along with the list.
+    let _guard = state.shopping_list_lock.lock().await;
+    let store = open_store(&state, &query)?;
+    let outcome = store.undo().map_err(|e| match e {
+        cookcli_core::CoreError::ShoppingListHistory { .. } => {
+            (StatusCode::CONFLICT, json_error(&e))
+        }
+        e => {
+            tracing::error!("Failed to undo shopping list change: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, json_error(&e))
+        }
+    })?;
+    for diagnostic in &outcome.diagnostics {
+        tracing::warn!("Undoing a shopping list change: {}", diagnostic.message);
+    }
+    Ok(Json(serde_json::json!({ "undone": outcome.value })))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RestoreRequest {
+    /// RFC 3339.
+    pub at: String,
+}
+
+/// Put the list back as it stood at `at`, answering with the changes taken
+/// back. 400 for a time that is not RFC 3339, is further back than the
+/// history goes, or is before a move to or from a list that has changed
+/// since.
+pub async fn restore_shopping_list(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ListQuery>,
+    Json(payload): Json<RestoreRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    let at = chrono::DateTime::parse_from_rfc3339(&payload.at)
+        .map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                json_error(format!("'{}' is not an RFC 3339 time: {e}", payload.at)),
+            )
+        })?
+        .with_timezone(&chrono::Utc);
+    let _guard = state.shopping_list_lock.lock().await;
+    let store = open_store(&state, &query)?;
+    let outcome = store.restore(at).map_err(|e| match e {
+        cookcli_core::CoreError::ShoppingListHistory { .. } => {
+            (StatusCode::BAD_REQUEST, json_error(&e))
+        }
+        e => {
+            tracing::error!("Failed to restore shopping list: {:?}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, json_error(&e))
+        }
+    })?;
+    for diagnostic in &outcome.diagnostics {
+        tracing::warn!("Restoring the shopping list: {}", diagnostic.message);
+    }
+    Ok(Json(serde_json::json!({ "undone": outcome.value })))
+}
+
+// -- Pantry --
+
+/// Move what has been ticked off into the pantry, answering with what went
+/// in. 404 when the server has no pantry configuration to write to: the
+/// pantry page cannot show one it was not started with, so one is not
+/// created behind its back.
+pub async fn commit_shopping_list(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ListQuery>,
+) -> Result<Json<serde_json::Value>, (StatusCode, Json<serde_json::Value>)> {
+    let Some(pantry_path) = &state.pantry_path else {
+        return Err((
+            StatusCode::NOT_FOUND,
+            json_error("Pantry configuration not found"),
+        ));
+    };
+    let mut core_ctx = cookcli_core::Context::new(state.base_path.clone())
+        .with_pantry(cookcli_core::ConfigSource::Path(pantry_path.clone()))
+        .with_origin("web");
+    if let Some(aisle_path) = &state.aisle_path {
+        core_ctx = core_ctx.with_aisle(cookcli_core::ConfigSource::Path(aisle_path.clone()));
+    }
+
+    // Committing takes ticks off and compacts the checked log, and writes
+    // the pantry.
+    let _guard = state.shopping_list_lock.lock().await;
+    let _pantry_guard = state.pantry_lock.lock().await;
+    let store = open_store(&state, &query)?;
+    let outcome = commit_to_pantry(&core_ctx, &store, CommitRequest::default()).map_err(|e| {
+        tracing::error!(
+            "Failed to move shopping list items into the pantry: {:?}",
+            e
+        );
+        (StatusCode::INTERNAL_SERVER_ERROR, json_error(&e))
+    })?;
+    for diagnostic in &outcome.diagnostics {
+        tracing::warn!("Committing to the pantry: {}", diagnostic.message);
+    }
+    Ok(Json(serde_json::json!({ "committed": outcome.value })))
+}
+
+// -- Named lists --
+
+/// One of the collection's shopping lists, as `GET /api/shopping_list/lists`
+/// reports it.
+#[derive(Debug, serde::Serialize)]
+pub struct ListSummary {
+    pub name: String,
+    /// How many entries are on it; a menu counts once.
+    pub entries: usize,
+}
+
+pub async fn list_shopping_lists(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<ListSummary>>, (StatusCode, Json<serde_json::Value>)> {
+    let failed = |e: cookcli_core::CoreError| {
+        tracing::error!("Failed to list shopping lists: {:?}", e);
+        (StatusCode::INTERNAL_SERVER_ERROR, json_error(&e))
     };
+    let mut lists = Vec::new();
+    for name in ShoppingListStore::list_names(&state.base_path).map_err(failed)? {
+        let store = ShoppingListStore::named(&state.base_path, &name).map_err(failed)?;
+        lists.push(ListSummary {
+            entries: store.load().map_err(failed)?.len(),
+            name,
+        });
+    }
+    Ok(Json(lists))
+}
+
+/// The names of the store profiles the page can offer, sorted.
+pub async fn list_store_profiles(State(state): State<Arc<AppState>>) -> Json<Vec<String>> {
+    Json(store_profile_names(&state.base_path))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MoveItemRequest {
+    pub path: String,
+    pub to: String,
+}
 
-    for item in &items {
-        if let Some(recipes) = &item.recipes {
-            // Menu/plan entry — expand each nested recipe.
-            for recipe in recipes {
-                add(
-                    &recipe.path,
-                    recipe.scale,
-                    recipe.included_references.as_deref(),
-                )?;
+/// Move an entry from the list `?list=` names to `to`, then compact the
+/// checked log it left, as removing it would.
+pub async fn move_shopping_list_item(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ListQuery>,
+    Json(payload): Json<MoveItemRequest>,
+) -> Result<StatusCode, (StatusCode, Json<serde_json::Value>)> {
+    let store = open_store(&state, &query)?;
+    let to = open_store(
+        &state,
+        &ListQuery {
+            list: Some(payload.to),
+            store: None,
+        },
+    )?;
+
+    let _guard = state.shopping_list_lock.lock().await;
+    let moved = store.move_entry(&payload.path, &to).map_err(|e| {
+        tracing::error!("Failed to move shopping list item: {:?}", e);
+        (StatusCode::INTERNAL_SERVER_ERROR, json_error(&e))
+    })?;
+    if !moved {
+        return Err((
+            StatusCode::NOT_FOUND,
+            json_error(format!(
+                "'{}' is not on the '{}' list",
+                payload.path,
+                store.name()
+            )),
+        ));
+    }
+
+    match aggregate_current_ingredient_names(&state, &store) {
+        Ok(names) => {
+            if let Err(e) = store.compact(names) {
+                tracing::warn!("Failed to compact checked log after move: {:?}", e);
             }
-        } else {
-            add(&item.path, item.scale, item.included_references.as_deref())?;
         }
+        Err(e) => tracing::warn!("Skipping compact after move — aggregation failed: {:?}", e),
     }
 
-    Ok(list.iter().map(|(name, _)| name.clone()).collect())
+    Ok(StatusCode::OK)
 }
 
 // -- Add menu (bulk) endpoint --
@@ -442,9 +657,10 @@ pub struct AddMenuRequest {
 /// plan entry with recipes nested inside.
 pub async fn add_menu_to_shopping_list(
     State(state): State<Arc<AppState>>,
+    Query(query): Query<ListQuery>,
     Json(payload): Json<AddMenuRequest>,
 ) -> Result<StatusCode, (StatusCode, Json<serde_json::Value>)> {
-    let store = ShoppingListStore::new(&state.base_path);
+    let store = open_store(&state, &query)?;
     let menu_scale = payload.scale;
 
     let recipe_path = Utf8PathBuf::from(&payload.path);
@@ -467,60 +683,9 @@ pub async fn add_menu_to_shopping_list(
         )
     })?;
 
-    let mut recipes = Vec::new();
-
-    for ingredient in &menu.ingredients {
-        if let Some(ref recipe_ref) = ingredient.reference {
-            // Build display path from reference components
-            let ref_display = if recipe_ref.components.is_empty() {
-                recipe_ref.name.clone()
-            } else {
-                format!("{}/{}", recipe_ref.components.join("/"), recipe_ref.name)
-            };
-
-            // Resolve this recipe's sub-recipe references, default servings, and yield
-            let ref_path_for_find = recipe_ref.path(cookcli_core::REFERENCE_SEPARATOR);
-            let info = match resolve_recipe_info(&state.base_path, &ref_path_for_find) {
-                Ok(info) => info,
-                Err(e) => {
-                    tracing::warn!(
-                        "Could not resolve referenced recipe '{}': {}",
-                        ref_display,
-                        e
-                    );
-                    RecipeInfo::default()
-                }
-            };
-
-            // Convert `{target%unit}` on the menu reference into a scale
-            // multiplier for `.shopping-list`. The menu was parsed at 1.0
-            // above, so `ingredient.quantity` is the quantity as authored,
-            // which is what `reference_scale_factor` requires.
-            //
-            // Storing a raw multiplier without this conversion was the bug:
-            // e.g. a 2-serving recipe referenced as `{3%servings}` got stored
-            // as `{3}` and scaled to 6 servings instead of 3.
-            let recipe_factor =
-                reference_scale_factor(ingredient.quantity.as_ref(), &info, &ref_display);
-            let final_scale = recipe_factor * menu_scale;
-            let sub_refs = info.sub_refs;
-
-            // Strip ./ prefix for storage (the format writer adds it back)
-            let path = ref_display
-                .strip_prefix("./")
-                .unwrap_or(&ref_display)
-                .to_string();
-
-            recipes.push(StoredEntry {
-                name: recipe_display_name(&path),
-                path,
-                scale: final_scale,
-                included_references: Some(sub_refs),
-                recipes: None,
-            });
-        }
-    }
+    let recipes = menu_shopping_entries(&state.base_path, &menu, menu_scale);
 
+    let _guard = state.shopping_list_lock.lock().await;
     store
         .add_menu(payload.path, menu_scale, recipes)
         .map_err(|e| {
@@ -42,13 +42,15 @@ use camino::Utf8PathBuf;
 use clap::Args;
 #[cfg(feature = "sync")]
 use std::sync::Mutex;
-use std::{net::IpAddr, net::SocketAddr, sync::Arc};
+use std::{net::IpAddr, net::SocketAddr, sync::Arc, time::Duration};
 use tower_http::{cors::CorsLayer, services::ServeDir};
 use tracing::{error, info};
 
 mod fs_atomic;
 mod handlers;
 mod lsp_bridge;
+mod notify_job;
+mod restock_job;
 mod shopping_list_watcher;
 mod ui;
 
@@ -95,6 +97,32 @@ pub struct ServerArgs {
     // #[cfg(feature = "ui")]
     #[arg(long, default_value_t = false)]
     open: bool,
+
+    /// Put what is running out in the pantry on the shopping list every
+    /// MINUTES minutes
+    ///
+    /// Runs `cook pantry restock` in the background against the default
+    /// shopping list, first at startup and then on this interval. Items the
+    /// list already has are never added twice. Off unless given.
+    #[arg(
+        long,
+        value_name = "MINUTES",
+        value_parser = clap::builder::RangedU64ValueParser::<u64>::new().range(1..)
+    )]
+    restock_every: Option<u64>,
+
+    /// Send what is newly expiring or running out in the pantry every
+    /// MINUTES minutes
+    ///
+    /// Runs `cook pantry notify` in the background with the command and
+    /// webhook notify.toml names, first at startup and then on this
+    /// interval. Each item is sent once. Off unless given.
+    #[arg(
+        long,
+        value_name = "MINUTES",
+        value_parser = clap::builder::RangedU64ValueParser::<u64>::new().range(1..)
+    )]
+    notify_every: Option<u64>,
 }
 
 impl ServerArgs {
@@ -112,6 +140,8 @@ pub async fn run(ctx: Context, args: ServerArgs) -> Result<()> {
     };
     let addr = SocketAddr::from((addr, args.port));
     let open = args.open;
+    let restock_every = args.restock_every;
+    let notify_every = args.notify_every;
 
     let state = build_state(ctx, args)?;
 
@@ -165,6 +195,13 @@ pub async fn run(ctx: Context, args: ServerArgs) -> Result<()> {
 
     println!("Serving recipe files from: {:?}", state.base_path);
 
+    if let Some(minutes) = restock_every {
+        restock_job::spawn(Arc::clone(&state), Duration::from_secs(minutes * 60));
+    }
+    if let Some(minutes) = notify_every {
+        notify_job::spawn(Arc::clone(&state), Duration::from_secs(minutes * 60));
+    }
+
     // Maximum request body size: 1MB (reasonable for recipe files)
     const MAX_BODY_SIZE: usize = 1024 * 1024;
 
@@ -277,9 +314,19 @@ fn build_state(ctx: Context, args: ServerArgs) -> Result<Arc<AppState>> {
         .pantry()
         .path()
         .map(camino::Utf8Path::to_path_buf);
+    let substitutes_path = server_ctx
+        .substitutes()
+        .path()
+        .map(camino::Utf8Path::to_path_buf);
+    let notify_path = server_ctx
+        .notify()
+        .path()
+        .map(camino::Utf8Path::to_path_buf);
 
     tracing::info!("Aisle configuration: {:?}", aisle_path);
     tracing::info!("Pantry configuration: {:?}", pantry_path);
+    tracing::info!("Substitutes configuration: {:?}", substitutes_path);
+    tracing::info!("Notify configuration: {:?}", notify_path);
 
     let shopping_list_events = match shopping_list_watcher::spawn(absolute_path.clone()) {
         Ok(tx) => Some(tx),
@@ -308,8 +355,11 @@ fn build_state(ctx: Context, args: ServerArgs) -> Result<Arc<AppState>> {
         base_path: absolute_path,
         aisle_path,
         pantry_path,
+        substitutes_path,
+        notify_path,
         url_prefix,
-        checked_log_lock: Arc::new(tokio::sync::Mutex::new(())),
+        shopping_list_lock: Arc::new(tokio::sync::Mutex::new(())),
+        pantry_lock: Arc::new(tokio::sync::Mutex::new(())),
         shopping_list_events,
         #[cfg(feature = "sync")]
         sync_session: Arc::new(Mutex::new(session)),
@@ -354,13 +404,25 @@ pub struct AppState {
     pub base_path: Utf8PathBuf,
     pub aisle_path: Option<Utf8PathBuf>,
     pub pantry_path: Option<Utf8PathBuf>,
+    pub substitutes_path: Option<Utf8PathBuf>,
+    pub notify_path: Option<Utf8PathBuf>,
     pub url_prefix: String,
-    /// Serializes access to `.shopping-checked` within this process.
-    /// File-level `flock` doesn't prevent two tasks in the *same* process
-    /// from racing on the file (the kernel treats them as one lock owner),
-    /// so we need an in-process mutex on top. All check / uncheck / compact
-    /// handlers acquire this before touching the checked log.
-    pub checked_log_lock: Arc<tokio::sync::Mutex<()>>,
+    /// Serializes writes to the shopping lists within this process: the
+    /// lists, their checked logs and their histories. File-level `flock`
+    /// doesn't prevent two tasks in the *same* process from racing on a file
+    /// (the kernel treats them as one lock owner), so we need an in-process
+    /// mutex on top. Every handler that changes a list, and the restock job,
+    /// acquires this first; one lock for all of them, because a change to
+    /// one list can touch another (a move, or undoing one).
+    pub shopping_list_lock: Arc<tokio::sync::Mutex<()>>,
+    /// Serializes writes to the pantry file and its journal within this
+    /// process, for the same reason: each write reads the file, edits it and
+    /// renames a new one over it, and each journal entry's id is one more
+    /// than the last, so two at once could lose an edit or share an id.
+    /// Every handler that changes the pantry or reads its journal acquires
+    /// this first. A handler that needs both locks takes
+    /// `shopping_list_lock` first.
+    pub pantry_lock: Arc<tokio::sync::Mutex<()>>,
     /// Broadcasts filesystem changes to `.shopping-list` / `.shopping-checked`
     /// to every open SSE subscriber. `None` means watcher init failed; SSE
     /// clients can still connect but will never receive events.
@@ -420,7 +482,32 @@ fn api(_state: &AppState) -> Result<Router<Arc<AppState>>> {
             "/shopping_list/remove",
             post(handlers::remove_from_shopping_list),
         )
+        .route("/shopping_list/manual", get(handlers::get_manual_items))
+        .route("/shopping_list/manual/add", post(handlers::add_manual_item))
+        .route(
+            "/shopping_list/manual/remove",
+            post(handlers::remove_manual_item),
+        )
         .route("/shopping_list/clear", post(handlers::clear_shopping_list))
+        .route("/shopping_list/undo", post(handlers::undo_shopping_list))
+        .route(
+            "/shopping_list/restore",
+            post(handlers::restore_shopping_list),
+        )
+        .route(
+            "/shopping_list/history",
+            get(handlers::get_shopping_list_history),
+        )
+        .route(
+            "/shopping_list/commit",
+            post(handlers::commit_shopping_list),
+        )
+        .route("/shopping_list/lists", get(handlers::list_shopping_lists))
+        .route("/shopping_list/stores", get(handlers::list_store_profiles))
+        .route(
+            "/shopping_list/move",
+            post(handlers::move_shopping_list_item),
+        )
         .route("/shopping_list/check", post(handlers::check_shopping_item))
         .route(
             "/shopping_list/uncheck",
@@ -431,8 +518,13 @@ fn api(_state: &AppState) -> Result<Router<Arc<AppState>>> {
         .route("/shopping_list/events", get(handlers::shopping_list_events))
         .route("/pantry", get(handlers::get_pantry))
         .route("/pantry/add", post(handlers::add_pantry_item))
+        .route("/pantry/batch", post(handlers::batch_pantry))
         .route("/pantry/expiring", get(handlers::get_expiring))
         .route("/pantry/depleted", get(handlers::get_depleted))
+        .route("/pantry/use-up", get(handlers::get_use_up))
+        .route("/pantry/consume", post(handlers::consume_recipe))
+        .route("/pantry/history", get(handlers::get_pantry_history))
+        .route("/pantry/undo", post(handlers::undo_pantry))
         .route(
             "/pantry/:section/:name",
             axum::routing::delete(handlers::remove_pantry_item),
@@ -453,6 +545,8 @@ fn api(_state: &AppState) -> Result<Router<Arc<AppState>>> {
         .route("/menus/*path", get(handlers::get_menu))
         .route("/search", get(handlers::search))
         .route("/stats", get(handlers::stats))
+        .route("/doctor/validate", get(handlers::validate_recipes))
+        .route("/graph", get(handlers::get_graph))
         .route("/reload", get(handlers::reload).post(handlers::reload))
         .route("/ws/lsp", get(lsp_bridge::lsp_websocket));
 
@@ -0,0 +1,81 @@
+//! Background job that runs `cook pantry notify` on a timer, so that a phone
+//! or Home Assistant hears about what is expiring or running out without
+//! anything polling `/api/pantry/expiring`.
+//!
+//! Opt-in with `cook server --notify-every <MINUTES>`. The first run is at
+//! startup. `notify.toml` is read afresh on every run, so changing where
+//! notifications go does not need a restart. Each event is sent once, as the
+//! command sends it, and the record of what was sent is shared with the
+//! command: running both does not send anything twice.
+//!
+//! Failures are logged and the job carries on. What failed to send is not
+//! recorded as sent, so the next run tries it again.
+
+use super::AppState;
+use crate::util::notify::{deliver, Payload};
+use cookcli_core::{
+    pantry::{mark_notified, pending_notifications, NotifyConfig},
+    ConfigSource, Context,
+};
+use std::{sync::Arc, time::Duration};
+
+/// Spawn the job, detached, to run every `every`. Does nothing, with a
+/// warning, when the server found no pantry to watch or no `notify.toml`
+/// saying where to send anything.
+pub fn spawn(state: Arc<AppState>, every: Duration) {
+    let Some(pantry_path) = state.pantry_path.clone() else {
+        tracing::warn!("--notify-every was given, but there is no pantry file to watch");
+        return;
+    };
+    let Some(notify_path) = state.notify_path.clone() else {
+        tracing::warn!(
+            "--notify-every was given, but there is no notify.toml to say where to send"
+        );
+        return;
+    };
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(every);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            let ctx = Context::new(state.base_path.clone())
+                .with_pantry(ConfigSource::Path(pantry_path.clone()))
+                .with_notify(ConfigSource::Path(notify_path.clone()));
+            if let Err(e) = run(ctx).await {
+                tracing::warn!("notify: {e:#}");
+            }
+        }
+    });
+}
+
+async fn run(ctx: Context) -> anyhow::Result<()> {
+    let config = NotifyConfig::load(ctx.notify())?;
+    for diagnostic in &config.diagnostics {
+        tracing::warn!("notify: {}", diagnostic.message);
+    }
+    let config = config.value;
+    if config.command.is_none() && config.webhook.is_none() {
+        anyhow::bail!("notify.toml has neither a command nor a webhook to send to");
+    }
+
+    let request = config.request();
+    let outcome =
+        tokio::task::spawn_blocking(move || pending_notifications(&ctx, request)).await??;
+    for diagnostic in &outcome.diagnostics {
+        tracing::warn!("notify: {}", diagnostic.message);
+    }
+    let notifications = outcome.value;
+
+    if !notifications.events.is_empty() {
+        let payload = Payload::new(&notifications);
+        deliver(
+            config.command.as_deref(),
+            config.webhook.as_deref(),
+            &payload,
+        )
+        .await?;
+        tracing::info!("notify: sent {} event(s)", notifications.events.len());
+    }
+    tokio::task::spawn_blocking(move || mark_notified(&notifications)).await??;
+    Ok(())
+}
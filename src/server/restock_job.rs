@@ -0,0 +1,75 @@
+//! Background job that runs `cook pantry restock` on a timer, so that what
+//! runs out in the pantry turns up on the default shopping list without
+//! anyone running the command.
+//!
+//! Opt-in with `cook server --restock-every <MINUTES>`. The first run is at
+//! startup. Each run is the same core call the command makes, logged as made
+//! by `restock` in the list's history; because restocking never adds what the
+//! list already has, running it often is harmless. Open browsers pick the
+//! additions up through the shopping list watcher like any other change.
+//!
+//! Failures are logged and the job carries on: a pantry file mid-edit, or a
+//! recipe on the list that does not parse, should not stop the next run.
+
+use super::AppState;
+use cookcli_core::{
+    pantry::{restock, RestockRequest},
+    shopping_list::ShoppingListStore,
+    ConfigSource, Context,
+};
+use std::{sync::Arc, time::Duration};
+
+/// Who the job's changes are logged as, in the list's history.
+const ACTOR: &str = "restock";
+
+/// Spawn the job, detached, to run every `every`. Does nothing, with a
+/// warning, when the server found no pantry to read.
+pub fn spawn(state: Arc<AppState>, every: Duration) {
+    let Some(pantry_path) = state.pantry_path.clone() else {
+        tracing::warn!("--restock-every was given, but there is no pantry file to restock from");
+        return;
+    };
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(every);
+        // A run that overlaps the next tick, on a very short interval, should
+        // not be followed by a burst of catch-up runs.
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            let state = Arc::clone(&state);
+            let pantry_path = pantry_path.clone();
+            // Held for the whole run, so a change made in the browser in the
+            // meantime is neither lost nor overwritten.
+            let _guard = Arc::clone(&state.shopping_list_lock).lock_owned().await;
+            let run = tokio::task::spawn_blocking(move || {
+                let mut ctx = Context::new(state.base_path.clone())
+                    .with_pantry(ConfigSource::Path(pantry_path));
+                // For the synonyms that say what the list already has.
+                if let Some(aisle_path) = &state.aisle_path {
+                    ctx = ctx.with_aisle(ConfigSource::Path(aisle_path.clone()));
+                }
+                let store = ShoppingListStore::new(&state.base_path).acting_as(ACTOR);
+                restock(&ctx, &store, RestockRequest::default())
+            })
+            .await;
+            match run {
+                Ok(Ok(outcome)) => {
+                    for diagnostic in &outcome.diagnostics {
+                        tracing::warn!("restock: {}", diagnostic.message);
+                    }
+                    if !outcome.value.added.is_empty() {
+                        let names: Vec<&str> = outcome
+                            .value
+                            .added
+                            .iter()
+                            .map(|item| item.name.as_str())
+                            .collect();
+                        tracing::info!("restock: added {} to the shopping list", names.join(", "));
+                    }
+                }
+                Ok(Err(e)) => tracing::warn!("restock: {e}"),
+                Err(e) => tracing::error!("restock job panicked: {e}"),
+            }
+        }
+    });
+}
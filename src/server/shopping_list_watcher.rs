@@ -1,5 +1,6 @@
 //! Filesystem watcher that broadcasts `.shopping-list` / `.shopping-checked`
-//! changes so open browsers can refresh without reload.
+//! changes — and those of the named lists beside them — so open browsers can
+//! refresh without reload.
 //!
 //! Startup is best-effort: if `notify` fails to initialize (permission
 //! issues, unsupported platform), the server logs a warning and continues
@@ -11,7 +12,7 @@ use std::path::Path;
 
 /// Which of the two watched files changed. The client uses this only as a
 /// hint for logging; it re-fetches regardless.
-#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "lowercase")]
 pub enum WatchedFile {
     List,
@@ -19,18 +20,21 @@ pub enum WatchedFile {
 }
 
 /// Event broadcast to every subscribed SSE connection.
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, Hash)]
 pub struct ShoppingListChangeEvent {
     pub file: WatchedFile,
+    /// Which list the file belongs to — `default` for the unnamed pair — so
+    /// a page showing another list can ignore the event.
+    pub list: String,
 }
 
-/// Classify a filesystem path as one of the two watched files, or None if
-/// it's something we don't care about (recipe files, temp files, backups,
+/// Classify a filesystem path as one of the watched files, or None if it's
+/// something we don't care about (recipe files, temp files, backups,
 /// directories, etc.).
 ///
 /// `base_path` is the server's recipe directory; paths outside of it (or
 /// nested deeper than immediate children) are ignored.
-pub fn classify_path(base_path: &Utf8Path, path: &Path) -> Option<WatchedFile> {
+pub fn classify_path(base_path: &Utf8Path, path: &Path) -> Option<ShoppingListChangeEvent> {
     let parent = path.parent()?;
     // Compare as Utf8Path to avoid surprises with non-UTF8 OsStr on the LHS;
     // our base_path is already Utf8Path-typed.
@@ -39,16 +43,39 @@ pub fn classify_path(base_path: &Utf8Path, path: &Path) -> Option<WatchedFile> {
         return None;
     }
     let name = path.file_name()?.to_str()?;
-    match name {
-        ".shopping-list" => Some(WatchedFile::List),
-        ".shopping-checked" => Some(WatchedFile::Checked),
-        _ => None,
-    }
+    let (file, list) = match name {
+        ".shopping-list" => (WatchedFile::List, DEFAULT_LIST),
+        ".shopping-checked" => (WatchedFile::Checked, DEFAULT_LIST),
+        _ => {
+            let (file, list) = if let Some(list) = name.strip_prefix(".shopping-list.") {
+                (WatchedFile::List, list)
+            } else {
+                (
+                    WatchedFile::Checked,
+                    name.strip_prefix(".shopping-checked.")?,
+                )
+            };
+            // Whatever the store would refuse to open is not one of its
+            // files — a backup, or a staging file mid-rename. The default
+            // list never lives under a suffix.
+            if list == DEFAULT_LIST {
+                return None;
+            }
+            ShoppingListStore::named(base_path, list).ok()?;
+            (file, list)
+        }
+    };
+    Some(ShoppingListChangeEvent {
+        file,
+        list: list.to_string(),
+    })
 }
 
 use anyhow::{Context, Result};
+use cookcli_core::shopping_list::{ShoppingListStore, DEFAULT_LIST};
 use notify::RecursiveMode;
 use notify_debouncer_full::{new_debouncer, DebounceEventResult};
+use std::collections::HashSet;
 use std::time::Duration;
 use tokio::sync::broadcast;
 
@@ -107,32 +134,18 @@ pub fn spawn(base_path: camino::Utf8PathBuf) -> Result<ChangeSender> {
             match result {
                 Ok(events) => {
                     // Collapse the batch: at most one event per file.
-                    let mut fired_list = false;
-                    let mut fired_checked = false;
+                    let mut fired = HashSet::new();
                     for event in events {
                         for path in &event.paths {
-                            match classify_path(&base_for_task, path) {
-                                Some(WatchedFile::List) if !fired_list => {
-                                    fired_list = true;
+                            if let Some(change) = classify_path(&base_for_task, path) {
+                                if fired.insert(change.clone()) {
+                                    // Send returns Err when there are no
+                                    // receivers — fine.
+                                    let _ = tx_for_task.send(change);
                                 }
-                                Some(WatchedFile::Checked) if !fired_checked => {
-                                    fired_checked = true;
-                                }
-                                _ => {}
                             }
                         }
                     }
-                    if fired_list {
-                        // Send returns Err when there are no receivers — fine.
-                        let _ = tx_for_task.send(ShoppingListChangeEvent {
-                            file: WatchedFile::List,
-                        });
-                    }
-                    if fired_checked {
-                        let _ = tx_for_task.send(ShoppingListChangeEvent {
-                            file: WatchedFile::Checked,
-                        });
-                    }
                 }
                 Err(errors) => {
                     for err in errors {
@@ -156,16 +169,56 @@ mod tests {
         Utf8PathBuf::from("/tmp/recipes")
     }
 
+    fn change(file: WatchedFile, list: &str) -> Option<ShoppingListChangeEvent> {
+        Some(ShoppingListChangeEvent {
+            file,
+            list: list.to_string(),
+        })
+    }
+
     #[test]
     fn classifies_shopping_list() {
         let p = PathBuf::from("/tmp/recipes/.shopping-list");
-        assert_eq!(classify_path(&base(), &p), Some(WatchedFile::List));
+        assert_eq!(
+            classify_path(&base(), &p),
+            change(WatchedFile::List, "default")
+        );
     }
 
     #[test]
     fn classifies_shopping_checked() {
         let p = PathBuf::from("/tmp/recipes/.shopping-checked");
-        assert_eq!(classify_path(&base(), &p), Some(WatchedFile::Checked));
+        assert_eq!(
+            classify_path(&base(), &p),
+            change(WatchedFile::Checked, "default")
+        );
+    }
+
+    #[test]
+    fn classifies_a_named_list() {
+        let p = PathBuf::from("/tmp/recipes/.shopping-list.costco");
+        assert_eq!(
+            classify_path(&base(), &p),
+            change(WatchedFile::List, "costco")
+        );
+        let p = PathBuf::from("/tmp/recipes/.shopping-checked.costco");
+        assert_eq!(
+            classify_path(&base(), &p),
+            change(WatchedFile::Checked, "costco")
+        );
+    }
+
+    #[test]
+    fn ignores_what_cannot_be_a_named_list() {
+        for name in [
+            ".shopping-list.costco.bak",
+            "..shopping-list.costco.4321.tmp",
+            ".shopping-list.Costco",
+            ".shopping-list.default",
+        ] {
+            let p = PathBuf::from(format!("/tmp/recipes/{name}"));
+            assert_eq!(classify_path(&base(), &p), None, "{name}");
+        }
     }
 
     /// The store stages every rewrite in a `.<name>.<pid>.tmp` sibling before
@@ -40,6 +40,7 @@ pub fn ui() -> Router<Arc<AppState>> {
         .route("/new", get(new_page).post(create_recipe))
         .route("/shopping-list", get(shopping_list_page))
         .route("/pantry", get(pantry_page))
+        .route("/graph", get(graph_page))
         .route("/preferences", get(preferences_page))
         .route("/api-docs", get(api_docs_page))
 }
@@ -109,6 +110,7 @@ async fn recipe_page(
         static_mode: false,
         repo_url: None,
         features,
+        pantry_configured: state.pantry_path.is_some(),
     };
 
     match crate::web::builders::build_recipe_template(input) {
@@ -462,6 +464,7 @@ async fn shopping_list_page(
 ) -> impl askama_axum::IntoResponse {
     ShoppingListTemplate {
         active: "shopping".to_string(),
+        pantry_configured: state.pantry_path.is_some(),
         tr: Tr::new(lang),
         prefix: state.url_prefix.clone(),
         static_mode: false,
@@ -495,6 +498,10 @@ async fn pantry_page(
                             quantity: item.quantity().map(|q| q.to_string()),
                             bought: item.bought().map(|b| b.to_string()),
                             expire: item.expire().map(|e| e.to_string()),
+                            expire_date: item
+                                .expire()
+                                .and_then(super::handlers::pantry::parse_date)
+                                .map(|date| date.format("%Y-%m-%d").to_string()),
                             low: item.low().map(|l| l.to_string()),
                         });
                     }
@@ -520,6 +527,90 @@ async fn pantry_page(
     })
 }
 
+#[derive(Deserialize, Default)]
+struct GraphPageQuery {
+    /// Both are strings because the form submits its fields empty rather than
+    /// leaving them out, and an empty field means "no limit" here, not a
+    /// malformed request.
+    root: Option<String>,
+    depth: Option<String>,
+}
+
+async fn graph_page(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<GraphPageQuery>,
+    Extension(lang): Extension<LanguageIdentifier>,
+    Extension(features): Extension<FeatureFlags>,
+) -> axum::response::Response {
+    use cookcli_core::graph::{reference_graph, GraphRequest, ReferenceGraph};
+
+    let root = query.root.filter(|root| !root.trim().is_empty());
+    let base_path = state.base_path.clone();
+    let request_root = root.clone();
+    let depth = query.depth.and_then(|depth| depth.trim().parse().ok());
+    // Parsing every recipe to find its references is blocking work.
+    let built = tokio::task::spawn_blocking(move || {
+        let ctx = cookcli_core::Context::discover(base_path);
+        let whole = reference_graph(&ctx, GraphRequest::default())?.into_value();
+        let drawn = match &request_root {
+            Some(root) => whole.rooted(root, depth),
+            None => Ok(whole.clone()),
+        };
+        Ok::<(ReferenceGraph, _), cookcli_core::CoreError>((whole, drawn))
+    })
+    .await;
+
+    let (whole, drawn) = match built {
+        Ok(Ok(built)) => built,
+        Ok(Err(e)) => {
+            tracing::error!("Failed to build reference graph: {e}");
+            return error_page(lang, &state.url_prefix, e, features);
+        }
+        Err(e) => {
+            tracing::error!("Graph task failed: {e}");
+            return error_page(lang, &state.url_prefix, e, features);
+        }
+    };
+    // A root that names nothing is the form's mistake, not the page's: show
+    // the whole collection with the error above it, so the user can pick
+    // again.
+    let (drawn, error) = match drawn {
+        Ok(graph) => (graph, None),
+        Err(e) => (whole.clone(), Some(e.to_string())),
+    };
+    let layout = crate::web::graph::layout(&drawn, &state.url_prefix);
+    let download_query = match (&drawn.root, depth) {
+        (Some(root), Some(depth)) => {
+            format!("&root={}&depth={depth}", urlencoding::encode(root.as_str()))
+        }
+        (Some(root), None) => format!("&root={}", urlencoding::encode(root.as_str())),
+        (None, _) => String::new(),
+    };
+
+    GraphTemplate {
+        active: "recipes".to_string(),
+        root,
+        depth,
+        download_query,
+        files: whole
+            .nodes
+            .iter()
+            .map(|node| node.path.to_string())
+            .collect(),
+        width: layout.width,
+        height: layout.height,
+        nodes: layout.nodes,
+        edges: layout.edges,
+        error,
+        tr: Tr::new(lang),
+        prefix: state.url_prefix.clone(),
+        static_mode: false,
+        repo_url: None,
+        features,
+    }
+    .into_response()
+}
+
 async fn preferences_page(
     State(state): State<Arc<AppState>>,
     Extension(lang): Extension<LanguageIdentifier>,
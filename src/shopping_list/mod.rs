@@ -30,12 +30,13 @@
 
 use anyhow::Result;
 use camino::Utf8PathBuf;
-use clap::{Args, ValueEnum};
+use chrono::NaiveDate;
+use clap::{Args, Subcommand, ValueEnum};
 use tracing::warn;
 
 use cookcli_core::{
     format::shopping_list as fmt,
-    shopping_list::{generate, GenerateRequest, ScaledRecipe},
+    shopping_list::{generate, store_profile, AggregatedList, GenerateRequest, ScaledRecipe},
     ConfigSource,
 };
 
@@ -44,9 +45,16 @@ use crate::{
     Context,
 };
 
+mod saved;
+
+pub(crate) use saved::open as open_saved_list;
+
 #[derive(Debug, Args)]
 #[command()]
 pub struct ShoppingListArgs {
+    #[command(subcommand)]
+    command: Option<ShoppingListCommand>,
+
     /// Recipe files to include in the shopping list
     ///
     /// Specify one or more recipe files by name or path. Each recipe can include
@@ -64,13 +72,13 @@ pub struct ShoppingListArgs {
     ///
     /// When recipe names (not full paths) are provided, the tool will search
     /// for them in this directory. Defaults to the current directory.
-    #[arg(short, long, value_hint = clap::ValueHint::DirPath)]
+    #[arg(short, long, global = true, value_hint = clap::ValueHint::DirPath)]
     base_path: Option<Utf8PathBuf>,
 
     /// Output file path (stdout if not specified)
     ///
     /// The output format can be inferred from the file extension
-    /// (.json, .yaml, .txt, .md)
+    /// (.json, .csv, .ics); anything else is written as the human table
     #[arg(short, long, value_hint = clap::ValueHint::FilePath)]
     output: Option<Utf8PathBuf>,
 
@@ -83,7 +91,9 @@ pub struct ShoppingListArgs {
 
     /// Output format for the shopping list
     ///
-    /// Available formats: human (default), json, yaml, markdown
+    /// Available formats: human (default), json, yaml, markdown, csv,
+    /// checklist (a compact printable list with checkboxes), todo (todo.txt)
+    /// and ics (iCalendar tasks for CalDAV apps).
     /// If not specified, format is inferred from output file extension.
     #[arg(short, long, value_enum)]
     format: Option<OutputFormat>,
@@ -92,6 +102,67 @@ pub struct ShoppingListArgs {
     #[arg(long)]
     pretty: bool,
 
+    #[command(flatten)]
+    config: ConfigArgs,
+
+    #[command(flatten)]
+    packages: PackageArgs,
+
+    /// Don't expand referenced recipes
+    ///
+    /// By default, recipes referenced from within a recipe (via @./other.cook)
+    /// are expanded and their ingredients included. This flag treats each recipe
+    /// in isolation.
+    #[arg(short, long)]
+    ignore_references: bool,
+
+    /// Display only ingredient names, one per line, without amounts
+    #[arg(long)]
+    ingredients_only: bool,
+
+    /// Under each ingredient, list the recipes that need it and how much
+    /// each
+    ///
+    /// For the human and markdown formats; JSON and YAML always include
+    /// them, as each item's `sources`.
+    #[arg(long)]
+    show_sources: bool,
+
+    /// The day you will cook, as YYYY-MM-DD (default: today)
+    ///
+    /// Pantry items whose expiry date falls before it are not subtracted
+    /// from the list, because they will have gone off by then.
+    ///
+    /// Examples:
+    ///   cook shopping-list --cook-date 2025-06-14 "Sunday Roast"
+    #[arg(long, value_name = "DATE")]
+    cook_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Subcommand)]
+enum ShoppingListCommand {
+    /// Manage the shopping list saved in the collection
+    ///
+    /// The saved list is the one the web UI's shopping list page shows and
+    /// edits: the recipes and menus in `.shopping-list`, and the ingredients
+    /// already ticked off in `.shopping-checked`, both at the top of the
+    /// recipe collection. These commands work on the same files, so a change
+    /// made here shows up in the web UI and the Cooklang apps, and the other
+    /// way round.
+    ///
+    /// Examples:
+    ///   cook shopping-list saved add "Easy Pancakes:2"   # Put a double batch on the list
+    ///   cook shopping-list saved add "Week 1.menu"       # Put a whole menu on the list
+    ///   cook shopping-list saved show                    # What to buy, with ticks
+    ///   cook shopping-list saved check eggs              # Tick eggs off
+    ///   cook shopping-list saved clear                   # Start again
+    Saved(saved::SavedArgs),
+}
+
+/// Where the aisle and pantry configuration come from, for every command that
+/// builds a list.
+#[derive(Debug, Args)]
+struct ConfigArgs {
     /// Load aisle configuration file
     ///
     /// The aisle file groups ingredients into categories (produce, dairy, etc.)
@@ -114,14 +185,6 @@ pub struct ShoppingListArgs {
     #[arg(long, value_hint = clap::ValueHint::FilePath)]
     pantry: Option<Utf8PathBuf>,
 
-    /// Don't expand referenced recipes
-    ///
-    /// By default, recipes referenced from within a recipe (via @./other.cook)
-    /// are expanded and their ingredients included. This flag treats each recipe
-    /// in isolation.
-    #[arg(short, long)]
-    ignore_references: bool,
-
     /// Don't subtract pantry items from the shopping list
     ///
     /// By default, ingredients found in the pantry configuration are subtracted
@@ -130,9 +193,104 @@ pub struct ShoppingListArgs {
     #[arg(long)]
     ignore_pantry: bool,
 
-    /// Display only ingredient names, one per line, without amounts
-    #[arg(long)]
-    ingredients_only: bool,
+    /// Lay the list out for a particular shop
+    ///
+    /// Names a store profile, `config/stores/<NAME>.conf` (or the same in the
+    /// global config directory), that puts the aisles in the order the shop
+    /// has them, uses its names for them, and moves ingredients it shelves
+    /// somewhere else. Only the categorised list changes; --plain ignores it.
+    ///
+    /// Examples:
+    ///   cook shopping-list --store lidl "Neapolitan Pizza"
+    ///   cook shopping-list saved show --store costco
+    #[arg(long, value_name = "NAME")]
+    store: Option<String>,
+}
+
+impl ConfigArgs {
+    /// `ctx` with these flags applied.
+    ///
+    /// An explicit flag wins, and otherwise the context's own search order
+    /// (local `config/`, then the global config directory) stands.
+    /// `--ignore-pantry` skips the pantry altogether, which core spells as "no
+    /// pantry configuration". `--store` has no default to fall back on: a
+    /// profile is used only when it is named.
+    ///
+    /// Fails only for a `--store` that names no profile.
+    fn apply(self, ctx: &Context) -> Result<Context> {
+        let mut core_ctx = ctx.clone();
+        if let Some(name) = &self.store {
+            // Named by hand, so a profile that is not there is an error
+            // rather than a list in the wrong order.
+            let profile = store_profile(core_ctx.base_path(), name).map_err(cli_error)?;
+            core_ctx = core_ctx.with_store(profile);
+        }
+        if let Some(path) = self.aisle {
+            core_ctx = core_ctx.with_aisle(ConfigSource::Path(path));
+        }
+        if self.ignore_pantry {
+            tracing::debug!("Pantry ignored via --ignore-pantry");
+            core_ctx = core_ctx.with_pantry(ConfigSource::None);
+        } else if let Some(path) = self.pantry {
+            // A pantry the user named by hand that cannot be read is fatal:
+            // they asked for it, so silently shopping for things they already
+            // own would be the wrong answer.
+            core_ctx = core_ctx.with_pantry(ConfigSource::Path(path));
+        } else if let Err(e) = core_ctx.pantry().read() {
+            // A merely discovered pantry is different: nobody asked for it, so
+            // an unreadable one is a warning and the list is built without it.
+            // The probe costs one extra read of a small file, and is what
+            // keeps the distinction honest — checking that the path exists is
+            // not enough, because a file can exist and still refuse to be
+            // read.
+            warn!("Failed to read pantry file: {e}");
+            core_ctx = core_ctx.with_pantry(ConfigSource::None);
+        }
+        Ok(core_ctx)
+    }
+}
+
+/// Counting the list in the packages the shop sells, for every command that
+/// builds a list.
+#[derive(Debug, Args)]
+struct PackageArgs {
+    /// Count ingredients in the packages they are sold in
+    ///
+    /// Points at the same per-ingredient datastore `cook report --datastore`
+    /// reads. An ingredient whose `<DIR>/<ingredient>/shopping.yml` has a
+    /// `package_size` (a count such as `6`, or a quantity such as `200%ml`)
+    /// is shown as the number of packages to buy and what is left of the
+    /// last one, e.g. `2 × 200 ml (150 ml left over)`. Sizes are matched
+    /// against the amounts the recipes give in the same unit; units are not
+    /// converted.
+    ///
+    /// Examples:
+    ///   cook shopping-list --datastore db "Easy Pancakes"
+    ///   cook shopping-list saved show --datastore db -f json
+    #[arg(long, value_name = "DIR", value_hint = clap::ValueHint::DirPath)]
+    datastore: Option<Utf8PathBuf>,
+}
+
+impl PackageArgs {
+    /// Count `list` in packages if a datastore was given, logging what could
+    /// not be counted.
+    fn apply(&self, list: &mut AggregatedList) {
+        if let Some(datastore) = &self.datastore {
+            log_diagnostics(&list.round_to_packages(datastore));
+        }
+    }
+}
+
+/// Core returns its warnings instead of logging them, so that a library
+/// consumer can show them its own way. Naming the file they came from is this
+/// boundary's job — one list can draw on many recipes.
+fn log_diagnostics(diagnostics: &[cookcli_core::Diagnostic]) {
+    for diagnostic in diagnostics {
+        match diagnostic.location.as_ref().and_then(|l| l.file.as_ref()) {
+            Some(file) => warn!("{file}: {}", diagnostic.message),
+            None => warn!("{}", diagnostic.message),
+        }
+    }
 }
 
 impl ShoppingListArgs {
@@ -147,9 +305,21 @@ enum OutputFormat {
     Json,
     Yaml,
     Markdown,
+    /// One row per quantity: category, item, quantity, unit, recipes.
+    Csv,
+    /// Checkbox lines under each category, for printing.
+    Checklist,
+    /// One todo.txt task per ingredient.
+    Todo,
+    /// An iCalendar file with a VTODO per ingredient.
+    Ics,
 }
 
 pub fn run(ctx: &Context, args: ShoppingListArgs) -> Result<()> {
+    if let Some(ShoppingListCommand::Saved(saved_args)) = args.command {
+        return saved::run(ctx, saved_args);
+    }
+
     // Expand directories to .cook files
     let mut expanded_recipes = Vec::new();
     for entry in &args.recipes {
@@ -192,35 +362,13 @@ pub fn run(ctx: &Context, args: ShoppingListArgs) -> Result<()> {
         expanded_recipes = args.recipes.clone();
     }
 
-    // Aisle and pantry resolution: an explicit flag wins, and otherwise the
-    // context's own search order (local `config/`, then the global config
-    // directory) stands. `--ignore-pantry` skips the pantry altogether, which
-    // core spells as "no pantry configuration".
-    let mut core_ctx = ctx.clone();
-    if let Some(path) = args.aisle {
-        core_ctx = core_ctx.with_aisle(ConfigSource::Path(path));
-    }
-    if args.ignore_pantry {
-        tracing::debug!("Pantry ignored via --ignore-pantry");
-        core_ctx = core_ctx.with_pantry(ConfigSource::None);
-    } else if let Some(path) = args.pantry {
-        // A pantry the user named by hand that cannot be read is fatal: they
-        // asked for it, so silently shopping for things they already own would
-        // be the wrong answer.
-        core_ctx = core_ctx.with_pantry(ConfigSource::Path(path));
-    } else if let Err(e) = core_ctx.pantry().read() {
-        // A merely discovered pantry is different: nobody asked for it, so an
-        // unreadable one is a warning and the list is built without it. The
-        // probe costs one extra read of a small file, and is what keeps the
-        // distinction honest — checking that the path exists is not enough,
-        // because a file can exist and still refuse to be read.
-        warn!("Failed to read pantry file: {e}");
-        core_ctx = core_ctx.with_pantry(ConfigSource::None);
-    }
+    let core_ctx = args.config.apply(ctx)?;
 
     let format = args.format.unwrap_or_else(|| match &args.output {
         Some(p) => match p.extension() {
             Some("json") => OutputFormat::Json,
+            Some("csv") => OutputFormat::Csv,
+            Some("ics") => OutputFormat::Ics,
             _ => OutputFormat::Human,
         },
         None => OutputFormat::Human,
@@ -243,21 +391,15 @@ pub fn run(ctx: &Context, args: ShoppingListArgs) -> Result<()> {
         GenerateRequest {
             recipes,
             ignore_references: args.ignore_references,
+            cook_date: args.cook_date,
         },
     )
     .map_err(cli_error)?;
 
-    // Core returns its warnings instead of logging them, so that a library
-    // consumer can show them its own way. Naming the file they came from is
-    // this boundary's job — one list can draw on many recipes.
-    for diagnostic in &outcome.diagnostics {
-        match diagnostic.location.as_ref().and_then(|l| l.file.as_ref()) {
-            Some(file) => warn!("{file}: {}", diagnostic.message),
-            None => warn!("{}", diagnostic.message),
-        }
-    }
+    log_diagnostics(&outcome.diagnostics);
 
-    let list = outcome.value;
+    let mut list = outcome.value;
+    args.packages.apply(&mut list);
 
     write_to_output(args.output.as_deref(), |w| {
         if args.ingredients_only {
@@ -285,9 +427,20 @@ pub fn run(ctx: &Context, args: ShoppingListArgs) -> Result<()> {
                     serde_yaml::to_writer(w, &ingredients)?;
                 }
                 OutputFormat::Markdown => {
-                    let value = fmt::build_md_value(list, args.plain, args.ingredients_only);
+                    let value = fmt::build_md_value(
+                        list,
+                        args.plain,
+                        args.ingredients_only,
+                        args.show_sources,
+                    );
                     write!(w, "{value}")?;
                 }
+                OutputFormat::Csv
+                | OutputFormat::Checklist
+                | OutputFormat::Todo
+                | OutputFormat::Ics => {
+                    write_export(w, format, list, args.plain, args.ingredients_only)?
+                }
             }
         } else {
             match format {
@@ -295,7 +448,8 @@ pub fn run(ctx: &Context, args: ShoppingListArgs) -> Result<()> {
                     // `Style::Ansi` keeps the green category headings the CLI
                     // has always printed; `write_to_output` strips them again
                     // for file output.
-                    let table = fmt::build_human_table(list, args.plain, Style::Ansi);
+                    let table =
+                        fmt::build_human_table(list, args.plain, Style::Ansi, args.show_sources);
                     write!(w, "{table}")?;
                 }
                 OutputFormat::Json => {
@@ -312,11 +466,45 @@ pub fn run(ctx: &Context, args: ShoppingListArgs) -> Result<()> {
                     serde_yaml::to_writer(w, &value)?;
                 }
                 OutputFormat::Markdown => {
-                    let value = fmt::build_md_value(list, args.plain, args.ingredients_only);
+                    let value = fmt::build_md_value(
+                        list,
+                        args.plain,
+                        args.ingredients_only,
+                        args.show_sources,
+                    );
                     write!(w, "{value}")?;
                 }
+                OutputFormat::Csv
+                | OutputFormat::Checklist
+                | OutputFormat::Todo
+                | OutputFormat::Ics => {
+                    write_export(w, format, list, args.plain, args.ingredients_only)?
+                }
             }
         }
         Ok(())
     })
 }
+
+/// Write one of the export formats, which take `--ingredients-only` as
+/// leaving the amounts out of an otherwise unchanged list — except CSV,
+/// whose columns are fixed and always filled.
+fn write_export(
+    w: &mut dyn std::io::Write,
+    format: OutputFormat,
+    list: AggregatedList,
+    plain: bool,
+    ingredients_only: bool,
+) -> Result<()> {
+    let value = match format {
+        OutputFormat::Csv => fmt::build_csv(list, plain),
+        OutputFormat::Checklist => fmt::build_checklist(list, plain, ingredients_only),
+        OutputFormat::Todo => fmt::build_todo_txt(list, plain, ingredients_only),
+        OutputFormat::Ics => fmt::build_ics(list, plain, ingredients_only, chrono::Utc::now()),
+        OutputFormat::Human | OutputFormat::Json | OutputFormat::Yaml | OutputFormat::Markdown => {
+            unreachable!("only the export formats are written here")
+        }
+    };
+    write!(w, "{value}")?;
+    Ok(())
+}
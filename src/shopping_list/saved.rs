@@ -0,0 +1,854 @@
+//! `cook shopping-list saved`: the shopping list kept in the collection.
+//!
+//! The web server has always driven [`ShoppingListStore`]; these commands
+//! drive the same store, so that a script, a cron job or an SSH session can
+//! manage the list the web UI and the Cooklang apps share. Entries are written
+//! the way the web UI writes them — a recipe by its path without extension,
+//! with the references it will follow stored beneath it, and a menu by its
+//! `.menu` path with its recipes beneath it — so neither side can tell which
+//! one added an entry.
+//!
+//! Things that come from no recipe — toilet paper, birthday candles — go on
+//! with `add-item`, in the free-hand form the Cooklang apps write them in.
+//!
+//! `--list` picks one of the collection's named lists instead of the default
+//! one; every subcommand takes it.
+//!
+//! Every change is logged under the user's login name, and can be taken back
+//! with `undo` or `restore`.
+//!
+//! Once the shopping is home, `commit` puts what was ticked off into the
+//! pantry.
+
+use anyhow::{bail, Context as _, Result};
+use camino::{Utf8Path, Utf8PathBuf};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use clap::{Args, Subcommand, ValueEnum};
+use cookcli_core::{
+    find,
+    shopping_list::{
+        commit_to_pantry, generate_saved, recipe_display_name, saved_ingredient_names,
+        CommitRequest, ItemSource, ManualItem, Packages, ShoppingListStore, StoredEntry,
+        DEFAULT_LIST,
+    },
+    ConfigSource,
+};
+use serde::Serialize;
+use std::collections::HashSet;
+use tracing::warn;
+use yansi::Paint;
+
+use super::{log_diagnostics, ConfigArgs, PackageArgs};
+use crate::{
+    util::{
+        cli_error,
+        menu_scale::{menu_shopping_entries, resolve_recipe_info},
+        parse_recipe_from_entry, split_recipe_name_and_scaling_factor, write_to_output,
+    },
+    Context,
+};
+
+#[derive(Debug, Args)]
+pub struct SavedArgs {
+    /// Which list to work on
+    ///
+    /// A collection can keep several lists — one per shop, say — each with
+    /// its own ticks. A list springs into being the first time something is
+    /// added to it. Names are lowercase letters, digits, `-` and `_`.
+    #[arg(short, long, global = true, default_value = DEFAULT_LIST, value_name = "NAME")]
+    list: String,
+
+    #[command(subcommand)]
+    command: SavedCommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum SavedCommand {
+    /// Put recipes or menus on the saved shopping list
+    ///
+    /// A recipe goes on with every recipe it references, as the web UI adds
+    /// it with all of its references ticked. A menu goes on as one entry with
+    /// its recipes beneath it, each scaled the way the menu asks for it, so
+    /// that removing the menu later removes all of them.
+    ///
+    /// Adding a recipe that is already on the list adds it again: that is how
+    /// to ask for a second batch.
+    ///
+    /// Examples:
+    ///   cook shopping-list saved add "Easy Pancakes"
+    ///   cook shopping-list saved add "Breakfast/Easy Pancakes:2"
+    ///   cook shopping-list saved add "Week 1.menu"
+    #[command(alias = "a")]
+    Add(AddArgs),
+
+    /// Take a recipe or menu off the saved shopping list
+    ///
+    /// Removes the first entry for it, and forgets any ticks on ingredients
+    /// that nothing left on the list needs.
+    ///
+    /// Examples:
+    ///   cook shopping-list saved remove "Easy Pancakes"
+    ///   cook shopping-list saved remove "Week 1.menu"
+    #[command(alias = "rm")]
+    Remove(RemoveArgs),
+
+    /// Move a recipe or menu to another list
+    ///
+    /// The entry keeps its scale and everything beneath it. Ticks on
+    /// ingredients nothing left on this list needs are forgotten, as for
+    /// `remove`.
+    ///
+    /// Examples:
+    ///   cook shopping-list saved move "Easy Pancakes" --to costco
+    ///   cook shopping-list saved --list party move Punch --to default
+    #[command(alias = "mv")]
+    Move(MoveArgs),
+
+    /// Put something that is not from a recipe on the saved shopping list
+    ///
+    /// It is listed, ticked and cleared like any ingredient, and adds up
+    /// with the recipes' amounts of the same thing. Without `--category` it
+    /// is categorised by the aisle configuration.
+    ///
+    /// Examples:
+    ///   cook shopping-list saved add-item "toilet paper" --quantity 4 --category household
+    ///   cook shopping-list saved add-item "birthday candles"
+    ///   cook shopping-list saved add-item milk --quantity 2%l
+    AddItem(AddItemArgs),
+
+    /// Take something added with `add-item` off the saved shopping list
+    ///
+    /// Removes the first item of that name, matched without regard to case.
+    ///
+    /// Examples:
+    ///   cook shopping-list saved remove-item "toilet paper"
+    RemoveItem(RemoveItemArgs),
+
+    /// List what was put on the saved shopping list with `add-item`
+    Items(ListArgs),
+
+    /// List the recipes and menus on the saved shopping list
+    ///
+    /// Each entry is shown with its scale, and a menu with the recipes
+    /// beneath it.
+    #[command(alias = "ls")]
+    List(ListArgs),
+
+    /// List the collection's shopping lists
+    ///
+    /// `default` always comes first, then the named lists alphabetically,
+    /// each with how many entries it holds.
+    Lists(ListArgs),
+
+    /// Tick ingredients off the saved shopping list
+    ///
+    /// Names are matched without regard to case, as the web UI matches them.
+    ///
+    /// Examples:
+    ///   cook shopping-list saved check eggs milk
+    Check(CheckArgs),
+
+    /// Put ticked-off ingredients back on the saved shopping list
+    Uncheck(CheckArgs),
+
+    /// Empty the saved shopping list and forget every tick
+    Clear,
+
+    /// Take back the last change to the saved shopping list
+    ///
+    /// Adding, removing, moving and clearing can each be undone, one at a
+    /// time, back through the last 50 changes. Undoing a clear brings back
+    /// the ticks too. Ticking and unticking are not changes in this sense:
+    /// untick instead.
+    ///
+    /// Examples:
+    ///   cook shopping-list saved undo
+    Undo,
+
+    /// Put the saved shopping list back as it was at a given time
+    ///
+    /// The changes made since are taken back together, and the restore can
+    /// itself be undone.
+    ///
+    /// Examples:
+    ///   cook shopping-list saved restore --at "2026-10-19 09:00"
+    ///   cook shopping-list saved restore --at 2026-10-18
+    Restore(RestoreArgs),
+
+    /// Show who changed the saved shopping list, when, and how
+    History(ListArgs),
+
+    /// Forget ticks on ingredients that nothing on the list needs any more
+    ///
+    /// Every tick and untick is appended to `.shopping-checked`, so it grows
+    /// as the list is used. This rewrites it down to the ticks that still
+    /// matter. Nothing is compacted if any recipe on the list fails to load,
+    /// because ticks that are still current would be thrown away with the
+    /// stale ones.
+    Compact,
+
+    /// Put what has been ticked off into the pantry
+    ///
+    /// Each ticked ingredient is added to the pantry with the amount the
+    /// list asked for and today's date as its `bought` date. One the pantry
+    /// already has is topped up, converting into the unit the pantry uses;
+    /// one it does not have goes under its aisle category. The ticks are
+    /// then taken off, so nothing is counted twice.
+    ///
+    /// Examples:
+    ///   cook shopping-list saved commit
+    ///   cook shopping-list saved commit --date 2026-10-18
+    Commit(CommitArgs),
+
+    /// Show what to buy for the saved shopping list
+    ///
+    /// The list is built as `cook shopping-list` builds one, from the
+    /// recipes and menus saved on it, and each ingredient is shown ticked or
+    /// not.
+    ///
+    /// Examples:
+    ///   cook shopping-list saved show
+    ///   cook shopping-list saved show --unchecked     # Only what is left to buy
+    ///   cook shopping-list saved show -f json
+    Show(ShowArgs),
+}
+
+#[derive(Debug, Args)]
+struct AddArgs {
+    /// Recipes or menus to add, by name or path, each with an optional
+    /// scaling factor using the :N syntax
+    #[arg(required = true, value_name = "RECIPE")]
+    recipes: Vec<String>,
+}
+
+#[derive(Debug, Args)]
+struct RemoveArgs {
+    /// Recipe or menu to remove, spelled as `saved list` shows it; the
+    /// `.cook` and `.menu` extensions are optional
+    #[arg(value_name = "RECIPE")]
+    recipe: String,
+}
+
+#[derive(Debug, Args)]
+struct MoveArgs {
+    /// Recipe or menu to move, spelled as for `remove`
+    #[arg(value_name = "RECIPE")]
+    recipe: String,
+
+    /// The list to move it to
+    #[arg(long, value_name = "NAME")]
+    to: String,
+}
+
+#[derive(Debug, Args)]
+struct AddItemArgs {
+    /// What to buy
+    #[arg(value_name = "NAME")]
+    name: String,
+
+    /// How much, written as in a recipe: 4, 500%g, "2 packs"
+    #[arg(short, long, value_name = "QUANTITY")]
+    quantity: Option<String>,
+
+    /// The aisle category to list it under, in place of the aisle
+    /// configuration's
+    #[arg(short, long, value_name = "CATEGORY")]
+    category: Option<String>,
+}
+
+#[derive(Debug, Args)]
+struct RemoveItemArgs {
+    /// The item's name
+    #[arg(value_name = "NAME")]
+    name: String,
+}
+
+#[derive(Debug, Args)]
+struct RestoreArgs {
+    /// The time to go back to, in local time unless an offset is given:
+    /// `2026-10-19 09:00`, `2026-10-19` for the start of that day, or RFC 3339
+    #[arg(long, value_name = "TIME", value_parser = parse_time)]
+    at: DateTime<Utc>,
+}
+
+#[derive(Debug, Args)]
+struct CommitArgs {
+    /// The date to record the items as bought on, as YYYY-MM-DD; today if
+    /// not given
+    #[arg(long, value_name = "DATE")]
+    date: Option<NaiveDate>,
+
+    /// Aisle configuration file to take the pantry sections from
+    #[arg(short, long, value_hint = clap::ValueHint::FilePath)]
+    aisle: Option<Utf8PathBuf>,
+
+    /// Pantry configuration file to write to
+    ///
+    /// If not specified, the pantry `cook pantry` uses; one is created under
+    /// `./config/` if there is none.
+    #[arg(long, value_hint = clap::ValueHint::FilePath)]
+    pantry: Option<Utf8PathBuf>,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value = "human")]
+    format: SavedFormat,
+}
+
+#[derive(Debug, Args)]
+struct ListArgs {
+    /// Output format
+    #[arg(short, long, value_enum, default_value = "human")]
+    format: SavedFormat,
+}
+
+#[derive(Debug, Args)]
+struct CheckArgs {
+    /// Ingredient names
+    #[arg(required = true, value_name = "INGREDIENT")]
+    names: Vec<String>,
+}
+
+#[derive(Debug, Args)]
+struct ShowArgs {
+    /// Output format
+    #[arg(short, long, value_enum, default_value = "human")]
+    format: SavedFormat,
+
+    /// Display ingredients without aisle categories
+    #[arg(short, long)]
+    plain: bool,
+
+    /// Leave out ingredients already ticked off
+    #[arg(long)]
+    unchecked: bool,
+
+    /// Under each ingredient, list the recipes that need it and how much
+    /// each (always included in JSON)
+    #[arg(long)]
+    show_sources: bool,
+
+    #[command(flatten)]
+    config: ConfigArgs,
+
+    #[command(flatten)]
+    packages: PackageArgs,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum SavedFormat {
+    Human,
+    Json,
+}
+
+pub fn run(ctx: &Context, args: SavedArgs) -> Result<()> {
+    let store = open(ctx, &args.list)?;
+    match args.command {
+        SavedCommand::Add(args) => run_add(ctx, &store, args),
+        SavedCommand::Remove(args) => run_remove(ctx, &store, args),
+        SavedCommand::Move(args) => run_move(ctx, &store, args),
+        SavedCommand::AddItem(args) => {
+            let name = args.name.trim().to_string();
+            store
+                .add_item(ManualItem {
+                    name: args.name,
+                    quantity: args.quantity,
+                    category: args.category,
+                })
+                .map_err(cli_error)?;
+            println!("Added '{name}' to {}.", list_label(&store));
+            Ok(())
+        }
+        SavedCommand::RemoveItem(args) => {
+            if !store.remove_item(&args.name).map_err(cli_error)? {
+                bail!("'{}' is not on {}", args.name, list_label(&store));
+            }
+            println!("Removed '{}' from {}.", args.name, list_label(&store));
+            compact_after(ctx, &store, "remove-item");
+            Ok(())
+        }
+        SavedCommand::Items(args) => run_items(&store, args),
+        SavedCommand::List(args) => run_list(&store, args),
+        SavedCommand::Lists(args) => run_lists(ctx, args),
+        SavedCommand::Check(args) => {
+            for name in &args.names {
+                store.check(name).map_err(cli_error)?;
+                println!("Checked '{name}'.");
+            }
+            Ok(())
+        }
+        SavedCommand::Uncheck(args) => {
+            for name in &args.names {
+                store.uncheck(name).map_err(cli_error)?;
+                println!("Unchecked '{name}'.");
+            }
+            Ok(())
+        }
+        SavedCommand::Clear => {
+            store.clear().map_err(cli_error)?;
+            println!("Cleared {}.", list_label(&store));
+            Ok(())
+        }
+        SavedCommand::Undo => {
+            let undone = store.undo().map_err(cli_error)?;
+            log_diagnostics(&undone.diagnostics);
+            match undone.value {
+                Some(change) => println!("Undid '{}' on {}.", change.what, list_label(&store)),
+                None => println!("Nothing to undo on {}.", list_label(&store)),
+            }
+            Ok(())
+        }
+        SavedCommand::Restore(args) => {
+            let outcome = store.restore(args.at).map_err(cli_error)?;
+            log_diagnostics(&outcome.diagnostics);
+            let undone = outcome.value;
+            if undone.is_empty() {
+                println!(
+                    "{} has not changed since then.",
+                    sentence(&list_label(&store))
+                );
+            } else {
+                for change in &undone {
+                    println!("Undid '{}'.", change.what);
+                }
+                println!(
+                    "Restored {} as it was at {}.",
+                    list_label(&store),
+                    args.at.with_timezone(&Local).format("%Y-%m-%d %H:%M")
+                );
+            }
+            Ok(())
+        }
+        SavedCommand::History(args) => run_history(&store, args),
+        SavedCommand::Compact => {
+            let names = saved_ingredient_names(ctx, &store).map_err(cli_error)?;
+            log_diagnostics(&names.diagnostics);
+            store.compact(names.value).map_err(cli_error)?;
+            Ok(())
+        }
+        SavedCommand::Commit(args) => run_commit(ctx, &store, args),
+        SavedCommand::Show(args) => run_show(ctx, &store, args),
+    }
+}
+
+fn run_add(ctx: &Context, store: &ShoppingListStore, args: AddArgs) -> Result<()> {
+    let base_path = ctx.base_path();
+    for arg in &args.recipes {
+        let (name, scale) = split_recipe_name_and_scaling_factor(arg).unwrap_or((arg, 1.0));
+        let entry = find::get_recipe(base_path, name).map_err(cli_error)?;
+        let path = stored_path(base_path, entry.path().map(|p| p.as_path()), name)?;
+
+        if path.ends_with(".menu") {
+            // Parsed at 1.0: the menu's references are turned into scales
+            // from their quantities as authored, then multiplied by `scale`.
+            let menu = parse_recipe_from_entry(&entry, 1.0)?;
+            let recipes = menu_shopping_entries(base_path, &menu, scale);
+            store
+                .add_menu(path.clone(), scale, recipes)
+                .map_err(cli_error)?;
+        } else {
+            let sub_refs = resolve_recipe_info(base_path, &path)?.sub_refs;
+            store
+                .add(StoredEntry {
+                    name: recipe_display_name(&path),
+                    path: path.clone(),
+                    scale,
+                    included_references: Some(sub_refs),
+                    recipes: None,
+                })
+                .map_err(cli_error)?;
+        }
+        println!(
+            "Added '{}' to {}.",
+            recipe_display_name(&path),
+            list_label(store)
+        );
+    }
+    Ok(())
+}
+
+/// The path an entry is stored under: relative to the collection, without the
+/// `.cook` extension for a recipe — which is how the web UI spells it — and
+/// with `.menu` for a menu, which is what marks it as one on load.
+fn stored_path(base_path: &Utf8Path, file: Option<&Utf8Path>, name: &str) -> Result<String> {
+    let Some(file) = file else {
+        bail!("'{name}' has no file to put on the shopping list");
+    };
+    let relative = file.strip_prefix(base_path).with_context(|| {
+        format!("'{file}' is outside the collection at '{base_path}', so it cannot be saved")
+    })?;
+    let relative = relative.as_str().replace('\\', "/");
+    Ok(match relative.strip_suffix(".cook") {
+        Some(recipe) => recipe.to_string(),
+        None => relative,
+    })
+}
+
+fn run_remove(ctx: &Context, store: &ShoppingListStore, args: RemoveArgs) -> Result<()> {
+    let entry = find_entry(store, &args.recipe)?;
+    store.remove(&entry.path).map_err(cli_error)?;
+    println!("Removed '{}' from {}.", entry.name, list_label(store));
+    compact_after(ctx, store, "remove");
+    Ok(())
+}
+
+fn run_move(ctx: &Context, store: &ShoppingListStore, args: MoveArgs) -> Result<()> {
+    let to = open(ctx, &args.to)?;
+    let entry = find_entry(store, &args.recipe)?;
+    store.move_entry(&entry.path, &to).map_err(cli_error)?;
+    println!("Moved '{}' to {}.", entry.name, list_label(&to));
+    compact_after(ctx, store, "move");
+    Ok(())
+}
+
+/// The entry `recipe` names on `store`.
+fn find_entry(store: &ShoppingListStore, recipe: &str) -> Result<StoredEntry> {
+    let wanted = comparable(recipe);
+    let entries = store.load().map_err(cli_error)?;
+    // An exact spelling wins; otherwise a menu answers to its name alone, as
+    // `saved list` shows it.
+    let found = entries
+        .iter()
+        .position(|entry| comparable(&entry.path) == wanted)
+        .or_else(|| {
+            entries
+                .iter()
+                .position(|entry| entry.path.strip_suffix(".menu") == Some(wanted))
+        });
+    match found {
+        Some(index) => Ok(entries.into_iter().nth(index).expect("found above")),
+        None => bail!("'{recipe}' is not on {}", list_label(store)),
+    }
+}
+
+/// The list called `name`, with its changes logged as made by whoever is
+/// logged in.
+pub(crate) fn open(ctx: &Context, name: &str) -> Result<ShoppingListStore> {
+    let store = ShoppingListStore::named(ctx.base_path(), name).map_err(cli_error)?;
+    Ok(
+        match std::env::var("USER").or_else(|_| std::env::var("USERNAME")) {
+            Ok(user) if !user.is_empty() => store.acting_as(user),
+            _ => store,
+        },
+    )
+}
+
+/// A `--at` time: RFC 3339, or a local date with an optional time of day.
+fn parse_time(text: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(at) = DateTime::parse_from_rfc3339(text) {
+        return Ok(at.with_timezone(&Utc));
+    }
+    let local = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })
+        .ok_or_else(|| format!("'{text}' is not a time like '2026-10-19 09:00' or a date"))?;
+    Local
+        .from_local_datetime(&local)
+        .earliest()
+        .map(|at| at.with_timezone(&Utc))
+        .ok_or_else(|| format!("'{text}' does not exist in the local time zone"))
+}
+
+/// `the shopping list` as the start of a sentence.
+fn sentence(label: &str) -> String {
+    let mut chars = label.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// How messages refer to `store`: "the shopping list" while there is only
+/// the one, its name otherwise.
+fn list_label(store: &ShoppingListStore) -> String {
+    if store.name() == DEFAULT_LIST {
+        "the shopping list".to_string()
+    } else {
+        format!("the '{}' list", store.name())
+    }
+}
+
+/// Drop the ticks on ingredients only the entry just taken off `store`
+/// needed.
+///
+/// Best-effort, as it is for the web UI: the entry is gone either way, and a
+/// list that no longer loads must not cost the ticks that are still current.
+fn compact_after(ctx: &Context, store: &ShoppingListStore, what: &str) {
+    match saved_ingredient_names(ctx, store) {
+        Ok(names) => {
+            if let Err(e) = store.compact(names.value) {
+                warn!("Failed to compact checked items after {what}: {e}");
+            }
+        }
+        Err(e) => warn!("Skipping compact after {what}: {e}"),
+    }
+}
+
+/// A stored or typed path, reduced to what two spellings of the same entry
+/// share: no `./`, no `.cook`.
+fn comparable(path: &str) -> &str {
+    let path = path.strip_prefix("./").unwrap_or(path);
+    path.strip_suffix(".cook").unwrap_or(path)
+}
+
+fn run_commit(ctx: &Context, store: &ShoppingListStore, args: CommitArgs) -> Result<()> {
+    let mut core_ctx = ctx.clone();
+    if let Some(path) = args.aisle {
+        core_ctx = core_ctx.with_aisle(ConfigSource::Path(path));
+    }
+    if let Some(path) = args.pantry {
+        core_ctx = core_ctx.with_pantry(ConfigSource::Path(path));
+    }
+    let outcome = commit_to_pantry(&core_ctx, store, CommitRequest { bought: args.date })
+        .map_err(cli_error)?;
+    log_diagnostics(&outcome.diagnostics);
+    let committed = outcome.value;
+
+    write_to_output(None, |w| {
+        match args.format {
+            SavedFormat::Json => serde_json::to_writer_pretty(&mut *w, &committed)?,
+            SavedFormat::Human if committed.is_empty() => {
+                writeln!(w, "Nothing is ticked off {}.", list_label(store))?;
+            }
+            SavedFormat::Human => {
+                for item in &committed {
+                    let verb = if item.added { "Added" } else { "Topped up" };
+                    let at = if item.added { "to" } else { "in" };
+                    write!(w, "{verb} {} {at} [{}]", item.name, item.section)?;
+                    match &item.stock {
+                        Some(stock) => writeln!(w, ", now {stock}.")?,
+                        None => writeln!(w, ".")?,
+                    }
+                }
+            }
+        }
+        Ok(())
+    })
+}
+
+fn run_list(store: &ShoppingListStore, args: ListArgs) -> Result<()> {
+    let entries = store.load().map_err(cli_error)?;
+    write_to_output(None, |w| {
+        match args.format {
+            SavedFormat::Json => serde_json::to_writer_pretty(&mut *w, &entries)?,
+            SavedFormat::Human => {
+                for entry in &entries {
+                    writeln!(w, "{}", entry_line(entry))?;
+                    for recipe in entry.recipes.iter().flatten() {
+                        writeln!(w, "    {}", entry_line(recipe))?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    })
+}
+
+fn run_items(store: &ShoppingListStore, args: ListArgs) -> Result<()> {
+    let items = store.items().map_err(cli_error)?;
+    write_to_output(None, |w| {
+        match args.format {
+            SavedFormat::Json => serde_json::to_writer_pretty(&mut *w, &items)?,
+            SavedFormat::Human => {
+                let mut table = tabular::Table::new("{:<} {:<} {:<}");
+                for item in &items {
+                    table.add_row(
+                        tabular::Row::new()
+                            .with_cell(&item.name)
+                            .with_cell(item.quantity.as_deref().unwrap_or_default())
+                            .with_cell(
+                                item.category
+                                    .as_deref()
+                                    .map(|category| format!("[{}]", category.green()))
+                                    .unwrap_or_default(),
+                            ),
+                    );
+                }
+                write!(w, "{table}")?;
+            }
+        }
+        Ok(())
+    })
+}
+
+fn run_history(store: &ShoppingListStore, args: ListArgs) -> Result<()> {
+    let log = store.log().map_err(cli_error)?;
+    write_to_output(None, |w| {
+        match args.format {
+            SavedFormat::Json => serde_json::to_writer_pretty(&mut *w, &log)?,
+            SavedFormat::Human => {
+                let mut table = tabular::Table::new("{:<} {:<} {:<}");
+                for change in &log {
+                    table.add_row(
+                        tabular::Row::new()
+                            .with_cell(
+                                change
+                                    .at
+                                    .with_timezone(&Local)
+                                    .format("%Y-%m-%d %H:%M:%S")
+                                    .to_string(),
+                            )
+                            .with_cell(change.by.as_deref().unwrap_or("-"))
+                            .with_cell(&change.what),
+                    );
+                }
+                write!(w, "{table}")?;
+            }
+        }
+        Ok(())
+    })
+}
+
+fn run_lists(ctx: &Context, args: ListArgs) -> Result<()> {
+    let mut lists = Vec::new();
+    for name in ShoppingListStore::list_names(ctx.base_path()).map_err(cli_error)? {
+        let store = ShoppingListStore::named(ctx.base_path(), &name).map_err(cli_error)?;
+        let entries = store.load().map_err(cli_error)?.len();
+        lists.push(serde_json::json!({ "name": name, "entries": entries }));
+    }
+    write_to_output(None, |w| {
+        match args.format {
+            SavedFormat::Json => serde_json::to_writer_pretty(&mut *w, &lists)?,
+            SavedFormat::Human => {
+                let mut table = tabular::Table::new("{:<} {:>}");
+                for list in &lists {
+                    table.add_row(
+                        tabular::Row::new()
+                            .with_cell(list["name"].as_str().unwrap_or_default())
+                            .with_cell(list["entries"].to_string()),
+                    );
+                }
+                write!(w, "{table}")?;
+            }
+        }
+        Ok(())
+    })
+}
+
+/// `Easy Pancakes ×2 (Breakfast/Easy Pancakes)`, the scale left out at 1.
+fn entry_line(entry: &StoredEntry) -> String {
+    let mut line = entry.name.clone();
+    if entry.scale != 1.0 {
+        line.push_str(&format!(" ×{}", entry.scale));
+    }
+    if entry.path != entry.name {
+        line.push_str(&format!(" ({})", entry.path));
+    }
+    line
+}
+
+/// One ingredient of `saved show -f json`.
+#[derive(Serialize)]
+struct ShownItem<'a> {
+    name: &'a str,
+    quantities: &'a [String],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    packages: Option<&'a Packages>,
+    /// What the human table shows: the packages, then what they don't say.
+    #[serde(skip)]
+    amount: Vec<String>,
+    checked: bool,
+    sources: &'a [ItemSource],
+}
+
+/// `Pizza › sauces/tomato`: the recipe on the list, then the references
+/// the ingredient came through.
+fn source_label(source: &ItemSource) -> String {
+    std::iter::once(source.recipe.as_str())
+        .chain(source.via.iter().map(String::as_str))
+        .collect::<Vec<_>>()
+        .join(" › ")
+}
+
+/// One category of `saved show -f json`.
+#[derive(Serialize)]
+struct ShownCategory<'a> {
+    name: &'a str,
+    items: Vec<ShownItem<'a>>,
+}
+
+fn run_show(ctx: &Context, store: &ShoppingListStore, args: ShowArgs) -> Result<()> {
+    let core_ctx = args.config.apply(ctx)?;
+    let outcome = generate_saved(&core_ctx, store).map_err(cli_error)?;
+    log_diagnostics(&outcome.diagnostics);
+    let mut list = outcome.value;
+    args.packages.apply(&mut list);
+    let checked: HashSet<String> = store.checked_set().map_err(cli_error)?;
+
+    // `--plain` is one category with no name, which the output below then
+    // leaves unheaded.
+    let groups: Vec<(&str, &[cookcli_core::shopping_list::ListItem])> = if args.plain {
+        vec![("", list.items.as_slice())]
+    } else {
+        list.categories
+            .iter()
+            .map(|category| (category.name.as_str(), category.items.as_slice()))
+            .collect()
+    };
+    let categories: Vec<ShownCategory> = groups
+        .into_iter()
+        .map(|(name, items)| ShownCategory {
+            name,
+            items: items
+                .iter()
+                .map(|item| ShownItem {
+                    name: &item.name,
+                    quantities: &item.quantities,
+                    packages: item.packages.as_ref(),
+                    amount: item.amount(),
+                    checked: checked.contains(&item.name.to_lowercase()),
+                    sources: &item.sources,
+                })
+                .filter(|item| !(args.unchecked && item.checked))
+                .collect(),
+        })
+        .filter(|category| !category.items.is_empty())
+        .collect();
+
+    write_to_output(None, |w| {
+        match args.format {
+            SavedFormat::Json => {
+                let value = if args.plain {
+                    serde_json::json!({
+                        "items": categories.first().map(|c| c.items.as_slice()).unwrap_or_default()
+                    })
+                } else {
+                    serde_json::json!({ "categories": categories })
+                };
+                serde_json::to_writer_pretty(&mut *w, &value)?;
+            }
+            SavedFormat::Human => {
+                let mut table = tabular::Table::new("{:<} {:<} {:<}");
+                for category in &categories {
+                    if !category.name.is_empty() {
+                        table.add_heading(format!("[{}]", category.name.green()));
+                    }
+                    for item in &category.items {
+                        table.add_row(
+                            tabular::Row::new()
+                                .with_cell(if item.checked { "[x]" } else { "[ ]" })
+                                .with_cell(item.name)
+                                .with_cell(item.amount.join(", ")),
+                        );
+                        if args.show_sources {
+                            for source in item.sources {
+                                table.add_row(
+                                    tabular::Row::new()
+                                        .with_cell("")
+                                        .with_cell(format!("  ↳ {}", source_label(source)))
+                                        .with_cell(source.quantities.join(", ")),
+                                );
+                            }
+                        }
+                    }
+                }
+                write!(w, "{table}")?;
+            }
+        }
+        Ok(())
+    })
+}
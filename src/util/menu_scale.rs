@@ -1,21 +1,23 @@
 //! Turning a menu's `@./Some Recipe{target%unit}` reference into a scale
 //! multiplier for the recipe it points at.
 //!
-//! Three callers need this and must agree: the menu JSON API, the shopping
-//! list's `add_menu`, and the HTML menu page (which is also the static export).
+//! Four callers need this and must agree: the menu JSON API, the shopping
+//! list's `add_menu`, `cook shopping-list saved add`, and the HTML menu page
+//! (which is also the static export). The two that put a menu on the saved
+//! shopping list share [`menu_shopping_entries`].
 //! It lives in `util` rather than next to the server handlers because
 //! `crate::server` is behind the `server` feature while `crate::web` is not.
 
 use camino::Utf8Path;
+use cookcli_core::shopping_list::{recipe_display_name, StoredEntry};
+use cooklang::Recipe;
 
 /// Information about a referenced recipe needed to interpret a `{target%unit}`
 /// pointed at it.
 #[derive(Default)]
 pub struct RecipeInfo {
-    /// Recipes this one references in turn. Only the shopping list's
-    /// `add_menu` reads this, and that lives behind the `server` feature,
-    /// so it is genuinely dead code in a `--no-default-features` build.
-    #[cfg_attr(not(feature = "server"), allow(dead_code))]
+    /// Recipes this one references in turn, stored with it on the shopping
+    /// list so that all of them are followed.
     pub sub_refs: Vec<String>,
     /// Numeric `servings` metadata. The cooklang API only exposes this as
     /// `u32`, so fractional defaults like `servings: 1.5` appear as `None`
@@ -83,6 +85,54 @@ pub fn ref_info_or_default(base_path: &Utf8Path, lookup: &str, ref_display: &str
     }
 }
 
+/// The entries a menu puts on the saved shopping list: one per recipe it
+/// references, scaled by the reference's `{target%unit}` times `menu_scale`,
+/// and carrying that recipe's own references so they are all followed.
+///
+/// `menu` must be parsed at scale 1.0, for the reason
+/// [`reference_scale_factor`] gives. A referenced recipe that cannot be read
+/// still gets its entry, at the reference's raw multiplier and with no
+/// references of its own; the warning says which.
+pub fn menu_shopping_entries(
+    base_path: &Utf8Path,
+    menu: &Recipe,
+    menu_scale: f64,
+) -> Vec<StoredEntry> {
+    let mut recipes = Vec::new();
+    for ingredient in &menu.ingredients {
+        let Some(recipe_ref) = &ingredient.reference else {
+            continue;
+        };
+        let ref_display = if recipe_ref.components.is_empty() {
+            recipe_ref.name.clone()
+        } else {
+            format!("{}/{}", recipe_ref.components.join("/"), recipe_ref.name)
+        };
+        let lookup = recipe_ref.path(cookcli_core::REFERENCE_SEPARATOR);
+        let info = ref_info_or_default(base_path, &lookup, &ref_display);
+
+        // Storing a raw multiplier without this conversion was a bug: a
+        // 2-serving recipe referenced as `{3%servings}` got stored as `{3}`
+        // and scaled to 6 servings instead of 3.
+        let scale =
+            reference_scale_factor(ingredient.quantity.as_ref(), &info, &ref_display) * menu_scale;
+
+        // Strip the `./` for storage; the format writer adds it back.
+        let path = ref_display
+            .strip_prefix("./")
+            .unwrap_or(&ref_display)
+            .to_string();
+        recipes.push(StoredEntry {
+            name: recipe_display_name(&path),
+            path,
+            scale,
+            included_references: Some(info.sub_refs),
+            recipes: None,
+        });
+    }
+    recipes
+}
+
 /// Convert a menu reference's `{target%unit}` into a scale multiplier for the
 /// referenced recipe, per the Cooklang spec's "Scaling Referenced Recipes"
 /// (conventions.md). The unit decides how `target` is interpreted:
@@ -29,6 +29,7 @@
 // SOFTWARE.
 
 pub mod menu_scale;
+pub mod notify;
 
 // The formatters and the parser now live in `cookcli-core`. Re-exported here
 // so the rest of the CLI keeps reaching them as `crate::util::format::..` and
@@ -127,6 +128,17 @@ pub fn cli_error(error: cookcli_core::CoreError) -> anyhow::Error {
         // lowercase like every other `CoreError`, and already names the item
         // and section, so the only thing left is the capital letter.
         CoreError::PantryEdit { message } => anyhow::anyhow!("{}", sentence_case(&message)),
+        // `cook shopping-list saved --list` with a name that cannot be a file
+        // name, and `--store` naming no profile. Only the capital letter is
+        // missing.
+        error @ (CoreError::ShoppingListName { .. }
+        | CoreError::ShoppingListItem { .. }
+        | CoreError::ShoppingListHistory { .. }
+        | CoreError::PantryHistory { .. }
+        | CoreError::PantryImport { .. }
+        | CoreError::UnknownStore { .. }) => {
+            anyhow::anyhow!("{}", sentence_case(&error.to_string()))
+        }
         // Named here only for the capital letter: the variant carries no
         // source, so nothing is lost by converting it to a message. `cook
         // doctor validate` is what makes this reachable — a missing or
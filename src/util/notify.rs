@@ -0,0 +1,130 @@
+//! Sending pantry notifications to where `notify.toml` says.
+//!
+//! Shared by `cook pantry notify` and the server's `--notify-every` job,
+//! which must send the same thing the same way. It lives in `util` rather
+//! than next to either because `crate::server` is behind the `server`
+//! feature.
+//!
+//! Both hooks are sent the same JSON:
+//!
+//! ```json
+//! {
+//!   "message": "milk (dairy) expires tomorrow\nhoney (cupboard) is running out",
+//!   "events": [{ "event": "expiring", "section": "dairy", "name": "milk", ... }]
+//! }
+//! ```
+//!
+//! The command gets it on its standard input, and the message and number of
+//! events in `COOK_NOTIFY_MESSAGE` and `COOK_NOTIFY_COUNT` for the scripts
+//! that only want a line of text. The webhook gets it as the body of a POST.
+
+use anyhow::{bail, Context as _, Result};
+use cookcli_core::pantry::{Notifications, PantryEvent};
+use serde::Serialize;
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+
+/// How long the command has to finish, or a webhook to answer, before the
+/// attempt counts as failed. A command still running then is killed, so one
+/// that hangs cannot hold up the server's job for good.
+const HOOK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// What both hooks are sent.
+#[derive(Debug, Serialize)]
+pub struct Payload<'a> {
+    pub message: String,
+    pub events: &'a [PantryEvent],
+}
+
+impl<'a> Payload<'a> {
+    pub fn new(notifications: &'a Notifications) -> Self {
+        Self {
+            message: notifications.message(),
+            events: &notifications.events,
+        }
+    }
+}
+
+/// Run `command` and post to `webhook`, whichever are given, with `payload`.
+///
+/// Both are tried even if the first fails, and then the first failure is
+/// returned. The caller should not mark the events as notified after an
+/// error, so they are sent again next time — to both, including the one that
+/// worked; a duplicate is better than a gap.
+pub async fn deliver(
+    command: Option<&str>,
+    webhook: Option<&str>,
+    payload: &Payload<'_>,
+) -> Result<()> {
+    let json = serde_json::to_vec(payload)?;
+    let ran = match command {
+        Some(command) => run_command(command, payload, &json).await,
+        None => Ok(()),
+    };
+    let posted = match webhook {
+        Some(url) => post(url, json).await,
+        None => Ok(()),
+    };
+    ran.and(posted)
+}
+
+async fn run_command(command: &str, payload: &Payload<'_>, json: &[u8]) -> Result<()> {
+    let mut shell = if cfg!(windows) {
+        let mut shell = tokio::process::Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = tokio::process::Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    let mut child = shell
+        .arg(command)
+        .env("COOK_NOTIFY_MESSAGE", &payload.message)
+        .env("COOK_NOTIFY_COUNT", payload.events.len().to_string())
+        .stdin(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run notify command `{command}`"))?;
+    let stdin = child.stdin.take();
+    let finished = tokio::time::timeout(HOOK_TIMEOUT, async {
+        if let Some(mut stdin) = stdin {
+            // A command that never reads its input closes the pipe early; that
+            // is its business, and its exit status still says whether it
+            // worked.
+            let _ = stdin.write_all(json).await;
+        }
+        child.wait().await
+    })
+    .await;
+    let Ok(status) = finished else {
+        let _ = child.kill().await;
+        bail!(
+            "Notify command `{command}` did not finish within {} seconds and was stopped",
+            HOOK_TIMEOUT.as_secs()
+        );
+    };
+    let status = status.with_context(|| format!("Failed to run notify command `{command}`"))?;
+    if !status.success() {
+        bail!("Notify command `{command}` failed: {status}");
+    }
+    Ok(())
+}
+
+#[cfg(feature = "webhook")]
+async fn post(url: &str, json: Vec<u8>) -> Result<()> {
+    reqwest::Client::new()
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(json)
+        .timeout(HOOK_TIMEOUT)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .with_context(|| format!("Failed to post to notify webhook {url}"))?;
+    Ok(())
+}
+
+#[cfg(not(feature = "webhook"))]
+async fn post(url: &str, _json: Vec<u8>) -> Result<()> {
+    bail!("Cannot post to notify webhook {url}: this build of cook was made without the `webhook` feature")
+}
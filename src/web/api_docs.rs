@@ -138,6 +138,19 @@ impl EndpointDoc {
         self
     }
 
+    /// The `?list=` parameter every endpoint touching the stored shopping list
+    /// accepts; appended after any body parameters.
+    fn on_list(mut self) -> Self {
+        self.params.push(param(
+            "list",
+            "query",
+            "string",
+            false,
+            "Which named shopping list to use. Omit for `default`.",
+        ));
+        self
+    }
+
     fn requires(mut self, feature: &str) -> Self {
         self.feature = Some(feature.to_string());
         self
@@ -152,6 +165,8 @@ pub fn sections() -> Vec<ApiSection> {
         shopping_list(),
         pantry(),
         search_and_stats(),
+        doctor(),
+        graph(),
         realtime(),
         sync(),
     ]
@@ -515,9 +530,14 @@ fn shopping_list() -> ApiSection {
         "Two distinct things live here. `POST /api/shopping_list` is stateless: send recipes, \
          get an aggregated ingredient list back. Everything else operates on the server's \
          persistent list, stored as `.shopping-list` and `.shopping-checked` in the recipe \
-         directory. Most of the endpoints that mutate the stored list respond `200 OK` with an \
-         empty body — only `GET /api/shopping_list/items`, `GET /api/shopping_list/checked`, \
-         and the stateless `POST /api/shopping_list` return JSON. (A third GET lives under \
+         directory. A collection can keep several named lists side by side — `weekly`, \
+         `party`, `costco` — each in its own `.shopping-list.<name>` / \
+         `.shopping-checked.<name>` pair; every endpoint below takes `?list=<name>` to pick \
+         one, and without it works on the `default` list. A name is 1–64 lowercase letters, \
+         digits, `-` or `_`; anything else is a 400. Most of the endpoints that mutate the stored list respond `200 OK` with an \
+         empty body — only `GET /api/shopping_list/items`, `GET /api/shopping_list/manual`, \
+         `GET /api/shopping_list/checked`, `GET /api/shopping_list/history`, `undo`, \
+         `restore`, `commit`, and the stateless `POST /api/shopping_list` return JSON. (A third GET lives under \
          this path in the router, `/api/shopping_list/events`, but it's a Server-Sent Events \
          stream, not JSON — see the Realtime section.)",
         vec![
@@ -525,13 +545,30 @@ fn shopping_list() -> ApiSection {
                 "POST",
                 "/api/shopping_list",
                 "Aggregate ingredients across recipes",
-                "Stateless — nothing is stored. Ingredients with the same name are combined and \
-                 unit-converted, then grouped into aisle categories from `aisle.conf`; a \
+                "Stateless — nothing is stored. The list is the one `cook shopping-list` builds \
+                 from the same recipes and configuration. Ingredients with the same name are \
+                 combined and unit-converted, then grouped into aisle categories from `aisle.conf`; a \
                  category with no matching entries is omitted from `categories` entirely, and \
                  ingredients that match no aisle category land in `other`, sorted \
-                 alphabetically. Quantities are reduced by anything in `pantry.conf`; \
-                 `pantry_items` lists the ingredient names that were found there (with a \
-                 nonzero or `unlim` quantity) and subtracted. `checked` echoes the server's \
+                 alphabetically. Quantities are reduced by anything in `pantry.conf` that \
+                 has not expired by today, converting between units of mass or volume; \
+                 `pantry_items` lists the ingredient names the pantry covers, wholly or in \
+                 part. An item the pantry covers only in part has `in_pantry`, how much of \
+                 it is already there, and `quantities` is then what is left to buy. Both are \
+                 display strings, `200 g`, as the command line shows them. An ingredient \
+                 whose `db/<ingredient>/shopping.yml` gives a `package_size` has `packages`, \
+                 as `cook shopping-list --datastore db` counts them: `count`, `size` and \
+                 `leftover`. `amount` is what the page shows: the packages, in place of the \
+                 quantity they were counted from, then the rest of `quantities`. An \
+                 ingredient the pantry has none of but has a substitute for from \
+                 `substitutes.conf` is left off the list and listed in `pantry_items`, and \
+                 `substitutions` says what stood in for it: `ingredient`, and `with`, every \
+                 pantry item the substitute needs. Every item has \
+                 `sources`: each recipe that asked for it, the referenced recipes it came \
+                 through (`via`, outermost first, empty for the recipe's own ingredients) and \
+                 that recipe's own amount as display strings, before the pantry. Items added by hand to \
+                 the stored list `?list=` names are aggregated with the recipes, in the category \
+                 they were given if any, with empty `sources`. `checked` echoes the server's \
                  current persistent checked state, unrelated to the recipes in this request.",
             )
             .params(vec![
@@ -556,6 +593,15 @@ fn shopping_list() -> ApiSection {
                     false,
                     "Which sub-recipe references to expand. Omit to include all of them.",
                 ),
+                param(
+                    "store",
+                    "query",
+                    "string",
+                    false,
+                    "A store profile, `config/stores/<name>.conf`, to lay `categories` out \
+                     with: its aisle order, its names for the categories, and the ingredients \
+                     it shelves elsewhere. 404 if there is no such profile.",
+                ),
             ])
             .request(
                 r#"
@@ -574,13 +620,11 @@ fn shopping_list() -> ApiSection {
       "items": [
         {
           "name": "ripe tomatoes",
-          "quantities": [
-            {
-              "scalable": true,
-              "unit": "large",
-              "value": { "type": "number", "value": { "type": "regular", "value": 3.0 } }
-            }
-          ]
+          "sources": [
+            { "recipe": "Caprese", "via": [], "quantities": ["3 large"] }
+          ],
+          "quantities": ["3 large"],
+          "amount": ["3 large"]
         }
       ]
     },
@@ -589,23 +633,20 @@ fn shopping_list() -> ApiSection {
       "items": [
         {
           "name": "fresh mozzarella",
-          "quantities": [
-            {
-              "scalable": false,
-              "unit": "g",
-              "value": { "type": "number", "value": { "type": "regular", "value": 200.0 } }
-            }
-          ]
+          "sources": [
+            { "recipe": "Caprese", "via": [], "quantities": ["200 g"] }
+          ],
+          "quantities": ["200 g"],
+          "packages": { "count": 2, "size": "125 g", "leftover": "50 g" },
+          "amount": ["2 × 125 g (50 g left over)"]
         },
         {
           "name": "mozzarella cheese",
-          "quantities": [
-            {
-              "scalable": false,
-              "unit": "g",
-              "value": { "type": "number", "value": { "type": "regular", "value": 200.0 } }
-            }
-          ]
+          "sources": [
+            { "recipe": "Neapolitan Pizza", "via": [], "quantities": ["200 g"] }
+          ],
+          "quantities": ["200 g"],
+          "amount": ["200 g"]
         }
       ]
     },
@@ -614,23 +655,20 @@ fn shopping_list() -> ApiSection {
       "items": [
         {
           "name": "tipo zero flour",
-          "quantities": [
-            {
-              "scalable": false,
-              "unit": "g",
-              "value": { "type": "number", "value": { "type": "regular", "value": 1680.0 } }
-            }
-          ]
+          "sources": [
+            { "recipe": "Neapolitan Pizza", "via": [], "quantities": ["1680 g"] }
+          ],
+          "quantities": ["1180 g"],
+          "amount": ["1180 g"],
+          "in_pantry": ["500 g"]
         },
         {
           "name": "fresh yeast",
-          "quantities": [
-            {
-              "scalable": false,
-              "unit": "g",
-              "value": { "type": "number", "value": { "type": "regular", "value": 3.2 } }
-            }
-          ]
+          "sources": [
+            { "recipe": "Neapolitan Pizza", "via": [], "quantities": ["3.2 g"] }
+          ],
+          "quantities": ["3.2 g"],
+          "amount": ["3.2 g"]
         }
       ]
     },
@@ -639,31 +677,20 @@ fn shopping_list() -> ApiSection {
       "items": [
         {
           "name": "salt",
-          "quantities": [
-            {
-              "scalable": false,
-              "unit": "tsp",
-              "value": {
-                "type": "number",
-                "value": { "type": "fraction", "value": { "whole": 0, "num": 1, "den": 8, "err": 0.0 } }
-              }
-            },
-            {
-              "scalable": false,
-              "unit": "g",
-              "value": { "type": "number", "value": { "type": "regular", "value": 49.2 } }
-            }
-          ]
+          "sources": [
+            { "recipe": "Neapolitan Pizza", "via": [], "quantities": ["49.2 g"] },
+            { "recipe": "Caprese", "via": ["Shared/Vinaigrette"], "quantities": ["0.125 tsp"] }
+          ],
+          "quantities": ["49.2 g", "1/8 tsp"],
+          "amount": ["49.2 g", "1/8 tsp"]
         },
         {
           "name": "black pepper",
-          "quantities": [
-            {
-              "scalable": false,
-              "unit": "tsp",
-              "value": { "type": "number", "value": { "type": "regular", "value": 0.0625 } }
-            }
-          ]
+          "sources": [
+            { "recipe": "Caprese", "via": ["Shared/Vinaigrette"], "quantities": ["0.0625 tsp"] }
+          ],
+          "quantities": ["0.0625 tsp"],
+          "amount": ["0.0625 tsp"]
         }
       ]
     },
@@ -672,65 +699,53 @@ fn shopping_list() -> ApiSection {
       "items": [
         {
           "name": "Dijon mustard",
-          "quantities": [
-            {
-              "scalable": false,
-              "unit": "tsp",
-              "value": {
-                "type": "number",
-                "value": { "type": "fraction", "value": { "whole": 0, "num": 1, "den": 4, "err": 0.0 } }
-              }
-            }
-          ]
+          "sources": [
+            { "recipe": "Caprese", "via": ["Shared/Vinaigrette"], "quantities": ["0.25 tsp"] }
+          ],
+          "quantities": ["1/4 tsp"],
+          "amount": ["1/4 tsp"]
         },
         {
           "name": "honey",
-          "quantities": [
-            {
-              "scalable": false,
-              "unit": "tsp",
-              "value": {
-                "type": "number",
-                "value": { "type": "fraction", "value": { "whole": 0, "num": 1, "den": 4, "err": 0.0 } }
-              }
-            }
-          ]
+          "sources": [
+            { "recipe": "Caprese", "via": ["Shared/Vinaigrette"], "quantities": ["0.25 tsp"] }
+          ],
+          "quantities": ["1/4 tsp"],
+          "amount": ["1/4 tsp"]
         },
         {
           "name": "red wine vinegar",
-          "quantities": [
-            {
-              "scalable": false,
-              "unit": "ml",
-              "value": { "type": "number", "value": { "type": "regular", "value": 10.0 } }
-            }
-          ]
+          "sources": [
+            { "recipe": "Caprese", "via": ["Shared/Vinaigrette"], "quantities": ["10 ml"] }
+          ],
+          "quantities": ["10 ml"],
+          "amount": ["10 ml"]
         }
       ]
     },
     {
       "category": "other",
       "items": [
-        { "name": "basil leaves", "quantities": [] },
+        { "name": "basil leaves", "sources": [{ "recipe": "Caprese", "via": [], "quantities": [] }], "quantities": [] },
         {
           "name": "San Marzano tomato sauce",
-          "quantities": [
-            {
-              "scalable": false,
-              "unit": "tbsp",
-              "value": { "type": "number", "value": { "type": "regular", "value": 10.0 } }
-            }
-          ]
+          "sources": [
+            { "recipe": "Neapolitan Pizza", "via": [], "quantities": ["10 tbsp"] }
+          ],
+          "quantities": ["10 tbsp"],
+          "amount": ["10 tbsp"]
         },
-        { "name": "semolina", "quantities": [] }
+        { "name": "semolina", "sources": [{ "recipe": "Neapolitan Pizza", "via": [], "quantities": [] }], "quantities": [] }
       ]
     }
   ],
   "pantry_items": ["flour", "water", "salt", "tipo zero flour", "olive oil", "fresh basil", "black pepper"],
+  "substitutions": [{ "ingredient": "fresh basil", "with": ["dried basil"] }],
   "checked": []
 }
 "#,
-            ),
+            )
+            .on_list(),
             ep(
                 "GET",
                 "/api/shopping_list/items",
@@ -778,7 +793,8 @@ fn shopping_list() -> ApiSection {
   }
 ]
 "#,
-            ),
+            )
+            .on_list(),
             ep(
                 "POST",
                 "/api/shopping_list/add",
@@ -826,7 +842,8 @@ fn shopping_list() -> ApiSection {
   "included_references": ["Shared/Vinaigrette"]
 }
 "#,
-            ),
+            )
+            .on_list(),
             ep(
                 "POST",
                 "/api/shopping_list/add_menu",
@@ -866,7 +883,8 @@ fn shopping_list() -> ApiSection {
   "scale": 1.0
 }
 "#,
-            ),
+            )
+            .on_list(),
             ep(
                 "POST",
                 "/api/shopping_list/remove",
@@ -886,13 +904,176 @@ fn shopping_list() -> ApiSection {
                 r#"
 { "path": "Salads/Caprese.cook" }
 "#,
-            ),
+            )
+            .on_list(),
+            ep(
+                "GET",
+                "/api/shopping_list/manual",
+                "Read the items added by hand",
+                "Returns what was put on the list without a recipe, in the order it was added. \
+                 `quantity` and `category` are omitted when the item has none.",
+            )
+            .response(
+                r#"
+[
+  { "name": "toilet paper", "quantity": "4", "category": "household" },
+  { "name": "birthday candles" }
+]
+"#,
+            )
+            .on_list(),
+            ep(
+                "POST",
+                "/api/shopping_list/manual/add",
+                "Add an item that is not from a recipe",
+                "Stored in `.shopping-list` as a free-hand line (`toilet paper{4}`), which the \
+                 Cooklang apps read too; a category is kept beside it in \
+                 `.shopping-categories`. The item is aggregated, ticked and cleared like any \
+                 ingredient. 400 for an empty name, one starting `./`, or a name or quantity \
+                 containing braces, a line break, `--` or `[-`. Responds `200 OK` with an \
+                 empty body.",
+            )
+            .params(vec![
+                param("name", "body", "string", true, "What to buy."),
+                param(
+                    "quantity",
+                    "body",
+                    "string",
+                    false,
+                    "How much, as a recipe writes it: `4`, `500%g`, `2 packs`.",
+                ),
+                param(
+                    "category",
+                    "body",
+                    "string",
+                    false,
+                    "The aisle category to list it under, in place of `aisle.conf`'s.",
+                ),
+            ])
+            .request(
+                r#"
+{ "name": "toilet paper", "quantity": "4", "category": "household" }
+"#,
+            )
+            .on_list(),
+            ep(
+                "POST",
+                "/api/shopping_list/manual/remove",
+                "Remove an item added by hand",
+                "Removes the first item of that name, matched without regard to case, then \
+                 compacts the checked log as `remove` does. 404 if there is no such item. \
+                 Responds `200 OK` with an empty body.",
+            )
+            .params(vec![param("name", "body", "string", true, "The item's name.")])
+            .request(
+                r#"
+{ "name": "toilet paper" }
+"#,
+            )
+            .on_list(),
             ep(
                 "POST",
                 "/api/shopping_list/clear",
                 "Empty the stored list",
-                "Removes every recipe and all checked state. Responds `200 OK` with an empty body.",
-            ),
+                "Removes every recipe, every item added by hand and all checked state. \
+                 `POST /api/shopping_list/undo` brings it all back. Responds `200 OK` with an \
+                 empty body.",
+            )
+            .on_list(),
+            ep(
+                "POST",
+                "/api/shopping_list/undo",
+                "Take back the last change",
+                "Every add, remove, move, clear and restore first saves the list as it was, \
+                 ticks included; the last 50 are kept. This puts back the latest and drops it, \
+                 so undoing again goes a further change back. Checking and unchecking are not \
+                 changes in this sense. `undone` is the change taken back, or `null` when \
+                 there is nothing to undo. The change and its undoing are both logged. \
+                 Undoing a move takes it back on both lists; 409 if the other list has \
+                 changed since.",
+            )
+            .response(
+                r#"
+{ "undone": { "at": "2026-10-19T18:30:00Z", "by": "web", "what": "clear" } }
+"#,
+            )
+            .on_list(),
+            ep(
+                "POST",
+                "/api/shopping_list/restore",
+                "Put the list back as it was at a given time",
+                "Takes back every change made after `at` in one go, and answers with them, \
+                 oldest first; empty if there were none. The restore is itself a change that \
+                 `undo` takes back. Restoring to before a move restores the other list to \
+                 before it too. 400 for a time that is not RFC 3339, one further back than \
+                 the kept history reaches, or one before a move to or from a list that has \
+                 changed since.",
+            )
+            .params(vec![param(
+                "at",
+                "body",
+                "string",
+                true,
+                "An RFC 3339 time, e.g. `2026-10-19T09:00:00+02:00`.",
+            )])
+            .request(
+                r#"
+{ "at": "2026-10-19T09:00:00Z" }
+"#,
+            )
+            .response(
+                r#"
+{
+  "undone": [
+    { "at": "2026-10-19T17:02:11Z", "by": "alice", "what": "add Salads/Caprese" },
+    { "at": "2026-10-19T18:30:00Z", "by": "web", "what": "clear" }
+  ]
+}
+"#,
+            )
+            .on_list(),
+            ep(
+                "GET",
+                "/api/shopping_list/history",
+                "Read who changed the list, when, and how",
+                "The append-only log of every change, oldest first, undoing included. `by` is \
+                 `web` for changes made through this API and the user's login name for `cook \
+                 shopping-list saved`; it is omitted when unknown.",
+            )
+            .response(
+                r#"
+[
+  { "at": "2026-10-19T17:02:11Z", "by": "alice", "what": "add Salads/Caprese" },
+  { "at": "2026-10-19T18:30:00Z", "by": "web", "what": "clear" },
+  { "at": "2026-10-19T18:30:04Z", "by": "web", "what": "undo clear" }
+]
+"#,
+            )
+            .on_list(),
+            ep(
+                "POST",
+                "/api/shopping_list/commit",
+                "Move what was bought into the pantry",
+                "Every checked ingredient still on the list goes into `pantry.conf` with the \
+                 amount the list asks for and today's date as `bought`. One the pantry already \
+                 has is topped up, converted into the unit the pantry writes it in; one it does \
+                 not have is added under its aisle category. Only those entries in the file \
+                 change. Their checks are then removed and the checked log compacted, so a \
+                 second commit adds nothing. `committed` lists what went in: `stock` is the \
+                 pantry's quantity now, `added` is true for a new entry. 404 when the server \
+                 has no pantry configuration.",
+            )
+            .response(
+                r#"
+{
+  "committed": [
+    { "name": "milk", "section": "dairy", "bought": ["0.5 l"], "stock": "1000%ml", "added": false },
+    { "name": "tomatoes", "section": "produce", "bought": ["2"], "stock": "2", "added": true }
+  ]
+}
+"#,
+            )
+            .on_list(),
             ep(
                 "POST",
                 "/api/shopping_list/check",
@@ -918,7 +1099,8 @@ fn shopping_list() -> ApiSection {
                 r#"
 { "name": "mozzarella cheese" }
 "#,
-            ),
+            )
+            .on_list(),
             ep(
                 "POST",
                 "/api/shopping_list/uncheck",
@@ -936,7 +1118,8 @@ fn shopping_list() -> ApiSection {
                 r#"
 { "name": "mozzarella cheese" }
 "#,
-            ),
+            )
+            .on_list(),
             ep(
                 "GET",
                 "/api/shopping_list/checked",
@@ -953,7 +1136,8 @@ fn shopping_list() -> ApiSection {
                 r#"
 ["tipo zero flour", "mozzarella cheese"]
 "#,
-            ),
+            )
+            .on_list(),
             ep(
                 "POST",
                 "/api/shopping_list/compact",
@@ -962,6 +1146,72 @@ fn shopping_list() -> ApiSection {
                  longer in it. Refuses to compact (500) if any recipe fails to parse, rather \
                  than wiping checks based on a partial ingredient set. Responds `200 OK` with \
                  an empty body.",
+            )
+            .on_list(),
+            ep(
+                "GET",
+                "/api/shopping_list/lists",
+                "List the named shopping lists",
+                "`default` always comes first, whether or not anything is on it; the named \
+                 lists follow alphabetically. A list exists once something has been added to \
+                 it, and stops existing when it is cleared. `entries` counts top-level \
+                 entries — a menu counts once, however many recipes it holds.",
+            )
+            .response(
+                r#"
+[
+  { "name": "default", "entries": 2 },
+  { "name": "costco", "entries": 1 },
+  { "name": "party", "entries": 3 }
+]
+"#,
+            ),
+            ep(
+                "GET",
+                "/api/shopping_list/stores",
+                "List the store profiles",
+                "The names `POST /api/shopping_list` accepts as `?store=`, sorted: every \
+                 `<name>.conf` in `config/stores/` under the recipe directory and in the \
+                 global configuration directory. `[]` when there are none.",
+            )
+            .response(
+                r#"
+["aldi", "lidl"]
+"#,
+            ),
+            ep(
+                "POST",
+                "/api/shopping_list/move",
+                "Move an entry to another list",
+                "Moves one top-level entry — a recipe with its expanded references, or a whole \
+                 menu — from the list named by `?list=` to `to`, which is created if it doesn't \
+                 exist yet. The destination is written before the entry is taken off the \
+                 source, so a failure part-way leaves it on both lists rather than on neither. \
+                 Checks stay with their list; the source's checked log is compacted \
+                 afterwards, best-effort. Responds `200 OK` with an empty body, 404 if `path` \
+                 is not on the source list, and 400 if `to` is not a valid list name.",
+            )
+            .params(vec![
+                param(
+                    "path",
+                    "body",
+                    "string",
+                    true,
+                    "Recipe path exactly as stored.",
+                ),
+                param(
+                    "to",
+                    "body",
+                    "string",
+                    true,
+                    "Name of the list to move the entry to.",
+                ),
+            ])
+            .on_list()
+            .request(
+                r#"
+{ "path": "Salads/Caprese.cook", "to": "party" }
+"#,
             ),
         ],
     )
@@ -1015,13 +1265,13 @@ fn pantry() -> ApiSection {
                 "POST",
                 "/api/pantry/add",
                 "Add an item",
-                "Creates the section if it does not exist. The response claims success and \
-                 reports a distinct item was appended even when the section already has an \
-                 item of that name — but every write round-trips through a TOML serializer \
-                 that keys each section's items by name, so a second item sharing a name with \
-                 an existing one in the same section silently replaces it on disk rather than \
-                 coexisting. Verified: adding `dup` twice with different quantities to a fresh \
-                 section leaves exactly one `dup` item, with the second call's quantity.",
+                "Creates the section if it does not exist, and `pantry.conf` if there is none \
+                 yet. Only the new entry is written: comments and everything else in the file \
+                 are left as they were, and the change is recorded in the pantry's journal \
+                 (see `GET /api/pantry/history`). Without an `expire`, one is filled in from \
+                 the `shelf life`, `fridge life` or `freezer life` in `db/<name>/meta.yml`, \
+                 when the collection has that file. 409 when the section already has an item \
+                 of that name; use `PUT` to change it.",
             )
             .params(vec![
                 param(
@@ -1087,11 +1337,9 @@ fn pantry() -> ApiSection {
                 "/api/pantry/:section/:name",
                 "Update an item",
                 "Only the fields present in the body are changed; omitted fields keep their \
-                 current values. Returns 404 if the section does not exist. A name that \
-                 matches nothing inside a valid section does not 404 — that case still \
-                 rewrites the file (a no-op) and responds `200` with a success message \
-                 naming the item that was never found. If more than one item in the section \
-                 shares the target name, only the first one is updated.",
+                 current values, and nothing else in `pantry.conf` is rewritten. The change \
+                 is recorded in the pantry's journal. Returns 404 if the section does not \
+                 exist or has no item of that name, and 400 for a body that sets nothing.",
             )
             .params(vec![
                 path_param("section", "Section containing the item."),
@@ -1118,10 +1366,9 @@ fn pantry() -> ApiSection {
                 "DELETE",
                 "/api/pantry/:section/:name",
                 "Remove an item",
-                "The section is deleted too if it becomes empty. Returns 404 if the section \
-                 does not exist, but — like `PUT` — responds `200` with a success message even \
-                 when no item in the section actually has that name; nothing is removed and the \
-                 file is rewritten unchanged.",
+                "The section is deleted too if it becomes empty. The change is recorded in the \
+                 pantry's journal, so it can be undone. Returns 404 if the section does not \
+                 exist or has no item of that name.",
             )
             .params(vec![
                 path_param("section", "Section containing the item."),
@@ -1133,6 +1380,44 @@ fn pantry() -> ApiSection {
   "success": true,
   "message": "Removed butter from fridge"
 }
+"#,
+            ),
+            ep(
+                "POST",
+                "/api/pantry/batch",
+                "Apply several edits at once",
+                "Each edit in `edits` is an object whose `op` says what it does — `add`, \
+                 `update` and `remove` take the fields the endpoints above do, and `move` takes \
+                 an item out of `section` and puts it, attributes and all, into `to`. They are \
+                 applied in order, each to the pantry as the ones before it left it, and \
+                 `pantry.conf` is written once: if any edit is refused, none is made, and the \
+                 400 says which one, counting from 1. The journal records the whole batch as \
+                 one change, so one `POST /api/pantry/undo` takes it back.",
+            )
+            .params(vec![param(
+                "edits",
+                "body",
+                "array",
+                true,
+                "The edits, at least one.",
+            )])
+            .request(
+                r#"
+{
+  "edits": [
+    { "op": "update", "section": "fridge", "name": "milk", "quantity": "1%l" },
+    { "op": "move", "section": "fridge", "name": "butter", "to": "freezer" },
+    { "op": "remove", "section": "fridge", "name": "sour cream" }
+  ]
+}
+"#,
+            )
+            .response(
+                r#"
+{
+  "success": true,
+  "message": "Applied 3 edits"
+}
 "#,
             ),
             ep(
@@ -1179,6 +1464,212 @@ fn pantry() -> ApiSection {
 [
   { "section": "fridge", "name": "yogurt", "low": "2%l" }
 ]
+"#,
+            ),
+            ep(
+                "GET",
+                "/api/pantry/use-up",
+                "Rank recipes by what expiring food they use up",
+                "Recipes using at least one pantry item expiring within `days`, best first, as \
+                 `cook pantry use-up` ranks them. Each expiring item a recipe uses counts for \
+                 more the sooner it expires and the larger `share` of it the recipe would use \
+                 (`1.0` when the amounts cannot be compared); the total is scaled by the \
+                 fraction of the recipe's ingredients in stock, so `missing` ones pull it down. \
+                 `path` is relative to the recipe directory, as `/recipe/{path}` takes it. 404 \
+                 when the server has no pantry configuration.",
+            )
+            .params(vec![
+                param(
+                    "days",
+                    "query",
+                    "number",
+                    false,
+                    "Count items expiring within this many days. Defaults to 7.",
+                ),
+                param(
+                    "limit",
+                    "query",
+                    "number",
+                    false,
+                    "Return only the best `limit` recipes.",
+                ),
+            ])
+            .response(
+                r#"
+[
+  {
+    "name": "Easy Pancakes",
+    "path": "Breakfast/Easy Pancakes.cook",
+    "score": 0.5,
+    "uses": [
+      {
+        "name": "milk",
+        "section": "fridge",
+        "expire_date": "2026-10-19",
+        "days_until_expiry": 0,
+        "share": 0.5
+      }
+    ],
+    "missing": []
+  }
+]
+"#,
+            ),
+            ep(
+                "POST",
+                "/api/pantry/consume",
+                "Take a cooked recipe out of the pantry",
+                "Each ingredient of the recipe, and of the recipes it references, is \
+                 subtracted from the pantry items of the same name, converted into the unit \
+                 the pantry writes it in, as `cook pantry consume` does. No item goes below \
+                 zero, and items kept without a quantity or as `unlim` are left alone. Only \
+                 the quantities that change are rewritten in `pantry.conf`. `empty` and `low` \
+                 flag what is used up or running low; `short` names ingredients the pantry \
+                 had too little of, `not_stocked` those it has no entry for. 404 when the \
+                 server has no pantry configuration or the recipe does not exist.",
+            )
+            .params(vec![
+                param(
+                    "recipe",
+                    "body",
+                    "string",
+                    true,
+                    "Recipe path relative to the collection root, without `.cook`.",
+                ),
+                param(
+                    "scale",
+                    "body",
+                    "number",
+                    false,
+                    "Scale the recipe was cooked at. Defaults to 1.",
+                ),
+                param(
+                    "dry_run",
+                    "body",
+                    "boolean",
+                    false,
+                    "Answer with what would change without writing the pantry.",
+                ),
+            ])
+            .request(
+                r#"
+{
+  "recipe": "Breakfast/Easy Pancakes",
+  "scale": 2
+}
+"#,
+            )
+            .response(
+                r#"
+{
+  "items": [
+    {
+      "name": "milk",
+      "section": "dairy",
+      "used": "0.6%l",
+      "before": "1%l",
+      "after": "0.4%l",
+      "empty": false,
+      "low": false
+    },
+    {
+      "name": "eggs",
+      "section": "dairy",
+      "used": "6",
+      "before": "6",
+      "after": "0",
+      "empty": true,
+      "low": true
+    }
+  ],
+  "short": ["eggs"],
+  "not_stocked": ["flour"]
+}
+"#,
+            ),
+            ep(
+                "GET",
+                "/api/pantry/history",
+                "List changes to the pantry",
+                "Every change recorded in the pantry's journal, `pantry.journal` beside \
+                 `pantry.conf`, oldest first — as `cook pantry history` shows it. `origin` is \
+                 `web` or `cli`; `operation` is `add`, `remove`, `update`, `consume` and the \
+                 recipe, or `undo` and the operation it took back, in which case `undoes` is \
+                 the `id` it undid. Each item carries its TOML value before and after; \
+                 `null` where it was not there. 404 when the server has no pantry \
+                 configuration.",
+            )
+            .params(vec![param(
+                "limit",
+                "query",
+                "number",
+                false,
+                "Only the last this many changes.",
+            )])
+            .response(
+                r#"
+[
+  {
+    "id": 7,
+    "at": "2026-10-19T18:30:00Z",
+    "origin": "web",
+    "operation": "update",
+    "items": [
+      {
+        "section": "dairy",
+        "name": "milk",
+        "before": "{ quantity = \"1%l\", low = \"500%ml\" }",
+        "after": "{ quantity = \"2%l\", low = \"500%ml\" }"
+      }
+    ]
+  }
+]
+"#,
+            ),
+            ep(
+                "POST",
+                "/api/pantry/undo",
+                "Undo the last changes to the pantry",
+                "Puts each item the last `count` changes touched back as it was before them, \
+                 newest first, as `cook pantry undo` does, and records the undo in the journal. \
+                 Changes already undone are skipped, so undoing again goes further back. \
+                 `undone` lists what was taken back; it is empty when there was nothing to \
+                 undo. 409, changing nothing, when an item has been changed again since — by \
+                 hand or otherwise — so that the newer value is not lost. 404 when the server \
+                 has no pantry configuration.",
+            )
+            .params(vec![param(
+                "count",
+                "body",
+                "number",
+                false,
+                "How many changes to take back. Defaults to 1; the body may be omitted.",
+            )])
+            .request(
+                r#"
+{ "count": 1 }
+"#,
+            )
+            .response(
+                r#"
+{
+  "undone": [
+    {
+      "id": 7,
+      "at": "2026-10-19T18:30:00Z",
+      "origin": "web",
+      "operation": "update",
+      "items": [
+        {
+          "section": "dairy",
+          "name": "milk",
+          "before": "{ quantity = \"1%l\", low = \"500%ml\" }",
+          "after": "{ quantity = \"2%l\", low = \"500%ml\" }"
+        }
+      ]
+    }
+  ]
+}
 "#,
             ),
         ],
@@ -1280,6 +1771,151 @@ fn search_and_stats() -> ApiSection {
     )
 }
 
+fn doctor() -> ApiSection {
+    section(
+        "doctor",
+        "Doctor",
+        "The checks behind `cook doctor`, run against the whole collection.",
+        vec![ep(
+            "GET",
+            "/api/doctor/validate",
+            "Validate every recipe",
+            "The report `cook doctor validate --format <format>` prints: every recipe's parser \
+             errors and warnings, with line and column ranges, plus one error per recipe \
+             reference that resolves to nothing. Rules enabled in `doctor.toml` run too, and \
+             their findings carry the rule's id as `code`. Paths are relative to the recipe \
+             directory. \
+             Broken recipes are the payload, not a failure, so this answers 200 for a \
+             collection full of them. `sarif` is JSON too; `junit` answers `application/xml` \
+             and `github` answers plain-text workflow commands.",
+        )
+        .params(vec![param(
+            "format",
+            "query",
+            "string",
+            false,
+            "One of `json` (the default), `sarif`, `junit` or `github`. Anything else returns 400.",
+        )])
+        .response(
+            r#"
+{
+  "broken_references": {
+    "Pizza.cook": ["./Pizza Dough"]
+  },
+  "recipes": [
+    {
+      "diagnostics": [],
+      "path": "Pizza.cook",
+      "references": ["./Pizza Dough"]
+    },
+    {
+      "diagnostics": [
+        {
+          "location": {
+            "file": "Soup.cook",
+            "range": {
+              "end": { "column": 6, "line": 2 },
+              "start": { "column": 6, "line": 2 }
+            },
+            "span": { "end": 12, "start": 12 }
+          },
+          "message": "Invalid ingredient name: is empty",
+          "severity": "error"
+        }
+      ],
+      "path": "Soup.cook",
+      "references": [],
+      "rendered": "Error: Invalid ingredient name: is empty\n  ╭─[Soup.cook]\n..."
+    }
+  ],
+  "summary": {
+    "recipes_with_errors": 2,
+    "recipes_with_warnings": 0,
+    "total_errors": 2,
+    "total_recipes": 2,
+    "total_warnings": 0
+  }
+}
+"#,
+        )],
+    )
+}
+
+fn graph() -> ApiSection {
+    section(
+        "graph",
+        "Reference graph",
+        "Which recipes and menus reference which, as `cook graph` draws it.",
+        vec![ep(
+            "GET",
+            "/api/graph",
+            "Get the reference graph",
+            "Every recipe and menu, and every recipe reference written in one of them, \
+             resolved against the recipe directory. A reference that resolves to nothing has \
+             a `to` of `null`; `line` is where it is written. With `root`, only what that file \
+             reaches is included, and the response names it. Paths are relative to the recipe \
+             directory. `dot` answers Graphviz source as `text/vnd.graphviz` and `mermaid` a \
+             Mermaid flowchart as plain text. A `root` that names no recipe or menu returns \
+             404.",
+        )
+        .params(vec![
+            param(
+                "root",
+                "query",
+                "string",
+                false,
+                "Recipe or menu to start from, relative to the recipe directory, e.g. `Week.menu` or `Mains/Stew`. The whole collection if omitted.",
+            ),
+            param(
+                "depth",
+                "query",
+                "integer",
+                false,
+                "How many references deep to follow from `root`: 0 is the root alone. Unlimited if omitted; ignored without `root`.",
+            ),
+            param(
+                "format",
+                "query",
+                "string",
+                false,
+                "One of `json` (the default), `dot` or `mermaid`. Anything else returns 400.",
+            ),
+        ])
+        .response(
+            r#"
+{
+  "edges": [
+    {
+      "from": "Mains/Stew.cook",
+      "line": 3,
+      "reference": "./Stock",
+      "to": "Stock.cook"
+    },
+    {
+      "from": "Week.menu",
+      "line": 1,
+      "reference": "./Mains/Stew",
+      "to": "Mains/Stew.cook"
+    },
+    {
+      "from": "Week.menu",
+      "line": 2,
+      "reference": "./Curry",
+      "to": null
+    }
+  ],
+  "nodes": [
+    { "kind": "recipe", "path": "Mains/Stew.cook" },
+    { "kind": "recipe", "path": "Stock.cook" },
+    { "kind": "menu", "path": "Week.menu" }
+  ],
+  "root": "Week.menu"
+}
+"#,
+        )],
+    )
+}
+
 fn realtime() -> ApiSection {
     section(
         "realtime",
@@ -1290,11 +1926,11 @@ fn realtime() -> ApiSection {
                 "GET",
                 "/api/shopping_list/events",
                 "Server-sent events for shopping list changes",
-                "Emits a `change` event whenever `.shopping-list` or `.shopping-checked` is \
-                 modified on disk — including by another client or by the `cook` CLI. The \
-                 event's `file` field is `\"list\"` or `\"checked\"`, naming which file changed \
-                 (captured live below by editing the shopping list in a second shell while \
-                 connected). It is not a snapshot of what changed, so the intended pattern is \
+                "Emits a `change` event whenever `.shopping-list` or `.shopping-checked` — or \
+                 the pair of a named list — is modified on disk, including by another client \
+                 or by the `cook` CLI. The event's `file` field is `\"list\"` or \
+                 `\"checked\"`, naming which file changed, and `list` names the list it belongs \
+                 to (`\"default\"` for the unsuffixed pair). It is not a snapshot of what changed, so the intended pattern is \
                  to re-fetch the list on each event rather than to apply a diff. A `ping` \
                  keep-alive comment is sent every 30 seconds. If the filesystem watcher failed \
                  to start, the stream still connects and returns 200 but never emits an event.",
@@ -1302,10 +1938,10 @@ fn realtime() -> ApiSection {
             .response(
                 r#"
 event: change
-data: {"file":"list"}
+data: {"file":"list","list":"default"}
 
 event: change
-data: {"file":"checked"}
+data: {"file":"checked","list":"party"}
 "#,
             ),
             ep(
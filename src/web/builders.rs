@@ -201,6 +201,9 @@ pub struct RecipeBuildInput<'a> {
     pub static_mode: bool,
     pub repo_url: Option<String>,
     pub features: FeatureFlags,
+    /// Whether the server has a pantry that cooking the recipe can draw
+    /// down; the Cooked It button is left out when it has none.
+    pub pantry_configured: bool,
 }
 
 /// Output of [`build_recipe_template`] — either a regular recipe or a menu.
@@ -221,6 +224,7 @@ pub fn build_recipe_template(input: RecipeBuildInput<'_>) -> Result<RecipeBuildO
         static_mode,
         repo_url,
         features,
+        pantry_configured,
     } = input;
 
     let recipe_path_buf = Utf8PathBuf::from(recipe_path);
@@ -776,6 +780,7 @@ pub fn build_recipe_template(input: RecipeBuildInput<'_>) -> Result<RecipeBuildO
         static_mode,
         repo_url,
         features,
+        pantry_configured,
     };
 
     Ok(RecipeBuildOutput::Recipe(Box::new(template)))
@@ -0,0 +1,183 @@
+//! Layout for the `/graph` page.
+//!
+//! The page draws the reference graph as an inline SVG, laid out here rather
+//! than in the browser so it needs no script and no third-party drawing
+//! library. The layout is layered, left to right: a file sits one column to
+//! the right of the nearest file that references it, so a menu comes before
+//! its recipes and a recipe before its sub-recipes. That is all it tries to
+//! be — a collection is a shallow, wide graph, and columns sorted by path
+//! read well enough without crossing-minimisation.
+
+use crate::web::templates::{GraphEdgeView, GraphNodeView};
+use camino::Utf8Path;
+use cookcli_core::graph::{NodeKind, ReferenceGraph};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+/// Width of a node's box, in SVG units.
+const NODE_WIDTH: usize = 200;
+/// Height of a node's box.
+const NODE_HEIGHT: usize = 36;
+/// Gap between columns, where the arrows run.
+const COLUMN_GAP: usize = 80;
+/// Gap between boxes in a column.
+const ROW_GAP: usize = 16;
+/// Longest label drawn whole; longer ones are cut, with the full path in the
+/// box's tooltip.
+const LABEL_CHARS: usize = 26;
+
+/// The graph, placed.
+pub struct GraphLayout {
+    pub width: usize,
+    pub height: usize,
+    pub nodes: Vec<GraphNodeView>,
+    pub edges: Vec<GraphEdgeView>,
+}
+
+/// A box in the drawing: a file, or a reference that leads nowhere.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Key<'a> {
+    File(&'a Utf8Path),
+    Missing(&'a str),
+}
+
+/// Place every file of `graph`, linking each to its page under `prefix`.
+pub fn layout(graph: &ReferenceGraph, prefix: &str) -> GraphLayout {
+    let mut targets: BTreeMap<Key, BTreeSet<Key>> = BTreeMap::new();
+    let mut referenced = BTreeSet::new();
+    for edge in &graph.edges {
+        let to = match &edge.to {
+            Some(to) => Key::File(to),
+            None => Key::Missing(&edge.reference),
+        };
+        targets.entry(Key::File(&edge.from)).or_default().insert(to);
+        referenced.insert(to);
+    }
+
+    // Columns are breadth-first distances from the files nothing references —
+    // or from the root, for a rooted graph. A cycle nothing outside it
+    // references has no such file, so its first unplaced file starts one.
+    let starts = graph
+        .root
+        .as_deref()
+        .map(|root| vec![Key::File(root)])
+        .unwrap_or_else(|| {
+            graph
+                .nodes
+                .iter()
+                .map(|node| Key::File(&node.path))
+                .filter(|key| !referenced.contains(key))
+                .collect()
+        });
+    let mut column: BTreeMap<Key, usize> = BTreeMap::new();
+    let every_file = graph.nodes.iter().map(|node| Key::File(&node.path));
+    for start in starts.into_iter().chain(every_file) {
+        if column.contains_key(&start) {
+            continue;
+        }
+        column.insert(start, 0);
+        let mut queue = VecDeque::from([start]);
+        while let Some(key) = queue.pop_front() {
+            let next = column[&key] + 1;
+            for &to in targets.get(&key).into_iter().flatten() {
+                if let std::collections::btree_map::Entry::Vacant(entry) = column.entry(to) {
+                    entry.insert(next);
+                    queue.push_back(to);
+                }
+            }
+        }
+    }
+
+    let mut columns: BTreeMap<usize, Vec<Key>> = BTreeMap::new();
+    for (&key, &index) in &column {
+        columns.entry(index).or_default().push(key);
+    }
+    let mut position: BTreeMap<Key, (usize, usize)> = BTreeMap::new();
+    for (&index, keys) in &columns {
+        for (row, &key) in keys.iter().enumerate() {
+            position.insert(
+                key,
+                (
+                    index * (NODE_WIDTH + COLUMN_GAP),
+                    row * (NODE_HEIGHT + ROW_GAP),
+                ),
+            );
+        }
+    }
+
+    let kinds: BTreeMap<&Utf8Path, NodeKind> = graph
+        .nodes
+        .iter()
+        .map(|node| (node.path.as_path(), node.kind))
+        .collect();
+    let nodes = position
+        .iter()
+        .map(|(&key, &(x, y))| match key {
+            Key::File(path) => {
+                let name = path.with_extension("");
+                GraphNodeView {
+                    x,
+                    y,
+                    label: shorten(name.as_str()),
+                    title: path.to_string(),
+                    href: Some(format!("{prefix}/recipe/{name}")),
+                    is_menu: kinds.get(path) == Some(&NodeKind::Menu),
+                    is_root: graph.root.as_deref() == Some(path),
+                    is_missing: false,
+                }
+            }
+            Key::Missing(reference) => GraphNodeView {
+                x,
+                y,
+                label: shorten(reference),
+                title: reference.to_string(),
+                href: None,
+                is_menu: false,
+                is_root: false,
+                is_missing: true,
+            },
+        })
+        .collect();
+
+    let edges = targets
+        .iter()
+        .flat_map(|(from, tos)| tos.iter().map(move |to| (from, to)))
+        .filter_map(|(from, to)| {
+            let &(x1, y1) = position.get(from)?;
+            let &(x2, y2) = position.get(to)?;
+            let (x1, y1) = (x1 + NODE_WIDTH, y1 + NODE_HEIGHT / 2);
+            let y2 = y2 + NODE_HEIGHT / 2;
+            // A cubic curve leaving to the right and arriving from the left,
+            // which also gives an arrow back to an earlier column a loop to
+            // follow rather than a line straight through the boxes between.
+            let bend = COLUMN_GAP / 2 + x1.abs_diff(x2) / 4;
+            Some(GraphEdgeView {
+                path: format!(
+                    "M {x1} {y1} C {} {y1}, {} {y2}, {x2} {y2}",
+                    x1 + bend,
+                    x2 as isize - bend as isize,
+                ),
+                is_missing: matches!(to, Key::Missing(_)),
+            })
+        })
+        .collect();
+
+    let columns_used = columns.len().max(1);
+    let rows_used = columns.values().map(Vec::len).max().unwrap_or(1);
+    GraphLayout {
+        width: columns_used * NODE_WIDTH + (columns_used - 1) * COLUMN_GAP,
+        height: rows_used * NODE_HEIGHT + (rows_used - 1) * ROW_GAP,
+        nodes,
+        edges,
+    }
+}
+
+/// Cut a label to [`LABEL_CHARS`], keeping its end — the file's own name —
+/// rather than the directories before it.
+fn shorten(label: &str) -> String {
+    let count = label.chars().count();
+    if count <= LABEL_CHARS {
+        return label.to_string();
+    }
+    let tail: String = label.chars().skip(count - (LABEL_CHARS - 1)).collect();
+    format!("…{tail}")
+}
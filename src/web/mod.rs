@@ -35,6 +35,10 @@ pub mod language;
 pub mod menus;
 pub mod templates;
 
+/// Layout for the `/graph` page. Server-only.
+#[cfg(feature = "server")]
+pub mod graph;
+
 /// API reference content for the `/api-docs` page. Server-only.
 #[cfg(feature = "server")]
 pub mod api_docs;
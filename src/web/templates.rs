@@ -178,6 +178,9 @@ pub struct RecipeTemplate {
     pub static_mode: bool,
     pub repo_url: Option<String>,
     pub features: FeatureFlags,
+    /// Whether the server has a pantry to take the recipe out of; the Cooked
+    /// It button is left out when it has none.
+    pub pantry_configured: bool,
 }
 
 impl RecipeTemplate {
@@ -240,11 +243,21 @@ impl RecipeTemplate {
             })
             .collect();
 
+        // The last card offers to take the recipe out of the pantry when the
+        // page itself does; `null` leaves the button out.
+        let cooked_it = (self.pantry_configured && !self.static_mode).then(|| {
+            serde_json::json!({
+                "recipe": self.recipe_path,
+                "label": self.tr.t("recipe-cooked-it"),
+            })
+        });
+
         let data = serde_json::json!({
             "name": self.recipe.name,
             "scale": self.scale,
             "image": self.image_path,
             "sections": sections,
+            "cookedIt": cooked_it,
         });
 
         // Escape </script> sequences to prevent premature script tag closing
@@ -487,6 +500,9 @@ pub struct MenuTemplate {
 #[template(path = "shopping_list.html")]
 pub struct ShoppingListTemplate {
     pub active: String,
+    /// Whether the server has a pantry to move bought items into; the Mark
+    /// as bought button is left out when it has none.
+    pub pantry_configured: bool,
     pub tr: Tr,
     pub prefix: String,
     pub static_mode: bool,
@@ -558,6 +574,56 @@ pub struct NewTemplate {
     pub features: FeatureFlags,
 }
 
+#[cfg(feature = "server")]
+#[derive(Template)]
+#[template(path = "graph.html")]
+pub struct GraphTemplate {
+    pub active: String,
+    /// The recipe or menu the graph was grown from, as typed in the form.
+    pub root: Option<String>,
+    pub depth: Option<usize>,
+    /// `&root=…&depth=…` for the download links, so they fetch what is
+    /// drawn; empty for the whole collection.
+    pub download_query: String,
+    /// Every recipe and menu, for the root picker.
+    pub files: Vec<String>,
+    pub width: usize,
+    pub height: usize,
+    pub nodes: Vec<GraphNodeView>,
+    pub edges: Vec<GraphEdgeView>,
+    /// Why the graph could not be drawn, e.g. a root that names nothing.
+    pub error: Option<String>,
+    pub tr: Tr,
+    pub prefix: String,
+    pub static_mode: bool,
+    pub repo_url: Option<String>,
+    pub features: FeatureFlags,
+}
+
+/// One box on the graph page, placed by [`crate::web::graph::layout`].
+#[cfg(feature = "server")]
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphNodeView {
+    pub x: usize,
+    pub y: usize,
+    pub label: String,
+    /// The full path, shown on hover.
+    pub title: String,
+    /// The recipe page; `None` for a reference that leads nowhere.
+    pub href: Option<String>,
+    pub is_menu: bool,
+    pub is_root: bool,
+    pub is_missing: bool,
+}
+
+/// One arrow on the graph page, as an SVG path.
+#[cfg(feature = "server")]
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphEdgeView {
+    pub path: String,
+    pub is_missing: bool,
+}
+
 #[cfg(feature = "server")]
 #[derive(Debug, Clone, Serialize)]
 pub struct PantrySection {
@@ -572,6 +638,9 @@ pub struct PantryItem {
     pub quantity: Option<String>,
     pub bought: Option<String>,
     pub expire: Option<String>,
+    /// `expire` as `YYYY-MM-DD`, for the page's filters and calendar, which
+    /// would otherwise each have to read every spelling the file may use.
+    pub expire_date: Option<String>,
     pub low: Option<String>,
 }
 
@@ -92,7 +92,8 @@
         cards.push({
             type: 'done',
             sectionIndex: data.sections.length - 1,
-            name: data.name
+            name: data.name,
+            cookedIt: data.cookedIt
         });
 
         return cards;
@@ -155,6 +156,19 @@
             div.innerHTML = '<h2>Bon Appetit!</h2>' +
                 '<button class="cooking-done-close-btn">Close</button>';
             div.querySelector('.cooking-done-close-btn').addEventListener('click', closeCookingMode);
+            // The recipe page defines cookedIt() when the server has a pantry;
+            // what it took out is shown there once cooking mode closes.
+            if (card.cookedIt && typeof window.cookedIt === 'function') {
+                const cooked = document.createElement('button');
+                cooked.className = 'cooking-done-close-btn cooking-done-cooked-btn';
+                cooked.textContent = card.cookedIt.label;
+                cooked.addEventListener('click', async function() {
+                    cooked.disabled = true;
+                    await window.cookedIt(null, card.cookedIt.recipe);
+                    closeCookingMode();
+                });
+                div.insertBefore(cooked, div.querySelector('.cooking-done-close-btn'));
+            }
         }
 
         return div;
@@ -435,5 +435,252 @@ fn doctor_with_no_subcommand_runs_every_check() {
         .stdout(predicate::str::contains("=== Pantry Check ==="))
         .stdout(predicate::str::contains(
             "10 ingredients from recipes are in your pantry:",
+        ))
+        .stdout(predicate::str::contains("=== Reference Graph ==="))
+        .stdout(predicate::str::contains("=== Ingredient Names ==="));
+}
+
+// ---------------------------------------------------------------------------
+// doctor validate --format
+// ---------------------------------------------------------------------------
+
+/// A collection with one recipe that fails to parse on its second line and
+/// one that references a recipe that does not exist.
+fn broken_collection() -> TempDir {
+    let dir = TempDir::new().unwrap();
+    fs::write(
+        dir.path().join("broken.cook"),
+        "Intro.\nAdd @{1%tsp} to the pot.\n",
+    )
+    .unwrap();
+    fs::write(dir.path().join("dish.cook"), "Make @./nonexistent{}.\n").unwrap();
+    dir
+}
+
+/// Paths are relative to the working directory, so that a CI job running
+/// from the repository root places each annotation on the right file.
+#[test]
+fn github_format_annotates_the_offending_line() {
+    let dir = broken_collection();
+
+    cook(dir.path())
+        .args(["doctor", "validate", "--format", "github"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "::error file=broken.cook,line=2,col=",
+        ))
+        .stdout(predicate::str::contains(
+            "::error file=dish.cook::Missing reference: ./nonexistent",
+        ))
+        // Nothing but workflow commands: the human summary would be noise in
+        // a job log and is not printed.
+        .stdout(predicate::str::contains("Validation Summary").not());
+}
+
+#[test]
+fn json_format_carries_the_summary_the_human_report_prints() {
+    let dir = broken_collection();
+
+    let output = cook(dir.path())
+        .args(["doctor", "validate", "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let value: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    assert_eq!(value["summary"]["total_recipes"], 2);
+    assert_eq!(value["summary"]["recipes_with_errors"], 2);
+    assert_eq!(value["broken_references"]["dish.cook"][0], "./nonexistent");
+    let location = &value["recipes"][0]["diagnostics"][0]["location"];
+    assert_eq!(location["file"], "broken.cook");
+    assert_eq!(location["range"]["start"]["line"], 2);
+}
+
+#[test]
+fn sarif_and_junit_formats_are_well_formed() {
+    let dir = broken_collection();
+
+    let output = cook(dir.path())
+        .args(["doctor", "validate", "--format", "sarif"])
+        .output()
+        .unwrap();
+    let sarif: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(sarif["version"], "2.1.0");
+    assert_eq!(sarif["runs"][0]["results"].as_array().unwrap().len(), 2);
+
+    cook(dir.path())
+        .args(["doctor", "validate", "--format", "junit"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("<?xml"))
+        .stdout(predicate::str::contains("tests=\"2\" failures=\"2\""));
+}
+
+/// `--strict` gates on the same findings whatever the format.
+#[test]
+fn strict_fails_a_machine_readable_validation_too() {
+    let dir = broken_collection();
+
+    cook(dir.path())
+        .args(["doctor", "validate", "--format", "json", "--strict"])
+        .assert()
+        .failure();
+}
+
+// ---------------------------------------------------------------------------
+// doctor.toml lint rules
+// ---------------------------------------------------------------------------
+
+/// A rule enabled in `config/doctor.toml` reports through the ordinary
+/// validation output, named by its rule, with its hint.
+#[test]
+fn lint_rules_from_doctor_toml_are_reported_with_their_hints() {
+    let dir = collection("Add @flour{200}.\n", None, None);
+    fs::create_dir(dir.path().join("config")).unwrap();
+    fs::write(
+        dir.path().join("config").join("doctor.toml"),
+        "[rules.unitless-quantity]\nseverity = \"error\"\n",
+    )
+    .unwrap();
+
+    cook(dir.path())
+        .args(["doctor", "validate"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "❌ quantity 200 of 'flour' has no unit [unitless-quantity]",
+        ))
+        .stdout(predicate::str::contains("hint: write it with a unit"))
+        .stdout(predicate::str::contains(
+            "❌ 1 error(s) found in 1 recipe(s)",
+        ));
+
+    cook(dir.path())
+        .args(["doctor", "validate", "--format", "github", "--strict"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            "::error file=dish.cook,line=1,col=6,endColumn=12,title=unitless-quantity::",
+        ));
+}
+
+/// Without a `doctor.toml` no rule runs: a unitless quantity is valid
+/// Cooklang.
+#[test]
+fn no_lint_rule_runs_without_doctor_toml() {
+    let dir = collection("Add @flour{200}.\n", None, None);
+
+    cook(dir.path())
+        .args(["doctor", "validate", "--strict"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("✅ All recipes are valid!"));
+}
+
+// ---------------------------------------------------------------------------
+// doctor ingredients
+// ---------------------------------------------------------------------------
+
+fn spelled_two_ways(aisle: Option<&str>) -> TempDir {
+    let dir = collection("Beat @eggs{2} with @Parmesan{}.\n", aisle, None);
+    fs::write(
+        dir.path().join("omelette.cook"),
+        "Fry @egg{1} in @olive oil{}.\n",
+    )
+    .unwrap();
+    dir
+}
+
+#[test]
+fn doctor_ingredients_groups_spellings_with_their_locations() {
+    let dir = spelled_two_ways(None);
+
+    cook(dir.path())
+        .args(["doctor", "ingredients"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Scanned 2 recipes, found 4 unique ingredient names",
+        ))
+        .stdout(predicate::str::contains(
+            "1 ingredients are spelled more than one way:",
+        ))
+        .stdout(predicate::str::contains("  egg (plural)"))
+        .stdout(predicate::str::contains("eggs  1 recipe: dish.cook:1:7"))
+        .stdout(predicate::str::contains(
+            "egg   1 recipe: omelette.cook:1:6",
+        ))
+        .stdout(predicate::str::contains("→ use \"egg\" in every recipe"));
+}
+
+#[test]
+fn doctor_ingredients_suggests_the_synonyms_aisle_conf_lacks() {
+    let dir = spelled_two_ways(Some("[dairy]\neggs\n"));
+
+    cook(dir.path())
+        .args(["doctor", "ingredients"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("  eggs (plural)"))
+        .stdout(predicate::str::contains("→ add to aisle.conf: eggs|egg"));
+
+    cook(dir.path())
+        .args(["doctor", "ingredients", "--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"canonical\": \"eggs\""))
+        .stdout(predicate::str::contains(
+            "\"missing_synonyms\": [\n        \"egg\"\n      ]",
+        ));
+}
+
+// ---------------------------------------------------------------------------
+// doctor graph
+// ---------------------------------------------------------------------------
+
+#[test]
+fn doctor_graph_reports_cycles_menus_and_images_with_locations() {
+    let dir = collection("Boil @./dish{}.\n", None, None);
+    fs::write(dir.path().join("Week.menu"), "Monday:\n- @./Gone{}\n").unwrap();
+    fs::write(dir.path().join("dish.4.jpg"), "").unwrap();
+
+    cook(dir.path())
+        .args(["doctor", "graph"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Checked 1 recipes, 1 menus and 1 images",
+        ))
+        .stdout(predicate::str::contains("📄 dish.cook:1:7"))
+        .stdout(predicate::str::contains(
+            "❌ recipe references form a cycle: dish.cook → dish.cook [reference-cycle]",
+        ))
+        .stdout(predicate::str::contains("📄 Week.menu:2:4"))
+        .stdout(predicate::str::contains("[missing-menu-recipe]"))
+        .stdout(predicate::str::contains(
+            "⚠️  image is for step 4, but dish.cook has 1 step(s) [orphaned-image]",
+        ))
+        .stdout(predicate::str::contains(
+            "2 error(s), 1 warning(s), 1 hint(s)",
+        ));
+
+    cook(dir.path())
+        .args(["doctor", "graph", "--strict"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn doctor_graph_passes_a_collection_that_fits_together() {
+    let dir = collection("Mix @flour{}.\n", None, None);
+    fs::write(dir.path().join("dish.jpg"), "").unwrap();
+
+    cook(dir.path())
+        .args(["doctor", "graph", "--strict"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "✅ Recipes, menus and images all fit together",
         ));
 }
@@ -0,0 +1,109 @@
+//! Integration tests for `cook graph`.
+//!
+//! The graph itself and each format are tested in `cookcli-core`; these pin
+//! what only the command decides — which format an output file's extension
+//! picks, how a root and depth are taken, and what an unknown root says.
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn cook(dir: &Path) -> Command {
+    let mut command = Command::cargo_bin("cook").unwrap();
+    command.current_dir(dir);
+    command
+}
+
+/// A menu of a stew, which uses a stock, plus a reference to nothing.
+fn collection() -> TempDir {
+    let dir = TempDir::new().unwrap();
+    fs::create_dir(dir.path().join("Mains")).unwrap();
+    fs::write(
+        dir.path().join("Week.menu"),
+        "- @./Mains/Stew{}\n- @./Curry{}\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("Mains/Stew.cook"),
+        "Simmer @./Stock{} with @carrots{2}.\n",
+    )
+    .unwrap();
+    fs::write(dir.path().join("Stock.cook"), "Boil @bones{1%kg}.\n").unwrap();
+    dir
+}
+
+#[test]
+fn the_whole_collection_is_drawn_as_dot_by_default() {
+    let dir = collection();
+    cook(dir.path())
+        .arg("graph")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("digraph recipes {"))
+        .stdout(predicate::str::contains(
+            "\"Week.menu\" -> \"Mains/Stew.cook\";",
+        ))
+        .stdout(predicate::str::contains(
+            "\"Mains/Stew.cook\" -> \"Stock.cook\";",
+        ))
+        .stdout(predicate::str::contains(
+            "\"Week.menu\" -> \"missing:./Curry\" [style=dashed",
+        ));
+}
+
+#[test]
+fn a_root_and_depth_limit_what_is_drawn() {
+    let dir = collection();
+    let output = cook(dir.path())
+        .args(["graph", "Week.menu", "--depth", "1", "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let value: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(value["root"], "Week.menu");
+    let nodes: Vec<&str> = value["nodes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|node| node["path"].as_str().unwrap())
+        .collect();
+    assert_eq!(nodes, ["Mains/Stew.cook", "Week.menu"]);
+    assert_eq!(value["edges"].as_array().unwrap().len(), 2);
+    assert_eq!(value["edges"][1]["to"], serde_json::Value::Null);
+}
+
+#[test]
+fn the_format_follows_the_output_file_extension() {
+    let dir = collection();
+    cook(dir.path())
+        .args(["graph", "Mains/Stew", "-o", "stew.mmd"])
+        .assert()
+        .success();
+
+    let mermaid = fs::read_to_string(dir.path().join("stew.mmd")).unwrap();
+    assert!(mermaid.starts_with("flowchart LR\n"));
+    assert!(mermaid.contains("([\"Mains/Stew\"])"));
+    assert!(mermaid.contains("class n0 root"));
+}
+
+#[test]
+fn an_unknown_root_is_an_error() {
+    let dir = collection();
+    cook(dir.path())
+        .args(["graph", "Lasagna"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Recipe not found: Lasagna"));
+}
+
+#[test]
+fn depth_without_a_root_is_rejected() {
+    let dir = collection();
+    cook(dir.path())
+        .args(["graph", "--depth", "1"])
+        .assert()
+        .failure();
+}
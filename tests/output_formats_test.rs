@@ -345,6 +345,7 @@ fn test_ingredients_only_output() {
         .current_dir(temp_dir.path())
         .arg("shopping-list")
         .arg("--ingredients-only")
+        .arg("--ignore-pantry")
         .arg("simple.cook")
         .assert()
         .success()
@@ -248,6 +248,65 @@ fn test_pantry_recipes_yaml_format() {
         .stdout(predicate::str::contains("partial_matches:"));
 }
 
+#[test]
+fn test_pantry_use_up_human_format() {
+    let temp_dir = common::setup_test_recipes().unwrap();
+
+    Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .arg("pantry")
+        .arg("use-up")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Recipes That Use Up"))
+        .stdout(predicate::str::contains("Pancakes"))
+        .stdout(predicate::str::contains(
+            "eggs (expires in 2 days, 17% of it)",
+        ));
+}
+
+#[test]
+fn test_pantry_use_up_json_format() {
+    let temp_dir = common::setup_test_recipes().unwrap();
+
+    let output = Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .arg("pantry")
+        .arg("-f")
+        .arg("json")
+        .arg("use-up")
+        .arg("-n")
+        .arg("1")
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let ranked = json.as_array().expect("an array of recipes");
+    assert_eq!(ranked.len(), 1, "--limit keeps only the best");
+    assert!(ranked[0]["score"].as_f64().unwrap() > 0.0);
+    assert!(!ranked[0]["uses"].as_array().unwrap().is_empty());
+}
+
+/// Nothing expires within the window, so nothing is suggested.
+#[test]
+fn test_pantry_use_up_with_nothing_expiring() {
+    let temp_dir = common::setup_test_recipes().unwrap();
+    let pantry = temp_dir.path().join("config").join("pantry.conf");
+    fs::write(&pantry, "[pantry]\nflour = \"1%kg\"\n").unwrap();
+
+    Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .arg("pantry")
+        .arg("use-up")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No recipe uses anything"));
+}
+
 #[test]
 fn test_pantry_with_base_path() {
     let temp_dir = common::setup_test_recipes().unwrap();
@@ -1183,3 +1242,585 @@ fn test_pantry_plan_with_no_recipes() {
         .success()
         .stdout(predicate::str::contains("No recipes found in collection."));
 }
+
+/// `--target` plans for the tagged recipes only, from what the pantry has,
+/// and prices the purchases from the datastore next to the recipes.
+#[test]
+fn test_pantry_plan_for_a_tag_within_a_budget() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("config")).unwrap();
+    fs::create_dir_all(root.join("db/butter")).unwrap();
+    fs::write(root.join("config/pantry.conf"), "[fridge]\neggs = \"6\"\n").unwrap();
+    fs::write(root.join("db/butter/shopping.yml"), "price_per_unit: 2.5\n").unwrap();
+    let weeknight = "---\ntags: [weeknight]\n---\n";
+    fs::write(
+        root.join("omelette.cook"),
+        format!("{weeknight}Whisk @eggs{{3}} in @butter{{10%g}}.\n"),
+    )
+    .unwrap();
+    fs::write(
+        root.join("pasta.cook"),
+        format!("{weeknight}Boil @pasta{{200%g}} with @tomatoes{{2}}.\n"),
+    )
+    .unwrap();
+    fs::write(root.join("roast.cook"), "Roast @beef{2%kg}.\n").unwrap();
+
+    Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(root)
+        .args([
+            "pantry",
+            "plan",
+            "--target",
+            "tag:weeknight",
+            "--budget",
+            "1",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Buying 1 ingredient makes 1 of 2 cookable:",
+        ))
+        .stdout(predicate::str::contains("1. omelette — buy butter (2.50)"))
+        .stdout(predicate::str::contains(
+            "pasta — still needs pasta, tomatoes",
+        ))
+        .stdout(predicate::str::contains("roast").not());
+
+    let output = Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(root)
+        .args(["pantry", "-f", "json", "plan", "-t", "tag:weeknight"])
+        .args(["--budget", "2", "--by-cost"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let json: Value = serde_json::from_slice(&output).expect("Valid JSON output");
+    assert_eq!(
+        json["buy"],
+        serde_json::json!(["pasta", "tomatoes"]),
+        "the unpriced pasta costs nothing, and the butter costs more than 2"
+    );
+    assert_eq!(json["unpriced"], serde_json::json!(["pasta", "tomatoes"]));
+    assert_eq!(json["out_of_budget"][0]["name"], "omelette");
+}
+
+/// A budget with no targets means nothing to the whole-collection plan.
+#[test]
+fn test_pantry_plan_budget_needs_a_target() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+
+    Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .args(["pantry", "plan", "--budget", "3"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--target"));
+}
+
+// ---------------------------------------------------------------------------
+// consume
+// ---------------------------------------------------------------------------
+
+/// A double batch of cake: the flour comes off in the pantry's own unit, the
+/// sugar runs out, and a dry run leaves the file alone.
+#[test]
+fn test_pantry_consume_takes_a_scaled_recipe_out_of_the_pantry() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let config_dir = temp_dir.path().join("config");
+    fs::create_dir(&config_dir).unwrap();
+    let pantry = config_dir.join("pantry.conf");
+    let original = "[baking]\nflour = \"1%kg\"\nsugar = \"150%g\"\n";
+    fs::write(&pantry, original).unwrap();
+    fs::write(
+        temp_dir.path().join("cake.cook"),
+        "Mix @flour{200%g} and @sugar{100%g} with @eggs{2}.\n",
+    )
+    .unwrap();
+
+    Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .args(["pantry", "consume", "cake:2", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("flour [baking]: 1%kg → 0.6%kg"))
+        .stdout(predicate::str::contains("Dry run"));
+    assert_eq!(fs::read_to_string(&pantry).unwrap(), original);
+
+    Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .args(["pantry", "consume", "cake:2"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "sugar [baking]: 150%g → 0%g — used up",
+        ))
+        .stdout(predicate::str::contains(
+            "The pantry did not have enough: sugar",
+        ))
+        .stdout(predicate::str::contains("Not in the pantry: eggs"));
+    assert_eq!(
+        fs::read_to_string(&pantry).unwrap(),
+        "[baking]\nflour = \"0.6%kg\"\nsugar = \"0%g\"\n"
+    );
+}
+
+// ---------------------------------------------------------------------------
+// use and top-up
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_pantry_use_and_top_up_do_the_arithmetic_across_units() {
+    let temp_dir = make_minimal_pantry();
+    let pantry = temp_dir.path().join("config/pantry.conf");
+
+    Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .args(["pantry", "use", "flour", "900%g"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "flour [pantry]: 1%kg → 0.1%kg — running low",
+        ));
+
+    let output = Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .args(["pantry", "-f", "json", "top-up", "flour", "1.5%kg"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["before"], "0.1%kg");
+    assert_eq!(json["after"], "1.6%kg");
+    assert_eq!(json["low"], false);
+
+    assert_eq!(
+        fs::read_to_string(&pantry).unwrap(),
+        "[pantry]\nflour = { quantity = \"1.6%kg\", low = \"200%g\" }\n"
+    );
+
+    Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .args(["pantry", "use", "flour", "2%l"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("does not convert"));
+}
+
+#[test]
+fn test_pantry_add_fills_the_expiry_from_the_datastore() {
+    let temp_dir = make_minimal_pantry();
+    let milk = temp_dir.path().join("db/milk");
+    fs::create_dir_all(&milk).unwrap();
+    fs::write(milk.join("meta.yml"), "shelf life: 1\nfridge life: 7\n").unwrap();
+
+    Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .args(["pantry", "add", "fridge", "milk", "--bought", "2026-03-01"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Expires 2026-03-08, from the ingredient datastore.",
+        ));
+
+    let content = fs::read_to_string(temp_dir.path().join("config/pantry.conf")).unwrap();
+    assert!(content.contains("expire = \"2026-03-08\""), "{content}");
+}
+
+// ---------------------------------------------------------------------------
+// history and undo
+// ---------------------------------------------------------------------------
+
+/// Changes from the command line are journalled as such, and undoing one
+/// puts the file back as it was.
+#[test]
+fn test_pantry_history_and_undo() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let config_dir = temp_dir.path().join("config");
+    fs::create_dir(&config_dir).unwrap();
+    let pantry = config_dir.join("pantry.conf");
+    let original = "# Kept by hand\n[dairy]\nmilk = \"1%l\"\n";
+    fs::write(&pantry, original).unwrap();
+
+    Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .args(["pantry", "update", "dairy", "milk", "--quantity", "2%l"])
+        .assert()
+        .success();
+
+    let output = Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .args(["pantry", "-f", "json", "history"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let journal: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(journal[0]["origin"], "cli");
+    assert_eq!(journal[0]["operation"], "update");
+    assert_eq!(journal[0]["items"][0]["before"], "\"1%l\"");
+    assert_eq!(journal[0]["items"][0]["after"], "\"2%l\"");
+
+    Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .args(["pantry", "undo"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Undid #1 update"));
+    assert_eq!(fs::read_to_string(&pantry).unwrap(), original);
+
+    Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .args(["pantry", "undo"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Nothing to undo."));
+}
+
+/// A collection whose scones need buttermilk, with a pantry holding what
+/// `substitutes.conf` says stands in for it.
+fn make_substituting_collection() -> tempfile::TempDir {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let config_dir = temp_dir.path().join("config");
+    fs::create_dir(&config_dir).unwrap();
+    fs::write(
+        temp_dir.path().join("scones.cook"),
+        "Mix @flour{200%g} with @buttermilk{100%ml}.\n",
+    )
+    .unwrap();
+    fs::write(
+        config_dir.join("pantry.conf"),
+        "[cupboard]\nflour = \"1%kg\"\n\n[fridge]\nmilk = \"1%l\"\nlemon = \"2\"\n",
+    )
+    .unwrap();
+    fs::write(
+        config_dir.join("substitutes.conf"),
+        "buttermilk = [\"milk + lemon\", \"yogurt\"]\n",
+    )
+    .unwrap();
+    temp_dir
+}
+
+#[test]
+fn test_pantry_recipes_counts_a_substitute_and_says_so() {
+    let temp_dir = make_substituting_collection();
+
+    Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .args(["pantry", "recipes"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Complete Matches"))
+        .stdout(predicate::str::contains(
+            "Substituting: buttermilk with milk + lemon",
+        ));
+
+    let output = Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .args(["pantry", "-f", "json", "recipes"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["full_matches"][0], "scones");
+    assert_eq!(
+        json["substitutions"]["scones"][0]["with"],
+        serde_json::json!(["milk", "lemon"])
+    );
+}
+
+#[test]
+fn test_shopping_list_and_doctor_use_pantry_substitutes() {
+    let temp_dir = make_substituting_collection();
+
+    Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .args(["shopping-list", "scones.cook"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[substituted from the pantry]"))
+        .stdout(predicate::str::contains("→ milk + lemon"));
+
+    Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .args(["doctor", "pantry"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "✓ buttermilk (substituted with milk + lemon)",
+        ));
+}
+
+#[test]
+fn test_pantry_export_and_import_round_trip() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let config_dir = temp_dir.path().join("config");
+    fs::create_dir(&config_dir).unwrap();
+    fs::write(
+        config_dir.join("pantry.conf"),
+        "[dairy]\nmilk = { quantity = \"1%l\", low = \"500%ml\" }\n",
+    )
+    .unwrap();
+
+    Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .args(["pantry", "export", "-o", "stock.csv"])
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("stock.csv")).unwrap(),
+        "name,section,quantity,bought,expire,low,restock\r\nmilk,dairy,1%l,,,500%ml,\r\n"
+    );
+
+    let other = tempfile::TempDir::new().unwrap();
+    Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(other.path())
+        .args(["pantry", "import"])
+        .arg(temp_dir.path().join("stock.csv"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Added 1, updated 0"));
+
+    let output = Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(other.path())
+        .args(["pantry", "export", "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let items: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        items,
+        serde_json::json!([
+            { "name": "milk", "section": "dairy", "quantity": "1%l", "low": "500%ml" }
+        ])
+    );
+}
+
+#[test]
+fn test_pantry_import_lists_rejected_rows_and_replace_removes() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let config_dir = temp_dir.path().join("config");
+    fs::create_dir(&config_dir).unwrap();
+    let pantry = config_dir.join("pantry.conf");
+    fs::write(
+        &pantry,
+        "# Kept by hand\n[dairy]\nmilk = \"1%l\"\ncream = \"200%ml\"\n",
+    )
+    .unwrap();
+    fs::write(
+        temp_dir.path().join("stock.json"),
+        r#"[{"name": "milk", "section": "dairy", "quantity": "2%l"},
+            {"name": "eggs", "section": "dairy", "expire": "next week"}]"#,
+    )
+    .unwrap();
+
+    Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .args(["pantry", "import", "stock.json", "--replace"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Added 0, updated 1, unchanged 0, removed 1.",
+        ))
+        .stdout(predicate::str::contains(
+            "row 2 (eggs): its expire date 'next week' cannot be read as a date",
+        ));
+    assert_eq!(
+        fs::read_to_string(&pantry).unwrap(),
+        "# Kept by hand\n[dairy]\nmilk = \"2%l\"\n"
+    );
+
+    Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .args(["pantry", "undo"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Undid #1 import replace"));
+}
+
+#[test]
+fn test_pantry_restock_adds_what_is_low_to_the_shopping_list_once() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let config_dir = temp_dir.path().join("config");
+    fs::create_dir(&config_dir).unwrap();
+    fs::write(
+        config_dir.join("pantry.conf"),
+        "[dairy]\nmilk = { quantity = \"300%ml\", low = \"500%ml\", restock = \"2%l\" }\nbutter = { quantity = \"250%g\", low = \"50%g\" }\n",
+    )
+    .unwrap();
+
+    Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .args(["pantry", "restock"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Added milk [dairy]: 1.7%l (have 300%ml)",
+        ))
+        .stdout(predicate::str::contains("butter").not())
+        .stderr(predicate::str::contains("restock").not());
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join(".shopping-list")).unwrap(),
+        "milk{1.7%l}\n"
+    );
+
+    Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .args(["pantry", "restock"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Already on the list: milk"));
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join(".shopping-list")).unwrap(),
+        "milk{1.7%l}\n"
+    );
+}
+
+// ---------------------------------------------------------------------------
+// notify
+// ---------------------------------------------------------------------------
+
+fn make_notify_pantry(notify: &str) -> tempfile::TempDir {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let config_dir = temp_dir.path().join("config");
+    fs::create_dir(&config_dir).unwrap();
+    fs::write(
+        config_dir.join("pantry.conf"),
+        "[dairy]\nmilk = { quantity = \"1%l\", expire = \"2001-01-01\" }\n\n[cupboard]\nhoney = \"0\"\nrice = \"2%kg\"\n",
+    )
+    .unwrap();
+    fs::write(config_dir.join("notify.toml"), notify).unwrap();
+    temp_dir
+}
+
+#[cfg(unix)]
+#[test]
+fn test_pantry_notify_runs_the_command_once_per_event() {
+    let temp_dir = make_notify_pantry(
+        "command = \"cat > sent.json; echo \\\"$COOK_NOTIFY_COUNT\\\" > count\"\n",
+    );
+
+    Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .args(["pantry", "notify"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Notified:"))
+        .stdout(predicate::str::contains("honey (cupboard) is running out"));
+
+    let sent: Value =
+        serde_json::from_str(&fs::read_to_string(temp_dir.path().join("sent.json")).unwrap())
+            .expect("the command is given JSON");
+    let events = sent["events"].as_array().unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0]["event"], "expiring");
+    assert_eq!(events[0]["name"], "milk");
+    assert_eq!(events[0]["expire_date"], "2001-01-01");
+    assert_eq!(events[1]["event"], "depleted");
+    assert_eq!(events[1]["name"], "honey");
+    assert!(sent["message"]
+        .as_str()
+        .unwrap()
+        .contains("milk (dairy) expired"));
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("count")).unwrap(),
+        "2\n"
+    );
+    assert!(temp_dir.path().join("config/pantry.notified").exists());
+
+    fs::remove_file(temp_dir.path().join("sent.json")).unwrap();
+    Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .args(["pantry", "notify"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Nothing new to notify about."));
+    assert!(
+        !temp_dir.path().join("sent.json").exists(),
+        "nothing new, so the command is not run"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_pantry_notify_does_not_record_what_failed_to_send() {
+    let temp_dir = make_notify_pantry("command = \"exit 3\"\n");
+
+    Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .args(["pantry", "notify"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Notify command `exit 3` failed"));
+    assert!(!temp_dir.path().join("config/pantry.notified").exists());
+
+    Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .args(["pantry", "notify", "--command", "true", "--days", "0"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Notified:"));
+}
+
+#[test]
+fn test_pantry_notify_dry_run_sends_and_records_nothing() {
+    let temp_dir = make_notify_pantry("events = [\"depleted\"]\n");
+
+    let output = Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .args(["pantry", "-f", "json", "notify", "--dry-run"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let json: Value = serde_json::from_slice(&output).expect("Valid JSON output");
+    assert_eq!(json["events"].as_array().unwrap().len(), 1);
+    assert_eq!(json["events"][0]["name"], "honey");
+    assert_eq!(json["message"], "honey (cupboard) is running out");
+    assert!(!temp_dir.path().join("config/pantry.notified").exists());
+}
+
+#[test]
+fn test_pantry_notify_without_a_hook_errors() {
+    let temp_dir = make_notify_pantry("days = 3\n");
+
+    Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .args(["pantry", "notify"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Nothing to notify with"));
+}
@@ -336,10 +336,10 @@ fn ingredients_only() {
 
 /// Compare against `multiple_recipes_aggregated`. `salt` is fully covered by
 /// the pantry and disappears — taking the whole `[spices]` heading with it —
-/// while `tomatoes` and `olive oil` are reduced. `flour` records the partial
-/// case: the `200 g` component is reduced to `100 g` while the inconvertible
-/// `1 c` component is left untouched (with a unit-mismatch warning on stderr,
-/// which this stdout snapshot does not capture).
+/// while `tomatoes` and `olive oil` are reduced, each saying how much the
+/// pantry already holds. `flour` records the partial case: the `200 g`
+/// component is reduced to `100 g` while the `1 c` component, which grams do
+/// not convert to, is left untouched.
 #[test]
 fn pantry_subtraction() {
     assert_snapshot!(run(&pantry_fixture(), &["pasta.cook", "salad.cook"]));
@@ -0,0 +1,371 @@
+//! Integration tests for `cook shopping-list saved`.
+//!
+//! The store and the list built from it are tested in `cookcli-core`; these
+//! pin what only the command decides — how a recipe or menu is spelled in
+//! `.shopping-list`, so that the web UI reads it back as its own, how a
+//! removal is matched, and what `show` marks as ticked off.
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn cook(dir: &Path) -> Command {
+    let mut command = Command::cargo_bin("cook").unwrap();
+    command.current_dir(dir);
+    command
+}
+
+fn saved(dir: &Path, args: &[&str]) -> Command {
+    let mut command = cook(dir);
+    command.args(["shopping-list", "saved"]).args(args);
+    command
+}
+
+/// A pasta with a sauce, a two-serving salad, and a menu of both.
+fn collection() -> TempDir {
+    let dir = TempDir::new().unwrap();
+    fs::create_dir(dir.path().join("Mains")).unwrap();
+    fs::write(
+        dir.path().join("Mains/Pasta.cook"),
+        "Boil @pasta{100%g} with @./Sauce{}.\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("Sauce.cook"),
+        "Simmer @tomatoes{2} with @Eggs{1}.\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("Salad.cook"),
+        "---\nservings: 2\n---\nToss @lettuce{1}.\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("Week.menu"),
+        "- @./Salad{4%servings}\n- @./Mains/Pasta{}\n",
+    )
+    .unwrap();
+    dir
+}
+
+#[test]
+fn entries_are_stored_as_the_web_ui_stores_them() {
+    let dir = collection();
+    saved(dir.path(), &["add", "Mains/Pasta:2", "Week.menu"])
+        .assert()
+        .success()
+        .stdout("Added 'Pasta' to the shopping list.\nAdded 'Week' to the shopping list.\n");
+
+    let stored = fs::read_to_string(dir.path().join(".shopping-list")).unwrap();
+    assert_eq!(
+        stored,
+        "./Mains/Pasta{2}\n  ./Sauce\n./Week.menu\n  ./Salad{2}\n  ./Mains/Pasta\n    ./Sauce\n"
+    );
+
+    saved(dir.path(), &["list"]).assert().success().stdout(
+        "Pasta ×2 (Mains/Pasta)\nWeek (Week.menu)\n    Salad ×2\n    Pasta (Mains/Pasta)\n",
+    );
+}
+
+#[test]
+fn show_marks_what_is_ticked_off() {
+    let dir = collection();
+    saved(dir.path(), &["add", "Mains/Pasta"])
+        .assert()
+        .success();
+    saved(dir.path(), &["check", "eggs"]).assert().success();
+
+    saved(dir.path(), &["show", "--plain", "--ignore-pantry"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"\[x\] Eggs\s+1").unwrap())
+        .stdout(predicate::str::is_match(r"\[ \] tomatoes\s+2").unwrap());
+
+    let output = saved(
+        dir.path(),
+        &["show", "--unchecked", "-f", "json", "--ignore-pantry"],
+    )
+    .output()
+    .unwrap();
+    assert!(output.status.success());
+    let value: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let names: Vec<&str> = value["categories"][0]["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["pasta", "tomatoes"]);
+
+    saved(dir.path(), &["uncheck", "Eggs"]).assert().success();
+    saved(dir.path(), &["show", "--plain", "--ignore-pantry"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[x]").not());
+}
+
+#[test]
+fn a_menu_is_removed_by_its_name_and_stale_ticks_go_with_it() {
+    let dir = collection();
+    saved(dir.path(), &["add", "Week.menu", "Mains/Pasta"])
+        .assert()
+        .success();
+    saved(dir.path(), &["check", "lettuce", "eggs"])
+        .assert()
+        .success();
+
+    saved(dir.path(), &["remove", "Week"])
+        .assert()
+        .success()
+        .stdout("Removed 'Week' from the shopping list.\n");
+
+    saved(dir.path(), &["list"])
+        .assert()
+        .success()
+        .stdout("Pasta (Mains/Pasta)\n");
+    let checked = fs::read_to_string(dir.path().join(".shopping-checked")).unwrap();
+    assert_eq!(checked, "+ eggs\n");
+}
+
+#[test]
+fn removing_what_is_not_there_is_an_error() {
+    let dir = collection();
+    saved(dir.path(), &["remove", "Salad"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "'Salad' is not on the shopping list",
+        ));
+}
+
+#[test]
+fn clear_empties_the_list_and_forgets_the_ticks() {
+    let dir = collection();
+    saved(dir.path(), &["add", "Salad"]).assert().success();
+    saved(dir.path(), &["check", "lettuce"]).assert().success();
+    saved(dir.path(), &["clear"]).assert().success();
+
+    saved(dir.path(), &["list"]).assert().success().stdout("");
+    assert!(!dir.path().join(".shopping-checked").exists());
+}
+
+#[test]
+fn the_base_path_can_follow_the_subcommand() {
+    let dir = collection();
+    let elsewhere = TempDir::new().unwrap();
+    cook(elsewhere.path())
+        .args(["shopping-list", "saved", "add", "Salad", "-b"])
+        .arg(dir.path())
+        .assert()
+        .success();
+    assert!(dir.path().join(".shopping-list").exists());
+}
+
+#[test]
+fn a_named_list_keeps_its_own_files_and_ticks() {
+    let dir = collection();
+    saved(dir.path(), &["add", "Salad"]).assert().success();
+    saved(dir.path(), &["--list", "party", "add", "Mains/Pasta"])
+        .assert()
+        .success()
+        .stdout("Added 'Pasta' to the 'party' list.\n");
+    saved(dir.path(), &["-l", "party", "check", "eggs"])
+        .assert()
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(dir.path().join(".shopping-list.party")).unwrap(),
+        "./Mains/Pasta\n  ./Sauce\n"
+    );
+    assert!(!dir.path().join(".shopping-checked").exists());
+    saved(dir.path(), &["list"])
+        .assert()
+        .success()
+        .stdout("Salad\n");
+
+    saved(dir.path(), &["lists"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"(?s)default\s+1\n.*party\s+1\n").unwrap());
+}
+
+#[test]
+fn move_takes_an_entry_to_another_list() {
+    let dir = collection();
+    saved(dir.path(), &["add", "Salad", "Mains/Pasta"])
+        .assert()
+        .success();
+
+    saved(dir.path(), &["move", "Mains/Pasta", "--to", "costco"])
+        .assert()
+        .success()
+        .stdout("Moved 'Pasta' to the 'costco' list.\n");
+
+    saved(dir.path(), &["list"])
+        .assert()
+        .success()
+        .stdout("Salad\n");
+    saved(dir.path(), &["--list", "costco", "list"])
+        .assert()
+        .success()
+        .stdout("Pasta (Mains/Pasta)\n");
+
+    saved(
+        dir.path(),
+        &["--list", "costco", "move", "Salad", "--to", "default"],
+    )
+    .assert()
+    .failure()
+    .stderr(predicate::str::contains(
+        "'Salad' is not on the 'costco' list",
+    ));
+}
+
+#[test]
+fn an_invalid_list_name_is_refused() {
+    let dir = collection();
+    saved(dir.path(), &["--list", "Big Shop", "add", "Salad"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Invalid shopping list name 'Big Shop'",
+        ));
+    assert!(!dir.path().join(".shopping-list").exists());
+}
+
+#[test]
+fn items_added_by_hand_are_shown_ticked_and_removed_like_ingredients() {
+    let dir = collection();
+    saved(dir.path(), &["add", "Salad"]).assert().success();
+    saved(
+        dir.path(),
+        &["add-item", "toilet paper", "-q", "4", "-c", "household"],
+    )
+    .assert()
+    .success()
+    .stdout("Added 'toilet paper' to the shopping list.\n");
+    saved(dir.path(), &["add-item", "lettuce", "--quantity", "1"])
+        .assert()
+        .success();
+
+    let stored = fs::read_to_string(dir.path().join(".shopping-list")).unwrap();
+    assert_eq!(stored, "./Salad\ntoilet paper{4}\nlettuce{1}\n");
+    saved(dir.path(), &["list"])
+        .assert()
+        .success()
+        .stdout("Salad\n");
+    saved(dir.path(), &["items"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"toilet paper\s+4\s+\[household\]").unwrap());
+
+    saved(dir.path(), &["check", "Toilet Paper"])
+        .assert()
+        .success();
+    saved(dir.path(), &["show", "--ignore-pantry"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[household]"))
+        .stdout(predicate::str::is_match(r"\[x\] toilet paper\s+4").unwrap())
+        .stdout(predicate::str::is_match(r"\[ \] lettuce\s+2").unwrap());
+
+    saved(dir.path(), &["remove-item", "TOILET PAPER"])
+        .assert()
+        .success()
+        .stdout("Removed 'TOILET PAPER' from the shopping list.\n");
+    let checked = fs::read_to_string(dir.path().join(".shopping-checked")).unwrap();
+    assert_eq!(checked, "", "the tick went with the item");
+    saved(dir.path(), &["remove-item", "toilet paper"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "'toilet paper' is not on the shopping list",
+        ));
+    saved(dir.path(), &["add-item", "candles{2}"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Cannot add 'candles{2}' to the shopping list",
+        ));
+}
+
+#[test]
+fn a_clear_can_be_undone_and_every_change_is_in_the_history() {
+    let dir = collection();
+    saved(dir.path(), &["add", "Salad", "Mains/Pasta"])
+        .assert()
+        .success();
+    saved(dir.path(), &["check", "eggs"]).assert().success();
+    saved(dir.path(), &["clear"]).assert().success();
+
+    saved(dir.path(), &["undo"])
+        .assert()
+        .success()
+        .stdout("Undid 'clear' on the shopping list.\n");
+    saved(dir.path(), &["list"])
+        .assert()
+        .success()
+        .stdout("Salad\nPasta (Mains/Pasta)\n");
+    let checked = fs::read_to_string(dir.path().join(".shopping-checked")).unwrap();
+    assert_eq!(checked, "+ eggs\n");
+
+    let output = saved(dir.path(), &["history", "-f", "json"])
+        .env("USER", "alice")
+        .output()
+        .unwrap();
+    let log: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let what: Vec<&str> = log
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|change| change["what"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        what,
+        ["add Salad", "add Mains/Pasta", "clear", "undo clear"]
+    );
+
+    saved(dir.path(), &["restore", "--at", "2000-01-01"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Restored the shopping list"));
+    saved(dir.path(), &["list"]).assert().success().stdout("");
+    saved(dir.path(), &["restore", "--at", "yesterday"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("is not a time"));
+}
+
+#[test]
+fn commit_moves_what_was_ticked_into_the_pantry() {
+    let dir = collection();
+    fs::create_dir(dir.path().join("config")).unwrap();
+    let pantry = dir.path().join("config/pantry.conf");
+    fs::write(&pantry, "[pantry]\npasta = \"50%g\"\n").unwrap();
+    saved(dir.path(), &["add", "Mains/Pasta"])
+        .assert()
+        .success();
+    saved(dir.path(), &["check", "pasta", "tomatoes"])
+        .assert()
+        .success();
+
+    saved(dir.path(), &["commit", "--date", "2026-10-19"])
+        .assert()
+        .success()
+        .stdout(
+            "Topped up pasta in [pantry], now 100%g.\n\
+             Added tomatoes to [other], now 2.\n",
+        );
+    let written = fs::read_to_string(&pantry).unwrap();
+    assert!(written.contains("bought = \"2026-10-19\""), "{written}");
+    assert!(written.contains("[other]\ntomatoes = "), "{written}");
+    let checked = fs::read_to_string(dir.path().join(".shopping-checked")).unwrap();
+    assert_eq!(checked, "");
+
+    saved(dir.path(), &["commit"])
+        .assert()
+        .success()
+        .stdout("Nothing is ticked off the shopping list.\n");
+}
@@ -53,6 +53,8 @@ fn test_shopping_list_multiple_recipes() {
         .unwrap()
         .current_dir(temp_dir.path())
         .arg("shopping-list")
+        // The fixture pantry's oil covers the sauce's two tablespoons.
+        .arg("--ignore-pantry")
         .arg("simple.cook")
         .arg("sauce.cook")
         .assert()
@@ -86,6 +88,8 @@ fn test_shopping_list_plain_format() {
         .current_dir(temp_dir.path())
         .arg("shopping-list")
         .arg("--plain")
+        // The fixture pantry's kilo of pasta covers the recipe's 200 g.
+        .arg("--ignore-pantry")
         .arg("simple.cook")
         .assert()
         .success()
@@ -167,6 +171,7 @@ fn test_shopping_list_output_to_file() {
         .arg("shopping-list")
         .arg("-o")
         .arg(&output_file)
+        .arg("--ignore-pantry")
         .arg("simple.cook")
         .assert()
         .success()
@@ -389,6 +394,7 @@ fn test_shopping_list_base_path() {
         .arg("shopping-list")
         .arg("-b")
         .arg(temp_dir.path())
+        .arg("--ignore-pantry")
         .arg("simple.cook")
         .assert()
         .success()
@@ -439,3 +445,229 @@ Add @pepper{}/black pepper.
         "the alias is not what a shopping list shows: {stdout}"
     );
 }
+
+#[test]
+fn test_shopping_list_store_profile() {
+    let temp_dir = common::setup_test_recipes().unwrap();
+    let stores = temp_dir.path().join("config").join("stores");
+    fs::create_dir(&stores).unwrap();
+    fs::write(
+        stores.join("corner.conf"),
+        r#"
+order = ["Dry goods"]
+
+[aisles]
+pantry = "Dry goods"
+
+[ingredients]
+salt = "Till"
+"#,
+    )
+    .unwrap();
+
+    let output = Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .args(["shopping-list", "--ignore-pantry", "--store", "corner"])
+        .args(["-f", "json", "simple.cook"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let json: Value = serde_json::from_slice(&output).expect("Valid JSON output");
+    let aisles: Vec<(&str, Vec<&str>)> = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|category| {
+            (
+                category["category"].as_str().unwrap(),
+                category["items"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|item| item["name"].as_str().unwrap())
+                    .collect(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        aisles,
+        [
+            ("Dry goods", vec!["pasta"]),
+            ("Till", vec!["salt"]),
+            ("other", vec!["water"]),
+        ]
+    );
+}
+
+#[test]
+fn test_shopping_list_unknown_store_profile() {
+    let temp_dir = common::setup_test_recipes().unwrap();
+
+    Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .args(["shopping-list", "--store", "nowhere", "simple.cook"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No store profile named 'nowhere'"));
+}
+
+#[test]
+fn test_shopping_list_packages() {
+    let temp_dir = common::setup_test_recipes().unwrap();
+    let pasta = temp_dir.path().join("db").join("pasta");
+    fs::create_dir_all(&pasta).unwrap();
+    fs::write(pasta.join("shopping.yml"), "package_size: 500%g\n").unwrap();
+
+    Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .args(["shopping-list", "--ignore-pantry", "--datastore", "db"])
+        .args(["--plain", "simple.cook"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 × 500 g (300 g left over)"));
+
+    let output = Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .args(["shopping-list", "--ignore-pantry", "--datastore", "db"])
+        .args(["-f", "json", "--plain", "simple.cook"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let json: Value = serde_json::from_slice(&output).expect("Valid JSON output");
+    let items = json.as_array().unwrap();
+    let pasta = items.iter().find(|i| i["name"] == "pasta").unwrap();
+    assert_eq!(pasta["packages"]["count"], 1);
+    assert_eq!(
+        pasta["packages"]["leftover"]["value"]["value"]["value"],
+        300.0
+    );
+    assert_eq!(pasta["packages"]["leftover"]["unit"], "g");
+    let salt = items.iter().find(|i| i["name"] == "salt").unwrap();
+    assert!(salt.get("packages").is_none());
+}
+
+#[test]
+fn test_shopping_list_pantry_subtracts_quantities_until_they_expire() {
+    let temp_dir = common::setup_test_recipes().unwrap();
+    fs::write(
+        temp_dir.path().join("stock.conf"),
+        "[cupboard]\npasta = { quantity = \"0.05%kg\", expire = \"2030-01-31\" }\n",
+    )
+    .unwrap();
+    let list_on = |date: &str| {
+        Command::cargo_bin("cook")
+            .unwrap()
+            .current_dir(temp_dir.path())
+            .args(["shopping-list", "--pantry", "stock.conf", "--plain"])
+            .args(["--cook-date", date, "simple.cook"])
+            .assert()
+            .success()
+    };
+
+    list_on("2030-01-01").stdout(predicate::str::contains("150 g (50 g in the pantry)"));
+    list_on("2030-02-01")
+        .stdout(
+            predicate::str::contains("200 g").and(predicate::str::contains("in the pantry").not()),
+        )
+        .stderr(predicate::str::contains("expires on 2030-01-31"));
+}
+
+#[test]
+fn test_shopping_list_export_formats() {
+    let temp_dir = common::setup_test_recipes().unwrap();
+
+    // `.csv` and `.ics` are recognised from the output file's extension.
+    let csv_file = temp_dir.path().join("shopping.csv");
+    let ics_file = temp_dir.path().join("shopping.ics");
+    for file in [&csv_file, &ics_file] {
+        Command::cargo_bin("cook")
+            .unwrap()
+            .current_dir(temp_dir.path())
+            .arg("shopping-list")
+            .arg("--ignore-pantry")
+            .arg("-o")
+            .arg(file)
+            .arg("simple.cook")
+            .assert()
+            .success();
+    }
+    let csv = fs::read_to_string(&csv_file).unwrap();
+    assert!(
+        csv.starts_with("category,item,quantity,unit,amount,recipes\r\n"),
+        "{csv}"
+    );
+    assert!(csv.contains(",pasta,200,g,200 g,simple\r\n"), "{csv}");
+    let ics = fs::read_to_string(&ics_file).unwrap();
+    assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"), "{ics}");
+    assert!(ics.contains("SUMMARY:pasta (200 g)\r\n"), "{ics}");
+
+    Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .args([
+            "shopping-list",
+            "--ignore-pantry",
+            "--plain",
+            "-f",
+            "checklist",
+        ])
+        .arg("simple.cook")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("☐ pasta (200 g)\n"));
+
+    Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .args(["shopping-list", "--ignore-pantry", "--plain", "-f", "todo"])
+        .arg("simple.cook")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("pasta (200 g) +shopping\n"));
+}
+
+#[test]
+fn test_shopping_list_show_sources() {
+    let temp_dir = common::setup_test_recipes().unwrap();
+
+    Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .args([
+            "shopping-list",
+            "--ignore-pantry",
+            "--plain",
+            "--show-sources",
+        ])
+        .arg("simple.cook")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"(?m)^pasta\s+200 g\n\s+↳ simple\s+200 g$").unwrap());
+
+    // JSON names the sources whether or not they were asked for.
+    let output = Command::cargo_bin("cook")
+        .unwrap()
+        .current_dir(temp_dir.path())
+        .args(["shopping-list", "--ignore-pantry", "--plain", "-f", "json"])
+        .arg("simple.cook")
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let pasta = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|item| item["name"] == "pasta")
+        .unwrap();
+    assert_eq!(pasta["sources"][0]["recipe"], "simple");
+}